@@ -1,6 +1,9 @@
 use std::env;
 
-use pyo3_build_config::pyo3_build_script_impl::{cargo_env_var, errors::Result};
+use pyo3_build_config::pyo3_build_script_impl::{
+    cargo_env_var,
+    errors::{check_denied_warnings, exit_with_error, Result},
+};
 use pyo3_build_config::{bail, print_feature_cfgs, InterpreterConfig};
 
 fn ensure_auto_initialize_ok(interpreter_config: &InterpreterConfig) -> Result<()> {
@@ -39,16 +42,42 @@ fn configure_pyo3() -> Result<()> {
         println!("{}", cfg)
     }
 
+    // Let PyO3's own tests find the shared Python library when embedding. Cargo rejects these
+    // lines if the package has no tests, so check they were packaged.
+    if std::path::Path::new("tests").is_dir() {
+        for line in interpreter_config.embedding_test_link_args(false) {
+            println!("{}", line)
+        }
+    }
+
+    if cargo_env_var("CARGO_FEATURE_BUILD_INFO").is_some() {
+        write_build_info(interpreter_config)?;
+    }
+
     // Emit cfgs like `invalid_from_utf8_lint`
     print_feature_cfgs();
 
     Ok(())
 }
 
+/// Writes the `key=value` lines which the `build-info` feature embeds as `__pyo3_build_info__`.
+fn write_build_info(interpreter_config: &InterpreterConfig) -> Result<()> {
+    let mut info = format!(
+        "pyo3_version={}\ntarget={}\n",
+        env::var("CARGO_PKG_VERSION").unwrap(),
+        env::var("TARGET").unwrap()
+    );
+    for (key, value) in interpreter_config.build_info() {
+        info.push_str(&format!("{}={}\n", key, value));
+    }
+    let path = std::path::Path::new(&env::var("OUT_DIR").unwrap()).join("pyo3-build-info.txt");
+    std::fs::write(&path, info)
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e).into())
+}
+
 fn main() {
     pyo3_build_config::print_expected_cfgs();
-    if let Err(e) = configure_pyo3() {
-        eprintln!("error: {}", e.report());
-        std::process::exit(1)
+    if let Err(e) = configure_pyo3().and_then(|()| check_denied_warnings()) {
+        exit_with_error(&e)
     }
 }
@@ -563,6 +563,8 @@ def set_minimal_package_versions(session: nox.Session):
         "trybuild": "1.0.89",
         "eyre": "0.6.8",
         "allocator-api2": "0.2.10",
+        "tokio": "1.38.1",
+        "uuid": "1.20.0",
     }
 
     # run cargo update first to ensure that everything is at highest
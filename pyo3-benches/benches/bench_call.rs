@@ -3,6 +3,7 @@ use std::hint::black_box;
 use codspeed_criterion_compat::{criterion_group, criterion_main, Bencher, Criterion};
 
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 
 macro_rules! test_module {
     ($py:ident, $code:literal) => {
@@ -46,9 +47,43 @@ class Foo:
     })
 }
 
+#[pyfunction]
+#[pyo3(signature = (a, b, c = None))]
+fn add(a: i32, b: i32, c: Option<i32>) -> i32 {
+    a + b + c.unwrap_or(0)
+}
+
+fn bench_call_pyfunction(b: &mut Bencher<'_>) {
+    Python::with_gil(|py| {
+        let add = &wrap_pyfunction_bound!(add, py).unwrap();
+
+        b.iter(|| {
+            for _ in 0..1000 {
+                black_box(add).call1((1, 2)).unwrap();
+            }
+        });
+    })
+}
+
+fn bench_call_pyfunction_kwargs(b: &mut Bencher<'_>) {
+    Python::with_gil(|py| {
+        let add = &wrap_pyfunction_bound!(add, py).unwrap();
+        let kwargs = &PyDict::new_bound(py);
+        kwargs.set_item("c", 3).unwrap();
+
+        b.iter(|| {
+            for _ in 0..1000 {
+                black_box(add).call((1, 2), Some(kwargs)).unwrap();
+            }
+        });
+    })
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("call_0", bench_call_0);
     c.bench_function("call_method_0", bench_call_method_0);
+    c.bench_function("call_pyfunction", bench_call_pyfunction);
+    c.bench_function("call_pyfunction_kwargs", bench_call_pyfunction_kwargs);
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -63,6 +63,30 @@ fn sequence_from_list(b: &mut Bencher<'_>) {
     });
 }
 
+fn list_extract_vec_i64(b: &mut Bencher<'_>) {
+    Python::with_gil(|py| {
+        const LEN: i32 = 50_000;
+        let list = PyList::new_bound(py, 0..LEN);
+        b.iter(|| black_box(&list).extract::<Vec<i64>>().unwrap());
+    });
+}
+
+fn list_extract_vec_f64(b: &mut Bencher<'_>) {
+    Python::with_gil(|py| {
+        const LEN: usize = 50_000;
+        let list = PyList::new_bound(py, (0..LEN).map(|i| i as f64));
+        b.iter(|| black_box(&list).extract::<Vec<f64>>().unwrap());
+    });
+}
+
+fn range_extract_vec_i64(b: &mut Bencher<'_>) {
+    Python::with_gil(|py| {
+        // Not a list or tuple, so takes the generic sequence path
+        let range = py.eval_bound("range(50_000)", None, None).unwrap();
+        b.iter(|| black_box(&range).extract::<Vec<i64>>().unwrap());
+    });
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("iter_list", iter_list);
     c.bench_function("list_new", list_new);
@@ -70,6 +94,9 @@ fn criterion_benchmark(c: &mut Criterion) {
     #[cfg(not(Py_LIMITED_API))]
     c.bench_function("list_get_item_unchecked", list_get_item_unchecked);
     c.bench_function("sequence_from_list", sequence_from_list);
+    c.bench_function("list_extract_vec_i64", list_extract_vec_i64);
+    c.bench_function("list_extract_vec_f64", list_extract_vec_f64);
+    c.bench_function("range_extract_vec_i64", range_extract_vec_i64);
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -121,6 +121,14 @@ fn tuple_into_py(b: &mut Bencher<'_>) {
     });
 }
 
+fn tuple_extract_vec_i64(b: &mut Bencher<'_>) {
+    Python::with_gil(|py| {
+        const LEN: i32 = 50_000;
+        let tuple = PyTuple::new_bound(py, 0..LEN);
+        b.iter(|| black_box(&tuple).extract::<Vec<i64>>().unwrap());
+    });
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("iter_tuple", iter_tuple);
     c.bench_function("tuple_new", tuple_new);
@@ -137,6 +145,7 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("tuple_new_list", tuple_new_list);
     c.bench_function("tuple_to_list", tuple_to_list);
     c.bench_function("tuple_into_py", tuple_into_py);
+    c.bench_function("tuple_extract_vec_i64", tuple_extract_vec_i64);
 }
 
 criterion_group!(benches, criterion_benchmark);
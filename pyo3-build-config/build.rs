@@ -1,8 +1,9 @@
 // Import some modules from this crate inline to generate the build config.
-// Allow dead code because not all code in the modules is used in this build script.
+// Allow dead code (and unused re-exports) because not all code in the modules is used in this
+// build script.
 
 #[path = "src/impl_.rs"]
-#[allow(dead_code)]
+#[allow(dead_code, unused_imports)]
 mod impl_;
 
 #[path = "src/errors.rs"]
@@ -12,7 +13,7 @@ mod errors;
 use std::{env, path::Path};
 
 use errors::{Context, Result};
-use impl_::{env_var, make_interpreter_config, InterpreterConfig};
+use impl_::{env_var, expand_home_dir, home_dir, make_interpreter_config, InterpreterConfig};
 
 fn configure(interpreter_config: Option<InterpreterConfig>, name: &str) -> Result<bool> {
     let target = Path::new(&env::var_os("OUT_DIR").unwrap()).join(name);
@@ -32,7 +33,7 @@ fn configure(interpreter_config: Option<InterpreterConfig>, name: &str) -> Resul
 /// If PYO3_CONFIG_FILE is set, copy it into the crate.
 fn config_file() -> Result<Option<InterpreterConfig>> {
     if let Some(path) = env_var("PYO3_CONFIG_FILE") {
-        let path = Path::new(&path);
+        let path = &expand_home_dir(Path::new(&path), home_dir().as_deref())?;
         println!("cargo:rerun-if-changed={}", path.display());
         // Absolute path is necessary because this build script is run with a cwd different to the
         // original `cargo build` instruction.
@@ -44,29 +45,52 @@ fn config_file() -> Result<Option<InterpreterConfig>> {
         let interpreter_config = InterpreterConfig::from_path(path)
             .context("failed to parse contents of PYO3_CONFIG_FILE")?;
         Ok(Some(interpreter_config))
+    } else {
+        config_fd()
+    }
+}
+
+/// If PYO3_CONFIG_FD is set, read the config from that file descriptor.
+#[cfg(unix)]
+fn config_fd() -> Result<Option<InterpreterConfig>> {
+    if let Some(fd) = env_var("PYO3_CONFIG_FD") {
+        let fd = fd.to_str().ok_or("PYO3_CONFIG_FD is not valid UTF-8")?;
+        let interpreter_config =
+            InterpreterConfig::from_fd(fd).context("failed to parse contents of PYO3_CONFIG_FD")?;
+        Ok(Some(interpreter_config))
     } else {
         Ok(None)
     }
 }
 
+#[cfg(not(unix))]
+fn config_fd() -> Result<Option<InterpreterConfig>> {
+    ensure!(
+        env_var("PYO3_CONFIG_FD").is_none(),
+        "PYO3_CONFIG_FD is only supported on unix"
+    );
+    Ok(None)
+}
+
 fn generate_build_configs() -> Result<()> {
     let configured = configure(config_file()?, "pyo3-build-config-file.txt")?;
 
     if configured {
         // Don't bother trying to find an interpreter on the host system
         // if the user-provided config file is present.
-        configure(None, "pyo3-build-config.txt")?;
+        configure(None, impl_::CONFIG_FILE_NAME)?;
     } else {
-        configure(Some(make_interpreter_config()?), "pyo3-build-config.txt")?;
+        let interpreter_config = make_interpreter_config()?;
+        interpreter_config.emit_rerun_if_changed_for_interpreter();
+        configure(Some(interpreter_config), impl_::CONFIG_FILE_NAME)?;
     }
     Ok(())
 }
 
 fn main() {
     if std::env::var("CARGO_FEATURE_RESOLVE_CONFIG").is_ok() {
-        if let Err(e) = generate_build_configs() {
-            eprintln!("error: {}", e.report());
-            std::process::exit(1)
+        if let Err(e) = generate_build_configs().and_then(|()| errors::check_denied_warnings()) {
+            errors::exit_with_error(&e)
         }
     } else {
         eprintln!("resolve-config feature not enabled; build script in no-op mode");
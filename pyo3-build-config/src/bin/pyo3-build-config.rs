@@ -0,0 +1,75 @@
+//! Prints and validates the configuration PyO3 resolves, to debug which Python was picked up.
+//!
+//! ```text
+//! cargo run -p pyo3-build-config --features resolve-config -- print
+//! cargo run -p pyo3-build-config -- check [PATH]
+//! ```
+
+use std::{env, ffi::OsString, path::PathBuf, process::ExitCode};
+
+const USAGE: &str = "\
+usage: pyo3-build-config <command>
+
+commands:
+    print          print the configuration PyO3 resolves, in the PyO3 config file format
+    check [PATH]   validate the config file at PATH (default: $PYO3_CONFIG_FILE)";
+
+fn main() -> ExitCode {
+    let mut args = env::args_os().skip(1);
+    let command = args.next();
+    let result = match command.as_ref().and_then(|command| command.to_str()) {
+        Some("print") if args.next().is_none() => print(),
+        Some("check") => match (args.next(), args.next()) {
+            (path, None) => check(path),
+            _ => usage(),
+        },
+        Some("help" | "--help" | "-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => usage(),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn usage() -> Result<(), String> {
+    Err(USAGE.to_owned())
+}
+
+/// Prints the same configuration as `pyo3_build_config::get()` returns.
+#[cfg(feature = "resolve-config")]
+fn print() -> Result<(), String> {
+    pyo3_build_config::get()
+        .to_writer(std::io::stdout().lock())
+        .map_err(|e| e.report().to_string())
+}
+
+#[cfg(not(feature = "resolve-config"))]
+fn print() -> Result<(), String> {
+    Err("`print` needs the configuration resolved when building, so this binary must be \
+         built with the `resolve-config` feature\n\
+         = help: run `cargo run -p pyo3-build-config --features resolve-config -- print`"
+        .to_owned())
+}
+
+fn check(path: Option<OsString>) -> Result<(), String> {
+    let path = path
+        .or_else(|| env::var_os("PYO3_CONFIG_FILE"))
+        .map(PathBuf::from)
+        .ok_or("no config file given and PYO3_CONFIG_FILE is not set")?;
+    pyo3_build_config::validate_config_file(&path).map_err(|e| {
+        format!(
+            "invalid PyO3 config file {}: {}",
+            path.display(),
+            e.report()
+        )
+    })?;
+    println!("{}: ok", path.display());
+    Ok(())
+}
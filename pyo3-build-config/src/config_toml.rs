@@ -0,0 +1,524 @@
+//! Reading and writing of PyO3 config files in (a subset of) TOML.
+//!
+//! The TOML document is translated into the same `key=value` pairs as the line-based format, so
+//! that both formats share the same keys and validation. Supported are `#` comments, bare or
+//! quoted keys, basic and literal strings, booleans, numbers and (possibly multi-line) arrays of
+//! strings, as well as the following tables:
+//!
+//! ```toml
+//! version = "3.11"
+//! extra_link_args = ["-Wl,--as-needed"]
+//!
+//! [extra_link_args_by_os]
+//! linux = ["-Wl,-rpath,/opt/python/lib"]
+//!
+//! [sysconfig_vars]
+//! MULTIARCH = "x86_64-linux-gnu"
+//! ```
+
+use std::{fmt::Write as _, io::Write};
+
+use super::InterpreterConfig;
+use crate::{
+    bail, ensure,
+    errors::{Context, Result},
+};
+
+/// Keys whose values may be given as an array in TOML, mapped to the corresponding (repeated)
+/// key of the line-based format.
+const ARRAY_KEYS: &[(&str, &str)] = &[
+    ("extra_build_script_lines", "extra_build_script_line"),
+    ("config_args", "config_arg"),
+    ("extra_link_args", "extra_link_args"),
+    ("extra_link_search_paths", "extra_link_search_path"),
+];
+
+#[derive(Debug, PartialEq, Eq)]
+enum Value {
+    /// A string, boolean or number, stored as the text to use in the line-based format.
+    Scalar(String),
+    Array(Vec<String>),
+}
+
+/// Translates a TOML config into `key=value` pairs.
+pub(crate) fn config_pairs(contents: &str) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    let mut seen = Vec::new();
+    let mut table: Option<String> = None;
+    let mut lines = contents.lines().enumerate();
+
+    while let Some((i, line)) = lines.next() {
+        let line_number = i + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| format!("invalid table header on line {}", line_number))?;
+            ensure!(
+                name == "extra_link_args_by_os" || name == "sysconfig_vars",
+                "unknown table `[{}]` on line {}",
+                name,
+                line_number
+            );
+            table = Some(name.to_owned());
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("expected `key = value` on line {}", line_number))?;
+        let key = parse_key(key.trim())
+            .with_context(|| format!("invalid key on line {}", line_number))?;
+
+        // Arrays may be continued over several lines.
+        let mut value = value.trim().to_owned();
+        if value.starts_with('[') {
+            while !array_is_closed(&value) {
+                let (_, next) = lines.next().ok_or_else(|| {
+                    format!("unterminated array starting on line {}", line_number)
+                })?;
+                value.push(' ');
+                value.push_str(strip_comment(next).trim());
+            }
+        }
+        let value = parse_value(&value)
+            .with_context(|| format!("invalid value for `{}` on line {}", key, line_number))?;
+
+        let full_key = match &table {
+            Some(table) => format!("{}.{}", table, key),
+            None => key.clone(),
+        };
+        ensure!(
+            !seen.contains(&full_key),
+            "duplicate key `{}` on line {}",
+            full_key,
+            line_number
+        );
+        seen.push(full_key);
+
+        match (table.as_deref(), value) {
+            (Some("extra_link_args_by_os"), Value::Array(args)) => {
+                let key = format!("extra_link_args_{}", key);
+                pairs.extend(args.into_iter().map(|arg| (key.clone(), arg)));
+            }
+            (Some("sysconfig_vars"), Value::Scalar(value)) => {
+                pairs.push(("sysconfig_var".to_owned(), format!("{}={}", key, value)));
+            }
+            (Some(table), _) => bail!(
+                "expected {} for `{}` in `[{}]` on line {}",
+                if table == "sysconfig_vars" {
+                    "a string"
+                } else {
+                    "an array"
+                },
+                key,
+                table,
+                line_number
+            ),
+            (None, Value::Array(values)) => {
+                if key == "build_flags" {
+                    pairs.push((key, values.join(",")));
+                } else if let Some((_, line_key)) =
+                    ARRAY_KEYS.iter().find(|(array_key, _)| *array_key == key)
+                {
+                    pairs.extend(
+                        values
+                            .into_iter()
+                            .map(|value| (line_key.to_string(), value)),
+                    );
+                } else {
+                    bail!("unexpected array for `{}` on line {}", key, line_number);
+                }
+            }
+            (None, Value::Scalar(value)) => {
+                ensure!(
+                    key != "include",
+                    "`include` is not supported in TOML config files (line {})",
+                    line_number
+                );
+                pairs.push((key, value));
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+/// Writes `config` as TOML, which [`config_pairs`] reads back into an equal config.
+pub(crate) fn write_config(config: &InterpreterConfig, mut writer: impl Write) -> Result<()> {
+    let mut contents = String::new();
+    let mut scalar = |key: &str, value: &dyn std::fmt::Display| {
+        let _ = writeln!(contents, "{} = {}", key, quote(&value.to_string()));
+    };
+
+    scalar("implementation", &config.implementation);
+    scalar("version", &config.version);
+    scalar("shared", &config.shared);
+    scalar("abi3", &config.abi3);
+    let optional = [
+        ("lib_name", &config.lib_name),
+        ("lib_dir", &config.lib_dir),
+        ("executable", &config.executable),
+        ("platform_tag", &config.platform_tag),
+        ("python_framework_prefix", &config.python_framework_prefix),
+    ];
+    for (key, value) in optional {
+        if let Some(value) = value {
+            scalar(key, value);
+        }
+    }
+    if let Some(pointer_width) = config.pointer_width {
+        scalar("pointer_width", &pointer_width);
+    }
+    scalar("pointer_width_inferred", &config.pointer_width_inferred);
+    scalar("build_flags", &config.build_flags);
+    scalar(
+        "suppress_build_script_link_lines",
+        &config.suppress_build_script_link_lines,
+    );
+
+    let arrays = [
+        &config.extra_build_script_lines,
+        &config.config_args,
+        &config.extra_link_args,
+        &config.extra_link_search_paths,
+    ];
+    for ((key, _), values) in ARRAY_KEYS.iter().zip(arrays) {
+        if !values.is_empty() {
+            let _ = writeln!(contents, "{} = {}", key, array(values));
+        }
+    }
+
+    if !config.extra_link_args_by_os.is_empty() {
+        contents.push_str("\n[extra_link_args_by_os]\n");
+        for (os, args) in &config.extra_link_args_by_os {
+            let _ = writeln!(contents, "{} = {}", key(os), array(args));
+        }
+    }
+    if !config.sysconfig_vars.is_empty() {
+        contents.push_str("\n[sysconfig_vars]\n");
+        for (name, value) in &config.sysconfig_vars {
+            let _ = writeln!(contents, "{} = {}", key(name), quote(value));
+        }
+    }
+
+    writer
+        .write_all(contents.as_bytes())
+        .context("failed to write TOML config")
+}
+
+/// Returns `key` as a bare key if possible, otherwise as a quoted key.
+fn key(key: &str) -> String {
+    if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        key.to_owned()
+    } else {
+        quote(key)
+    }
+}
+
+fn array(values: &[String]) -> String {
+    let values: Vec<String> = values.iter().map(|value| quote(value)).collect();
+    format!("[{}]", values.join(", "))
+}
+
+/// Returns `value` as a basic string, escaping it as [`parse_string`] expects.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04X}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Removes a trailing `# comment`, ignoring `#` inside strings.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+/// Returns `true` if `value` contains as many `]` as `[` outside of strings.
+fn array_is_closed(value: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut escaped = false;
+    for c in value.chars() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '[' => depth += 1,
+            None if c == ']' => depth -= 1,
+            None => {}
+        }
+    }
+    depth <= 0
+}
+
+fn parse_key(key: &str) -> Result<String> {
+    if key.starts_with('"') || key.starts_with('\'') {
+        let (key, rest) = parse_string(key)?;
+        ensure!(
+            rest.trim().is_empty(),
+            "unexpected `{}` after key",
+            rest.trim()
+        );
+        return Ok(key);
+    }
+    ensure!(
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'),
+        "`{}` is not a valid bare key",
+        key
+    );
+    Ok(key.to_owned())
+}
+
+fn parse_value(value: &str) -> Result<Value> {
+    if let Some(mut rest) = value.strip_prefix('[') {
+        let mut values = Vec::new();
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(']') {
+                ensure!(
+                    after.trim().is_empty(),
+                    "unexpected `{}` after array",
+                    after.trim()
+                );
+                return Ok(Value::Array(values));
+            }
+            let (item, after) = parse_string(rest).context("expected a string in array")?;
+            values.push(item);
+            rest = after.trim_start();
+            if let Some(after) = rest.strip_prefix(',') {
+                rest = after;
+            } else {
+                ensure!(rest.starts_with(']'), "expected `,` or `]` in array");
+            }
+        }
+    }
+
+    if value.starts_with('"') || value.starts_with('\'') {
+        let (string, rest) = parse_string(value)?;
+        ensure!(
+            rest.trim().is_empty(),
+            "unexpected `{}` after string",
+            rest.trim()
+        );
+        return Ok(Value::Scalar(string));
+    }
+
+    // Booleans and numbers are passed on as written, e.g. `version = 3.11` keeps "3.11".
+    ensure!(
+        value == "true"
+            || value == "false"
+            || (!value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || matches!(c, '.' | '_' | '+' | '-'))),
+        "expected a string, boolean, number or array, got `{}`",
+        value
+    );
+    Ok(Value::Scalar(value.replace('_', "")))
+}
+
+/// Parses a basic (`"..."`) or literal (`'...'`) string at the start of `input`, returning the
+/// string and the remaining input.
+fn parse_string(input: &str) -> Result<(String, &str)> {
+    let mut chars = input.char_indices();
+    let quote = match chars.next() {
+        Some((_, q @ ('"' | '\''))) => q,
+        _ => bail!("expected a string"),
+    };
+
+    let mut string = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c == quote => return Ok((string, &input[i + 1..])),
+            '\\' if quote == '"' => {
+                let escaped = match chars.next() {
+                    Some((_, '\\')) => '\\',
+                    Some((_, '"')) => '"',
+                    Some((_, 'n')) => '\n',
+                    Some((_, 't')) => '\t',
+                    Some((_, 'r')) => '\r',
+                    Some((_, u @ ('u' | 'U'))) => {
+                        let len = if u == 'u' { 4 } else { 8 };
+                        let hex: String = chars.by_ref().take(len).map(|(_, c)| c).collect();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .filter(|_| hex.len() == len)
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| format!("invalid unicode escape `\\{}{}`", u, hex))?
+                    }
+                    Some((_, c)) => bail!("invalid escape sequence `\\{}`", c),
+                    None => break,
+                };
+                string.push(escaped);
+            }
+            c => string.push(c),
+        }
+    }
+    bail!("unterminated string")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(contents: &str) -> Vec<(String, String)> {
+        config_pairs(contents).unwrap()
+    }
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.to_owned(), value.to_owned())
+    }
+
+    #[test]
+    fn scalars_and_comments() {
+        assert_eq!(
+            pairs(
+                r#"
+                # a cross compile config
+                implementation = "CPython"  # trailing comment
+                version = 3.10
+                shared = false
+                pointer_width = 64
+                lib_dir = '/opt/python#3/lib'
+                "executable" = "C:\\Python\\python.exe"
+                "#
+            ),
+            [
+                pair("implementation", "CPython"),
+                pair("version", "3.10"),
+                pair("shared", "false"),
+                pair("pointer_width", "64"),
+                pair("lib_dir", "/opt/python#3/lib"),
+                pair("executable", "C:\\Python\\python.exe"),
+            ]
+        );
+    }
+
+    #[test]
+    fn arrays_and_tables() {
+        assert_eq!(
+            pairs(
+                r#"
+                build_flags = ["Py_DEBUG", "Py_REF_DEBUG"]
+                config_args = ["--enable-shared"]
+                extra_link_args = [
+                    "-Wl,--as-needed",  # comment in array
+                    "-lm",
+                ]
+                extra_link_search_paths = ["/opt/lib"]
+
+                [extra_link_args_by_os]
+                linux = ["-Wl,-rpath,/opt/lib"]
+
+                [sysconfig_vars]
+                MULTIARCH = "x86_64-linux-gnu"
+                "#
+            ),
+            [
+                pair("build_flags", "Py_DEBUG,Py_REF_DEBUG"),
+                pair("config_arg", "--enable-shared"),
+                pair("extra_link_args", "-Wl,--as-needed"),
+                pair("extra_link_args", "-lm"),
+                pair("extra_link_search_path", "/opt/lib"),
+                pair("extra_link_args_linux", "-Wl,-rpath,/opt/lib"),
+                pair("sysconfig_var", "MULTIARCH=x86_64-linux-gnu"),
+            ]
+        );
+    }
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(
+            pairs(r#"extra_build_script_lines = ["a\"b\tc\u00e9"]"#),
+            [pair("extra_build_script_line", "a\"b\tc\u{e9}")]
+        );
+    }
+
+    #[test]
+    fn quote_round_trips() {
+        for value in [
+            "",
+            "plain",
+            "C:\\Python's \"libs\"",
+            "a\nb\r\tc",
+            "\u{1}\u{7f}#é",
+        ] {
+            assert_eq!(parse_string(&quote(value)).unwrap(), (value.to_owned(), ""));
+        }
+        assert_eq!(key("linux"), "linux");
+        assert_eq!(key("my os"), "\"my os\"");
+    }
+
+    #[test]
+    fn errors() {
+        let error = |contents: &str| config_pairs(contents).unwrap_err().report().to_string();
+
+        assert_eq!(error("version"), "expected `key = value` on line 1");
+        assert_eq!(error("[target]"), "unknown table `[target]` on line 1");
+        assert_eq!(
+            error("version = \"3.9\"\nversion = \"3.10\""),
+            "duplicate key `version` on line 2"
+        );
+        assert_eq!(
+            error("lib_dir = [\"a\"]"),
+            "unexpected array for `lib_dir` on line 1"
+        );
+        assert_eq!(
+            error("[sysconfig_vars]\nMULTIARCH = [\"a\"]"),
+            "expected a string for `MULTIARCH` in `[sysconfig_vars]` on line 2"
+        );
+        assert_eq!(
+            error("extra_link_args = [\n\"-lm\","),
+            "unterminated array starting on line 1"
+        );
+        assert_eq!(
+            error("lib_dir = \"/usr/lib"),
+            "invalid value for `lib_dir` on line 1\ncaused by:\n  - 0: unterminated string\n"
+        );
+        assert_eq!(
+            error("include = \"other.toml\""),
+            "`include` is not supported in TOML config files (line 1)"
+        );
+    }
+}
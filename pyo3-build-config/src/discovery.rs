@@ -0,0 +1,1072 @@
+//! Locating the Python interpreter on the build host: `PYO3_PYTHON`, virtualenvs, conda
+//! environments, pyenv shims, `PATH` and, on Windows, the `py` launcher and the registry.
+
+use std::{
+    collections::HashSet,
+    env,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    str,
+};
+
+use target_lexicon::Triple;
+
+use super::{
+    env_var, get_abi3_version, home_dir, run_python_script, supported_versions,
+    target_triple_from_env, CrossCompileConfig, InterpreterConfig, PythonImplementation,
+    PythonVersion, MAXIMUM_SUPPORTED_VERSION, MINIMUM_SUPPORTED_VERSION,
+};
+use crate::{
+    bail, ensure,
+    errors::{Context, Result},
+    warn,
+};
+
+fn venv_interpreter(virtual_env: &OsStr, windows: bool) -> PathBuf {
+    if windows {
+        Path::new(virtual_env).join("Scripts").join("python.exe")
+    } else {
+        Path::new(virtual_env).join("bin").join("python")
+    }
+}
+
+/// Returns the interpreter of the virtualenv at `virtual_env`, if it has one.
+///
+/// `VIRTUAL_ENV` is often left over from a deactivated or deleted virtualenv, in which case the
+/// interpreter is searched for on `PATH` as if no environment was active.
+fn existing_venv_interpreter(virtual_env: &OsStr, windows: bool) -> Option<PathBuf> {
+    let interpreter = venv_interpreter(virtual_env, windows);
+    if interpreter.is_file() {
+        Some(interpreter)
+    } else {
+        warn!(
+            PYO3_W005,
+            "VIRTUAL_ENV is set to {}, but this virtualenv does not contain a Python interpreter \
+             at {}; PyO3 will search for Python on PATH instead",
+            Path::new(virtual_env).display(),
+            interpreter.display()
+        );
+        None
+    }
+}
+
+/// Reads the `home` key of the `pyvenv.cfg` file of the virtualenv at `virtual_env`.
+///
+/// This is the directory containing the interpreter the virtualenv was created from.
+fn pyvenv_cfg_home(virtual_env: &Path) -> Option<PathBuf> {
+    let contents = fs::read_to_string(virtual_env.join("pyvenv.cfg")).ok()?;
+    contents.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim().eq_ignore_ascii_case("home")).then(|| PathBuf::from(value.trim()))
+    })
+}
+
+/// Returns the directory to link libpython from for an interpreter in the virtualenv at
+/// `virtual_env`, given the `lib_dir` the interpreter itself reported.
+///
+/// A virtualenv does not contain libpython, so the library is linked from the base installation.
+/// The reported directory is usually already that of the base installation, but some relocatable
+/// Python distributions report the directory they were built in rather than where they are
+/// installed. In that case the base installation is located through `pyvenv.cfg` instead.
+pub(crate) fn venv_lib_dir(
+    virtual_env: &Path,
+    lib_dir: Option<String>,
+    windows: bool,
+) -> Option<String> {
+    if lib_dir
+        .as_ref()
+        .map_or(false, |lib_dir| Path::new(lib_dir).is_dir())
+    {
+        return lib_dir;
+    }
+    pyvenv_cfg_home(virtual_env)
+        .map(|home| {
+            if windows {
+                // `home` is the installation root, which contains `python.exe` and `libs`.
+                home.join("libs")
+            } else {
+                // `home` is the `bin` directory of the installation prefix.
+                home.parent().unwrap_or(&home).join("lib")
+            }
+        })
+        .filter(|base_lib_dir| base_lib_dir.is_dir())
+        .map(|base_lib_dir| base_lib_dir.display().to_string())
+        .or(lib_dir)
+}
+
+fn conda_env_interpreter(conda_prefix: &OsStr, windows: bool) -> PathBuf {
+    if windows {
+        Path::new(conda_prefix).join("python.exe")
+    } else {
+        Path::new(conda_prefix).join("bin").join("python")
+    }
+}
+
+/// Returns the interpreter of the conda environment at `conda_prefix`, if it has one.
+///
+/// A conda environment need not have Python installed, in which case the interpreter is searched
+/// for on `PATH` as if no environment was active.
+fn existing_conda_env_interpreter(conda_prefix: &OsStr, windows: bool) -> Option<PathBuf> {
+    let interpreter = conda_env_interpreter(conda_prefix, windows);
+    if interpreter.is_file() {
+        Some(interpreter)
+    } else {
+        warn!(
+            PYO3_W005,
+            "CONDA_PREFIX is set to {}, but this conda environment does not contain a Python \
+             interpreter at {}; PyO3 will search for Python on PATH instead",
+            Path::new(conda_prefix).display(),
+            interpreter.display()
+        );
+        None
+    }
+}
+
+fn get_env_interpreter() -> Option<PathBuf> {
+    match (env_var("VIRTUAL_ENV"), env_var("CONDA_PREFIX")) {
+        // Use cfg rather than CARGO_CFG_TARGET_OS because this affects where files are located on the
+        // build host
+        (Some(dir), None) => existing_venv_interpreter(&dir, cfg!(windows)),
+        (None, Some(dir)) => existing_conda_env_interpreter(&dir, cfg!(windows)),
+        (Some(_), Some(_)) => {
+            warn!(
+                PYO3_W005,
+                "Both VIRTUAL_ENV and CONDA_PREFIX are set. PyO3 will ignore both of these for \
+                 locating the Python interpreter until you unset one of them."
+            );
+            None
+        }
+        (None, None) => None,
+    }
+}
+
+/// Attempts to locate a python interpreter.
+///
+/// Locations are checked in the order listed:
+///   1. If `PYO3_PYTHON` is set, this interpreter is used.
+///   2. If `PYO3_PYTHONS` is set, the newest working interpreter from that list is used.
+///   3. If in a virtualenv (`VIRTUAL_ENV`) or a conda environment (`CONDA_PREFIX`), that
+///      environment's interpreter is used, regardless of the order of `PATH`. A virtualenv does
+///      not contain libpython, so its base installation (located through `pyvenv.cfg`) is used for
+///      linking.
+///   4. If `PYO3_PYTHON_BEST_MATCH` is set to `1`, the newest of the `python3.Y`, `python3` and
+///      `python` executables on `PATH` which is recent enough for the build and matches the
+///      target pointer width, skipping the steps below.
+///   5. `python`, if this is functional a Python 3.x interpreter
+///   6. `python3`, as above
+///   7. On Windows, the default interpreter of the `py` launcher, or the newest Python 3
+///      registered in the Windows registry (PEP 514)
+pub fn find_interpreter() -> Result<PathBuf> {
+    // Trigger rebuilds when `PYO3_ENVIRONMENT_SIGNATURE` env var value changes
+    // See https://github.com/PyO3/pyo3/issues/2724
+    println!("cargo:rerun-if-env-changed=PYO3_ENVIRONMENT_SIGNATURE");
+
+    if let Some(exe) = env_var("PYO3_PYTHON") {
+        Ok(exe.into())
+    } else if let Some(candidates) = env_var("PYO3_PYTHONS") {
+        let exact_version = env_var("PYO3_PYTHONS_VERSION")
+            .map(|version| {
+                version
+                    .to_str()
+                    .ok_or("PYO3_PYTHONS_VERSION is not valid UTF-8")?
+                    .parse::<PythonVersion>()
+                    .context("failed to parse PYO3_PYTHONS_VERSION")
+            })
+            .transpose()?;
+        select_interpreter(find_all_interpreters(&candidates), exact_version).ok_or_else(|| {
+            match exact_version {
+                Some(version) => format!(
+                    "no Python {} interpreter found among the candidates in PYO3_PYTHONS",
+                    version
+                ),
+                None => "no working Python interpreter found among the candidates in PYO3_PYTHONS"
+                    .to_owned(),
+            }
+            .into()
+        })
+    } else if let Some(env_interpreter) = get_env_interpreter() {
+        Ok(env_interpreter)
+    } else if env_var("PYO3_PYTHON_BEST_MATCH").map_or(false, |os_str| os_str == "1") {
+        println!("cargo:rerun-if-env-changed=PATH");
+        let candidates = probe_interpreter_candidates(env::var_os("PATH").as_deref());
+        select_best_interpreter(candidates, &InterpreterConstraints::from_env())
+    } else {
+        println!("cargo:rerun-if-env-changed=PATH");
+        ["python", "python3"]
+            .iter()
+            .find(|bin| {
+                if let Ok(out) = Command::new(bin).arg("--version").output() {
+                    // begin with `Python 3.X.X :: additional info`
+                    out.stdout.starts_with(b"Python 3")
+                        || out.stderr.starts_with(b"Python 3")
+                        || out.stdout.starts_with(b"GraalPy 3")
+                } else {
+                    false
+                }
+            })
+            .map(PathBuf::from)
+            .or_else(|| {
+                if cfg!(windows) {
+                    find_windows_interpreter()
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| "no Python 3.x interpreter found".into())
+    }
+}
+
+/// Probes each of the interpreters in a `PATH`-style list, returning those which ran successfully
+/// along with their reported versions.
+///
+/// Candidates which fail to run are skipped.
+fn find_all_interpreters(candidates: &OsStr) -> Vec<(PathBuf, PythonVersion)> {
+    const SCRIPT: &str = "import sys; print('{}.{}'.format(*sys.version_info[:2]))";
+    env::split_paths(candidates)
+        .filter_map(|candidate| {
+            let version = run_python_script(&candidate, SCRIPT)
+                .ok()?
+                .trim()
+                .parse()
+                .ok()?;
+            Some((candidate, version))
+        })
+        .collect()
+}
+
+/// Chooses an interpreter from the successfully probed candidates.
+///
+/// If `exact_version` is given, the first candidate with that version is chosen. Otherwise the
+/// newest candidate is chosen, preferring earlier candidates when versions are equal.
+fn select_interpreter(
+    mut candidates: Vec<(PathBuf, PythonVersion)>,
+    exact_version: Option<PythonVersion>,
+) -> Option<PathBuf> {
+    if let Some(exact_version) = exact_version {
+        return candidates
+            .into_iter()
+            .find(|(_, version)| *version == exact_version)
+            .map(|(path, _)| path);
+    }
+    // stable sort, so equal versions keep their order from the list
+    candidates.sort_by(|(_, a), (_, b)| b.cmp(a));
+    candidates.into_iter().next().map(|(path, _)| path)
+}
+
+/// The requirements an interpreter found by [`select_best_interpreter`] has to meet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InterpreterConstraints {
+    /// The oldest acceptable version, taking the `abi3` minimum version into account.
+    min_version: PythonVersion,
+    /// The pointer width of the compile target, if the host interpreter has to match it.
+    pointer_width: Option<u32>,
+}
+
+impl InterpreterConstraints {
+    /// Must be called from a PyO3 crate build script.
+    fn from_env() -> Self {
+        let target = target_triple_from_env();
+        // A host interpreter only has to match the target when it could run the target's code.
+        let pointer_width =
+            if CrossCompileConfig::is_cross_compiling_from_to(&Triple::host(), &target) {
+                None
+            } else {
+                target
+                    .pointer_width()
+                    .ok()
+                    .map(|width| u32::from(width.bits()))
+            };
+        InterpreterConstraints {
+            min_version: get_abi3_version().map_or(MINIMUM_SUPPORTED_VERSION, |version| {
+                version.max(MINIMUM_SUPPORTED_VERSION)
+            }),
+            pointer_width,
+        }
+    }
+
+    /// Returns why an interpreter with `version` and `pointer_width` does not match, if it doesn't.
+    fn mismatch(&self, version: PythonVersion, pointer_width: u32) -> Option<String> {
+        if version < self.min_version {
+            Some(format!(
+                "Python {} is older than the minimum version {}",
+                version, self.min_version
+            ))
+        } else if self
+            .pointer_width
+            .map_or(false, |width| width != pointer_width)
+        {
+            Some(format!(
+                "{}-bit Python does not match the {}-bit target",
+                pointer_width,
+                self.pointer_width.unwrap()
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// An interpreter considered by [`select_best_interpreter`], with its version and pointer width
+/// or the reason it could not be probed.
+type InterpreterCandidate = (PathBuf, std::result::Result<(PythonVersion, u32), String>);
+
+/// Finds and probes the `python3.Y` (newest first), `python3` and `python` executables in the
+/// `PATH`-style list `path`. Executables reached through more than one name are probed once.
+fn probe_interpreter_candidates(path: Option<&OsStr>) -> Vec<InterpreterCandidate> {
+    const SCRIPT: &str = "import struct, sys; \
+        print('{}.{}'.format(*sys.version_info[:2])); print(struct.calcsize('P') * 8)";
+
+    let names = (MINIMUM_SUPPORTED_VERSION.minor..=MAXIMUM_SUPPORTED_VERSION.minor)
+        .rev()
+        .map(|minor| format!("python3.{}", minor))
+        .chain(["python3".to_owned(), "python".to_owned()]);
+    let mut seen = HashSet::new();
+    names
+        .filter_map(|name| {
+            find_on_path(
+                Path::new(&format!("{}{}", name, env::consts::EXE_SUFFIX)),
+                path,
+            )
+        })
+        .filter(|candidate| {
+            seen.insert(fs::canonicalize(candidate).unwrap_or_else(|_| candidate.clone()))
+        })
+        .map(|candidate| {
+            let probe = run_python_script(&candidate, SCRIPT)
+                .map_err(|e| e.to_string())
+                .and_then(|output| {
+                    let mut lines = output.lines();
+                    let version = lines.next().and_then(|line| line.trim().parse().ok());
+                    let pointer_width = lines.next().and_then(|line| line.trim().parse().ok());
+                    version
+                        .zip(pointer_width)
+                        .ok_or_else(|| format!("unexpected output `{}`", output.trim()))
+                });
+            (candidate, probe)
+        })
+        .collect()
+}
+
+/// Chooses the interpreter which best matches `constraints` from the probed candidates.
+///
+/// Among the matching candidates the newest version supported by PyO3 wins, then the newest
+/// unsupported one, then the earliest candidate. If none match, the error lists every candidate
+/// with the reason it was rejected.
+fn select_best_interpreter(
+    candidates: Vec<InterpreterCandidate>,
+    constraints: &InterpreterConstraints,
+) -> Result<PathBuf> {
+    let mut rejected = Vec::new();
+    let mut matching = Vec::new();
+    for (path, probe) in candidates {
+        match probe
+            .map(|(version, pointer_width)| (version, constraints.mismatch(version, pointer_width)))
+        {
+            Ok((version, None)) => matching.push((path, version)),
+            Ok((_, Some(reason))) | Err(reason) => rejected.push((path, reason)),
+        }
+    }
+
+    // stable sort, so equal candidates keep their order from `PATH`
+    matching.sort_by_key(|(_, version)| {
+        std::cmp::Reverse((*version <= MAXIMUM_SUPPORTED_VERSION, *version))
+    });
+    if let Some((path, _)) = matching.into_iter().next() {
+        return Ok(path);
+    }
+
+    ensure!(
+        !rejected.is_empty(),
+        "no Python interpreters found on PATH (PYO3_PYTHON_BEST_MATCH=1 is set)"
+    );
+    let mut error_msg = String::from(
+        "no Python interpreter on PATH matches the build (PYO3_PYTHON_BEST_MATCH=1 is set)\n\
+        candidates considered:",
+    );
+    for (path, reason) in rejected {
+        use std::fmt::Write;
+        write!(&mut error_msg, "\n\t{}: {}", path.display(), reason).unwrap();
+    }
+    bail!("{}", error_msg)
+}
+
+/// Checks if the executable is a Microsoft Store Python execution alias.
+fn is_windows_store_python(executable: &str) -> bool {
+    executable
+        .to_ascii_lowercase()
+        .contains("\\microsoft\\windowsapps\\")
+}
+
+/// Checks if `PYO3_ALLOW_WINDOWS_STORE_PYTHON` is set to `1`.
+pub(crate) fn allow_windows_store_python() -> bool {
+    env_var("PYO3_ALLOW_WINDOWS_STORE_PYTHON").map_or(false, |os_str| os_str == "1")
+}
+
+/// Rejects Microsoft Store Python unless explicitly allowed, in which case the library
+/// directory is taken from the real install location registered for the interpreter version.
+///
+/// `query_install_path` looks up the PEP 514 `InstallPath` registered for a version.
+pub(crate) fn fixup_for_windows_store_python(
+    interpreter_config: &mut InterpreterConfig,
+    allow: bool,
+    query_install_path: impl FnOnce(PythonVersion) -> Option<String>,
+) -> Result<()> {
+    match &interpreter_config.executable {
+        Some(executable) if is_windows_store_python(executable) => {}
+        _ => return Ok(()),
+    }
+
+    ensure!(
+        allow,
+        "the Python interpreter at {} is a Microsoft Store execution alias, which is not \
+        supported by default\n\
+        = help: install Python from python.org or set PYO3_PYTHON to a different interpreter\n\
+        = help: set PYO3_ALLOW_WINDOWS_STORE_PYTHON=1 to build against the Store Python anyway",
+        interpreter_config.executable.as_deref().unwrap_or_default()
+    );
+
+    if let Some(install_path) = query_install_path(interpreter_config.version) {
+        interpreter_config.lib_dir = Some(format!("{}\\libs", install_path.trim_end_matches('\\')));
+    }
+
+    Ok(())
+}
+
+/// Reads the PEP 514 `InstallPath` of a CPython version registered for the current user.
+pub(crate) fn query_python_core_install_path(version: PythonVersion) -> Option<String> {
+    query_python_core_tag_install_path("HKCU", &version.to_string())
+}
+
+/// Reads the PEP 514 `InstallPath` of the CPython registration `tag` (e.g. `3.11` or `3.11-32`)
+/// in the registry `hive` (`HKCU` or `HKLM`).
+fn query_python_core_tag_install_path(hive: &str, tag: &str) -> Option<String> {
+    let key = format!(
+        "{}\\Software\\Python\\PythonCore\\{}\\InstallPath",
+        hive, tag
+    );
+    parse_reg_query_default_value(&run_reg_query(&[&key, "/ve"])?)
+}
+
+/// Runs `reg query` with the given arguments, returning its output if successful.
+fn run_reg_query(args: &[&str]) -> Option<String> {
+    let output = Command::new("reg").arg("query").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Extracts the default value from the output of `reg query <key> /ve`.
+fn parse_reg_query_default_value(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (_, value) = line.split_once("REG_SZ")?;
+        let value = value.trim();
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_owned())
+        }
+    })
+}
+
+/// Finds `name` in the `PATH`-style list of directories `path`, if it is a bare file name.
+///
+/// Paths with more than one component are returned unchanged.
+pub(crate) fn find_on_path(name: &Path, path: Option<&OsStr>) -> Option<PathBuf> {
+    if name.components().count() != 1 {
+        return Some(name.to_owned());
+    }
+    env::split_paths(path?)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Returns the pyenv installation directory, from `PYENV_ROOT` or defaulting to `~/.pyenv`.
+pub(crate) fn pyenv_root() -> Option<PathBuf> {
+    env_var("PYENV_ROOT")
+        .filter(|root| !root.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| Some(Path::new(&home_dir()?).join(".pyenv")))
+}
+
+/// Returns `true` if `interpreter` is one of the shims in the `shims` directory of pyenv.
+fn is_pyenv_shim(interpreter: &Path, pyenv_root: &Path) -> bool {
+    interpreter.parent() == Some(&pyenv_root.join("shims"))
+}
+
+/// If `interpreter` is a pyenv shim, resolves it to the interpreter it currently dispatches to.
+///
+/// The shim's target depends on `PYENV_VERSION` and `.python-version` files, which are not known
+/// to the interpreter itself, so `pyenv_which` is used to ask pyenv for the real interpreter
+/// (given the shim's file name, e.g. `python3`). Returns `Ok(None)` if `interpreter` is not a
+/// shim.
+pub(crate) fn resolve_pyenv_shim(
+    interpreter: &Path,
+    pyenv_root: &Path,
+    pyenv_which: impl FnOnce(&OsStr) -> Result<PathBuf>,
+) -> Result<Option<PathBuf>> {
+    if !is_pyenv_shim(interpreter, pyenv_root) {
+        return Ok(None);
+    }
+    let name = interpreter
+        .file_name()
+        .expect("pyenv shim paths have a file name");
+    let resolved = pyenv_which(name)
+        .with_context(|| format!("failed to resolve pyenv shim {}", interpreter.display()))?;
+    Ok(Some(resolved))
+}
+
+/// Runs `pyenv which <name>`, asking Cargo to rerun the build script when the selected pyenv
+/// version may have changed.
+pub(crate) fn pyenv_which(pyenv_root: &Path, name: &OsStr) -> Result<PathBuf> {
+    let pyenv = pyenv_root.join("bin").join("pyenv");
+    let pyenv = if pyenv.is_file() {
+        pyenv
+    } else {
+        PathBuf::from("pyenv")
+    };
+
+    // Either of these selects the version the shim dispatches to.
+    env_var("PYENV_VERSION");
+    if let Ok(output) = Command::new(&pyenv).arg("version-file").output() {
+        if let Ok(version_file) = str::from_utf8(&output.stdout) {
+            let version_file = version_file.trim();
+            if output.status.success() && !version_file.is_empty() {
+                println!("cargo:rerun-if-changed={}", version_file);
+            }
+        }
+    }
+
+    let output = Command::new(&pyenv)
+        .arg("which")
+        .arg(name)
+        .output()
+        .with_context(|| format!("failed to run {}", pyenv.display()))?;
+    ensure!(
+        output.status.success(),
+        "`pyenv which {}` failed: {}",
+        Path::new(name).display(),
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    let resolved = String::from_utf8(output.stdout)
+        .context("`pyenv which` output is not valid UTF-8")?
+        .trim()
+        .to_owned();
+    ensure!(
+        !resolved.is_empty(),
+        "`pyenv which {}` returned nothing",
+        Path::new(name).display()
+    );
+    Ok(PathBuf::from(resolved))
+}
+
+/// Finds a Python interpreter on Windows hosts where neither `python` nor `python3` is on `PATH`.
+///
+/// The `py` launcher is asked for its default interpreter first, as it knows about all
+/// registered Python installations (including those from the Microsoft Store). Failing that, the
+/// newest Python 3 registered according to PEP 514 is used.
+fn find_windows_interpreter() -> Option<PathBuf> {
+    let from_launcher = Command::new("py")
+        .arg("-0p")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_py_launcher_list(str::from_utf8(&output.stdout).ok()?));
+    from_launcher.or_else(|| {
+        ["HKCU", "HKLM"].iter().find_map(|hive| {
+            let key = format!("{}\\Software\\Python\\PythonCore", hive);
+            let tags = parse_python_core_tags(&run_reg_query(&[&key])?);
+            tags.iter().find_map(|(_, tag)| {
+                let install_path = query_python_core_tag_install_path(hive, tag)?;
+                let executable = Path::new(&install_path).join("python.exe");
+                executable.is_file().then_some(executable)
+            })
+        })
+    })
+}
+
+/// Parses the output of `py -0p`, returning the launcher's default interpreter if marked with
+/// `*`, or else the first Python 3 interpreter listed.
+///
+/// Lines look like ` -V:3.12 *        C:\Python312\python.exe` (or ` -3.9-64  C:\...` for older
+/// launchers).
+fn parse_py_launcher_list(output: &str) -> Option<PathBuf> {
+    let mut first = None;
+    for line in output.lines() {
+        let line = line.trim();
+        let (tag, rest) = match line.split_once(char::is_whitespace) {
+            Some(split) => split,
+            None => continue,
+        };
+        let version = tag.trim_start_matches("-V:").trim_start_matches('-');
+        if !version.starts_with("3.") {
+            continue;
+        }
+        let rest = rest.trim_start();
+        let (is_default, path) = match rest.strip_prefix('*') {
+            Some(path) => (true, path.trim()),
+            None => (false, rest.trim_end()),
+        };
+        if path.is_empty() {
+            continue;
+        }
+        if is_default {
+            return Some(PathBuf::from(path));
+        }
+        first.get_or_insert_with(|| PathBuf::from(path));
+    }
+    first
+}
+
+/// Parses the subkeys of a PEP 514 `PythonCore` registry key from the output of `reg query`,
+/// returning the supported versions with their tags, newest first.
+fn parse_python_core_tags(output: &str) -> Vec<(PythonVersion, String)> {
+    let (min, max) = supported_versions(PythonImplementation::CPython);
+    let mut tags: Vec<_> = output
+        .lines()
+        .filter_map(|line| {
+            let (_, tag) = line.trim().rsplit_once("\\PythonCore\\")?;
+            let version = tag.split('-').next()?.parse::<PythonVersion>().ok()?;
+            (min..=max)
+                .contains(&version)
+                .then(|| (version, tag.to_owned()))
+        })
+        .collect();
+    tags.sort_by(|(a, _), (b, _)| b.cmp(a));
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::impl_::BuildFlags;
+
+    #[test]
+    fn test_venv_interpreter() {
+        let base = OsStr::new("base");
+        assert_eq!(
+            venv_interpreter(base, true),
+            PathBuf::from_iter(&["base", "Scripts", "python.exe"])
+        );
+        assert_eq!(
+            venv_interpreter(base, false),
+            PathBuf::from_iter(&["base", "bin", "python"])
+        );
+    }
+
+    #[test]
+    fn venv_layouts() {
+        let dir = env::temp_dir().join(format!("pyo3-venv-layouts-{}", std::process::id()));
+        // (windows, pyvenv.cfg home, base library directory)
+        let layouts = [
+            // Windows: `home` is the installation root.
+            (
+                true,
+                dir.join("Python312"),
+                dir.join("Python312").join("libs"),
+            ),
+            // Linux: `home` is the `bin` directory of the prefix.
+            (
+                false,
+                dir.join("usr").join("bin"),
+                dir.join("usr").join("lib"),
+            ),
+            // macOS framework builds follow the same layout inside the framework.
+            (
+                false,
+                dir.join("Python.framework/Versions/3.12/bin"),
+                dir.join("Python.framework/Versions/3.12/lib"),
+            ),
+        ];
+        for (windows, home, base_lib_dir) in layouts {
+            let venv = dir.join(".venv");
+            std::fs::create_dir_all(&venv).unwrap();
+            std::fs::create_dir_all(&base_lib_dir).unwrap();
+            std::fs::write(
+                venv.join("pyvenv.cfg"),
+                format!(
+                    "home = {}\ninclude-system-site-packages = false\nversion = 3.12.1\n",
+                    home.display()
+                ),
+            )
+            .unwrap();
+
+            assert_eq!(pyvenv_cfg_home(&venv), Some(home));
+            let base_lib_dir = base_lib_dir.display().to_string();
+            // A library directory which exists is kept...
+            assert_eq!(
+                venv_lib_dir(&venv, Some(base_lib_dir.clone()), windows).as_deref(),
+                Some(base_lib_dir.as_str())
+            );
+            // ... but a stale one is resolved to the base installation.
+            assert_eq!(
+                venv_lib_dir(&venv, Some("/install/lib".to_owned()), windows).as_deref(),
+                Some(base_lib_dir.as_str())
+            );
+            assert_eq!(
+                venv_lib_dir(&venv, None, windows).as_deref(),
+                Some(base_lib_dir.as_str())
+            );
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+
+        // Without pyvenv.cfg there is nothing better than the reported directory.
+        assert_eq!(
+            venv_lib_dir(&dir, Some("/install/lib".to_owned()), false).as_deref(),
+            Some("/install/lib")
+        );
+
+        let venv = dir.join(".venv");
+        assert_eq!(existing_venv_interpreter(venv.as_os_str(), false), None);
+        std::fs::create_dir_all(venv.join("bin")).unwrap();
+        std::fs::write(venv.join("bin").join("python"), "").unwrap();
+        assert_eq!(
+            existing_venv_interpreter(venv.as_os_str(), false),
+            Some(venv.join("bin").join("python"))
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_conda_env_interpreter() {
+        let base = OsStr::new("base");
+        assert_eq!(
+            conda_env_interpreter(base, true),
+            PathBuf::from_iter(&["base", "python.exe"])
+        );
+        assert_eq!(
+            conda_env_interpreter(base, false),
+            PathBuf::from_iter(&["base", "bin", "python"])
+        );
+    }
+
+    #[test]
+    fn test_resolve_pyenv_shim() {
+        let root = Path::new("/home/pyo3/.pyenv");
+        let resolved = PathBuf::from("/home/pyo3/.pyenv/versions/3.12.1/bin/python3");
+        let which = |name: &OsStr| -> Result<PathBuf> {
+            assert_eq!(name, "python3");
+            Ok(resolved.clone())
+        };
+
+        assert_eq!(
+            resolve_pyenv_shim(&root.join("shims").join("python3"), root, which).unwrap(),
+            Some(resolved.clone())
+        );
+        for not_a_shim in [
+            "/usr/bin/python3",
+            "/home/pyo3/.pyenv/versions/3.12.1/bin/python3",
+        ] {
+            assert_eq!(
+                resolve_pyenv_shim(Path::new(not_a_shim), root, |_| unreachable!()).unwrap(),
+                None
+            );
+        }
+
+        let error = resolve_pyenv_shim(&root.join("shims").join("python3"), root, |_| {
+            Err("`pyenv which python3` failed: python3: command not found".into())
+        })
+        .unwrap_err();
+        assert_eq!(
+            error.report().to_string(),
+            "failed to resolve pyenv shim /home/pyo3/.pyenv/shims/python3\ncaused by:\n  \
+             - 0: `pyenv which python3` failed: python3: command not found\n"
+        );
+    }
+
+    #[test]
+    fn test_find_on_path() {
+        let dir = env::temp_dir().join(format!("pyo3-find-on-path-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("b")).unwrap();
+        std::fs::write(dir.join("b").join("python3"), "").unwrap();
+        let path = env::join_paths([dir.join("a"), dir.join("b")]).unwrap();
+
+        assert_eq!(
+            find_on_path(Path::new("python3"), Some(&path)),
+            Some(dir.join("b").join("python3"))
+        );
+        assert_eq!(find_on_path(Path::new("python"), Some(&path)), None);
+        assert_eq!(find_on_path(Path::new("python3"), None), None);
+        assert_eq!(
+            find_on_path(Path::new("/usr/bin/python3"), None),
+            Some(PathBuf::from("/usr/bin/python3"))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_existing_conda_env_interpreter() {
+        let dir = env::temp_dir().join(format!("pyo3-conda-env-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("bin")).unwrap();
+
+        // no Python installed in the environment
+        assert_eq!(existing_conda_env_interpreter(dir.as_os_str(), false), None);
+
+        std::fs::write(dir.join("bin").join("python"), "").unwrap();
+        assert_eq!(
+            existing_conda_env_interpreter(dir.as_os_str(), false),
+            Some(dir.join("bin").join("python"))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_windows_store_python() {
+        let store_executable = "C:\\Users\\user\\AppData\\Local\\Microsoft\\WindowsApps\\\
+                                PythonSoftwareFoundation.Python.3.11_qbz5n2kfra8p0\\python.exe";
+        let install_path = "C:\\Program Files\\WindowsApps\\\
+                            PythonSoftwareFoundation.Python.3.11_3.11.2544.0_x64__qbz5n2kfra8p0";
+        assert!(is_windows_store_python(store_executable));
+        assert!(!is_windows_store_python(
+            "C:\\Users\\user\\AppData\\Local\\Programs\\Python\\Python311\\python.exe"
+        ));
+
+        let config = InterpreterConfig {
+            implementation: PythonImplementation::CPython,
+            version: PythonVersion {
+                major: 3,
+                minor: 11,
+            },
+            shared: true,
+            abi3: false,
+            lib_name: Some("python311".into()),
+            lib_dir: Some("C:\\Users\\user\\AppData\\Local\\Microsoft\\WindowsApps\\libs".into()),
+            executable: Some(store_executable.into()),
+            platform_tag: None,
+            python_framework_prefix: None,
+            pointer_width: Some(64),
+            pointer_width_inferred: false,
+            build_flags: BuildFlags::default(),
+            suppress_build_script_link_lines: false,
+            extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+
+        // rejected by default
+        let mut rejected = config.clone();
+        let error = fixup_for_windows_store_python(&mut rejected, false, |_| {
+            panic!("registry should not be queried")
+        })
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("Microsoft Store"));
+        assert!(error.contains("PYO3_ALLOW_WINDOWS_STORE_PYTHON"));
+
+        // resolved from the registry when allowed
+        let mut allowed = config.clone();
+        fixup_for_windows_store_python(&mut allowed, true, |version| {
+            assert_eq!(version.to_string(), "3.11");
+            Some(format!("{}\\", install_path))
+        })
+        .unwrap();
+        assert_eq!(allowed.lib_dir, Some(format!("{}\\libs", install_path)));
+
+        // falls back to the interpreter's own lib_dir if no registry entry exists
+        let mut unregistered = config.clone();
+        fixup_for_windows_store_python(&mut unregistered, true, |_| None).unwrap();
+        assert_eq!(unregistered.lib_dir, config.lib_dir);
+
+        // non-Store interpreters are untouched
+        let mut regular = InterpreterConfig {
+            executable: Some("C:\\Python311\\python.exe".into()),
+            ..config
+        };
+        fixup_for_windows_store_python(&mut regular, false, |_| unreachable!()).unwrap();
+
+        assert_eq!(
+            parse_reg_query_default_value(&format!(
+                "\r\nHKEY_CURRENT_USER\\Software\\Python\\PythonCore\\3.11\\InstallPath\r\n    \
+                 (Default)    REG_SZ    {}\\\r\n\r\n",
+                install_path
+            )),
+            Some(format!("{}\\", install_path))
+        );
+        assert_eq!(parse_reg_query_default_value(""), None);
+    }
+
+    #[test]
+    fn test_parse_py_launcher_list() {
+        let output = " -V:3.12          C:\\Program Files\\Python312\\python.exe\r\n \
+                      -V:3.11 *        C:\\Python311\\python.exe\r\n \
+                      -V:2.7           C:\\Python27\\python.exe\r\n";
+        assert_eq!(
+            parse_py_launcher_list(output),
+            Some(PathBuf::from("C:\\Python311\\python.exe"))
+        );
+
+        // without a default, the first Python 3 is used; older launchers use `-X.Y-64` tags
+        let output = " -2.7-64        C:\\Python27\\python.exe\r\n \
+                      -3.9-64        C:\\Program Files\\Python39\\python.exe\r\n";
+        assert_eq!(
+            parse_py_launcher_list(output),
+            Some(PathBuf::from("C:\\Program Files\\Python39\\python.exe"))
+        );
+
+        assert_eq!(
+            parse_py_launcher_list("No installed Pythons found!\r\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_python_core_tags() {
+        let output = "\r\nHKEY_CURRENT_USER\\Software\\Python\\PythonCore\\3.9-32\r\n\
+                      HKEY_CURRENT_USER\\Software\\Python\\PythonCore\\3.12\r\n\
+                      HKEY_CURRENT_USER\\Software\\Python\\PythonCore\\2.7\r\n\
+                      HKEY_CURRENT_USER\\Software\\Python\\PythonCore\\3.6\r\n";
+        assert_eq!(
+            parse_python_core_tags(output),
+            [
+                (
+                    PythonVersion {
+                        major: 3,
+                        minor: 12
+                    },
+                    "3.12".to_owned()
+                ),
+                (PythonVersion { major: 3, minor: 9 }, "3.9-32".to_owned()),
+            ]
+        );
+        assert!(parse_python_core_tags("").is_empty());
+    }
+
+    #[test]
+    fn best_interpreter_selection() {
+        let constraints = InterpreterConstraints {
+            min_version: PythonVersion { major: 3, minor: 8 },
+            pointer_width: Some(64),
+        };
+        let v = |minor| PythonVersion { major: 3, minor };
+        let unsupported = PythonVersion {
+            major: 3,
+            minor: MAXIMUM_SUPPORTED_VERSION.minor + 1,
+        };
+
+        let candidates = vec![
+            (PathBuf::from("/usr/bin/python3.7"), Ok((v(7), 64))),
+            (PathBuf::from("/opt/bin/python3.11"), Ok((v(11), 32))),
+            (PathBuf::from("/usr/bin/python3.9"), Ok((v(9), 64))),
+            (PathBuf::from("/usr/bin/python3.10"), Ok((v(10), 64))),
+            (PathBuf::from("/usr/local/bin/python3.10"), Ok((v(10), 64))),
+            (PathBuf::from("/usr/bin/python3"), Ok((unsupported, 64))),
+            (PathBuf::from("/usr/bin/python"), Err("broken".to_owned())),
+        ];
+        // newest supported version wins, the first on PATH among equals
+        assert_eq!(
+            select_best_interpreter(candidates, &constraints).unwrap(),
+            PathBuf::from("/usr/bin/python3.10")
+        );
+
+        // a version newer than PyO3 supports is still better than none
+        assert_eq!(
+            select_best_interpreter(
+                vec![
+                    (PathBuf::from("/usr/bin/python3.7"), Ok((v(7), 64))),
+                    (PathBuf::from("/usr/bin/python3"), Ok((unsupported, 64))),
+                ],
+                &constraints
+            )
+            .unwrap(),
+            PathBuf::from("/usr/bin/python3")
+        );
+
+        assert_eq!(
+            select_best_interpreter(
+                vec![
+                    (PathBuf::from("/usr/bin/python3.7"), Ok((v(7), 64))),
+                    (PathBuf::from("/opt/bin/python3.11"), Ok((v(11), 32))),
+                    (PathBuf::from("/usr/bin/python"), Err("broken".to_owned())),
+                ],
+                &constraints
+            )
+            .unwrap_err()
+            .to_string(),
+            "no Python interpreter on PATH matches the build (PYO3_PYTHON_BEST_MATCH=1 is set)\n\
+            candidates considered:\n\
+            \t/usr/bin/python3.7: Python 3.7 is older than the minimum version 3.8\n\
+            \t/opt/bin/python3.11: 32-bit Python does not match the 64-bit target\n\
+            \t/usr/bin/python: broken"
+        );
+
+        assert_eq!(
+            select_best_interpreter(vec![], &constraints)
+                .unwrap_err()
+                .to_string(),
+            "no Python interpreters found on PATH (PYO3_PYTHON_BEST_MATCH=1 is set)"
+        );
+
+        // without a pointer width to match, e.g. when cross compiling, any width is fine
+        let cross_constraints = InterpreterConstraints {
+            pointer_width: None,
+            ..constraints
+        };
+        assert_eq!(cross_constraints.mismatch(v(11), 32), None);
+    }
+
+    #[test]
+    fn test_select_interpreter_prefers_newest() {
+        let candidates = || {
+            vec![
+                (
+                    PathBuf::from("python3.9"),
+                    PythonVersion { major: 3, minor: 9 },
+                ),
+                (
+                    PathBuf::from("python3.12"),
+                    PythonVersion {
+                        major: 3,
+                        minor: 12,
+                    },
+                ),
+                (
+                    PathBuf::from("python3.10"),
+                    PythonVersion {
+                        major: 3,
+                        minor: 10,
+                    },
+                ),
+                (
+                    PathBuf::from("other/python3.12"),
+                    PythonVersion {
+                        major: 3,
+                        minor: 12,
+                    },
+                ),
+            ]
+        };
+
+        assert_eq!(
+            select_interpreter(candidates(), None),
+            Some(PathBuf::from("python3.12"))
+        );
+        assert_eq!(
+            select_interpreter(candidates(), Some(PythonVersion { major: 3, minor: 9 })),
+            Some(PathBuf::from("python3.9"))
+        );
+        assert_eq!(
+            select_interpreter(candidates(), Some(PythonVersion { major: 3, minor: 7 })),
+            None
+        );
+        assert_eq!(select_interpreter(Vec::new(), None), None);
+    }
+
+    #[test]
+    fn test_find_all_interpreters_skips_failures() {
+        let interpreter = find_interpreter().unwrap();
+        let candidates =
+            env::join_paths([Path::new("/nonexistent/python3"), interpreter.as_path()]).unwrap();
+        let found = find_all_interpreters(&candidates);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, interpreter);
+    }
+}
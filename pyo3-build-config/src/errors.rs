@@ -13,11 +13,17 @@ macro_rules! ensure {
 }
 
 /// Show warning.
+///
+/// The message may be preceded by a warning code, e.g. `warn!(PYO3_W002, "...")`, which is shown
+/// with the message and can be denied with `PYO3_DENY_WARNINGS`. See [`emit_warning`].
 #[macro_export]
 #[doc(hidden)]
 macro_rules! warn {
+    ($code:ident, $($args: tt)+) => {
+        $crate::errors::emit_warning(Some(stringify!($code)), &format!($($args)+))
+    };
     ($($args: tt)+) => {
-        println!("{}", $crate::format_warn!($($args)+))
+        $crate::errors::emit_warning(None, &format!($($args)+))
     };
 }
 
@@ -30,6 +36,12 @@ macro_rules! format_warn {
     };
 }
 
+use std::{
+    ffi::{OsStr, OsString},
+    io::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
 /// A simple error implementation which allows chaining of errors, inspired somewhat by anyhow.
 #[derive(Debug)]
 pub struct Error {
@@ -42,6 +54,7 @@ pub struct Error {
 pub struct ErrorReport<'a>(&'a Error);
 
 impl Error {
+    /// Returns a report of this error which also displays the chain of causes.
     pub fn report(&self) -> ErrorReport<'_> {
         ErrorReport(self)
     }
@@ -124,6 +137,126 @@ where
     }
 }
 
+/// The number of warnings emitted so far which `PYO3_DENY_WARNINGS` turns into an error.
+static DENIED_WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+/// Shows a warning from a build script, with its `code` (such as `PYO3_W001`) if it has one.
+///
+/// Warnings are printed as `cargo:warning=` lines. In addition:
+/// - if `PYO3_PRINT_DIAGNOSTICS_JSON` is set, the warning is also written as a line of JSON, to
+///   stderr if the variable is `1` and otherwise appended to the file it names;
+/// - if `PYO3_DENY_WARNINGS` is `1`, or a comma-separated list of codes containing `code`, the
+///   warning is counted so that [`check_denied_warnings`] fails the build.
+pub fn emit_warning(code: Option<&str>, message: &str) {
+    println!("cargo:warning={}", warning_text(code, message));
+    emit_json("warning", code, message);
+    if is_denied(code, diagnostics_env_var("PYO3_DENY_WARNINGS").as_deref()) {
+        DENIED_WARNINGS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Fails if any warning denied by `PYO3_DENY_WARNINGS` was emitted by this build script.
+pub fn check_denied_warnings() -> Result<()> {
+    match DENIED_WARNINGS.load(Ordering::Relaxed) {
+        0 => Ok(()),
+        1 => Err("1 warning was denied by PYO3_DENY_WARNINGS".into()),
+        n => Err(format!("{} warnings were denied by PYO3_DENY_WARNINGS", n).into()),
+    }
+}
+
+/// Reports a build script error, also as JSON if requested, and exits the build script.
+pub fn exit_with_error(error: &Error) -> ! {
+    let report = error.report().to_string();
+    eprintln!("error: {}", report);
+    emit_json("error", None, report.trim_end());
+    std::process::exit(1)
+}
+
+fn warning_text(code: Option<&str>, message: &str) -> String {
+    match code {
+        Some(code) => format!("{}: {}", code, message),
+        None => message.to_owned(),
+    }
+}
+
+fn is_denied(code: Option<&str>, deny: Option<&OsStr>) -> bool {
+    let deny = match deny.and_then(OsStr::to_str) {
+        Some(deny) => deny,
+        None => return false,
+    };
+    match code {
+        _ if deny == "1" => true,
+        Some(code) => deny.split(',').any(|denied| denied.trim() == code),
+        None => false,
+    }
+}
+
+fn emit_json(level: &str, code: Option<&str>, message: &str) {
+    let destination = match diagnostics_env_var("PYO3_PRINT_DIAGNOSTICS_JSON") {
+        Some(destination) => destination,
+        None => return,
+    };
+    let package = std::env::var("CARGO_PKG_NAME").ok();
+    let line = diagnostic_json(level, code, package.as_deref(), message);
+    if destination == "1" {
+        eprintln!("{}", line);
+    } else {
+        // Failing to record a diagnostic should not fail the build as well.
+        let _ = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&destination)
+            .and_then(|mut file| writeln!(file, "{}", line));
+    }
+}
+
+/// Formats a diagnostic as a single line of JSON, e.g.
+/// `{"level":"warning","code":"PYO3_W002","package":"pyo3-ffi","message":"..."}`.
+fn diagnostic_json(
+    level: &str,
+    code: Option<&str>,
+    package: Option<&str>,
+    message: &str,
+) -> String {
+    fn json_value(value: Option<&str>) -> String {
+        let value = match value {
+            Some(value) => value,
+            None => return "null".to_owned(),
+        };
+        let mut out = String::with_capacity(value.len() + 2);
+        out.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+        out
+    }
+    format!(
+        "{{\"level\":{},\"code\":{},\"package\":{},\"message\":{}}}",
+        json_value(Some(level)),
+        json_value(code),
+        json_value(package),
+        json_value(Some(message))
+    )
+}
+
+/// Reads an environment variable which controls how diagnostics are reported.
+///
+/// This is only called once there is something to report, so the build script is only rerun
+/// when the variable changes if that would change its output.
+fn diagnostics_env_var(name: &str) -> Option<OsString> {
+    println!("cargo:rerun-if-env-changed={}", name);
+    std::env::var_os(name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -150,4 +283,44 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn warning_codes() {
+        assert_eq!(
+            warning_text(Some("PYO3_W002"), "unknown config key `foo`"),
+            "PYO3_W002: unknown config key `foo`"
+        );
+        assert_eq!(warning_text(None, "a note"), "a note");
+
+        assert!(!is_denied(Some("PYO3_W002"), None));
+        assert!(is_denied(Some("PYO3_W002"), Some(OsStr::new("1"))));
+        assert!(is_denied(None, Some(OsStr::new("1"))));
+        assert!(is_denied(
+            Some("PYO3_W002"),
+            Some(OsStr::new("PYO3_W001, PYO3_W002"))
+        ));
+        assert!(!is_denied(
+            Some("PYO3_W003"),
+            Some(OsStr::new("PYO3_W001,PYO3_W002"))
+        ));
+        assert!(!is_denied(None, Some(OsStr::new("PYO3_W001"))));
+        assert!(!is_denied(Some("PYO3_W001"), Some(OsStr::new("0"))));
+    }
+
+    #[test]
+    fn diagnostics_as_json() {
+        assert_eq!(
+            diagnostic_json(
+                "warning",
+                Some("PYO3_W002"),
+                Some("pyo3-ffi"),
+                "unknown config key `foo`"
+            ),
+            r#"{"level":"warning","code":"PYO3_W002","package":"pyo3-ffi","message":"unknown config key `foo`"}"#
+        );
+        assert_eq!(
+            diagnostic_json("error", None, None, "failed:\n\t\"C:\\Python\" \u{1}"),
+            r#"{"level":"error","code":null,"package":null,"message":"failed:\n\t\"C:\\Python\" \u0001"}"#
+        );
+    }
 }
@@ -6,12 +6,28 @@
 #[path = "import_lib.rs"]
 mod import_lib;
 
+// Reading of config files written in TOML
+#[path = "config_toml.rs"]
+mod config_toml;
+
+// Locating the Python interpreter on the build host
+#[path = "discovery.rs"]
+mod discovery;
+
+// Searching for the target's sysconfigdata
+#[path = "sysconfigdata.rs"]
+mod sysconfigdata;
+
+// Python versions and the range supported by PyO3
+#[path = "version.rs"]
+mod version;
+
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     env,
     ffi::{OsStr, OsString},
     fmt::Display,
-    fs::{self, DirEntry},
+    fs,
     io::{BufRead, BufReader, Read, Write},
     path::{Path, PathBuf},
     process::{Command, Stdio},
@@ -21,7 +37,7 @@ use std::{
 
 pub use target_lexicon::Triple;
 
-use target_lexicon::{Environment, OperatingSystem};
+use target_lexicon::{Architecture, Environment, OperatingSystem};
 
 use crate::{
     bail, ensure,
@@ -29,17 +45,30 @@ use crate::{
     warn,
 };
 
-/// Minimum Python version PyO3 supports.
-pub(crate) const MINIMUM_SUPPORTED_VERSION: PythonVersion = PythonVersion { major: 3, minor: 7 };
-
-/// GraalPy may implement the same CPython version over multiple releases.
-const MINIMUM_SUPPORTED_VERSION_GRAALPY: PythonVersion = PythonVersion {
-    major: 24,
-    minor: 0,
+pub use self::discovery::find_interpreter;
+use self::discovery::{
+    allow_windows_store_python, find_on_path, fixup_for_windows_store_python, pyenv_root,
+    pyenv_which, query_python_core_install_path, resolve_pyenv_shim, venv_lib_dir,
+};
+use self::sysconfigdata::find_sysconfigdata;
+pub use self::sysconfigdata::{
+    find_all_sysconfigdata, parse_sysconfigdata, search_sysconfigdata, Sysconfigdata,
+    SysconfigdataSearch,
 };
+pub use self::version::{check_support, PythonVersion, PythonVersionReq};
+pub(crate) use self::version::{
+    supported_versions, MAXIMUM_SUPPORTED_VERSION, MINIMUM_SUPPORTED_VERSION,
+    MINIMUM_SUPPORTED_VERSION_GRAALPY,
+};
+
+/// Minor version of the first `Py_3_*` cfg emitted.
+///
+/// pyo3-build-config was released when Python 3.6 was supported, so minimum flag to emit is
+/// Py_3_6 (to avoid silently breaking users who depend on this cfg).
+pub(crate) const MINIMUM_VERSION_CFG_MINOR: u8 = 6;
 
 /// Maximum Python version that can be used as minimum required Python version with abi3.
-pub(crate) const ABI3_MAX_MINOR: u8 = 12;
+pub(crate) const ABI3_MAX_MINOR: u8 = 13;
 
 /// Gets an environment variable owned by cargo.
 ///
@@ -57,6 +86,9 @@ pub fn env_var(var: &str) -> Option<OsString> {
     env::var_os(var)
 }
 
+/// File name of the canonical text serialization of an [`InterpreterConfig`].
+pub(crate) const CONFIG_FILE_NAME: &str = "pyo3-build-config.txt";
+
 /// Gets the compilation target triple from environment variables set by Cargo.
 ///
 /// Must be called from a crate build script.
@@ -74,7 +106,9 @@ pub fn target_triple_from_env() -> Triple {
 ///
 /// When the `PYO3_NO_PYTHON` variable is set, or during cross compile situations, then alternative
 /// strategies are used to populate this type.
-#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+///
+/// Build tools can also construct a config programmatically using [`InterpreterConfigBuilder`].
+#[cfg_attr(test, derive(Clone, Debug, PartialEq, Eq))]
 pub struct InterpreterConfig {
     /// The Python implementation flavor.
     ///
@@ -122,11 +156,35 @@ pub struct InterpreterConfig {
     /// Serialized to `executable`.
     pub executable: Option<String>,
 
+    /// The platform tag of the target Python, as returned by `sysconfig.get_platform()`.
+    ///
+    /// e.g. `linux-x86_64`, `macosx-11.0-arm64` or `win-amd64`. Tooling building wheels can use
+    /// this to construct platform tags and to check that the compile target matches.
+    ///
+    /// Serialized to `platform_tag`.
+    pub platform_tag: Option<String>,
+
+    /// The directory containing `Python.framework`, for macOS framework builds of Python, as
+    /// found in the `PYTHONFRAMEWORKPREFIX` sysconfig variable.
+    ///
+    /// e.g. `/Library/Frameworks` for the python.org installers, or
+    /// `/opt/homebrew/opt/python@3.12/Frameworks` for Homebrew. See
+    /// [`add_python_framework_link_args`](crate::add_python_framework_link_args).
+    ///
+    /// Serialized to `python_framework_prefix`.
+    pub python_framework_prefix: Option<String>,
+
     /// Width in bits of pointers on the target machine.
     ///
     /// Serialized to `pointer_width`.
     pub pointer_width: Option<u32>,
 
+    /// Whether `pointer_width` was assumed from the target triple rather than detected from
+    /// the target Python interpreter or its sysconfigdata.
+    ///
+    /// Serialized to `pointer_width_inferred`.
+    pub pointer_width_inferred: bool,
+
     /// Additional relevant Python build flags / configuration settings.
     ///
     /// Serialized to `build_flags`.
@@ -155,19 +213,63 @@ pub struct InterpreterConfig {
     ///
     /// Serialized to multiple `extra_build_script_line` values.
     pub extra_build_script_lines: Vec<String>,
+
+    /// Additional arguments to pass to the linker, for all target operating systems.
+    ///
+    /// These are emitted as `cargo:rustc-link-arg=` lines alongside the link lines for the Python
+    /// library. Each entry is a single argument, so e.g. `-framework CoreFoundation` needs two
+    /// entries.
+    ///
+    /// Serialized to multiple `extra_link_args` values.
+    pub extra_link_args: Vec<String>,
+
+    /// Like [`extra_link_args`](InterpreterConfig::extra_link_args), but only used when the
+    /// target operating system (as in `CARGO_CFG_TARGET_OS`, e.g. `linux` or `macos`) matches
+    /// the key.
+    ///
+    /// Serialized to multiple `extra_link_args_<os>` values.
+    pub extra_link_args_by_os: BTreeMap<String, Vec<String>>,
+
+    /// Additional directories to search for native libraries, e.g. for the dependencies of a
+    /// vendored or Bazel-built Python.
+    ///
+    /// These are emitted as `cargo:rustc-link-search=native=` lines alongside the link lines for
+    /// the Python library, after the one for [`lib_dir`](InterpreterConfig::lib_dir).
+    ///
+    /// Serialized to multiple `extra_link_search_path` values.
+    pub extra_link_search_paths: Vec<String>,
+
+    /// The arguments the Python interpreter's `configure` script was run with, as found in the
+    /// `CONFIG_ARGS` sysconfig variable, e.g. `--enable-shared`.
+    ///
+    /// This records how the interpreter was built, and is empty when that is unknown (for example
+    /// on Windows).
+    ///
+    /// Serialized to multiple `config_arg` values.
+    pub config_args: Vec<String>,
+
+    /// Additional `sysconfig` variables captured from the interpreter, as requested with the
+    /// comma-separated `PYO3_EXTRA_PROBE_VARS` environment variable.
+    ///
+    /// Requested variables which the interpreter doesn't define are left out.
+    ///
+    /// Serialized to multiple `sysconfig_var` values of the form `NAME=value`.
+    pub sysconfig_vars: BTreeMap<String, String>,
 }
 
 impl InterpreterConfig {
     #[doc(hidden)]
     pub fn build_script_outputs(&self) -> Vec<String> {
+        self.build_script_outputs_with_strictness(self.abi3 && is_abi3_strict())
+    }
+
+    pub(crate) fn build_script_outputs_with_strictness(&self, abi3_strict: bool) -> Vec<String> {
         // This should have been checked during pyo3-build-config build time.
         assert!(self.version >= MINIMUM_SUPPORTED_VERSION);
 
         let mut out = vec![];
 
-        // pyo3-build-config was released when Python 3.6 was supported, so minimum flag to emit is
-        // Py_3_6 (to avoid silently breaking users who depend on this cfg).
-        for i in 6..=self.version.minor {
+        for i in MINIMUM_VERSION_CFG_MINOR..=self.version.minor {
             out.push(format!("cargo:rustc-cfg=Py_3_{}", i));
         }
 
@@ -179,16 +281,55 @@ impl InterpreterConfig {
 
         if self.abi3 {
             out.push("cargo:rustc-cfg=Py_LIMITED_API".to_owned());
+            out.push("cargo:rustc-cfg=abi3".to_owned());
         }
 
         for flag in &self.build_flags.0 {
             out.push(format!("cargo:rustc-cfg=py_sys_config=\"{}\"", flag));
         }
 
+        // Marks builds where version-specific code must not be used, see `is_abi3_strict`.
+        if self.abi3 && abi3_strict {
+            out.push("cargo:rustc-cfg=py_sys_config=\"ABI3_STRICT\"".to_owned());
+        }
+
+        if self.gil_disabled() {
+            out.push("cargo:rustc-cfg=Py_GIL_DISABLED".to_owned());
+        }
+
+        if self.supports_subinterpreters() {
+            out.push("cargo:rustc-cfg=py_subinterpreters".to_owned());
+        }
+
+        for (supported, cfg) in [
+            (self.has_tp_vectorcall(), "py_tp_vectorcall"),
+            (self.has_tp_watched(), "py_tp_watched"),
+            (self.has_managed_dict(), "py_managed_dict"),
+        ] {
+            if supported {
+                out.push(format!("cargo:rustc-cfg={}", cfg));
+            }
+        }
+
+        // Lets crates which run Python (e.g. in tests) use `env!("PYO3_PYTHON_RESOLVED")`.
+        if let Some(executable) = &self.executable {
+            out.push(format!(
+                "cargo:rustc-env=PYO3_PYTHON_RESOLVED={}",
+                executable
+            ));
+        }
+
         out
     }
 
-    #[doc(hidden)]
+    /// Probes the Python interpreter at `interpreter` for its configuration.
+    ///
+    /// This runs the interpreter, so `interpreter` must be runnable on the build host; use
+    /// [`find_interpreter`](crate::find_interpreter) to locate one the way PyO3 does.
+    ///
+    /// In a build script the output of the interpreter is cached in `OUT_DIR`, and reused while
+    /// the interpreter executable (by path and modification time) is unchanged. Set
+    /// `PYO3_NO_CACHE=1` to always run the interpreter.
     pub fn from_interpreter(interpreter: impl AsRef<Path>) -> Result<Self> {
         const SCRIPT: &str = r#"
 # Allow the script to run on Python 2, so that nicer error can be printed later.
@@ -224,6 +365,21 @@ def print_if_set(varname, value):
     if value is not None:
         print(varname, value)
 
+def is_translated():
+    # Detects x86_64 processes running under Rosetta 2 on Apple Silicon
+    if platform.system() != "Darwin":
+        return False
+    try:
+        import ctypes
+        libc = ctypes.CDLL(None)
+        value = ctypes.c_int(0)
+        size = ctypes.c_size_t(ctypes.sizeof(value))
+        if libc.sysctlbyname(b"sysctl.proc_translated", ctypes.byref(value), ctypes.byref(size), None, 0) != 0:
+            return False
+        return value.value == 1
+    except Exception:
+        return False
+
 # Windows always uses shared linking
 WINDOWS = platform.system() == "Windows"
 
@@ -240,12 +396,48 @@ print("shared", PYPY or GRAALPY or ANACONDA or WINDOWS or FRAMEWORK or SHARED)
 print_if_set("ld_version", get_config_var("LDVERSION"))
 print_if_set("libdir", get_config_var("LIBDIR"))
 print_if_set("base_prefix", base_prefix)
+print("prefix", sys.prefix)
 print("executable", sys.executable)
 print("calcsize_pointer", struct.calcsize("P"))
 print("mingw", get_platform().startswith("mingw"))
+print("platform", get_platform())
+print("machine", platform.machine())
+print("translated", is_translated())
 print("ext_suffix", get_config_var("EXT_SUFFIX"))
+print_if_set("config_args", get_config_var("CONFIG_ARGS"))
+if FRAMEWORK:
+    print_if_set("python_framework_prefix", get_config_var("PYTHONFRAMEWORKPREFIX"))
+print("gil_disabled", get_config_var("Py_GIL_DISABLED") == 1)
+
+for name in os.environ.get("PYO3_EXTRA_PROBE_VARS", "").split(","):
+    if name:
+        print_if_set("sysconfig_var_" + name, get_config_var(name))
 "#;
-        let output = run_python_script(interpreter.as_ref(), SCRIPT)?;
+        let extra_probe_vars = extra_probe_vars()?;
+        let cache = cargo_env_var("OUT_DIR")
+            .filter(|_| !env_var("PYO3_NO_CACHE").map_or(false, |os_str| os_str == "1"))
+            .and_then(|out_dir| {
+                let key = probe_cache_key(interpreter.as_ref(), SCRIPT, &extra_probe_vars)?;
+                Some((Path::new(&out_dir).join(PROBE_CACHE_FILE_NAME), key))
+            });
+        let cached_output = cache
+            .as_ref()
+            .and_then(|(path, key)| read_probe_cache(path, key));
+        let output = match cached_output {
+            Some(output) => output,
+            None => {
+                let output = run_python_script_with_envs(
+                    interpreter.as_ref(),
+                    SCRIPT,
+                    [("PYO3_EXTRA_PROBE_VARS", extra_probe_vars.join(","))],
+                )?;
+                if let Some((path, key)) = &cache {
+                    // A failure to write the cache only costs the next build some time.
+                    let _ = fs::write(path, format!("{}\n{}", key, output));
+                }
+                output
+            }
+        };
         let map: HashMap<String, String> = parse_script_output(&output);
 
         ensure!(
@@ -254,6 +446,15 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
             interpreter.as_ref().display()
         );
 
+        ensure!(
+            map.get("version_major").map(String::as_str) != Some("2"),
+            "the Python interpreter at {} is Python {}.{}, but Python 2 is not supported by PyO3\n\
+            = help: use a Python 3 interpreter, e.g. by setting PYO3_PYTHON=python3",
+            interpreter.as_ref().display(),
+            map["version_major"],
+            map.get("version_minor").map_or("?", String::as_str),
+        );
+
         if let Some(value) = map.get("graalpy_major") {
             let graalpy_version = PythonVersion {
                 major: value
@@ -271,6 +472,13 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
             );
         };
 
+        if let Some(warning) = rosetta_warning(
+            map.get("machine").map_or("", String::as_str),
+            map.get("translated").map_or(false, |value| value == "True"),
+        ) {
+            warn!(PYO3_W008, "{}", warning);
+        }
+
         let shared = map["shared"].as_str() == "True";
 
         let version = PythonVersion {
@@ -286,6 +494,10 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
 
         let implementation = map["implementation"].parse()?;
 
+        let gil_disabled = map
+            .get("gil_disabled")
+            .map_or(false, |value| value == "True");
+
         let lib_name = if cfg!(windows) {
             default_lib_name_windows(
                 version,
@@ -296,6 +508,7 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
                 // on Windows from sysconfig - e.g. ext_suffix may be
                 // `_d.cp312-win_amd64.pyd` for 3.12 debug build
                 map["ext_suffix"].starts_with("_d."),
+                gil_disabled,
             )
         } else {
             default_lib_name_unix(
@@ -311,6 +524,23 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
         } else {
             map.get("libdir").cloned()
         };
+        let (lib_dir, python_framework_prefix) =
+            match (map.get("base_prefix"), map.get("python_framework_prefix")) {
+                (Some(base_prefix), Some(python_framework_prefix)) => framework_dirs(
+                    Path::new(base_prefix),
+                    lib_dir,
+                    python_framework_prefix.clone(),
+                ),
+                (_, python_framework_prefix) => (lib_dir, python_framework_prefix.cloned()),
+            };
+        // `sys.prefix` differs from `sys.base_prefix` inside a virtualenv.
+        let lib_dir = match map
+            .get("prefix")
+            .filter(|prefix| map.get("base_prefix") != Some(*prefix))
+        {
+            Some(virtual_env) => venv_lib_dir(Path::new(virtual_env), lib_dir, cfg!(windows)),
+            None => lib_dir,
+        };
 
         // The reason we don't use platform.architecture() here is that it's not
         // reliable on macOS. See https://stackoverflow.com/a/1405971/823869.
@@ -321,6 +551,15 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
             .parse()
             .context("failed to parse calcsize_pointer")?;
 
+        let mut build_flags = BuildFlags::from_interpreter(interpreter)?;
+        // `BuildFlags::from_interpreter` can't read the flags on Windows.
+        if gil_disabled {
+            build_flags.0.insert(BuildFlag::Py_GIL_DISABLED);
+        }
+        if cfg!(windows) {
+            build_flags = build_flags.with_windows_debug_flags(&map["ext_suffix"]);
+        }
+
         Ok(InterpreterConfig {
             version,
             implementation,
@@ -329,10 +568,22 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
             lib_name: Some(lib_name),
             lib_dir,
             executable: map.get("executable").cloned(),
+            platform_tag: map.get("platform").cloned(),
+            python_framework_prefix,
             pointer_width: Some(calcsize_pointer * 8),
-            build_flags: BuildFlags::from_interpreter(interpreter)?,
+            pointer_width_inferred: false,
+            build_flags,
             suppress_build_script_link_lines: false,
             extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: map
+                .get("config_args")
+                .map_or_else(Vec::new, |config_args| parse_config_args(config_args)),
+            sysconfig_vars: collect_extra_probe_vars(&extra_probe_vars, |name| {
+                map.get(&format!("sysconfig_var_{}", name)).cloned()
+            }),
         })
     }
 
@@ -360,9 +611,17 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
         let soabi = get_key!(sysconfigdata, "SOABI")?;
         let implementation = PythonImplementation::from_soabi(soabi)?;
         let version = parse_key!(sysconfigdata, "VERSION")?;
+        let config_args = sysconfigdata
+            .get_value("CONFIG_ARGS")
+            .map_or_else(Vec::new, parse_config_args);
         let shared = match sysconfigdata.get_value("Py_ENABLE_SHARED") {
+            // PyPy and GraalPy always use a shared library, whatever their sysconfigdata says
+            // (the same is assumed when probing an interpreter)
+            _ if implementation.is_pypy() || implementation.is_graalpy() => true,
             Some("1") | Some("true") | Some("True") => true,
             Some("0") | Some("false") | Some("False") => false,
+            // fall back to how the interpreter was configured
+            None if !config_args.is_empty() => has_config_arg(&config_args, "--enable-shared"),
             _ => bail!("expected a bool (1/true/True or 0/false/False) for Py_ENABLE_SHARED"),
         };
         // macOS framework packages use shared linking (PYTHONFRAMEWORK is the framework name, hence the empty check)
@@ -370,6 +629,10 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
             Some(s) => !s.is_empty(),
             _ => false,
         };
+        let python_framework_prefix = sysconfigdata
+            .get_value("PYTHONFRAMEWORKPREFIX")
+            .filter(|_| framework)
+            .map(str::to_string);
         let lib_dir = get_key!(sysconfigdata, "LIBDIR").ok().map(str::to_string);
         let lib_name = Some(default_lib_name_unix(
             version,
@@ -389,33 +652,80 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
             lib_dir,
             lib_name,
             executable: None,
+            platform_tag: None,
+            python_framework_prefix,
             pointer_width,
+            pointer_width_inferred: false,
             build_flags,
             suppress_build_script_link_lines: false,
             extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args,
+            sysconfig_vars: collect_extra_probe_vars(&extra_probe_vars()?, |name| {
+                sysconfigdata.get_value(name).map(str::to_owned)
+            }),
         })
     }
 
-    #[doc(hidden)]
+    /// Reads a config file. A leading `~` in `path` is expanded to the user's home directory.
+    ///
+    /// This accepts the same files as the `PYO3_CONFIG_FILE` environment variable.
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
-        let path = path.as_ref();
-        let config_file = std::fs::File::open(path)
-            .with_context(|| format!("failed to open PyO3 config file at {}", path.display()))?;
-        let reader = std::io::BufReader::new(config_file);
-        InterpreterConfig::from_reader(reader)
+        InterpreterConfig::from_pairs(read_config_file(path.as_ref())?, warn_unknown_key)
     }
 
+    /// Reads a config from an inherited file descriptor, given as a decimal number as in the
+    /// `PYO3_CONFIG_FD` environment variable.
+    ///
+    /// Ownership of the file descriptor is taken, so it is closed after the config has been read.
+    #[cfg(unix)]
     #[doc(hidden)]
+    pub fn from_fd(fd: &str) -> Result<Self> {
+        use std::os::unix::io::{FromRawFd, RawFd};
+
+        let raw_fd: RawFd = fd
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid file descriptor number `{}`", fd))?;
+        ensure!(
+            raw_fd > 2,
+            "invalid file descriptor number `{}`; standard streams cannot be used",
+            fd
+        );
+        // SAFETY: the caller hands this descriptor over to us, and the standard streams (which the
+        // process might still use) were excluded above.
+        let file = unsafe { std::fs::File::from_raw_fd(raw_fd) };
+        InterpreterConfig::from_reader(file)
+            .with_context(|| format!("failed to read PyO3 config from file descriptor {}", raw_fd))
+    }
+
+    /// Reads the config which `pyo3-ffi` passes to the build scripts of dependent crates in
+    /// `DEP_PYTHON_PYO3_CONFIG`, returning `None` when it is not set.
     pub fn from_cargo_dep_env() -> Option<Result<Self>> {
         cargo_env_var("DEP_PYTHON_PYO3_CONFIG")
             .map(|buf| InterpreterConfig::from_reader(&*unescape(&buf)))
     }
 
-    #[doc(hidden)]
+    /// Parses a config from `key=value` lines.
+    ///
+    /// An `include=path` line is replaced by the contents of the config file at `path`, so keys
+    /// which come after the `include` override the included values. Relative paths are resolved
+    /// against the directory of the including file, or the current directory for the top level
+    /// of `from_reader`.
     pub fn from_reader(reader: impl Read) -> Result<Self> {
-        let reader = BufReader::new(reader);
-        let lines = reader.lines();
+        InterpreterConfig::from_pairs(
+            read_config_pairs(reader, None, &mut Vec::new())?,
+            warn_unknown_key,
+        )
+    }
 
+    /// Builds a config from `key=value` pairs, calling `unknown_key` for each unrecognized key.
+    fn from_pairs(
+        pairs: Vec<(String, String)>,
+        mut unknown_key: impl FnMut(&str) -> Result<()>,
+    ) -> Result<Self> {
         macro_rules! parse_value {
             ($variable:ident, $value:ident) => {
                 $variable = Some($value.trim().parse().context(format!(
@@ -436,23 +746,21 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
         let mut lib_name = None;
         let mut lib_dir = None;
         let mut executable = None;
+        let mut platform_tag = None;
+        let mut python_framework_prefix = None;
         let mut pointer_width = None;
+        let mut pointer_width_inferred = None;
         let mut build_flags = None;
         let mut suppress_build_script_link_lines = None;
         let mut extra_build_script_lines = vec![];
-
-        for (i, line) in lines.enumerate() {
-            let line = line.context("failed to read line from config")?;
-            let mut split = line.splitn(2, '=');
-            let (key, value) = (
-                split
-                    .next()
-                    .expect("first splitn value should always be present"),
-                split
-                    .next()
-                    .ok_or_else(|| format!("expected key=value pair on line {}", i + 1))?,
-            );
-            match key {
+        let mut extra_link_args = vec![];
+        let mut extra_link_args_by_os: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut extra_link_search_paths = vec![];
+        let mut config_args = vec![];
+        let mut sysconfig_vars = BTreeMap::new();
+
+        for (key, value) in &pairs {
+            match key.as_str() {
                 "implementation" => parse_value!(implementation, value),
                 "version" => parse_value!(version, value),
                 "shared" => parse_value!(shared, value),
@@ -460,7 +768,10 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
                 "lib_name" => parse_value!(lib_name, value),
                 "lib_dir" => parse_value!(lib_dir, value),
                 "executable" => parse_value!(executable, value),
+                "platform_tag" => parse_value!(platform_tag, value),
+                "python_framework_prefix" => parse_value!(python_framework_prefix, value),
                 "pointer_width" => parse_value!(pointer_width, value),
+                "pointer_width_inferred" => parse_value!(pointer_width_inferred, value),
                 "build_flags" => parse_value!(build_flags, value),
                 "suppress_build_script_link_lines" => {
                     parse_value!(suppress_build_script_link_lines, value)
@@ -468,7 +779,26 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
                 "extra_build_script_line" => {
                     extra_build_script_lines.push(value.to_string());
                 }
-                unknown => warn!("unknown config key `{}`", unknown),
+                "config_arg" => config_args.push(value.to_string()),
+                "sysconfig_var" => {
+                    let (name, value) = value.split_once('=').ok_or_else(|| {
+                        format!("expected name=value for sysconfig_var '{}'", value)
+                    })?;
+                    sysconfig_vars.insert(name.to_owned(), value.to_owned());
+                }
+                "extra_link_args" => extra_link_args.push(parse_link_arg(key, value)?),
+                key if key.starts_with("extra_link_args_") => {
+                    let os = &key["extra_link_args_".len()..];
+                    ensure!(!os.is_empty(), "missing target OS in config key `{}`", key);
+                    extra_link_args_by_os
+                        .entry(os.to_owned())
+                        .or_default()
+                        .push(parse_link_arg(key, value)?);
+                }
+                "extra_link_search_path" => {
+                    extra_link_search_paths.push(parse_link_arg(key, value)?)
+                }
+                unknown => unknown_key(unknown)?,
             }
         }
 
@@ -492,10 +822,18 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
             lib_name,
             lib_dir,
             executable,
+            platform_tag,
+            python_framework_prefix,
             pointer_width,
+            pointer_width_inferred: pointer_width_inferred.unwrap_or(false),
             build_flags: build_flags.unwrap_or_default(),
             suppress_build_script_link_lines: suppress_build_script_link_lines.unwrap_or(false),
             extra_build_script_lines,
+            extra_link_args,
+            extra_link_args_by_os,
+            extra_link_search_paths,
+            config_args,
+            sysconfig_vars,
         })
     }
 
@@ -529,34 +867,76 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
     /// documentation for the
     /// [`DEP_<name>_<key>`](https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-build-scripts)
     /// environment variable.
+    ///
+    /// The most commonly needed values are additionally emitted on their own, so that other
+    /// build scripts can read e.g. `DEP_PYTHON_VERSION` without parsing the config; see
+    /// [`cargo_dep_env_lines`](InterpreterConfig::cargo_dep_env_lines).
     pub fn to_cargo_dep_env(&self) -> Result<()> {
+        for line in self.cargo_dep_env_lines()? {
+            println!("{}", line);
+        }
+        Ok(())
+    }
+
+    /// Returns the `cargo:KEY=value` metadata lines printed by `to_cargo_dep_env`.
+    ///
+    /// Build scripts of crates depending on `pyo3-ffi` receive these as `DEP_PYTHON_<KEY>`:
+    ///
+    /// - `PYO3_CONFIG`: the whole config, hex-encoded in the format of
+    ///   [`to_writer`](InterpreterConfig::to_writer)
+    /// - `VERSION`: e.g. `3.12`
+    /// - `IMPLEMENTATION`: e.g. `CPython`
+    /// - `ABI3` and `SHARED`: `true` or `false`
+    /// - `LIB_NAME`, `LIB_DIR`, `EXECUTABLE` and `POINTER_WIDTH`: only when known
+    pub fn cargo_dep_env_lines(&self) -> Result<Vec<String>> {
         let mut buf = Vec::new();
         self.to_writer(&mut buf)?;
         // escape newlines in env var
-        println!("cargo:PYO3_CONFIG={}", escape(&buf));
-        Ok(())
+        let mut lines = vec![
+            format!("cargo:PYO3_CONFIG={}", escape(&buf)),
+            format!("cargo:VERSION={}", self.version),
+            format!("cargo:IMPLEMENTATION={}", self.implementation),
+            format!("cargo:ABI3={}", self.abi3),
+            format!("cargo:SHARED={}", self.shared),
+        ];
+        let optional_values = [
+            ("LIB_NAME", self.lib_name.clone()),
+            ("LIB_DIR", self.lib_dir.clone()),
+            ("EXECUTABLE", self.executable.clone()),
+            (
+                "POINTER_WIDTH",
+                self.pointer_width.map(|width| width.to_string()),
+            ),
+        ];
+        for (key, value) in optional_values {
+            if let Some(value) = value {
+                ensure!(
+                    !value.contains('\n'),
+                    "cannot emit {} `{}` as cargo metadata: it contains a newline",
+                    key,
+                    value.escape_debug()
+                );
+                lines.push(format!("cargo:{}={}", key, value));
+            }
+        }
+        Ok(lines)
     }
 
-    #[doc(hidden)]
+    /// Writes the config in the format of the `PYO3_CONFIG_FILE` environment variable.
+    ///
+    /// Reading the output back with [`InterpreterConfig::from_reader`] gives an equal config.
+    /// Values which would break this by spanning several lines are rejected with an error.
     pub fn to_writer(&self, mut writer: impl Write) -> Result<()> {
         macro_rules! write_line {
             ($value:ident) => {
-                writeln!(writer, "{}={}", stringify!($value), self.$value).context(concat!(
-                    "failed to write ",
-                    stringify!($value),
-                    " to config"
-                ))
+                write_config_line(&mut writer, stringify!($value), &self.$value)
             };
         }
 
         macro_rules! write_option_line {
             ($value:ident) => {
                 if let Some(value) = &self.$value {
-                    writeln!(writer, "{}={}", stringify!($value), value).context(concat!(
-                        "failed to write ",
-                        stringify!($value),
-                        " to config"
-                    ))
+                    write_config_line(&mut writer, stringify!($value), value)
                 } else {
                     Ok(())
                 }
@@ -570,1346 +950,4012 @@ print("ext_suffix", get_config_var("EXT_SUFFIX"))
         write_option_line!(lib_name)?;
         write_option_line!(lib_dir)?;
         write_option_line!(executable)?;
+        write_option_line!(platform_tag)?;
+        write_option_line!(python_framework_prefix)?;
         write_option_line!(pointer_width)?;
+        write_line!(pointer_width_inferred)?;
         write_line!(build_flags)?;
         write_line!(suppress_build_script_link_lines)?;
         for line in &self.extra_build_script_lines {
-            writeln!(writer, "extra_build_script_line={}", line)
-                .context("failed to write extra_build_script_line")?;
+            write_config_line(&mut writer, "extra_build_script_line", line)?;
+        }
+        for arg in &self.config_args {
+            write_config_line(&mut writer, "config_arg", arg)?;
+        }
+        for (name, value) in &self.sysconfig_vars {
+            write_config_line(
+                &mut writer,
+                "sysconfig_var",
+                &format_args!("{}={}", name, value),
+            )?;
+        }
+        for arg in &self.extra_link_args {
+            write_config_line(&mut writer, "extra_link_args", arg)?;
+        }
+        for (os, args) in &self.extra_link_args_by_os {
+            for arg in args {
+                write_config_line(&mut writer, &format!("extra_link_args_{}", os), arg)?;
+            }
+        }
+        for path in &self.extra_link_search_paths {
+            write_config_line(&mut writer, "extra_link_search_path", path)?;
         }
         Ok(())
     }
 
-    /// Run a python script using the [`InterpreterConfig::executable`].
+    /// Writes the config to a file at `path`, as [`InterpreterConfig::to_writer`] does, replacing
+    /// any existing file. A leading `~` in `path` is expanded to the user's home directory.
     ///
-    /// # Panics
-    ///
-    /// This function will panic if the [`executable`](InterpreterConfig::executable) is `None`.
-    pub fn run_python_script(&self, script: &str) -> Result<String> {
-        run_python_script_with_envs(
-            Path::new(self.executable.as_ref().expect("no interpreter executable")),
-            script,
-            std::iter::empty::<(&str, &str)>(),
-        )
+    /// The file can be read back with [`InterpreterConfig::from_path`], or used as the
+    /// `PYO3_CONFIG_FILE` of a later build, e.g. to probe an interpreter on the host once and
+    /// build offline afterwards. TOML is only read, so `path` must not have a `.toml` extension.
+    pub fn to_path(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = &expand_home_dir(path.as_ref(), home_dir().as_deref())?;
+        ensure!(
+            !is_toml_config(path),
+            "cannot write PyO3 config file {}: only the line-based format can be written, \
+            so the path must not have a `.toml` extension",
+            path.display()
+        );
+        let mut contents = Vec::new();
+        self.to_writer(&mut contents)?;
+        fs::write(path, contents)
+            .with_context(|| format!("failed to write PyO3 config file at {}", path.display()))
     }
 
-    /// Run a python script using the [`InterpreterConfig::executable`] with additional
-    /// environment variables (e.g. PYTHONPATH) set.
-    ///
-    /// # Panics
-    ///
-    /// This function will panic if the [`executable`](InterpreterConfig::executable) is `None`.
-    pub fn run_python_script_with_envs<I, K, V>(&self, script: &str, envs: I) -> Result<String>
-    where
-        I: IntoIterator<Item = (K, V)>,
-        K: AsRef<OsStr>,
-        V: AsRef<OsStr>,
-    {
-        run_python_script_with_envs(
-            Path::new(self.executable.as_ref().expect("no interpreter executable")),
-            script,
-            envs,
-        )
+    /// Returns the `cargo:rustc-link-arg=` lines for the extra link arguments which apply to
+    /// `target_os`.
+    pub fn extra_link_arg_directives(&self, target_os: &str) -> Vec<String> {
+        self.extra_link_args
+            .iter()
+            .chain(
+                self.extra_link_args_by_os
+                    .get(target_os)
+                    .into_iter()
+                    .flatten(),
+            )
+            .map(|arg| format!("cargo:rustc-link-arg={}", arg))
+            .collect()
     }
 
-    /// Lowers the configured version to the abi3 version, if set.
-    fn fixup_for_abi3_version(&mut self, abi3_version: Option<PythonVersion>) -> Result<()> {
-        // PyPy doesn't support abi3; don't adjust the version
-        if self.implementation.is_pypy() || self.implementation.is_graalpy() {
-            return Ok(());
+    /// Returns the `cargo:rustc-link-search=native=` lines for the
+    /// [`extra_link_search_paths`](InterpreterConfig::extra_link_search_paths).
+    pub fn extra_link_search_directives(&self) -> Vec<String> {
+        self.extra_link_search_paths
+            .iter()
+            .map(|path| format!("cargo:rustc-link-search=native={}", path))
+            .collect()
+    }
+
+    /// Returns the `cargo:rustc-link-arg=` lines needed to load a macOS framework build of
+    /// Python at runtime from binaries linking libpython for `target`.
+    ///
+    /// See [`add_python_framework_link_args`](crate::add_python_framework_link_args).
+    pub fn python_framework_link_args_for_target(
+        &self,
+        target: &Triple,
+        linking_libpython: bool,
+    ) -> Vec<String> {
+        match &self.python_framework_prefix {
+            Some(prefix)
+                if target.operating_system == OperatingSystem::Darwin && linking_libpython =>
+            {
+                vec![format!("cargo:rustc-link-arg=-Wl,-rpath,{}", prefix)]
+            }
+            _ => Vec::new(),
         }
+    }
 
-        if let Some(version) = abi3_version {
-            ensure!(
-                version <= self.version,
-                "cannot set a minimum Python version {} higher than the interpreter version {} \
-                (the minimum Python version is implied by the abi3-py3{} feature)",
-                version,
-                self.version,
-                version.minor,
-            );
+    /// Prints `cargo:rerun-if-changed` for the interpreter executable, so that replacing the
+    /// interpreter (e.g. upgrading it with pyenv) triggers a rebuild.
+    ///
+    /// If the executable is known but doesn't exist, a warning is printed instead.
+    #[doc(hidden)]
+    pub fn emit_rerun_if_changed_for_interpreter(&self) {
+        if let Some(line) = self.rerun_if_changed_for_interpreter() {
+            println!("{}", line);
+        }
+    }
 
-            self.version = version;
+    fn rerun_if_changed_for_interpreter(&self) -> Option<String> {
+        let executable = self.executable.as_deref()?;
+        if Path::new(executable).exists() {
+            Some(format!("cargo:rerun-if-changed={}", executable))
+        } else {
+            Some(crate::format_warn!(
+                "the Python interpreter at {} does not exist, so changes to it will not trigger a rebuild",
+                executable
+            ))
         }
+    }
 
-        Ok(())
+    /// Writes the config into `dir` in every supported format, returning the paths written.
+    ///
+    /// `pyo3-build-config.txt`, in the format of [`to_writer`](InterpreterConfig::to_writer), is
+    /// always written first and is the authoritative copy which tooling should prefer. It is
+    /// followed by `pyo3-build-config.toml`, which [`from_path`](InterpreterConfig::from_path)
+    /// reads back into an equal config.
+    #[doc(hidden)]
+    pub fn write_all_formats(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let path = dir.join(CONFIG_FILE_NAME);
+        let file = std::fs::File::create(&path)
+            .with_context(|| format!("failed to create config file at {}", path.display()))?;
+        self.to_writer(file)?;
+
+        let toml_path = path.with_extension("toml");
+        let file = std::fs::File::create(&toml_path)
+            .with_context(|| format!("failed to create config file at {}", toml_path.display()))?;
+        config_toml::write_config(self, file)?;
+
+        Ok(vec![path, toml_path])
     }
-}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct PythonVersion {
-    pub major: u8,
-    pub minor: u8,
-}
+    /// Returns `true` if [`pointer_width`](InterpreterConfig::pointer_width) was assumed from
+    /// the compile target rather than detected from the target Python installation.
+    ///
+    /// Inferred values are typically produced when cross compiling without a
+    /// `_sysconfigdata*.py` file to read the target configuration from.
+    pub fn pointer_width_is_inferred(&self) -> bool {
+        self.pointer_width.is_some() && self.pointer_width_inferred
+    }
 
-impl PythonVersion {
-    const PY37: Self = PythonVersion { major: 3, minor: 7 };
-}
+    /// Returns the parts of the config which describe the Python the extension was built for, as
+    /// `(key, value)` pairs using the same keys and value formats as
+    /// [`to_writer`](InterpreterConfig::to_writer).
+    ///
+    /// This is what the `build-info` feature of `pyo3` embeds as `__pyo3_build_info__`. Paths on
+    /// the build machine, such as `lib_dir` and `executable`, are left out.
+    pub fn build_info(&self) -> Vec<(&'static str, String)> {
+        let mut info = vec![
+            ("implementation", self.implementation.to_string()),
+            ("version", self.version.to_string()),
+            ("abi3", self.abi3.to_string()),
+            ("shared", self.shared.to_string()),
+        ];
+        if let Some(pointer_width) = self.pointer_width {
+            info.push(("pointer_width", pointer_width.to_string()));
+        }
+        if let Some(platform_tag) = &self.platform_tag {
+            info.push(("platform_tag", platform_tag.clone()));
+        }
+        info.push(("build_flags", self.build_flags.to_string()));
+        info
+    }
 
-impl Display for PythonVersion {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}.{}", self.major, self.minor)
+    /// Returns the native libraries which should be linked for the current build mode.
+    ///
+    /// This is empty when building an extension module for a target which resolves the Python
+    /// symbols at load time (e.g. Linux and macOS), and contains the Python library otherwise.
+    ///
+    /// For macOS framework builds whose [`lib_name`](InterpreterConfig::lib_name) is the
+    /// framework itself (e.g. `Python3` for the Python of the Xcode command line tools), the
+    /// library is a [`LinkLibKind::Framework`] found in
+    /// [`python_framework_prefix`](InterpreterConfig::python_framework_prefix). Other framework
+    /// builds link the versioned library in [`lib_dir`](InterpreterConfig::lib_dir) instead, as
+    /// linking the framework would pick whichever version it currently points to.
+    ///
+    /// Must be called from a PyO3 crate build script.
+    pub fn link_libraries(&self) -> Vec<LinkLib> {
+        self.link_libraries_for_target(&target_triple_from_env(), is_extension_module())
     }
-}
 
-impl FromStr for PythonVersion {
-    type Err = crate::errors::Error;
+    fn link_libraries_for_target(&self, target: &Triple, extension_module: bool) -> Vec<LinkLib> {
+        let kind = match self.link_model_for_target(target, extension_module) {
+            LinkModel::Extension => return Vec::new(),
+            _ if self.links_python_framework(target) => LinkLibKind::Framework,
+            LinkModel::Shared => LinkLibKind::Dylib,
+            LinkModel::Static => LinkLibKind::Static,
+        };
+        self.lib_name
+            .iter()
+            .map(|lib_name| LinkLib {
+                name: lib_name.clone(),
+                kind,
+            })
+            .collect()
+    }
 
-    fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let mut split = value.splitn(2, '.');
-        let (major, minor) = (
-            split
-                .next()
-                .expect("first splitn value should always be present"),
-            split.next().ok_or("expected major.minor version")?,
-        );
-        Ok(Self {
-            major: major.parse().context("failed to parse major version")?,
-            minor: minor.parse().context("failed to parse minor version")?,
-        })
+    /// Whether the Python library is the framework in `python_framework_prefix`, e.g.
+    /// `Python3.framework` for `lib_name=Python3`.
+    fn links_python_framework(&self, target: &Triple) -> bool {
+        target.operating_system == OperatingSystem::Darwin
+            && match (&self.python_framework_prefix, &self.lib_name) {
+                (Some(prefix), Some(lib_name)) => Path::new(prefix)
+                    .join(format!("{}.framework", lib_name))
+                    .is_dir(),
+                _ => false,
+            }
     }
-}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum PythonImplementation {
-    CPython,
-    PyPy,
-    GraalPy,
-}
+    /// Returns the `cargo:rustc-link-search=framework=` line needed to find the libraries
+    /// returned by [`link_libraries`](InterpreterConfig::link_libraries), if one of them is a
+    /// framework.
+    ///
+    /// Must be called from a PyO3 crate build script.
+    pub fn framework_search_directive(&self) -> Option<String> {
+        self.framework_search_directive_for_target(&target_triple_from_env(), is_extension_module())
+    }
 
-impl PythonImplementation {
-    #[doc(hidden)]
-    pub fn is_pypy(self) -> bool {
-        self == PythonImplementation::PyPy
+    fn framework_search_directive_for_target(
+        &self,
+        target: &Triple,
+        extension_module: bool,
+    ) -> Option<String> {
+        let links_framework = self
+            .link_libraries_for_target(target, extension_module)
+            .iter()
+            .any(|lib| lib.kind == LinkLibKind::Framework);
+        match &self.python_framework_prefix {
+            Some(prefix) if links_framework => {
+                Some(format!("cargo:rustc-link-search=framework={}", prefix))
+            }
+            _ => None,
+        }
     }
 
-    #[doc(hidden)]
-    pub fn is_graalpy(self) -> bool {
-        self == PythonImplementation::GraalPy
+    /// Returns how the Python library is linked for the current build mode.
+    ///
+    /// This is derived from [`shared`](InterpreterConfig::shared), which is detected from
+    /// `Py_ENABLE_SHARED`, and from whether an extension module is being built for a target
+    /// which resolves the Python symbols at load time.
+    ///
+    /// Must be called from a PyO3 crate build script.
+    pub fn link_model(&self) -> LinkModel {
+        self.link_model_for_target(&target_triple_from_env(), is_extension_module())
     }
 
-    #[doc(hidden)]
-    pub fn from_soabi(soabi: &str) -> Result<Self> {
-        if soabi.starts_with("pypy") {
-            Ok(PythonImplementation::PyPy)
-        } else if soabi.starts_with("cpython") {
-            Ok(PythonImplementation::CPython)
-        } else if soabi.starts_with("graalpy") {
-            Ok(PythonImplementation::GraalPy)
+    fn link_model_for_target(&self, target: &Triple, extension_module: bool) -> LinkModel {
+        if !target_requires_libpython(target, extension_module) {
+            LinkModel::Extension
+        } else if self.shared {
+            LinkModel::Shared
         } else {
-            bail!("unsupported Python interpreter");
+            LinkModel::Static
         }
     }
-}
 
-impl Display for PythonImplementation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            PythonImplementation::CPython => write!(f, "CPython"),
-            PythonImplementation::PyPy => write!(f, "PyPy"),
-            PythonImplementation::GraalPy => write!(f, "GraalVM"),
-        }
+    /// Checks that the Python library can be linked as described by
+    /// [`link_model`](InterpreterConfig::link_model).
+    ///
+    /// Static linking fails with a descriptive error for PyPy and GraalPy, which have no static
+    /// library, and when the static library is missing from
+    /// [`lib_dir`](InterpreterConfig::lib_dir) (as is common for distribution Pythons).
+    ///
+    /// Must be called from a PyO3 crate build script.
+    pub fn check_link_model(&self) -> Result<()> {
+        self.check_link_model_for_target(&target_triple_from_env(), is_extension_module())
     }
-}
 
-impl FromStr for PythonImplementation {
-    type Err = Error;
-    fn from_str(s: &str) -> Result<Self> {
-        match s {
-            "CPython" => Ok(PythonImplementation::CPython),
-            "PyPy" => Ok(PythonImplementation::PyPy),
-            "GraalVM" => Ok(PythonImplementation::GraalPy),
-            _ => bail!("unknown interpreter: {}", s),
+    fn check_link_model_for_target(&self, target: &Triple, extension_module: bool) -> Result<()> {
+        if self.link_model_for_target(target, extension_module) != LinkModel::Static {
+            return Ok(());
+        }
+        ensure!(
+            !(self.implementation.is_pypy() || self.implementation.is_graalpy()),
+            "{} cannot be linked statically\n\
+            = help: set `shared=true` in the PyO3 config file",
+            self.implementation
+        );
+        // Windows static and import libraries share the `.lib` extension, so only check Unix.
+        if target.operating_system != OperatingSystem::Windows {
+            if let (Some(lib_dir), Some(lib_name)) = (&self.lib_dir, &self.lib_name) {
+                let static_lib = Path::new(lib_dir).join(format!("lib{}.a", lib_name));
+                ensure!(
+                    static_lib.is_file(),
+                    "the Python installation is configured for static linking, \
+                    but {} does not exist\n\
+                    = help: install the static Python library, or use a Python built with \
+                    `--enable-shared`",
+                    static_lib.display()
+                );
+            }
         }
+        Ok(())
     }
-}
-
-/// Checks if we should look for a Python interpreter installation
-/// to get the target interpreter configuration.
-///
-/// Returns `false` if `PYO3_NO_PYTHON` environment variable is set.
-fn have_python_interpreter() -> bool {
-    env_var("PYO3_NO_PYTHON").is_none()
-}
 
-/// Checks if `abi3` or any of the `abi3-py3*` features is enabled for the PyO3 crate.
-///
-/// Must be called from a PyO3 crate build script.
-fn is_abi3() -> bool {
-    cargo_env_var("CARGO_FEATURE_ABI3").is_some()
-        || env_var("PYO3_USE_ABI3_FORWARD_COMPATIBILITY").map_or(false, |os_str| os_str == "1")
-}
+    /// Returns the `cargo:rustc-link-arg-tests=` (and, if `benches` is set,
+    /// `cargo:rustc-link-arg-benches=`) lines which let test and benchmark binaries embedding
+    /// Python find the shared Python library at runtime.
+    ///
+    /// This adds an rpath for [`lib_dir`](InterpreterConfig::lib_dir), so that `cargo test` works
+    /// without setting e.g. `LD_LIBRARY_PATH`. On macOS an rpath is also added for the
+    /// [`python_framework_prefix`](InterpreterConfig::python_framework_prefix) of framework builds
+    /// of Python, whose library may otherwise fail to load with a dyld error. Nothing is returned
+    /// when building an extension
+    /// module, for static builds, when link lines are suppressed, or for targets which don't
+    /// support rpaths such as Windows.
+    ///
+    /// Cargo rejects these lines for packages without a test or benchmark target respectively,
+    /// so only set `benches` if the package has benchmarks.
+    ///
+    /// Must be called from a PyO3 crate build script.
+    pub fn embedding_test_link_args(&self, benches: bool) -> Vec<String> {
+        self.embedding_test_link_args_for_target(
+            &target_triple_from_env(),
+            is_extension_module(),
+            benches,
+        )
+    }
 
-/// Gets the minimum supported Python version from PyO3 `abi3-py*` features.
-///
-/// Must be called from a PyO3 crate build script.
-pub fn get_abi3_version() -> Option<PythonVersion> {
-    let minor_version = (MINIMUM_SUPPORTED_VERSION.minor..=ABI3_MAX_MINOR)
-        .find(|i| cargo_env_var(&format!("CARGO_FEATURE_ABI3_PY3{}", i)).is_some());
-    minor_version.map(|minor| PythonVersion { major: 3, minor })
-}
+    fn embedding_test_link_args_for_target(
+        &self,
+        target: &Triple,
+        extension_module: bool,
+        benches: bool,
+    ) -> Vec<String> {
+        let supports_rpath = !matches!(
+            target.operating_system,
+            OperatingSystem::Windows | OperatingSystem::Emscripten | OperatingSystem::Wasi
+        );
+        if !(self.shared
+            && !self.suppress_build_script_link_lines
+            && supports_rpath
+            && target_requires_libpython(target, extension_module))
+        {
+            return Vec::new();
+        }
+        let mut rpaths: Vec<&str> = self.lib_dir.iter().map(String::as_str).collect();
+        if target.operating_system == OperatingSystem::Darwin {
+            // Frameworks with an `@rpath`-relative install name, such as Xcode's, are found
+            // through the directory containing them.
+            rpaths.extend(self.python_framework_prefix.as_deref());
+        }
+        let kinds: &[&str] = if benches {
+            &["tests", "benches"]
+        } else {
+            &["tests"]
+        };
+        kinds
+            .iter()
+            .flat_map(|kind| {
+                rpaths
+                    .iter()
+                    .map(move |rpath| format!("cargo:rustc-link-arg-{}=-Wl,-rpath,{}", kind, rpath))
+            })
+            .collect()
+    }
 
-/// Checks if the `extension-module` feature is enabled for the PyO3 crate.
-///
-/// Must be called from a PyO3 crate build script.
-pub fn is_extension_module() -> bool {
-    cargo_env_var("CARGO_FEATURE_EXTENSION_MODULE").is_some()
-}
+    /// Adds all of `flags` to this configuration's build flags.
+    ///
+    /// As when detecting flags from an interpreter, adding `Py_DEBUG` also adds `Py_REF_DEBUG`.
+    pub fn merge_build_flags_from(&mut self, flags: &BuildFlags) {
+        self.build_flags.0.extend(flags.0.iter().cloned());
+        self.build_flags = std::mem::take(&mut self.build_flags).fixup();
+    }
 
-/// Checks if we need to link to `libpython` for the current build target.
-///
-/// Must be called from a PyO3 crate build script.
-pub fn is_linking_libpython() -> bool {
-    is_linking_libpython_for_target(&target_triple_from_env())
-}
+    /// Removes a single flag from this configuration's build flags.
+    ///
+    /// Returns `true` if the flag was present. Implied flags are not removed, e.g. removing
+    /// `Py_DEBUG` leaves `Py_REF_DEBUG` in place.
+    pub fn remove_build_flag(&mut self, flag: &BuildFlag) -> bool {
+        self.build_flags.0.remove(flag)
+    }
 
-/// Checks if we need to link to `libpython` for the target.
-///
-/// Must be called from a PyO3 crate build script.
-fn is_linking_libpython_for_target(target: &Triple) -> bool {
-    target.operating_system == OperatingSystem::Windows
-        // See https://github.com/PyO3/pyo3/issues/4068#issuecomment-2051159852
-        || target.operating_system == OperatingSystem::Aix
-        || target.environment == Environment::Android
-        || target.environment == Environment::Androideabi
-        || !is_extension_module()
-}
+    /// Returns `true` if extensions built for this configuration can be loaded by the interpreter
+    /// described by `other`.
+    ///
+    /// The configurations are compatible when all of the following hold:
+    /// - Both use the same [`implementation`](InterpreterConfig::implementation).
+    /// - If this configuration targets the stable ABI ([`abi3`](InterpreterConfig::abi3)), its
+    ///   version is the minimum supported version, so `other` must be the same version or newer.
+    ///   Otherwise the `major.minor` versions must be equal.
+    /// - The [`pointer_width`](InterpreterConfig::pointer_width)s are equal. A pointer width which
+    ///   is unknown in either configuration is assumed to match.
+    ///
+    /// Other settings, such as how the Python library is linked, don't affect the ABI.
+    pub fn is_compatible_with(&self, other: &InterpreterConfig) -> bool {
+        let version_compatible = if self.abi3 {
+            other.version >= self.version
+        } else {
+            other.version == self.version
+        };
+        let pointer_width_compatible = match (self.pointer_width, other.pointer_width) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        };
+        self.implementation == other.implementation
+            && version_compatible
+            && pointer_width_compatible
+    }
 
-/// Checks if we need to discover the Python library directory
-/// to link the extension module binary.
-///
-/// Must be called from a PyO3 crate build script.
-fn require_libdir_for_target(target: &Triple) -> bool {
-    let is_generating_libpython = cfg!(feature = "python3-dll-a")
-        && target.operating_system == OperatingSystem::Windows
-        && is_abi3();
+    /// Checks that extensions built with this configuration for `target` can be shipped in a wheel
+    /// with the given tag, e.g. `cp311-abi3-manylinux_2_28_aarch64`.
+    ///
+    /// Mismatches, which are easy to end up with when cross-compiling, otherwise only surface as
+    /// confusing linker errors or as the wheel failing to import. The following are checked:
+    /// - The Python tag names this [`implementation`](InterpreterConfig::implementation) and
+    ///   version. For [`abi3`](InterpreterConfig::abi3) builds the tag may also name a newer
+    ///   version, as elsewhere.
+    /// - The ABI tag is `abi3` exactly when this configuration targets the stable ABI. Otherwise
+    ///   it must match the `SOABI` of the interpreter if that is among the
+    ///   [`sysconfig_vars`](InterpreterConfig::sysconfig_vars), or for CPython 3.8 and newer the
+    ///   tag implied by the version and build flags.
+    /// - The [`pointer_width`](InterpreterConfig::pointer_width) matches `target`.
+    /// - The platform tag is for the operating system, architecture and C library of `target`, and
+    ///   for the architecture of the [`platform_tag`](InterpreterConfig::platform_tag).
+    ///
+    /// Compressed tag sets such as `manylinux_2_17_x86_64.manylinux2014_x86_64` match if any of
+    /// their tags match. Settings which are unknown, and platform tags other than those for Linux,
+    /// macOS and Windows, are not checked. All mismatches found are returned.
+    pub fn validate_for_target(
+        &self,
+        target: &Triple,
+        wheel_tag: &str,
+    ) -> Result<(), Vec<TargetMismatch>> {
+        let mut parts = wheel_tag.split('-');
+        let (python_tag, abi_tag, platform_tag) =
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(python), Some(abi), Some(platform), None)
+                    if !python.is_empty() && !abi.is_empty() && !platform.is_empty() =>
+                {
+                    (python, abi, platform)
+                }
+                _ => return Err(vec![TargetMismatch::InvalidWheelTag(wheel_tag.to_owned())]),
+            };
 
-    is_linking_libpython_for_target(target) && !is_generating_libpython
-}
+        let mut mismatches = Vec::new();
 
-/// Configuration needed by PyO3 to cross-compile for a target platform.
-///
-/// Usually this is collected from the environment (i.e. `PYO3_CROSS_*` and `CARGO_CFG_TARGET_*`)
-/// when a cross-compilation configuration is detected.
-#[derive(Debug, PartialEq, Eq)]
-pub struct CrossCompileConfig {
-    /// The directory containing the Python library to link against.
-    pub lib_dir: Option<PathBuf>,
+        if !python_tag
+            .split('.')
+            .any(|tag| self.matches_python_tag(tag))
+        {
+            mismatches.push(TargetMismatch::PythonTag {
+                expected: self.python_tag(),
+                tag: python_tag.to_owned(),
+            });
+        }
 
-    /// The version of the Python library to link against.
-    version: Option<PythonVersion>,
+        let abi3_tag = abi_tag.split('.').any(|tag| tag == "abi3");
+        if abi3_tag != self.abi3 {
+            mismatches.push(TargetMismatch::Abi3 {
+                abi3: self.abi3,
+                tag: abi_tag.to_owned(),
+            });
+        } else if let Some(expected) = self.abi_tag() {
+            if !abi_tag.split('.').any(|tag| tag == expected) {
+                mismatches.push(TargetMismatch::AbiTag {
+                    expected,
+                    tag: abi_tag.to_owned(),
+                });
+            }
+        }
 
-    /// The target Python implementation hint (CPython, PyPy, GraalPy, ...)
-    implementation: Option<PythonImplementation>,
+        if let (Some(pointer_width), Ok(target_pointer_width)) =
+            (self.pointer_width, target.pointer_width())
+        {
+            let target_pointer_width = u32::from(target_pointer_width.bits());
+            if pointer_width != target_pointer_width {
+                mismatches.push(TargetMismatch::PointerWidth {
+                    config: pointer_width,
+                    target: target_pointer_width,
+                });
+            }
+        }
 
-    /// The compile target triple (e.g. aarch64-unknown-linux-gnu)
-    target: Triple,
-}
+        let platforms: Vec<_> = platform_tag
+            .split('.')
+            .filter_map(PythonPlatform::from_wheel_tag)
+            .collect();
+        if !platforms.is_empty() {
+            if !platforms
+                .iter()
+                .any(|platform| platform.matches_target(target))
+            {
+                mismatches.push(TargetMismatch::Platform {
+                    tag: platform_tag.to_owned(),
+                    target: target.to_string(),
+                });
+            }
+            let interpreter_platform = self
+                .platform_tag
+                .as_deref()
+                .and_then(PythonPlatform::from_sysconfig);
+            if let Some(interpreter_platform) = interpreter_platform {
+                if !platforms
+                    .iter()
+                    .any(|platform| platform.matches(&interpreter_platform))
+                {
+                    mismatches.push(TargetMismatch::InterpreterPlatform {
+                        platform_tag: self.platform_tag.clone().unwrap_or_default(),
+                        tag: platform_tag.to_owned(),
+                    });
+                }
+            }
+        }
 
-impl CrossCompileConfig {
-    /// Creates a new cross compile config struct from PyO3 environment variables
-    /// and the build environment when cross compilation mode is detected.
-    ///
-    /// Returns `None` when not cross compiling.
-    fn try_from_env_vars_host_target(
-        env_vars: CrossCompileEnvVars,
-        host: &Triple,
-        target: &Triple,
-    ) -> Result<Option<Self>> {
-        if env_vars.any() || Self::is_cross_compiling_from_to(host, target) {
-            let lib_dir = env_vars.lib_dir_path()?;
-            let version = env_vars.parse_version()?;
-            let implementation = env_vars.parse_implementation()?;
-            let target = target.clone();
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
 
-            Ok(Some(CrossCompileConfig {
-                lib_dir,
-                version,
-                implementation,
-                target,
-            }))
+    /// The Python tag of wheels built for exactly this implementation and version, e.g. `cp311`.
+    fn python_tag(&self) -> String {
+        let prefix = match self.implementation {
+            PythonImplementation::CPython => "cp",
+            PythonImplementation::PyPy => "pp",
+            PythonImplementation::GraalPy => "graalpy",
+        };
+        format!("{}{}{}", prefix, self.version.major, self.version.minor)
+    }
+
+    /// Returns `true` if a single Python tag, e.g. `cp311` or `py3`, accepts this configuration.
+    fn matches_python_tag(&self, tag: &str) -> bool {
+        let (implementation, digits) = if let Some(digits) = tag.strip_prefix("cp") {
+            (Some(PythonImplementation::CPython), digits)
+        } else if let Some(digits) = tag.strip_prefix("pp") {
+            (Some(PythonImplementation::PyPy), digits)
+        } else if let Some(digits) = tag.strip_prefix("graalpy") {
+            (Some(PythonImplementation::GraalPy), digits)
+        } else if let Some(digits) = tag.strip_prefix("py") {
+            (None, digits)
         } else {
-            Ok(None)
+            return false;
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return false;
+        }
+        let (major, minor) = digits.split_at(1);
+        if matches!(implementation, Some(implementation) if implementation != self.implementation)
+            || major.parse::<u8>().ok() != Some(self.version.major)
+        {
+            return false;
+        }
+        match minor.parse::<u8>() {
+            Ok(minor) if self.abi3 => minor >= self.version.minor,
+            Ok(minor) => minor == self.version.minor,
+            // e.g. `py3` accepts all `3.x` versions
+            Err(_) => minor.is_empty(),
         }
     }
 
-    /// Checks if compiling on `host` for `target` required "real" cross compilation.
+    /// The ABI tag of wheels built for this configuration, if it can be determined.
     ///
-    /// Returns `false` if the target Python interpreter can run on the host.
-    fn is_cross_compiling_from_to(host: &Triple, target: &Triple) -> bool {
-        // Not cross-compiling if arch-vendor-os is all the same
-        // e.g. x86_64-unknown-linux-musl on x86_64-unknown-linux-gnu host
-        //      x86_64-pc-windows-gnu on x86_64-pc-windows-msvc host
-        let mut compatible = host.architecture == target.architecture
-            && host.vendor == target.vendor
-            && host.operating_system == target.operating_system;
-
-        // Not cross-compiling to compile for 32-bit Python from windows 64-bit
-        compatible |= target.operating_system == OperatingSystem::Windows
-            && host.operating_system == OperatingSystem::Windows;
+    /// This follows what `packaging` derives from `SOABI`, e.g. `cpython-311-x86_64-linux-gnu`
+    /// gives `cp311` and `pypy310-pp73-x86_64-linux-gnu` gives `pypy310_pp73`.
+    fn abi_tag(&self) -> Option<String> {
+        if self.abi3 {
+            return None;
+        }
+        if let Some(soabi) = self.sysconfig_vars.get("SOABI") {
+            let parts: Vec<_> = soabi.split('-').collect();
+            return match self.implementation {
+                PythonImplementation::CPython => parts.get(1).map(|abi| format!("cp{}", abi)),
+                PythonImplementation::PyPy => Some(parts[..parts.len().min(2)].join("_")),
+                PythonImplementation::GraalPy => Some(parts[..parts.len().min(3)].join("_")),
+            };
+        }
+        // Before 3.8 the tag also depends on whether pymalloc is used, which isn't recorded.
+        if self.implementation != PythonImplementation::CPython
+            || self.version < PythonVersion::PY38
+        {
+            return None;
+        }
+        let mut tag = self.python_tag();
+        if self.gil_disabled() {
+            tag.push('t');
+        }
+        if self.is_debug_build() {
+            tag.push('d');
+        }
+        Some(tag)
+    }
 
-        // Not cross-compiling to compile for x86-64 Python from macOS arm64 and vice versa
-        compatible |= target.operating_system == OperatingSystem::Darwin
-            && host.operating_system == OperatingSystem::Darwin;
+    /// Returns `true` if the CPython `PyTypeObject` layout includes features introduced in
+    /// `3.minor`.
+    ///
+    /// The layout of type objects is not part of the limited API, so this is `false` for `abi3`
+    /// builds and for other Python implementations.
+    fn has_cpython_type_layout_since(&self, minor: u8) -> bool {
+        self.implementation == PythonImplementation::CPython
+            && !self.abi3
+            && self.version >= PythonVersion { major: 3, minor }
+    }
 
-        !compatible
+    /// Returns `true` if the `tp_vectorcall` slot of type objects is used to call types
+    /// (CPython 3.9+). Emitted as `cfg(py_tp_vectorcall)`.
+    pub fn has_tp_vectorcall(&self) -> bool {
+        self.has_cpython_type_layout_since(9)
     }
 
-    /// Converts `lib_dir` member field to an UTF-8 string.
-    ///
-    /// The conversion can not fail because `PYO3_CROSS_LIB_DIR` variable
-    /// is ensured contain a valid UTF-8 string.
-    fn lib_dir_string(&self) -> Option<String> {
-        self.lib_dir
-            .as_ref()
-            .map(|s| s.to_str().unwrap().to_owned())
+    /// Returns `true` if type objects have the `tp_watched` field used by type watchers
+    /// (CPython 3.12+). Emitted as `cfg(py_tp_watched)`.
+    pub fn has_tp_watched(&self) -> bool {
+        self.has_cpython_type_layout_since(12)
     }
-}
 
-/// PyO3-specific cross compile environment variable values
-struct CrossCompileEnvVars {
-    /// `PYO3_CROSS`
-    pyo3_cross: Option<OsString>,
-    /// `PYO3_CROSS_LIB_DIR`
-    pyo3_cross_lib_dir: Option<OsString>,
-    /// `PYO3_CROSS_PYTHON_VERSION`
-    pyo3_cross_python_version: Option<OsString>,
-    /// `PYO3_CROSS_PYTHON_IMPLEMENTATION`
-    pyo3_cross_python_implementation: Option<OsString>,
-}
+    /// Returns `true` if `Py_TPFLAGS_MANAGED_DICT` is available to let the interpreter manage the
+    /// instance `__dict__` (CPython 3.12+). Emitted as `cfg(py_managed_dict)`.
+    pub fn has_managed_dict(&self) -> bool {
+        self.has_cpython_type_layout_since(12)
+    }
 
-impl CrossCompileEnvVars {
-    /// Grabs the PyO3 cross-compile variables from the environment.
+    /// Returns `true` if the configured Python supports subinterpreters with their own GIL
+    /// ([PEP 684](https://peps.python.org/pep-0684/)).
     ///
-    /// Registers the build script to rerun if any of the variables changes.
-    fn from_env() -> Self {
-        CrossCompileEnvVars {
-            pyo3_cross: env_var("PYO3_CROSS"),
-            pyo3_cross_lib_dir: env_var("PYO3_CROSS_LIB_DIR"),
-            pyo3_cross_python_version: env_var("PYO3_CROSS_PYTHON_VERSION"),
-            pyo3_cross_python_implementation: env_var("PYO3_CROSS_PYTHON_IMPLEMENTATION"),
-        }
+    /// This requires CPython 3.12 or newer. The APIs to create such interpreters are not part of
+    /// the limited API, so this is always `false` for `abi3` builds.
+    pub fn supports_subinterpreters(&self) -> bool {
+        self.implementation == PythonImplementation::CPython
+            && !self.abi3
+            && self.version
+                >= PythonVersion {
+                    major: 3,
+                    minor: 12,
+                }
     }
 
-    /// Checks if any of the variables is set.
-    fn any(&self) -> bool {
-        self.pyo3_cross.is_some()
-            || self.pyo3_cross_lib_dir.is_some()
-            || self.pyo3_cross_python_version.is_some()
-            || self.pyo3_cross_python_implementation.is_some()
+    /// Returns `true` if the interpreter was configured with the given `configure` option, either
+    /// on its own (`--enable-shared`) or with a value (`--enable-shared=yes`).
+    pub fn has_config_arg(&self, option: &str) -> bool {
+        has_config_arg(&self.config_args, option)
     }
 
-    /// Parses `PYO3_CROSS_PYTHON_VERSION` environment variable value
-    /// into `PythonVersion`.
-    fn parse_version(&self) -> Result<Option<PythonVersion>> {
-        let version = self
-            .pyo3_cross_python_version
-            .as_ref()
-            .map(|os_string| {
-                let utf8_str = os_string
-                    .to_str()
-                    .ok_or("PYO3_CROSS_PYTHON_VERSION is not valid a UTF-8 string")?;
-                utf8_str
-                    .parse()
-                    .context("failed to parse PYO3_CROSS_PYTHON_VERSION")
-            })
-            .transpose()?;
-
-        Ok(version)
+    /// Returns `true` if the interpreter was built with profile guided optimizations, i.e.
+    /// configured with `--enable-optimizations`.
+    pub fn is_pgo_build(&self) -> bool {
+        self.has_config_arg("--enable-optimizations")
     }
 
-    /// Parses `PYO3_CROSS_PYTHON_IMPLEMENTATION` environment variable value
-    /// into `PythonImplementation`.
-    fn parse_implementation(&self) -> Result<Option<PythonImplementation>> {
-        let implementation = self
-            .pyo3_cross_python_implementation
-            .as_ref()
-            .map(|os_string| {
-                let utf8_str = os_string
-                    .to_str()
-                    .ok_or("PYO3_CROSS_PYTHON_IMPLEMENTATION is not valid a UTF-8 string")?;
-                utf8_str
-                    .parse()
-                    .context("failed to parse PYO3_CROSS_PYTHON_IMPLEMENTATION")
-            })
-            .transpose()?;
+    /// Returns `true` if the configured Python is a free-threaded build of CPython (i.e. it was
+    /// built with `--disable-gil`, setting `Py_GIL_DISABLED`).
+    pub fn gil_disabled(&self) -> bool {
+        self.build_flags.0.contains(&BuildFlag::Py_GIL_DISABLED)
+    }
 
-        Ok(implementation)
+    /// Returns `true` if the configured Python was built with `--with-pydebug` (i.e. `Py_DEBUG`).
+    pub fn is_debug_build(&self) -> bool {
+        self.build_flags.0.contains(&BuildFlag::Py_DEBUG)
     }
 
-    /// Converts the stored `PYO3_CROSS_LIB_DIR` variable value (if any)
-    /// into a `PathBuf` instance.
+    /// Checks whether the stable ABI was requested for a debug Python build.
     ///
-    /// Ensures that the path is a valid UTF-8 string.
-    fn lib_dir_path(&self) -> Result<Option<PathBuf>> {
-        let lib_dir = self.pyo3_cross_lib_dir.as_ref().map(PathBuf::from);
+    /// Debug builds of CPython have a different object layout, so extension modules built with
+    /// `abi3` against them are not guaranteed to load on release interpreters. When `strict` is
+    /// set this is an error; otherwise the warning message is returned for the caller to emit.
+    #[doc(hidden)]
+    pub fn check_abi3_debug_build(&self, strict: bool) -> Result<Option<String>> {
+        if !(self.abi3 && self.is_debug_build()) {
+            return Ok(None);
+        }
+        let message = format!(
+            "abi3 was requested, but the configured Python interpreter ({} {}) is a debug build \
+            (Py_DEBUG); the stable ABI is not guaranteed for debug builds, so the output may not be \
+            loadable by release interpreters",
+            self.implementation, self.version
+        );
+        ensure!(
+            !strict,
+            "{}\n= help: unset PYO3_ABI3_STRICT to downgrade this error to a warning",
+            message
+        );
+        Ok(Some(message))
+    }
 
-        if let Some(dir) = lib_dir.as_ref() {
+    /// Checks that the configuration is internally consistent.
+    ///
+    /// This only performs checks which don't depend on the build environment; the
+    /// PyO3 build scripts perform further checks against the compile target.
+    pub(crate) fn validate(&self) -> Result<()> {
+        ensure!(
+            self.version >= MINIMUM_SUPPORTED_VERSION,
+            "the configured Python version ({}) is lower than PyO3's minimum supported version ({})",
+            self.version,
+            MINIMUM_SUPPORTED_VERSION,
+        );
+        ensure!(
+            !(self.abi3 && self.gil_disabled()),
+            "the free-threaded build of Python {} does not support the limited API (abi3)\n\
+            = help: build without the `abi3` features for free-threaded Python",
+            self.version
+        );
+        if let Some(pointer_width) = self.pointer_width {
             ensure!(
-                dir.to_str().is_some(),
-                "PYO3_CROSS_LIB_DIR variable value is not a valid UTF-8 string"
+                pointer_width == 32 || pointer_width == 64,
+                "unexpected pointer width in config: {}",
+                pointer_width
             );
         }
-
-        Ok(lib_dir)
+        Ok(())
     }
-}
 
-/// Detect whether we are cross compiling and return an assembled CrossCompileConfig if so.
-///
-/// This function relies on PyO3 cross-compiling environment variables:
-///
-/// * `PYO3_CROSS`: If present, forces PyO3 to configure as a cross-compilation.
-/// * `PYO3_CROSS_LIB_DIR`: If present, must be set to the directory containing
-///   the target's libpython DSO and the associated `_sysconfigdata*.py` file for
-///   Unix-like targets, or the Python DLL import libraries for the Windows target.
-/// * `PYO3_CROSS_PYTHON_VERSION`: Major and minor version (e.g. 3.9) of the target Python
-///   installation. This variable is only needed if PyO3 cannnot determine the version to target
-///   from `abi3-py3*` features, or if there are multiple versions of Python present in
-///   `PYO3_CROSS_LIB_DIR`.
-///
-/// See the [PyO3 User Guide](https://pyo3.rs/) for more info on cross-compiling.
-pub fn cross_compiling_from_to(
-    host: &Triple,
-    target: &Triple,
-) -> Result<Option<CrossCompileConfig>> {
-    let env_vars = CrossCompileEnvVars::from_env();
-    CrossCompileConfig::try_from_env_vars_host_target(env_vars, host, target)
-}
+    /// Run a python script using the [`InterpreterConfig::executable`].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the [`executable`](InterpreterConfig::executable) is `None`.
+    pub fn run_python_script(&self, script: &str) -> Result<String> {
+        run_python_script_with_envs(
+            Path::new(self.executable.as_ref().expect("no interpreter executable")),
+            script,
+            std::iter::empty::<(&str, &str)>(),
+        )
+    }
 
-/// Detect whether we are cross compiling from Cargo and `PYO3_CROSS_*` environment
-/// variables and return an assembled `CrossCompileConfig` if so.
-///
-/// This must be called from PyO3's build script, because it relies on environment
-/// variables such as `CARGO_CFG_TARGET_OS` which aren't available at any other time.
-pub fn cross_compiling_from_cargo_env() -> Result<Option<CrossCompileConfig>> {
-    let env_vars = CrossCompileEnvVars::from_env();
-    let host = Triple::host();
-    let target = target_triple_from_env();
+    /// Run a python script using the [`InterpreterConfig::executable`] with additional
+    /// environment variables (e.g. PYTHONPATH) set.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the [`executable`](InterpreterConfig::executable) is `None`.
+    pub fn run_python_script_with_envs<I, K, V>(&self, script: &str, envs: I) -> Result<String>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        run_python_script_with_envs(
+            Path::new(self.executable.as_ref().expect("no interpreter executable")),
+            script,
+            envs,
+        )
+    }
 
-    CrossCompileConfig::try_from_env_vars_host_target(env_vars, &host, &target)
-}
+    /// Lowers the configured version to the abi3 version, if set.
+    fn fixup_for_abi3_version(&mut self, abi3_version: Option<PythonVersion>) -> Result<()> {
+        // PyPy doesn't support abi3; don't adjust the version
+        if self.implementation.is_pypy() || self.implementation.is_graalpy() {
+            return Ok(());
+        }
 
-#[allow(non_camel_case_types)]
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-pub enum BuildFlag {
-    Py_DEBUG,
-    Py_REF_DEBUG,
-    Py_TRACE_REFS,
-    COUNT_ALLOCS,
-    Other(String),
-}
+        if let Some(version) = abi3_version {
+            ensure!(
+                version <= self.version,
+                "cannot set a minimum Python version {} higher than the interpreter version {} \
+                (the minimum Python version is implied by the abi3-py3{} feature)",
+                version,
+                self.version,
+                version.minor,
+            );
 
-impl Display for BuildFlag {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            BuildFlag::Other(flag) => write!(f, "{}", flag),
-            _ => write!(f, "{:?}", self),
+            self.version = version;
         }
-    }
-}
 
-impl FromStr for BuildFlag {
-    type Err = std::convert::Infallible;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Py_DEBUG" => Ok(BuildFlag::Py_DEBUG),
-            "Py_REF_DEBUG" => Ok(BuildFlag::Py_REF_DEBUG),
-            "Py_TRACE_REFS" => Ok(BuildFlag::Py_TRACE_REFS),
-            "COUNT_ALLOCS" => Ok(BuildFlag::COUNT_ALLOCS),
-            other => Ok(BuildFlag::Other(other.to_owned())),
-        }
+        Ok(())
     }
 }
 
-/// A list of python interpreter compile-time preprocessor defines that
-/// we will pick up and pass to rustc via `--cfg=py_sys_config={varname}`;
-/// this allows using them conditional cfg attributes in the .rs files, so
+/// Builds an [`InterpreterConfig`] programmatically, e.g. in build tools which already know
+/// the Python interpreter being targeted.
+///
+/// Fields which are not set have the same defaults as keys missing from a config file.
 ///
-/// ```rust
-/// #[cfg(py_sys_config="{varname}")]
-/// # struct Foo;
 /// ```
+/// use pyo3_build_config::{InterpreterConfigBuilder, PythonImplementation, PythonVersion};
 ///
-/// is the equivalent of `#ifdef {varname}` in C.
+/// let config = InterpreterConfigBuilder::new(
+///     PythonImplementation::CPython,
+///     PythonVersion { major: 3, minor: 11 },
+/// )
+/// .lib_dir("/opt/python/lib")
+/// .shared(false)
+/// .build()
+/// .unwrap();
 ///
-/// see Misc/SpecialBuilds.txt in the python source for what these mean.
-#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
-#[derive(Clone, Default)]
-pub struct BuildFlags(pub HashSet<BuildFlag>);
+/// // The config can be written out as a file for `PYO3_CONFIG_FILE`.
+/// let mut file = Vec::new();
+/// config.to_writer(&mut file).unwrap();
+/// ```
+///
+/// An existing config can be converted back into a builder to override individual fields.
+pub struct InterpreterConfigBuilder {
+    config: InterpreterConfig,
+}
 
-impl BuildFlags {
-    const ALL: [BuildFlag; 4] = [
-        BuildFlag::Py_DEBUG,
-        BuildFlag::Py_REF_DEBUG,
-        BuildFlag::Py_TRACE_REFS,
-        BuildFlag::COUNT_ALLOCS,
-    ];
+impl InterpreterConfigBuilder {
+    /// Starts building a config for the given Python implementation and version.
+    pub fn new(implementation: PythonImplementation, version: PythonVersion) -> Self {
+        InterpreterConfigBuilder {
+            config: InterpreterConfig {
+                implementation,
+                version,
+                shared: true,
+                abi3: false,
+                lib_name: None,
+                lib_dir: None,
+                executable: None,
+                platform_tag: None,
+                python_framework_prefix: None,
+                pointer_width: None,
+                pointer_width_inferred: false,
+                build_flags: BuildFlags::default(),
+                suppress_build_script_link_lines: false,
+                extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
+            },
+        }
+    }
 
-    pub fn new() -> Self {
-        BuildFlags(HashSet::new())
+    /// Sets [`InterpreterConfig::implementation`].
+    pub fn implementation(mut self, implementation: PythonImplementation) -> Self {
+        self.config.implementation = implementation;
+        self
     }
 
-    fn from_sysconfigdata(config_map: &Sysconfigdata) -> Self {
-        Self(
-            BuildFlags::ALL
-                .iter()
-                .filter(|flag| {
-                    config_map
-                        .get_value(flag.to_string())
-                        .map_or(false, |value| value == "1")
-                })
-                .cloned()
-                .collect(),
-        )
-        .fixup()
+    /// Sets [`InterpreterConfig::version`].
+    pub fn version(mut self, version: PythonVersion) -> Self {
+        self.config.version = version;
+        self
     }
 
-    /// Examine python's compile flags to pass to cfg by launching
-    /// the interpreter and printing variables of interest from
-    /// sysconfig.get_config_vars.
-    fn from_interpreter(interpreter: impl AsRef<Path>) -> Result<Self> {
-        // sysconfig is missing all the flags on windows, so we can't actually
-        // query the interpreter directly for its build flags.
-        if cfg!(windows) {
-            return Ok(Self::new());
-        }
+    /// Sets [`InterpreterConfig::shared`]. Defaults to `true`.
+    pub fn shared(mut self, shared: bool) -> Self {
+        self.config.shared = shared;
+        self
+    }
 
-        let mut script = String::from("import sysconfig\n");
-        script.push_str("config = sysconfig.get_config_vars()\n");
+    /// Sets [`InterpreterConfig::abi3`]. Defaults to `false`.
+    pub fn abi3(mut self, abi3: bool) -> Self {
+        self.config.abi3 = abi3;
+        self
+    }
 
-        for k in &BuildFlags::ALL {
-            use std::fmt::Write;
-            writeln!(&mut script, "print(config.get('{}', '0'))", k).unwrap();
-        }
+    /// Sets [`InterpreterConfig::lib_name`].
+    ///
+    /// If not set, it defaults to the usual library name for the target given by the `TARGET`
+    /// environment variable (when building in a build script).
+    pub fn lib_name(mut self, lib_name: impl Into<String>) -> Self {
+        self.config.lib_name = Some(lib_name.into());
+        self
+    }
 
-        let stdout = run_python_script(interpreter.as_ref(), &script)?;
-        let split_stdout: Vec<&str> = stdout.trim_end().lines().collect();
-        ensure!(
-            split_stdout.len() == BuildFlags::ALL.len(),
-            "Python stdout len didn't return expected number of lines: {}",
-            split_stdout.len()
-        );
-        let flags = BuildFlags::ALL
-            .iter()
-            .zip(split_stdout)
-            .filter(|(_, flag_value)| *flag_value == "1")
-            .map(|(flag, _)| flag.clone())
-            .collect();
+    /// Sets [`InterpreterConfig::lib_dir`].
+    pub fn lib_dir(mut self, lib_dir: impl Into<String>) -> Self {
+        self.config.lib_dir = Some(lib_dir.into());
+        self
+    }
 
-        Ok(Self(flags).fixup())
+    /// Sets [`InterpreterConfig::executable`].
+    pub fn executable(mut self, executable: impl Into<String>) -> Self {
+        self.config.executable = Some(executable.into());
+        self
     }
 
-    fn fixup(mut self) -> Self {
-        if self.0.contains(&BuildFlag::Py_DEBUG) {
-            self.0.insert(BuildFlag::Py_REF_DEBUG);
-        }
+    /// Sets [`InterpreterConfig::platform_tag`].
+    pub fn platform_tag(mut self, platform_tag: impl Into<String>) -> Self {
+        self.config.platform_tag = Some(platform_tag.into());
+        self
+    }
 
+    /// Sets [`InterpreterConfig::python_framework_prefix`].
+    pub fn python_framework_prefix(mut self, python_framework_prefix: impl Into<String>) -> Self {
+        self.config.python_framework_prefix = Some(python_framework_prefix.into());
         self
     }
-}
 
-impl Display for BuildFlags {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut first = true;
-        for flag in &self.0 {
-            if first {
-                first = false;
-            } else {
-                write!(f, ",")?;
-            }
-            write!(f, "{}", flag)?;
-        }
-        Ok(())
+    /// Sets [`InterpreterConfig::pointer_width`].
+    pub fn pointer_width(mut self, pointer_width: u32) -> Self {
+        self.config.pointer_width = Some(pointer_width);
+        self.config.pointer_width_inferred = false;
+        self
     }
-}
 
-impl FromStr for BuildFlags {
-    type Err = std::convert::Infallible;
+    /// Sets [`InterpreterConfig::build_flags`].
+    pub fn build_flags(mut self, build_flags: BuildFlags) -> Self {
+        self.config.build_flags = build_flags;
+        self
+    }
 
-    fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let mut flags = HashSet::new();
-        for flag in value.split_terminator(',') {
-            flags.insert(flag.parse().unwrap());
-        }
-        Ok(BuildFlags(flags))
+    /// Sets [`InterpreterConfig::suppress_build_script_link_lines`]. Defaults to `false`.
+    pub fn suppress_build_script_link_lines(mut self, suppress: bool) -> Self {
+        self.config.suppress_build_script_link_lines = suppress;
+        self
     }
-}
 
-fn parse_script_output(output: &str) -> HashMap<String, String> {
-    output
-        .lines()
-        .filter_map(|line| {
-            let mut i = line.splitn(2, ' ');
-            Some((i.next()?.into(), i.next()?.into()))
-        })
-        .collect()
-}
+    /// Appends a line to [`InterpreterConfig::extra_build_script_lines`].
+    pub fn extra_build_script_line(mut self, line: impl Into<String>) -> Self {
+        self.config.extra_build_script_lines.push(line.into());
+        self
+    }
 
-/// Parsed data from Python sysconfigdata file
-///
-/// A hash map of all values from a sysconfigdata file.
-pub struct Sysconfigdata(HashMap<String, String>);
+    /// Appends an argument to [`InterpreterConfig::extra_link_args`].
+    pub fn extra_link_arg(mut self, arg: impl Into<String>) -> Self {
+        self.config.extra_link_args.push(arg.into());
+        self
+    }
 
-impl Sysconfigdata {
-    pub fn get_value<S: AsRef<str>>(&self, k: S) -> Option<&str> {
-        self.0.get(k.as_ref()).map(String::as_str)
+    /// Appends an argument to [`InterpreterConfig::extra_link_args_by_os`] for `target_os`.
+    pub fn extra_link_arg_for_os(
+        mut self,
+        target_os: impl Into<String>,
+        arg: impl Into<String>,
+    ) -> Self {
+        self.config
+            .extra_link_args_by_os
+            .entry(target_os.into())
+            .or_default()
+            .push(arg.into());
+        self
     }
 
-    #[allow(dead_code)]
-    fn new() -> Self {
-        Sysconfigdata(HashMap::new())
+    /// Appends a directory to [`InterpreterConfig::extra_link_search_paths`].
+    pub fn extra_link_search_path(mut self, path: impl Into<String>) -> Self {
+        self.config.extra_link_search_paths.push(path.into());
+        self
     }
 
-    #[allow(dead_code)]
-    fn insert<S: Into<String>>(&mut self, k: S, v: S) {
-        self.0.insert(k.into(), v.into());
+    /// Appends an argument to [`InterpreterConfig::config_args`].
+    pub fn config_arg(mut self, arg: impl Into<String>) -> Self {
+        self.config.config_args.push(arg.into());
+        self
     }
-}
 
-/// Parse sysconfigdata file
-///
-/// The sysconfigdata is simply a dictionary containing all the build time variables used for the
-/// python executable and library. This function necessitates a python interpreter on the host
-/// machine to work. Here it is read into a `Sysconfigdata` (hash map), which can be turned into an
-/// [`InterpreterConfig`] using
-/// [`from_sysconfigdata`](InterpreterConfig::from_sysconfigdata).
-pub fn parse_sysconfigdata(sysconfigdata_path: impl AsRef<Path>) -> Result<Sysconfigdata> {
-    let sysconfigdata_path = sysconfigdata_path.as_ref();
-    let mut script = fs::read_to_string(sysconfigdata_path).with_context(|| {
-        format!(
-            "failed to read config from {}",
-            sysconfigdata_path.display()
-        )
-    })?;
-    script += r#"
-for key, val in build_time_vars.items():
-    print(key, val)
-"#;
+    /// Adds a variable to [`InterpreterConfig::sysconfig_vars`].
+    pub fn sysconfig_var(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.sysconfig_vars.insert(name.into(), value.into());
+        self
+    }
 
-    let output = run_python_script(&find_interpreter()?, &script)?;
+    /// Finishes the config, checking that it can be used by PyO3.
+    ///
+    /// Values which can't be written to a config file (such as a `lib_dir` containing a
+    /// newline) are rejected, so that [`InterpreterConfig::to_writer`] round-trips.
+    pub fn build(self) -> Result<InterpreterConfig> {
+        let mut config = self.config;
+        if config.lib_name.is_none() {
+            if let Ok(Ok(target)) = env::var("TARGET").map(|target| target.parse::<Triple>()) {
+                config.lib_name = default_lib_name_for_target(
+                    config.version,
+                    config.implementation,
+                    config.abi3,
+                    &target,
+                );
+            }
+        }
 
-    Ok(Sysconfigdata(parse_script_output(&output)))
-}
+        let optional_values = [
+            ("lib_name", &config.lib_name),
+            ("lib_dir", &config.lib_dir),
+            ("executable", &config.executable),
+            ("platform_tag", &config.platform_tag),
+            ("python_framework_prefix", &config.python_framework_prefix),
+        ];
+        let values = optional_values
+            .into_iter()
+            .filter_map(|(name, value)| Some((name, value.as_ref()?)))
+            .chain(
+                (config.extra_build_script_lines.iter())
+                    .map(|line| ("extra_build_script_line", line)),
+            )
+            .chain(
+                config
+                    .extra_link_args
+                    .iter()
+                    .map(|arg| ("extra_link_arg", arg)),
+            )
+            .chain(
+                (config.extra_link_args_by_os.values().flatten())
+                    .map(|arg| ("extra_link_arg", arg)),
+            )
+            .chain(
+                (config.extra_link_search_paths.iter())
+                    .map(|path| ("extra_link_search_path", path)),
+            )
+            .chain(config.config_args.iter().map(|arg| ("config_arg", arg)))
+            .chain((config.sysconfig_vars.values()).map(|value| ("sysconfig_var value", value)));
+        for (name, value) in values {
+            ensure!(
+                !value.contains('\n'),
+                "invalid {} `{}`: config values cannot contain newlines",
+                name,
+                value.escape_debug()
+            );
+        }
 
-fn starts_with(entry: &DirEntry, pat: &str) -> bool {
-    let name = entry.file_name();
-    name.to_string_lossy().starts_with(pat)
+        config.validate()?;
+        Ok(config)
+    }
 }
-fn ends_with(entry: &DirEntry, pat: &str) -> bool {
-    let name = entry.file_name();
-    name.to_string_lossy().ends_with(pat)
+
+impl From<InterpreterConfig> for InterpreterConfigBuilder {
+    fn from(config: InterpreterConfig) -> Self {
+        InterpreterConfigBuilder { config }
+    }
 }
 
-/// Finds the sysconfigdata file when the target Python library directory is set.
-///
-/// Returns `None` if the library directory is not available, and a runtime error
-/// when no or multiple sysconfigdata files are found.
-fn find_sysconfigdata(cross: &CrossCompileConfig) -> Result<Option<PathBuf>> {
-    let mut sysconfig_paths = find_all_sysconfigdata(cross);
-    if sysconfig_paths.is_empty() {
-        if let Some(lib_dir) = cross.lib_dir.as_ref() {
-            bail!("Could not find _sysconfigdata*.py in {}", lib_dir.display());
-        } else {
-            // Continue with the default configuration when PYO3_CROSS_LIB_DIR is not set.
-            return Ok(None);
-        }
-    } else if sysconfig_paths.len() > 1 {
-        let mut error_msg = String::from(
-            "Detected multiple possible Python versions. Please set either the \
-            PYO3_CROSS_PYTHON_VERSION variable to the wanted version or the \
-            _PYTHON_SYSCONFIGDATA_NAME variable to the wanted sysconfigdata file name.\n\n\
-            sysconfigdata files found:",
-        );
-        for path in sysconfig_paths {
-            use std::fmt::Write;
-            write!(&mut error_msg, "\n\t{}", path.display()).unwrap();
+/// Returns the current user's home directory, from `HOME` (or `USERPROFILE` on Windows).
+pub(crate) fn home_dir() -> Option<OsString> {
+    let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    env::var_os(var).filter(|home| !home.is_empty())
+}
+
+/// Expands a leading `~` component of `path` to `home`.
+pub(crate) fn expand_home_dir(path: &Path, home: Option<&OsStr>) -> Result<PathBuf> {
+    let mut components = path.components();
+    match components.next() {
+        Some(std::path::Component::Normal(first)) if first == "~" => {
+            let home = home.ok_or_else(|| {
+                format!(
+                    "failed to expand `~` in {}: could not determine the home directory",
+                    path.display()
+                )
+            })?;
+            Ok(Path::new(home).join(components.as_path()))
         }
-        bail!("{}\n", error_msg);
+        _ => Ok(path.to_owned()),
     }
+}
 
-    Ok(Some(sysconfig_paths.remove(0)))
+/// Validates an `extra_link_args` config value, which must be a non-empty argument.
+fn parse_link_arg(key: &str, value: &str) -> Result<String> {
+    let arg = value.trim();
+    ensure!(!arg.is_empty(), "empty value for config key `{}`", key);
+    Ok(arg.to_owned())
 }
 
-/// Finds `_sysconfigdata*.py` files for detected Python interpreters.
-///
-/// From the python source for `_sysconfigdata*.py` is always going to be located at
-/// `build/lib.{PLATFORM}-{PY_MINOR_VERSION}` when built from source. The [exact line][1] is defined as:
-///
-/// ```py
-/// pybuilddir = 'build/lib.%s-%s' % (get_platform(), sys.version_info[:2])
-/// ```
-///
-/// Where get_platform returns a kebab-case formatted string containing the os, the architecture and
-/// possibly the os' kernel version (not the case on linux). However, when installed using a package
-/// manager, the `_sysconfigdata*.py` file is installed in the `${PREFIX}/lib/python3.Y/` directory.
-/// The `_sysconfigdata*.py` is generally in a sub-directory of the location of `libpython3.Y.so`.
-/// So we must find the file in the following possible locations:
-///
-/// ```sh
-/// # distribution from package manager, (lib_dir may or may not include lib/)
-/// ${INSTALL_PREFIX}/lib/python3.Y/_sysconfigdata*.py
-/// ${INSTALL_PREFIX}/lib/libpython3.Y.so
-/// ${INSTALL_PREFIX}/lib/python3.Y/config-3.Y-${HOST_TRIPLE}/libpython3.Y.so
-///
-/// # Built from source from host
-/// ${CROSS_COMPILED_LOCATION}/build/lib.linux-x86_64-Y/_sysconfigdata*.py
-/// ${CROSS_COMPILED_LOCATION}/libpython3.Y.so
-///
-/// # if cross compiled, kernel release is only present on certain OS targets.
-/// ${CROSS_COMPILED_LOCATION}/build/lib.{OS}(-{OS-KERNEL-RELEASE})?-{ARCH}-Y/_sysconfigdata*.py
-/// ${CROSS_COMPILED_LOCATION}/libpython3.Y.so
-///
-/// # PyPy includes a similar file since v73
-/// ${INSTALL_PREFIX}/lib/pypy3.Y/_sysconfigdata.py
-/// ${INSTALL_PREFIX}/lib_pypy/_sysconfigdata.py
-/// ```
-///
-/// [1]: https://github.com/python/cpython/blob/3.5/Lib/sysconfig.py#L389
-///
-/// Returns an empty vector when the target Python library directory
-/// is not set via `PYO3_CROSS_LIB_DIR`.
-pub fn find_all_sysconfigdata(cross: &CrossCompileConfig) -> Vec<PathBuf> {
-    let sysconfig_paths = if let Some(lib_dir) = cross.lib_dir.as_ref() {
-        search_lib_dir(lib_dir, cross)
-    } else {
-        return Vec::new();
-    };
+fn warn_unknown_key(key: &str) -> Result<()> {
+    warn!(PYO3_W002, "unknown config key `{}`", key);
+    Ok(())
+}
 
-    let sysconfig_name = env_var("_PYTHON_SYSCONFIGDATA_NAME");
-    let mut sysconfig_paths = sysconfig_paths
-        .iter()
-        .filter_map(|p| {
-            let canonical = fs::canonicalize(p).ok();
-            match &sysconfig_name {
-                Some(_) => canonical.filter(|p| p.file_stem() == sysconfig_name.as_deref()),
-                None => canonical,
-            }
-        })
-        .collect::<Vec<PathBuf>>();
+/// Reads the `key=value` pairs of the config file at `path`, expanding a leading `~`.
+/// Writes a `key=value` line of a config file, refusing values which span several lines.
+fn write_config_line(writer: &mut impl Write, key: &str, value: &dyn Display) -> Result<()> {
+    let value = value.to_string();
+    ensure!(
+        !value.contains('\n'),
+        "cannot write {} `{}` to config: it contains a newline",
+        key,
+        value.escape_debug()
+    );
+    writeln!(writer, "{}={}", key, value)
+        .with_context(|| format!("failed to write {} to config", key))
+}
 
-    sysconfig_paths.sort();
-    sysconfig_paths.dedup();
+fn read_config_file(path: &Path) -> Result<Vec<(String, String)>> {
+    let path = &expand_home_dir(path, home_dir().as_deref())?;
+    let config_file = std::fs::File::open(path)
+        .with_context(|| format!("failed to open PyO3 config file at {}", path.display()))?;
+    let canonical_path = path
+        .canonicalize()
+        .with_context(|| format!("failed to resolve PyO3 config file {}", path.display()))?;
+    read_config_pairs_of_file(config_file, path, &mut vec![canonical_path])
+}
 
-    sysconfig_paths
+/// Returns `true` if the config file at `path` is written in TOML, judging by its extension.
+fn is_toml_config(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension.eq_ignore_ascii_case("toml"))
 }
 
-fn is_pypy_lib_dir(path: &str, v: &Option<PythonVersion>) -> bool {
-    let pypy_version_pat = if let Some(v) = v {
-        format!("pypy{}", v)
+/// Reads the pairs of the config file at `path`, in the format given by its extension.
+fn read_config_pairs_of_file(
+    mut file: std::fs::File,
+    path: &Path,
+    include_stack: &mut Vec<PathBuf>,
+) -> Result<Vec<(String, String)>> {
+    if is_toml_config(path) {
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .with_context(|| format!("failed to read PyO3 config file {}", path.display()))?;
+        config_toml::config_pairs(&contents)
     } else {
-        "pypy3.".into()
-    };
-    path == "lib_pypy" || path.starts_with(&pypy_version_pat)
+        read_config_pairs(BufReader::new(file), path.parent(), include_stack)
+    }
 }
 
-fn is_graalpy_lib_dir(path: &str, v: &Option<PythonVersion>) -> bool {
-    let graalpy_version_pat = if let Some(v) = v {
-        format!("graalpy{}", v)
-    } else {
-        "graalpy2".into()
-    };
-    path == "lib_graalpython" || path.starts_with(&graalpy_version_pat)
+/// Checks that the config file at `path` can be used by PyO3, without emitting any cargo
+/// directives.
+///
+/// Unlike when building, unknown keys are reported as errors. Use `.report()` on the error to
+/// display it together with its causes.
+pub fn validate_config_file(path: &Path) -> Result<()> {
+    let config = InterpreterConfig::from_pairs(read_config_file(path)?, |key| {
+        bail!("unknown config key `{}`", key)
+    })?;
+    config.validate()
 }
 
-fn is_cpython_lib_dir(path: &str, v: &Option<PythonVersion>) -> bool {
-    let cpython_version_pat = if let Some(v) = v {
-        format!("python{}", v)
-    } else {
-        "python3.".into()
-    };
-    path.starts_with(&cpython_version_pat)
-}
-
-/// recursive search for _sysconfigdata, returns all possibilities of sysconfigdata paths
-fn search_lib_dir(path: impl AsRef<Path>, cross: &CrossCompileConfig) -> Vec<PathBuf> {
-    let mut sysconfig_paths = vec![];
-    for f in fs::read_dir(path).expect("Path does not exist") {
-        sysconfig_paths.extend(match &f {
-            // Python 3.7+ sysconfigdata with platform specifics
-            Ok(f) if starts_with(f, "_sysconfigdata_") && ends_with(f, "py") => vec![f.path()],
-            Ok(f) if f.metadata().map_or(false, |metadata| metadata.is_dir()) => {
-                let file_name = f.file_name();
-                let file_name = file_name.to_string_lossy();
-                if file_name == "build" || file_name == "lib" {
-                    search_lib_dir(f.path(), cross)
-                } else if file_name.starts_with("lib.") {
-                    // check if right target os
-                    if !file_name.contains(&cross.target.operating_system.to_string()) {
-                        continue;
-                    }
-                    // Check if right arch
-                    if !file_name.contains(&cross.target.architecture.to_string()) {
-                        continue;
-                    }
-                    search_lib_dir(f.path(), cross)
-                } else if is_cpython_lib_dir(&file_name, &cross.version)
-                    || is_pypy_lib_dir(&file_name, &cross.version)
-                    || is_graalpy_lib_dir(&file_name, &cross.version)
-                {
-                    search_lib_dir(f.path(), cross)
-                } else {
-                    continue;
-                }
-            }
-            _ => continue,
-        });
+/// Reads the `key=value` pairs of a config, expanding `include=path` lines in place.
+///
+/// `include_stack` holds the canonical paths of the files currently being read, to detect cycles.
+fn read_config_pairs(
+    reader: impl Read,
+    base_dir: Option<&Path>,
+    include_stack: &mut Vec<PathBuf>,
+) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line.context("failed to read line from config")?;
+        let mut split = line.splitn(2, '=');
+        let (key, value) = (
+            split
+                .next()
+                .expect("first splitn value should always be present"),
+            split
+                .next()
+                .ok_or_else(|| format!("expected key=value pair on line {}", i + 1))?,
+        );
+        if key != "include" {
+            pairs.push((key.to_owned(), value.to_owned()));
+            continue;
+        }
+
+        let path = match base_dir {
+            Some(base_dir) => base_dir.join(value.trim()),
+            None => PathBuf::from(value.trim()),
+        };
+        let canonical_path = path
+            .canonicalize()
+            .with_context(|| format!("failed to resolve included config {}", path.display()))?;
+        if include_stack.contains(&canonical_path) {
+            let cycle = include_stack
+                .iter()
+                .chain(std::iter::once(&canonical_path))
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            bail!("include cycle detected in PyO3 config: {}", cycle);
+        }
+
+        let file = std::fs::File::open(&path)
+            .with_context(|| format!("failed to open included config {}", path.display()))?;
+        include_stack.push(canonical_path);
+        let included = read_config_pairs_of_file(file, &path, include_stack)
+            .with_context(|| format!("failed to read included config {}", path.display()))?;
+        include_stack.pop();
+        pairs.extend(included);
     }
-    // If we got more than one file, only take those that contain the arch name.
-    // For ubuntu 20.04 with host architecture x86_64 and a foreign architecture of armhf
-    // this reduces the number of candidates to 1:
-    //
-    // $ find /usr/lib/python3.8/ -name '_sysconfigdata*.py' -not -lname '*'
-    //  /usr/lib/python3.8/_sysconfigdata__x86_64-linux-gnu.py
-    //  /usr/lib/python3.8/_sysconfigdata__arm-linux-gnueabihf.py
-    if sysconfig_paths.len() > 1 {
-        let temp = sysconfig_paths
-            .iter()
-            .filter(|p| {
-                p.to_string_lossy()
-                    .contains(&cross.target.architecture.to_string())
-            })
-            .cloned()
-            .collect::<Vec<PathBuf>>();
-        if !temp.is_empty() {
-            sysconfig_paths = temp;
+    Ok(pairs)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PythonImplementation {
+    CPython,
+    PyPy,
+    GraalPy,
+}
+
+impl PythonImplementation {
+    /// Returns `true` for [`PythonImplementation::PyPy`].
+    pub fn is_pypy(self) -> bool {
+        self == PythonImplementation::PyPy
+    }
+
+    /// Returns `true` for [`PythonImplementation::GraalPy`].
+    pub fn is_graalpy(self) -> bool {
+        self == PythonImplementation::GraalPy
+    }
+
+    #[doc(hidden)]
+    pub fn from_soabi(soabi: &str) -> Result<Self> {
+        if soabi.starts_with("pypy") {
+            Ok(PythonImplementation::PyPy)
+        } else if soabi.starts_with("cpython") {
+            Ok(PythonImplementation::CPython)
+        } else if soabi.starts_with("graalpy") {
+            Ok(PythonImplementation::GraalPy)
+        } else {
+            bail!("unsupported Python interpreter");
         }
     }
+}
 
-    sysconfig_paths
+impl Display for PythonImplementation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PythonImplementation::CPython => write!(f, "CPython"),
+            PythonImplementation::PyPy => write!(f, "PyPy"),
+            PythonImplementation::GraalPy => write!(f, "GraalVM"),
+        }
+    }
 }
 
-/// Find cross compilation information from sysconfigdata file
+impl FromStr for PythonImplementation {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        // Be lenient with hand-written configs; `Display` still produces the canonical names.
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cpython" => Ok(PythonImplementation::CPython),
+            "pypy" => Ok(PythonImplementation::PyPy),
+            // `platform.python_implementation()` reports GraalPy as "GraalVM"
+            "graalvm" | "graalpy" => Ok(PythonImplementation::GraalPy),
+            _ => bail!("unknown interpreter: {}", s),
+        }
+    }
+}
+
+/// Returns a warning if the interpreter is running under Rosetta 2 translation.
 ///
-/// first find sysconfigdata file which follows the pattern [`_sysconfigdata_{abi}_{platform}_{multiarch}`][1]
+/// The interpreter reports its own (translated) architecture, so the configuration stays
+/// consistent with the interpreter's build, but this is rarely what the user intended on an
+/// Apple Silicon machine.
+fn rosetta_warning(machine: &str, translated: bool) -> Option<String> {
+    if !translated {
+        return None;
+    }
+    Some(format!(
+        "The Python interpreter is a {} build running under Rosetta 2 translation on an Apple \
+        Silicon host, so PyO3 will build for {}. Use a native arm64 (or universal2) Python \
+        interpreter to build for the host architecture.",
+        machine, machine
+    ))
+}
+
+/// Checks if we should look for a Python interpreter installation
+/// to get the target interpreter configuration.
 ///
-/// [1]: https://github.com/python/cpython/blob/3.8/Lib/sysconfig.py#L348
+/// Returns `false` if `PYO3_NO_PYTHON` environment variable is set.
+fn have_python_interpreter() -> bool {
+    env_var("PYO3_NO_PYTHON").is_none()
+}
+
+/// Checks if `abi3` or any of the `abi3-py3*` features is enabled for the PyO3 crate.
 ///
-/// Returns `None` when the target Python library directory is not set.
-fn cross_compile_from_sysconfigdata(
-    cross_compile_config: &CrossCompileConfig,
-) -> Result<Option<InterpreterConfig>> {
-    if let Some(path) = find_sysconfigdata(cross_compile_config)? {
-        let data = parse_sysconfigdata(path)?;
-        let config = InterpreterConfig::from_sysconfigdata(&data)?;
+/// Must be called from a PyO3 crate build script.
+fn is_abi3() -> bool {
+    cargo_env_var("CARGO_FEATURE_ABI3").is_some()
+        || env_var("PYO3_ABI3_VERSION").is_some()
+        || env_var("PYO3_USE_ABI3_FORWARD_COMPATIBILITY").map_or(false, |os_str| os_str == "1")
+}
 
-        Ok(Some(config))
-    } else {
-        Ok(None)
-    }
+/// Checks if the `PYO3_ABI3_STRICT` environment variable is set to `1`.
+///
+/// In strict mode non-portable `abi3` configurations are hard errors, and `abi3` builds
+/// additionally get the `py_sys_config="ABI3_STRICT"` cfg so that version-specific code can be
+/// rejected at compile time.
+pub fn is_abi3_strict() -> bool {
+    env_var("PYO3_ABI3_STRICT").map_or(false, |os_str| os_str == "1")
 }
 
-/// Generates "default" cross compilation information for the target.
+/// Gets the minimum supported Python version for `abi3` builds.
 ///
-/// This should work for most CPython extension modules when targeting
-/// Windows, macOS and Linux.
+/// The `PYO3_ABI3_VERSION` environment variable (e.g. `3.9`) takes precedence over the
+/// version from the PyO3 `abi3-py*` features when set and valid.
 ///
 /// Must be called from a PyO3 crate build script.
-#[allow(unused_mut)]
-fn default_cross_compile(cross_compile_config: &CrossCompileConfig) -> Result<InterpreterConfig> {
-    let version = cross_compile_config
-        .version
-        .or_else(get_abi3_version)
-        .ok_or_else(||
-            format!(
-                "PYO3_CROSS_PYTHON_VERSION or an abi3-py3* feature must be specified \
-                when cross-compiling and PYO3_CROSS_LIB_DIR is not set.\n\
-                = help: see the PyO3 user guide for more information: https://pyo3.rs/v{}/building-and-distribution.html#cross-compiling",
-                env!("CARGO_PKG_VERSION")
-            )
-        )?;
-
-    let abi3 = is_abi3();
-    let implementation = cross_compile_config
-        .implementation
-        .unwrap_or(PythonImplementation::CPython);
+pub fn get_abi3_version() -> Option<PythonVersion> {
+    if let Ok(Some(version)) = abi3_version_from_env() {
+        return Some(version);
+    }
+    enabled_abi3_feature_minors()
+        .first()
+        .map(|&minor| PythonVersion { major: 3, minor })
+}
 
-    let lib_name =
-        default_lib_name_for_target(version, implementation, abi3, &cross_compile_config.target);
+/// Checks that the `abi3` minimum version is unambiguous.
+///
+/// Reports an invalid `PYO3_ABI3_VERSION`, or, when it is unset, more than one enabled
+/// `abi3-py*` feature.
+fn validate_abi3_version() -> Result<()> {
+    if abi3_version_from_env()?.is_none() {
+        abi3_version_from_feature_minors(&enabled_abi3_feature_minors())?;
+    }
+    Ok(())
+}
 
-    let mut lib_dir = cross_compile_config.lib_dir_string();
+/// Lists the minor versions of the enabled `abi3-py3*` features, lowest first.
+fn enabled_abi3_feature_minors() -> Vec<u8> {
+    (MINIMUM_SUPPORTED_VERSION.minor..=ABI3_MAX_MINOR)
+        .filter(|i| cargo_env_var(&format!("CARGO_FEATURE_ABI3_PY3{}", i)).is_some())
+        .collect()
+}
 
-    // Auto generate python3.dll import libraries for Windows targets.
-    #[cfg(feature = "python3-dll-a")]
-    if lib_dir.is_none() {
-        let py_version = if abi3 { None } else { Some(version) };
-        lib_dir = self::import_lib::generate_import_lib(
-            &cross_compile_config.target,
-            cross_compile_config
-                .implementation
-                .unwrap_or(PythonImplementation::CPython),
-            py_version,
-        )?;
+/// Each `abi3-py3*` feature names a single minimum version, so several of them being enabled
+/// at once means that crates in the dependency graph asked for conflicting minimum versions.
+fn abi3_version_from_feature_minors(minors: &[u8]) -> Result<Option<PythonVersion>> {
+    match minors {
+        [] => Ok(None),
+        [minor] => Ok(Some(PythonVersion {
+            major: 3,
+            minor: *minor,
+        })),
+        _ => {
+            let features: Vec<String> = minors
+                .iter()
+                .map(|minor| format!("abi3-py3{}", minor))
+                .collect();
+            bail!(
+                "conflicting abi3 minimum versions: the features {} are all enabled\n\
+                = help: crates in the dependency graph enable different `abi3-py3*` features of \
+                PyO3; run `cargo tree -e features -i pyo3` to find them\n\
+                = help: make the features agree, or set PYO3_ABI3_VERSION to choose the minimum \
+                version explicitly",
+                features.join(", ")
+            )
+        }
     }
+}
 
-    Ok(InterpreterConfig {
-        implementation,
+/// Reads the `PYO3_ABI3_VERSION` environment variable, which enables `abi3` with the given
+/// minimum Python version without the `abi3-py*` features.
+fn abi3_version_from_env() -> Result<Option<PythonVersion>> {
+    env_var("PYO3_ABI3_VERSION")
+        .map(|value| parse_abi3_version(&value))
+        .transpose()
+}
+
+fn parse_abi3_version(value: &OsStr) -> Result<PythonVersion> {
+    let version: PythonVersion = value
+        .to_str()
+        .ok_or("PYO3_ABI3_VERSION is not valid a UTF-8 string")?
+        .parse()
+        .context("failed to parse PYO3_ABI3_VERSION")?;
+    ensure!(
+        version.major == 3
+            && (MINIMUM_SUPPORTED_VERSION.minor..=ABI3_MAX_MINOR).contains(&version.minor),
+        "PYO3_ABI3_VERSION={} is not supported; the abi3 minimum version must be between {} \
+        and 3.{}",
         version,
-        shared: true,
-        abi3,
-        lib_name,
-        lib_dir,
-        executable: None,
-        pointer_width: None,
-        build_flags: BuildFlags::default(),
-        suppress_build_script_link_lines: false,
-        extra_build_script_lines: vec![],
-    })
+        MINIMUM_SUPPORTED_VERSION,
+        ABI3_MAX_MINOR
+    );
+    Ok(version)
 }
 
-/// Generates "default" interpreter configuration when compiling "abi3" extensions
-/// without a working Python interpreter.
-///
-/// `version` specifies the minimum supported Stable ABI CPython version.
+/// Checks if the `extension-module` feature is enabled for the PyO3 crate.
 ///
-/// This should work for most CPython extension modules when compiling on
-/// Windows, macOS and Linux.
+/// The `PYO3_EXTENSION_MODULE` environment variable (`0` or `1`) takes precedence over the
+/// feature when set.
 ///
 /// Must be called from a PyO3 crate build script.
-fn default_abi3_config(host: &Triple, version: PythonVersion) -> InterpreterConfig {
-    // FIXME: PyPy & GraalPy do not support the Stable ABI.
-    let implementation = PythonImplementation::CPython;
-    let abi3 = true;
+pub fn is_extension_module() -> bool {
+    let feature_enabled = cargo_env_var("CARGO_FEATURE_EXTENSION_MODULE").is_some();
+    // Invalid values are reported by `validate_extension_module_env`.
+    resolve_extension_module(env_var("PYO3_EXTENSION_MODULE").as_deref(), feature_enabled)
+        .unwrap_or(feature_enabled)
+}
 
-    let lib_name = if host.operating_system == OperatingSystem::Windows {
-        Some(default_lib_name_windows(
-            version,
-            implementation,
-            abi3,
-            false,
-            false,
-        ))
-    } else {
-        None
-    };
+/// Checks that the `PYO3_EXTENSION_MODULE` environment variable, if set, is a recognized value.
+pub fn validate_extension_module_env() -> Result<()> {
+    resolve_extension_module(env_var("PYO3_EXTENSION_MODULE").as_deref(), false).map(drop)
+}
 
-    InterpreterConfig {
-        implementation,
-        version,
-        shared: true,
-        abi3,
-        lib_name,
-        lib_dir: None,
-        executable: None,
-        pointer_width: None,
-        build_flags: BuildFlags::default(),
-        suppress_build_script_link_lines: false,
-        extra_build_script_lines: vec![],
+fn resolve_extension_module(env_value: Option<&OsStr>, feature_enabled: bool) -> Result<bool> {
+    match env_value {
+        None => Ok(feature_enabled),
+        Some(value) if value == "1" => Ok(true),
+        Some(value) if value == "0" => Ok(false),
+        Some(value) => bail!(
+            "expected `0` or `1` for PYO3_EXTENSION_MODULE, got `{}`",
+            value.to_string_lossy()
+        ),
     }
 }
 
-/// Detects the cross compilation target interpreter configuration from all
-/// available sources (PyO3 environment variables, Python sysconfigdata, etc.).
-///
-/// Returns the "default" target interpreter configuration for Windows and
-/// when no target Python interpreter is found.
+/// Checks if we need to link to `libpython` for the current build target.
 ///
 /// Must be called from a PyO3 crate build script.
-fn load_cross_compile_config(
-    cross_compile_config: CrossCompileConfig,
-) -> Result<InterpreterConfig> {
-    let windows = cross_compile_config.target.operating_system == OperatingSystem::Windows;
+pub fn is_linking_libpython() -> bool {
+    is_linking_libpython_for_target(&target_triple_from_env())
+}
 
-    let config = if windows || !have_python_interpreter() {
-        // Load the defaults for Windows even when `PYO3_CROSS_LIB_DIR` is set
-        // since it has no sysconfigdata files in it.
-        // Also, do not try to look for sysconfigdata when `PYO3_NO_PYTHON` variable is set.
-        default_cross_compile(&cross_compile_config)?
-    } else if let Some(config) = cross_compile_from_sysconfigdata(&cross_compile_config)? {
-        // Try to find and parse sysconfigdata files on other targets.
-        config
-    } else {
-        // Fall back to the defaults when nothing else can be done.
-        default_cross_compile(&cross_compile_config)?
-    };
-
-    if config.lib_name.is_some() && config.lib_dir.is_none() {
-        warn!(
-            "The output binary will link to libpython, \
-            but PYO3_CROSS_LIB_DIR environment variable is not set. \
-            Ensure that the target Python library directory is \
-            in the rustc native library search path."
-        );
-    }
-
-    Ok(config)
+/// Checks if we need to link to `libpython` for the target.
+///
+/// Must be called from a PyO3 crate build script.
+fn is_linking_libpython_for_target(target: &Triple) -> bool {
+    target_requires_libpython(target, is_extension_module())
 }
 
-// Link against python3.lib for the stable ABI on Windows.
-// See https://www.python.org/dev/peps/pep-0384/#linkage
-//
-// This contains only the limited ABI symbols.
-const WINDOWS_ABI3_LIB_NAME: &str = "python3";
-
-fn default_lib_name_for_target(
-    version: PythonVersion,
-    implementation: PythonImplementation,
-    abi3: bool,
-    target: &Triple,
-) -> Option<String> {
-    if target.operating_system == OperatingSystem::Windows {
-        Some(default_lib_name_windows(
-            version,
-            implementation,
-            abi3,
-            false,
-            false,
-        ))
-    } else if is_linking_libpython_for_target(target) {
-        Some(default_lib_name_unix(version, implementation, None))
-    } else {
-        None
-    }
+/// Checks if linking to `libpython` is needed for the target, given whether an extension
+/// module is being built.
+fn target_requires_libpython(target: &Triple, extension_module: bool) -> bool {
+    target.operating_system == OperatingSystem::Windows
+        // See https://github.com/PyO3/pyo3/issues/4068#issuecomment-2051159852
+        || target.operating_system == OperatingSystem::Aix
+        || target.environment == Environment::Android
+        || target.environment == Environment::Androideabi
+        // iOS does not allow `-undefined dynamic_lookup`; extensions link the Python framework.
+        || target.operating_system == OperatingSystem::Ios
+        || !extension_module
 }
 
-fn default_lib_name_windows(
-    version: PythonVersion,
-    implementation: PythonImplementation,
-    abi3: bool,
-    mingw: bool,
-    debug: bool,
-) -> String {
-    if debug {
-        // CPython bug: linking against python3_d.dll raises error
-        // https://github.com/python/cpython/issues/101614
-        format!("python{}{}_d", version.major, version.minor)
-    } else if abi3 && !(implementation.is_pypy() || implementation.is_graalpy()) {
-        WINDOWS_ABI3_LIB_NAME.to_owned()
-    } else if mingw {
-        // https://packages.msys2.org/base/mingw-w64-python
-        format!("python{}.{}", version.major, version.minor)
-    } else {
-        format!("python{}{}", version.major, version.minor)
-    }
-}
+/// Checks if we need to discover the Python library directory
+/// to link the extension module binary.
+///
+/// Must be called from a PyO3 crate build script.
+fn require_libdir_for_target(target: &Triple) -> bool {
+    let is_generating_libpython = cfg!(feature = "python3-dll-a")
+        && target.operating_system == OperatingSystem::Windows
+        && is_abi3();
 
-fn default_lib_name_unix(
-    version: PythonVersion,
-    implementation: PythonImplementation,
-    ld_version: Option<&str>,
-) -> String {
-    match implementation {
-        PythonImplementation::CPython => match ld_version {
-            Some(ld_version) => format!("python{}", ld_version),
-            None => {
-                if version > PythonVersion::PY37 {
-                    // PEP 3149 ABI version tags are finally gone
-                    format!("python{}.{}", version.major, version.minor)
-                } else {
-                    // Work around https://bugs.python.org/issue36707
-                    format!("python{}.{}m", version.major, version.minor)
-                }
-            }
-        },
-        PythonImplementation::PyPy => {
-            if version >= (PythonVersion { major: 3, minor: 9 }) {
-                match ld_version {
-                    Some(ld_version) => format!("pypy{}-c", ld_version),
-                    None => format!("pypy{}.{}-c", version.major, version.minor),
-                }
-            } else {
-                format!("pypy{}-c", version.major)
-            }
-        }
-        PythonImplementation::GraalPy => "python-native".to_string(),
-    }
+    is_linking_libpython_for_target(target) && !is_generating_libpython
 }
 
-/// Run a python script using the specified interpreter binary.
-fn run_python_script(interpreter: &Path, script: &str) -> Result<String> {
-    run_python_script_with_envs(interpreter, script, std::iter::empty::<(&str, &str)>())
+/// How the Python library is linked to the output binary.
+///
+/// See [`InterpreterConfig::link_model`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LinkModel {
+    /// libpython is linked dynamically, e.g. `libpython3.12.so`.
+    Shared,
+    /// libpython is linked statically into the output binary, e.g. `libpython3.12.a`.
+    Static,
+    /// libpython is not linked; the Python symbols are resolved when the extension module is
+    /// loaded by the interpreter.
+    Extension,
 }
 
-/// Run a python script using the specified interpreter binary with additional environment
-/// variables (e.g. PYTHONPATH) set.
-fn run_python_script_with_envs<I, K, V>(interpreter: &Path, script: &str, envs: I) -> Result<String>
-where
-    I: IntoIterator<Item = (K, V)>,
-    K: AsRef<OsStr>,
-    V: AsRef<OsStr>,
-{
-    let out = Command::new(interpreter)
-        .env("PYTHONIOENCODING", "utf-8")
-        .envs(envs)
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .and_then(|mut child| {
-            child
-                .stdin
-                .as_mut()
-                .expect("piped stdin")
-                .write_all(script.as_bytes())?;
-            child.wait_with_output()
-        });
+/// The kind of a native library to link, mirroring the `KIND` of `cargo:rustc-link-lib=KIND=NAME`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LinkLibKind {
+    /// A dynamic library, e.g. `libpython3.12.so` or `python312.dll`.
+    Dylib,
+    /// A static library, e.g. `libpython3.12.a`.
+    Static,
+    /// A macOS framework, e.g. `Python3.framework`.
+    Framework,
+}
 
-    match out {
-        Err(err) => bail!(
-            "failed to run the Python interpreter at {}: {}",
-            interpreter.display(),
-            err
-        ),
-        Ok(ok) if !ok.status.success() => bail!("Python script failed"),
-        Ok(ok) => Ok(String::from_utf8(ok.stdout)
-            .context("failed to parse Python script output as utf-8")?),
-    }
+/// A native library which should be linked for the current build mode.
+///
+/// See [`InterpreterConfig::link_libraries`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkLib {
+    /// The name of the library, without any `lib` prefix or file extension.
+    pub name: String,
+    /// How the library should be linked.
+    pub kind: LinkLibKind,
 }
 
-fn venv_interpreter(virtual_env: &OsStr, windows: bool) -> PathBuf {
-    if windows {
-        Path::new(virtual_env).join("Scripts").join("python.exe")
-    } else {
-        Path::new(virtual_env).join("bin").join("python")
+impl LinkLib {
+    /// Formats this library as a `cargo:rustc-link-lib` directive for the given target.
+    ///
+    /// On Windows the library is renamed to `pythonXY` so that `pyo3-ffi`'s `#[link]`
+    /// attributes resolve against it.
+    pub fn to_cargo_directive(&self, target: &Triple) -> String {
+        let kind = match self.kind {
+            // cargo's default is to let rustc decide, which matches how PyO3 has always
+            // emitted the shared library
+            LinkLibKind::Dylib => "",
+            LinkLibKind::Static => "static=",
+            LinkLibKind::Framework => "framework=",
+        };
+        let alias = if target.operating_system == OperatingSystem::Windows {
+            "pythonXY:"
+        } else {
+            ""
+        };
+        format!("cargo:rustc-link-lib={}{}{}", kind, alias, self.name)
     }
 }
 
-fn conda_env_interpreter(conda_prefix: &OsStr, windows: bool) -> PathBuf {
-    if windows {
-        Path::new(conda_prefix).join("python.exe")
-    } else {
-        Path::new(conda_prefix).join("bin").join("python")
-    }
+/// A way in which an [`InterpreterConfig`] doesn't fit the wheel it is built into.
+///
+/// See [`InterpreterConfig::validate_for_target`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TargetMismatch {
+    /// The wheel tag is not of the form `{python tag}-{abi tag}-{platform tag}`.
+    InvalidWheelTag(String),
+    /// The Python tag names another implementation or version.
+    PythonTag {
+        /// The Python tag for the configured implementation and version, e.g. `cp311`.
+        expected: String,
+        /// The Python tag of the wheel.
+        tag: String,
+    },
+    /// The ABI tag is `abi3` but the configuration doesn't target the stable ABI, or the other way
+    /// round.
+    Abi3 {
+        /// Whether the configuration targets the stable ABI.
+        abi3: bool,
+        /// The ABI tag of the wheel.
+        tag: String,
+    },
+    /// The ABI tag doesn't match the ABI of the configured interpreter.
+    AbiTag {
+        /// The ABI tag of the configured interpreter, e.g. `cp313t`.
+        expected: String,
+        /// The ABI tag of the wheel.
+        tag: String,
+    },
+    /// The configured pointer width differs from that of the compile target.
+    PointerWidth {
+        /// The [`pointer_width`](InterpreterConfig::pointer_width) of the configuration.
+        config: u32,
+        /// The pointer width of the compile target.
+        target: u32,
+    },
+    /// The platform tag is for another operating system, architecture or C library than the
+    /// compile target.
+    Platform {
+        /// The platform tag of the wheel.
+        tag: String,
+        /// The compile target triple.
+        target: String,
+    },
+    /// The platform tag is for another architecture than the configured interpreter.
+    InterpreterPlatform {
+        /// The [`platform_tag`](InterpreterConfig::platform_tag) of the configuration.
+        platform_tag: String,
+        /// The platform tag of the wheel.
+        tag: String,
+    },
 }
 
-fn get_env_interpreter() -> Option<PathBuf> {
-    match (env_var("VIRTUAL_ENV"), env_var("CONDA_PREFIX")) {
-        // Use cfg rather than CARGO_CFG_TARGET_OS because this affects where files are located on the
-        // build host
-        (Some(dir), None) => Some(venv_interpreter(&dir, cfg!(windows))),
-        (None, Some(dir)) => Some(conda_env_interpreter(&dir, cfg!(windows))),
-        (Some(_), Some(_)) => {
-            warn!(
-                "Both VIRTUAL_ENV and CONDA_PREFIX are set. PyO3 will ignore both of these for \
-                 locating the Python interpreter until you unset one of them."
-            );
-            None
+impl Display for TargetMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TargetMismatch::InvalidWheelTag(tag) => write!(
+                f,
+                "`{}` is not a wheel tag of the form `{{python tag}}-{{abi tag}}-{{platform tag}}`",
+                tag
+            ),
+            TargetMismatch::PythonTag { expected, tag } => write!(
+                f,
+                "Python tag `{}` does not match the configured Python, which has tag `{}`",
+                tag, expected
+            ),
+            TargetMismatch::Abi3 { abi3: true, tag } => write!(
+                f,
+                "ABI tag `{}` is not `abi3`, but the configuration targets the stable ABI",
+                tag
+            ),
+            TargetMismatch::Abi3 { abi3: false, tag } => write!(
+                f,
+                "ABI tag `{}` is for the stable ABI, but the configuration doesn't target it \
+                (enable one of PyO3's `abi3` features)",
+                tag
+            ),
+            TargetMismatch::AbiTag { expected, tag } => write!(
+                f,
+                "ABI tag `{}` does not match the configured Python, which has ABI tag `{}`",
+                tag, expected
+            ),
+            TargetMismatch::PointerWidth { config, target } => write!(
+                f,
+                "the configured Python has {}-bit pointers, but the compile target has {}-bit pointers",
+                config, target
+            ),
+            TargetMismatch::Platform { tag, target } => write!(
+                f,
+                "platform tag `{}` does not match the compile target `{}`",
+                tag, target
+            ),
+            TargetMismatch::InterpreterPlatform { platform_tag, tag } => write!(
+                f,
+                "platform tag `{}` does not match the platform `{}` of the configured Python",
+                tag, platform_tag
+            ),
         }
-        (None, None) => None,
     }
 }
 
-/// Attempts to locate a python interpreter.
-///
-/// Locations are checked in the order listed:
-///   1. If `PYO3_PYTHON` is set, this interpreter is used.
-///   2. If in a virtualenv, that environment's interpreter is used.
-///   3. `python`, if this is functional a Python 3.x interpreter
-///   4. `python3`, as above
-pub fn find_interpreter() -> Result<PathBuf> {
-    // Trigger rebuilds when `PYO3_ENVIRONMENT_SIGNATURE` env var value changes
-    // See https://github.com/PyO3/pyo3/issues/2724
-    println!("cargo:rerun-if-env-changed=PYO3_ENVIRONMENT_SIGNATURE");
-
-    if let Some(exe) = env_var("PYO3_PYTHON") {
-        Ok(exe.into())
-    } else if let Some(env_interpreter) = get_env_interpreter() {
-        Ok(env_interpreter)
-    } else {
-        println!("cargo:rerun-if-env-changed=PATH");
-        ["python", "python3"]
-            .iter()
-            .find(|bin| {
-                if let Ok(out) = Command::new(bin).arg("--version").output() {
-                    // begin with `Python 3.X.X :: additional info`
-                    out.stdout.starts_with(b"Python 3")
-                        || out.stderr.starts_with(b"Python 3")
-                        || out.stdout.starts_with(b"GraalPy 3")
-                } else {
-                    false
-                }
-            })
-            .map(PathBuf::from)
-            .ok_or_else(|| "no Python 3.x interpreter found".into())
-    }
-}
+impl std::error::Error for TargetMismatch {}
 
-/// Locates and extracts the build host Python interpreter configuration.
+/// Configuration needed by PyO3 to cross-compile for a target platform.
 ///
-/// Lowers the configured Python version to `abi3_version` if required.
-fn get_host_interpreter(abi3_version: Option<PythonVersion>) -> Result<InterpreterConfig> {
-    let interpreter_path = find_interpreter()?;
-
-    let mut interpreter_config = InterpreterConfig::from_interpreter(interpreter_path)?;
-    interpreter_config.fixup_for_abi3_version(abi3_version)?;
+/// Usually this is collected from the environment (i.e. `PYO3_CROSS_*` and `CARGO_CFG_TARGET_*`)
+/// when a cross-compilation configuration is detected.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CrossCompileConfig {
+    /// The directory containing the Python library to link against.
+    pub lib_dir: Option<PathBuf>,
 
-    Ok(interpreter_config)
-}
+    /// The version of the Python library to link against.
+    version: Option<PythonVersion>,
 
-/// Generates an interpreter config suitable for cross-compilation.
-///
-/// This must be called from PyO3's build script, because it relies on environment variables such as
-/// CARGO_CFG_TARGET_OS which aren't available at any other time.
-pub fn make_cross_compile_config() -> Result<Option<InterpreterConfig>> {
-    let interpreter_config = if let Some(cross_config) = cross_compiling_from_cargo_env()? {
-        let mut interpreter_config = load_cross_compile_config(cross_config)?;
-        interpreter_config.fixup_for_abi3_version(get_abi3_version())?;
-        Some(interpreter_config)
-    } else {
-        None
-    };
+    /// The target Python implementation hint (CPython, PyPy, GraalPy, ...)
+    implementation: Option<PythonImplementation>,
 
-    Ok(interpreter_config)
+    /// The compile target triple (e.g. aarch64-unknown-linux-gnu)
+    target: Triple,
 }
 
-/// Generates an interpreter config which will be hard-coded into the pyo3-build-config crate.
-/// Only used by `pyo3-build-config` build script.
-#[allow(dead_code, unused_mut)]
-pub fn make_interpreter_config() -> Result<InterpreterConfig> {
-    let host = Triple::host();
-    let abi3_version = get_abi3_version();
-
-    // See if we can safely skip the Python interpreter configuration detection.
-    // Unix "abi3" extension modules can usually be built without any interpreter.
-    let need_interpreter = abi3_version.is_none() || require_libdir_for_target(&host);
+impl CrossCompileConfig {
+    /// Creates a new cross compile config struct from PyO3 environment variables
+    /// and the build environment when cross compilation mode is detected.
+    ///
+    /// Returns `None` when not cross compiling.
+    fn try_from_env_vars_host_target(
+        env_vars: CrossCompileEnvVars,
+        host: &Triple,
+        target: &Triple,
+    ) -> Result<Option<Self>> {
+        if env_vars.any() || Self::is_cross_compiling_from_to(host, target) {
+            let lib_dir = env_vars.lib_dir_path()?;
+            let version = env_vars.parse_version()?;
+            let implementation = env_vars.parse_implementation()?;
+            let target = target.clone();
 
-    if have_python_interpreter() {
-        match get_host_interpreter(abi3_version) {
-            Ok(interpreter_config) => return Ok(interpreter_config),
-            // Bail if the interpreter configuration is required to build.
-            Err(e) if need_interpreter => return Err(e),
-            _ => {
-                // Fall back to the "abi3" defaults just as if `PYO3_NO_PYTHON`
-                // environment variable was set.
-                warn!("Compiling without a working Python interpreter.");
-            }
+            Ok(Some(CrossCompileConfig {
+                lib_dir,
+                version,
+                implementation,
+                target,
+            }))
+        } else {
+            Ok(None)
         }
-    } else {
-        ensure!(
-            abi3_version.is_some(),
-            "An abi3-py3* feature must be specified when compiling without a Python interpreter."
-        );
-    };
+    }
 
-    let mut interpreter_config = default_abi3_config(&host, abi3_version.unwrap());
+    /// Checks if compiling on `host` for `target` required "real" cross compilation.
+    ///
+    /// Returns `false` if the target Python interpreter can run on the host.
+    fn is_cross_compiling_from_to(host: &Triple, target: &Triple) -> bool {
+        // Not cross-compiling if arch-vendor-os is all the same
+        // e.g. x86_64-unknown-linux-musl on x86_64-unknown-linux-gnu host
+        //      x86_64-pc-windows-gnu on x86_64-pc-windows-msvc host
+        let mut compatible = host.architecture == target.architecture
+            && host.vendor == target.vendor
+            && host.operating_system == target.operating_system;
+
+        // Not cross-compiling to compile for 32-bit Python from windows 64-bit
+        compatible |= target.operating_system == OperatingSystem::Windows
+            && host.operating_system == OperatingSystem::Windows;
+
+        // Not cross-compiling to compile for x86-64 Python from macOS arm64 and vice versa
+        compatible |= target.operating_system == OperatingSystem::Darwin
+            && host.operating_system == OperatingSystem::Darwin;
+
+        !compatible
+    }
+
+    /// Converts `lib_dir` member field to an UTF-8 string.
+    ///
+    /// The conversion can not fail because `PYO3_CROSS_LIB_DIR` variable
+    /// is ensured contain a valid UTF-8 string.
+    fn lib_dir_string(&self) -> Option<String> {
+        self.lib_dir
+            .as_ref()
+            .map(|s| s.to_str().unwrap().to_owned())
+    }
+}
+
+/// PyO3-specific cross compile environment variable values
+struct CrossCompileEnvVars {
+    /// `PYO3_CROSS`
+    pyo3_cross: Option<OsString>,
+    /// `PYO3_CROSS_LIB_DIR`
+    pyo3_cross_lib_dir: Option<OsString>,
+    /// `PYO3_CROSS_PYTHON_VERSION`
+    pyo3_cross_python_version: Option<OsString>,
+    /// `PYO3_CROSS_PYTHON_IMPLEMENTATION`
+    pyo3_cross_python_implementation: Option<OsString>,
+}
+
+impl CrossCompileEnvVars {
+    /// Grabs the PyO3 cross-compile variables from the environment.
+    ///
+    /// Registers the build script to rerun if any of the variables changes.
+    fn from_env() -> Self {
+        CrossCompileEnvVars {
+            pyo3_cross: env_var("PYO3_CROSS"),
+            pyo3_cross_lib_dir: env_var("PYO3_CROSS_LIB_DIR"),
+            pyo3_cross_python_version: env_var("PYO3_CROSS_PYTHON_VERSION"),
+            pyo3_cross_python_implementation: env_var("PYO3_CROSS_PYTHON_IMPLEMENTATION"),
+        }
+    }
+
+    /// Checks if any of the variables is set.
+    fn any(&self) -> bool {
+        self.pyo3_cross.is_some()
+            || self.pyo3_cross_lib_dir.is_some()
+            || self.pyo3_cross_python_version.is_some()
+            || self.pyo3_cross_python_implementation.is_some()
+    }
+
+    /// Parses `PYO3_CROSS_PYTHON_VERSION` environment variable value
+    /// into `PythonVersion`.
+    fn parse_version(&self) -> Result<Option<PythonVersion>> {
+        let version = self
+            .pyo3_cross_python_version
+            .as_ref()
+            .map(|os_string| {
+                let utf8_str = os_string
+                    .to_str()
+                    .ok_or("PYO3_CROSS_PYTHON_VERSION is not valid a UTF-8 string")?;
+                utf8_str
+                    .parse()
+                    .context("failed to parse PYO3_CROSS_PYTHON_VERSION")
+            })
+            .transpose()?;
+
+        Ok(version)
+    }
+
+    /// Parses `PYO3_CROSS_PYTHON_IMPLEMENTATION` environment variable value
+    /// into `PythonImplementation`.
+    fn parse_implementation(&self) -> Result<Option<PythonImplementation>> {
+        let implementation = self
+            .pyo3_cross_python_implementation
+            .as_ref()
+            .map(|os_string| {
+                let utf8_str = os_string
+                    .to_str()
+                    .ok_or("PYO3_CROSS_PYTHON_IMPLEMENTATION is not valid a UTF-8 string")?;
+                utf8_str
+                    .parse()
+                    .context("failed to parse PYO3_CROSS_PYTHON_IMPLEMENTATION")
+            })
+            .transpose()?;
+
+        Ok(implementation)
+    }
+
+    /// Converts the stored `PYO3_CROSS_LIB_DIR` variable value (if any)
+    /// into a `PathBuf` instance.
+    ///
+    /// Ensures that the path is a valid UTF-8 string.
+    fn lib_dir_path(&self) -> Result<Option<PathBuf>> {
+        let lib_dir = self.pyo3_cross_lib_dir.as_ref().map(PathBuf::from);
+
+        if let Some(dir) = lib_dir.as_ref() {
+            ensure!(
+                dir.to_str().is_some(),
+                "PYO3_CROSS_LIB_DIR variable value is not a valid UTF-8 string"
+            );
+        }
+
+        Ok(lib_dir)
+    }
+}
+
+/// Detect whether we are cross compiling and return an assembled CrossCompileConfig if so.
+///
+/// This function relies on PyO3 cross-compiling environment variables:
+///
+/// * `PYO3_CROSS`: If present, forces PyO3 to configure as a cross-compilation.
+/// * `PYO3_CROSS_LIB_DIR`: If present, must be set to the directory containing
+///   the target's libpython DSO and the associated `_sysconfigdata*.py` file for
+///   Unix-like targets, or the Python DLL import libraries for the Windows target.
+/// * `PYO3_CROSS_PYTHON_VERSION`: Major and minor version (e.g. 3.9) of the target Python
+///   installation. This variable is only needed if PyO3 cannnot determine the version to target
+///   from `abi3-py3*` features, or if there are multiple versions of Python present in
+///   `PYO3_CROSS_LIB_DIR`.
+/// * `PYO3_CROSS_PYTHON_SYSCONFIGDATA`: If present, the path of the target's `_sysconfigdata*.py`
+///   file, which is then used instead of searching `PYO3_CROSS_LIB_DIR` for one.
+///
+/// See the [PyO3 User Guide](https://pyo3.rs/) for more info on cross-compiling.
+pub fn cross_compiling_from_to(
+    host: &Triple,
+    target: &Triple,
+) -> Result<Option<CrossCompileConfig>> {
+    let env_vars = CrossCompileEnvVars::from_env();
+    CrossCompileConfig::try_from_env_vars_host_target(env_vars, host, target)
+}
+
+/// Detect whether we are cross compiling from Cargo and `PYO3_CROSS_*` environment
+/// variables and return an assembled `CrossCompileConfig` if so.
+///
+/// This must be called from PyO3's build script, because it relies on environment
+/// variables such as `CARGO_CFG_TARGET_OS` which aren't available at any other time.
+pub fn cross_compiling_from_cargo_env() -> Result<Option<CrossCompileConfig>> {
+    let env_vars = CrossCompileEnvVars::from_env();
+    let host = Triple::host();
+    let target = target_triple_from_env();
+
+    CrossCompileConfig::try_from_env_vars_host_target(env_vars, &host, &target)
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum BuildFlag {
+    Py_DEBUG,
+    Py_REF_DEBUG,
+    Py_TRACE_REFS,
+    COUNT_ALLOCS,
+    Py_GIL_DISABLED,
+    Other(String),
+}
+
+impl Display for BuildFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildFlag::Other(flag) => write!(f, "{}", flag),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl FromStr for BuildFlag {
+    type Err = std::convert::Infallible;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Py_DEBUG" => Ok(BuildFlag::Py_DEBUG),
+            "Py_REF_DEBUG" => Ok(BuildFlag::Py_REF_DEBUG),
+            "Py_TRACE_REFS" => Ok(BuildFlag::Py_TRACE_REFS),
+            "COUNT_ALLOCS" => Ok(BuildFlag::COUNT_ALLOCS),
+            "Py_GIL_DISABLED" => Ok(BuildFlag::Py_GIL_DISABLED),
+            other => Ok(BuildFlag::Other(other.to_owned())),
+        }
+    }
+}
+
+/// A list of python interpreter compile-time preprocessor defines that
+/// we will pick up and pass to rustc via `--cfg=py_sys_config={varname}`;
+/// this allows using them conditional cfg attributes in the .rs files, so
+///
+/// ```rust
+/// #[cfg(py_sys_config="{varname}")]
+/// # struct Foo;
+/// ```
+///
+/// is the equivalent of `#ifdef {varname}` in C.
+///
+/// see Misc/SpecialBuilds.txt in the python source for what these mean.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Default)]
+pub struct BuildFlags(pub HashSet<BuildFlag>);
+
+impl BuildFlags {
+    const ALL: [BuildFlag; 5] = [
+        BuildFlag::Py_DEBUG,
+        BuildFlag::Py_REF_DEBUG,
+        BuildFlag::Py_TRACE_REFS,
+        BuildFlag::COUNT_ALLOCS,
+        BuildFlag::Py_GIL_DISABLED,
+    ];
+
+    pub fn new() -> Self {
+        BuildFlags(HashSet::new())
+    }
+
+    fn from_sysconfigdata(config_map: &Sysconfigdata) -> Self {
+        Self(
+            BuildFlags::ALL
+                .iter()
+                .filter(|flag| {
+                    config_map
+                        .get_value(flag.to_string())
+                        .map_or(false, |value| value == "1")
+                })
+                .cloned()
+                .collect(),
+        )
+        .fixup()
+    }
+
+    /// Examine python's compile flags to pass to cfg by launching
+    /// the interpreter and printing variables of interest from
+    /// sysconfig.get_config_vars.
+    fn from_interpreter(interpreter: impl AsRef<Path>) -> Result<Self> {
+        // sysconfig is missing all the flags on windows, so we can't actually
+        // query the interpreter directly for its build flags.
+        if cfg!(windows) {
+            return Ok(Self::new());
+        }
+
+        let mut script = String::from("import sysconfig\n");
+        script.push_str("config = sysconfig.get_config_vars()\n");
+
+        for k in &BuildFlags::ALL {
+            use std::fmt::Write;
+            writeln!(&mut script, "print(config.get('{}', '0'))", k).unwrap();
+        }
+
+        let stdout = run_python_script(interpreter.as_ref(), &script)?;
+        let split_stdout: Vec<&str> = stdout.trim_end().lines().collect();
+        ensure!(
+            split_stdout.len() == BuildFlags::ALL.len(),
+            "Python stdout len didn't return expected number of lines: {}",
+            split_stdout.len()
+        );
+        let flags = BuildFlags::ALL
+            .iter()
+            .zip(split_stdout)
+            .filter(|(_, flag_value)| *flag_value == "1")
+            .map(|(flag, _)| flag.clone())
+            .collect();
+
+        Ok(Self(flags).fixup())
+    }
+
+    /// Adds the flags of Windows debug builds, which can't be read from sysconfig on Windows.
+    ///
+    /// Debug builds are detected from `ext_suffix` (e.g. `_d.cp312-win_amd64.pyd`), and define
+    /// `Py_DEBUG` (and so `Py_REF_DEBUG`), but not `Py_TRACE_REFS`.
+    fn with_windows_debug_flags(mut self, ext_suffix: &str) -> Self {
+        if ext_suffix.starts_with("_d.") {
+            self.0.insert(BuildFlag::Py_DEBUG);
+        }
+        self.fixup()
+    }
+
+    fn fixup(mut self) -> Self {
+        if self.0.contains(&BuildFlag::Py_DEBUG) {
+            self.0.insert(BuildFlag::Py_REF_DEBUG);
+        }
+
+        self
+    }
+}
+
+impl Display for BuildFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut first = true;
+        for flag in &self.0 {
+            if first {
+                first = false;
+            } else {
+                write!(f, ",")?;
+            }
+            write!(f, "{}", flag)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for BuildFlags {
+    type Err = std::convert::Infallible;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut flags = HashSet::new();
+        for flag in value.split_terminator(',') {
+            flags.insert(flag.parse().unwrap());
+        }
+        Ok(BuildFlags(flags))
+    }
+}
+
+fn parse_script_output(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut i = line.splitn(2, ' ');
+            Some((i.next()?.into(), i.next()?.into()))
+        })
+        .collect()
+}
+
+/// Reads the names of the additional sysconfig variables requested in `PYO3_EXTRA_PROBE_VARS`.
+/// The file in `OUT_DIR` caching the output of the interpreter probe in `from_interpreter`.
+const PROBE_CACHE_FILE_NAME: &str = "pyo3-interpreter-probe.cache";
+
+/// Returns the key identifying a probe of `interpreter` with `script`: the canonical path and
+/// modification time of the executable, a hash of the script and the extra probed variables.
+///
+/// Returns `None` if the interpreter's metadata can't be read, in which case nothing is cached.
+fn probe_cache_key(
+    interpreter: &Path,
+    script: &str,
+    extra_probe_vars: &[String],
+) -> Option<String> {
+    use std::hash::{Hash, Hasher};
+
+    let path = env::var_os("PATH");
+    let executable = find_on_path(interpreter, path.as_deref()).or_else(|| {
+        // e.g. `python` is found as `python.exe` on Windows
+        let with_suffix = format!("{}{}", interpreter.display(), env::consts::EXE_SUFFIX);
+        find_on_path(Path::new(&with_suffix), path.as_deref())
+    })?;
+    let executable = fs::canonicalize(executable).ok()?;
+    let modified = fs::metadata(&executable)
+        .and_then(|metadata| metadata.modified())
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    script.hash(&mut hasher);
+    let key = format!(
+        "{}\t{}\t{:016x}\t{}",
+        executable.display(),
+        modified.as_nanos(),
+        hasher.finish(),
+        extra_probe_vars.join(",")
+    );
+    (!key.contains('\n')).then_some(key)
+}
+
+/// Reads the cached probe output from `path`, if it was cached under `key`.
+fn read_probe_cache(path: &Path, key: &str) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    let (cached_key, output) = contents.split_once('\n')?;
+    (cached_key == key).then(|| output.to_owned())
+}
+
+fn extra_probe_vars() -> Result<Vec<String>> {
+    match env_var("PYO3_EXTRA_PROBE_VARS") {
+        Some(value) => parse_extra_probe_vars(
+            value
+                .to_str()
+                .ok_or("PYO3_EXTRA_PROBE_VARS is not valid UTF-8")?,
+        ),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn parse_extra_probe_vars(value: &str) -> Result<Vec<String>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let mut chars = name.chars();
+            let first = chars.next().expect("empty names are filtered out");
+            ensure!(
+                (first.is_ascii_alphabetic() || first == '_')
+                    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+                "invalid sysconfig variable name `{}` in PYO3_EXTRA_PROBE_VARS",
+                name
+            );
+            Ok(name.to_owned())
+        })
+        .collect()
+}
+
+/// Looks up each of the requested sysconfig variables, warning about any which are missing.
+fn collect_extra_probe_vars(
+    names: &[String],
+    mut get: impl FnMut(&str) -> Option<String>,
+) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    for name in names {
+        match get(name) {
+            Some(value) => {
+                vars.insert(name.clone(), value);
+            }
+            None => warn!(
+                PYO3_W003,
+                "sysconfig variable `{}` requested in PYO3_EXTRA_PROBE_VARS is not set", name
+            ),
+        }
+    }
+    vars
+}
+
+/// Splits the `CONFIG_ARGS` sysconfig variable into the individual `configure` arguments.
+///
+/// The value is quoted for a POSIX shell, e.g. `'--prefix=/usr' 'CFLAGS=-O2 -g'`.
+fn parse_config_args(config_args: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = config_args.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                arg.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => arg.extend(chars.next()),
+                        c => arg.push(c),
+                    }
+                }
+            }
+            '\\' => current.get_or_insert_with(String::new).extend(chars.next()),
+            c if c.is_whitespace() => args.extend(current.take()),
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    args
+}
+
+fn has_config_arg(config_args: &[String], option: &str) -> bool {
+    config_args.iter().any(|arg| {
+        arg.strip_prefix(option)
+            .map_or(false, |rest| rest.is_empty() || rest.starts_with('='))
+    })
+}
+
+/// Find cross compilation information from sysconfigdata file
+///
+/// first find sysconfigdata file which follows the pattern [`_sysconfigdata_{abi}_{platform}_{multiarch}`][1]
+///
+/// [1]: https://github.com/python/cpython/blob/3.8/Lib/sysconfig.py#L348
+///
+/// Returns `None` when the target Python library directory is not set.
+fn cross_compile_from_sysconfigdata(
+    cross_compile_config: &CrossCompileConfig,
+) -> Result<Option<InterpreterConfig>> {
+    if let Some(path) = find_sysconfigdata(cross_compile_config)? {
+        let data = parse_sysconfigdata(&path)?;
+        check_sysconfigdata_target(&data, &cross_compile_config.target)
+            .with_context(|| format!("unsuitable sysconfigdata at {}", path.display()))?;
+        let config = InterpreterConfig::from_sysconfigdata(&data)?;
+
+        Ok(Some(config))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Checks that the `MULTIARCH` of sysconfigdata describes the target, for the mobile targets
+/// where a mismatched sysconfigdata (e.g. from the simulator SDK) is easy to pick up by mistake.
+///
+/// Sysconfigdata without `MULTIARCH` and other targets are always accepted.
+fn check_sysconfigdata_target(sysconfigdata: &Sysconfigdata, target: &Triple) -> Result<()> {
+    let android = matches!(
+        target.environment,
+        Environment::Android | Environment::Androideabi
+    );
+    let ios = target.operating_system == OperatingSystem::Ios;
+    let multiarch = match sysconfigdata.get_value("MULTIARCH") {
+        Some(multiarch) if android || ios => multiarch,
+        _ => return Ok(()),
+    };
+
+    // e.g. `aarch64-linux-android`, `arm-linux-androideabi`, `arm64-iphonesimulator`
+    let arch_matches = multiarch.split('-').next().map(normalize_arch)
+        == Some(normalize_arch(&target.architecture.to_string()));
+    let os_matches = if android {
+        multiarch.contains("android")
+    } else {
+        // x86_64 iOS is only ever the simulator.
+        let simulator =
+            target.environment == Environment::Sim || target.architecture == Architecture::X86_64;
+        multiarch.contains("iphone") && multiarch.contains("simulator") == simulator
+    };
+
+    ensure!(
+        arch_matches && os_matches,
+        "sysconfigdata is for `{}`, which does not match the target `{}`",
+        multiarch,
+        target
+    );
+    Ok(())
+}
+
+/// Normalizes the different spellings of an architecture used by target triples, sysconfig and
+/// wheel tags, e.g. `arm64` and `aarch64`.
+fn normalize_arch(arch: &str) -> &str {
+    match arch {
+        "arm64" => "aarch64",
+        "amd64" => "x86_64",
+        "powerpc64" => "ppc64",
+        "powerpc64le" => "ppc64le",
+        "riscv64gc" => "riscv64",
+        arch if arch.starts_with("arm") => "arm",
+        "i386" | "i586" | "i686" => "x86",
+        arch => arch,
+    }
+}
+
+/// The operating system, architecture and C library described by a wheel platform tag or by
+/// `sysconfig.get_platform()`.
+#[derive(Debug, PartialEq, Eq)]
+struct PythonPlatform<'a> {
+    /// `linux`, `macos` or `windows`.
+    os: &'static str,
+    /// The normalized architecture, or `universal2` for macOS fat binaries.
+    arch: &'a str,
+    /// The prefix of the target environment for `manylinux` (`gnu`) and `musllinux` (`musl`).
+    libc: Option<&'static str>,
+}
+
+impl<'a> PythonPlatform<'a> {
+    /// Parses a single wheel platform tag, e.g. `manylinux_2_28_aarch64` or `win_amd64`.
+    fn from_wheel_tag(tag: &'a str) -> Option<Self> {
+        // The architecture follows the platform version, e.g. `_2_28_` or `2014_`.
+        fn strip_version(rest: &str) -> &str {
+            rest.trim_start_matches(|c: char| c.is_ascii_digit() || c == '_')
+        }
+        let (os, libc, arch) = if let Some(rest) = tag.strip_prefix("manylinux") {
+            ("linux", Some("gnu"), strip_version(rest))
+        } else if let Some(rest) = tag.strip_prefix("musllinux") {
+            ("linux", Some("musl"), strip_version(rest))
+        } else if let Some(arch) = tag.strip_prefix("linux_") {
+            ("linux", None, arch)
+        } else if let Some(rest) = tag.strip_prefix("macosx") {
+            ("macos", None, strip_version(rest))
+        } else if tag == "win32" {
+            ("windows", None, "x86")
+        } else if let Some(arch) = tag.strip_prefix("win_") {
+            ("windows", None, arch)
+        } else {
+            return None;
+        };
+        Some(Self {
+            os,
+            arch: normalize_arch(arch),
+            libc,
+        })
+    }
+
+    /// Parses the result of `sysconfig.get_platform()`, e.g. `linux-x86_64` or
+    /// `macosx-11.0-arm64`.
+    fn from_sysconfig(platform: &'a str) -> Option<Self> {
+        if platform == "win32" {
+            return Some(Self {
+                os: "windows",
+                arch: "x86",
+                libc: None,
+            });
+        }
+        let (os, rest) = platform.split_once('-')?;
+        let os = match os {
+            "linux" => "linux",
+            "macosx" => "macos",
+            "win" => "windows",
+            _ => return None,
+        };
+        Some(Self {
+            os,
+            arch: normalize_arch(rest.rsplit('-').next()?),
+            libc: None,
+        })
+    }
+
+    fn arch_matches(&self, arch: &str) -> bool {
+        match (self.arch, arch) {
+            ("universal2", "x86_64" | "aarch64") | ("x86_64" | "aarch64", "universal2") => true,
+            (a, b) => a == b,
+        }
+    }
+
+    /// Returns `true` if this wheel platform accepts extensions built for the interpreter
+    /// platform `other`.
+    fn matches(&self, other: &PythonPlatform<'_>) -> bool {
+        self.os == other.os && self.arch_matches(other.arch)
+    }
+
+    /// Returns `true` if this wheel platform accepts extensions compiled for `target`.
+    fn matches_target(&self, target: &Triple) -> bool {
+        let os = match target.operating_system {
+            OperatingSystem::Linux => "linux",
+            OperatingSystem::Darwin => "macos",
+            OperatingSystem::Windows => "windows",
+            _ => return false,
+        };
+        let libc_matches = match self.libc {
+            Some(libc) => target.environment.to_string().starts_with(libc),
+            None => true,
+        };
+        let arch = target.architecture.to_string();
+        self.os == os && libc_matches && self.arch_matches(normalize_arch(&arch))
+    }
+}
+
+/// Generates "default" cross compilation information for the target.
+///
+/// This should work for most CPython extension modules when targeting
+/// Windows, macOS and Linux.
+///
+/// Must be called from a PyO3 crate build script.
+#[allow(unused_mut)]
+fn default_cross_compile(cross_compile_config: &CrossCompileConfig) -> Result<InterpreterConfig> {
+    let version = cross_compile_config
+        .version
+        .or_else(get_abi3_version)
+        .ok_or_else(||
+            format!(
+                "PYO3_CROSS_PYTHON_VERSION or an abi3-py3* feature must be specified \
+                when cross-compiling and PYO3_CROSS_LIB_DIR is not set.\n\
+                = help: see the PyO3 user guide for more information: https://pyo3.rs/v{}/building-and-distribution.html#cross-compiling",
+                env!("CARGO_PKG_VERSION")
+            )
+        )?;
+
+    let abi3 = is_abi3();
+    let implementation = cross_compile_config
+        .implementation
+        .unwrap_or(PythonImplementation::CPython);
+
+    let mut lib_name =
+        default_lib_name_for_target(version, implementation, abi3, &cross_compile_config.target);
+    // Emscripten Python (e.g. Pyodide) links libpython statically into the main module.
+    let mut shared = cross_compile_config.target.operating_system != OperatingSystem::Emscripten;
+
+    let mut lib_dir = cross_compile_config.lib_dir_string();
+
+    // Without sysconfigdata, look at the libraries in the directory to find the exact name.
+    let search_lib_dir = cross_compile_config
+        .lib_dir
+        .as_deref()
+        .filter(|dir| dir.is_dir())
+        .filter(|_| {
+            !abi3 && cross_compile_config.target.operating_system != OperatingSystem::Windows
+        });
+    if let Some(dir) = search_lib_dir {
+        if let Some(found) = find_libpython_in_dir(dir, version, implementation, None)? {
+            lib_name = Some(found.lib_name);
+            shared = found.shared;
+        }
+    }
+
+    // Auto generate python3.dll import libraries for Windows targets.
+    #[cfg(feature = "python3-dll-a")]
+    if lib_dir.is_none() {
+        let py_version = if abi3 { None } else { Some(version) };
+        lib_dir = self::import_lib::generate_import_lib(
+            &cross_compile_config.target,
+            cross_compile_config
+                .implementation
+                .unwrap_or(PythonImplementation::CPython),
+            py_version,
+        )?;
+    }
+
+    // Without sysconfigdata the best guess for the pointer width is the target triple.
+    let pointer_width = cross_compile_config
+        .target
+        .pointer_width()
+        .ok()
+        .map(|width| u32::from(width.bits()));
+
+    Ok(InterpreterConfig {
+        implementation,
+        version,
+        shared,
+        abi3,
+        lib_name,
+        lib_dir,
+        executable: None,
+        platform_tag: None,
+        python_framework_prefix: None,
+        pointer_width,
+        pointer_width_inferred: pointer_width.is_some(),
+        build_flags: BuildFlags::default(),
+        suppress_build_script_link_lines: false,
+        extra_build_script_lines: vec![],
+        extra_link_args: vec![],
+        extra_link_args_by_os: BTreeMap::new(),
+        extra_link_search_paths: vec![],
+        config_args: vec![],
+        sysconfig_vars: BTreeMap::new(),
+    })
+}
+
+/// A Python library found in a library directory.
+#[derive(Debug, PartialEq, Eq)]
+struct FoundLibpython {
+    /// Name to link, e.g. `python3.11d`.
+    lib_name: String,
+    shared: bool,
+}
+
+/// Parses a library file name such as `libpython3.11.so.1.0` (or `libpypy3.10-c.so` for PyPy)
+/// into the name to link and whether it is a shared library, if it is a Python library for
+/// `version` of `implementation`.
+fn parse_libpython_file_name(
+    file_name: &str,
+    version: PythonVersion,
+    implementation: PythonImplementation,
+) -> Option<FoundLibpython> {
+    let name = file_name.strip_prefix("lib")?;
+    let (lib_name, shared) = if let Some(lib_name) = name.strip_suffix(".dll.a") {
+        (lib_name, true)
+    } else if let Some(lib_name) = name.strip_suffix(".a") {
+        (lib_name, false)
+    } else if let Some(lib_name) = name.strip_suffix(".dylib") {
+        (lib_name, true)
+    } else if let Some(end) = name.find(".so") {
+        let rest = &name[end + ".so".len()..];
+        if !(rest.is_empty() || rest.starts_with('.')) {
+            return None;
+        }
+        (&name[..end], true)
+    } else {
+        return None;
+    };
+    let abiflags = match implementation {
+        PythonImplementation::CPython => lib_name.strip_prefix(&format!("python{}", version))?,
+        PythonImplementation::PyPy => {
+            lib_name.strip_prefix(&default_lib_name_unix(version, implementation, None))?
+        }
+        // GraalPy's library is not versioned, so can't be told apart from other versions.
+        PythonImplementation::GraalPy => return None,
+    };
+    // Only ABI flags such as `d` or `m` may follow the version, and only for CPython.
+    if !abiflags.chars().all(|c| c.is_ascii_lowercase())
+        || (implementation.is_pypy() && !abiflags.is_empty())
+    {
+        return None;
+    }
+    Some(FoundLibpython {
+        lib_name: lib_name.to_owned(),
+        shared,
+    })
+}
+
+/// Searches `dir` for the Python library for `version` of `implementation`.
+///
+/// When `shared` is given, only libraries of that kind are considered. Otherwise a shared library
+/// is preferred if both kinds are present. Fails listing the candidates if more than one
+/// library name matches, e.g. both `libpython3.11.so` and `libpython3.11d.so`.
+fn find_libpython_in_dir(
+    dir: &Path,
+    version: PythonVersion,
+    implementation: PythonImplementation,
+    shared: Option<bool>,
+) -> Result<Option<FoundLibpython>> {
+    let mut candidates = Vec::new();
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read library directory {}", dir.display()))?
+    {
+        let entry = entry.context("failed to read library directory entry")?;
+        if let Some(found) = entry
+            .file_name()
+            .to_str()
+            .and_then(|file_name| parse_libpython_file_name(file_name, version, implementation))
+        {
+            candidates.push((entry.file_name(), found));
+        }
+    }
+    candidates.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let shared = shared.unwrap_or_else(|| candidates.iter().any(|(_, found)| found.shared));
+    candidates.retain(|(_, found)| found.shared == shared);
+
+    let mut lib_names: Vec<&str> = candidates
+        .iter()
+        .map(|(_, found)| found.lib_name.as_str())
+        .collect();
+    lib_names.dedup();
+    ensure!(
+        lib_names.len() <= 1,
+        "found multiple Python {} libraries in {}: {}\n\
+        = help: set PYO3_CROSS_LIB_DIR to a directory containing only the library to link",
+        version,
+        dir.display(),
+        candidates
+            .iter()
+            .map(|(file_name, _)| file_name.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    Ok(candidates.into_iter().next().map(|(_, found)| found))
+}
+
+/// Generates "default" interpreter configuration when compiling "abi3" extensions
+/// without a working Python interpreter.
+///
+/// `version` specifies the minimum supported Stable ABI CPython version.
+///
+/// This should work for most CPython extension modules when compiling on
+/// Windows, macOS and Linux.
+///
+/// Must be called from a PyO3 crate build script.
+fn default_abi3_config(host: &Triple, version: PythonVersion) -> InterpreterConfig {
+    // FIXME: PyPy & GraalPy do not support the Stable ABI.
+    let implementation = PythonImplementation::CPython;
+    let abi3 = true;
+
+    let lib_name = if host.operating_system == OperatingSystem::Windows {
+        Some(default_lib_name_windows(
+            version,
+            implementation,
+            abi3,
+            false,
+            false,
+            false,
+        ))
+    } else {
+        None
+    };
+
+    InterpreterConfig {
+        implementation,
+        version,
+        shared: true,
+        abi3,
+        lib_name,
+        lib_dir: None,
+        executable: None,
+        platform_tag: None,
+        python_framework_prefix: None,
+        pointer_width: None,
+        pointer_width_inferred: false,
+        build_flags: BuildFlags::default(),
+        suppress_build_script_link_lines: false,
+        extra_build_script_lines: vec![],
+        extra_link_args: vec![],
+        extra_link_args_by_os: BTreeMap::new(),
+        extra_link_search_paths: vec![],
+        config_args: vec![],
+        sysconfig_vars: BTreeMap::new(),
+    }
+}
+
+/// Detects the cross compilation target interpreter configuration from all
+/// available sources (PyO3 environment variables, Python sysconfigdata, etc.).
+///
+/// Returns the "default" target interpreter configuration for Windows, for Emscripten
+/// when the target Python version is known, and when no target Python interpreter is found.
+///
+/// Must be called from a PyO3 crate build script.
+fn load_cross_compile_config(
+    cross_compile_config: CrossCompileConfig,
+) -> Result<InterpreterConfig> {
+    let windows = cross_compile_config.target.operating_system == OperatingSystem::Windows;
+    // Emscripten extension modules are side modules which never link libpython, so once the
+    // version is known there is nothing to gain from running the host interpreter over the
+    // target's sysconfigdata.
+    let emscripten_version_known = cross_compile_config.target.operating_system
+        == OperatingSystem::Emscripten
+        && (cross_compile_config.version.is_some() || get_abi3_version().is_some());
+
+    let config = if windows || emscripten_version_known || !have_python_interpreter() {
+        // Load the defaults for Windows even when `PYO3_CROSS_LIB_DIR` is set
+        // since it has no sysconfigdata files in it.
+        // Also, do not try to look for sysconfigdata when `PYO3_NO_PYTHON` variable is set.
+        default_cross_compile(&cross_compile_config)?
+    } else if let Some(config) = cross_compile_from_sysconfigdata(&cross_compile_config)? {
+        // Try to find and parse sysconfigdata files on other targets.
+        config
+    } else {
+        // Fall back to the defaults when nothing else can be done.
+        default_cross_compile(&cross_compile_config)?
+    };
+
+    if config.lib_name.is_some()
+        && config.lib_dir.is_none()
+        && is_linking_libpython_for_target(&cross_compile_config.target)
+    {
+        warn!(PYO3_W004, "{}", missing_cross_lib_dir_warning(windows));
+    }
+
+    Ok(config)
+}
+
+/// Warning for cross compiles which link to libpython without knowing where it is.
+fn missing_cross_lib_dir_warning(windows: bool) -> String {
+    let mut warning = "The output binary will link to libpython, \
+        but PYO3_CROSS_LIB_DIR environment variable is not set. \
+        Ensure that the target Python library directory is \
+        in the rustc native library search path."
+        .to_owned();
+    // Without the feature, `default_cross_compile` can't generate the import library.
+    if windows && cfg!(not(feature = "python3-dll-a")) {
+        warning.push_str(
+            "\n= help: enable the `generate-import-lib` feature of `pyo3` to generate the \
+             Python import library for Windows targets automatically",
+        );
+    }
+    warning
+}
+
+// Link against python3.lib for the stable ABI on Windows.
+// See https://www.python.org/dev/peps/pep-0384/#linkage
+//
+// This contains only the limited ABI symbols.
+const WINDOWS_ABI3_LIB_NAME: &str = "python3";
+
+fn default_lib_name_for_target(
+    version: PythonVersion,
+    implementation: PythonImplementation,
+    abi3: bool,
+    target: &Triple,
+) -> Option<String> {
+    if target.operating_system == OperatingSystem::Windows {
+        Some(default_lib_name_windows(
+            version,
+            implementation,
+            abi3,
+            false,
+            false,
+            false,
+        ))
+    } else if is_linking_libpython_for_target(target) {
+        Some(default_lib_name_unix(version, implementation, None))
+    } else {
+        None
+    }
+}
+
+fn default_lib_name_windows(
+    version: PythonVersion,
+    implementation: PythonImplementation,
+    abi3: bool,
+    mingw: bool,
+    debug: bool,
+    gil_disabled: bool,
+) -> String {
+    // Free-threaded builds use e.g. python313t.dll, and their limited API is not supported.
+    let gil_disabled = if gil_disabled { "t" } else { "" };
+    if debug {
+        // CPython bug: linking against python3_d.dll raises error
+        // https://github.com/python/cpython/issues/101614
+        format!("python{}{}{}_d", version.major, version.minor, gil_disabled)
+    } else if abi3 && !(implementation.is_pypy() || implementation.is_graalpy()) {
+        WINDOWS_ABI3_LIB_NAME.to_owned()
+    } else if mingw {
+        // https://packages.msys2.org/base/mingw-w64-python
+        format!("python{}.{}{}", version.major, version.minor, gil_disabled)
+    } else {
+        format!("python{}{}{}", version.major, version.minor, gil_disabled)
+    }
+}
+
+fn default_lib_name_unix(
+    version: PythonVersion,
+    implementation: PythonImplementation,
+    ld_version: Option<&str>,
+) -> String {
+    match implementation {
+        PythonImplementation::CPython => match ld_version {
+            Some(ld_version) => format!("python{}", ld_version),
+            None => {
+                if version > PythonVersion::PY37 {
+                    // PEP 3149 ABI version tags are finally gone
+                    format!("python{}.{}", version.major, version.minor)
+                } else {
+                    // Work around https://bugs.python.org/issue36707
+                    format!("python{}.{}m", version.major, version.minor)
+                }
+            }
+        },
+        PythonImplementation::PyPy => {
+            if version >= (PythonVersion { major: 3, minor: 9 }) {
+                match ld_version {
+                    Some(ld_version) => format!("pypy{}-c", ld_version),
+                    None => format!("pypy{}.{}-c", version.major, version.minor),
+                }
+            } else {
+                format!("pypy{}-c", version.major)
+            }
+        }
+        PythonImplementation::GraalPy => "python-native".to_string(),
+    }
+}
+
+/// Run a python script using the specified interpreter binary.
+fn run_python_script(interpreter: &Path, script: &str) -> Result<String> {
+    run_python_script_with_envs(interpreter, script, std::iter::empty::<(&str, &str)>())
+}
+
+/// Run a python script using the specified interpreter binary with additional environment
+/// variables (e.g. PYTHONPATH) set.
+fn run_python_script_with_envs<I, K, V>(interpreter: &Path, script: &str, envs: I) -> Result<String>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<OsStr>,
+    V: AsRef<OsStr>,
+{
+    let out = Command::new(interpreter)
+        .env("PYTHONIOENCODING", "utf-8")
+        .envs(envs)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .and_then(|mut child| {
+            child
+                .stdin
+                .as_mut()
+                .expect("piped stdin")
+                .write_all(script.as_bytes())?;
+            child.wait_with_output()
+        });
+
+    match out {
+        Err(err) => bail!(
+            "failed to run the Python interpreter at {}: {}",
+            interpreter.display(),
+            err
+        ),
+        Ok(ok) if !ok.status.success() => bail!("Python script failed"),
+        Ok(ok) => Ok(String::from_utf8(ok.stdout)
+            .context("failed to parse Python script output as utf-8")?),
+    }
+}
+
+/// Returns the `lib_dir` and `python_framework_prefix` to use for a macOS framework build of
+/// Python installed at `base_prefix`, given the values reported by its sysconfig.
+///
+/// For framework builds (the python.org installers including their universal2 builds, Homebrew
+/// and Xcode) `base_prefix` is the version directory inside the framework, e.g.
+/// `/Library/Frameworks/Python.framework/Versions/3.12`. The Python of the Xcode command line
+/// tools reports the paths inside `Xcode.app` it was built with, which don't exist when it is
+/// installed elsewhere, so reported directories which don't exist are derived from `base_prefix`
+/// instead. Directories inside a Homebrew keg are replaced by the `opt` link of the formula, as
+/// the keg is removed when the formula is upgraded.
+fn framework_dirs(
+    base_prefix: &Path,
+    lib_dir: Option<String>,
+    python_framework_prefix: String,
+) -> (Option<String>, Option<String>) {
+    let mut ancestors = base_prefix.ancestors().skip(1);
+    let is_framework = ancestors.next().and_then(Path::file_name) == Some(OsStr::new("Versions"))
+        && ancestors.next().and_then(Path::extension) == Some(OsStr::new("framework"));
+    let derived_prefix = ancestors.next().filter(|_| is_framework);
+
+    let resolve = |reported: Option<String>, derived: Option<PathBuf>| {
+        let path = reported
+            .as_ref()
+            .map(PathBuf::from)
+            .filter(|path| path.is_dir())
+            .or_else(|| derived.filter(|path| path.is_dir()));
+        match path {
+            Some(path) => Some(
+                homebrew_opt_path(&path)
+                    .filter(|opt_path| opt_path.is_dir())
+                    .unwrap_or(path)
+                    .display()
+                    .to_string(),
+            ),
+            None => reported,
+        }
+    };
+    (
+        resolve(lib_dir, derived_prefix.map(|_| base_prefix.join("lib"))),
+        resolve(
+            Some(python_framework_prefix),
+            derived_prefix.map(Path::to_path_buf),
+        ),
+    )
+}
+
+/// Maps a path inside a Homebrew keg, `<prefix>/Cellar/<formula>/<version>/...`, to the same path
+/// through the `<prefix>/opt/<formula>` link which Homebrew keeps pointing at the installed keg.
+fn homebrew_opt_path(path: &Path) -> Option<PathBuf> {
+    let components: Vec<_> = path.components().collect();
+    let cellar = components
+        .iter()
+        .position(|component| component.as_os_str() == "Cellar")?;
+    if components.len() < cellar + 3 {
+        return None;
+    }
+    let mut opt_path: PathBuf = components[..cellar].iter().collect();
+    opt_path.push("opt");
+    opt_path.push(components[cellar + 1]);
+    opt_path.extend(&components[cellar + 3..]);
+    Some(opt_path)
+}
+
+/// Locates and extracts the build host Python interpreter configuration.
+///
+/// Lowers the configured Python version to `abi3_version` if required.
+fn get_host_interpreter(abi3_version: Option<PythonVersion>) -> Result<InterpreterConfig> {
+    let mut interpreter_path = find_interpreter()?;
+    if let Some(pyenv_root) = pyenv_root() {
+        let shim = find_on_path(&interpreter_path, env::var_os("PATH").as_deref());
+        if let Some(shim) = shim {
+            match resolve_pyenv_shim(&shim, &pyenv_root, |name| pyenv_which(&pyenv_root, name)) {
+                Ok(Some(resolved)) => interpreter_path = resolved,
+                Ok(None) => {}
+                // Running the shim will most likely fail with a more detailed error.
+                Err(e) => warn!("{}", e.report().to_string().trim_end()),
+            }
+        }
+    }
+
+    let mut interpreter_config = InterpreterConfig::from_interpreter(interpreter_path)?;
+    if cfg!(windows) {
+        fixup_for_windows_store_python(
+            &mut interpreter_config,
+            allow_windows_store_python(),
+            query_python_core_install_path,
+        )?;
+    }
+    if let Some(python_home) = env_var("PYTHONHOME") {
+        if let Some(note) = fixup_lib_dir_for_python_home(&mut interpreter_config, &python_home) {
+            warn!("{}", note);
+        }
+    }
+    interpreter_config.fixup_for_abi3_version(abi3_version)?;
+
+    Ok(interpreter_config)
+}
+
+/// Resolves a missing or relative `lib_dir` against `PYTHONHOME`, as used by embedded and frozen
+/// Python distributions whose sysconfig data doesn't point at the real installation.
+///
+/// Returns a note describing the change if `lib_dir` was updated.
+fn fixup_lib_dir_for_python_home(
+    interpreter_config: &mut InterpreterConfig,
+    python_home: &OsStr,
+) -> Option<String> {
+    if python_home.is_empty() {
+        return None;
+    }
+    let python_home = Path::new(python_home);
+    let lib_dir = match &interpreter_config.lib_dir {
+        Some(lib_dir) if Path::new(lib_dir).is_absolute() => return None,
+        Some(lib_dir) => python_home.join(lib_dir),
+        None if cfg!(windows) => python_home.join("libs"),
+        None => python_home.join("lib"),
+    };
+    if !lib_dir.is_dir() {
+        return None;
+    }
+    let lib_dir = lib_dir.to_str()?.to_owned();
+    let note = format!(
+        "using PYTHONHOME to resolve the Python library directory to {}",
+        lib_dir
+    );
+    interpreter_config.lib_dir = Some(lib_dir);
+    Some(note)
+}
+
+/// Describes a difference between the Python version being cross compiled for and the version of
+/// the build host's interpreter.
+///
+/// This is usually intentional, so it is only reported for information.
+#[cfg_attr(not(feature = "resolve-config"), allow(dead_code))]
+pub(crate) fn cross_compile_version_note(
+    cross_version: PythonVersion,
+    host_version: PythonVersion,
+) -> Option<String> {
+    if cross_version == host_version {
+        return None;
+    }
+    Some(format!(
+        "cross compiling for Python {}, but the Python interpreter on the build host is version {}",
+        cross_version, host_version
+    ))
+}
+
+/// Generates an interpreter config suitable for cross-compilation.
+///
+/// This must be called from PyO3's build script, because it relies on environment variables such as
+/// CARGO_CFG_TARGET_OS which aren't available at any other time.
+pub fn make_cross_compile_config() -> Result<Option<InterpreterConfig>> {
+    // Report an ambiguous minimum version rather than silently picking one.
+    validate_abi3_version()?;
+    let interpreter_config = if let Some(cross_config) = cross_compiling_from_cargo_env()? {
+        let mut interpreter_config = load_cross_compile_config(cross_config)?;
+        interpreter_config.fixup_for_abi3_version(get_abi3_version())?;
+        Some(interpreter_config)
+    } else {
+        None
+    };
+
+    Ok(interpreter_config)
+}
+
+/// Generates an interpreter config which will be hard-coded into the pyo3-build-config crate.
+/// Only used by `pyo3-build-config` build script.
+#[allow(dead_code, unused_mut)]
+pub fn make_interpreter_config() -> Result<InterpreterConfig> {
+    let host = Triple::host();
+    // Report an ambiguous minimum version rather than silently picking one.
+    validate_abi3_version()?;
+    let abi3_version = get_abi3_version();
+
+    // See if we can safely skip the Python interpreter configuration detection.
+    // Unix "abi3" extension modules can usually be built without any interpreter.
+    let need_interpreter = abi3_version.is_none() || require_libdir_for_target(&host);
+
+    if have_python_interpreter() {
+        match get_host_interpreter(abi3_version) {
+            Ok(interpreter_config) => {
+                check_support(&interpreter_config)?;
+                return Ok(interpreter_config);
+            }
+            // Bail if the interpreter configuration is required to build.
+            Err(e) if need_interpreter => return Err(e),
+            _ => {
+                // Fall back to the "abi3" defaults just as if `PYO3_NO_PYTHON`
+                // environment variable was set.
+                warn!(PYO3_W006, "Compiling without a working Python interpreter.");
+            }
+        }
+    } else {
+        ensure!(
+            abi3_version.is_some(),
+            "An abi3-py3* feature must be specified when compiling without a Python interpreter."
+        );
+    };
+
+    let mut interpreter_config = default_abi3_config(&host, abi3_version.unwrap());
+
+    // Auto generate python3.dll import libraries for Windows targets.
+    #[cfg(feature = "python3-dll-a")]
+    {
+        let py_version = if interpreter_config.abi3 {
+            None
+        } else {
+            Some(interpreter_config.version)
+        };
+        interpreter_config.lib_dir = self::import_lib::generate_import_lib(
+            &host,
+            interpreter_config.implementation,
+            py_version,
+        )?;
+    }
+
+    Ok(interpreter_config)
+}
+
+fn escape(bytes: &[u8]) -> String {
+    let mut escaped = String::with_capacity(2 * bytes.len());
+
+    for byte in bytes {
+        const LUT: &[u8; 16] = b"0123456789abcdef";
+
+        escaped.push(LUT[(byte >> 4) as usize] as char);
+        escaped.push(LUT[(byte & 0x0F) as usize] as char);
+    }
+
+    escaped
+}
+
+fn unescape(escaped: &str) -> Vec<u8> {
+    assert!(escaped.len() % 2 == 0, "invalid hex encoding");
+
+    let mut bytes = Vec::with_capacity(escaped.len() / 2);
+
+    for chunk in escaped.as_bytes().chunks_exact(2) {
+        fn unhex(hex: u8) -> u8 {
+            match hex {
+                b'a'..=b'f' => hex - b'a' + 10,
+                b'0'..=b'9' => hex - b'0',
+                _ => panic!("invalid hex encoding"),
+            }
+        }
+
+        bytes.push(unhex(chunk[0]) << 4 | unhex(chunk[1]));
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use target_lexicon::triple;
+
+    use super::*;
+
+    #[test]
+    fn test_config_file_roundtrip() {
+        let config = InterpreterConfig {
+            abi3: true,
+            build_flags: BuildFlags::default(),
+            pointer_width: Some(32),
+            pointer_width_inferred: false,
+            executable: Some("executable".into()),
+            platform_tag: Some("linux-x86_64".into()),
+            python_framework_prefix: Some("/Library/Frameworks".into()),
+            implementation: PythonImplementation::CPython,
+            lib_name: Some("lib_name".into()),
+            lib_dir: Some("lib_dir".into()),
+            shared: true,
+            version: MINIMUM_SUPPORTED_VERSION,
+            suppress_build_script_link_lines: true,
+            extra_build_script_lines: vec!["cargo:test1".to_string(), "cargo:test2".to_string()],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+        let mut buf: Vec<u8> = Vec::new();
+        config.to_writer(&mut buf).unwrap();
+
+        assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+
+        // And some different options, for variety
+
+        let config = InterpreterConfig {
+            abi3: false,
+            build_flags: {
+                let mut flags = HashSet::new();
+                flags.insert(BuildFlag::Py_DEBUG);
+                flags.insert(BuildFlag::Other(String::from("Py_SOME_FLAG")));
+                BuildFlags(flags)
+            },
+            pointer_width: None,
+            pointer_width_inferred: false,
+            executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
+            implementation: PythonImplementation::PyPy,
+            lib_dir: None,
+            lib_name: None,
+            shared: true,
+            version: PythonVersion {
+                major: 3,
+                minor: 10,
+            },
+            suppress_build_script_link_lines: false,
+            extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+        let mut buf: Vec<u8> = Vec::new();
+        config.to_writer(&mut buf).unwrap();
+
+        assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+    }
+
+    #[test]
+    fn test_config_file_roundtrip_with_escaping() {
+        let config = InterpreterConfig {
+            abi3: true,
+            build_flags: BuildFlags::default(),
+            pointer_width: Some(32),
+            pointer_width_inferred: false,
+            executable: Some("executable".into()),
+            platform_tag: None,
+            python_framework_prefix: None,
+            implementation: PythonImplementation::CPython,
+            lib_name: Some("lib_name".into()),
+            lib_dir: Some("lib_dir\\n".into()),
+            shared: true,
+            version: MINIMUM_SUPPORTED_VERSION,
+            suppress_build_script_link_lines: true,
+            extra_build_script_lines: vec!["cargo:test1".to_string(), "cargo:test2".to_string()],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+        let mut buf: Vec<u8> = Vec::new();
+        config.to_writer(&mut buf).unwrap();
+
+        let buf = unescape(&escape(&buf));
+
+        assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+    }
+
+    #[test]
+    fn test_config_file_roundtrip_all_fields() {
+        let config = InterpreterConfig {
+            implementation: PythonImplementation::PyPy,
+            version: PythonVersion::PY310,
+            shared: false,
+            abi3: false,
+            lib_name: Some("pypy3.10-c".into()),
+            lib_dir: Some("/opt/pypy/lib".into()),
+            executable: Some("/opt/pypy/bin/pypy3".into()),
+            platform_tag: Some("linux-aarch64".into()),
+            python_framework_prefix: Some("/Library/Frameworks".into()),
+            pointer_width: Some(64),
+            pointer_width_inferred: true,
+            build_flags: BuildFlags(vec![BuildFlag::Py_DEBUG].into_iter().collect()),
+            suppress_build_script_link_lines: true,
+            extra_build_script_lines: vec!["cargo:rustc-cfg=vendored".into()],
+            extra_link_args: vec!["-Wl,--as-needed".into()],
+            extra_link_args_by_os: [(
+                "macos".to_owned(),
+                vec!["-framework".into(), "CoreFoundation".into()],
+            )]
+            .into_iter()
+            .collect(),
+            extra_link_search_paths: vec!["/opt/deps/lib".into()],
+            config_args: vec!["--enable-optimizations".into(), "CFLAGS=-O2 -g".into()],
+            sysconfig_vars: [("MULTIARCH".to_owned(), "aarch64-linux-gnu".to_owned())]
+                .into_iter()
+                .collect(),
+        };
+        let mut buf: Vec<u8> = Vec::new();
+        config.to_writer(&mut buf).unwrap();
+        assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+
+        // Probe once, adjust, and write a config file for a later build.
+        let dir = env::temp_dir().join(format!("pyo3-config-to-path-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pyo3-config.txt");
+        let mut modified = config.clone();
+        modified.lib_dir = Some("/sysroot/opt/pypy/lib".into());
+        modified.to_path(&path).unwrap();
+        assert_eq!(modified, InterpreterConfig::from_path(&path).unwrap());
+
+        assert_eq!(
+            config
+                .to_path(dir.join("pyo3.toml"))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "cannot write PyO3 config file {}: only the line-based format can be written, \
+                so the path must not have a `.toml` extension",
+                dir.join("pyo3.toml").display()
+            )
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Values which would not read back the same are rejected.
+        let multiline = InterpreterConfig {
+            extra_build_script_lines: vec!["cargo:a\ncargo:b".into()],
+            ..config
+        };
+        assert_eq!(
+            multiline.to_writer(Vec::new()).unwrap_err().to_string(),
+            "cannot write extra_build_script_line `cargo:a\\ncargo:b` to config: it contains a \
+            newline"
+        );
+    }
+
+    #[test]
+    fn test_config_file_platform_tag() {
+        for platform_tag in [
+            "linux-x86_64",
+            "linux-aarch64",
+            "macosx-11.0-arm64",
+            "macosx-10.9-universal2",
+            "win-amd64",
+            "mingw_x86_64",
+        ] {
+            let config = InterpreterConfig::from_reader(
+                format!("version=3.9\nplatform_tag={}", platform_tag).as_bytes(),
+            )
+            .unwrap();
+            assert_eq!(config.platform_tag.as_deref(), Some(platform_tag));
+
+            let mut buf: Vec<u8> = Vec::new();
+            config.to_writer(&mut buf).unwrap();
+            assert!(std::str::from_utf8(&buf)
+                .unwrap()
+                .contains(&format!("platform_tag={}\n", platform_tag)));
+            assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_config_file_include() {
+        let dir = env::temp_dir().join(format!("pyo3-config-include-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("targets")).unwrap();
+        std::fs::write(
+            dir.join("base.txt"),
+            "version=3.8\nshared=false\nlib_dir=/base/lib\nextra_build_script_line=base\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("targets").join("middle.txt"),
+            "include=../base.txt\nversion=3.9\nextra_build_script_line=middle\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("targets").join("leaf.txt"),
+            "include=middle.txt\nlib_dir=/leaf/lib\n",
+        )
+        .unwrap();
 
-    // Auto generate python3.dll import libraries for Windows targets.
-    #[cfg(feature = "python3-dll-a")]
-    {
-        let py_version = if interpreter_config.abi3 {
-            None
-        } else {
-            Some(interpreter_config.version)
+        let config = InterpreterConfig::from_path(dir.join("targets").join("leaf.txt")).unwrap();
+        assert_eq!(config.version, PythonVersion { major: 3, minor: 9 });
+        assert!(!config.shared);
+        assert_eq!(config.lib_dir.as_deref(), Some("/leaf/lib"));
+        assert_eq!(config.extra_build_script_lines, ["base", "middle"]);
+
+        // includes in `from_reader` are relative to the current directory
+        let config = InterpreterConfig::from_reader(
+            format!("include={}\nabi3=true", dir.join("base.txt").display()).as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(config.version, PythonVersion { major: 3, minor: 8 });
+        assert!(config.abi3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_expand_home_dir() {
+        let home = Some(OsStr::new("/home/pyo3"));
+        assert_eq!(
+            expand_home_dir(Path::new("~/pyo3-config.txt"), home).unwrap(),
+            PathBuf::from("/home/pyo3/pyo3-config.txt")
+        );
+        assert_eq!(
+            expand_home_dir(Path::new("~"), home).unwrap(),
+            PathBuf::from("/home/pyo3")
+        );
+        // only a leading `~` component is expanded
+        for path in [
+            "/etc/pyo3-config.txt",
+            "~user/config.txt",
+            "config/~/config.txt",
+        ] {
+            assert_eq!(
+                expand_home_dir(Path::new(path), home).unwrap(),
+                Path::new(path)
+            );
+        }
+        assert_eq!(
+            expand_home_dir(Path::new("~/pyo3-config.txt"), None)
+                .unwrap_err()
+                .to_string(),
+            "failed to expand `~` in ~/pyo3-config.txt: could not determine the home directory"
+        );
+
+        // from_path expands the home directory
+        let home = home_dir().unwrap();
+        let dir = Path::new(&home).join(format!(".pyo3-config-home-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("config.txt"), "version=3.11\n").unwrap();
+        let path = Path::new("~")
+            .join(dir.file_name().unwrap())
+            .join("config.txt");
+        assert_eq!(
+            InterpreterConfig::from_path(path).unwrap().version,
+            PythonVersion {
+                major: 3,
+                minor: 11
+            }
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_interpreter_config_builder() {
+        let config = InterpreterConfigBuilder::new(
+            PythonImplementation::PyPy,
+            PythonVersion {
+                major: 3,
+                minor: 10,
+            },
+        )
+        .shared(false)
+        .lib_name("pypy3.10-c")
+        .lib_dir("/opt/pypy/lib")
+        .pointer_width(64)
+        .extra_link_arg("-lm")
+        .extra_link_arg_for_os("linux", "-Wl,--as-needed")
+        .sysconfig_var("MULTIARCH", "x86_64-linux-gnu")
+        .build()
+        .unwrap();
+        assert_eq!(config.implementation, PythonImplementation::PyPy);
+        assert!(!config.shared);
+        assert_eq!(config.lib_dir.as_deref(), Some("/opt/pypy/lib"));
+        assert_eq!(config.pointer_width, Some(64));
+        assert!(!config.pointer_width_inferred);
+
+        // serializing and reading back gives the same config
+        let mut buf = Vec::new();
+        config.to_writer(&mut buf).unwrap();
+        assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+
+        // individual fields of an existing config can be overridden
+        let config = InterpreterConfigBuilder::from(config)
+            .implementation(PythonImplementation::CPython)
+            .version(PythonVersion {
+                major: 3,
+                minor: 12,
+            })
+            .lib_dir("/usr/lib")
+            .build()
+            .unwrap();
+        assert_eq!(config.implementation, PythonImplementation::CPython);
+        assert_eq!(
+            config.version,
+            PythonVersion {
+                major: 3,
+                minor: 12
+            }
+        );
+        assert_eq!(config.lib_dir.as_deref(), Some("/usr/lib"));
+        assert_eq!(config.extra_link_args, ["-lm"]);
+
+        assert!(InterpreterConfigBuilder::new(
+            PythonImplementation::CPython,
+            PythonVersion { major: 3, minor: 6 },
+        )
+        .build()
+        .is_err());
+        assert_eq!(
+            InterpreterConfigBuilder::new(
+                PythonImplementation::CPython,
+                PythonVersion { major: 3, minor: 9 },
+            )
+            .lib_dir("/usr/lib\nshared=false")
+            .build()
+            .unwrap_err()
+            .to_string(),
+            "invalid lib_dir `/usr/lib\\nshared=false`: config values cannot contain newlines"
+        );
+    }
+
+    #[test]
+    fn test_config_file_toml() {
+        let dir = env::temp_dir().join(format!("pyo3-config-toml-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            "# cross compile config\nversion = \"3.10\"\nshared = false\n\n\
+             [extra_link_args_by_os]\nlinux = [\"-lm\"]\n",
+        )
+        .unwrap();
+        let config = InterpreterConfig::from_path(dir.join("config.toml")).unwrap();
+        assert_eq!(
+            config.version,
+            PythonVersion {
+                major: 3,
+                minor: 10
+            }
+        );
+        assert!(!config.shared);
+        assert_eq!(
+            config.extra_link_arg_directives("linux"),
+            ["cargo:rustc-link-arg=-lm"]
+        );
+
+        // TOML files can be included from the line-based format
+        std::fs::write(dir.join("leaf.txt"), "include=config.toml\nabi3=true\n").unwrap();
+        let config = InterpreterConfig::from_path(dir.join("leaf.txt")).unwrap();
+        assert_eq!(
+            config.version,
+            PythonVersion {
+                major: 3,
+                minor: 10
+            }
+        );
+        assert!(config.abi3);
+
+        // unknown keys are still rejected by validation
+        std::fs::write(
+            dir.join("unknown.toml"),
+            "version = \"3.10\"\nshard = false\n",
+        )
+        .unwrap();
+        assert_eq!(
+            validate_config_file(&dir.join("unknown.toml"))
+                .unwrap_err()
+                .to_string(),
+            "unknown config key `shard`"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_config_file_include_cycle() {
+        let dir = env::temp_dir().join(format!("pyo3-config-cycle-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.txt"), "version=3.8\ninclude=b.txt\n").unwrap();
+        std::fs::write(dir.join("b.txt"), "include=a.txt\n").unwrap();
+
+        let report = InterpreterConfig::from_path(dir.join("a.txt"))
+            .unwrap_err()
+            .report()
+            .to_string();
+        assert!(
+            report.contains("include cycle detected in PyO3 config"),
+            "unexpected error: {}",
+            report
+        );
+
+        let report = InterpreterConfig::from_reader("include=a.txt".as_bytes())
+            .unwrap_err()
+            .report()
+            .to_string();
+        assert!(
+            report.contains("failed to resolve included config a.txt"),
+            "unexpected error: {}",
+            report
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_python_implementation_from_str() {
+        for (value, implementation) in [
+            ("CPython", PythonImplementation::CPython),
+            ("cpython", PythonImplementation::CPython),
+            ("CPYTHON", PythonImplementation::CPython),
+            (" CPython\t", PythonImplementation::CPython),
+            ("PyPy", PythonImplementation::PyPy),
+            (" PyPy ", PythonImplementation::PyPy),
+            ("pypy", PythonImplementation::PyPy),
+            ("GraalVM", PythonImplementation::GraalPy),
+            ("graalvm ", PythonImplementation::GraalPy),
+            ("GraalPy", PythonImplementation::GraalPy),
+        ] {
+            assert_eq!(
+                value.parse::<PythonImplementation>().unwrap(),
+                implementation
+            );
+        }
+
+        assert_eq!(
+            "Jython"
+                .parse::<PythonImplementation>()
+                .unwrap_err()
+                .to_string(),
+            "unknown interpreter: Jython"
+        );
+
+        // serialization is always canonical
+        let config =
+            InterpreterConfig::from_reader("version=3.10\nimplementation= pypy ".as_bytes())
+                .unwrap();
+        let mut buf: Vec<u8> = Vec::new();
+        config.to_writer(&mut buf).unwrap();
+        assert!(std::str::from_utf8(&buf)
+            .unwrap()
+            .contains("implementation=PyPy\n"));
+    }
+
+    #[test]
+    fn test_config_file_extra_link_args() {
+        let config = InterpreterConfig::from_reader(
+            "version=3.9\n\
+            extra_link_args=-lutil\n\
+            extra_link_args_macos=-framework\n\
+            extra_link_args_macos=CoreFoundation\n\
+            extra_link_args_linux=-lrt\n\
+            extra_link_search_path=/opt/python/lib\n\
+            extra_link_search_path=/opt/deps/lib\n"
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(config.extra_link_args, ["-lutil"]);
+        assert_eq!(
+            config.extra_link_search_directives(),
+            [
+                "cargo:rustc-link-search=native=/opt/python/lib",
+                "cargo:rustc-link-search=native=/opt/deps/lib",
+            ]
+        );
+        assert_eq!(
+            config.extra_link_arg_directives("macos"),
+            [
+                "cargo:rustc-link-arg=-lutil",
+                "cargo:rustc-link-arg=-framework",
+                "cargo:rustc-link-arg=CoreFoundation",
+            ]
+        );
+        assert_eq!(
+            config.extra_link_arg_directives("linux"),
+            ["cargo:rustc-link-arg=-lutil", "cargo:rustc-link-arg=-lrt"]
+        );
+        assert_eq!(
+            config.extra_link_arg_directives("windows"),
+            ["cargo:rustc-link-arg=-lutil"]
+        );
+
+        let mut buf: Vec<u8> = Vec::new();
+        config.to_writer(&mut buf).unwrap();
+        assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+
+        assert_eq!(
+            InterpreterConfig::from_reader("version=3.9\nextra_link_args= ".as_bytes())
+                .unwrap_err()
+                .to_string(),
+            "empty value for config key `extra_link_args`"
+        );
+        assert_eq!(
+            InterpreterConfig::from_reader("version=3.9\nextra_link_args_=-lutil".as_bytes())
+                .unwrap_err()
+                .to_string(),
+            "missing target OS in config key `extra_link_args_`"
+        );
+        assert_eq!(
+            InterpreterConfig::from_reader("version=3.9\nextra_link_search_path=".as_bytes())
+                .unwrap_err()
+                .to_string(),
+            "empty value for config key `extra_link_search_path`"
+        );
+    }
+
+    #[test]
+    fn test_supports_subinterpreters() {
+        let config = |implementation, minor, abi3| InterpreterConfig {
+            implementation,
+            version: PythonVersion { major: 3, minor },
+            abi3,
+            ..InterpreterConfig::from_reader("version=3.7".as_bytes()).unwrap()
         };
-        interpreter_config.lib_dir = self::import_lib::generate_import_lib(
-            &host,
-            interpreter_config.implementation,
-            py_version,
-        )?;
+
+        for (minor, supported) in [(7, false), (11, false), (12, true), (13, true)] {
+            let interpreter_config = config(PythonImplementation::CPython, minor, false);
+            assert_eq!(interpreter_config.supports_subinterpreters(), supported);
+            assert_eq!(
+                interpreter_config
+                    .build_script_outputs()
+                    .contains(&"cargo:rustc-cfg=py_subinterpreters".to_owned()),
+                supported
+            );
+        }
+
+        assert!(!config(PythonImplementation::CPython, 12, true).supports_subinterpreters());
+        assert!(!config(PythonImplementation::PyPy, 12, false).supports_subinterpreters());
+        assert!(!config(PythonImplementation::GraalPy, 12, false).supports_subinterpreters());
     }
 
-    Ok(interpreter_config)
-}
+    #[cfg(unix)]
+    #[test]
+    fn test_config_from_fd() {
+        use std::os::unix::{io::IntoRawFd, net::UnixStream};
 
-fn escape(bytes: &[u8]) -> String {
-    let mut escaped = String::with_capacity(2 * bytes.len());
+        let (mut writer, reader) = UnixStream::pair().unwrap();
+        writer.write_all(b"version=3.10\nabi3=true\n").unwrap();
+        drop(writer);
 
-    for byte in bytes {
-        const LUT: &[u8; 16] = b"0123456789abcdef";
+        let config = InterpreterConfig::from_fd(&reader.into_raw_fd().to_string()).unwrap();
+        assert_eq!(
+            config.version,
+            PythonVersion {
+                major: 3,
+                minor: 10
+            }
+        );
+        assert!(config.abi3);
 
-        escaped.push(LUT[(byte >> 4) as usize] as char);
-        escaped.push(LUT[(byte & 0x0F) as usize] as char);
+        assert_eq!(
+            InterpreterConfig::from_fd("three").unwrap_err().to_string(),
+            "invalid file descriptor number `three`"
+        );
+        assert_eq!(
+            InterpreterConfig::from_fd("1").unwrap_err().to_string(),
+            "invalid file descriptor number `1`; standard streams cannot be used"
+        );
+    }
+
+    #[test]
+    fn test_type_layout_capabilities() {
+        let config = |implementation, minor, abi3| InterpreterConfig {
+            implementation,
+            version: PythonVersion { major: 3, minor },
+            abi3,
+            ..InterpreterConfig::from_reader("version=3.7".as_bytes()).unwrap()
+        };
+
+        for (minor, tp_vectorcall, tp_watched, managed_dict) in [
+            (7, false, false, false),
+            (8, false, false, false),
+            (9, true, false, false),
+            (11, true, false, false),
+            (12, true, true, true),
+            (13, true, true, true),
+        ] {
+            let interpreter_config = config(PythonImplementation::CPython, minor, false);
+            assert_eq!(interpreter_config.has_tp_vectorcall(), tp_vectorcall);
+            assert_eq!(interpreter_config.has_tp_watched(), tp_watched);
+            assert_eq!(interpreter_config.has_managed_dict(), managed_dict);
+
+            let outputs = interpreter_config.build_script_outputs();
+            for (supported, cfg) in [
+                (tp_vectorcall, "cargo:rustc-cfg=py_tp_vectorcall"),
+                (tp_watched, "cargo:rustc-cfg=py_tp_watched"),
+                (managed_dict, "cargo:rustc-cfg=py_managed_dict"),
+            ] {
+                assert_eq!(outputs.contains(&cfg.to_owned()), supported, "{}", cfg);
+            }
+        }
+
+        for interpreter_config in [
+            config(PythonImplementation::CPython, 12, true),
+            config(PythonImplementation::PyPy, 12, false),
+            config(PythonImplementation::GraalPy, 12, false),
+        ] {
+            assert!(!interpreter_config.has_tp_vectorcall());
+            assert!(!interpreter_config.has_tp_watched());
+            assert!(!interpreter_config.has_managed_dict());
+        }
+    }
+
+    #[test]
+    fn test_validate_config_file() {
+        let dir = env::temp_dir().join(format!("pyo3-config-validate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let validate = |name: &str, contents: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).unwrap();
+            validate_config_file(&path).map_err(|e| e.report().to_string())
+        };
+
+        assert_eq!(
+            validate(
+                "valid.txt",
+                "implementation=CPython\nversion=3.11\nshared=true\n"
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            validate("missing-version.txt", "shared=true\n"),
+            Err("missing value for version".to_owned())
+        );
+        assert_eq!(
+            validate("old-version.txt", "version=3.6\n"),
+            Err(
+                "the configured Python version (3.6) is lower than PyO3's minimum supported version (3.7)"
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            validate("pointer-width.txt", "version=3.11\npointer_width=16\n"),
+            Err("unexpected pointer width in config: 16".to_owned())
+        );
+        assert_eq!(
+            validate("unknown-key.txt", "version=3.11\nshraed=true\n"),
+            Err("unknown config key `shraed`".to_owned())
+        );
+        assert_eq!(
+            validate("not-a-pair.txt", "version=3.11\nshared\n"),
+            Err("expected key=value pair on line 2".to_owned())
+        );
+        assert_eq!(
+            validate("bad-bool.txt", "version=3.11\nshared=yes\n"),
+            Err("failed to parse shared from config value 'yes'\n\
+                caused by:\n  \
+                - 0: provided string was not `true` or `false`\n"
+                .to_owned())
+        );
+        assert!(validate_config_file(&dir.join("does-not-exist.txt"))
+            .unwrap_err()
+            .to_string()
+            .starts_with("failed to open PyO3 config file at"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_compatible_with() {
+        let config = |implementation, minor, abi3, pointer_width| InterpreterConfig {
+            implementation,
+            version: PythonVersion { major: 3, minor },
+            abi3,
+            pointer_width,
+            ..InterpreterConfig::from_reader("version=3.7".as_bytes()).unwrap()
+        };
+        let cpython = PythonImplementation::CPython;
+
+        // version-specific builds need the same version
+        let py310 = config(cpython, 10, false, Some(64));
+        assert!(py310.is_compatible_with(&py310));
+        assert!(!py310.is_compatible_with(&config(cpython, 11, false, Some(64))));
+        assert!(!py310.is_compatible_with(&config(cpython, 9, false, Some(64))));
+
+        // abi3 builds work on the minimum version and newer
+        let abi3_py38 = config(cpython, 8, true, Some(64));
+        assert!(abi3_py38.is_compatible_with(&config(cpython, 8, false, Some(64))));
+        assert!(abi3_py38.is_compatible_with(&config(cpython, 12, false, Some(64))));
+        assert!(!abi3_py38.is_compatible_with(&config(cpython, 7, false, Some(64))));
+
+        // implementations must match
+        assert!(!py310.is_compatible_with(&config(
+            PythonImplementation::PyPy,
+            10,
+            false,
+            Some(64)
+        )));
+        assert!(!config(PythonImplementation::GraalPy, 10, false, Some(64))
+            .is_compatible_with(&config(PythonImplementation::PyPy, 10, false, Some(64))));
+
+        // pointer widths must match when known
+        assert!(!py310.is_compatible_with(&config(cpython, 10, false, Some(32))));
+        assert!(py310.is_compatible_with(&config(cpython, 10, false, None)));
+        assert!(config(cpython, 10, false, None).is_compatible_with(&py310));
+
+        // linking details don't matter
+        let static_py310 = InterpreterConfig {
+            shared: false,
+            lib_dir: Some("/opt/python/lib".into()),
+            ..config(cpython, 10, false, Some(64))
+        };
+        assert!(py310.is_compatible_with(&static_py310));
+    }
+
+    #[test]
+    fn test_validate_for_target() {
+        let config = |implementation, minor, abi3| InterpreterConfig {
+            implementation,
+            version: PythonVersion { major: 3, minor },
+            abi3,
+            pointer_width: Some(64),
+            ..InterpreterConfig::from_reader("version=3.7".as_bytes()).unwrap()
+        };
+        let cpython = PythonImplementation::CPython;
+        let aarch64_linux: Triple = "aarch64-unknown-linux-gnu".parse().unwrap();
+        let x86_64_musl: Triple = "x86_64-unknown-linux-musl".parse().unwrap();
+        let aarch64_macos: Triple = "aarch64-apple-darwin".parse().unwrap();
+        let i686_windows: Triple = "i686-pc-windows-msvc".parse().unwrap();
+
+        let abi3_py311 = config(cpython, 11, true);
+        assert_eq!(
+            abi3_py311.validate_for_target(&aarch64_linux, "cp311-abi3-manylinux_2_28_aarch64"),
+            Ok(())
+        );
+        // abi3 wheels may require a newer version than the configuration
+        assert_eq!(
+            abi3_py311.validate_for_target(&aarch64_linux, "cp312-abi3-manylinux2014_aarch64"),
+            Ok(())
+        );
+        assert_eq!(
+            abi3_py311.validate_for_target(&aarch64_linux, "cp310-abi3-linux_aarch64"),
+            Err(vec![TargetMismatch::PythonTag {
+                expected: "cp311".into(),
+                tag: "cp310".into()
+            }])
+        );
+        assert_eq!(
+            abi3_py311.validate_for_target(&aarch64_linux, "cp311-cp311-manylinux_2_28_aarch64"),
+            Err(vec![TargetMismatch::Abi3 {
+                abi3: true,
+                tag: "cp311".into()
+            }])
+        );
+
+        // all mismatches are reported
+        let py312 = config(cpython, 12, false);
+        assert_eq!(
+            py312.validate_for_target(&aarch64_linux, "cp311-abi3-manylinux_2_28_x86_64"),
+            Err(vec![
+                TargetMismatch::PythonTag {
+                    expected: "cp312".into(),
+                    tag: "cp311".into()
+                },
+                TargetMismatch::Abi3 {
+                    abi3: false,
+                    tag: "abi3".into()
+                },
+                TargetMismatch::Platform {
+                    tag: "manylinux_2_28_x86_64".into(),
+                    target: "aarch64-unknown-linux-gnu".into()
+                },
+            ])
+        );
+
+        // the ABI tag follows the build flags, or SOABI when it was probed
+        let mut py313t = config(cpython, 13, false);
+        py313t.build_flags.0.insert(BuildFlag::Py_GIL_DISABLED);
+        assert_eq!(
+            py313t.validate_for_target(&x86_64_musl, "cp313-cp313t-musllinux_1_2_x86_64"),
+            Ok(())
+        );
+        assert_eq!(
+            py313t.validate_for_target(&x86_64_musl, "cp313-cp313-musllinux_1_2_x86_64"),
+            Err(vec![TargetMismatch::AbiTag {
+                expected: "cp313t".into(),
+                tag: "cp313".into()
+            }])
+        );
+        let mut pypy310 = config(PythonImplementation::PyPy, 10, false);
+        pypy310
+            .sysconfig_vars
+            .insert("SOABI".into(), "pypy310-pp73-aarch64-linux-gnu".into());
+        assert_eq!(
+            pypy310
+                .validate_for_target(&aarch64_linux, "pp310-pypy310_pp73-manylinux_2_28_aarch64"),
+            Ok(())
+        );
+
+        // manylinux wheels need glibc, and compressed tag sets match if any tag does
+        assert_eq!(
+            py312.validate_for_target(
+                &x86_64_musl,
+                "cp312-cp312-manylinux_2_17_x86_64.manylinux2014_x86_64"
+            ),
+            Err(vec![TargetMismatch::Platform {
+                tag: "manylinux_2_17_x86_64.manylinux2014_x86_64".into(),
+                target: "x86_64-unknown-linux-musl".into()
+            }])
+        );
+        assert_eq!(
+            py312.validate_for_target(
+                &x86_64_musl,
+                "cp312-cp312-manylinux_2_17_x86_64.musllinux_1_1_x86_64"
+            ),
+            Ok(())
+        );
+
+        // the platform of the interpreter must match too
+        let macos = InterpreterConfig {
+            platform_tag: Some("macosx-11.0-arm64".into()),
+            ..config(cpython, 12, false)
+        };
+        assert_eq!(
+            macos.validate_for_target(&aarch64_macos, "cp312-cp312-macosx_11_0_arm64"),
+            Ok(())
+        );
+        assert_eq!(
+            macos.validate_for_target(&aarch64_macos, "cp312-cp312-macosx_10_9_universal2"),
+            Ok(())
+        );
+        assert_eq!(
+            InterpreterConfig {
+                platform_tag: Some("linux-x86_64".into()),
+                ..config(cpython, 12, false)
+            }
+            .validate_for_target(&aarch64_linux, "cp312-cp312-manylinux_2_28_aarch64"),
+            Err(vec![TargetMismatch::InterpreterPlatform {
+                platform_tag: "linux-x86_64".into(),
+                tag: "manylinux_2_28_aarch64".into()
+            }])
+        );
+
+        // pointer widths must match
+        assert_eq!(
+            py312.validate_for_target(&i686_windows, "cp312-cp312-win32"),
+            Err(vec![TargetMismatch::PointerWidth {
+                config: 64,
+                target: 32
+            }])
+        );
+
+        // unknown platforms are not checked
+        assert_eq!(
+            py312.validate_for_target(&aarch64_linux, "cp312-cp312-android_21_arm64_v8a"),
+            Ok(())
+        );
+
+        assert_eq!(
+            py312.validate_for_target(&aarch64_linux, "cp312-cp312"),
+            Err(vec![TargetMismatch::InvalidWheelTag("cp312-cp312".into())])
+        );
+        assert_eq!(
+            TargetMismatch::Abi3 {
+                abi3: false,
+                tag: "abi3".into()
+            }
+            .to_string(),
+            "ABI tag `abi3` is for the stable ABI, but the configuration doesn't target it \
+            (enable one of PyO3's `abi3` features)"
+        );
     }
 
-    escaped
-}
-
-fn unescape(escaped: &str) -> Vec<u8> {
-    assert!(escaped.len() % 2 == 0, "invalid hex encoding");
+    #[test]
+    fn test_embedding_test_link_args() {
+        let interpreter_config = InterpreterConfig {
+            shared: true,
+            lib_dir: Some("/usr/lib".into()),
+            ..InterpreterConfig::from_reader("version=3.11".as_bytes()).unwrap()
+        };
+        let linux = triple!("x86_64-unknown-linux-gnu");
 
-    let mut bytes = Vec::with_capacity(escaped.len() / 2);
+        assert_eq!(
+            interpreter_config.embedding_test_link_args_for_target(&linux, false, true),
+            [
+                "cargo:rustc-link-arg-tests=-Wl,-rpath,/usr/lib",
+                "cargo:rustc-link-arg-benches=-Wl,-rpath,/usr/lib",
+            ]
+        );
+        assert_eq!(
+            interpreter_config.embedding_test_link_args_for_target(
+                &triple!("aarch64-apple-darwin"),
+                false,
+                false
+            ),
+            ["cargo:rustc-link-arg-tests=-Wl,-rpath,/usr/lib"]
+        );
 
-    for chunk in escaped.as_bytes().chunks_exact(2) {
-        fn unhex(hex: u8) -> u8 {
-            match hex {
-                b'a'..=b'f' => hex - b'a' + 10,
-                b'0'..=b'9' => hex - b'0',
-                _ => panic!("invalid hex encoding"),
-            }
-        }
+        // macOS framework builds can also be found through the framework prefix
+        let framework_config = InterpreterConfig {
+            python_framework_prefix: Some("/Library/Frameworks".into()),
+            ..interpreter_config.clone()
+        };
+        assert_eq!(
+            framework_config.embedding_test_link_args_for_target(
+                &triple!("aarch64-apple-darwin"),
+                false,
+                false
+            ),
+            [
+                "cargo:rustc-link-arg-tests=-Wl,-rpath,/usr/lib",
+                "cargo:rustc-link-arg-tests=-Wl,-rpath,/Library/Frameworks"
+            ]
+        );
+        assert_eq!(
+            framework_config.embedding_test_link_args_for_target(&linux, false, false),
+            ["cargo:rustc-link-arg-tests=-Wl,-rpath,/usr/lib"]
+        );
 
-        bytes.push(unhex(chunk[0]) << 4 | unhex(chunk[1]));
+        // not embedding
+        assert!(interpreter_config
+            .embedding_test_link_args_for_target(&linux, true, true)
+            .is_empty());
+        // no rpath on windows
+        assert!(interpreter_config
+            .embedding_test_link_args_for_target(&triple!("x86_64-pc-windows-msvc"), false, true)
+            .is_empty());
+        // static builds
+        let static_config = InterpreterConfig {
+            shared: false,
+            ..interpreter_config.clone()
+        };
+        assert!(static_config
+            .embedding_test_link_args_for_target(&linux, false, true)
+            .is_empty());
+        // unknown lib_dir
+        let no_lib_dir = InterpreterConfig {
+            lib_dir: None,
+            ..interpreter_config
+        };
+        assert!(no_lib_dir
+            .embedding_test_link_args_for_target(&linux, false, true)
+            .is_empty());
     }
 
-    bytes
-}
+    #[test]
+    fn test_write_all_formats() {
+        let dir = env::temp_dir().join(format!("pyo3-config-formats-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = InterpreterConfig::from_reader(
+            r##"implementation=PyPy
+version=3.10
+shared=false
+abi3=true
+lib_name=pypy3.10-c
+lib_dir=C:\Program Files\"PyPy"#3\libs
+executable=/usr/bin/pypy3
+pointer_width=64
+pointer_width_inferred=true
+build_flags=Py_DEBUG,Py_TRACE_REFS
+extra_build_script_line=cargo:rustc-cfg=foo
+config_arg=--with-pydebug
+extra_link_args=-lutil
+extra_link_args=-Wl,--as-needed
+extra_link_args_linux=-Wl,-rpath,/opt/pypy/lib
+extra_link_args_macos=-Wl,-rpath,@loader_path
+extra_link_search_path=/opt/pypy/lib
+sysconfig_var=MULTIARCH=x86_64-linux-gnu
+sysconfig_var=Py_ENABLE_SHARED=0
+sysconfig_var=CONFIG_ARGS='--prefix=/opt/pypy' "CFLAGS=-O2"
+"##
+            .as_bytes(),
+        )
+        .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use target_lexicon::triple;
+        let paths = config.write_all_formats(&dir).unwrap();
+        assert_eq!(
+            paths,
+            [
+                dir.join("pyo3-build-config.txt"),
+                dir.join("pyo3-build-config.toml")
+            ]
+        );
+        for path in &paths {
+            assert_eq!(config, InterpreterConfig::from_path(path).unwrap());
+        }
 
-    use super::*;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
     #[test]
-    fn test_config_file_roundtrip() {
-        let config = InterpreterConfig {
-            abi3: true,
-            build_flags: BuildFlags::default(),
-            pointer_width: Some(32),
-            executable: Some("executable".into()),
-            implementation: PythonImplementation::CPython,
-            lib_name: Some("lib_name".into()),
-            lib_dir: Some("lib_dir".into()),
-            shared: true,
-            version: MINIMUM_SUPPORTED_VERSION,
-            suppress_build_script_link_lines: true,
-            extra_build_script_lines: vec!["cargo:test1".to_string(), "cargo:test2".to_string()],
+    fn test_rerun_if_changed_for_interpreter() {
+        let executable = env::current_exe().unwrap().to_str().unwrap().to_owned();
+        let interpreter_config = InterpreterConfig {
+            executable: Some(executable.clone()),
+            ..InterpreterConfig::from_reader("version=3.11".as_bytes()).unwrap()
         };
-        let mut buf: Vec<u8> = Vec::new();
-        config.to_writer(&mut buf).unwrap();
-
-        assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+        assert_eq!(
+            interpreter_config.rerun_if_changed_for_interpreter(),
+            Some(format!("cargo:rerun-if-changed={}", executable))
+        );
 
-        // And some different options, for variety
+        let interpreter_config = InterpreterConfig {
+            executable: Some("/nonexistent/bin/python3".into()),
+            ..interpreter_config
+        };
+        assert_eq!(
+            interpreter_config.rerun_if_changed_for_interpreter(),
+            Some(
+                "cargo:warning=the Python interpreter at /nonexistent/bin/python3 does not exist, \
+                so changes to it will not trigger a rebuild"
+                    .to_owned()
+            )
+        );
 
-        let config = InterpreterConfig {
-            abi3: false,
-            build_flags: {
-                let mut flags = HashSet::new();
-                flags.insert(BuildFlag::Py_DEBUG);
-                flags.insert(BuildFlag::Other(String::from("Py_SOME_FLAG")));
-                BuildFlags(flags)
-            },
-            pointer_width: None,
+        let interpreter_config = InterpreterConfig {
             executable: None,
-            implementation: PythonImplementation::PyPy,
-            lib_dir: None,
-            lib_name: None,
-            shared: true,
-            version: PythonVersion {
-                major: 3,
-                minor: 10,
-            },
-            suppress_build_script_link_lines: false,
-            extra_build_script_lines: vec![],
+            ..interpreter_config
         };
-        let mut buf: Vec<u8> = Vec::new();
-        config.to_writer(&mut buf).unwrap();
-
-        assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+        assert_eq!(interpreter_config.rerun_if_changed_for_interpreter(), None);
     }
 
     #[test]
-    fn test_config_file_roundtrip_with_escaping() {
-        let config = InterpreterConfig {
-            abi3: true,
-            build_flags: BuildFlags::default(),
-            pointer_width: Some(32),
-            executable: Some("executable".into()),
-            implementation: PythonImplementation::CPython,
-            lib_name: Some("lib_name".into()),
-            lib_dir: Some("lib_dir\\n".into()),
-            shared: true,
-            version: MINIMUM_SUPPORTED_VERSION,
-            suppress_build_script_link_lines: true,
-            extra_build_script_lines: vec!["cargo:test1".to_string(), "cargo:test2".to_string()],
-        };
-        let mut buf: Vec<u8> = Vec::new();
-        config.to_writer(&mut buf).unwrap();
+    fn test_extra_probe_vars() {
+        assert_eq!(
+            parse_extra_probe_vars("ABIFLAGS, MULTIARCH,,_PRIVATE_1").unwrap(),
+            ["ABIFLAGS", "MULTIARCH", "_PRIVATE_1"]
+        );
+        assert!(parse_extra_probe_vars("").unwrap().is_empty());
+        for invalid in ["1ABI", "ABI-FLAGS", "A B", "ABI$"] {
+            assert_eq!(
+                parse_extra_probe_vars(invalid).unwrap_err().to_string(),
+                format!(
+                    "invalid sysconfig variable name `{}` in PYO3_EXTRA_PROBE_VARS",
+                    invalid
+                )
+            );
+        }
 
-        let buf = unescape(&escape(&buf));
+        let mut sysconfigdata = Sysconfigdata::new();
+        sysconfigdata.insert("ABIFLAGS", "d");
+        sysconfigdata.insert("MULTIARCH", "x86_64-linux-gnu");
+        let vars = collect_extra_probe_vars(
+            &parse_extra_probe_vars("ABIFLAGS,MULTIARCH").unwrap(),
+            |name| sysconfigdata.get_value(name).map(str::to_owned),
+        );
+        assert_eq!(vars["ABIFLAGS"], "d");
+        assert_eq!(vars["MULTIARCH"], "x86_64-linux-gnu");
 
-        assert_eq!(config, InterpreterConfig::from_reader(&*buf).unwrap());
+        // missing variables are skipped (with a warning)
+        let vars = collect_extra_probe_vars(&["MISSING".to_owned()], |_| None);
+        assert!(vars.is_empty());
+
+        let interpreter_config = InterpreterConfig {
+            sysconfig_vars: [("MULTIARCH".to_owned(), "x86_64-linux-gnu".to_owned())].into(),
+            ..InterpreterConfig::from_reader("version=3.11".as_bytes()).unwrap()
+        };
+        let mut buf: Vec<u8> = Vec::new();
+        interpreter_config.to_writer(&mut buf).unwrap();
+        assert!(std::str::from_utf8(&buf)
+            .unwrap()
+            .contains("sysconfig_var=MULTIARCH=x86_64-linux-gnu\n"));
+        assert_eq!(
+            interpreter_config,
+            InterpreterConfig::from_reader(&*buf).unwrap()
+        );
     }
 
     #[test]
@@ -1925,10 +4971,18 @@ mod tests {
                 lib_name: None,
                 lib_dir: None,
                 executable: None,
+                platform_tag: None,
+                python_framework_prefix: None,
                 pointer_width: None,
+                pointer_width_inferred: false,
                 build_flags: BuildFlags::default(),
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         )
     }
@@ -1947,10 +5001,18 @@ mod tests {
                 lib_name: None,
                 lib_dir: None,
                 executable: None,
+                platform_tag: None,
+                python_framework_prefix: None,
                 pointer_width: None,
+                pointer_width_inferred: false,
                 build_flags: BuildFlags::default(),
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         )
     }
@@ -2005,6 +5067,61 @@ mod tests {
         assert!(build_flags.0.contains(&BuildFlag::Py_REF_DEBUG));
     }
 
+    #[test]
+    fn merge_and_remove_build_flags() {
+        let mut interpreter_config = InterpreterConfig {
+            implementation: PythonImplementation::CPython,
+            version: PythonVersion { major: 3, minor: 7 },
+            shared: true,
+            abi3: false,
+            lib_name: None,
+            lib_dir: None,
+            executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
+            pointer_width: None,
+            pointer_width_inferred: false,
+            build_flags: "Py_TRACE_REFS".parse().unwrap(),
+            suppress_build_script_link_lines: false,
+            extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+
+        interpreter_config.merge_build_flags_from(&"Py_DEBUG,Py_TRACE_REFS".parse().unwrap());
+        let mut expected = HashSet::new();
+        expected.insert(BuildFlag::Py_DEBUG);
+        expected.insert(BuildFlag::Py_REF_DEBUG);
+        expected.insert(BuildFlag::Py_TRACE_REFS);
+        assert_eq!(interpreter_config.build_flags.0, expected);
+
+        assert!(interpreter_config.remove_build_flag(&BuildFlag::Py_DEBUG));
+        assert!(!interpreter_config.remove_build_flag(&BuildFlag::Py_DEBUG));
+        assert!(interpreter_config.remove_build_flag(&BuildFlag::Py_TRACE_REFS));
+
+        assert_eq!(
+            interpreter_config.build_script_outputs(),
+            [
+                "cargo:rustc-cfg=Py_3_6".to_owned(),
+                "cargo:rustc-cfg=Py_3_7".to_owned(),
+                "cargo:rustc-cfg=py_sys_config=\"Py_REF_DEBUG\"".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rosetta_warning() {
+        assert_eq!(rosetta_warning("arm64", false), None);
+        assert_eq!(rosetta_warning("x86_64", false), None);
+
+        let warning = rosetta_warning("x86_64", true).unwrap();
+        assert!(warning.contains("x86_64 build running under Rosetta 2"));
+        assert!(warning.contains("native arm64"));
+    }
+
     #[test]
     fn parse_script_output() {
         let output = "foo bar\nbar foobar\n\n";
@@ -2014,6 +5131,67 @@ mod tests {
         assert_eq!(map["bar"], "foobar");
     }
 
+    #[test]
+    fn test_parse_config_args() {
+        let config_args = parse_config_args(
+            " '--prefix=/usr' '--enable-shared' '--enable-optimizations' \
+            'CFLAGS=-O2 -g' \"LDFLAGS=-Wl,-rpath,\\\"/opt/lib\\\"\" --with-lto=thin",
+        );
+        assert_eq!(
+            config_args,
+            [
+                "--prefix=/usr",
+                "--enable-shared",
+                "--enable-optimizations",
+                "CFLAGS=-O2 -g",
+                "LDFLAGS=-Wl,-rpath,\"/opt/lib\"",
+                "--with-lto=thin",
+            ]
+        );
+        assert!(parse_config_args("").is_empty());
+        assert_eq!(parse_config_args("''"), [""]);
+
+        let interpreter_config = InterpreterConfig {
+            config_args,
+            ..InterpreterConfig::from_reader("version=3.12".as_bytes()).unwrap()
+        };
+        assert!(interpreter_config.has_config_arg("--enable-shared"));
+        assert!(interpreter_config.has_config_arg("--with-lto"));
+        assert!(!interpreter_config.has_config_arg("--with-pydebug"));
+        assert!(!interpreter_config.has_config_arg("--enable"));
+        assert!(interpreter_config.is_pgo_build());
+
+        let mut buf: Vec<u8> = Vec::new();
+        interpreter_config.to_writer(&mut buf).unwrap();
+        assert_eq!(
+            interpreter_config,
+            InterpreterConfig::from_reader(&*buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sysconfigdata_shared_from_config_args() {
+        let mut sysconfigdata = Sysconfigdata::new();
+        sysconfigdata.insert("SOABI", "cpython-312-x86_64-linux-gnu");
+        sysconfigdata.insert("VERSION", "3.12");
+        sysconfigdata.insert("SIZEOF_VOID_P", "8");
+        sysconfigdata.insert("CONFIG_ARGS", "'--prefix=/usr' '--enable-shared'");
+
+        let interpreter_config = InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap();
+        assert!(interpreter_config.shared);
+        assert_eq!(
+            interpreter_config.config_args,
+            ["--prefix=/usr", "--enable-shared"]
+        );
+
+        sysconfigdata.insert("Py_ENABLE_SHARED", "0");
+        assert!(
+            !InterpreterConfig::from_sysconfigdata(&sysconfigdata)
+                .unwrap()
+                .shared
+        );
+    }
+
     #[test]
     fn config_from_interpreter() {
         // Smoke test to just see whether this works
@@ -2023,38 +5201,194 @@ mod tests {
     }
 
     #[test]
-    fn config_from_empty_sysconfigdata() {
-        let sysconfigdata = Sysconfigdata::new();
-        assert!(InterpreterConfig::from_sysconfigdata(&sysconfigdata).is_err());
+    fn config_from_empty_sysconfigdata() {
+        let sysconfigdata = Sysconfigdata::new();
+        assert!(InterpreterConfig::from_sysconfigdata(&sysconfigdata).is_err());
+    }
+
+    #[test]
+    fn config_from_sysconfigdata() {
+        let mut sysconfigdata = Sysconfigdata::new();
+        // these are the minimal values required such that InterpreterConfig::from_sysconfigdata
+        // does not error
+        sysconfigdata.insert("SOABI", "cpython-37m-x86_64-linux-gnu");
+        sysconfigdata.insert("VERSION", "3.7");
+        sysconfigdata.insert("Py_ENABLE_SHARED", "1");
+        sysconfigdata.insert("LIBDIR", "/usr/lib");
+        sysconfigdata.insert("LDVERSION", "3.7m");
+        sysconfigdata.insert("SIZEOF_VOID_P", "8");
+        assert_eq!(
+            InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap(),
+            InterpreterConfig {
+                abi3: false,
+                build_flags: BuildFlags::from_sysconfigdata(&sysconfigdata),
+                pointer_width: Some(64),
+                pointer_width_inferred: false,
+                executable: None,
+                platform_tag: None,
+                python_framework_prefix: None,
+                implementation: PythonImplementation::CPython,
+                lib_dir: Some("/usr/lib".into()),
+                lib_name: Some("python3.7m".into()),
+                shared: true,
+                version: PythonVersion::PY37,
+                suppress_build_script_link_lines: false,
+                extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_cross_lib_dir_warning() {
+        assert!(!missing_cross_lib_dir_warning(false).contains("generate-import-lib"));
+        assert_eq!(
+            missing_cross_lib_dir_warning(true).contains("generate-import-lib"),
+            cfg!(not(feature = "python3-dll-a"))
+        );
+    }
+
+    #[test]
+    fn windows_debug_build_flags() {
+        let flags = BuildFlags::new().with_windows_debug_flags("_d.cp312-win_amd64.pyd");
+        assert_eq!(
+            flags.0,
+            [BuildFlag::Py_DEBUG, BuildFlag::Py_REF_DEBUG]
+                .into_iter()
+                .collect()
+        );
+        assert!(!flags.0.contains(&BuildFlag::Py_TRACE_REFS));
+
+        assert!(BuildFlags::new()
+            .with_windows_debug_flags(".cp312-win_amd64.pyd")
+            .0
+            .is_empty());
+    }
+
+    #[test]
+    fn config_from_sysconfigdata_free_threaded() {
+        let mut sysconfigdata = Sysconfigdata::new();
+        sysconfigdata.insert("SOABI", "cpython-313t-x86_64-linux-gnu");
+        sysconfigdata.insert("VERSION", "3.13");
+        sysconfigdata.insert("Py_ENABLE_SHARED", "1");
+        sysconfigdata.insert("Py_GIL_DISABLED", "1");
+        sysconfigdata.insert("LIBDIR", "/usr/lib");
+        sysconfigdata.insert("LDVERSION", "3.13t");
+        sysconfigdata.insert("SIZEOF_VOID_P", "8");
+        let config = InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap();
+        assert!(config.gil_disabled());
+        assert_eq!(config.lib_name.as_deref(), Some("python3.13t"));
+        let outputs = config.build_script_outputs();
+        assert!(outputs.contains(&"cargo:rustc-cfg=Py_GIL_DISABLED".to_owned()));
+        assert!(outputs.contains(&"cargo:rustc-cfg=py_sys_config=\"Py_GIL_DISABLED\"".to_owned()));
+        config.validate().unwrap();
+
+        // free-threaded builds have no limited API
+        let config = InterpreterConfig {
+            abi3: true,
+            ..config
+        };
+        assert_eq!(
+            config.validate().unwrap_err().to_string(),
+            "the free-threaded build of Python 3.13 does not support the limited API (abi3)\n\
+            = help: build without the `abi3` features for free-threaded Python"
+        );
+
+        sysconfigdata.insert("Py_GIL_DISABLED", "0");
+        sysconfigdata.insert("LDVERSION", "3.13");
+        let config = InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap();
+        assert!(!config.gil_disabled());
+        assert!(!config
+            .build_script_outputs()
+            .contains(&"cargo:rustc-cfg=Py_GIL_DISABLED".to_owned()));
+    }
+
+    #[test]
+    fn sysconfigdata_target_check() {
+        let sysconfigdata_for = |multiarch: &str| {
+            let mut sysconfigdata = Sysconfigdata::new();
+            sysconfigdata.insert("MULTIARCH", multiarch);
+            sysconfigdata
+        };
+
+        for (multiarch, target) in [
+            ("aarch64-linux-android", "aarch64-linux-android"),
+            ("arm-linux-androideabi", "armv7-linux-androideabi"),
+            ("x86_64-linux-android", "x86_64-linux-android"),
+            ("i686-linux-android", "i686-linux-android"),
+            ("arm64-iphoneos", "aarch64-apple-ios"),
+            ("arm64-iphonesimulator", "aarch64-apple-ios-sim"),
+            ("x86_64-iphonesimulator", "x86_64-apple-ios"),
+            // Only mobile targets are checked.
+            ("aarch64-linux-gnu", "x86_64-unknown-linux-gnu"),
+        ] {
+            check_sysconfigdata_target(&sysconfigdata_for(multiarch), &triple!(target))
+                .unwrap_or_else(|e| panic!("{} for {}: {}", multiarch, target, e));
+        }
+
+        for (multiarch, target) in [
+            ("x86_64-linux-android", "aarch64-linux-android"),
+            ("aarch64-linux-gnu", "aarch64-linux-android"),
+            ("arm64-iphonesimulator", "aarch64-apple-ios"),
+            ("arm64-iphoneos", "aarch64-apple-ios-sim"),
+        ] {
+            assert_eq!(
+                check_sysconfigdata_target(&sysconfigdata_for(multiarch), &triple!(target))
+                    .unwrap_err()
+                    .to_string(),
+                format!(
+                    "sysconfigdata is for `{}`, which does not match the target `{}`",
+                    multiarch, target
+                )
+            );
+        }
+
+        // Without `MULTIARCH` there is nothing to check.
+        check_sysconfigdata_target(&Sysconfigdata::new(), &triple!("aarch64-linux-android"))
+            .unwrap();
+    }
+
+    #[test]
+    fn mobile_targets_require_libpython() {
+        for target in [
+            "aarch64-linux-android",
+            "armv7-linux-androideabi",
+            "aarch64-apple-ios",
+            "aarch64-apple-ios-sim",
+        ] {
+            assert!(
+                target_requires_libpython(&triple!(target), true),
+                "{}",
+                target
+            );
+        }
+        assert!(!target_requires_libpython(
+            &triple!("aarch64-apple-darwin"),
+            true
+        ));
     }
 
     #[test]
-    fn config_from_sysconfigdata() {
+    fn config_from_sysconfigdata_pypy() {
         let mut sysconfigdata = Sysconfigdata::new();
-        // these are the minimal values required such that InterpreterConfig::from_sysconfigdata
-        // does not error
-        sysconfigdata.insert("SOABI", "cpython-37m-x86_64-linux-gnu");
-        sysconfigdata.insert("VERSION", "3.7");
-        sysconfigdata.insert("Py_ENABLE_SHARED", "1");
-        sysconfigdata.insert("LIBDIR", "/usr/lib");
-        sysconfigdata.insert("LDVERSION", "3.7m");
+        // PyPy's sysconfigdata doesn't define Py_ENABLE_SHARED
+        sysconfigdata.insert("SOABI", "pypy310-pp73-aarch64-linux-gnu");
+        sysconfigdata.insert("VERSION", "3.10");
+        sysconfigdata.insert("LIBDIR", "/opt/pypy/bin");
+        sysconfigdata.insert("LDVERSION", "3.10");
         sysconfigdata.insert("SIZEOF_VOID_P", "8");
-        assert_eq!(
-            InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap(),
-            InterpreterConfig {
-                abi3: false,
-                build_flags: BuildFlags::from_sysconfigdata(&sysconfigdata),
-                pointer_width: Some(64),
-                executable: None,
-                implementation: PythonImplementation::CPython,
-                lib_dir: Some("/usr/lib".into()),
-                lib_name: Some("python3.7m".into()),
-                shared: true,
-                version: PythonVersion::PY37,
-                suppress_build_script_link_lines: false,
-                extra_build_script_lines: vec![],
-            }
-        );
+        let config = InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap();
+        assert_eq!(config.implementation, PythonImplementation::PyPy);
+        assert!(config.shared);
+        assert_eq!(config.lib_name.as_deref(), Some("pypy3.10-c"));
+        assert_eq!(config.lib_dir.as_deref(), Some("/opt/pypy/bin"));
+        assert!(config
+            .build_script_outputs()
+            .contains(&"cargo:rustc-cfg=PyPy".to_owned()));
     }
 
     #[test]
@@ -2065,6 +5399,7 @@ mod tests {
         // PYTHONFRAMEWORK should override Py_ENABLE_SHARED
         sysconfigdata.insert("Py_ENABLE_SHARED", "0");
         sysconfigdata.insert("PYTHONFRAMEWORK", "Python");
+        sysconfigdata.insert("PYTHONFRAMEWORKPREFIX", "/Library/Frameworks");
         sysconfigdata.insert("LIBDIR", "/usr/lib");
         sysconfigdata.insert("LDVERSION", "3.7m");
         sysconfigdata.insert("SIZEOF_VOID_P", "8");
@@ -2074,7 +5409,10 @@ mod tests {
                 abi3: false,
                 build_flags: BuildFlags::from_sysconfigdata(&sysconfigdata),
                 pointer_width: Some(64),
+                pointer_width_inferred: false,
                 executable: None,
+                platform_tag: None,
+                python_framework_prefix: Some("/Library/Frameworks".into()),
                 implementation: PythonImplementation::CPython,
                 lib_dir: Some("/usr/lib".into()),
                 lib_name: Some("python3.7m".into()),
@@ -2082,6 +5420,11 @@ mod tests {
                 version: PythonVersion::PY37,
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         );
 
@@ -2100,7 +5443,10 @@ mod tests {
                 abi3: false,
                 build_flags: BuildFlags::from_sysconfigdata(&sysconfigdata),
                 pointer_width: Some(64),
+                pointer_width_inferred: false,
                 executable: None,
+                platform_tag: None,
+                python_framework_prefix: None,
                 implementation: PythonImplementation::CPython,
                 lib_dir: Some("/usr/lib".into()),
                 lib_name: Some("python3.7m".into()),
@@ -2108,6 +5454,11 @@ mod tests {
                 version: PythonVersion::PY37,
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         );
     }
@@ -2127,10 +5478,18 @@ mod tests {
                 lib_name: Some("python3".into()),
                 lib_dir: None,
                 executable: None,
+                platform_tag: None,
+                python_framework_prefix: None,
                 pointer_width: None,
+                pointer_width_inferred: false,
                 build_flags: BuildFlags::default(),
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         );
     }
@@ -2150,10 +5509,18 @@ mod tests {
                 lib_name: None,
                 lib_dir: None,
                 executable: None,
+                platform_tag: None,
+                python_framework_prefix: None,
                 pointer_width: None,
+                pointer_width_inferred: false,
                 build_flags: BuildFlags::default(),
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         );
     }
@@ -2184,10 +5551,18 @@ mod tests {
                 lib_name: Some("python37".into()),
                 lib_dir: Some("C:\\some\\path".into()),
                 executable: None,
-                pointer_width: None,
+                platform_tag: None,
+                python_framework_prefix: None,
+                pointer_width: Some(32),
+                pointer_width_inferred: true,
                 build_flags: BuildFlags::default(),
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         );
     }
@@ -2218,10 +5593,18 @@ mod tests {
                 lib_name: Some("python38".into()),
                 lib_dir: Some("/usr/lib/mingw".into()),
                 executable: None,
-                pointer_width: None,
+                platform_tag: None,
+                python_framework_prefix: None,
+                pointer_width: Some(32),
+                pointer_width_inferred: true,
                 build_flags: BuildFlags::default(),
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         );
     }
@@ -2252,10 +5635,18 @@ mod tests {
                 lib_name: Some("python3.9".into()),
                 lib_dir: Some("/usr/arm64/lib".into()),
                 executable: None,
-                pointer_width: None,
+                platform_tag: None,
+                python_framework_prefix: None,
+                pointer_width: Some(64),
+                pointer_width_inferred: true,
                 build_flags: BuildFlags::default(),
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         );
     }
@@ -2288,12 +5679,110 @@ mod tests {
                 lib_name: Some("pypy3.10-c".into()),
                 lib_dir: None,
                 executable: None,
-                pointer_width: None,
+                platform_tag: None,
+                python_framework_prefix: None,
+                pointer_width: Some(64),
+                pointer_width_inferred: true,
+                build_flags: BuildFlags::default(),
+                suppress_build_script_link_lines: false,
+                extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn emscripten_hardcoded_cross_compile() {
+        let env_vars = CrossCompileEnvVars {
+            pyo3_cross: None,
+            pyo3_cross_lib_dir: None,
+            pyo3_cross_python_implementation: None,
+            pyo3_cross_python_version: Some("3.12".into()),
+        };
+
+        let host = triple!("x86_64-unknown-linux-gnu");
+        let target = triple!("wasm32-unknown-emscripten");
+        let cross_config =
+            CrossCompileConfig::try_from_env_vars_host_target(env_vars, &host, &target)
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(
+            default_cross_compile(&cross_config).unwrap(),
+            InterpreterConfig {
+                implementation: PythonImplementation::CPython,
+                version: PythonVersion {
+                    major: 3,
+                    minor: 12
+                },
+                shared: false,
+                abi3: false,
+                lib_name: Some("python3.12".into()),
+                lib_dir: None,
+                executable: None,
+                platform_tag: None,
+                python_framework_prefix: None,
+                pointer_width: Some(32),
+                pointer_width_inferred: true,
                 build_flags: BuildFlags::default(),
                 suppress_build_script_link_lines: false,
                 extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: vec![],
+                sysconfig_vars: BTreeMap::new(),
             }
         );
+
+        // Extension modules are side modules which never link libpython.
+        assert!(!target_requires_libpython(&target, true));
+    }
+
+    #[test]
+    fn pointer_width_provenance() {
+        let mut sysconfigdata = Sysconfigdata::new();
+        sysconfigdata.insert("SOABI", "cpython-39-aarch64-linux-gnu");
+        sysconfigdata.insert("VERSION", "3.9");
+        sysconfigdata.insert("Py_ENABLE_SHARED", "1");
+        sysconfigdata.insert("SIZEOF_VOID_P", "8");
+        let detected = InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap();
+        assert_eq!(detected.pointer_width, Some(64));
+        assert!(!detected.pointer_width_is_inferred());
+
+        let env_vars = CrossCompileEnvVars {
+            pyo3_cross: None,
+            pyo3_cross_lib_dir: None,
+            pyo3_cross_python_implementation: None,
+            pyo3_cross_python_version: Some("3.9".into()),
+        };
+        let host = triple!("x86_64-unknown-linux-gnu");
+        let target = triple!("armv7-unknown-linux-gnueabihf");
+        let cross_config =
+            CrossCompileConfig::try_from_env_vars_host_target(env_vars, &host, &target)
+                .unwrap()
+                .unwrap();
+        let inferred = default_cross_compile(&cross_config).unwrap();
+        assert_eq!(inferred.pointer_width, Some(32));
+        assert!(inferred.pointer_width_is_inferred());
+
+        // provenance survives serialization
+        let mut buf: Vec<u8> = Vec::new();
+        inferred.to_writer(&mut buf).unwrap();
+        assert!(InterpreterConfig::from_reader(&*buf)
+            .unwrap()
+            .pointer_width_is_inferred());
+
+        // an unset pointer width is never reported as inferred
+        let unknown = InterpreterConfig {
+            pointer_width: None,
+            ..inferred
+        };
+        assert!(!unknown.pointer_width_is_inferred());
     }
 
     #[test]
@@ -2306,6 +5795,7 @@ mod tests {
                 false,
                 false,
                 false,
+                false,
             ),
             "python37",
         );
@@ -2316,6 +5806,7 @@ mod tests {
                 true,
                 false,
                 false,
+                false,
             ),
             "python3",
         );
@@ -2326,6 +5817,7 @@ mod tests {
                 false,
                 true,
                 false,
+                false,
             ),
             "python3.7",
         );
@@ -2336,6 +5828,7 @@ mod tests {
                 true,
                 true,
                 false,
+                false,
             ),
             "python3",
         );
@@ -2346,6 +5839,7 @@ mod tests {
                 true,
                 false,
                 false,
+                false,
             ),
             "python37",
         );
@@ -2356,6 +5850,7 @@ mod tests {
                 false,
                 false,
                 true,
+                false,
             ),
             "python37_d",
         );
@@ -2368,9 +5863,39 @@ mod tests {
                 true,
                 false,
                 true,
+                false,
             ),
             "python37_d",
         );
+        // free-threaded builds
+        assert_eq!(
+            super::default_lib_name_windows(
+                PythonVersion {
+                    major: 3,
+                    minor: 13
+                },
+                CPython,
+                false,
+                false,
+                false,
+                true,
+            ),
+            "python313t",
+        );
+        assert_eq!(
+            super::default_lib_name_windows(
+                PythonVersion {
+                    major: 3,
+                    minor: 13
+                },
+                CPython,
+                false,
+                false,
+                true,
+                true,
+            ),
+            "python313t_d",
+        );
     }
 
     #[test]
@@ -2452,7 +5977,10 @@ mod tests {
             abi3: true,
             build_flags: BuildFlags::default(),
             pointer_width: None,
+            pointer_width_inferred: false,
             executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
             implementation: PythonImplementation::CPython,
             lib_dir: None,
             lib_name: None,
@@ -2460,6 +5988,11 @@ mod tests {
             version: PythonVersion { major: 3, minor: 7 },
             suppress_build_script_link_lines: false,
             extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
         };
 
         config
@@ -2474,7 +6007,10 @@ mod tests {
             abi3: true,
             build_flags: BuildFlags::new(),
             pointer_width: None,
+            pointer_width_inferred: false,
             executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
             implementation: PythonImplementation::CPython,
             lib_dir: None,
             lib_name: None,
@@ -2482,6 +6018,11 @@ mod tests {
             version: PythonVersion { major: 3, minor: 7 },
             suppress_build_script_link_lines: false,
             extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
         };
 
         assert!(config
@@ -2494,81 +6035,274 @@ mod tests {
     }
 
     #[test]
-    #[cfg(all(
-        target_os = "linux",
-        target_arch = "x86_64",
-        feature = "resolve-config"
-    ))]
-    fn parse_sysconfigdata() {
-        // A best effort attempt to get test coverage for the sysconfigdata parsing.
-        // Might not complete successfully depending on host installation; that's ok as long as
-        // CI demonstrates this path is covered!
-
-        let interpreter_config = crate::get();
-
-        let lib_dir = match &interpreter_config.lib_dir {
-            Some(lib_dir) => Path::new(lib_dir),
-            // Don't know where to search for sysconfigdata; never mind.
-            None => return,
+    fn test_cross_compile_version_note() {
+        let py39 = PythonVersion { major: 3, minor: 9 };
+        let py311 = PythonVersion {
+            major: 3,
+            minor: 11,
         };
+        assert_eq!(cross_compile_version_note(py39, py39), None);
+        assert_eq!(
+            cross_compile_version_note(py39, py311).as_deref(),
+            Some(
+                "cross compiling for Python 3.9, but the Python interpreter on the build host is version 3.11"
+            )
+        );
+    }
 
-        let cross = CrossCompileConfig {
-            lib_dir: Some(lib_dir.into()),
-            version: Some(interpreter_config.version),
-            implementation: Some(interpreter_config.implementation),
-            target: triple!("x86_64-unknown-linux-gnu"),
+    #[test]
+    fn test_fixup_lib_dir_for_python_home() {
+        let python_home = env::temp_dir().join(format!("pyo3-python-home-{}", std::process::id()));
+        let default_lib_dir = python_home.join(if cfg!(windows) { "libs" } else { "lib" });
+        std::fs::create_dir_all(&default_lib_dir).unwrap();
+        std::fs::create_dir_all(python_home.join("embedded").join("lib")).unwrap();
+
+        let config = |lib_dir: Option<&str>| InterpreterConfig {
+            lib_dir: lib_dir.map(str::to_owned),
+            ..InterpreterConfig::from_reader("version=3.11".as_bytes()).unwrap()
         };
+        let home = python_home.as_os_str();
 
-        let sysconfigdata_path = match find_sysconfigdata(&cross) {
-            Ok(Some(path)) => path,
-            // Couldn't find a matching sysconfigdata; never mind!
-            _ => return,
-        };
-        let sysconfigdata = super::parse_sysconfigdata(sysconfigdata_path).unwrap();
-        let parsed_config = InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap();
+        // missing lib_dir
+        let mut interpreter_config = config(None);
+        let note = fixup_lib_dir_for_python_home(&mut interpreter_config, home).unwrap();
+        let expected = default_lib_dir.to_str().unwrap();
+        assert_eq!(interpreter_config.lib_dir.as_deref(), Some(expected));
+        assert_eq!(
+            note,
+            format!(
+                "using PYTHONHOME to resolve the Python library directory to {}",
+                expected
+            )
+        );
 
+        // relative lib_dir
+        let relative = Path::new("embedded").join("lib");
+        let mut interpreter_config = config(relative.to_str());
+        assert!(fixup_lib_dir_for_python_home(&mut interpreter_config, home).is_some());
         assert_eq!(
-            parsed_config,
-            InterpreterConfig {
-                abi3: false,
-                build_flags: BuildFlags(interpreter_config.build_flags.0.clone()),
-                pointer_width: Some(64),
-                executable: None,
-                implementation: PythonImplementation::CPython,
-                lib_dir: interpreter_config.lib_dir.to_owned(),
-                lib_name: interpreter_config.lib_name.to_owned(),
-                shared: true,
-                version: interpreter_config.version,
-                suppress_build_script_link_lines: false,
-                extra_build_script_lines: vec![],
-            }
+            interpreter_config.lib_dir.as_deref(),
+            python_home.join(&relative).to_str()
+        );
+
+        // absolute lib_dir is left alone
+        let absolute = env::temp_dir().join("lib");
+        let mut interpreter_config = config(absolute.to_str());
+        assert_eq!(
+            fixup_lib_dir_for_python_home(&mut interpreter_config, home),
+            None
+        );
+        assert_eq!(interpreter_config.lib_dir.as_deref(), absolute.to_str());
+
+        // lib dirs which don't exist under PYTHONHOME are not used
+        let mut interpreter_config = config(Some("missing"));
+        assert_eq!(
+            fixup_lib_dir_for_python_home(&mut interpreter_config, home),
+            None
+        );
+        assert_eq!(interpreter_config.lib_dir.as_deref(), Some("missing"));
+
+        let mut interpreter_config = config(None);
+        assert_eq!(
+            fixup_lib_dir_for_python_home(&mut interpreter_config, OsStr::new("")),
+            None
+        );
+
+        std::fs::remove_dir_all(&python_home).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reject_python2_interpreter() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = env::temp_dir().join(format!("pyo3-python2-stub-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let stub = dir.join("python");
+        std::fs::write(
+            &stub,
+            "#!/bin/sh\n\
+            cat > /dev/null\n\
+            printf 'implementation CPython\\nversion_major 2\\nversion_minor 7\\nshared True\\n'\n",
         )
+        .unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let err = InterpreterConfig::from_interpreter(&stub).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "the Python interpreter at {} is Python 2.7, but Python 2 is not supported by PyO3\n\
+                = help: use a Python 3 interpreter, e.g. by setting PYO3_PYTHON=python3",
+                stub.display()
+            )
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
-    fn test_venv_interpreter() {
-        let base = OsStr::new("base");
+    fn test_find_libpython_in_dir() {
+        use PythonImplementation::*;
+        let py311 = PythonVersion {
+            major: 3,
+            minor: 11,
+        };
+        let make_dir = |name: &str, files: &[&str]| {
+            let dir = env::temp_dir().join(format!("pyo3-libdir-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            for file in files {
+                std::fs::write(dir.join(file), "").unwrap();
+            }
+            dir
+        };
+        let found = |lib_name: &str, shared| {
+            Some(FoundLibpython {
+                lib_name: lib_name.into(),
+                shared,
+            })
+        };
+
+        let dir = make_dir(
+            "mixed",
+            &[
+                "libpython3.11.so",
+                "libpython3.11.so.1.0",
+                "libpython3.11.a",
+                "libpython3.1.so",
+                "libpython3.12.so",
+                "libpython3.so",
+                "python3.11",
+            ],
+        );
+        assert_eq!(
+            find_libpython_in_dir(&dir, py311, CPython, None).unwrap(),
+            found("python3.11", true)
+        );
+        assert_eq!(
+            find_libpython_in_dir(&dir, py311, CPython, Some(false)).unwrap(),
+            found("python3.11", false)
+        );
+        assert_eq!(
+            find_libpython_in_dir(&dir, PythonVersion { major: 3, minor: 9 }, CPython, None)
+                .unwrap(),
+            None
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let dir = make_dir("static", &["libpython3.11d.a"]);
+        assert_eq!(
+            find_libpython_in_dir(&dir, py311, CPython, None).unwrap(),
+            found("python3.11d", false)
+        );
+        assert_eq!(
+            find_libpython_in_dir(&dir, py311, CPython, Some(true)).unwrap(),
+            None
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let dir = make_dir("mingw", &["libpython3.11.dll.a"]);
+        assert_eq!(
+            find_libpython_in_dir(&dir, py311, CPython, None).unwrap(),
+            found("python3.11", true)
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let dir = make_dir(
+            "pypy",
+            &["libpypy3.11-c.so", "libpypy3.10-c.so", "libpython3.11.so"],
+        );
+        assert_eq!(
+            find_libpython_in_dir(&dir, py311, PyPy, None).unwrap(),
+            found("pypy3.11-c", true)
+        );
         assert_eq!(
-            venv_interpreter(base, true),
-            PathBuf::from_iter(&["base", "Scripts", "python.exe"])
+            find_libpython_in_dir(&dir, py311, GraalPy, None).unwrap(),
+            None
         );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let dir = make_dir("ambiguous", &["libpython3.11.so", "libpython3.11d.so"]);
         assert_eq!(
-            venv_interpreter(base, false),
-            PathBuf::from_iter(&["base", "bin", "python"])
+            find_libpython_in_dir(&dir, py311, CPython, None)
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "found multiple Python 3.11 libraries in {}: libpython3.11.so, libpython3.11d.so\n\
+                = help: set PYO3_CROSS_LIB_DIR to a directory containing only the library to link",
+                dir.display()
+            )
         );
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
-    fn test_conda_env_interpreter() {
-        let base = OsStr::new("base");
+    fn macos_framework_dirs() {
+        let dir = env::temp_dir().join(format!("pyo3-framework-dirs-{}", std::process::id()));
+        let display = |path: &Path| path.display().to_string();
+
+        // python.org installers (also universal2): the reported directories exist
+        let prefix = dir.join("Library").join("Frameworks");
+        let base_prefix = prefix.join("Python.framework/Versions/3.12");
+        std::fs::create_dir_all(base_prefix.join("lib")).unwrap();
+        assert_eq!(
+            framework_dirs(
+                &base_prefix,
+                Some(display(&base_prefix.join("lib"))),
+                display(&prefix)
+            ),
+            (
+                Some(display(&base_prefix.join("lib"))),
+                Some(display(&prefix))
+            )
+        );
+
+        // Xcode command line tools: the reported directories are those inside Xcode.app
+        let prefix = dir.join("CommandLineTools/Library/Frameworks");
+        let base_prefix = prefix.join("Python3.framework/Versions/3.9");
+        std::fs::create_dir_all(base_prefix.join("lib")).unwrap();
+        assert_eq!(
+            framework_dirs(
+                &base_prefix,
+                Some("/Applications/Xcode.app/Contents/Developer/Library/Frameworks/Python3.framework/Versions/3.9/lib".into()),
+                "/Applications/Xcode.app/Contents/Developer/Library/Frameworks".into()
+            ),
+            (
+                Some(display(&base_prefix.join("lib"))),
+                Some(display(&prefix))
+            )
+        );
+
+        // Homebrew: directories inside the keg go through the `opt` link
+        let keg_prefix = dir.join("Cellar/python@3.12/3.12.4/Frameworks");
+        let opt_prefix = dir.join("opt/python@3.12/Frameworks");
+        let framework = Path::new("Python.framework/Versions/3.12");
+        std::fs::create_dir_all(keg_prefix.join(framework).join("lib")).unwrap();
+        std::fs::create_dir_all(opt_prefix.join(framework).join("lib")).unwrap();
+        assert_eq!(
+            framework_dirs(&keg_prefix.join(framework), None, display(&keg_prefix)),
+            (
+                Some(display(&opt_prefix.join(framework).join("lib"))),
+                Some(display(&opt_prefix))
+            )
+        );
+
+        // Nothing better is known for other layouts, or if no directory exists.
+        assert_eq!(
+            framework_dirs(&dir, Some("/install/lib".into()), "/install".into()),
+            (Some("/install/lib".into()), Some("/install".into()))
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
         assert_eq!(
-            conda_env_interpreter(base, true),
-            PathBuf::from_iter(&["base", "python.exe"])
+            homebrew_opt_path(Path::new("/opt/homebrew/Cellar/python@3.12/3.12.4/bin")),
+            Some(PathBuf::from("/opt/homebrew/opt/python@3.12/bin"))
         );
         assert_eq!(
-            conda_env_interpreter(base, false),
-            PathBuf::from_iter(&["base", "bin", "python"])
+            homebrew_opt_path(Path::new("/opt/homebrew/Cellar/python@3.12")),
+            None
         );
+        assert_eq!(homebrew_opt_path(Path::new("/usr/lib")), None);
     }
 
     #[test]
@@ -2642,26 +6376,83 @@ mod tests {
     }
 
     #[test]
-    fn test_build_script_outputs_base() {
+    fn test_build_script_outputs_base() {
+        let interpreter_config = InterpreterConfig {
+            implementation: PythonImplementation::CPython,
+            version: PythonVersion { major: 3, minor: 8 },
+            shared: true,
+            abi3: false,
+            lib_name: Some("python3".into()),
+            lib_dir: None,
+            executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
+            pointer_width: None,
+            pointer_width_inferred: false,
+            build_flags: BuildFlags::default(),
+            suppress_build_script_link_lines: false,
+            extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+        assert_eq!(
+            interpreter_config.build_script_outputs(),
+            [
+                "cargo:rustc-cfg=Py_3_6".to_owned(),
+                "cargo:rustc-cfg=Py_3_7".to_owned(),
+                "cargo:rustc-cfg=Py_3_8".to_owned(),
+            ]
+        );
+
+        let interpreter_config = InterpreterConfig {
+            implementation: PythonImplementation::PyPy,
+            ..interpreter_config
+        };
+        assert_eq!(
+            interpreter_config.build_script_outputs(),
+            [
+                "cargo:rustc-cfg=Py_3_6".to_owned(),
+                "cargo:rustc-cfg=Py_3_7".to_owned(),
+                "cargo:rustc-cfg=Py_3_8".to_owned(),
+                "cargo:rustc-cfg=PyPy".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_script_outputs_abi3() {
         let interpreter_config = InterpreterConfig {
             implementation: PythonImplementation::CPython,
-            version: PythonVersion { major: 3, minor: 8 },
+            version: PythonVersion { major: 3, minor: 7 },
             shared: true,
-            abi3: false,
+            abi3: true,
             lib_name: Some("python3".into()),
             lib_dir: None,
             executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
             pointer_width: None,
+            pointer_width_inferred: false,
             build_flags: BuildFlags::default(),
             suppress_build_script_link_lines: false,
             extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
         };
+
         assert_eq!(
             interpreter_config.build_script_outputs(),
             [
                 "cargo:rustc-cfg=Py_3_6".to_owned(),
                 "cargo:rustc-cfg=Py_3_7".to_owned(),
-                "cargo:rustc-cfg=Py_3_8".to_owned(),
+                "cargo:rustc-cfg=Py_LIMITED_API".to_owned(),
+                "cargo:rustc-cfg=abi3".to_owned(),
             ]
         );
 
@@ -2674,14 +6465,49 @@ mod tests {
             [
                 "cargo:rustc-cfg=Py_3_6".to_owned(),
                 "cargo:rustc-cfg=Py_3_7".to_owned(),
-                "cargo:rustc-cfg=Py_3_8".to_owned(),
                 "cargo:rustc-cfg=PyPy".to_owned(),
+                "cargo:rustc-cfg=Py_LIMITED_API".to_owned(),
+                "cargo:rustc-cfg=abi3".to_owned(),
             ]
         );
     }
 
     #[test]
-    fn test_build_script_outputs_abi3() {
+    fn test_build_script_outputs_resolved_executable() {
+        let interpreter_config = InterpreterConfig {
+            implementation: PythonImplementation::CPython,
+            version: PythonVersion { major: 3, minor: 7 },
+            shared: true,
+            abi3: false,
+            lib_name: Some("python3.7".into()),
+            lib_dir: None,
+            executable: Some("/usr/bin/python3.7".into()),
+            platform_tag: None,
+            python_framework_prefix: None,
+            pointer_width: None,
+            pointer_width_inferred: false,
+            build_flags: BuildFlags::default(),
+            suppress_build_script_link_lines: false,
+            extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+
+        assert_eq!(
+            interpreter_config.build_script_outputs(),
+            [
+                "cargo:rustc-cfg=Py_3_6".to_owned(),
+                "cargo:rustc-cfg=Py_3_7".to_owned(),
+                "cargo:rustc-env=PYO3_PYTHON_RESOLVED=/usr/bin/python3.7".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_script_outputs_abi3_strict() {
         let interpreter_config = InterpreterConfig {
             implementation: PythonImplementation::CPython,
             version: PythonVersion { major: 3, minor: 7 },
@@ -2690,36 +6516,488 @@ mod tests {
             lib_name: Some("python3".into()),
             lib_dir: None,
             executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
             pointer_width: None,
+            pointer_width_inferred: false,
             build_flags: BuildFlags::default(),
             suppress_build_script_link_lines: false,
             extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
         };
 
         assert_eq!(
-            interpreter_config.build_script_outputs(),
+            interpreter_config.build_script_outputs_with_strictness(true),
             [
                 "cargo:rustc-cfg=Py_3_6".to_owned(),
                 "cargo:rustc-cfg=Py_3_7".to_owned(),
                 "cargo:rustc-cfg=Py_LIMITED_API".to_owned(),
+                "cargo:rustc-cfg=abi3".to_owned(),
+                "cargo:rustc-cfg=py_sys_config=\"ABI3_STRICT\"".to_owned(),
             ]
         );
 
+        // not strict
+        assert!(!interpreter_config
+            .build_script_outputs_with_strictness(false)
+            .contains(&"cargo:rustc-cfg=py_sys_config=\"ABI3_STRICT\"".to_owned()));
+
+        // strict has no effect on version-specific builds
         let interpreter_config = InterpreterConfig {
-            implementation: PythonImplementation::PyPy,
+            abi3: false,
             ..interpreter_config
         };
         assert_eq!(
-            interpreter_config.build_script_outputs(),
+            interpreter_config.build_script_outputs_with_strictness(true),
             [
                 "cargo:rustc-cfg=Py_3_6".to_owned(),
                 "cargo:rustc-cfg=Py_3_7".to_owned(),
-                "cargo:rustc-cfg=PyPy".to_owned(),
-                "cargo:rustc-cfg=Py_LIMITED_API".to_owned(),
             ]
         );
     }
 
+    #[test]
+    fn abi3_debug_build() {
+        let mut build_flags = BuildFlags::default();
+        build_flags.0.insert(BuildFlag::Py_DEBUG);
+        let interpreter_config = InterpreterConfig {
+            implementation: PythonImplementation::CPython,
+            version: PythonVersion { major: 3, minor: 9 },
+            shared: true,
+            abi3: true,
+            lib_name: Some("python3".into()),
+            lib_dir: None,
+            executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
+            pointer_width: None,
+            pointer_width_inferred: false,
+            build_flags,
+            suppress_build_script_link_lines: false,
+            extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+        assert!(interpreter_config.is_debug_build());
+
+        let warning = interpreter_config
+            .check_abi3_debug_build(false)
+            .unwrap()
+            .unwrap();
+        assert!(warning.contains("debug build"));
+
+        let error = interpreter_config
+            .check_abi3_debug_build(true)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("debug build"));
+        assert!(error.contains("PYO3_ABI3_STRICT"));
+
+        // release builds and non-abi3 debug builds are fine
+        let release_config = InterpreterConfig {
+            build_flags: BuildFlags::default(),
+            ..interpreter_config
+        };
+        assert!(!release_config.is_debug_build());
+        assert_eq!(release_config.check_abi3_debug_build(true).unwrap(), None);
+
+        let mut build_flags = BuildFlags::default();
+        build_flags.0.insert(BuildFlag::Py_DEBUG);
+        let debug_config = InterpreterConfig {
+            abi3: false,
+            build_flags,
+            ..release_config
+        };
+        assert_eq!(debug_config.check_abi3_debug_build(true).unwrap(), None);
+    }
+
+    #[test]
+    fn link_libraries() {
+        let interpreter_config = InterpreterConfig {
+            implementation: PythonImplementation::CPython,
+            version: PythonVersion { major: 3, minor: 9 },
+            shared: true,
+            abi3: false,
+            lib_name: Some("python3.9".into()),
+            lib_dir: Some("/usr/lib".into()),
+            executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
+            pointer_width: None,
+            pointer_width_inferred: false,
+            build_flags: BuildFlags::default(),
+            suppress_build_script_link_lines: false,
+            extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+        let macos = triple!("x86_64-apple-darwin");
+        let linux = triple!("x86_64-unknown-linux-gnu");
+        let windows = triple!("x86_64-pc-windows-msvc");
+
+        // extension modules resolve Python symbols at load time
+        assert_eq!(
+            interpreter_config.link_libraries_for_target(&macos, true),
+            []
+        );
+        assert_eq!(
+            interpreter_config.link_libraries_for_target(&linux, true),
+            []
+        );
+
+        // embedding links libpython
+        let libpython = LinkLib {
+            name: "python3.9".into(),
+            kind: LinkLibKind::Dylib,
+        };
+        assert_eq!(
+            interpreter_config.link_libraries_for_target(&linux, false),
+            vec![libpython.clone()]
+        );
+        assert_eq!(
+            libpython.to_cargo_directive(&linux),
+            "cargo:rustc-link-lib=python3.9"
+        );
+
+        // windows always links, with an alias
+        let windows_config = InterpreterConfig {
+            lib_name: Some("python39".into()),
+            ..interpreter_config
+        };
+        let libs = windows_config.link_libraries_for_target(&windows, true);
+        assert_eq!(
+            libs.iter()
+                .map(|lib| lib.to_cargo_directive(&windows))
+                .collect::<Vec<_>>(),
+            ["cargo:rustc-link-lib=pythonXY:python39"]
+        );
+
+        // static embedding
+        let static_config = InterpreterConfig {
+            shared: false,
+            lib_name: Some("python3.9".into()),
+            ..windows_config
+        };
+        assert_eq!(
+            static_config.link_libraries_for_target(&linux, false),
+            [LinkLib {
+                name: "python3.9".into(),
+                kind: LinkLibKind::Static,
+            }]
+        );
+
+        // macOS framework builds are always linked dynamically, even without Py_ENABLE_SHARED
+        let mut sysconfigdata = Sysconfigdata::new();
+        sysconfigdata.insert("SOABI", "cpython-39-darwin");
+        sysconfigdata.insert("VERSION", "3.9");
+        sysconfigdata.insert("Py_ENABLE_SHARED", "0");
+        sysconfigdata.insert("PYTHONFRAMEWORK", "Python");
+        sysconfigdata.insert("LDVERSION", "3.9");
+        sysconfigdata.insert("SIZEOF_VOID_P", "8");
+        let framework_config = InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap();
+        assert_eq!(
+            framework_config.link_libraries_for_target(&macos, false),
+            vec![libpython]
+        );
+        assert_eq!(framework_config.link_libraries_for_target(&macos, true), []);
+        assert_eq!(
+            framework_config.framework_search_directive_for_target(&macos, false),
+            None
+        );
+
+        // frameworks named by lib_name, like Xcode's Python3, are linked as frameworks
+        let prefix = env::temp_dir().join(format!("pyo3-link-framework-{}", std::process::id()));
+        std::fs::create_dir_all(prefix.join("Python3.framework")).unwrap();
+        let xcode_config = InterpreterConfig {
+            lib_name: Some("Python3".into()),
+            python_framework_prefix: Some(prefix.display().to_string()),
+            ..framework_config
+        };
+        let framework = LinkLib {
+            name: "Python3".into(),
+            kind: LinkLibKind::Framework,
+        };
+        assert_eq!(
+            xcode_config.link_libraries_for_target(&macos, false),
+            vec![framework.clone()]
+        );
+        assert_eq!(
+            framework.to_cargo_directive(&macos),
+            "cargo:rustc-link-lib=framework=Python3"
+        );
+        assert_eq!(
+            xcode_config.framework_search_directive_for_target(&macos, false),
+            Some(format!(
+                "cargo:rustc-link-search=framework={}",
+                prefix.display()
+            ))
+        );
+        assert_eq!(xcode_config.link_libraries_for_target(&macos, true), []);
+        assert_eq!(
+            xcode_config.link_libraries_for_target(&linux, false),
+            vec![LinkLib {
+                name: "Python3".into(),
+                kind: LinkLibKind::Dylib,
+            }]
+        );
+        std::fs::remove_dir_all(&prefix).unwrap();
+    }
+
+    #[test]
+    fn python_framework_link_args() {
+        let config =
+            InterpreterConfigBuilder::new(PythonImplementation::CPython, PythonVersion::PY312)
+                .python_framework_prefix("/opt/homebrew/opt/python@3.12/Frameworks")
+                .build()
+                .unwrap();
+        let macos = triple!("aarch64-apple-darwin");
+        assert_eq!(
+            config.python_framework_link_args_for_target(&macos, true),
+            ["cargo:rustc-link-arg=-Wl,-rpath,/opt/homebrew/opt/python@3.12/Frameworks"]
+        );
+        // Extension modules don't link libpython on macOS.
+        assert!(config
+            .python_framework_link_args_for_target(&macos, false)
+            .is_empty());
+        assert!(config
+            .python_framework_link_args_for_target(&triple!("x86_64-unknown-linux-gnu"), true)
+            .is_empty());
+
+        let not_framework = InterpreterConfig {
+            python_framework_prefix: None,
+            ..config
+        };
+        assert!(not_framework
+            .python_framework_link_args_for_target(&macos, true)
+            .is_empty());
+    }
+
+    #[test]
+    fn cargo_dep_env_lines() {
+        let config = InterpreterConfigBuilder::new(
+            PythonImplementation::CPython,
+            PythonVersion {
+                major: 3,
+                minor: 12,
+            },
+        )
+        .lib_name("python3.12")
+        .lib_dir("/usr/lib")
+        .pointer_width(64)
+        .build()
+        .unwrap();
+
+        let lines = config.cargo_dep_env_lines().unwrap();
+        let mut buf = Vec::new();
+        config.to_writer(&mut buf).unwrap();
+        assert_eq!(
+            lines,
+            [
+                format!("cargo:PYO3_CONFIG={}", escape(&buf)),
+                "cargo:VERSION=3.12".to_owned(),
+                "cargo:IMPLEMENTATION=CPython".to_owned(),
+                "cargo:ABI3=false".to_owned(),
+                "cargo:SHARED=true".to_owned(),
+                "cargo:LIB_NAME=python3.12".to_owned(),
+                "cargo:LIB_DIR=/usr/lib".to_owned(),
+                "cargo:POINTER_WIDTH=64".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn interpreter_probe_cache() {
+        let dir = env::temp_dir().join(format!("pyo3-probe-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let interpreter = dir.join("python3");
+        std::fs::write(&interpreter, "").unwrap();
+
+        let key = probe_cache_key(&interpreter, "print(1)", &[]).unwrap();
+        assert!(key.starts_with(&format!(
+            "{}\t",
+            std::fs::canonicalize(&interpreter).unwrap().display()
+        )));
+        assert_eq!(probe_cache_key(&interpreter, "print(1)", &[]).unwrap(), key);
+        assert_ne!(probe_cache_key(&interpreter, "print(2)", &[]).unwrap(), key);
+        assert_ne!(
+            probe_cache_key(&interpreter, "print(1)", &["MULTIARCH".to_owned()]).unwrap(),
+            key
+        );
+        assert_eq!(probe_cache_key(&dir.join("missing"), "print(1)", &[]), None);
+
+        let cache = dir.join(PROBE_CACHE_FILE_NAME);
+        assert_eq!(read_probe_cache(&cache, &key), None);
+        std::fs::write(
+            &cache,
+            format!("{}\nversion_major 3\nversion_minor 12\n", key),
+        )
+        .unwrap();
+        assert_eq!(
+            read_probe_cache(&cache, &key).as_deref(),
+            Some("version_major 3\nversion_minor 12\n")
+        );
+        assert_eq!(read_probe_cache(&cache, "other key"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn abi3_version_env() {
+        assert_eq!(
+            parse_abi3_version(OsStr::new("3.9")).unwrap(),
+            PythonVersion { major: 3, minor: 9 }
+        );
+        assert_eq!(
+            parse_abi3_version(OsStr::new("3.6"))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "PYO3_ABI3_VERSION=3.6 is not supported; the abi3 minimum version must be \
+                between 3.7 and 3.{}",
+                ABI3_MAX_MINOR
+            )
+        );
+        assert!(parse_abi3_version(OsStr::new(&format!("3.{}", ABI3_MAX_MINOR + 1))).is_err());
+        assert_eq!(
+            parse_abi3_version(OsStr::new("39"))
+                .unwrap_err()
+                .to_string(),
+            "failed to parse PYO3_ABI3_VERSION"
+        );
+    }
+
+    #[test]
+    fn abi3_version_features() {
+        assert_eq!(abi3_version_from_feature_minors(&[]).unwrap(), None);
+        assert_eq!(
+            abi3_version_from_feature_minors(&[11]).unwrap(),
+            Some(PythonVersion {
+                major: 3,
+                minor: 11
+            })
+        );
+        assert_eq!(
+            abi3_version_from_feature_minors(&[8, 11])
+                .unwrap_err()
+                .to_string(),
+            "conflicting abi3 minimum versions: the features abi3-py38, abi3-py311 are all \
+            enabled\n\
+            = help: crates in the dependency graph enable different `abi3-py3*` features of \
+            PyO3; run `cargo tree -e features -i pyo3` to find them\n\
+            = help: make the features agree, or set PYO3_ABI3_VERSION to choose the minimum \
+            version explicitly"
+        );
+    }
+
+    #[test]
+    fn link_model() {
+        let dir = env::temp_dir().join(format!("pyo3-link-model-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let static_config = InterpreterConfigBuilder::new(
+            PythonImplementation::CPython,
+            PythonVersion {
+                major: 3,
+                minor: 12,
+            },
+        )
+        .shared(false)
+        .lib_name("python3.12")
+        .lib_dir(dir.to_str().unwrap())
+        .build()
+        .unwrap();
+        let linux = triple!("x86_64-unknown-linux-musl");
+        let windows = triple!("x86_64-pc-windows-msvc");
+
+        assert_eq!(
+            static_config.link_model_for_target(&linux, true),
+            LinkModel::Extension
+        );
+        assert_eq!(
+            static_config.link_model_for_target(&linux, false),
+            LinkModel::Static
+        );
+        assert_eq!(
+            InterpreterConfig {
+                shared: true,
+                ..static_config.clone()
+            }
+            .link_model_for_target(&linux, false),
+            LinkModel::Shared
+        );
+
+        // extension modules don't need the static library
+        static_config
+            .check_link_model_for_target(&linux, true)
+            .unwrap();
+        assert_eq!(
+            static_config
+                .check_link_model_for_target(&linux, false)
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "the Python installation is configured for static linking, but {} does not exist\n\
+                = help: install the static Python library, or use a Python built with `--enable-shared`",
+                dir.join("libpython3.12.a").display()
+            )
+        );
+        // Windows static libraries can't be told apart from import libraries
+        static_config
+            .check_link_model_for_target(&windows, false)
+            .unwrap();
+
+        std::fs::write(dir.join("libpython3.12.a"), "").unwrap();
+        static_config
+            .check_link_model_for_target(&linux, false)
+            .unwrap();
+
+        let pypy_config = InterpreterConfig {
+            implementation: PythonImplementation::PyPy,
+            ..static_config
+        };
+        assert_eq!(
+            pypy_config
+                .check_link_model_for_target(&linux, false)
+                .unwrap_err()
+                .to_string(),
+            "PyPy cannot be linked statically\n= help: set `shared=true` in the PyO3 config file"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn extension_module_env_override() {
+        // unset follows the cargo feature
+        assert!(resolve_extension_module(None, true).unwrap());
+        assert!(!resolve_extension_module(None, false).unwrap());
+
+        // override on
+        assert!(resolve_extension_module(Some(OsStr::new("1")), false).unwrap());
+        assert!(resolve_extension_module(Some(OsStr::new("1")), true).unwrap());
+
+        // override off
+        assert!(!resolve_extension_module(Some(OsStr::new("0")), true).unwrap());
+        assert!(!resolve_extension_module(Some(OsStr::new("0")), false).unwrap());
+
+        assert_eq!(
+            resolve_extension_module(Some(OsStr::new("yes")), true)
+                .unwrap_err()
+                .to_string(),
+            "expected `0` or `1` for PYO3_EXTENSION_MODULE, got `yes`"
+        );
+    }
+
     #[test]
     fn test_build_script_outputs_debug() {
         let mut build_flags = BuildFlags::default();
@@ -2732,10 +7010,18 @@ mod tests {
             lib_name: Some("python3".into()),
             lib_dir: None,
             executable: None,
+            platform_tag: None,
+            python_framework_prefix: None,
             pointer_width: None,
+            pointer_width_inferred: false,
             build_flags,
             suppress_build_script_link_lines: false,
             extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
         };
 
         assert_eq!(
@@ -2747,4 +7033,66 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_build_info() {
+        let mut build_flags = BuildFlags::default();
+        build_flags.0.insert(BuildFlag::Py_DEBUG);
+        let interpreter_config = InterpreterConfig {
+            implementation: PythonImplementation::CPython,
+            version: PythonVersion {
+                major: 3,
+                minor: 12,
+            },
+            shared: true,
+            abi3: false,
+            lib_name: Some("python3.12".into()),
+            lib_dir: Some("/usr/lib".into()),
+            executable: Some("/usr/bin/python3".into()),
+            platform_tag: Some("linux-x86_64".into()),
+            python_framework_prefix: None,
+            pointer_width: Some(64),
+            pointer_width_inferred: false,
+            build_flags,
+            suppress_build_script_link_lines: false,
+            extra_build_script_lines: vec![],
+            extra_link_args: vec![],
+            extra_link_args_by_os: BTreeMap::new(),
+            extra_link_search_paths: vec![],
+            config_args: vec![],
+            sysconfig_vars: BTreeMap::new(),
+        };
+
+        assert_eq!(
+            interpreter_config.build_info(),
+            [
+                ("implementation", "CPython".to_owned()),
+                ("version", "3.12".to_owned()),
+                ("abi3", "false".to_owned()),
+                ("shared", "true".to_owned()),
+                ("pointer_width", "64".to_owned()),
+                ("platform_tag", "linux-x86_64".to_owned()),
+                ("build_flags", "Py_DEBUG".to_owned()),
+            ]
+        );
+
+        let interpreter_config = InterpreterConfig {
+            implementation: PythonImplementation::PyPy,
+            abi3: true,
+            pointer_width: None,
+            platform_tag: None,
+            build_flags: BuildFlags::default(),
+            ..interpreter_config
+        };
+        assert_eq!(
+            interpreter_config.build_info(),
+            [
+                ("implementation", "PyPy".to_owned()),
+                ("version", "3.12".to_owned()),
+                ("abi3", "true".to_owned()),
+                ("shared", "true".to_owned()),
+                ("build_flags", "".to_owned()),
+            ]
+        );
+    }
 }
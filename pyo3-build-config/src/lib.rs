@@ -4,27 +4,56 @@
 //! which depend on PyO3.
 //!
 //! It used internally by the PyO3 crate's build script to apply the same configuration.
+//!
+//! # Stability
+//!
+//! The items documented here are the public API of this crate, which follows semver together
+//! with PyO3. Packaging tools which need to locate and inspect Python the same way PyO3 does
+//! should use:
+//!
+//! - [`get`], the configuration PyO3 was built with (requires the `resolve-config` feature),
+//! - [`find_interpreter`] and [`InterpreterConfig::from_interpreter`], to probe an interpreter
+//!   (requires the `resolve-config` feature),
+//! - [`InterpreterConfig::from_path`], [`InterpreterConfig::from_reader`],
+//!   [`InterpreterConfig::to_path`] and [`InterpreterConfig::to_writer`], to read and write PyO3
+//!   config files, and [`InterpreterConfigBuilder`] to create them.
+//!
+//! Items which are `#[doc(hidden)]`, including everything in `pyo3_build_script_impl`, are
+//! implementation details of PyO3's own build scripts and may change in any release.
 
 #![warn(elided_lifetimes_in_paths, unused_lifetimes)]
 
-mod errors;
+// Public only so that the `warn!` macro can be used from other crates' build scripts.
+#[doc(hidden)]
+pub mod errors;
 mod impl_;
 
 #[cfg(feature = "resolve-config")]
+use std::io::Cursor;
+#[cfg(feature = "resolve-config")]
+use std::sync::Mutex;
+
 use std::{
-    io::Cursor,
+    env,
     path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
 };
 
-use std::{env, process::Command, str::FromStr};
-
+use errors::Result;
 use once_cell::sync::OnceCell;
 
+pub use errors::{Error, ErrorReport};
+#[cfg(feature = "resolve-config")]
+pub use impl_::find_interpreter;
 pub use impl_::{
-    cross_compiling_from_to, find_all_sysconfigdata, parse_sysconfigdata, BuildFlag, BuildFlags,
-    CrossCompileConfig, InterpreterConfig, PythonImplementation, PythonVersion, Triple,
+    check_support, cross_compiling_from_to, find_all_sysconfigdata, parse_sysconfigdata,
+    search_sysconfigdata, validate_config_file, BuildFlag, BuildFlags, CrossCompileConfig,
+    InterpreterConfig, InterpreterConfigBuilder, LinkLib, LinkLibKind, LinkModel,
+    PythonImplementation, PythonVersion, PythonVersionReq, SysconfigdataSearch, TargetMismatch,
+    Triple,
 };
-use target_lexicon::OperatingSystem;
+use target_lexicon::{Environment, OperatingSystem};
 
 /// Adds all the [`#[cfg]` flags](index.html) to the current compilation.
 ///
@@ -36,10 +65,18 @@ use target_lexicon::OperatingSystem;
 /// | ---- | ----------- |
 /// | `#[cfg(Py_3_7)]`, `#[cfg(Py_3_8)]`, `#[cfg(Py_3_9)]`, `#[cfg(Py_3_10)]` | These attributes mark code only for a given Python version and up. For example, `#[cfg(Py_3_7)]` marks code which can run on Python 3.7 **and newer**. |
 /// | `#[cfg(Py_LIMITED_API)]` | This marks code which is run when compiling with PyO3's `abi3` feature enabled. |
+/// | `#[cfg(abi3)]` | An alias of `#[cfg(Py_LIMITED_API)]`, always set together with it. |
+/// | `#[cfg(py_subinterpreters)]` | This marks code which uses subinterpreters with their own GIL, which are supported from CPython 3.12 when not compiling for `abi3`. |
+/// | `#[cfg(py_tp_vectorcall)]`, `#[cfg(py_tp_watched)]`, `#[cfg(py_managed_dict)]` | These mark code which depends on the layout of CPython type objects: `tp_vectorcall` being used to call types (3.9+), the `tp_watched` field (3.12+) and `Py_TPFLAGS_MANAGED_DICT` (3.12+). They are not set for `abi3` builds. |
 /// | `#[cfg(PyPy)]` | This marks code which is run when compiling for PyPy. |
 /// | `#[cfg(GraalPy)]` | This marks code which is run when compiling for GraalPy. |
+/// | `#[cfg(Py_GIL_DISABLED)]` | This marks code which is run when compiling for a free-threaded build of CPython (3.13+), e.g. `python3.13t`. |
 ///
 /// For examples of how to use these attributes, [see PyO3's guide](https://pyo3.rs/latest/building-and-distribution/multiple_python_versions.html).
+///
+/// When the path of the Python interpreter is known, it is also made available to the current
+/// compilation as the `PYO3_PYTHON_RESOLVED` environment variable, which can be read with
+/// `env!("PYO3_PYTHON_RESOLVED")` (or `option_env!` if the interpreter may not be known).
 #[cfg(feature = "resolve-config")]
 pub fn use_pyo3_cfgs() {
     print_expected_cfgs();
@@ -54,10 +91,12 @@ pub fn use_pyo3_cfgs() {
 ///
 /// The following link flags are added:
 /// - macOS: `-undefined dynamic_lookup`
+/// - Android: `-llog`, as libpython on Android writes its output to the Android log
 /// - wasm32-unknown-emscripten: `-sSIDE_MODULE=2 -sWASM_BIGINT`
 ///
 /// All other platforms currently are no-ops, however this may change as necessary
-/// in future.
+/// in future. In particular iOS extension modules link to libpython instead of using
+/// `-undefined dynamic_lookup`, which iOS does not allow.
 pub fn add_extension_module_link_args() {
     _add_extension_module_link_args(&impl_::target_triple_from_env(), std::io::stdout())
 }
@@ -66,39 +105,188 @@ fn _add_extension_module_link_args(triple: &Triple, mut writer: impl std::io::Wr
     if triple.operating_system == OperatingSystem::Darwin {
         writeln!(writer, "cargo:rustc-cdylib-link-arg=-undefined").unwrap();
         writeln!(writer, "cargo:rustc-cdylib-link-arg=dynamic_lookup").unwrap();
+    } else if matches!(
+        triple.environment,
+        Environment::Android | Environment::Androideabi
+    ) {
+        writeln!(writer, "cargo:rustc-cdylib-link-arg=-llog").unwrap();
     } else if triple == &Triple::from_str("wasm32-unknown-emscripten").unwrap() {
         writeln!(writer, "cargo:rustc-cdylib-link-arg=-sSIDE_MODULE=2").unwrap();
         writeln!(writer, "cargo:rustc-cdylib-link-arg=-sWASM_BIGINT").unwrap();
     }
 }
 
+/// Adds linker arguments to load a macOS framework build of Python, for binaries which embed
+/// Python.
+///
+/// This should be called from a build script, and has no effect for extension modules, which
+/// don't link libpython on macOS.
+///
+/// Framework builds (the python.org installers, Homebrew and the Python shipped with Xcode)
+/// install libpython as `Python.framework` in
+/// [`InterpreterConfig::python_framework_prefix`]. Some of them, such as Xcode's, give the
+/// framework an `@rpath`-relative install name, so the following link flag is added for the
+/// binary to find it when it runs:
+/// - macOS: `-Wl,-rpath,<python_framework_prefix>`
+///
+/// All other platforms and Python builds currently are no-ops.
+#[cfg(feature = "resolve-config")]
+pub fn add_python_framework_link_args() {
+    let target = impl_::target_triple_from_env();
+    for line in get().python_framework_link_args_for_target(&target, impl_::is_linking_libpython())
+    {
+        println!("{}", line);
+    }
+}
+
 /// Loads the configuration determined from the build environment.
 ///
 /// Because this will never change in a given compilation run, this is cached in a `once_cell`.
+///
+/// If a resolver was registered with [`set_lib_dir_resolver`], it is used to fill in an empty
+/// `lib_dir`. Then, if a hook was registered with [`on_config_resolved`], it is applied to the
+/// configuration before it is validated and cached.
 #[cfg(feature = "resolve-config")]
 pub fn get() -> &'static InterpreterConfig {
     static CONFIG: OnceCell<InterpreterConfig> = OnceCell::new();
     CONFIG.get_or_init(|| {
-        // Check if we are in a build script and cross compiling to a different target.
-        let cross_compile_config_path = resolve_cross_compile_config_path();
-        let cross_compiling = cross_compile_config_path
-            .as_ref()
-            .map(|path| path.exists())
-            .unwrap_or(false);
+        let interpreter_config = resolve_config().expect("failed to parse PyO3 config");
+        let hook = CONFIG_RESOLVED_HOOK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        finish_config(interpreter_config, LIB_DIR_RESOLVER.get(), hook.as_ref())
+            .expect("invalid PyO3 config after applying config resolved hook")
+    })
+}
 
-        // CONFIG_FILE is generated in build.rs, so it's content can vary
-        #[allow(unknown_lints, clippy::const_is_empty)]
-        if let Some(interpreter_config) = InterpreterConfig::from_cargo_dep_env() {
-            interpreter_config
-        } else if !CONFIG_FILE.is_empty() {
-            InterpreterConfig::from_reader(Cursor::new(CONFIG_FILE))
-        } else if cross_compiling {
-            InterpreterConfig::from_path(cross_compile_config_path.as_ref().unwrap())
-        } else {
-            InterpreterConfig::from_reader(Cursor::new(HOST_CONFIG))
+#[cfg(feature = "resolve-config")]
+fn resolve_config() -> Result<InterpreterConfig> {
+    // Check if we are in a build script and cross compiling to a different target.
+    let cross_compile_config_path = resolve_cross_compile_config_path();
+    let cross_compiling = cross_compile_config_path
+        .as_ref()
+        .map(|path| path.exists())
+        .unwrap_or(false);
+
+    // CONFIG_FILE is generated in build.rs, so it's content can vary
+    #[allow(unknown_lints, clippy::const_is_empty)]
+    if let Some(interpreter_config) = InterpreterConfig::from_cargo_dep_env() {
+        interpreter_config
+    } else if !CONFIG_FILE.is_empty() {
+        InterpreterConfig::from_reader(Cursor::new(CONFIG_FILE))
+    } else if cross_compiling {
+        InterpreterConfig::from_path(cross_compile_config_path.as_ref().unwrap())
+    } else {
+        InterpreterConfig::from_reader(Cursor::new(HOST_CONFIG))
+    }
+}
+
+type ConfigResolvedHook = Box<dyn Fn(&mut InterpreterConfig) + Send + Sync>;
+
+#[cfg(feature = "resolve-config")]
+static CONFIG_RESOLVED_HOOK: Mutex<Option<ConfigResolvedHook>> = Mutex::new(None);
+
+/// Registers a hook which can adjust the configuration returned by [`get`].
+///
+/// The hook is called exactly once, after the configuration has been resolved from the build
+/// environment but before it is validated and cached. This allows build scripts to tweak the
+/// configuration programmatically, e.g. to force static linking in a particular CI job.
+///
+/// Only a single hook can be registered per build script process. Returns `false` (and the hook
+/// is dropped) if a hook was already registered.
+///
+/// The hook must be registered before the first call to [`get`] (including indirect calls such
+/// as [`use_pyo3_cfgs`]); once the configuration is cached, registering a hook has no effect.
+/// Because the configuration is resolved lazily, the hook may run on whichever thread first
+/// calls [`get`].
+#[cfg(feature = "resolve-config")]
+pub fn on_config_resolved<F>(hook: F) -> bool
+where
+    F: Fn(&mut InterpreterConfig) + Send + Sync + 'static,
+{
+    let mut registered = CONFIG_RESOLVED_HOOK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if registered.is_some() {
+        return false;
+    }
+    *registered = Some(Box::new(hook));
+    true
+}
+
+type LibDirResolver = Box<dyn Fn(&InterpreterConfig) -> Option<PathBuf> + Send + Sync>;
+
+static LIB_DIR_RESOLVER: OnceCell<LibDirResolver> = OnceCell::new();
+
+/// Registers a resolver which provides [`lib_dir`](InterpreterConfig::lib_dir) when it is empty.
+///
+/// This is intended for exotic build environments (e.g. embedders with a custom Python layout)
+/// where none of PyO3's built-in heuristics can locate the Python library directory.
+///
+/// The resolver is only called if `lib_dir` is still empty after the configuration has been
+/// resolved from the build environment, i.e. after all built-in fallbacks (such as
+/// `PYO3_CROSS_LIB_DIR`, `PYTHONHOME` and the Windows `libs` directory) have been tried. It runs
+/// before any hook registered with [`on_config_resolved`], so the hook sees the resolved
+/// `lib_dir`. Returning `None` leaves `lib_dir` empty. A returned path must be an existing
+/// directory, otherwise [`get`] panics with an error describing the invalid path.
+///
+/// As with [`on_config_resolved`], only a single resolver can be registered per build script
+/// process, and it must be registered before the first call to [`get`]. Returns `false` (and the
+/// resolver is dropped) if a resolver was already registered.
+pub fn set_lib_dir_resolver<F>(resolver: F) -> bool
+where
+    F: Fn(&InterpreterConfig) -> Option<PathBuf> + Send + Sync + 'static,
+{
+    LIB_DIR_RESOLVER.set(Box::new(resolver)).is_ok()
+}
+
+/// Fills an empty `lib_dir` using the registered resolver (if any).
+#[cfg_attr(not(feature = "resolve-config"), allow(dead_code))]
+fn resolve_lib_dir(
+    interpreter_config: &mut InterpreterConfig,
+    resolver: Option<&LibDirResolver>,
+) -> Result<()> {
+    let resolver = match resolver {
+        Some(resolver)
+            if interpreter_config
+                .lib_dir
+                .as_ref()
+                .map_or(true, |lib_dir| lib_dir.is_empty()) =>
+        {
+            resolver
         }
-        .expect("failed to parse PyO3 config")
-    })
+        _ => return Ok(()),
+    };
+    if let Some(lib_dir) = resolver(interpreter_config) {
+        ensure!(
+            lib_dir.is_dir(),
+            "lib_dir resolver returned {}, which is not a directory",
+            lib_dir.display()
+        );
+        let lib_dir = lib_dir.into_os_string().into_string().map_err(|lib_dir| {
+            format!(
+                "lib_dir resolver returned {}, which is not valid UTF-8",
+                Path::new(&lib_dir).display()
+            )
+        })?;
+        interpreter_config.lib_dir = Some(lib_dir);
+    }
+    Ok(())
+}
+
+/// Applies the registered lib_dir resolver and hook (if any) and validates the result.
+#[cfg_attr(not(feature = "resolve-config"), allow(dead_code))]
+fn finish_config(
+    mut interpreter_config: InterpreterConfig,
+    lib_dir_resolver: Option<&LibDirResolver>,
+    hook: Option<&ConfigResolvedHook>,
+) -> Result<InterpreterConfig> {
+    resolve_lib_dir(&mut interpreter_config, lib_dir_resolver)?;
+    if let Some(hook) = hook {
+        hook(&mut interpreter_config);
+    }
+    interpreter_config.validate()?;
+    Ok(interpreter_config)
 }
 
 /// Build configuration provided by `PYO3_CONFIG_FILE`. May be empty if env var not set.
@@ -123,7 +311,7 @@ fn resolve_cross_compile_config_path() -> Option<PathBuf> {
     env::var_os("TARGET").map(|target| {
         let mut path = PathBuf::from(env!("OUT_DIR"));
         path.push(Path::new(&target));
-        path.push("pyo3-build-config.txt");
+        path.push(impl_::CONFIG_FILE_NAME);
         path
     })
 }
@@ -144,33 +332,69 @@ pub fn print_feature_cfgs() {
     if rustc_minor_version >= 78 {
         println!("cargo:rustc-cfg=diagnostic_namespace");
     }
+
+    // std::backtrace was stabilized in Rust 1.65
+    if rustc_minor_version >= 65 {
+        println!("cargo:rustc-cfg=std_backtrace");
+    }
 }
 
 /// Registers `pyo3`s config names as reachable cfg expressions
 ///
+/// This emits a `cargo:rustc-check-cfg` line for every cfg which [`use_pyo3_cfgs`] may set,
+/// including the `Py_3_*` flags of versions other than the one being built for, so that the
+/// `unexpected_cfgs` lint accepts all of them. [`use_pyo3_cfgs`] already calls this; build
+/// scripts which emit PyO3's cfgs in some other way (e.g. from the `DEP_PYTHON_PYO3_CONFIG`
+/// passed on by `pyo3-ffi`) can call it directly. Nothing is emitted for compilers older than
+/// Rust 1.80, which don't support `rustc-check-cfg`.
+///
 /// - <https://github.com/rust-lang/cargo/pull/13571>
 /// - <https://doc.rust-lang.org/nightly/cargo/reference/build-scripts.html#rustc-check-cfg>
-#[doc(hidden)]
 pub fn print_expected_cfgs() {
     if rustc_minor_version().map_or(false, |version| version < 80) {
         // rustc 1.80.0 stabilized `rustc-check-cfg` feature, don't emit before
         return;
     }
 
-    println!("cargo:rustc-check-cfg=cfg(Py_LIMITED_API)");
-    println!("cargo:rustc-check-cfg=cfg(PyPy)");
-    println!("cargo:rustc-check-cfg=cfg(GraalPy)");
-    println!("cargo:rustc-check-cfg=cfg(py_sys_config, values(\"Py_DEBUG\", \"Py_REF_DEBUG\", \"Py_TRACE_REFS\", \"COUNT_ALLOCS\"))");
-    println!("cargo:rustc-check-cfg=cfg(invalid_from_utf8_lint)");
-    println!("cargo:rustc-check-cfg=cfg(pyo3_disable_reference_pool)");
-    println!("cargo:rustc-check-cfg=cfg(pyo3_leak_on_drop_without_reference_pool)");
-    println!("cargo:rustc-check-cfg=cfg(diagnostic_namespace)");
+    for cfg in expected_cfgs() {
+        println!("cargo:rustc-check-cfg={}", cfg);
+    }
+}
+
+/// The `cfg(...)` expressions registered by [`print_expected_cfgs`].
+///
+/// This must include every cfg emitted by [`InterpreterConfig::build_script_outputs`] and
+/// [`print_feature_cfgs`], so that downstream crates can use e.g. `#[cfg(not(Py_3_12))]`
+/// without `unexpected_cfgs` warnings.
+fn expected_cfgs() -> Vec<String> {
+    let mut cfgs: Vec<String> = [
+        "cfg(Py_LIMITED_API)",
+        "cfg(abi3)",
+        "cfg(py_subinterpreters)",
+        "cfg(py_tp_vectorcall)",
+        "cfg(py_tp_watched)",
+        "cfg(py_managed_dict)",
+        "cfg(PyPy)",
+        "cfg(GraalPy)",
+        "cfg(Py_GIL_DISABLED)",
+        "cfg(py_sys_config, values(\"Py_DEBUG\", \"Py_REF_DEBUG\", \"Py_TRACE_REFS\", \"COUNT_ALLOCS\", \"Py_GIL_DISABLED\", \"ABI3_STRICT\"))",
+        "cfg(invalid_from_utf8_lint)",
+        "cfg(pyo3_disable_reference_pool)",
+        "cfg(pyo3_leak_on_drop_without_reference_pool)",
+        "cfg(diagnostic_namespace)",
+        "cfg(std_backtrace)",
+    ]
+    .iter()
+    .map(|cfg| cfg.to_string())
+    .collect();
 
-    // allow `Py_3_*` cfgs from the minimum supported version up to the
+    // allow the contiguous range of `Py_3_*` cfgs from the first one emitted up to the
     // maximum minor version (+1 for development for the next)
-    for i in impl_::MINIMUM_SUPPORTED_VERSION.minor..=impl_::ABI3_MAX_MINOR + 1 {
-        println!("cargo:rustc-check-cfg=cfg(Py_3_{i})");
+    let max_minor = impl_::ABI3_MAX_MINOR.max(impl_::MAXIMUM_SUPPORTED_VERSION.minor) + 1;
+    for i in impl_::MINIMUM_VERSION_CFG_MINOR..=max_minor {
+        cfgs.push(format!("cfg(Py_3_{})", i));
     }
+    cfgs
 }
 
 /// Private exports used in PyO3's build.rs
@@ -188,8 +412,8 @@ pub mod pyo3_build_script_impl {
         pub use crate::errors::*;
     }
     pub use crate::impl_::{
-        cargo_env_var, env_var, is_linking_libpython, make_cross_compile_config, InterpreterConfig,
-        PythonVersion,
+        cargo_env_var, env_var, is_abi3_strict, is_linking_libpython, make_cross_compile_config,
+        target_triple_from_env, validate_extension_module_env, InterpreterConfig, PythonVersion,
     };
 
     /// Gets the configuration for use from PyO3's build script.
@@ -206,6 +430,14 @@ pub mod pyo3_build_script_impl {
             interperter_config.generate_import_libs()?;
             Ok(interperter_config)
         } else if let Some(interpreter_config) = make_cross_compile_config()? {
+            if let Ok(host_config) = InterpreterConfig::from_reader(Cursor::new(HOST_CONFIG)) {
+                if let Some(note) = impl_::cross_compile_version_note(
+                    interpreter_config.version,
+                    host_config.version,
+                ) {
+                    warn!("{}", note);
+                }
+            }
             // This is a cross compile and need to write the config file.
             let path = resolve_cross_compile_config_path()
                 .expect("resolve_interpreter_config() must be called from a build script");
@@ -221,9 +453,7 @@ pub mod pyo3_build_script_impl {
                     parent_dir.display()
                 )
             })?;
-            interpreter_config.to_writer(&mut std::fs::File::create(&path).with_context(
-                || format!("failed to create config file at {}", path.display()),
-            )?)?;
+            interpreter_config.write_all_formats(parent_dir)?;
             Ok(interpreter_config)
         } else {
             InterpreterConfig::from_reader(Cursor::new(HOST_CONFIG))
@@ -249,6 +479,145 @@ fn rustc_minor_version() -> Option<u32> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn emitted_cfgs_are_expected() {
+        // Parse the check-cfg expressions into (name, value) pairs, `None` for plain cfgs.
+        let mut expected = std::collections::HashSet::new();
+        for cfg in expected_cfgs() {
+            let inner = cfg.strip_prefix("cfg(").unwrap().strip_suffix(')').unwrap();
+            match inner.split_once(", values(") {
+                Some((name, values)) => {
+                    for value in values.trim_end_matches(')').split(", ") {
+                        expected.insert((name.to_owned(), Some(value.to_owned())));
+                    }
+                }
+                None => {
+                    expected.insert((inner.to_owned(), None));
+                }
+            }
+        }
+
+        let max_minor = impl_::MAXIMUM_SUPPORTED_VERSION.minor;
+        for minor in impl_::MINIMUM_SUPPORTED_VERSION.minor..=max_minor + 1 {
+            for implementation in [
+                PythonImplementation::CPython,
+                PythonImplementation::PyPy,
+                PythonImplementation::GraalPy,
+            ] {
+                for abi3 in [false, true] {
+                    let interpreter_config = InterpreterConfig::from_reader(
+                        format!(
+                            "version=3.{}\nimplementation={}\nabi3={}\nbuild_flags=Py_DEBUG,Py_GIL_DISABLED",
+                            minor, implementation, abi3
+                        )
+                        .as_bytes(),
+                    )
+                    .unwrap();
+                    for output in interpreter_config.build_script_outputs_with_strictness(true) {
+                        let cfg = match output.strip_prefix("cargo:rustc-cfg=") {
+                            Some(cfg) => cfg,
+                            None => continue,
+                        };
+                        let key = match cfg.split_once('=') {
+                            Some((name, value)) => (name.to_owned(), Some(value.to_owned())),
+                            None => (cfg.to_owned(), None),
+                        };
+                        assert!(expected.contains(&key), "{} is not declared", cfg);
+                    }
+                }
+            }
+        }
+
+        for feature_cfg in [
+            "invalid_from_utf8_lint",
+            "diagnostic_namespace",
+            "std_backtrace",
+        ] {
+            assert!(expected.contains(&(feature_cfg.to_owned(), None)));
+        }
+
+        // the version cfgs are a contiguous range
+        let versions: Vec<u8> = expected
+            .iter()
+            .filter_map(|(name, _)| name.strip_prefix("Py_3_")?.parse().ok())
+            .collect();
+        let (min, max) = (
+            *versions.iter().min().unwrap(),
+            *versions.iter().max().unwrap(),
+        );
+        assert_eq!(usize::from(max - min + 1), versions.len());
+        assert_eq!(min, impl_::MINIMUM_VERSION_CFG_MINOR);
+    }
+
+    #[test]
+    fn config_resolved_hook() {
+        let base = InterpreterConfig::from_reader("version=3.9\nshared=true".as_bytes()).unwrap();
+        assert!(base.shared);
+
+        let hook: ConfigResolvedHook = Box::new(|config| config.shared = false);
+        let cache: OnceCell<InterpreterConfig> = OnceCell::new();
+        let cached = cache.get_or_init(|| finish_config(base, None, Some(&hook)).unwrap());
+        assert!(!cached.shared);
+
+        // tweaks made by the hook are validated
+        let base = InterpreterConfig::from_reader("version=3.9".as_bytes()).unwrap();
+        let hook: ConfigResolvedHook = Box::new(|config| config.version.minor = 6);
+        assert!(finish_config(base, None, Some(&hook))
+            .unwrap_err()
+            .to_string()
+            .contains("lower than PyO3's minimum supported version"));
+    }
+
+    #[cfg(feature = "resolve-config")]
+    #[test]
+    fn on_config_resolved_registers_once() {
+        assert!(on_config_resolved(|_| {}));
+        assert!(!on_config_resolved(|_| {}));
+
+        // don't leak the hook into other tests which resolve the config
+        *CONFIG_RESOLVED_HOOK.lock().unwrap() = None;
+    }
+
+    #[test]
+    fn lib_dir_resolver() {
+        let dir = std::env::temp_dir();
+        let resolved = dir.clone();
+        let resolver: LibDirResolver = Box::new(move |config| {
+            assert_eq!(config.version.minor, 9);
+            Some(resolved.clone())
+        });
+
+        // an empty lib_dir is filled in
+        let base = InterpreterConfig::from_reader("version=3.9".as_bytes()).unwrap();
+        assert_eq!(base.lib_dir, None);
+        let config = finish_config(base, Some(&resolver), None).unwrap();
+        assert_eq!(config.lib_dir.as_deref(), dir.to_str());
+
+        // the resolver runs before the config resolved hook
+        let base = InterpreterConfig::from_reader("version=3.9".as_bytes()).unwrap();
+        let hook: ConfigResolvedHook = Box::new(|config| assert!(config.lib_dir.is_some()));
+        finish_config(base, Some(&resolver), Some(&hook)).unwrap();
+
+        // a configured lib_dir is kept
+        let base =
+            InterpreterConfig::from_reader("version=3.9\nlib_dir=/usr/lib".as_bytes()).unwrap();
+        let config = finish_config(base, Some(&resolver), None).unwrap();
+        assert_eq!(config.lib_dir.as_deref(), Some("/usr/lib"));
+
+        // the returned path is validated
+        let base = InterpreterConfig::from_reader("version=3.9".as_bytes()).unwrap();
+        let resolver: LibDirResolver = Box::new(|_| Some(PathBuf::from("/does/not/exist")));
+        assert_eq!(
+            finish_config(base, Some(&resolver), None)
+                .unwrap_err()
+                .to_string(),
+            "lib_dir resolver returned /does/not/exist, which is not a directory"
+        );
+
+        assert!(set_lib_dir_resolver(|_| None));
+        assert!(!set_lib_dir_resolver(|_| None));
+    }
+
     #[test]
     fn extension_module_link_args() {
         let mut buf = Vec::new();
@@ -280,5 +649,18 @@ mod tests {
             "cargo:rustc-cdylib-link-arg=-sSIDE_MODULE=2\n\
              cargo:rustc-cdylib-link-arg=-sWASM_BIGINT\n"
         );
+
+        for android in ["aarch64-linux-android", "armv7-linux-androideabi"] {
+            buf.clear();
+            _add_extension_module_link_args(&Triple::from_str(android).unwrap(), &mut buf);
+            assert_eq!(
+                std::str::from_utf8(&buf).unwrap(),
+                "cargo:rustc-cdylib-link-arg=-llog\n"
+            );
+        }
+
+        buf.clear();
+        _add_extension_module_link_args(&Triple::from_str("aarch64-apple-ios").unwrap(), &mut buf);
+        assert_eq!(buf, Vec::new());
     }
 }
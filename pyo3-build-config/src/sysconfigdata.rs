@@ -0,0 +1,568 @@
+//! Searching for and parsing the `_sysconfigdata*.py` file of a target Python installation.
+
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fs::{self, DirEntry},
+    path::{Path, PathBuf},
+};
+
+use target_lexicon::{Architecture, OperatingSystem, Triple};
+
+use super::{
+    env_var, find_interpreter, parse_script_output, run_python_script, CrossCompileConfig,
+    PythonVersion,
+};
+use crate::{
+    bail, ensure,
+    errors::{Context, Result},
+};
+
+/// Parsed data from Python sysconfigdata file
+///
+/// A hash map of all values from a sysconfigdata file.
+pub struct Sysconfigdata(HashMap<String, String>);
+
+impl Sysconfigdata {
+    pub fn get_value<S: AsRef<str>>(&self, k: S) -> Option<&str> {
+        self.0.get(k.as_ref()).map(String::as_str)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn new() -> Self {
+        Sysconfigdata(HashMap::new())
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn insert<S: Into<String>>(&mut self, k: S, v: S) {
+        self.0.insert(k.into(), v.into());
+    }
+}
+
+/// Parse sysconfigdata file
+///
+/// The sysconfigdata is simply a dictionary containing all the build time variables used for the
+/// python executable and library. This function necessitates a python interpreter on the host
+/// machine to work. Here it is read into a `Sysconfigdata` (hash map), which can be turned into an
+/// [`InterpreterConfig`](super::InterpreterConfig) using
+/// [`from_sysconfigdata`](super::InterpreterConfig::from_sysconfigdata).
+pub fn parse_sysconfigdata(sysconfigdata_path: impl AsRef<Path>) -> Result<Sysconfigdata> {
+    let sysconfigdata_path = sysconfigdata_path.as_ref();
+    let mut script = fs::read_to_string(sysconfigdata_path).with_context(|| {
+        format!(
+            "failed to read config from {}",
+            sysconfigdata_path.display()
+        )
+    })?;
+    script += r#"
+for key, val in build_time_vars.items():
+    print(key, val)
+"#;
+
+    let output = run_python_script(&find_interpreter()?, &script)?;
+
+    Ok(Sysconfigdata(parse_script_output(&output)))
+}
+
+fn starts_with(entry: &DirEntry, pat: &str) -> bool {
+    let name = entry.file_name();
+    name.to_string_lossy().starts_with(pat)
+}
+fn ends_with(entry: &DirEntry, pat: &str) -> bool {
+    let name = entry.file_name();
+    name.to_string_lossy().ends_with(pat)
+}
+
+/// Finds the sysconfigdata file when the target Python library directory is set.
+///
+/// Returns `None` if the library directory is not available, and a runtime error
+/// when no or multiple sysconfigdata files are found.
+pub(crate) fn find_sysconfigdata(cross: &CrossCompileConfig) -> Result<Option<PathBuf>> {
+    let mut search = search_sysconfigdata(cross)?;
+    if search.found.is_empty() {
+        if let Some(lib_dir) = cross.lib_dir.as_ref() {
+            bail!(
+                "Could not find _sysconfigdata*.py in {}\n\
+                directories searched:{}\n\
+                = help: set PYO3_CROSS_PYTHON_SYSCONFIGDATA to the path of the target's \
+                _sysconfigdata*.py file",
+                lib_dir.display(),
+                search.searched_list()
+            );
+        } else {
+            // Continue with the default configuration when PYO3_CROSS_LIB_DIR is not set.
+            return Ok(None);
+        }
+    } else if search.found.len() > 1 {
+        let mut error_msg = String::from(
+            "Detected multiple possible Python versions. Please set either the \
+            PYO3_CROSS_PYTHON_VERSION variable to the wanted version or the \
+            _PYTHON_SYSCONFIGDATA_NAME variable to the wanted sysconfigdata file name.\n\n\
+            sysconfigdata files found:",
+        );
+        for path in search.found {
+            use std::fmt::Write;
+            write!(&mut error_msg, "\n\t{}", path.display()).unwrap();
+        }
+        bail!("{}\n", error_msg);
+    }
+
+    Ok(Some(search.found.remove(0)))
+}
+
+/// The sysconfigdata files found by [`search_sysconfigdata`], and where they were looked for.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SysconfigdataSearch {
+    /// The candidate sysconfigdata files, sorted and without duplicates.
+    pub found: Vec<PathBuf>,
+    /// Every directory which was searched, in the order it was searched.
+    pub searched: Vec<PathBuf>,
+}
+
+impl SysconfigdataSearch {
+    fn searched_list(&self) -> String {
+        if self.searched.is_empty() {
+            return " (none)".to_owned();
+        }
+        self.searched
+            .iter()
+            .map(|dir| format!("\n\t{}", dir.display()))
+            .collect()
+    }
+}
+
+/// Finds `_sysconfigdata*.py` files for detected Python interpreters.
+///
+/// From the python source for `_sysconfigdata*.py` is always going to be located at
+/// `build/lib.{PLATFORM}-{PY_MINOR_VERSION}` when built from source. The [exact line][1] is defined as:
+///
+/// ```py
+/// pybuilddir = 'build/lib.%s-%s' % (get_platform(), sys.version_info[:2])
+/// ```
+///
+/// Where get_platform returns a kebab-case formatted string containing the os, the architecture and
+/// possibly the os' kernel version (not the case on linux). However, when installed using a package
+/// manager, the `_sysconfigdata*.py` file is installed in the `${PREFIX}/lib/python3.Y/` directory.
+/// The `_sysconfigdata*.py` is generally in a sub-directory of the location of `libpython3.Y.so`.
+/// So we must find the file in the following possible locations:
+///
+/// ```sh
+/// # distribution from package manager, (lib_dir may or may not include lib/)
+/// ${INSTALL_PREFIX}/lib/python3.Y/_sysconfigdata*.py
+/// ${INSTALL_PREFIX}/lib/libpython3.Y.so
+/// ${INSTALL_PREFIX}/lib/python3.Y/config-3.Y-${HOST_TRIPLE}/libpython3.Y.so
+///
+/// # Built from source from host
+/// ${CROSS_COMPILED_LOCATION}/build/lib.linux-x86_64-Y/_sysconfigdata*.py
+/// ${CROSS_COMPILED_LOCATION}/libpython3.Y.so
+///
+/// # if cross compiled, kernel release is only present on certain OS targets.
+/// ${CROSS_COMPILED_LOCATION}/build/lib.{OS}(-{OS-KERNEL-RELEASE})?-{ARCH}-Y/_sysconfigdata*.py
+/// ${CROSS_COMPILED_LOCATION}/libpython3.Y.so
+///
+/// # PyPy includes a similar file since v73
+/// ${INSTALL_PREFIX}/lib/pypy3.Y/_sysconfigdata.py
+/// ${INSTALL_PREFIX}/lib_pypy/_sysconfigdata.py
+/// ```
+///
+/// [1]: https://github.com/python/cpython/blob/3.5/Lib/sysconfig.py#L389
+///
+/// Returns an empty vector when the target Python library directory
+/// is not set via `PYO3_CROSS_LIB_DIR`. See [`search_sysconfigdata`] for the directories which
+/// were searched.
+pub fn find_all_sysconfigdata(cross: &CrossCompileConfig) -> Vec<PathBuf> {
+    search_sysconfigdata(cross).map_or_else(|_| Vec::new(), |search| search.found)
+}
+
+/// Searches for the target's `_sysconfigdata*.py` files.
+///
+/// If `PYO3_CROSS_PYTHON_SYSCONFIGDATA` is set, that file is used without searching. Otherwise
+/// `PYO3_CROSS_LIB_DIR` is searched recursively in the locations described for
+/// [`find_all_sysconfigdata`], keeping only files named by `_PYTHON_SYSCONFIGDATA_NAME` if it is
+/// set. When the library directory is a multiarch directory such as `/usr/lib/aarch64-linux-gnu`,
+/// its parent is searched too, since distributions install `python3.Y/` there.
+///
+/// If several files are found, those for the target's multiarch tuple (e.g.
+/// `_sysconfigdata__linux_aarch64-linux-gnu.py`) or architecture are preferred.
+pub fn search_sysconfigdata(cross: &CrossCompileConfig) -> Result<SysconfigdataSearch> {
+    let override_path = env_var("PYO3_CROSS_PYTHON_SYSCONFIGDATA").map(PathBuf::from);
+    let sysconfig_name = env_var("_PYTHON_SYSCONFIGDATA_NAME");
+    search_sysconfigdata_with(cross, override_path, sysconfig_name.as_deref())
+}
+
+fn search_sysconfigdata_with(
+    cross: &CrossCompileConfig,
+    override_path: Option<PathBuf>,
+    sysconfig_name: Option<&OsStr>,
+) -> Result<SysconfigdataSearch> {
+    let mut search = SysconfigdataSearch::default();
+    if let Some(path) = override_path {
+        ensure!(
+            path.is_file(),
+            "PYO3_CROSS_PYTHON_SYSCONFIGDATA is set to {}, which is not a file",
+            path.display()
+        );
+        search.found.push(path);
+        return Ok(search);
+    }
+    let lib_dir = match cross.lib_dir.as_ref() {
+        Some(lib_dir) => lib_dir,
+        None => return Ok(search),
+    };
+    ensure!(
+        lib_dir.is_dir(),
+        "PYO3_CROSS_LIB_DIR is set to {}, which is not a directory",
+        lib_dir.display()
+    );
+
+    let multiarch = target_multiarch(&cross.target);
+    let mut paths = search_lib_dir(lib_dir, cross, &mut search.searched);
+    let is_multiarch_dir = |dir: &Path| {
+        multiarch.as_deref().map_or(false, |multiarch| {
+            dir.file_name() == Some(OsStr::new(multiarch))
+        })
+    };
+    if let Some(parent) = lib_dir.parent().filter(|_| is_multiarch_dir(lib_dir)) {
+        paths.extend(search_lib_dir(parent, cross, &mut search.searched));
+    }
+
+    let mut paths = paths
+        .iter()
+        .filter_map(|p| fs::canonicalize(p).ok())
+        .filter(|p| sysconfig_name.map_or(true, |name| p.file_stem() == Some(name)))
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+    paths.dedup();
+
+    // If we got more than one file, only take those for the target.
+    // For ubuntu 20.04 with host architecture x86_64 and a foreign architecture of armhf
+    // this reduces the number of candidates to 1:
+    //
+    // $ find /usr/lib/python3.8/ -name '_sysconfigdata*.py' -not -lname '*'
+    //  /usr/lib/python3.8/_sysconfigdata__x86_64-linux-gnu.py
+    //  /usr/lib/python3.8/_sysconfigdata__arm-linux-gnueabihf.py
+    if paths.len() > 1 {
+        let architecture = cross.target.architecture.to_string();
+        let file_name_matches = |pattern: &dyn Fn(&str) -> bool| {
+            paths
+                .iter()
+                .filter(|p| p.file_stem().and_then(OsStr::to_str).map_or(false, pattern))
+                .cloned()
+                .collect::<Vec<PathBuf>>()
+        };
+        let for_multiarch = multiarch.as_deref().map_or_else(Vec::new, |multiarch| {
+            file_name_matches(&|stem| stem.ends_with(&format!("_{}", multiarch)))
+        });
+        let for_architecture = file_name_matches(&|stem| stem.contains(&architecture));
+        if !for_multiarch.is_empty() {
+            paths = for_multiarch;
+        } else if !for_architecture.is_empty() {
+            paths = for_architecture;
+        }
+    }
+
+    search.found = paths;
+    Ok(search)
+}
+
+/// The Debian-style multiarch tuple for Linux targets, e.g. `aarch64-linux-gnu` or
+/// `arm-linux-gnueabihf`, which CPython uses in sysconfigdata file names.
+fn target_multiarch(target: &Triple) -> Option<String> {
+    if target.operating_system != OperatingSystem::Linux {
+        return None;
+    }
+    let architecture = match target.architecture {
+        Architecture::Arm(_) => "arm".to_owned(),
+        Architecture::X86_32(_) => "i386".to_owned(),
+        architecture => architecture.to_string(),
+    };
+    Some(format!("{}-linux-{}", architecture, target.environment))
+}
+
+fn is_pypy_lib_dir(path: &str, v: &Option<PythonVersion>) -> bool {
+    let pypy_version_pat = if let Some(v) = v {
+        format!("pypy{}", v)
+    } else {
+        "pypy3.".into()
+    };
+    path == "lib_pypy" || path.starts_with(&pypy_version_pat)
+}
+
+fn is_graalpy_lib_dir(path: &str, v: &Option<PythonVersion>) -> bool {
+    let graalpy_version_pat = if let Some(v) = v {
+        format!("graalpy{}", v)
+    } else {
+        "graalpy2".into()
+    };
+    path == "lib_graalpython" || path.starts_with(&graalpy_version_pat)
+}
+
+fn is_cpython_lib_dir(path: &str, v: &Option<PythonVersion>) -> bool {
+    let cpython_version_pat = if let Some(v) = v {
+        format!("python{}", v)
+    } else {
+        "python3.".into()
+    };
+    path.starts_with(&cpython_version_pat)
+}
+
+/// recursive search for _sysconfigdata, returns all possibilities of sysconfigdata paths
+///
+/// Every directory read is recorded in `searched`; directories which can't be read are skipped.
+fn search_lib_dir(
+    path: impl AsRef<Path>,
+    cross: &CrossCompileConfig,
+    searched: &mut Vec<PathBuf>,
+) -> Vec<PathBuf> {
+    let path = path.as_ref();
+    let mut sysconfig_paths = vec![];
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return sysconfig_paths,
+    };
+    searched.push(path.to_owned());
+    for f in entries {
+        sysconfig_paths.extend(match &f {
+            // Python 3.7+ sysconfigdata with platform specifics
+            Ok(f) if starts_with(f, "_sysconfigdata_") && ends_with(f, "py") => vec![f.path()],
+            Ok(f) if f.metadata().map_or(false, |metadata| metadata.is_dir()) => {
+                let file_name = f.file_name();
+                let file_name = file_name.to_string_lossy();
+                if file_name == "build" || file_name == "lib" {
+                    search_lib_dir(f.path(), cross, searched)
+                } else if file_name.starts_with("lib.") {
+                    // check if right target os
+                    if !file_name.contains(&cross.target.operating_system.to_string()) {
+                        continue;
+                    }
+                    // Check if right arch
+                    if !file_name.contains(&cross.target.architecture.to_string()) {
+                        continue;
+                    }
+                    search_lib_dir(f.path(), cross, searched)
+                } else if is_cpython_lib_dir(&file_name, &cross.version)
+                    || is_pypy_lib_dir(&file_name, &cross.version)
+                    || is_graalpy_lib_dir(&file_name, &cross.version)
+                {
+                    search_lib_dir(f.path(), cross, searched)
+                } else {
+                    continue;
+                }
+            }
+            _ => continue,
+        });
+    }
+
+    sysconfig_paths
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use target_lexicon::triple;
+
+    use super::*;
+
+    #[test]
+    #[cfg(all(
+        target_os = "linux",
+        target_arch = "x86_64",
+        feature = "resolve-config"
+    ))]
+    fn parse_sysconfigdata() {
+        use std::collections::BTreeMap;
+
+        use crate::impl_::{BuildFlags, InterpreterConfig, PythonImplementation};
+
+        // A best effort attempt to get test coverage for the sysconfigdata parsing.
+        // Might not complete successfully depending on host installation; that's ok as long as
+        // CI demonstrates this path is covered!
+
+        let interpreter_config = crate::get();
+
+        let lib_dir = match &interpreter_config.lib_dir {
+            Some(lib_dir) => Path::new(lib_dir),
+            // Don't know where to search for sysconfigdata; never mind.
+            None => return,
+        };
+
+        let cross = CrossCompileConfig {
+            lib_dir: Some(lib_dir.into()),
+            version: Some(interpreter_config.version),
+            implementation: Some(interpreter_config.implementation),
+            target: triple!("x86_64-unknown-linux-gnu"),
+        };
+
+        let sysconfigdata_path = match find_sysconfigdata(&cross) {
+            Ok(Some(path)) => path,
+            // Couldn't find a matching sysconfigdata; never mind!
+            _ => return,
+        };
+        let sysconfigdata = super::parse_sysconfigdata(sysconfigdata_path).unwrap();
+        let parsed_config = InterpreterConfig::from_sysconfigdata(&sysconfigdata).unwrap();
+
+        assert_eq!(
+            parsed_config,
+            InterpreterConfig {
+                abi3: false,
+                build_flags: BuildFlags(interpreter_config.build_flags.0.clone()),
+                pointer_width: Some(64),
+                pointer_width_inferred: false,
+                executable: None,
+                platform_tag: None,
+                python_framework_prefix: None,
+                implementation: PythonImplementation::CPython,
+                lib_dir: interpreter_config.lib_dir.to_owned(),
+                lib_name: interpreter_config.lib_name.to_owned(),
+                shared: true,
+                version: interpreter_config.version,
+                suppress_build_script_link_lines: false,
+                extra_build_script_lines: vec![],
+                extra_link_args: vec![],
+                extra_link_args_by_os: BTreeMap::new(),
+                extra_link_search_paths: vec![],
+                config_args: interpreter_config.config_args.clone(),
+                sysconfig_vars: BTreeMap::new(),
+            }
+        )
+    }
+
+    #[test]
+    fn sysconfigdata_search() {
+        let dir = env::temp_dir().join(format!("pyo3-sysconfigdata-{}", std::process::id()));
+        let usr_lib = dir.join("usr").join("lib");
+        let python_lib = usr_lib.join("python3.11");
+        std::fs::create_dir_all(&python_lib).unwrap();
+        std::fs::create_dir_all(usr_lib.join("aarch64-linux-gnu")).unwrap();
+        for multiarch in [
+            "x86_64-linux-gnu",
+            "aarch64-linux-gnu",
+            "arm-linux-gnueabihf",
+        ] {
+            std::fs::write(
+                python_lib.join(format!("_sysconfigdata__linux_{}.py", multiarch)),
+                "",
+            )
+            .unwrap();
+        }
+        let python_lib = python_lib.canonicalize().unwrap();
+        let cross = |lib_dir: PathBuf, target| CrossCompileConfig {
+            lib_dir: Some(lib_dir),
+            version: None,
+            implementation: None,
+            target,
+        };
+
+        // The sysconfigdata for the target's multiarch tuple is picked out of a multiarch layout.
+        let search = search_sysconfigdata_with(
+            &cross(usr_lib.clone(), triple!("armv7-unknown-linux-gnueabihf")),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            search.found,
+            [python_lib.join("_sysconfigdata__linux_arm-linux-gnueabihf.py")]
+        );
+        assert_eq!(
+            search.searched,
+            [usr_lib.clone(), usr_lib.join("python3.11")]
+        );
+
+        // The Python directory is found next to a multiarch library directory.
+        let search = search_sysconfigdata_with(
+            &cross(
+                usr_lib.join("aarch64-linux-gnu"),
+                triple!("aarch64-unknown-linux-gnu"),
+            ),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            search.found,
+            [python_lib.join("_sysconfigdata__linux_aarch64-linux-gnu.py")]
+        );
+        assert_eq!(
+            search.searched,
+            [
+                usr_lib.join("aarch64-linux-gnu"),
+                usr_lib.clone(),
+                usr_lib.join("python3.11")
+            ]
+        );
+
+        // `_PYTHON_SYSCONFIGDATA_NAME` narrows down the candidates.
+        let search = search_sysconfigdata_with(
+            &cross(usr_lib.clone(), triple!("aarch64-unknown-linux-gnu")),
+            None,
+            Some(OsStr::new("_sysconfigdata__linux_x86_64-linux-gnu")),
+        )
+        .unwrap();
+        assert_eq!(
+            search.found,
+            [python_lib.join("_sysconfigdata__linux_x86_64-linux-gnu.py")]
+        );
+
+        // An explicit path is used without searching.
+        let explicit = usr_lib.join("python3.11/_sysconfigdata__linux_x86_64-linux-gnu.py");
+        let search = search_sysconfigdata_with(
+            &cross(usr_lib.clone(), triple!("aarch64-unknown-linux-gnu")),
+            Some(explicit.clone()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            search,
+            SysconfigdataSearch {
+                found: vec![explicit],
+                searched: vec![],
+            }
+        );
+        assert_eq!(
+            search_sysconfigdata_with(
+                &cross(usr_lib.clone(), triple!("aarch64-unknown-linux-gnu")),
+                Some(dir.join("missing.py")),
+                None,
+            )
+            .unwrap_err()
+            .to_string(),
+            format!(
+                "PYO3_CROSS_PYTHON_SYSCONFIGDATA is set to {}, which is not a file",
+                dir.join("missing.py").display()
+            )
+        );
+
+        // Every directory searched is listed when nothing is found.
+        let empty = dir.join("empty");
+        std::fs::create_dir_all(empty.join("lib")).unwrap();
+        assert_eq!(
+            find_sysconfigdata(&cross(empty.clone(), triple!("aarch64-unknown-linux-gnu")))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "Could not find _sysconfigdata*.py in {0}\n\
+                directories searched:\n\t{0}\n\t{1}\n\
+                = help: set PYO3_CROSS_PYTHON_SYSCONFIGDATA to the path of the target's \
+                _sysconfigdata*.py file",
+                empty.display(),
+                empty.join("lib").display()
+            )
+        );
+        assert_eq!(
+            search_sysconfigdata_with(
+                &cross(dir.join("missing"), triple!("aarch64-unknown-linux-gnu")),
+                None,
+                None,
+            )
+            .unwrap_err()
+            .to_string(),
+            format!(
+                "PYO3_CROSS_LIB_DIR is set to {}, which is not a directory",
+                dir.join("missing").display()
+            )
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,389 @@
+//! Python versions, and the versions supported by this release of PyO3.
+
+use std::{fmt::Display, str::FromStr};
+
+use super::{env_var, InterpreterConfig, PythonImplementation};
+use crate::{
+    bail,
+    errors::{Context, Result},
+    warn,
+};
+
+/// Minimum Python version PyO3 supports.
+pub(crate) const MINIMUM_SUPPORTED_VERSION: PythonVersion = PythonVersion { major: 3, minor: 7 };
+
+/// Newest Python version known to this release of PyO3.
+pub(crate) const MAXIMUM_SUPPORTED_VERSION: PythonVersion = PythonVersion {
+    major: 3,
+    minor: 13,
+};
+
+/// GraalPy may implement the same CPython version over multiple releases.
+pub(crate) const MINIMUM_SUPPORTED_VERSION_GRAALPY: PythonVersion = PythonVersion {
+    major: 24,
+    minor: 0,
+};
+
+/// A Python `major.minor` version.
+///
+/// Versions are ordered numerically, so build scripts can compare against the constants on this
+/// type directly, e.g. `config.version >= PythonVersion::PY310`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PythonVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl PythonVersion {
+    pub const PY37: Self = Self::new(3, 7);
+    pub const PY38: Self = Self::new(3, 8);
+    pub const PY39: Self = Self::new(3, 9);
+    pub const PY310: Self = Self::new(3, 10);
+    pub const PY311: Self = Self::new(3, 11);
+    pub const PY312: Self = Self::new(3, 12);
+    pub const PY313: Self = Self::new(3, 13);
+
+    /// The oldest version of CPython supported by this release of PyO3.
+    pub const MIN_SUPPORTED: Self = MINIMUM_SUPPORTED_VERSION;
+    /// The newest version of CPython supported by this release of PyO3.
+    pub const MAX_SUPPORTED: Self = MAXIMUM_SUPPORTED_VERSION;
+
+    /// Creates the version `major.minor`.
+    pub const fn new(major: u8, minor: u8) -> Self {
+        PythonVersion { major, minor }
+    }
+
+    /// Returns `true` if this version matches every comparison in `req`.
+    ///
+    /// ```
+    /// use pyo3_build_config::{PythonVersion, PythonVersionReq};
+    ///
+    /// let req: PythonVersionReq = ">=3.8, <3.13".parse().unwrap();
+    /// assert!(PythonVersion::PY310.satisfies(&req));
+    /// assert!(!PythonVersion::PY37.satisfies(&req));
+    /// ```
+    pub fn satisfies(&self, req: &PythonVersionReq) -> bool {
+        req.matches(*self)
+    }
+}
+
+/// The range of Python versions supported by this release of PyO3 for `implementation`.
+pub(crate) fn supported_versions(
+    implementation: PythonImplementation,
+) -> (PythonVersion, PythonVersion) {
+    match implementation {
+        PythonImplementation::CPython => {
+            (PythonVersion::MIN_SUPPORTED, PythonVersion::MAX_SUPPORTED)
+        }
+        PythonImplementation::PyPy => (PythonVersion::PY37, PythonVersion::PY310),
+        PythonImplementation::GraalPy => (PythonVersion::PY310, PythonVersion::PY311),
+    }
+}
+
+/// Checks that the Python version of `interpreter_config` is supported by this release of PyO3.
+///
+/// Build scripts can call this to fail early with a clear error, instead of with compile errors
+/// in the FFI bindings later. PyO3's own build scripts run the same check.
+///
+/// CPython versions newer than supported are accepted for `abi3` builds when
+/// `PYO3_USE_ABI3_FORWARD_COMPATIBILITY=1` is set. If `PYO3_USE_UNSUPPORTED_PYTHON=1` is set, any
+/// unsupported version is only a warning, and the build continues at the user's risk.
+pub fn check_support(interpreter_config: &InterpreterConfig) -> Result<()> {
+    let abi3_forward_compatibility =
+        env_var("PYO3_USE_ABI3_FORWARD_COMPATIBILITY").map_or(false, |os_str| os_str == "1");
+    let error = match unsupported_version_error(interpreter_config, abi3_forward_compatibility) {
+        Some(error) => error,
+        None => return Ok(()),
+    };
+    if env_var("PYO3_USE_UNSUPPORTED_PYTHON").map_or(false, |os_str| os_str == "1") {
+        let summary = error.lines().next().unwrap_or_default();
+        warn!(
+            PYO3_W009,
+            "{}; continuing because PYO3_USE_UNSUPPORTED_PYTHON=1 is set", summary
+        );
+        Ok(())
+    } else {
+        bail!(
+            "{}\n= help: set PYO3_USE_UNSUPPORTED_PYTHON=1 to build anyway, at your own risk",
+            error
+        )
+    }
+}
+
+fn unsupported_version_error(
+    interpreter_config: &InterpreterConfig,
+    abi3_forward_compatibility: bool,
+) -> Option<String> {
+    let implementation = interpreter_config.implementation;
+    let version = interpreter_config.version;
+    let (min, max) = supported_versions(implementation);
+    let name = match implementation {
+        PythonImplementation::CPython => "Python",
+        PythonImplementation::PyPy => "PyPy",
+        PythonImplementation::GraalPy => "GraalPy",
+    };
+    if version < min {
+        Some(format!(
+            "the configured {} interpreter version ({}) is lower than PyO3's minimum supported \
+            version ({})",
+            name, version, min
+        ))
+    } else if version > max {
+        let mut error = format!(
+            "the configured {} interpreter version ({}) is newer than PyO3's maximum supported \
+            version ({})\n\
+            = help: please check if an updated version of PyO3 is available. Current version: {}",
+            name,
+            version,
+            max,
+            env!("CARGO_PKG_VERSION")
+        );
+        // Only CPython has a stable ABI which newer versions are compatible with.
+        if implementation == PythonImplementation::CPython {
+            if abi3_forward_compatibility {
+                return None;
+            }
+            error.push_str(
+                "\n= help: set PYO3_USE_ABI3_FORWARD_COMPATIBILITY=1 to suppress this check and \
+                build anyway using the stable ABI",
+            );
+        }
+        Some(error)
+    } else {
+        None
+    }
+}
+
+impl Display for PythonVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for PythonVersion {
+    type Err = crate::errors::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut split = value.splitn(2, '.');
+        let (major, minor) = (
+            split
+                .next()
+                .expect("first splitn value should always be present"),
+            split.next().ok_or("expected major.minor version")?,
+        );
+        Ok(Self {
+            major: major.parse().context("failed to parse major version")?,
+            minor: minor.parse().context("failed to parse minor version")?,
+        })
+    }
+}
+
+/// A requirement on the Python version, such as `>=3.8, <3.13`.
+///
+/// Parsed from a comma-separated list of comparisons, each one of `==`, `!=`, `>=`, `>`, `<=`
+/// or `<` followed by a `major.minor` version. A version matches if it satisfies all of them;
+/// the empty requirement matches every version.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PythonVersionReq {
+    comparators: Vec<(VersionOp, PythonVersion)>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum VersionOp {
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+    Le,
+    Lt,
+}
+
+impl VersionOp {
+    fn as_str(self) -> &'static str {
+        match self {
+            VersionOp::Eq => "==",
+            VersionOp::Ne => "!=",
+            VersionOp::Ge => ">=",
+            VersionOp::Gt => ">",
+            VersionOp::Le => "<=",
+            VersionOp::Lt => "<",
+        }
+    }
+}
+
+impl PythonVersionReq {
+    /// Returns `true` if `version` satisfies every comparison in this requirement.
+    pub fn matches(&self, version: PythonVersion) -> bool {
+        self.comparators.iter().all(|&(op, bound)| match op {
+            VersionOp::Eq => version == bound,
+            VersionOp::Ne => version != bound,
+            VersionOp::Ge => version >= bound,
+            VersionOp::Gt => version > bound,
+            VersionOp::Le => version <= bound,
+            VersionOp::Lt => version < bound,
+        })
+    }
+}
+
+impl FromStr for PythonVersionReq {
+    type Err = crate::errors::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut comparators = Vec::new();
+        if value.trim().is_empty() {
+            return Ok(Self { comparators });
+        }
+        for comparator in value.split(',') {
+            let comparator = comparator.trim();
+            // Two-character operators must be tried before their one-character prefixes.
+            let (op, version) = [
+                VersionOp::Eq,
+                VersionOp::Ne,
+                VersionOp::Ge,
+                VersionOp::Le,
+                VersionOp::Gt,
+                VersionOp::Lt,
+            ]
+            .iter()
+            .find_map(|&op| {
+                comparator
+                    .strip_prefix(op.as_str())
+                    .map(|version| (op, version))
+            })
+            .ok_or_else(|| {
+                format!(
+                    "invalid Python version requirement `{}`: expected a comparison operator \
+                    (one of ==, !=, >=, >, <=, <)",
+                    comparator
+                )
+            })?;
+            let version: PythonVersion = version
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid Python version requirement `{}`", comparator))?;
+            comparators.push((op, version));
+        }
+        Ok(Self { comparators })
+    }
+}
+
+impl Display for PythonVersionReq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (op, version)) in self.comparators.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}{}", op.as_str(), version)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impl_::InterpreterConfigBuilder;
+
+    #[test]
+    fn python_version_ordering_and_requirements() {
+        assert!(PythonVersion::PY310 > PythonVersion::PY39);
+        assert!(PythonVersion::new(4, 0) > PythonVersion::PY313);
+        assert_eq!(
+            "3.10".parse::<PythonVersion>().unwrap(),
+            PythonVersion::PY310
+        );
+
+        let req: PythonVersionReq = " >=3.8 ,<3.13".parse().unwrap();
+        assert_eq!(req.to_string(), ">=3.8, <3.13");
+        assert!(!PythonVersion::PY37.satisfies(&req));
+        assert!(PythonVersion::PY38.satisfies(&req));
+        assert!(PythonVersion::PY312.satisfies(&req));
+        assert!(!PythonVersion::PY313.satisfies(&req));
+
+        let req: PythonVersionReq = "!=3.9, >3.7, <=3.10".parse().unwrap();
+        assert!(PythonVersion::PY38.satisfies(&req));
+        assert!(!PythonVersion::PY39.satisfies(&req));
+        assert!(PythonVersion::PY310.satisfies(&req));
+        assert!(!PythonVersion::PY311.satisfies(&req));
+
+        assert!("==3.11"
+            .parse::<PythonVersionReq>()
+            .unwrap()
+            .matches(PythonVersion::PY311));
+        assert!(""
+            .parse::<PythonVersionReq>()
+            .unwrap()
+            .matches(PythonVersion::PY37));
+
+        assert_eq!(
+            "3.8".parse::<PythonVersionReq>().unwrap_err().to_string(),
+            "invalid Python version requirement `3.8`: expected a comparison operator \
+            (one of ==, !=, >=, >, <=, <)"
+        );
+        assert_eq!(
+            ">=3"
+                .parse::<PythonVersionReq>()
+                .unwrap_err()
+                .report()
+                .to_string(),
+            "invalid Python version requirement `>=3`\n\
+            caused by:\n  \
+            - 0: expected major.minor version\n"
+        );
+    }
+
+    #[test]
+    fn python_version_support() {
+        assert_eq!(PythonVersion::MIN_SUPPORTED, MINIMUM_SUPPORTED_VERSION);
+        assert_eq!(PythonVersion::MAX_SUPPORTED, MAXIMUM_SUPPORTED_VERSION);
+
+        // The builder rejects unsupported versions itself, so set the version afterwards.
+        let config = |implementation, version| InterpreterConfig {
+            version,
+            ..InterpreterConfigBuilder::new(implementation, PythonVersion::PY310)
+                .build()
+                .unwrap()
+        };
+        for (implementation, version) in [
+            (PythonImplementation::CPython, PythonVersion::MIN_SUPPORTED),
+            (PythonImplementation::CPython, PythonVersion::MAX_SUPPORTED),
+            (PythonImplementation::PyPy, PythonVersion::PY310),
+            (PythonImplementation::GraalPy, PythonVersion::PY311),
+        ] {
+            assert_eq!(
+                unsupported_version_error(&config(implementation, version), false),
+                None
+            );
+        }
+
+        assert_eq!(
+            unsupported_version_error(
+                &config(PythonImplementation::CPython, PythonVersion::new(3, 6)),
+                false
+            )
+            .unwrap(),
+            "the configured Python interpreter version (3.6) is lower than PyO3's minimum \
+            supported version (3.7)"
+        );
+        assert_eq!(
+            unsupported_version_error(
+                &config(PythonImplementation::PyPy, PythonVersion::PY311),
+                true
+            )
+            .unwrap(),
+            format!(
+                "the configured PyPy interpreter version (3.11) is newer than PyO3's maximum \
+                supported version (3.10)\n\
+                = help: please check if an updated version of PyO3 is available. Current \
+                version: {}",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+
+        let newer = config(PythonImplementation::CPython, PythonVersion::new(3, 14));
+        assert!(unsupported_version_error(&newer, false)
+            .unwrap()
+            .ends_with("= help: set PYO3_USE_ABI3_FORWARD_COMPATIBILITY=1 to suppress this check and build anyway using the stable ABI"));
+        assert_eq!(unsupported_version_error(&newer, true), None);
+    }
+}
@@ -1,45 +1,14 @@
 use pyo3_build_config::{
-    bail, ensure, print_feature_cfgs,
+    bail, check_support, ensure, print_feature_cfgs,
     pyo3_build_script_impl::{
-        cargo_env_var, env_var, errors::Result, is_linking_libpython, resolve_interpreter_config,
-        InterpreterConfig, PythonVersion,
+        cargo_env_var, env_var,
+        errors::{check_denied_warnings, exit_with_error, Result},
+        is_abi3_strict, is_linking_libpython, resolve_interpreter_config, target_triple_from_env,
+        validate_extension_module_env, InterpreterConfig,
     },
     warn, PythonImplementation,
 };
 
-/// Minimum Python version PyO3 supports.
-struct SupportedVersions {
-    min: PythonVersion,
-    max: PythonVersion,
-}
-
-const SUPPORTED_VERSIONS_CPYTHON: SupportedVersions = SupportedVersions {
-    min: PythonVersion { major: 3, minor: 7 },
-    max: PythonVersion {
-        major: 3,
-        minor: 13,
-    },
-};
-
-const SUPPORTED_VERSIONS_PYPY: SupportedVersions = SupportedVersions {
-    min: PythonVersion { major: 3, minor: 7 },
-    max: PythonVersion {
-        major: 3,
-        minor: 10,
-    },
-};
-
-const SUPPORTED_VERSIONS_GRAALPY: SupportedVersions = SupportedVersions {
-    min: PythonVersion {
-        major: 3,
-        minor: 10,
-    },
-    max: PythonVersion {
-        major: 3,
-        minor: 11,
-    },
-};
-
 fn ensure_python_version(interpreter_config: &InterpreterConfig) -> Result<()> {
     // This is an undocumented env var which is only really intended to be used in CI / for testing
     // and development.
@@ -47,74 +16,32 @@ fn ensure_python_version(interpreter_config: &InterpreterConfig) -> Result<()> {
         return Ok(());
     }
 
-    match interpreter_config.implementation {
-        PythonImplementation::CPython => {
-            let versions = SUPPORTED_VERSIONS_CPYTHON;
-            ensure!(
-                interpreter_config.version >= versions.min,
-                "the configured Python interpreter version ({}) is lower than PyO3's minimum supported version ({})",
-                interpreter_config.version,
-                versions.min,
-            );
-            ensure!(
-                interpreter_config.version <= versions.max || env_var("PYO3_USE_ABI3_FORWARD_COMPATIBILITY").map_or(false, |os_str| os_str == "1"),
-                "the configured Python interpreter version ({}) is newer than PyO3's maximum supported version ({})\n\
-                 = help: please check if an updated version of PyO3 is available. Current version: {}\n\
-                 = help: set PYO3_USE_ABI3_FORWARD_COMPATIBILITY=1 to suppress this check and build anyway using the stable ABI",
-                interpreter_config.version,
-                versions.max,
-                std::env::var("CARGO_PKG_VERSION").unwrap(),
-            );
-        }
-        PythonImplementation::PyPy => {
-            let versions = SUPPORTED_VERSIONS_PYPY;
-            ensure!(
-                interpreter_config.version >= versions.min,
-                "the configured PyPy interpreter version ({}) is lower than PyO3's minimum supported version ({})",
-                interpreter_config.version,
-                versions.min,
-            );
-            // PyO3 does not support abi3, so we cannot offer forward compatibility
-            ensure!(
-                interpreter_config.version <= versions.max,
-                "the configured PyPy interpreter version ({}) is newer than PyO3's maximum supported version ({})\n\
-                 = help: please check if an updated version of PyO3 is available. Current version: {}",
-                interpreter_config.version,
-                versions.max,
-                std::env::var("CARGO_PKG_VERSION").unwrap()
-            );
-        }
-        PythonImplementation::GraalPy => {
-            let versions = SUPPORTED_VERSIONS_GRAALPY;
-            ensure!(
-                interpreter_config.version >= versions.min,
-                "the configured GraalPy interpreter version ({}) is lower than PyO3's minimum supported version ({})",
-                interpreter_config.version,
-                versions.min,
-            );
-            // GraalPy does not support abi3, so we cannot offer forward compatibility
-            ensure!(
-                interpreter_config.version <= versions.max,
-                "the configured GraalPy interpreter version ({}) is newer than PyO3's maximum supported version ({})\n\
-                 = help: please check if an updated version of PyO3 is available. Current version: {}",
-                interpreter_config.version,
-                versions.max,
-                std::env::var("CARGO_PKG_VERSION").unwrap()
-            );
-        }
-    }
+    check_support(interpreter_config)?;
+
+    ensure!(
+        !interpreter_config.gil_disabled(),
+        "the configured Python interpreter is a free-threaded build of Python {}, which is not yet supported by PyO3\n\
+         = help: use a build of Python with the GIL enabled",
+        interpreter_config.version,
+    );
 
     if interpreter_config.abi3 {
         match interpreter_config.implementation {
             PythonImplementation::CPython => {}
             PythonImplementation::PyPy => warn!(
+                PYO3_W007,
                 "PyPy does not yet support abi3 so the build artifacts will be version-specific. \
                 See https://foss.heptapod.net/pypy/pypy/-/issues/3397 for more information."
             ),
             PythonImplementation::GraalPy => warn!(
+                PYO3_W007,
                 "GraalPy does not support abi3 so the build artifacts will be version-specific."
             ),
         }
+
+        if let Some(warning) = interpreter_config.check_abi3_debug_build(is_abi3_strict())? {
+            warn!(PYO3_W007, "{}", warning);
+        }
     }
 
     Ok(())
@@ -143,28 +70,32 @@ fn ensure_target_pointer_width(interpreter_config: &InterpreterConfig) -> Result
 }
 
 fn emit_link_config(interpreter_config: &InterpreterConfig) -> Result<()> {
-    let target_os = cargo_env_var("CARGO_CFG_TARGET_OS").unwrap();
-
-    println!(
-        "cargo:rustc-link-lib={link_model}{alias}{lib_name}",
-        link_model = if interpreter_config.shared {
-            ""
-        } else {
-            "static="
-        },
-        alias = if target_os == "windows" {
-            "pythonXY:"
-        } else {
-            ""
-        },
-        lib_name = interpreter_config.lib_name.as_ref().ok_or(
-            "attempted to link to Python shared library but config does not contain lib_name"
-        )?,
+    ensure!(
+        interpreter_config.lib_name.is_some(),
+        "attempted to link to Python shared library but config does not contain lib_name"
     );
+    interpreter_config.check_link_model()?;
+
+    let target = target_triple_from_env();
+    for lib in interpreter_config.link_libraries() {
+        println!("{}", lib.to_cargo_directive(&target));
+    }
 
     if let Some(lib_dir) = &interpreter_config.lib_dir {
         println!("cargo:rustc-link-search=native={}", lib_dir);
     }
+    if let Some(directive) = interpreter_config.framework_search_directive() {
+        println!("{}", directive);
+    }
+    for directive in interpreter_config.extra_link_search_directives() {
+        println!("{}", directive);
+    }
+
+    for directive in
+        interpreter_config.extra_link_arg_directives(&cargo_env_var("CARGO_CFG_TARGET_OS").unwrap())
+    {
+        println!("{}", directive);
+    }
 
     Ok(())
 }
@@ -185,6 +116,7 @@ fn configure_pyo3() -> Result<()> {
 
     ensure_python_version(&interpreter_config)?;
     ensure_target_pointer_width(&interpreter_config)?;
+    validate_extension_module_env()?;
 
     // Serialize the whole interpreter config into DEP_PYTHON_PYO3_CONFIG env var.
     interpreter_config.to_cargo_dep_env()?;
@@ -213,14 +145,18 @@ fn print_config_and_exit(config: &InterpreterConfig) {
     config
         .to_writer(std::io::stdout())
         .expect("failed to print config to stdout");
+    if config.pointer_width_is_inferred() {
+        println!(
+            "\nnote: pointer_width was inferred from the compile target rather than detected from the target Python installation"
+        );
+    }
     println!("\nnote: unset the PYO3_PRINT_CONFIG environment variable and retry to compile with the above config");
     std::process::exit(101);
 }
 
 fn main() {
     pyo3_build_config::print_expected_cfgs();
-    if let Err(e) = configure_pyo3() {
-        eprintln!("error: {}", e.report());
-        std::process::exit(1)
+    if let Err(e) = configure_pyo3().and_then(|()| check_denied_warnings()) {
+        exit_with_error(&e)
     }
 }
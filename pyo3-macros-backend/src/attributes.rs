@@ -21,21 +21,28 @@ pub mod kw {
     syn::custom_keyword!(freelist);
     syn::custom_keyword!(from_py_with);
     syn::custom_keyword!(frozen);
+    syn::custom_keyword!(generic);
     syn::custom_keyword!(get);
     syn::custom_keyword!(get_all);
     syn::custom_keyword!(hash);
+    syn::custom_keyword!(into_py_with);
     syn::custom_keyword!(item);
     syn::custom_keyword!(from_item_all);
     syn::custom_keyword!(mapping);
+    syn::custom_keyword!(metaclass);
     syn::custom_keyword!(module);
     syn::custom_keyword!(name);
+    syn::custom_keyword!(namedtuple);
     syn::custom_keyword!(ord);
     syn::custom_keyword!(pass_module);
+    syn::custom_keyword!(pickle);
     syn::custom_keyword!(rename_all);
     syn::custom_keyword!(sequence);
     syn::custom_keyword!(set);
     syn::custom_keyword!(set_all);
     syn::custom_keyword!(signature);
+    syn::custom_keyword!(skip);
+    syn::custom_keyword!(str);
     syn::custom_keyword!(subclass);
     syn::custom_keyword!(text_signature);
     syn::custom_keyword!(transparent);
@@ -172,8 +179,53 @@ impl ToTokens for TextSignatureAttributeValue {
     }
 }
 
+/// A metaclass is either a Rust type or the import path of a Python class
+#[derive(Clone, Debug)]
+pub enum MetaclassAttributeValue {
+    Type(Path),
+    // e.g. "my_module.MyMeta", split into the module and class name
+    Import {
+        lit: LitStr,
+        module: String,
+        name: String,
+    },
+}
+
+impl Parse for MetaclassAttributeValue {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        if input.peek(LitStr) {
+            let lit: LitStr = input.parse()?;
+            let value = lit.value();
+            match value.rsplit_once('.') {
+                Some((module, name)) if !module.is_empty() && !name.is_empty() => {
+                    Ok(MetaclassAttributeValue::Import {
+                        module: module.to_owned(),
+                        name: name.to_owned(),
+                        lit,
+                    })
+                }
+                _ => bail_spanned!(
+                    lit.span() => "expected the metaclass as \"module.ClassName\""
+                ),
+            }
+        } else {
+            input.parse().map(MetaclassAttributeValue::Type)
+        }
+    }
+}
+
+impl ToTokens for MetaclassAttributeValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            MetaclassAttributeValue::Type(path) => path.to_tokens(tokens),
+            MetaclassAttributeValue::Import { lit, .. } => lit.to_tokens(tokens),
+        }
+    }
+}
+
 pub type ExtendsAttribute = KeywordAttribute<kw::extends, Path>;
 pub type FreelistAttribute = KeywordAttribute<kw::freelist, Box<Expr>>;
+pub type MetaclassAttribute = KeywordAttribute<kw::metaclass, MetaclassAttributeValue>;
 pub type ModuleAttribute = KeywordAttribute<kw::module, LitStr>;
 pub type NameAttribute = KeywordAttribute<kw::name, NameLitStr>;
 pub type RenameAllAttribute = KeywordAttribute<kw::rename_all, RenamingRuleLitStr>;
@@ -197,6 +249,7 @@ impl<K: ToTokens, V: ToTokens> ToTokens for KeywordAttribute<K, V> {
 }
 
 pub type FromPyWithAttribute = KeywordAttribute<kw::from_py_with, LitStrValue<ExprPath>>;
+pub type IntoPyWithAttribute = KeywordAttribute<kw::into_py_with, LitStrValue<ExprPath>>;
 
 /// For specifying the path to the pyo3 crate.
 pub type CrateAttribute = KeywordAttribute<Token![crate], LitStrValue<Path>>;
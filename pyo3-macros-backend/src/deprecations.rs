@@ -50,7 +50,10 @@ impl<'ctx> ToTokens for Deprecations<'ctx> {
 }
 
 pub(crate) fn deprecate_trailing_option_default(spec: &FnSpec<'_>) -> TokenStream {
+    // `with` always passes all three arguments to `__exit__`, so its `Option<T>` arguments
+    // (which are `None` when no exception was raised) never fall back to the default.
     if spec.signature.attribute.is_none()
+        && spec.python_name != "__exit__"
         && spec.signature.arguments.iter().any(|arg| {
             if let FnArg::Regular(arg) = arg {
                 arg.option_wrapped_type.is_some()
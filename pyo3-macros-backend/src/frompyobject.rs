@@ -94,6 +94,7 @@ struct NamedStructField<'a> {
     ident: &'a syn::Ident,
     getter: Option<FieldGetter>,
     from_py_with: Option<FromPyWithAttribute>,
+    skip: bool,
 }
 
 struct TupleStructField {
@@ -148,6 +149,10 @@ impl<'a> Container<'a> {
                             attrs.getter.is_none(),
                             field.span() => "`getter` is not permitted on tuple struct elements."
                         );
+                        ensure_spanned!(
+                            attrs.skip.is_none(),
+                            field.span() => "`skip` is not permitted on tuple struct elements."
+                        );
                         Ok(TupleStructField {
                             from_py_with: attrs.from_py_with,
                         })
@@ -197,6 +202,7 @@ impl<'a> Container<'a> {
                             ident,
                             getter: attrs.getter,
                             from_py_with: attrs.from_py_with,
+                            skip: attrs.skip.is_some(),
                         })
                     })
                     .collect::<Result<Vec<_>>>()?;
@@ -210,6 +216,10 @@ impl<'a> Container<'a> {
                         field.getter.is_none(),
                         field.ident.span() => "`transparent` structs may not have a `getter` for the inner field"
                     );
+                    ensure_spanned!(
+                        !field.skip,
+                        field.ident.span() => "`transparent` structs may not skip the inner field"
+                    );
                     ContainerType::StructNewtype(field.ident, field.from_py_with)
                 } else {
                     ContainerType::Struct(struct_fields)
@@ -388,6 +398,10 @@ impl<'a> Container<'a> {
         let mut fields: Punctuated<TokenStream, syn::Token![,]> = Punctuated::new();
         for field in struct_fields {
             let ident = &field.ident;
+            if field.skip {
+                fields.push(quote!(#ident: ::std::default::Default::default()));
+                continue;
+            }
             let field_name = ident.to_string();
             let getter = match field.getter.as_ref().unwrap_or(&FieldGetter::GetAttr(None)) {
                 FieldGetter::GetAttr(Some(name)) => {
@@ -445,16 +459,19 @@ impl<'a> Container<'a> {
     }
 }
 
+/// Attributes for deriving FromPyObject (and IntoPyObject) scoped on containers.
 #[derive(Default)]
-struct ContainerOptions {
+pub(crate) struct ContainerOptions {
     /// Treat the Container as a Wrapper, directly extract its fields from the input object.
-    transparent: bool,
+    pub(crate) transparent: bool,
     /// Force every field to be extracted from item of source Python object.
-    from_item_all: Option<attributes::kw::from_item_all>,
+    pub(crate) from_item_all: Option<attributes::kw::from_item_all>,
     /// Change the name of an enum variant in the generated error message.
-    annotation: Option<syn::LitStr>,
+    pub(crate) annotation: Option<syn::LitStr>,
     /// Change the path for the pyo3 crate
-    krate: Option<CrateAttribute>,
+    pub(crate) krate: Option<CrateAttribute>,
+    /// Convert the container into a `collections.namedtuple` (only used by `IntoPyObject`).
+    pub(crate) namedtuple: Option<attributes::kw::namedtuple>,
 }
 
 /// Attributes for deriving FromPyObject scoped on containers.
@@ -467,6 +484,8 @@ enum ContainerPyO3Attribute {
     ErrorAnnotation(LitStr),
     /// Change the path for the pyo3 crate
     Crate(CrateAttribute),
+    /// Convert the container into a `collections.namedtuple` (only used by `IntoPyObject`).
+    NamedTuple(attributes::kw::namedtuple),
 }
 
 impl Parse for ContainerPyO3Attribute {
@@ -484,6 +503,9 @@ impl Parse for ContainerPyO3Attribute {
             input.parse().map(ContainerPyO3Attribute::ErrorAnnotation)
         } else if lookahead.peek(Token![crate]) {
             input.parse().map(ContainerPyO3Attribute::Crate)
+        } else if lookahead.peek(attributes::kw::namedtuple) {
+            let kw: attributes::kw::namedtuple = input.parse()?;
+            Ok(ContainerPyO3Attribute::NamedTuple(kw))
         } else {
             Err(lookahead.error())
         }
@@ -491,7 +513,7 @@ impl Parse for ContainerPyO3Attribute {
 }
 
 impl ContainerOptions {
-    fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
         let mut options = ContainerOptions::default();
 
         for attr in attrs {
@@ -526,6 +548,13 @@ impl ContainerOptions {
                             );
                             options.krate = Some(path);
                         }
+                        ContainerPyO3Attribute::NamedTuple(kw) => {
+                            ensure_spanned!(
+                                options.namedtuple.is_none(),
+                                kw.span() => "`namedtuple` may only be provided once"
+                            );
+                            options.namedtuple = Some(kw);
+                        }
                     }
                 }
             }
@@ -534,15 +563,16 @@ impl ContainerOptions {
     }
 }
 
-/// Attributes for deriving FromPyObject scoped on fields.
+/// Attributes for deriving FromPyObject (and IntoPyObject) scoped on fields.
 #[derive(Clone, Debug)]
-struct FieldPyO3Attributes {
-    getter: Option<FieldGetter>,
-    from_py_with: Option<FromPyWithAttribute>,
+pub(crate) struct FieldPyO3Attributes {
+    pub(crate) getter: Option<FieldGetter>,
+    pub(crate) from_py_with: Option<FromPyWithAttribute>,
+    pub(crate) skip: Option<attributes::kw::skip>,
 }
 
 #[derive(Clone, Debug)]
-enum FieldGetter {
+pub(crate) enum FieldGetter {
     GetItem(Option<syn::Lit>),
     GetAttr(Option<LitStr>),
 }
@@ -550,6 +580,7 @@ enum FieldGetter {
 enum FieldPyO3Attribute {
     Getter(FieldGetter),
     FromPyWith(FromPyWithAttribute),
+    Skip(attributes::kw::skip),
 }
 
 impl Parse for FieldPyO3Attribute {
@@ -593,6 +624,8 @@ impl Parse for FieldPyO3Attribute {
             }
         } else if lookahead.peek(attributes::kw::from_py_with) {
             input.parse().map(FieldPyO3Attribute::FromPyWith)
+        } else if lookahead.peek(attributes::kw::skip) {
+            input.parse().map(FieldPyO3Attribute::Skip)
         } else {
             Err(lookahead.error())
         }
@@ -601,9 +634,10 @@ impl Parse for FieldPyO3Attribute {
 
 impl FieldPyO3Attributes {
     /// Extract the field attributes.
-    fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
         let mut getter = None;
         let mut from_py_with = None;
+        let mut skip = None;
 
         for attr in attrs {
             if let Some(pyo3_attrs) = get_pyo3_options(attr)? {
@@ -623,6 +657,13 @@ impl FieldPyO3Attributes {
                             );
                             from_py_with = Some(from_py_with_attr);
                         }
+                        FieldPyO3Attribute::Skip(kw) => {
+                            ensure_spanned!(
+                                skip.is_none(),
+                                kw.span() => "`skip` may only be provided once"
+                            );
+                            skip = Some(kw);
+                        }
                     }
                 }
             }
@@ -631,6 +672,7 @@ impl FieldPyO3Attributes {
         Ok(FieldPyO3Attributes {
             getter,
             from_py_with,
+            skip,
         })
     }
 }
@@ -651,6 +693,7 @@ fn verify_and_get_lifetime(generics: &syn::Generics) -> Result<Option<&syn::Life
 ///   * At least one field, in case of `#[transparent]`, exactly one field
 ///   * At least one variant for enums.
 ///   * Fields of input structs and enums must implement `FromPyObject` or be annotated with `from_py_with`
+///   * Named fields annotated with `skip` are not extracted and are set to `Default::default()`
 ///   * Derivation for structs with generic fields like `struct<T> Foo(T)`
 ///     adds `T: FromPyObject` on the derived implementation.
 pub fn build_derive_from_pyobject(tokens: &DeriveInput) -> Result<TokenStream> {
@@ -0,0 +1,333 @@
+use crate::frompyobject::{ContainerOptions, FieldGetter, FieldPyO3Attributes};
+use crate::utils::Ctx;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, spanned::Spanned, DataEnum, DeriveInput, Fields, Ident, Result};
+
+/// Describes a named field of a struct that is converted into a `dict` item.
+struct NamedStructField<'a> {
+    ident: &'a Ident,
+    key: Option<FieldGetter>,
+    skip: bool,
+}
+
+/// Container Style
+///
+/// Mirrors the container styles supported by `#[derive(FromPyObject)]`.
+enum ContainerType<'a> {
+    /// Struct Container, e.g. `struct Foo { a: String }`
+    ///
+    /// Converted into a `dict` whose keys are the field names.
+    Struct(Vec<NamedStructField<'a>>),
+    /// Struct container converted into a named tuple, e.g. `#[pyo3(namedtuple)] struct Foo { a: String }`
+    ///
+    /// The `collections.namedtuple` type is named after the container and has one field per
+    /// converted struct field.
+    NamedTuple(Vec<NamedStructField<'a>>),
+    /// Newtype struct container, e.g. `#[transparent] struct Foo { a: String }`
+    ///
+    /// The field specified by the identifier is converted directly.
+    StructNewtype(&'a Ident),
+    /// Tuple struct, e.g. `struct Foo(String)`.
+    ///
+    /// Converted into a `tuple` with the given number of elements.
+    Tuple(usize),
+    /// Tuple newtype, e.g. `#[transparent] struct Foo(String)`
+    ///
+    /// The wrapped field is converted directly.
+    TupleNewtype,
+}
+
+/// Data container
+///
+/// Either describes a struct or an enum variant.
+struct Container<'a> {
+    path: syn::Path,
+    ty: ContainerType<'a>,
+}
+
+impl<'a> Container<'a> {
+    /// Construct a container based on fields, identifier and attributes.
+    ///
+    /// Fails if the variant has no fields or incompatible attributes.
+    fn new(fields: &'a Fields, path: syn::Path, options: ContainerOptions) -> Result<Self> {
+        if let Some(namedtuple) = &options.namedtuple {
+            ensure_spanned!(
+                !options.transparent,
+                namedtuple.span() => "`transparent` and `namedtuple` cannot be used together"
+            );
+            ensure_spanned!(
+                matches!(fields, Fields::Named(named) if !named.named.is_empty()),
+                namedtuple.span() => "`namedtuple` requires a struct or variant with named fields"
+            );
+        }
+        let ty = match fields {
+            Fields::Unnamed(unnamed) if !unnamed.unnamed.is_empty() => {
+                for field in &unnamed.unnamed {
+                    let attrs = FieldPyO3Attributes::from_attrs(&field.attrs)?;
+                    ensure_spanned!(
+                        attrs.getter.is_none(),
+                        field.span() => "`getter` is not permitted on tuple struct elements."
+                    );
+                    ensure_spanned!(
+                        attrs.skip.is_none(),
+                        field.span() => "`skip` is not permitted on tuple struct elements."
+                    );
+                }
+                if unnamed.unnamed.len() == 1 {
+                    // Always treat a 1-length tuple struct as "transparent", even without the
+                    // explicit annotation.
+                    ContainerType::TupleNewtype
+                } else if options.transparent {
+                    bail_spanned!(
+                        fields.span() => "transparent structs and variants can only have 1 field"
+                    );
+                } else {
+                    ContainerType::Tuple(unnamed.unnamed.len())
+                }
+            }
+            Fields::Named(named) if !named.named.is_empty() => {
+                let struct_fields = named
+                    .named
+                    .iter()
+                    .map(|field| {
+                        let ident = field
+                            .ident
+                            .as_ref()
+                            .expect("Named fields should have identifiers");
+                        let attrs = FieldPyO3Attributes::from_attrs(&field.attrs)?;
+                        Ok(NamedStructField {
+                            ident,
+                            key: attrs.getter,
+                            skip: attrs.skip.is_some(),
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                if options.transparent {
+                    ensure_spanned!(
+                        struct_fields.len() == 1,
+                        fields.span() => "transparent structs and variants can only have 1 field"
+                    );
+                    let field = &struct_fields[0];
+                    ensure_spanned!(
+                        field.key.is_none(),
+                        field.ident.span() => "`transparent` structs may not have a `getter` for the inner field"
+                    );
+                    ensure_spanned!(
+                        !field.skip,
+                        field.ident.span() => "`transparent` structs may not skip the inner field"
+                    );
+                    ContainerType::StructNewtype(field.ident)
+                } else {
+                    ensure_spanned!(
+                        struct_fields.iter().any(|field| !field.skip),
+                        fields.span() => "cannot skip every field of a struct or variant"
+                    );
+                    if options.namedtuple.is_some() {
+                        for field in &struct_fields {
+                            ensure_spanned!(
+                                !matches!(field.key, Some(FieldGetter::GetItem(Some(_)))),
+                                field.ident.span() => "`item` keys are not supported by `namedtuple`, use `attribute(\"name\")` to rename the field"
+                            );
+                        }
+                        ContainerType::NamedTuple(struct_fields)
+                    } else {
+                        ContainerType::Struct(struct_fields)
+                    }
+                }
+            }
+            _ => bail_spanned!(
+                fields.span() => "cannot derive IntoPyObject for empty structs and variants"
+            ),
+        };
+        Ok(Container { path, ty })
+    }
+
+    /// Build the pattern destructuring the container, and the expression converting the bound
+    /// fields into a Python object.
+    fn build(&self, ctx: &Ctx) -> (TokenStream, TokenStream) {
+        let Ctx { pyo3_path } = ctx;
+        let path = &self.path;
+        match &self.ty {
+            ContainerType::StructNewtype(ident) => (
+                quote!(#path { #ident }),
+                quote!(#pyo3_path::IntoPy::<#pyo3_path::PyObject>::into_py(#ident, py)),
+            ),
+            ContainerType::TupleNewtype => (
+                quote!(#path(arg0)),
+                quote!(#pyo3_path::IntoPy::<#pyo3_path::PyObject>::into_py(arg0, py)),
+            ),
+            ContainerType::Tuple(len) => {
+                let idents: Vec<_> = (0..*len).map(|i| format_ident!("arg{}", i)).collect();
+                (
+                    quote!(#path(#(#idents),*)),
+                    quote!(
+                        #pyo3_path::Bound::unbind(
+                            #pyo3_path::Bound::into_any(
+                                #pyo3_path::types::PyTuple::new_bound(py, [
+                                    #(#pyo3_path::IntoPy::<#pyo3_path::PyObject>::into_py(#idents, py)),*
+                                ])
+                            )
+                        )
+                    ),
+                )
+            }
+            ContainerType::NamedTuple(fields) => {
+                let bindings = fields.iter().map(|field| {
+                    let ident = field.ident;
+                    if field.skip {
+                        quote!(#ident: _)
+                    } else {
+                        quote!(#ident)
+                    }
+                });
+                let converted: Vec<_> = fields.iter().filter(|field| !field.skip).collect();
+                let names = converted.iter().map(|field| match &field.key {
+                    Some(FieldGetter::GetAttr(Some(name))) => name.value(),
+                    _ => field.ident.to_string(),
+                });
+                let values = converted.iter().map(|field| {
+                    let ident = field.ident;
+                    quote!(#pyo3_path::IntoPy::<#pyo3_path::PyObject>::into_py(#ident, py))
+                });
+                let name = path.segments.last().unwrap().ident.to_string();
+                (
+                    quote!(#path { #(#bindings),* }),
+                    quote!({
+                        static TYPE: #pyo3_path::sync::GILOnceCell<
+                            #pyo3_path::Py<#pyo3_path::types::PyType>
+                        > = #pyo3_path::sync::GILOnceCell::new();
+                        #pyo3_path::impl_::intopyobject::namedtuple(
+                            py,
+                            &TYPE,
+                            #name,
+                            [#(#names),*],
+                            [#(#values),*],
+                        )
+                    }),
+                )
+            }
+            ContainerType::Struct(fields) => {
+                let bindings = fields.iter().map(|field| {
+                    let ident = field.ident;
+                    if field.skip {
+                        quote!(#ident: _)
+                    } else {
+                        quote!(#ident)
+                    }
+                });
+                let items = fields.iter().filter(|field| !field.skip).map(|field| {
+                    let ident = field.ident;
+                    let key = match &field.key {
+                        Some(FieldGetter::GetItem(Some(key))) => quote!(#key),
+                        Some(FieldGetter::GetAttr(Some(name))) => quote!(#name),
+                        Some(FieldGetter::GetItem(None))
+                        | Some(FieldGetter::GetAttr(None))
+                        | None => {
+                            let name = ident.to_string();
+                            quote!(#name)
+                        }
+                    };
+                    quote!(
+                        #pyo3_path::types::PyDictMethods::set_item(
+                            &dict,
+                            #key,
+                            #pyo3_path::IntoPy::<#pyo3_path::PyObject>::into_py(#ident, py),
+                        )
+                        .expect("Failed to set_item on dict");
+                    )
+                });
+                (
+                    quote!(#path { #(#bindings),* }),
+                    quote!({
+                        let dict = #pyo3_path::types::PyDict::new_bound(py);
+                        #(#items)*
+                        #pyo3_path::Bound::unbind(
+                            #pyo3_path::Bound::into_any(dict)
+                        )
+                    }),
+                )
+            }
+        }
+    }
+}
+
+fn build_enum(data_enum: &DataEnum, ident: &Ident, ctx: &Ctx) -> Result<TokenStream> {
+    ensure_spanned!(
+        !data_enum.variants.is_empty(),
+        ident.span() => "cannot derive IntoPyObject for empty enum"
+    );
+    let arms = data_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            let options = ContainerOptions::from_attrs(&variant.attrs)?;
+            let var_ident = &variant.ident;
+            let container =
+                Container::new(&variant.fields, parse_quote!(#ident::#var_ident), options)?;
+            let (pattern, conversion) = container.build(ctx);
+            Ok(quote!(#pattern => #conversion))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(quote!(
+        match self {
+            #(#arms),*
+        }
+    ))
+}
+
+/// Derive `IntoPy<PyObject>` for enums and structs.
+///
+///   * At least one field, in case of `#[transparent]`, exactly one field
+///   * At least one variant for enums.
+///   * Fields of input structs and enums must implement `IntoPy<PyObject>`
+///   * Derivation for structs with generic fields like `struct<T> Foo(T)`
+///     adds `T: IntoPy<PyObject>` on the derived implementation.
+pub fn build_derive_into_pyobject(tokens: &DeriveInput) -> Result<TokenStream> {
+    let options = ContainerOptions::from_attrs(&tokens.attrs)?;
+    let ctx = &Ctx::new(&options.krate);
+    let Ctx { pyo3_path } = &ctx;
+
+    let mut generics = tokens.generics.clone();
+    let type_params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+    for gen_ident in type_params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#gen_ident: #pyo3_path::IntoPy<#pyo3_path::PyObject>));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let ident = &tokens.ident;
+    let body = match &tokens.data {
+        syn::Data::Enum(en) => {
+            if options.transparent {
+                bail_spanned!(tokens.span() => "`transparent` is not supported at top level for enums");
+            }
+            build_enum(en, ident, ctx)?
+        }
+        syn::Data::Struct(st) => {
+            let st = Container::new(&st.fields, parse_quote!(#ident), options)?;
+            let (pattern, conversion) = st.build(ctx);
+            quote!(
+                let #pattern = self;
+                #conversion
+            )
+        }
+        syn::Data::Union(_) => bail_spanned!(
+            tokens.span() => "#[derive(IntoPyObject)] is not supported for unions"
+        ),
+    };
+
+    Ok(quote!(
+        #[automatically_derived]
+        impl #impl_generics #pyo3_path::IntoPy<#pyo3_path::PyObject> for #ident #ty_generics #where_clause {
+            fn into_py(self, py: #pyo3_path::Python<'_>) -> #pyo3_path::PyObject {
+                #body
+            }
+        }
+    ))
+}
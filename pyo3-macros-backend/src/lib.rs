@@ -11,6 +11,7 @@ mod utils;
 mod attributes;
 mod deprecations;
 mod frompyobject;
+mod intopyobject;
 mod konst;
 mod method;
 mod module;
@@ -19,12 +20,15 @@ mod pyclass;
 mod pyfunction;
 mod pyimpl;
 mod pymethod;
+mod pytest;
 mod pyversions;
 mod quotes;
 
 pub use frompyobject::build_derive_from_pyobject;
+pub use intopyobject::build_derive_into_pyobject;
 pub use module::{pymodule_function_impl, pymodule_module_impl, PyModuleOptions};
 pub use pyclass::{build_py_class, build_py_enum, PyClassArgs};
 pub use pyfunction::{build_py_function, PyFunctionOptions};
 pub use pyimpl::{build_py_methods, PyClassMethodsType};
+pub use pytest::{build_py_test, PyTestOptions};
 pub use utils::get_doc;
@@ -8,13 +8,16 @@ use syn::{ext::IdentExt, spanned::Spanned, Ident, Result};
 use crate::deprecations::deprecate_trailing_option_default;
 use crate::utils::Ctx;
 use crate::{
-    attributes::{FromPyWithAttribute, TextSignatureAttribute, TextSignatureAttributeValue},
+    attributes::{
+        FromPyWithAttribute, IntoPyWithAttribute, TextSignatureAttribute,
+        TextSignatureAttributeValue,
+    },
     deprecations::{Deprecation, Deprecations},
     params::{impl_arg_params, Holders},
     pyfunction::{
         FunctionSignature, PyFunctionArgPyO3Attributes, PyFunctionOptions, SignatureAttribute,
     },
-    quotes,
+    pyversions, quotes,
     utils::{self, is_abi3, PythonDoc},
 };
 
@@ -350,7 +353,7 @@ impl SelfType {
 pub enum CallingConvention {
     Noargs,   // METH_NOARGS
     Varargs,  // METH_VARARGS | METH_KEYWORDS
-    Fastcall, // METH_FASTCALL | METH_KEYWORDS (not compatible with `abi3` feature)
+    Fastcall, // METH_FASTCALL | METH_KEYWORDS (only in the stable ABI since 3.10)
     TpNew,    // special convention for tp_new
 }
 
@@ -365,8 +368,7 @@ impl CallingConvention {
         } else if signature.python_signature.kwargs.is_some() {
             // for functions that accept **kwargs, always prefer varargs
             Self::Varargs
-        } else if !is_abi3() {
-            // FIXME: available in the stable ABI since 3.10
+        } else if pyo3_build_config::get().version >= pyversions::PY_3_10 || !is_abi3() {
             Self::Fastcall
         } else {
             Self::Varargs
@@ -384,6 +386,9 @@ pub struct FnSpec<'a> {
     pub signature: FunctionSignature<'a>,
     pub convention: CallingConvention,
     pub text_signature: Option<TextSignatureAttribute>,
+    pub into_py_with: Option<IntoPyWithAttribute>,
+    /// Span of the return type, if it is `impl Iterator<..>` and converted to a Python iterator.
+    pub impl_iterator: Option<Span>,
     pub asyncness: Option<syn::Token![async]>,
     pub unsafety: Option<syn::Token![unsafe]>,
     pub deprecations: Deprecations<'a>,
@@ -411,6 +416,31 @@ pub fn parse_method_receiver(arg: &syn::FnArg) -> Result<SelfType> {
     }
 }
 
+/// Returns the span of the return type if it is `impl Iterator<..>`.
+///
+/// Such return values are converted into a Python iterator with `PyIterator::from_rust_iter`.
+pub fn impl_iterator_output(output: &syn::ReturnType) -> Option<Span> {
+    let ty = match output {
+        syn::ReturnType::Type(_, ty) => ty,
+        syn::ReturnType::Default => return None,
+    };
+    let impl_trait = match &**ty {
+        syn::Type::ImplTrait(impl_trait) => impl_trait,
+        _ => return None,
+    };
+    impl_trait
+        .bounds
+        .iter()
+        .any(|bound| match bound {
+            syn::TypeParamBound::Trait(bound) => matches!(
+                bound.path.segments.last(),
+                Some(segment) if segment.ident == "Iterator"
+            ),
+            _ => false,
+        })
+        .then(|| ty.span())
+}
+
 impl<'a> FnSpec<'a> {
     /// Parser function signature and function attributes
     pub fn parse(
@@ -424,6 +454,7 @@ impl<'a> FnSpec<'a> {
             text_signature,
             name,
             signature,
+            into_py_with,
             ..
         } = options;
 
@@ -432,6 +463,7 @@ impl<'a> FnSpec<'a> {
 
         let fn_type = Self::parse_fn_type(sig, meth_attrs, &mut python_name, &mut deprecations)?;
         ensure_signatures_on_valid_method(&fn_type, signature.as_ref(), text_signature.as_ref())?;
+        ensure_into_py_with_on_valid_method(&fn_type, into_py_with.as_ref())?;
 
         let name = &sig.ident;
         let python_name = python_name.as_ref().unwrap_or(name).unraw();
@@ -466,6 +498,8 @@ impl<'a> FnSpec<'a> {
             python_name,
             signature,
             text_signature,
+            into_py_with,
+            impl_iterator: impl_iterator_output(&sig.output),
             asyncness: sig.asyncness,
             unsafety: sig.unsafety,
             deprecations,
@@ -618,6 +652,9 @@ impl<'a> FnSpec<'a> {
                 self.asyncness.span() => "async functions are only supported with the `experimental-async` feature"
             );
         }
+        if let (Some(_), Some(into_py_with)) = (&self.asyncness, &self.into_py_with) {
+            bail_spanned!(into_py_with.kw.span() => "`into_py_with` cannot be used with `async fn`");
+        }
 
         let rust_call = |args: Vec<TokenStream>, holders: &mut Holders| {
             let mut self_arg = || self.tp.self_arg(cls, ExtractErrorMode::Raise, holders, ctx);
@@ -699,7 +736,16 @@ impl<'a> FnSpec<'a> {
                     }
                 }
             };
-            quotes::map_result_into_ptr(quotes::ok_wrap(call, ctx), ctx)
+            let call = match (self.impl_iterator, &self.into_py_with, &self.asyncness) {
+                (Some(span), None, None) => quote_spanned! { span =>
+                    #pyo3_path::types::PyIterator::from_rust_iter(py, #call)
+                },
+                _ => call,
+            };
+            quotes::map_result_into_ptr(
+                quotes::ok_wrap_with(call, self.into_py_with.as_ref(), ctx),
+                ctx,
+            )
         };
 
         let func_name = &self.name;
@@ -810,16 +856,18 @@ impl<'a> FnSpec<'a> {
                         _args: *mut #pyo3_path::ffi::PyObject,
                         _kwargs: *mut #pyo3_path::ffi::PyObject
                     ) -> #pyo3_path::PyResult<*mut #pyo3_path::ffi::PyObject> {
-                        use #pyo3_path::callback::IntoPyCallbackOutput;
                         #deprecation
                         let _slf_ref = &_slf;
+                        // Keep the raw arguments for the base type's `__new__`, as the
+                        // argument extraction below may shadow `_args` and `_kwargs`
+                        let (_raw_args, _raw_kwargs) = (_args, _kwargs);
                         let function = #rust_name; // Shadow the function name to avoid #3017
                         #arg_convert
                         #init_holders
                         let result = #call;
-                        let initializer: #pyo3_path::PyClassInitializer::<#cls> = result.convert(py)?;
+                        let initializer = #pyo3_path::impl_::pymethods::PyClassNewOutput::<#cls>::into_initializer(result)?;
                         #check_gil_refs
-                        #pyo3_path::impl_::pymethods::tp_new_impl(py, initializer, _slf)
+                        #pyo3_path::impl_::pymethods::tp_new_impl(py, initializer, _slf, _raw_args, _raw_kwargs)
                     }
                 }
             }
@@ -1101,3 +1149,21 @@ fn ensure_signatures_on_valid_method(
     }
     Ok(())
 }
+
+fn ensure_into_py_with_on_valid_method(
+    fn_type: &FnType,
+    into_py_with: Option<&IntoPyWithAttribute>,
+) -> syn::Result<()> {
+    if let Some(into_py_with) = into_py_with {
+        match fn_type {
+            FnType::Setter(_) => {
+                bail_spanned!(into_py_with.kw.span() => "`into_py_with` not allowed with `setter`")
+            }
+            FnType::FnNew | FnType::FnNewClass(_) => {
+                bail_spanned!(into_py_with.kw.span() => "`into_py_with` not allowed with `#[new]`")
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -285,6 +285,11 @@ pub fn pymodule_module_impl(mut module: syn::ItemMod) -> Result<TokenStream> {
         }
     }
 
+    // Submodules are named after their parent, so that they can be imported from it
+    let set_qualified_name = options.module.as_ref().map(|_| {
+        quote! { #pyo3_path::impl_::pymodule::set_qualified_name(module, #full_name)?; }
+    });
+
     let initialization = module_initialization(&name, ctx);
     Ok(quote!(
         #(#attrs)*
@@ -310,6 +315,7 @@ pub fn pymodule_module_impl(mut module: syn::ItemMod) -> Result<TokenStream> {
 
             fn __pyo3_pymodule(module: &#pyo3_path::Bound<'_, #pyo3_path::types::PyModule>) -> #pyo3_path::PyResult<()> {
                 use #pyo3_path::impl_::pymodule::PyAddToModule;
+                #set_qualified_name
                 #(
                     #(#module_items_cfg_attrs)*
                     #module_items::_PYO3_DEF.add_to_module(module)?;
@@ -416,7 +422,7 @@ fn module_initialization(name: &syn::Ident, ctx: &Ctx) -> TokenStream {
         #[doc(hidden)]
         #[export_name = #pyinit_symbol]
         pub unsafe extern "C" fn __pyo3_init() -> *mut #pyo3_path::ffi::PyObject {
-            #pyo3_path::impl_::trampoline::module_init(|py| _PYO3_DEF.make_module(py))
+            _PYO3_DEF.init_multi_phase()
         }
     }
 }
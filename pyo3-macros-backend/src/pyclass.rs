@@ -10,16 +10,17 @@ use syn::{parse_quote, parse_quote_spanned, spanned::Spanned, Result, Token};
 use crate::attributes::kw::frozen;
 use crate::attributes::{
     self, kw, take_pyo3_options, CrateAttribute, ExtendsAttribute, FreelistAttribute,
-    ModuleAttribute, NameAttribute, NameLitStr, RenameAllAttribute,
+    MetaclassAttribute, MetaclassAttributeValue, ModuleAttribute, NameAttribute, NameLitStr,
+    RenameAllAttribute,
 };
 use crate::deprecations::Deprecations;
 use crate::konst::{ConstAttributes, ConstSpec};
-use crate::method::{FnArg, FnSpec, PyArg, RegularArg};
+use crate::method::{FnArg, FnSpec, FnType, PyArg, RegularArg};
 use crate::pyfunction::ConstructorAttribute;
 use crate::pyimpl::{gen_py_const, PyClassMethodsType};
 use crate::pymethod::{
     impl_py_getter_def, impl_py_setter_def, MethodAndMethodDef, MethodAndSlotDef, PropertyType,
-    SlotDef, __GETITEM__, __HASH__, __INT__, __LEN__, __REPR__, __RICHCMP__,
+    SlotDef, __GETITEM__, __HASH__, __INDEX__, __INT__, __LEN__, __REPR__, __RICHCMP__, __STR__,
 };
 use crate::utils::{self, apply_renaming_rule, PythonDoc};
 use crate::utils::{is_abi3, Ctx};
@@ -66,14 +67,18 @@ pub struct PyClassPyO3Options {
     pub get_all: Option<kw::get_all>,
     pub freelist: Option<FreelistAttribute>,
     pub frozen: Option<kw::frozen>,
+    pub generic: Option<kw::generic>,
     pub hash: Option<kw::hash>,
     pub mapping: Option<kw::mapping>,
+    pub metaclass: Option<MetaclassAttribute>,
     pub module: Option<ModuleAttribute>,
     pub name: Option<NameAttribute>,
     pub ord: Option<kw::ord>,
+    pub pickle: Option<kw::pickle>,
     pub rename_all: Option<RenameAllAttribute>,
     pub sequence: Option<kw::sequence>,
     pub set_all: Option<kw::set_all>,
+    pub str: Option<kw::str>,
     pub subclass: Option<kw::subclass>,
     pub unsendable: Option<kw::unsendable>,
     pub weakref: Option<kw::weakref>,
@@ -87,15 +92,19 @@ pub enum PyClassPyO3Option {
     Extends(ExtendsAttribute),
     Freelist(FreelistAttribute),
     Frozen(kw::frozen),
+    Generic(kw::generic),
     GetAll(kw::get_all),
     Hash(kw::hash),
     Mapping(kw::mapping),
+    Metaclass(MetaclassAttribute),
     Module(ModuleAttribute),
     Name(NameAttribute),
     Ord(kw::ord),
+    Pickle(kw::pickle),
     RenameAll(RenameAllAttribute),
     Sequence(kw::sequence),
     SetAll(kw::set_all),
+    Str(kw::str),
     Subclass(kw::subclass),
     Unsendable(kw::unsendable),
     Weakref(kw::weakref),
@@ -118,24 +127,32 @@ impl Parse for PyClassPyO3Option {
             input.parse().map(PyClassPyO3Option::Freelist)
         } else if lookahead.peek(attributes::kw::frozen) {
             input.parse().map(PyClassPyO3Option::Frozen)
+        } else if lookahead.peek(attributes::kw::generic) {
+            input.parse().map(PyClassPyO3Option::Generic)
         } else if lookahead.peek(attributes::kw::get_all) {
             input.parse().map(PyClassPyO3Option::GetAll)
         } else if lookahead.peek(attributes::kw::hash) {
             input.parse().map(PyClassPyO3Option::Hash)
         } else if lookahead.peek(attributes::kw::mapping) {
             input.parse().map(PyClassPyO3Option::Mapping)
+        } else if lookahead.peek(attributes::kw::metaclass) {
+            input.parse().map(PyClassPyO3Option::Metaclass)
         } else if lookahead.peek(attributes::kw::module) {
             input.parse().map(PyClassPyO3Option::Module)
         } else if lookahead.peek(kw::name) {
             input.parse().map(PyClassPyO3Option::Name)
         } else if lookahead.peek(attributes::kw::ord) {
             input.parse().map(PyClassPyO3Option::Ord)
+        } else if lookahead.peek(attributes::kw::pickle) {
+            input.parse().map(PyClassPyO3Option::Pickle)
         } else if lookahead.peek(kw::rename_all) {
             input.parse().map(PyClassPyO3Option::RenameAll)
         } else if lookahead.peek(attributes::kw::sequence) {
             input.parse().map(PyClassPyO3Option::Sequence)
         } else if lookahead.peek(attributes::kw::set_all) {
             input.parse().map(PyClassPyO3Option::SetAll)
+        } else if lookahead.peek(attributes::kw::str) {
+            input.parse().map(PyClassPyO3Option::Str)
         } else if lookahead.peek(attributes::kw::subclass) {
             input.parse().map(PyClassPyO3Option::Subclass)
         } else if lookahead.peek(attributes::kw::unsendable) {
@@ -196,15 +213,31 @@ impl PyClassPyO3Options {
             PyClassPyO3Option::Extends(extends) => set_option!(extends),
             PyClassPyO3Option::Freelist(freelist) => set_option!(freelist),
             PyClassPyO3Option::Frozen(frozen) => set_option!(frozen),
+            PyClassPyO3Option::Generic(generic) => {
+                ensure_spanned!(
+                    python_version >= pyversions::PY_3_9,
+                    generic.span() => "`generic` requires Python >= 3.9"
+                );
+                set_option!(generic);
+            }
             PyClassPyO3Option::GetAll(get_all) => set_option!(get_all),
             PyClassPyO3Option::Hash(hash) => set_option!(hash),
             PyClassPyO3Option::Mapping(mapping) => set_option!(mapping),
+            PyClassPyO3Option::Metaclass(metaclass) => {
+                ensure_spanned!(
+                    python_version >= pyversions::PY_3_12,
+                    metaclass.span() => "`metaclass` requires Python >= 3.12"
+                );
+                set_option!(metaclass);
+            }
             PyClassPyO3Option::Module(module) => set_option!(module),
             PyClassPyO3Option::Name(name) => set_option!(name),
             PyClassPyO3Option::Ord(ord) => set_option!(ord),
+            PyClassPyO3Option::Pickle(pickle) => set_option!(pickle),
             PyClassPyO3Option::RenameAll(rename_all) => set_option!(rename_all),
             PyClassPyO3Option::Sequence(sequence) => set_option!(sequence),
             PyClassPyO3Option::SetAll(set_all) => set_option!(set_all),
+            PyClassPyO3Option::Str(str) => set_option!(str),
             PyClassPyO3Option::Subclass(subclass) => set_option!(subclass),
             PyClassPyO3Option::Unsendable(unsendable) => set_option!(unsendable),
             PyClassPyO3Option::Weakref(weakref) => {
@@ -391,25 +424,32 @@ fn impl_class(
     let (default_hash, default_hash_slot) =
         pyclass_hash(&args.options, &syn::parse_quote!(#cls), ctx)?;
 
+    let (default_str, default_str_slot) = pyclass_str(&args.options, &syn::parse_quote!(#cls), ctx);
+
     let mut slots = Vec::new();
     slots.extend(default_richcmp_slot);
     slots.extend(default_hash_slot);
+    slots.extend(default_str_slot);
 
-    let py_class_impl = PyClassImplsBuilder::new(
+    let (default_pickle, default_pickle_methods) =
+        pyclass_pickle(&args.options, &syn::parse_quote!(#cls), ctx)?;
+
+    let (default_class_getitem, default_class_getitem_method) =
+        pyclass_class_getitem(&args.options, &syn::parse_quote!(#cls), ctx)?;
+
+    let mut default_methods = descriptors_to_items(
         cls,
-        args,
-        methods_type,
-        descriptors_to_items(
-            cls,
-            args.options.rename_all.as_ref(),
-            args.options.frozen,
-            field_options,
-            ctx,
-        )?,
-        slots,
-    )
-    .doc(doc)
-    .impl_all(ctx)?;
+        args.options.rename_all.as_ref(),
+        args.options.frozen,
+        field_options,
+        ctx,
+    )?;
+    default_methods.extend(default_pickle_methods);
+    default_methods.extend(default_class_getitem_method);
+
+    let py_class_impl = PyClassImplsBuilder::new(cls, args, methods_type, default_methods, slots)
+        .doc(doc)
+        .impl_all(ctx)?;
 
     Ok(quote! {
         impl #pyo3_path::types::DerefToPyAny for #cls {}
@@ -423,6 +463,9 @@ fn impl_class(
         impl #cls {
             #default_richcmp
             #default_hash
+            #default_str
+            #(#default_pickle)*
+            #default_class_getitem
         }
     })
 }
@@ -461,6 +504,10 @@ pub fn build_py_enum(
         bail_spanned!(extends.span() => "enums can't extend from other classes");
     } else if let Some(subclass) = &args.options.subclass {
         bail_spanned!(subclass.span() => "enums can't be inherited by other classes");
+    } else if let Some(pickle) = &args.options.pickle {
+        bail_spanned!(pickle.span() => "`pickle` is not supported for enums");
+    } else if let Some(generic) = &args.options.generic {
+        bail_spanned!(generic.span() => "`generic` is not supported for enums");
     } else if enum_.variants.is_empty() {
         bail_spanned!(enum_.brace_token.span.join() => "#[pyclass] can't be used on enums without any variants");
     }
@@ -826,27 +873,66 @@ fn impl_simple_enum(
         (int_impl, int_slot)
     };
 
+    let (default_index, default_index_slot) = {
+        let mut index_impl: syn::ImplItemFn = syn::parse_quote! {
+            fn __pyo3__index__(&self) -> #repr_type {
+                self.__pyo3__int__()
+            }
+        };
+        let index_slot =
+            generate_default_protocol_slot(&ty, &mut index_impl, &__INDEX__, ctx).unwrap();
+        (index_impl, index_slot)
+    };
+
+    let (default_from_int, default_from_int_method) = {
+        let Ctx { pyo3_path } = ctx;
+        let cls_name = get_class_python_name(cls, args).to_string();
+        let variants_from_int = variants.iter().map(|variant| {
+            let variant_name = variant.ident;
+            quote! {
+                if value == #cls::#variant_name as #repr_type {
+                    return ::std::result::Result::Ok(#cls::#variant_name);
+                }
+            }
+        });
+        let mut from_int_impl: syn::ImplItemFn = syn::parse_quote! {
+            /// Returns the variant with the given integer value.
+            #[classmethod]
+            fn __pyo3__from_int__(
+                _cls: &#pyo3_path::Bound<'_, #pyo3_path::types::PyType>,
+                value: #repr_type,
+            ) -> #pyo3_path::PyResult<Self> {
+                #(#variants_from_int)*
+                ::std::result::Result::Err(#pyo3_path::exceptions::PyValueError::new_err(
+                    ::std::format!("{} is not a valid {}", value, #cls_name),
+                ))
+            }
+        };
+        let from_int_method = generate_default_method(&ty, &mut from_int_impl, "from_int", ctx)?;
+        (from_int_impl, from_int_method)
+    };
+
     let (default_richcmp, default_richcmp_slot) =
         pyclass_richcmp_simple_enum(&args.options, &ty, repr_type, ctx)?;
     let (default_hash, default_hash_slot) = pyclass_hash(&args.options, &ty, ctx)?;
+    let (default_str, default_str_slot) = pyclass_str(&args.options, &ty, ctx);
 
-    let mut default_slots = vec![default_repr_slot, default_int_slot];
+    let mut default_slots = vec![default_repr_slot, default_int_slot, default_index_slot];
     default_slots.extend(default_richcmp_slot);
     default_slots.extend(default_hash_slot);
+    default_slots.extend(default_str_slot);
 
-    let pyclass_impls = PyClassImplsBuilder::new(
+    let mut default_methods = simple_enum_default_methods(
         cls,
-        args,
-        methods_type,
-        simple_enum_default_methods(
-            cls,
-            variants.iter().map(|v| (v.ident, v.get_python_name(args))),
-            ctx,
-        ),
-        default_slots,
-    )
-    .doc(doc)
-    .impl_all(ctx)?;
+        variants.iter().map(|v| (v.ident, v.get_python_name(args))),
+        ctx,
+    );
+    default_methods.push(default_from_int_method);
+
+    let pyclass_impls =
+        PyClassImplsBuilder::new(cls, args, methods_type, default_methods, default_slots)
+            .doc(doc)
+            .impl_all(ctx)?;
 
     Ok(quote! {
         #pytypeinfo
@@ -858,8 +944,11 @@ fn impl_simple_enum(
         impl #cls {
             #default_repr
             #default_int
+            #default_index
+            #default_from_int
             #default_richcmp
             #default_hash
+            #default_str
         }
     })
 }
@@ -892,10 +981,12 @@ fn impl_complex_enum(
 
     let (default_richcmp, default_richcmp_slot) = pyclass_richcmp(&args.options, &ty, ctx)?;
     let (default_hash, default_hash_slot) = pyclass_hash(&args.options, &ty, ctx)?;
+    let (default_str, default_str_slot) = pyclass_str(&args.options, &ty, ctx);
 
     let mut default_slots = vec![];
     default_slots.extend(default_richcmp_slot);
     default_slots.extend(default_hash_slot);
+    default_slots.extend(default_str_slot);
 
     let impl_builder = PyClassImplsBuilder::new(
         cls,
@@ -1008,6 +1099,7 @@ fn impl_complex_enum(
         impl #cls {
             #default_richcmp
             #default_hash
+            #default_str
         }
 
         #(#variant_cls_zsts)*
@@ -1345,6 +1437,47 @@ fn generate_default_protocol_slot(
     )
 }
 
+/// Like `generate_default_protocol_slot`, for a method exposed to Python as `python_name`.
+///
+/// The wrapper is named so that it can't clash with a method of the same name in `#[pymethods]`,
+/// which takes precedence.
+fn generate_default_method(
+    cls: &syn::Type,
+    method: &mut syn::ImplItemFn,
+    python_name: &str,
+    ctx: &Ctx,
+) -> syn::Result<MethodAndMethodDef> {
+    let Ctx { pyo3_path } = ctx;
+    let options = PyFunctionOptions {
+        name: Some(NameAttribute {
+            kw: syn::parse_quote! { name },
+            value: NameLitStr(format_ident!("{}", python_name)),
+        }),
+        ..Default::default()
+    };
+    let spec = FnSpec::parse(&mut method.sig, &mut method.attrs, options, ctx)?;
+    let wrapper_ident = format_ident!("__default_{}__", python_name);
+    let associated_method = spec.get_wrapper_function(&wrapper_ident, Some(cls), ctx)?;
+    let methoddef = spec.get_methoddef(
+        quote! { #cls::#wrapper_ident },
+        &spec.get_doc(&method.attrs),
+        ctx,
+    );
+    let method_def = match spec.tp {
+        FnType::FnClass(_) => quote! {
+            #pyo3_path::class::PyMethodDefType::Class(#methoddef.flags(#pyo3_path::ffi::METH_CLASS))
+        },
+        FnType::FnStatic => quote! {
+            #pyo3_path::class::PyMethodDefType::Static(#methoddef.flags(#pyo3_path::ffi::METH_STATIC))
+        },
+        _ => quote! { #pyo3_path::class::PyMethodDefType::Method(#methoddef) },
+    };
+    Ok(MethodAndMethodDef {
+        associated_method,
+        method_def,
+    })
+}
+
 fn simple_enum_default_methods<'a>(
     cls: &'a syn::Ident,
     unit_variant_names: impl IntoIterator<Item = (&'a syn::Ident, Cow<'a, syn::Ident>)>,
@@ -1362,10 +1495,33 @@ fn simple_enum_default_methods<'a>(
             deprecations: Deprecations::new(ctx),
         },
     };
-    unit_variant_names
-        .into_iter()
-        .map(|(var, py_name)| gen_py_const(&cls_type, &variant_to_attribute(var, &py_name), ctx))
-        .collect()
+    let Ctx { pyo3_path } = ctx;
+    let unit_variant_names: Vec<_> = unit_variant_names.into_iter().collect();
+    let mut methods: Vec<_> = unit_variant_names
+        .iter()
+        .map(|(var, py_name)| gen_py_const(&cls_type, &variant_to_attribute(var, py_name), ctx))
+        .collect();
+
+    let members = unit_variant_names.iter().map(|(var, py_name)| {
+        let py_name = py_name.to_string();
+        quote! { (#py_name, #pyo3_path::IntoPy::into_py(#cls::#var, py)) }
+    });
+    methods.push(MethodAndMethodDef {
+        associated_method: quote! {
+            fn __default___members____(py: #pyo3_path::Python<'_>) -> #pyo3_path::PyResult<#pyo3_path::PyObject> {
+                #pyo3_path::impl_::pyclass::simple_enum_members(py, &[#(#members),*])
+            }
+        },
+        method_def: quote! {
+            #pyo3_path::class::PyMethodDefType::ClassAttribute({
+                #pyo3_path::class::PyClassAttributeDef::new(
+                    "__members__\0",
+                    #cls::__default___members____
+                )
+            })
+        },
+    });
+    methods
 }
 
 fn complex_enum_default_methods<'a>(
@@ -1492,6 +1648,8 @@ fn complex_enum_struct_variant_new<'a>(
         signature,
         convention: crate::method::CallingConvention::TpNew,
         text_signature: None,
+        into_py_with: None,
+        impl_iterator: None,
         asyncness: None,
         unsafety: None,
         deprecations: Deprecations::new(ctx),
@@ -1547,6 +1705,8 @@ fn complex_enum_tuple_variant_new<'a>(
         signature,
         convention: crate::method::CallingConvention::TpNew,
         text_signature: None,
+        into_py_with: None,
+        impl_iterator: None,
         asyncness: None,
         unsafety: None,
         deprecations: Deprecations::new(ctx),
@@ -1572,6 +1732,8 @@ fn complex_enum_variant_field_getter<'a>(
         signature,
         convention: crate::method::CallingConvention::Noargs,
         text_signature: None,
+        into_py_with: None,
+        impl_iterator: None,
         asyncness: None,
         unsafety: None,
         deprecations: Deprecations::new(ctx),
@@ -1885,6 +2047,85 @@ fn pyclass_hash(
     }
 }
 
+fn pyclass_str(
+    options: &PyClassPyO3Options,
+    cls: &syn::Type,
+    ctx: &Ctx,
+) -> (Option<syn::ImplItemFn>, Option<MethodAndSlotDef>) {
+    // FIXME: Use str.map(...).unzip() on MSRV >= 1.66
+    match options.str {
+        Some(opt) => {
+            let mut str_impl = parse_quote_spanned! { opt.span() =>
+                fn __pyo3__generated____str__(&self) -> ::std::string::String {
+                    ::std::string::ToString::to_string(self)
+                }
+            };
+            let str_slot =
+                generate_protocol_slot(cls, &mut str_impl, &__STR__, "__str__", ctx).unwrap();
+            (Some(str_impl), Some(str_slot))
+        }
+        None => (None, None),
+    }
+}
+
+fn pyclass_pickle(
+    options: &PyClassPyO3Options,
+    cls: &syn::Type,
+    ctx: &Ctx,
+) -> Result<(Vec<syn::ImplItemFn>, Vec<MethodAndMethodDef>)> {
+    let Ctx { pyo3_path } = ctx;
+    let pickle = match options.pickle {
+        Some(pickle) => pickle,
+        None => return Ok((Vec::new(), Vec::new())),
+    };
+    let mut reduce_impl: syn::ImplItemFn = parse_quote_spanned! { pickle.span() =>
+        fn __pyo3__generated____reduce__<'py>(
+            slf: &#pyo3_path::Bound<'py, Self>,
+        ) -> #pyo3_path::PyResult<#pyo3_path::Bound<'py, #pyo3_path::types::PyTuple>> {
+            #pyo3_path::impl_::pyclass::pickle_reduce(slf.as_any())
+        }
+    };
+    let mut unpickle_impl: syn::ImplItemFn = parse_quote_spanned! { pickle.span() =>
+        #[classmethod]
+        fn __pyo3__generated____pyo3_unpickle__(
+            cls: &#pyo3_path::Bound<'_, #pyo3_path::types::PyType>,
+        ) -> #pyo3_path::PyResult<#pyo3_path::PyObject> {
+            #pyo3_path::impl_::pyclass::pickle_default::<Self>(cls)
+        }
+    };
+    let reduce_method = generate_default_method(cls, &mut reduce_impl, "__reduce__", ctx)?;
+    let unpickle_method =
+        generate_default_method(cls, &mut unpickle_impl, "__pyo3_unpickle__", ctx)?;
+    Ok((
+        vec![reduce_impl, unpickle_impl],
+        vec![reduce_method, unpickle_method],
+    ))
+}
+
+fn pyclass_class_getitem(
+    options: &PyClassPyO3Options,
+    cls: &syn::Type,
+    ctx: &Ctx,
+) -> Result<(Option<syn::ImplItemFn>, Option<MethodAndMethodDef>)> {
+    let Ctx { pyo3_path } = ctx;
+    let generic = match options.generic {
+        Some(generic) => generic,
+        None => return Ok((None, None)),
+    };
+    let mut class_getitem_impl: syn::ImplItemFn = parse_quote_spanned! { generic.span() =>
+        #[classmethod]
+        fn __pyo3__generated____class_getitem__<'py>(
+            cls: &#pyo3_path::Bound<'py, #pyo3_path::types::PyType>,
+            key: &#pyo3_path::Bound<'py, #pyo3_path::PyAny>,
+        ) -> #pyo3_path::PyResult<#pyo3_path::Bound<'py, #pyo3_path::PyAny>> {
+            #pyo3_path::impl_::pyclass::class_getitem(cls, key)
+        }
+    };
+    let class_getitem_method =
+        generate_default_method(cls, &mut class_getitem_impl, "__class_getitem__", ctx)?;
+    Ok((Some(class_getitem_impl), Some(class_getitem_method)))
+}
+
 /// Implements most traits used by `#[pyclass]`.
 ///
 /// Specifically, it implements traits that only depend on class name,
@@ -2050,6 +2291,23 @@ impl<'a> PyClassImplsBuilder<'a> {
             TokenStream::new()
         };
 
+        let metaclass = match self.attr.options.metaclass.as_ref().map(|attr| &attr.value) {
+            Some(MetaclassAttributeValue::Type(metaclass)) => quote! {
+                fn metaclass(py: #pyo3_path::Python<'_>) -> #pyo3_path::PyResult<::std::option::Option<#pyo3_path::Bound<'_, #pyo3_path::types::PyType>>> {
+                    ::std::result::Result::Ok(::std::option::Option::Some(
+                        <#metaclass as #pyo3_path::PyTypeInfo>::type_object_bound(py)
+                    ))
+                }
+            },
+            Some(MetaclassAttributeValue::Import { module, name, .. }) => quote! {
+                fn metaclass(py: #pyo3_path::Python<'_>) -> #pyo3_path::PyResult<::std::option::Option<#pyo3_path::Bound<'_, #pyo3_path::types::PyType>>> {
+                    static METACLASS: #pyo3_path::sync::GILOnceCell<#pyo3_path::Py<#pyo3_path::types::PyType>> = #pyo3_path::sync::GILOnceCell::new();
+                    #pyo3_path::impl_::pyclass::import_metaclass(py, &METACLASS, #module, #name)
+                }
+            },
+            None => TokenStream::new(),
+        };
+
         let thread_checker = if self.attr.options.unsendable.is_some() {
             quote! { #pyo3_path::impl_::pyclass::ThreadCheckerImpl }
         } else {
@@ -2162,6 +2420,8 @@ impl<'a> PyClassImplsBuilder<'a> {
 
                 #weaklist_offset
 
+                #metaclass
+
                 fn lazy_type_object() -> &'static #pyo3_path::impl_::pyclass::LazyTypeObject<Self> {
                     use #pyo3_path::impl_::pyclass::LazyTypeObject;
                     static TYPE_OBJECT: LazyTypeObject<#cls> = LazyTypeObject::new();
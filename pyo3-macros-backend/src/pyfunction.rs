@@ -2,7 +2,7 @@ use crate::utils::Ctx;
 use crate::{
     attributes::{
         self, get_pyo3_options, take_attributes, take_pyo3_options, CrateAttribute,
-        FromPyWithAttribute, NameAttribute, TextSignatureAttribute,
+        FromPyWithAttribute, IntoPyWithAttribute, NameAttribute, TextSignatureAttribute,
     },
     deprecations::Deprecations,
     method::{self, CallingConvention, FnArg},
@@ -90,6 +90,7 @@ pub struct PyFunctionOptions {
     pub name: Option<NameAttribute>,
     pub signature: Option<SignatureAttribute>,
     pub text_signature: Option<TextSignatureAttribute>,
+    pub into_py_with: Option<IntoPyWithAttribute>,
     pub krate: Option<CrateAttribute>,
 }
 
@@ -103,6 +104,7 @@ impl Parse for PyFunctionOptions {
                 || lookahead.peek(attributes::kw::pass_module)
                 || lookahead.peek(attributes::kw::signature)
                 || lookahead.peek(attributes::kw::text_signature)
+                || lookahead.peek(attributes::kw::into_py_with)
             {
                 options.add_attributes(std::iter::once(input.parse()?))?;
                 if !input.is_empty() {
@@ -125,6 +127,7 @@ pub enum PyFunctionOption {
     PassModule(attributes::kw::pass_module),
     Signature(SignatureAttribute),
     TextSignature(TextSignatureAttribute),
+    IntoPyWith(IntoPyWithAttribute),
     Crate(CrateAttribute),
 }
 
@@ -139,6 +142,8 @@ impl Parse for PyFunctionOption {
             input.parse().map(PyFunctionOption::Signature)
         } else if lookahead.peek(attributes::kw::text_signature) {
             input.parse().map(PyFunctionOption::TextSignature)
+        } else if lookahead.peek(attributes::kw::into_py_with) {
+            input.parse().map(PyFunctionOption::IntoPyWith)
         } else if lookahead.peek(syn::Token![crate]) {
             input.parse().map(PyFunctionOption::Crate)
         } else {
@@ -175,6 +180,7 @@ impl PyFunctionOptions {
                 PyFunctionOption::PassModule(pass_module) => set_option!(pass_module),
                 PyFunctionOption::Signature(signature) => set_option!(signature),
                 PyFunctionOption::TextSignature(text_signature) => set_option!(text_signature),
+                PyFunctionOption::IntoPyWith(into_py_with) => set_option!(into_py_with),
                 PyFunctionOption::Crate(krate) => set_option!(krate),
             }
         }
@@ -202,6 +208,7 @@ pub fn impl_wrap_pyfunction(
         name,
         signature,
         text_signature,
+        into_py_with,
         krate,
     } = options;
 
@@ -250,6 +257,8 @@ pub fn impl_wrap_pyfunction(
         python_name,
         signature,
         text_signature,
+        into_py_with,
+        impl_iterator: method::impl_iterator_output(&func.sig.output),
         asyncness: func.sig.asyncness,
         unsafety: func.sig.unsafety,
         deprecations: Deprecations::new(ctx),
@@ -301,8 +301,7 @@ fn submit_methods_inventory(
     let Ctx { pyo3_path } = ctx;
     quote! {
         #pyo3_path::inventory::submit! {
-            type Inventory = <#ty as #pyo3_path::impl_::pyclass::PyClassImpl>::Inventory;
-            Inventory::new(#pyo3_path::impl_::pyclass::PyClassItems { methods: &[#(#methods),*], slots: &[#(#proto_impls),*] })
+            <<#ty as #pyo3_path::impl_::pyclass::PyClassImpl>::Inventory>::new(#pyo3_path::impl_::pyclass::PyClassItems { methods: &[#(#methods),*], slots: &[#(#proto_impls),*] })
         }
     }
 }
@@ -4,8 +4,9 @@ use crate::attributes::{NameAttribute, RenamingRule};
 use crate::deprecations::deprecate_trailing_option_default;
 use crate::method::{CallingConvention, ExtractErrorMode, PyArg};
 use crate::params::{check_arg_for_gil_refs, impl_regular_arg_param, Holders};
-use crate::utils::Ctx;
+use crate::pyversions;
 use crate::utils::PythonDoc;
+use crate::utils::{is_abi3, Ctx};
 use crate::{
     method::{FnArg, FnSpec, FnType, SelfType},
     pyfunction::PyFunctionOptions,
@@ -144,6 +145,7 @@ impl PyMethodKind {
             "__ge__" => PyMethodKind::Proto(PyMethodProtoKind::SlotFragment(&__GE__)),
             // Some tricky protocols which don't fit the pattern of the rest
             "__call__" => PyMethodKind::Proto(PyMethodProtoKind::Call),
+            "__init__" => PyMethodKind::Proto(PyMethodProtoKind::Init),
             "__traverse__" => PyMethodKind::Proto(PyMethodProtoKind::Traverse),
             // Not a proto
             _ => PyMethodKind::Fn,
@@ -154,6 +156,7 @@ impl PyMethodKind {
 enum PyMethodProtoKind {
     Slot(&'static SlotDef),
     Call,
+    Init,
     Traverse,
     SlotFragment(&'static SlotFragmentDef),
 }
@@ -214,23 +217,29 @@ pub fn gen_py_method(
                 PyMethodProtoKind::Call => {
                     GeneratedPyMethod::Proto(impl_call_slot(cls, method.spec, ctx)?)
                 }
+                PyMethodProtoKind::Init => {
+                    GeneratedPyMethod::Proto(impl_init_slot(cls, method.spec, ctx)?)
+                }
                 PyMethodProtoKind::Traverse => {
                     GeneratedPyMethod::Proto(impl_traverse_slot(cls, spec, ctx)?)
                 }
                 PyMethodProtoKind::SlotFragment(slot_fragment_def) => {
-                    let proto = slot_fragment_def.generate_pyproto_fragment(cls, spec, ctx)?;
+                    let proto = slot_fragment_def.generate_slot_fragment(cls, spec, ctx)?;
                     GeneratedPyMethod::SlotTraitImpl(method.method_name, proto)
                 }
             }
         }
         // ordinary functions (with some specialties)
-        (_, FnType::Fn(_)) => GeneratedPyMethod::Method(impl_py_method_def(
-            cls,
-            spec,
-            &spec.get_doc(meth_attrs),
-            None,
-            ctx,
-        )?),
+        (_, FnType::Fn(_)) => {
+            ensure_exit_signature_valid(spec)?;
+            GeneratedPyMethod::Method(impl_py_method_def(
+                cls,
+                spec,
+                &spec.get_doc(meth_attrs),
+                None,
+                ctx,
+            )?)
+        }
         (_, FnType::FnClass(_)) => GeneratedPyMethod::Method(impl_py_method_def(
             cls,
             spec,
@@ -299,14 +308,23 @@ fn ensure_no_forbidden_protocol_attributes(
     method_name: &str,
 ) -> syn::Result<()> {
     if let Some(signature) = &spec.signature.attribute {
-        // __call__ is allowed to have a signature, but nothing else is.
-        if !matches!(proto_kind, PyMethodProtoKind::Call) {
+        // __call__ and __init__ are allowed to have a signature, but nothing else is.
+        if !matches!(
+            proto_kind,
+            PyMethodProtoKind::Call | PyMethodProtoKind::Init
+        ) {
             bail_spanned!(signature.kw.span() => format!("`signature` cannot be used with magic method `{}`", method_name));
         }
     }
     if let Some(text_signature) = &spec.text_signature {
         bail_spanned!(text_signature.kw.span() => format!("`text_signature` cannot be used with magic method `{}`", method_name));
     }
+    if let Some(into_py_with) = &spec.into_py_with {
+        // __call__ is converted like an ordinary method, other magic methods are not.
+        if !matches!(proto_kind, PyMethodProtoKind::Call) {
+            bail_spanned!(into_py_with.kw.span() => format!("`into_py_with` cannot be used with magic method `{}`", method_name));
+        }
+    }
     Ok(())
 }
 
@@ -428,6 +446,65 @@ fn impl_call_slot(cls: &syn::Type, mut spec: FnSpec<'_>, ctx: &Ctx) -> Result<Me
     })
 }
 
+/// The `with` statement calls `__exit__` with exactly three positional arguments, so reject
+/// signatures which could never accept them.
+fn ensure_exit_signature_valid(spec: &FnSpec<'_>) -> Result<()> {
+    if spec.python_name != "__exit__" {
+        return Ok(());
+    }
+    let signature = &spec.signature.python_signature;
+    let accepts_three = (signature.varargs.is_some() || signature.positional_parameters.len() >= 3)
+        && signature.required_positional_parameters <= 3
+        && signature
+            .keyword_only_parameters
+            .iter()
+            .all(|(_, required)| !required);
+    ensure_spanned!(
+        accepts_three,
+        spec.name.span() => "`__exit__` must accept three positional arguments: the exception type, value and traceback"
+    );
+    Ok(())
+}
+
+fn impl_init_slot(cls: &syn::Type, mut spec: FnSpec<'_>, ctx: &Ctx) -> Result<MethodAndSlotDef> {
+    let Ctx { pyo3_path } = ctx;
+
+    ensure_spanned!(
+        matches!(spec.tp, FnType::Fn(_)),
+        spec.name.span() => "`__init__` must take `self` as its first argument"
+    );
+    // Like __call__, tp_init receives the arguments as a tuple and dict.
+    spec.convention = CallingConvention::Varargs;
+
+    let wrapper_ident = syn::Ident::new("__pymethod___init____", Span::call_site());
+    let associated_method = spec.get_wrapper_function(&wrapper_ident, Some(cls), ctx)?;
+    let slot_def = quote! {
+        #pyo3_path::ffi::PyType_Slot {
+            slot: #pyo3_path::ffi::Py_tp_init,
+            pfunc: {
+                unsafe extern "C" fn trampoline(
+                    slf: *mut #pyo3_path::ffi::PyObject,
+                    args: *mut #pyo3_path::ffi::PyObject,
+                    kwargs: *mut #pyo3_path::ffi::PyObject,
+                ) -> ::std::os::raw::c_int
+                {
+                    #pyo3_path::impl_::trampoline::initproc(
+                        slf,
+                        args,
+                        kwargs,
+                        #cls::#wrapper_ident
+                    )
+                }
+                trampoline
+            } as #pyo3_path::ffi::initproc as _
+        }
+    };
+    Ok(MethodAndSlotDef {
+        associated_method,
+        slot_def,
+    })
+}
+
 fn impl_traverse_slot(
     cls: &syn::Type,
     spec: &FnSpec<'_>,
@@ -500,7 +577,7 @@ fn impl_py_class_attribute(
 
     let wrapper_ident = format_ident!("__pymethod_{}__", name);
     let python_name = spec.null_terminated_python_name();
-    let body = quotes::ok_wrap(fncall, ctx);
+    let body = quotes::ok_wrap_with(fncall, spec.into_py_with.as_ref(), ctx);
 
     let associated_method = quote! {
         fn #wrapper_ident(py: #pyo3_path::Python<'_>) -> #pyo3_path::PyResult<#pyo3_path::PyObject> {
@@ -780,7 +857,13 @@ pub fn impl_py_getter_def(
         PropertyType::Function {
             spec, self_type, ..
         } => {
-            let call = impl_call_getter(cls, spec, self_type, &mut holders, ctx)?;
+            let mut call = impl_call_getter(cls, spec, self_type, &mut holders, ctx)?;
+            if let Some(into_py_with) = &spec.into_py_with {
+                let into_py_with = &into_py_with.value;
+                call = quote_spanned! { into_py_with.span() =>
+                    #pyo3_path::impl_::wrap::convert_with(py, #call, #into_py_with)
+                };
+            }
             quote! {
                 #pyo3_path::callback::convert(py, #call)
             }
@@ -908,7 +991,7 @@ impl PropertyType<'_> {
     }
 }
 
-const __STR__: SlotDef = SlotDef::new("Py_tp_str", "reprfunc");
+pub const __STR__: SlotDef = SlotDef::new("Py_tp_str", "reprfunc");
 pub const __REPR__: SlotDef = SlotDef::new("Py_tp_repr", "reprfunc");
 pub const __HASH__: SlotDef = SlotDef::new("Py_tp_hash", "hashfunc")
     .ret_ty(Ty::PyHashT)
@@ -951,7 +1034,7 @@ const __POS__: SlotDef = SlotDef::new("Py_nb_positive", "unaryfunc");
 const __NEG__: SlotDef = SlotDef::new("Py_nb_negative", "unaryfunc");
 const __ABS__: SlotDef = SlotDef::new("Py_nb_absolute", "unaryfunc");
 const __INVERT__: SlotDef = SlotDef::new("Py_nb_invert", "unaryfunc");
-const __INDEX__: SlotDef = SlotDef::new("Py_nb_index", "unaryfunc");
+pub const __INDEX__: SlotDef = SlotDef::new("Py_nb_index", "unaryfunc");
 pub const __INT__: SlotDef = SlotDef::new("Py_nb_int", "unaryfunc");
 const __FLOAT__: SlotDef = SlotDef::new("Py_nb_float", "unaryfunc");
 const __BOOL__: SlotDef = SlotDef::new("Py_nb_bool", "inquiry").ret_ty(Ty::Int);
@@ -1011,11 +1094,13 @@ const __IOR__: SlotDef = SlotDef::new("Py_nb_inplace_or", "binaryfunc")
 const __GETBUFFER__: SlotDef = SlotDef::new("Py_bf_getbuffer", "getbufferproc")
     .arguments(&[Ty::PyBuffer, Ty::Int])
     .ret_ty(Ty::Int)
-    .require_unsafe();
+    .require_unsafe()
+    .require_buffer_api();
 const __RELEASEBUFFER__: SlotDef = SlotDef::new("Py_bf_releasebuffer", "releasebufferproc")
     .arguments(&[Ty::PyBuffer])
     .ret_ty(Ty::Void)
-    .require_unsafe();
+    .require_unsafe()
+    .require_buffer_api();
 const __CLEAR__: SlotDef = SlotDef::new("Py_tp_clear", "inquiry")
     .arguments(&[])
     .ret_ty(Ty::Int);
@@ -1202,6 +1287,7 @@ pub struct SlotDef {
     extract_error_mode: ExtractErrorMode,
     return_mode: Option<ReturnMode>,
     require_unsafe: bool,
+    require_buffer_api: bool,
 }
 
 const NO_ARGUMENTS: &[Ty] = &[];
@@ -1216,6 +1302,7 @@ impl SlotDef {
             extract_error_mode: ExtractErrorMode::Raise,
             return_mode: None,
             require_unsafe: false,
+            require_buffer_api: false,
         }
     }
 
@@ -1258,6 +1345,12 @@ impl SlotDef {
         self
     }
 
+    /// The buffer protocol is only part of the limited API from Python 3.11.
+    const fn require_buffer_api(mut self) -> Self {
+        self.require_buffer_api = true;
+        self
+    }
+
     pub fn generate_type_slot(
         &self,
         cls: &syn::Type,
@@ -1274,6 +1367,7 @@ impl SlotDef {
             ret_ty,
             return_mode,
             require_unsafe,
+            require_buffer_api,
         } = self;
         if *require_unsafe {
             ensure_spanned!(
@@ -1281,6 +1375,15 @@ impl SlotDef {
                 spec.name.span() => format!("`{}` must be `unsafe fn`", method_name)
             );
         }
+        if *require_buffer_api {
+            ensure_spanned!(
+                pyo3_build_config::get().version >= pyversions::PY_3_11 || !is_abi3(),
+                spec.name.span() => format!(
+                    "`{}` requires Python >= 3.11 when using the `abi3` feature",
+                    method_name
+                )
+            );
+        }
         let arg_types: &Vec<_> = &arguments.iter().map(|arg| arg.ffi_type(ctx)).collect();
         let arg_idents: &Vec<_> = &(0..arguments.len())
             .map(|i| format_ident!("arg{}", i))
@@ -1391,7 +1494,7 @@ impl SlotFragmentDef {
         self
     }
 
-    fn generate_pyproto_fragment(
+    fn generate_slot_fragment(
         &self,
         cls: &syn::Type,
         spec: &FnSpec<'_>,
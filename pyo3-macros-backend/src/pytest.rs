@@ -0,0 +1,95 @@
+use crate::attributes::{kw, CrateAttribute, KeywordAttribute};
+use crate::utils::Ctx;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Result, Token,
+};
+
+/// `module = path::to::module`, a `#[pymodule]` made importable by the test.
+type ModuleAttribute = KeywordAttribute<kw::module, syn::Path>;
+
+/// The arguments of `#[pyo3::testing::test(...)]`.
+#[derive(Default)]
+pub struct PyTestOptions {
+    krate: Option<CrateAttribute>,
+    modules: Vec<ModuleAttribute>,
+}
+
+impl Parse for PyTestOptions {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let mut options = PyTestOptions::default();
+
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::module) {
+                options.modules.push(input.parse()?);
+            } else if lookahead.peek(Token![crate]) {
+                let krate: CrateAttribute = input.parse()?;
+                ensure_spanned!(
+                    options.krate.is_none(),
+                    krate.kw.span() => "`crate` may only be specified once"
+                );
+                options.krate = Some(krate);
+            } else {
+                return Err(lookahead.error());
+            }
+            if !input.is_empty() {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Wraps a test function so that it runs with the GIL held, after initializing the interpreter
+/// and adding the requested modules to `sys.modules`.
+pub fn build_py_test(func: syn::ItemFn, options: PyTestOptions) -> Result<TokenStream> {
+    let ctx = &Ctx::new(&options.krate);
+    let Ctx { pyo3_path } = ctx;
+    let syn::ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = func;
+
+    ensure_spanned!(
+        sig.asyncness.is_none(),
+        sig.asyncness.span() => "`#[pyo3::testing::test]` does not support async functions"
+    );
+    ensure_spanned!(
+        sig.generics.params.is_empty(),
+        sig.generics.span() => "`#[pyo3::testing::test]` functions cannot be generic"
+    );
+    let ident = &sig.ident;
+    let call = match sig.inputs.len() {
+        0 => quote!(#ident()),
+        1 => quote!(#ident(py)),
+        _ => bail_spanned!(
+            sig.inputs.span() => "`#[pyo3::testing::test]` functions take either no arguments or a single `Python<'_>` argument"
+        ),
+    };
+    let output = &sig.output;
+    let modules = options.modules.iter().map(|module| {
+        let path = &module.value;
+        quote!(#pyo3_path::impl_::pytest::add_module(py, #pyo3_path::wrap_pymodule!(#path)(py));)
+    });
+
+    Ok(quote! {
+        // fully qualified, so that `use pyo3::*` or a local `test` item can't shadow it
+        #[::core::prelude::v1::test]
+        #(#attrs)*
+        #vis fn #ident() #output {
+            #sig #block
+
+            #pyo3_path::impl_::pytest::run(|py| {
+                #(#modules)*
+                #call
+            })
+        }
+    })
+}
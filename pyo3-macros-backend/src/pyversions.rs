@@ -1,3 +1,15 @@
 use pyo3_build_config::PythonVersion;
 
 pub const PY_3_9: PythonVersion = PythonVersion { major: 3, minor: 9 };
+pub const PY_3_10: PythonVersion = PythonVersion {
+    major: 3,
+    minor: 10,
+};
+pub const PY_3_11: PythonVersion = PythonVersion {
+    major: 3,
+    minor: 11,
+};
+pub const PY_3_12: PythonVersion = PythonVersion {
+    major: 3,
+    minor: 12,
+};
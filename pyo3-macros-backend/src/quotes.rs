@@ -1,6 +1,8 @@
+use crate::attributes::IntoPyWithAttribute;
 use crate::utils::Ctx;
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
 
 pub(crate) fn some_wrap(obj: TokenStream, ctx: &Ctx) -> TokenStream {
     let Ctx { pyo3_path } = ctx;
@@ -17,6 +19,24 @@ pub(crate) fn ok_wrap(obj: TokenStream, ctx: &Ctx) -> TokenStream {
     }
 }
 
+/// Like `ok_wrap`, but converts the value with the `into_py_with` function if there is one.
+pub(crate) fn ok_wrap_with(
+    obj: TokenStream,
+    into_py_with: Option<&IntoPyWithAttribute>,
+    ctx: &Ctx,
+) -> TokenStream {
+    let Ctx { pyo3_path } = ctx;
+    match into_py_with {
+        Some(into_py_with) => {
+            let into_py_with = &into_py_with.value;
+            quote_spanned! { into_py_with.span() =>
+                #pyo3_path::impl_::wrap::convert_with(py, #obj, #into_py_with)
+            }
+        }
+        None => ok_wrap(obj, ctx),
+    }
+}
+
 pub(crate) fn map_result_into_ptr(result: TokenStream, ctx: &Ctx) -> TokenStream {
     let Ctx { pyo3_path } = ctx;
     quote! { #pyo3_path::impl_::wrap::map_result_into_ptr(py, #result) }
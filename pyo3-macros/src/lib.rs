@@ -5,9 +5,9 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use pyo3_macros_backend::{
-    build_derive_from_pyobject, build_py_class, build_py_enum, build_py_function, build_py_methods,
-    pymodule_function_impl, pymodule_module_impl, PyClassArgs, PyClassMethodsType,
-    PyFunctionOptions,
+    build_derive_from_pyobject, build_derive_into_pyobject, build_py_class, build_py_enum,
+    build_py_function, build_py_methods, build_py_test, pymodule_function_impl,
+    pymodule_module_impl, PyClassArgs, PyClassMethodsType, PyFunctionOptions, PyTestOptions,
 };
 use quote::quote;
 use syn::{parse::Nothing, parse_macro_input, Item};
@@ -137,6 +137,29 @@ pub fn pyfunction(attr: TokenStream, input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// A proc macro for tests which use the Python interpreter.
+///
+/// The annotated function becomes a regular `#[test]`, which initializes the Python interpreter
+/// if needed and runs with the GIL held. The function can take a `Python<'_>` token as its only
+/// argument, and return anything a `#[test]` function can return, for instance `PyResult<()>`.
+///
+/// `#[pyo3::testing::test]` accepts the following options:
+///
+/// |  Option  |  Description |
+/// | :-  | :- |
+/// | `module = path::to::module` | Adds the `#[pymodule]` at this path to `sys.modules`, so that Python code run by the test can import it. May be repeated. |
+/// | `crate = "some::path"` | Path to the `pyo3` crate, if it is not accessible as `::pyo3`. |
+///
+/// See the [testing section of the guide][1] for examples.
+///
+/// [1]: https://pyo3.rs/latest/testing.html
+#[proc_macro_attribute]
+pub fn test(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::ItemFn);
+    let options = parse_macro_input!(attr as PyTestOptions);
+    build_py_test(ast, options).unwrap_or_compile_error().into()
+}
+
 #[proc_macro_derive(FromPyObject, attributes(pyo3))]
 pub fn derive_from_py_object(item: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(item as syn::DeriveInput);
@@ -147,6 +170,16 @@ pub fn derive_from_py_object(item: TokenStream) -> TokenStream {
     .into()
 }
 
+#[proc_macro_derive(IntoPyObject, attributes(pyo3))]
+pub fn derive_into_py_object(item: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(item as syn::DeriveInput);
+    let expanded = build_derive_into_pyobject(&ast).unwrap_or_compile_error();
+    quote!(
+        #expanded
+    )
+    .into()
+}
+
 fn pyclass_impl(
     attrs: TokenStream,
     mut ast: syn::ItemStruct,
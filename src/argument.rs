@@ -0,0 +1,145 @@
+//! Arguments which distinguish "not passed" from an explicit `None`.
+//!
+//! An `Option<T>` argument with a default of `None` cannot tell `f()` apart from `f(None)`. Many
+//! Python APIs give the two calls different meanings, typically by comparing against a private
+//! sentinel object. [`MaybeMissing`] covers these cases: give the argument a default of
+//! `MaybeMissing::Missing` in the function signature, and any value the caller passes, including
+//! `None`, is extracted into `MaybeMissing::Present`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use pyo3::argument::MaybeMissing;
+//! use pyo3::prelude::*;
+//!
+//! /// Like `dict.pop`: raises `KeyError` only if no default was given, even if it was `None`.
+//! #[pyfunction]
+//! #[pyo3(signature = (key, default = MaybeMissing::Missing))]
+//! fn pop(key: &str, default: MaybeMissing<Option<i32>>) -> PyResult<Option<i32>> {
+//!     match default {
+//!         MaybeMissing::Missing => Err(pyo3::exceptions::PyKeyError::new_err(key.to_owned())),
+//!         MaybeMissing::Present(value) => Ok(value),
+//!     }
+//! }
+//! #
+//! # Python::with_gil(|py| {
+//! #     let pop = wrap_pyfunction_bound!(pop, py).unwrap();
+//! #     assert!(pop.call1(("a",)).is_err());
+//! #     assert!(pop.call1(("a", py.None())).unwrap().is_none());
+//! #     assert_eq!(pop.call1(("a", 3)).unwrap().extract::<i32>().unwrap(), 3);
+//! # });
+//! ```
+
+#[cfg(feature = "experimental-inspect")]
+use crate::inspect::types::TypeInfo;
+use crate::{types::any::PyAnyMethods, Bound, FromPyObject, PyAny, PyResult};
+
+/// A function argument which may not have been passed at all, see the
+/// [module-level documentation](self).
+///
+/// Extracting a `MaybeMissing<T>` from a Python object always produces `Present`; `Missing` is
+/// only ever produced by the argument's default in the `#[pyo3(signature = (...))]`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MaybeMissing<T> {
+    /// The argument was not passed by the caller.
+    #[default]
+    Missing,
+    /// The argument was passed by the caller, possibly as `None`.
+    Present(T),
+}
+
+impl<T> MaybeMissing<T> {
+    /// Returns `true` if the argument was not passed.
+    #[inline]
+    pub fn is_missing(&self) -> bool {
+        matches!(self, MaybeMissing::Missing)
+    }
+
+    /// Returns `true` if the argument was passed.
+    #[inline]
+    pub fn is_present(&self) -> bool {
+        matches!(self, MaybeMissing::Present(_))
+    }
+
+    /// Converts from `&MaybeMissing<T>` to `MaybeMissing<&T>`.
+    #[inline]
+    pub fn as_ref(&self) -> MaybeMissing<&T> {
+        match self {
+            MaybeMissing::Missing => MaybeMissing::Missing,
+            MaybeMissing::Present(value) => MaybeMissing::Present(value),
+        }
+    }
+
+    /// Returns the passed value, or `None` if the argument was missing.
+    #[inline]
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            MaybeMissing::Missing => None,
+            MaybeMissing::Present(value) => Some(value),
+        }
+    }
+
+    /// Returns the passed value, or `default` if the argument was missing.
+    #[inline]
+    pub fn unwrap_or(self, default: T) -> T {
+        self.into_option().unwrap_or(default)
+    }
+
+    /// Returns the passed value, or computes one with `f` if the argument was missing.
+    #[inline]
+    pub fn unwrap_or_else(self, f: impl FnOnce() -> T) -> T {
+        self.into_option().unwrap_or_else(f)
+    }
+}
+
+impl<T> From<MaybeMissing<T>> for Option<T> {
+    #[inline]
+    fn from(value: MaybeMissing<T>) -> Self {
+        value.into_option()
+    }
+}
+
+impl<'py, T> FromPyObject<'py> for MaybeMissing<T>
+where
+    T: FromPyObject<'py>,
+{
+    #[inline]
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        obj.extract().map(MaybeMissing::Present)
+    }
+
+    #[cfg(feature = "experimental-inspect")]
+    fn type_input() -> TypeInfo {
+        T::type_input()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Python;
+
+    #[test]
+    fn test_extract_is_present() {
+        Python::with_gil(|py| {
+            let none = py.None().into_bound(py);
+            assert_eq!(
+                none.extract::<MaybeMissing<Option<i32>>>().unwrap(),
+                MaybeMissing::Present(None)
+            );
+            assert!(none.extract::<MaybeMissing<i32>>().is_err());
+        });
+    }
+
+    #[test]
+    fn test_accessors() {
+        let missing = MaybeMissing::<i32>::Missing;
+        let present = MaybeMissing::Present(5);
+        assert!(missing.is_missing() && !missing.is_present());
+        assert!(present.is_present() && !present.is_missing());
+        assert_eq!(missing.unwrap_or(1), 1);
+        assert_eq!(present.unwrap_or_else(|| 1), 5);
+        assert_eq!(Option::from(present.as_ref()), Some(&5));
+        assert_eq!(MaybeMissing::<i32>::default(), missing);
+    }
+}
@@ -0,0 +1,290 @@
+//! Interoperability between Rust futures and Python's `asyncio`.
+//!
+//! [`future_into_py`] schedules a Rust future on an async [`Runtime`] and returns an
+//! `asyncio.Future` which completes with the future's output, so it can be awaited from Python.
+//! [`into_future`] goes the other way, turning a Python awaitable into a Rust future which can be
+//! awaited from any runtime.
+//!
+//! Support for [tokio](https://tokio.rs/) and [async-std](https://async.rs/) is available behind
+//! the eponymous features, see [`tokio`](mod@tokio) and [`async_std`](mod@async_std).
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "tokio")]
+//! # {
+//! use pyo3::prelude::*;
+//!
+//! /// Awaits `awaitable` from Rust, and returns the doubled result to Python.
+//! #[pyfunction]
+//! fn double<'py>(py: Python<'py>, awaitable: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+//!     let future = pyo3::asyncio::into_future(awaitable)?;
+//!     pyo3::asyncio::tokio::future_into_py(py, async move {
+//!         let value = future.await?;
+//!         Python::with_gil(|py| Ok(value.extract::<u32>(py)? * 2))
+//!     })
+//! }
+//! #
+//! # Python::with_gil(|py| {
+//! #     let globals = pyo3::types::PyDict::new_bound(py);
+//! #     globals.set_item("double", wrap_pyfunction_bound!(double, py).unwrap()).unwrap();
+//! #     py.run_bound(r#"
+//! # import asyncio
+//! #
+//! # async def answer():
+//! #     return 21
+//! #
+//! # async def main():
+//! #     assert await double(answer()) == 42
+//! #
+//! # asyncio.run(main())
+//! # "#, Some(&globals), None).unwrap();
+//! # });
+//! # }
+//! ```
+//!
+//! Both directions need a running event loop: the functions should be called from Python code
+//! executing inside the loop, for example from a `#[pyfunction]` called by a coroutine.
+use std::{
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    intern,
+    panic::panic_to_pyerr,
+    sync::GILOnceCell,
+    types::{any::PyAnyMethods, PyCFunction, PyTuple, PyTupleMethods},
+    wrap_pyfunction_bound, Bound, IntoPy, Py, PyAny, PyObject, PyResult, Python,
+};
+use pyo3_macros::pyfunction;
+
+#[cfg(feature = "async-std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+pub mod async_std;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub mod tokio;
+
+/// An async runtime which can drive the Rust futures passed to [`future_into_py`].
+pub trait Runtime {
+    /// Spawns `fut` onto the runtime, running it to completion in the background.
+    fn spawn<F>(fut: F)
+    where
+        F: Future<Output = ()> + Send + 'static;
+}
+
+/// Converts a Rust future into an `asyncio.Future` bound to the running event loop.
+///
+/// The future is spawned onto the runtime `R`; its output is converted to a Python object and
+/// set as the result of the returned `asyncio.Future`. An error, or a panic, is set as its
+/// exception instead. If the `asyncio.Future` is cancelled the Rust future still runs to
+/// completion, but its output is discarded.
+///
+/// Fails if there is no running event loop.
+pub fn future_into_py<R, F, T>(py: Python<'_>, fut: F) -> PyResult<Bound<'_, PyAny>>
+where
+    R: Runtime,
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: IntoPy<PyObject>,
+{
+    let event_loop = get_running_loop(py)?;
+    let py_future = event_loop.call_method0(intern!(py, "create_future"))?;
+
+    let event_loop = event_loop.unbind();
+    let future = py_future.clone().unbind();
+    R::spawn(async move {
+        let result = CatchUnwind(fut).await;
+        Python::with_gil(|py| {
+            let result = result.map(|value| value.into_py(py));
+            if let Err(err) = set_future_result(py, event_loop.bind(py), future.bind(py), result) {
+                err.write_unraisable_bound(py, Some(event_loop.bind(py)));
+            }
+        });
+    });
+
+    Ok(py_future)
+}
+
+/// Converts a Python awaitable into a Rust future resolving to the awaitable's result.
+///
+/// The awaitable is scheduled as a task on the running event loop, so the returned future can
+/// be awaited from any thread or runtime. Dropping the returned future does not cancel the task.
+///
+/// Fails if there is no running event loop.
+pub fn into_future(
+    awaitable: &Bound<'_, PyAny>,
+) -> PyResult<impl Future<Output = PyResult<PyObject>> + Send + 'static> {
+    let py = awaitable.py();
+    let event_loop = get_running_loop(py)?;
+
+    let shared = Arc::new(Mutex::new(Shared::default()));
+    let sender = shared.clone();
+    let on_done = PyCFunction::new_closure_bound(
+        py,
+        None,
+        None,
+        move |args: &Bound<'_, PyTuple>, _kwargs| -> PyResult<()> {
+            let result = args
+                .get_item(0)?
+                .call_method0(intern!(args.py(), "result"))
+                .map(Bound::unbind);
+            let mut shared = sender.lock().unwrap();
+            shared.result = Some(result);
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+            Ok(())
+        },
+    )?;
+
+    static SCHEDULE: GILOnceCell<Py<PyCFunction>> = GILOnceCell::new();
+    let schedule = SCHEDULE.get_or_try_init(py, || {
+        wrap_pyfunction_bound!(schedule, py).map(Bound::unbind)
+    })?;
+    event_loop.call_method1(
+        intern!(py, "call_soon_threadsafe"),
+        (schedule, awaitable, on_done),
+    )?;
+
+    Ok(PyFuture { shared })
+}
+
+fn get_running_loop(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    static GET_RUNNING_LOOP: GILOnceCell<PyObject> = GILOnceCell::new();
+    let import = || -> PyResult<_> {
+        let module = py.import_bound("asyncio")?;
+        Ok(module.getattr("get_running_loop")?.into())
+    };
+    GET_RUNNING_LOOP
+        .get_or_try_init(py, import)?
+        .bind(py)
+        .call0()
+}
+
+fn set_future_result(
+    py: Python<'_>,
+    event_loop: &Bound<'_, PyAny>,
+    future: &Bound<'_, PyAny>,
+    result: PyResult<PyObject>,
+) -> PyResult<()> {
+    static SET_RESULT: GILOnceCell<Py<PyCFunction>> = GILOnceCell::new();
+    static SET_EXCEPTION: GILOnceCell<Py<PyCFunction>> = GILOnceCell::new();
+    let (callback, arg) = match result {
+        Ok(value) => (
+            SET_RESULT.get_or_try_init(py, || {
+                wrap_pyfunction_bound!(set_result, py).map(Bound::unbind)
+            })?,
+            value,
+        ),
+        Err(err) => (
+            SET_EXCEPTION.get_or_try_init(py, || {
+                wrap_pyfunction_bound!(set_exception, py).map(Bound::unbind)
+            })?,
+            err.into_value(py).into_any(),
+        ),
+    };
+    // `Future.set_result` must be called in event loop thread,
+    // so it requires `call_soon_threadsafe`
+    let call_soon_threadsafe =
+        event_loop.call_method1(intern!(py, "call_soon_threadsafe"), (callback, future, arg));
+    if let Err(err) = call_soon_threadsafe {
+        // The event loop may have been closed while the Rust future was running, in which case
+        // nobody is left to observe the result.
+        let is_closed = event_loop.call_method0(intern!(py, "is_closed"))?;
+        if !is_closed.extract::<bool>()? {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Call `future.set_result` if the future was not cancelled in the meantime.
+#[pyfunction(crate = "crate")]
+fn set_result(future: &Bound<'_, PyAny>, value: &Bound<'_, PyAny>) -> PyResult<()> {
+    if !future
+        .call_method0(intern!(future.py(), "done"))?
+        .extract::<bool>()?
+    {
+        future.call_method1(intern!(future.py(), "set_result"), (value,))?;
+    }
+    Ok(())
+}
+
+/// Call `future.set_exception` if the future was not cancelled in the meantime.
+#[pyfunction(crate = "crate")]
+fn set_exception(future: &Bound<'_, PyAny>, exception: &Bound<'_, PyAny>) -> PyResult<()> {
+    if !future
+        .call_method0(intern!(future.py(), "done"))?
+        .extract::<bool>()?
+    {
+        future.call_method1(intern!(future.py(), "set_exception"), (exception,))?;
+    }
+    Ok(())
+}
+
+/// Wrap `awaitable` in a task on the running loop, calling `on_done` once it completes.
+#[pyfunction(crate = "crate")]
+fn schedule(awaitable: &Bound<'_, PyAny>, on_done: &Bound<'_, PyAny>) -> PyResult<()> {
+    let py = awaitable.py();
+    static ENSURE_FUTURE: GILOnceCell<PyObject> = GILOnceCell::new();
+    let import = || -> PyResult<_> {
+        let module = py.import_bound("asyncio")?;
+        Ok(module.getattr("ensure_future")?.into())
+    };
+    let task = ENSURE_FUTURE
+        .get_or_try_init(py, import)?
+        .bind(py)
+        .call1((awaitable,))?;
+    task.call_method1(intern!(py, "add_done_callback"), (on_done,))?;
+    Ok(())
+}
+
+/// State shared between the done callback of a Python task and the [`PyFuture`] awaiting it.
+#[derive(Default)]
+struct Shared {
+    result: Option<PyResult<PyObject>>,
+    waker: Option<Waker>,
+}
+
+/// Rust future returned by [`into_future`].
+struct PyFuture {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Future for PyFuture {
+    type Output = PyResult<PyObject>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Future converting a panic while polling the inner future into a Python exception.
+struct CatchUnwind<F>(F);
+
+impl<F, T> Future for CatchUnwind<F>
+where
+    F: Future<Output = PyResult<T>>,
+{
+    type Output = PyResult<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: the inner future is never moved out of the pinned wrapper.
+        let fut = unsafe { self.map_unchecked_mut(|this| &mut this.0) };
+        match panic::catch_unwind(AssertUnwindSafe(|| fut.poll(cx))) {
+            Ok(poll) => poll,
+            Err(payload) => Poll::Ready(Err(panic_to_pyerr(payload))),
+        }
+    }
+}
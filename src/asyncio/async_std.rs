@@ -0,0 +1,27 @@
+//! Running Rust futures passed to Python on the [async-std](https://async.rs/) runtime.
+use std::future::Future;
+
+use crate::{Bound, IntoPy, PyAny, PyObject, PyResult, Python};
+
+/// The async-std [`Runtime`](super::Runtime).
+pub struct AsyncStd;
+
+impl super::Runtime for AsyncStd {
+    fn spawn<F>(fut: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        drop(::async_std::task::spawn(fut));
+    }
+}
+
+/// Converts a Rust future into an `asyncio.Future`, running it on async-std.
+///
+/// See [`asyncio::future_into_py`](super::future_into_py) for details.
+pub fn future_into_py<F, T>(py: Python<'_>, fut: F) -> PyResult<Bound<'_, PyAny>>
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: IntoPy<PyObject>,
+{
+    super::future_into_py::<AsyncStd, F, T>(py, fut)
+}
@@ -0,0 +1,56 @@
+//! Running Rust futures passed to Python on a [tokio](https://tokio.rs/) runtime.
+//!
+//! Futures are spawned onto the runtime of the calling thread if there is one, and otherwise
+//! onto a multi-threaded runtime created by PyO3 on first use, see [`get_runtime`].
+use std::future::Future;
+
+use once_cell::sync::OnceCell;
+
+use crate::{Bound, IntoPy, PyAny, PyObject, PyResult, Python};
+
+static RUNTIME: OnceCell<::tokio::runtime::Runtime> = OnceCell::new();
+
+/// The tokio [`Runtime`](super::Runtime).
+pub struct Tokio;
+
+impl super::Runtime for Tokio {
+    fn spawn<F>(fut: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        match ::tokio::runtime::Handle::try_current() {
+            Ok(handle) => drop(handle.spawn(fut)),
+            Err(_) => drop(get_runtime().spawn(fut)),
+        }
+    }
+}
+
+/// Sets the runtime used to spawn futures when the calling thread is not inside a tokio runtime.
+///
+/// Returns `Err(runtime)` if the runtime was already initialized, either by a previous call or
+/// by [`get_runtime`].
+pub fn init(runtime: ::tokio::runtime::Runtime) -> Result<(), ::tokio::runtime::Runtime> {
+    RUNTIME.set(runtime)
+}
+
+/// Returns the runtime used to spawn futures when the calling thread is not inside a tokio
+/// runtime, creating a multi-threaded runtime with all drivers enabled if [`init`] was not called.
+pub fn get_runtime() -> &'static ::tokio::runtime::Runtime {
+    RUNTIME.get_or_init(|| {
+        ::tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the tokio runtime")
+    })
+}
+
+/// Converts a Rust future into an `asyncio.Future`, running it on tokio.
+///
+/// See [`asyncio::future_into_py`](super::future_into_py) for details.
+pub fn future_into_py<F, T>(py: Python<'_>, fut: F) -> PyResult<Bound<'_, PyAny>>
+where
+    F: Future<Output = PyResult<T>> + Send + 'static,
+    T: IntoPy<PyObject>,
+{
+    super::future_into_py::<Tokio, F, T>(py, fut)
+}
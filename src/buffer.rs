@@ -18,6 +18,7 @@
 // DEALINGS IN THE SOFTWARE.
 
 //! `PyBuffer` implementation
+use crate::impl_::extract_argument::PyFunctionArgument;
 use crate::Bound;
 #[cfg(feature = "gil-refs")]
 use crate::PyNativeType;
@@ -687,6 +688,60 @@ impl_element!(isize, SignedInteger);
 impl_element!(f32, Float);
 impl_element!(f64, Float);
 
+impl<T: Element> PyBuffer<T> {
+    /// Gets the buffer of `obj`, checking that it is one-dimensional and contiguous so that its
+    /// memory can be viewed directly as a `[T]`.
+    fn get_contiguous_1d(obj: &Bound<'_, PyAny>) -> PyResult<PyBuffer<T>> {
+        let buf = Self::get_bound(obj)?;
+        if buf.dimensions() != 1 {
+            Err(PyBufferError::new_err(format!(
+                "expected a one-dimensional buffer, got {} dimensions",
+                buf.dimensions()
+            )))
+        } else if !buf.is_c_contiguous() {
+            Err(PyBufferError::new_err("buffer is not contiguous"))
+        } else {
+            Ok(buf)
+        }
+    }
+}
+
+// `&[T]` function arguments are extracted from any object supporting the buffer protocol (such as
+// one-dimensional numpy arrays or `array.array`) without copying. The buffer is held for the
+// duration of the function call.
+//
+// Writable buffers are accepted, so the slice is only immutable as long as nothing else writes to
+// the buffer. The function must not run Python code which modifies the buffer, nor release the GIL
+// while other threads could modify it, until the slice is no longer used. Functions which can't
+// guarantee this should take `PyBuffer<T>` and copy the contents with `copy_to_slice` instead.
+//
+// `&[u8]` is not included, because it is extracted from `bytes` only.
+macro_rules! impl_slice_argument(
+    ($($t:ty),*) => {
+        $(
+            impl<'a> PyFunctionArgument<'a, '_> for &'a [$t] {
+                type Holder = Option<PyBuffer<$t>>;
+
+                fn extract(
+                    obj: &'a Bound<'_, PyAny>,
+                    holder: &'a mut Option<PyBuffer<$t>>,
+                ) -> PyResult<Self> {
+                    let buf = holder.insert(PyBuffer::get_contiguous_1d(obj)?);
+                    if buf.item_count() == 0 {
+                        return Ok(&[]);
+                    }
+                    // Safety: the buffer was checked to be contiguous and compatible with the
+                    // element type, and remains valid until the holder is dropped. Writes to it
+                    // while the slice is in use are excluded by the contract documented above.
+                    Ok(unsafe { slice::from_raw_parts(buf.0.buf as *const $t, buf.item_count()) })
+                }
+            }
+        )*
+    }
+);
+
+impl_slice_argument!(u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
 #[cfg(test)]
 mod tests {
     use super::PyBuffer;
@@ -88,7 +88,7 @@ impl IntoPyCallbackOutput<ffi::Py_ssize_t> for usize {
     }
 }
 
-// Converters needed for `#[pyproto]` implementations
+// Converters needed for the slot implementations generated by `#[pymethods]`
 
 impl IntoPyCallbackOutput<bool> for bool {
     #[inline]
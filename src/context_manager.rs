@@ -0,0 +1,269 @@
+//! Using Python context managers from Rust.
+//!
+//! Calling `__enter__` and `__exit__` by hand makes it easy to skip `__exit__` when an error is
+//! returned early with `?` or when the code in between panics. [`PyContextManager`] is a guard
+//! which performs the steps of a Python `with` statement and calls `__exit__` when it is dropped
+//! if it has not been exited explicitly.
+//!
+//! # Example
+//!
+//! ```rust
+//! use pyo3::context_manager::PyContextManager;
+//! use pyo3::prelude::*;
+//!
+//! # fn main() -> PyResult<()> {
+//! Python::with_gil(|py| {
+//!     let tempfile = py.import_bound("tempfile")?;
+//!     let file = tempfile.call_method0("TemporaryFile")?;
+//!
+//!     // Equivalent to `with file as f: f.write(b"hello")`
+//!     let written = PyContextManager::with(&file, |f| {
+//!         f.call_method1("write", (&b"hello"[..],))?.extract::<usize>()
+//!     })?;
+//!     assert_eq!(written, Some(5));
+//!     assert!(file.getattr("closed")?.is_truthy()?);
+//!     Ok(())
+//! })
+//! # }
+//! ```
+use crate::exceptions::{PyAttributeError, PyTypeError};
+use crate::panic::PanicException;
+use crate::types::any::PyAnyMethods;
+use crate::types::typeobject::PyTypeMethods;
+use crate::{intern, Bound, PyAny, PyErr, PyResult};
+
+/// A Python context manager which has been entered, like the target of a `with` statement.
+///
+/// If the guard is dropped without calling [`exit`](Self::exit) or
+/// [`exit_with_error`](Self::exit_with_error), `__exit__` is still called: with
+/// `(None, None, None)` on a normal drop, and with a [`PanicException`] while unwinding from a
+/// panic. Errors raised by `__exit__` in that case cannot be returned and are reported through
+/// [`PyErr::write_unraisable_bound`].
+#[derive(Debug)]
+pub struct PyContextManager<'py> {
+    manager: Bound<'py, PyAny>,
+    /// The `__exit__` method looked up on the type, or `None` once it has been called.
+    exit: Option<Bound<'py, PyAny>>,
+}
+
+impl<'py> PyContextManager<'py> {
+    /// Calls `__enter__` on `manager`, returning the guard and the value `__enter__` returned,
+    /// which is what `with manager as value` binds to `value`.
+    ///
+    /// Like the `with` statement, `__enter__` and `__exit__` are looked up on the type of
+    /// `manager`, and a `TypeError` is raised if either of them is missing.
+    pub fn enter(manager: &Bound<'py, PyAny>) -> PyResult<(Self, Bound<'py, PyAny>)> {
+        let py = manager.py();
+        let ty = manager.get_type();
+        let lookup = |name| {
+            ty.getattr(name).map_err(|err| {
+                if err.is_instance_of::<PyAttributeError>(py) {
+                    let type_name = ty.qualname().unwrap_or_else(|_| "<unknown>".to_owned());
+                    PyTypeError::new_err(format!(
+                        "'{}' object does not support the context manager protocol",
+                        type_name
+                    ))
+                } else {
+                    err
+                }
+            })
+        };
+        let enter = lookup(intern!(py, "__enter__"))?;
+        let exit = lookup(intern!(py, "__exit__"))?;
+        let value = enter.call1((manager,))?;
+        let guard = PyContextManager {
+            manager: manager.clone(),
+            exit: Some(exit),
+        };
+        Ok((guard, value))
+    }
+
+    /// Runs `f` inside the runtime context of `manager`, passing it the value returned by
+    /// `__enter__`.
+    ///
+    /// Returns `Ok(None)` if `f` failed and `__exit__` suppressed the error by returning a true
+    /// value.
+    pub fn with<R>(
+        manager: &Bound<'py, PyAny>,
+        f: impl FnOnce(&Bound<'py, PyAny>) -> PyResult<R>,
+    ) -> PyResult<Option<R>> {
+        let (guard, value) = Self::enter(manager)?;
+        match f(&value) {
+            Ok(result) => guard.exit().map(|()| Some(result)),
+            Err(err) => guard.exit_with_error(err).map(|()| None),
+        }
+    }
+
+    /// Exits the runtime context without an exception, calling `__exit__(None, None, None)`.
+    pub fn exit(mut self) -> PyResult<()> {
+        self.call_exit(None).map(drop)
+    }
+
+    /// Exits the runtime context because of `err`, passing its type, value and traceback to
+    /// `__exit__`.
+    ///
+    /// Returns `Ok(())` if `__exit__` suppressed the error, `Err(err)` if it did not, and the
+    /// error raised by `__exit__` itself if it failed.
+    pub fn exit_with_error(mut self, err: PyErr) -> PyResult<()> {
+        if self.call_exit(Some(&err))? {
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /// Calls `__exit__` and returns whether it asked for the exception to be suppressed.
+    fn call_exit(&mut self, err: Option<&PyErr>) -> PyResult<bool> {
+        let py = self.manager.py();
+        let exit = self
+            .exit
+            .take()
+            .expect("__exit__ is only called once per context manager");
+        let result = match err {
+            Some(err) => exit.call1((
+                &self.manager,
+                err.get_type_bound(py),
+                err.value_bound(py),
+                err.traceback_bound(py),
+            ))?,
+            None => exit.call1((&self.manager, py.None(), py.None(), py.None()))?,
+        };
+        // `with` only consults the return value when an exception is being handled.
+        Ok(err.is_some() && result.is_truthy()?)
+    }
+}
+
+impl Drop for PyContextManager<'_> {
+    fn drop(&mut self) {
+        if self.exit.is_none() {
+            return;
+        }
+        let result = if std::thread::panicking() {
+            let err = PanicException::new_err("panic while inside a context manager");
+            self.call_exit(Some(&err))
+        } else {
+            self.call_exit(None)
+        };
+        if let Err(err) = result {
+            err.write_unraisable_bound(self.manager.py(), Some(&self.manager));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exceptions::PyValueError;
+    use crate::types::{PyList, PyModule};
+    use crate::Python;
+
+    const RECORDER: &str = r#"
+class Recorder:
+    def __init__(self, suppress=False):
+        self.calls = []
+        self.suppress = suppress
+
+    def __enter__(self):
+        self.calls.append("enter")
+        return "value"
+
+    def __exit__(self, exc_type, exc_value, traceback):
+        self.calls.append(None if exc_type is None else exc_type.__name__)
+        return self.suppress
+"#;
+
+    fn recorder<'py>(py: Python<'py>, suppress: bool) -> Bound<'py, PyAny> {
+        PyModule::from_code_bound(py, RECORDER, "recorder.py", "recorder")
+            .unwrap()
+            .getattr("Recorder")
+            .unwrap()
+            .call1((suppress,))
+            .unwrap()
+    }
+
+    fn calls(manager: &Bound<'_, PyAny>) -> Vec<Option<String>> {
+        manager
+            .getattr("calls")
+            .unwrap()
+            .downcast_into::<PyList>()
+            .unwrap()
+            .extract()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_with_ok() {
+        Python::with_gil(|py| {
+            let manager = recorder(py, false);
+            let value = PyContextManager::with(&manager, |value| value.extract::<String>())
+                .unwrap()
+                .unwrap();
+            assert_eq!(value, "value");
+            assert_eq!(calls(&manager), [Some("enter".to_owned()), None]);
+        });
+    }
+
+    #[test]
+    fn test_with_error() {
+        Python::with_gil(|py| {
+            let manager = recorder(py, false);
+            let err =
+                PyContextManager::with(&manager, |_| Err::<(), _>(PyValueError::new_err("oops")))
+                    .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert_eq!(
+                calls(&manager),
+                [Some("enter".to_owned()), Some("ValueError".to_owned())]
+            );
+        });
+    }
+
+    #[test]
+    fn test_with_error_suppressed() {
+        Python::with_gil(|py| {
+            let manager = recorder(py, true);
+            let result =
+                PyContextManager::with(&manager, |_| Err::<(), _>(PyValueError::new_err("oops")))
+                    .unwrap();
+            assert_eq!(result, None);
+        });
+    }
+
+    #[test]
+    fn test_exit_on_drop() {
+        Python::with_gil(|py| {
+            let manager = recorder(py, false);
+            let (guard, _) = PyContextManager::enter(&manager).unwrap();
+            drop(guard);
+            assert_eq!(calls(&manager), [Some("enter".to_owned()), None]);
+        });
+    }
+
+    #[test]
+    fn test_exit_on_panic() {
+        Python::with_gil(|py| {
+            let manager = recorder(py, true);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let _guard = PyContextManager::enter(&manager).unwrap();
+                panic!("oops");
+            }));
+            assert!(result.is_err());
+            assert_eq!(
+                calls(&manager),
+                [Some("enter".to_owned()), Some("PanicException".to_owned())]
+            );
+        });
+    }
+
+    #[test]
+    fn test_not_a_context_manager() {
+        Python::with_gil(|py| {
+            let err = PyContextManager::enter(&py.None().into_bound(py)).unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+            assert_eq!(
+                err.to_string(),
+                "TypeError: 'NoneType' object does not support the context manager protocol"
+            );
+        });
+    }
+}
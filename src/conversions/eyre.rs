@@ -13,6 +13,10 @@
 //! You might find that you need to map the error from your Rust code into another Python exception.
 //! See [`PyErr::new`] for more information about that.
 //!
+//! The [`RuntimeError`] contains the whole chain of errors in its message. To instead raise each
+//! error of the chain as a separate exception, linked through `__cause__`, use
+//! [`into_pyerr_with_causes`].
+//!
 //! For information about error handling in general, see the [Error handling] chapter of the Rust
 //! book.
 //!
@@ -103,7 +107,7 @@
 //! [Error handling]: https://doc.rust-lang.org/book/ch09-02-recoverable-errors-with-result.html "Recoverable Errors with Result - The Rust Programming Language"
 
 use crate::exceptions::PyRuntimeError;
-use crate::PyErr;
+use crate::{PyErr, Python};
 use eyre::Report;
 
 /// Converts [`eyre::Report`] to a [`PyErr`] containing a [`PyRuntimeError`].
@@ -123,6 +127,43 @@ impl From<eyre::Report> for PyErr {
     }
 }
 
+/// Converts [`eyre::Report`] to a [`PyErr`], attaching the chain of sources as exception causes.
+///
+/// Unlike the `From` conversion, which formats the whole chain into the message of a single
+/// `RuntimeError`, each error of the chain becomes a separate exception, linked to the next one
+/// through `__cause__`; Python then prints the chain like one raised with `raise ... from ...`.
+/// Errors of the chain which are a [`PyErr`] are kept as they are, other errors are converted to a
+/// `RuntimeError` with the error's message.
+///
+/// # Examples
+///
+/// ```rust
+/// use pyo3::eyre::into_pyerr_with_causes;
+/// use pyo3::exceptions::PyValueError;
+/// use pyo3::prelude::*;
+/// use eyre::WrapErr;
+///
+/// #[pyfunction]
+/// fn load_config() -> PyResult<()> {
+///     let result: Result<(), PyErr> = Err(PyValueError::new_err("invalid port"));
+///     Python::with_gil(|py| {
+///         result
+///             .wrap_err("failed to load config")
+///             .map_err(|error| into_pyerr_with_causes(py, error))
+///     })
+/// }
+///
+/// Python::with_gil(|py| {
+///     let err = load_config().unwrap_err();
+///     assert_eq!(err.to_string(), "RuntimeError: failed to load config");
+///     let cause = err.cause(py).unwrap();
+///     assert!(cause.is_instance_of::<PyValueError>(py));
+/// });
+/// ```
+pub fn into_pyerr_with_causes(py: Python<'_>, error: eyre::Report) -> PyErr {
+    crate::err::error_chain_to_pyerr(py, error.chain())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::exceptions::{PyRuntimeError, PyValueError};
@@ -193,4 +234,26 @@ mod tests {
             |py| converted.is_instance_of::<PyRuntimeError>(py)
         ))
     }
+
+    #[test]
+    fn test_into_pyerr_with_causes() {
+        Python::with_gil(|py| {
+            let err = super::into_pyerr_with_causes(py, h().unwrap_err());
+            assert_eq!(err.to_string(), "RuntimeError: g failed");
+            let cause = err.cause(py).unwrap();
+            assert_eq!(cause.to_string(), "RuntimeError: f failed");
+            let cause = cause.cause(py).unwrap();
+            assert_eq!(cause.to_string(), "RuntimeError: oh no!");
+            assert!(cause.cause(py).is_none());
+
+            let origin_exc = PyValueError::new_err("Value Error");
+            let mut error: Report = origin_exc.into();
+            error = error.wrap_err("Wrapped");
+            let err = super::into_pyerr_with_causes(py, error);
+            assert_eq!(err.to_string(), "RuntimeError: Wrapped");
+            let cause = err.cause(py).unwrap();
+            assert!(cause.is_instance_of::<PyValueError>(py));
+            assert_eq!(cause.to_string(), "ValueError: Value Error");
+        })
+    }
 }
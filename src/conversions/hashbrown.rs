@@ -16,6 +16,8 @@
 //!
 //! Note that you must use compatible versions of hashbrown and PyO3.
 //! The required hashbrown version may vary based on the version of PyO3.
+#[cfg(feature = "experimental-inspect")]
+use crate::inspect::types::TypeInfo;
 use crate::{
     types::any::PyAnyMethods,
     types::dict::PyDictMethods,
@@ -49,6 +51,11 @@ where
             .map(|(k, v)| (k.into_py(py), v.into_py(py)));
         IntoPyDict::into_py_dict_bound(iter, py).into()
     }
+
+    #[cfg(feature = "experimental-inspect")]
+    fn type_output() -> TypeInfo {
+        TypeInfo::dict_of(K::type_output(), V::type_output())
+    }
 }
 
 impl<'py, K, V, S> FromPyObject<'py> for hashbrown::HashMap<K, V, S>
@@ -65,11 +72,17 @@ where
         }
         Ok(ret)
     }
+
+    #[cfg(feature = "experimental-inspect")]
+    fn type_input() -> TypeInfo {
+        TypeInfo::mapping_of(K::type_input(), V::type_input())
+    }
 }
 
-impl<T> ToPyObject for hashbrown::HashSet<T>
+impl<T, S> ToPyObject for hashbrown::HashSet<T, S>
 where
     T: hash::Hash + Eq + ToPyObject,
+    S: hash::BuildHasher + Default,
 {
     fn to_object(&self, py: Python<'_>) -> PyObject {
         new_from_iter(py, self)
@@ -88,6 +101,11 @@ where
             .expect("Failed to create Python set from hashbrown::HashSet")
             .into()
     }
+
+    #[cfg(feature = "experimental-inspect")]
+    fn type_output() -> TypeInfo {
+        TypeInfo::set_of(K::type_output())
+    }
 }
 
 impl<'py, K, S> FromPyObject<'py> for hashbrown::HashSet<K, S>
@@ -107,6 +125,11 @@ where
             }
         }
     }
+
+    #[cfg(feature = "experimental-inspect")]
+    fn type_input() -> TypeInfo {
+        TypeInfo::set_of(K::type_input())
+    }
 }
 
 #[cfg(test)]
@@ -201,4 +224,17 @@ mod tests {
             assert_eq!(hs, hso.extract(py).unwrap());
         });
     }
+
+    #[test]
+    fn test_hashbrown_hashset_custom_hasher_to_python() {
+        Python::with_gil(|py| {
+            let mut hs =
+                hashbrown::HashSet::with_hasher(std::collections::hash_map::RandomState::new());
+            hs.extend([1u64, 2, 3]);
+
+            let hso = hs.to_object(py);
+
+            assert_eq!(hs, hso.extract(py).unwrap());
+        });
+    }
 }
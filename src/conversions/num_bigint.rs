@@ -121,7 +121,11 @@ macro_rules! bigint_conversion {
                     None
                 };
                 py.get_type_bound::<PyLong>()
-                    .call_method("from_bytes", (bytes_obj, "little"), kwargs.as_ref())
+                    .call_method(
+                        crate::intern!(py, "from_bytes"),
+                        (bytes_obj, crate::intern!(py, "little")),
+                        kwargs.as_ref(),
+                    )
                     .expect("int.from_bytes() failed during to_object()") // FIXME: #1813 or similar
                     .into()
             }
@@ -429,6 +433,22 @@ mod tests {
         })
     }
 
+    #[test]
+    fn negative_to_biguint() {
+        Python::with_gil(|py| {
+            for value in [-1i64, i64::MIN] {
+                let err = value.to_object(py).extract::<BigUint>(py).unwrap_err();
+                assert!(err.is_instance_of::<crate::exceptions::PyOverflowError>(py));
+            }
+            let big_negative = -(BigInt::from(1) << 100usize);
+            let err = big_negative
+                .to_object(py)
+                .extract::<BigUint>(py)
+                .unwrap_err();
+            assert!(err.is_instance_of::<crate::exceptions::PyOverflowError>(py));
+        })
+    }
+
     /// `OverflowError` on converting Python int to BigInt, see issue #629
     #[test]
     fn check_overflow() {
@@ -181,6 +181,23 @@ mod test_rust_decimal {
         }
     }
 
+    #[test]
+    fn test_integers_outside_i64() {
+        Python::with_gil(|py| {
+            for expr in ["2**70", "-2**70", "2**96 - 1"] {
+                let py_num = py.eval_bound(expr, None, None).unwrap();
+                let roundtripped: Decimal = py_num.extract().unwrap();
+                assert_eq!(
+                    roundtripped.to_string(),
+                    py_num.str().unwrap().to_cow().unwrap()
+                );
+            }
+            // too large for the 96 bit mantissa of `Decimal`
+            let py_num = py.eval_bound("2**96", None, None).unwrap();
+            assert!(py_num.extract::<Decimal>().is_err());
+        })
+    }
+
     #[test]
     fn test_nan() {
         Python::with_gil(|py| {
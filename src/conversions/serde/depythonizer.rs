@@ -0,0 +1,258 @@
+use super::Error;
+use crate::exceptions::{PyTypeError, PyValueError};
+use crate::types::{
+    PyAnyMethods, PyBool, PyBoolMethods, PyByteArray, PyByteArrayMethods, PyBytes, PyBytesMethods,
+    PyDict, PyDictMethods, PyFloat, PyFrozenSet, PyList, PyLong, PyMapping, PyMappingMethods,
+    PySequence, PySet, PyString, PyStringMethods, PyTuple, PyTypeMethods,
+};
+use crate::{Bound, PyAny, PyResult};
+use serde::de::{self, IntoDeserializer, Visitor};
+
+/// A serde `Deserializer` reading from a Python object.
+pub(super) struct Depythonizer<'py> {
+    obj: Bound<'py, PyAny>,
+}
+
+impl<'py> Depythonizer<'py> {
+    pub(super) fn new(obj: Bound<'py, PyAny>) -> Self {
+        Depythonizer { obj }
+    }
+
+    fn unsupported(&self, expected: &str) -> Error {
+        let type_name = self
+            .obj
+            .get_type()
+            .qualname()
+            .map_or_else(|_| "<unknown>".to_owned(), |name| name.to_string());
+        Error(PyTypeError::new_err(format!(
+            "cannot deserialize '{}' object, expected {}",
+            type_name, expected
+        )))
+    }
+
+    fn visit_int<'de, V: Visitor<'de>>(&self, visitor: V) -> Result<V::Value, Error> {
+        if let Ok(v) = self.obj.extract::<i64>() {
+            visitor.visit_i64(v)
+        } else if let Ok(v) = self.obj.extract::<u64>() {
+            visitor.visit_u64(v)
+        } else if let Ok(v) = self.obj.extract::<i128>() {
+            visitor.visit_i128(v)
+        } else {
+            visitor.visit_u128(self.obj.extract::<u128>()?)
+        }
+    }
+}
+
+fn items<'py>(iterable: &Bound<'py, PyAny>) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    iterable.iter()?.collect()
+}
+
+impl<'de, 'py> de::Deserializer<'de> for Depythonizer<'py> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let obj = &self.obj;
+        if obj.is_none() {
+            visitor.visit_unit()
+        } else if let Ok(b) = obj.downcast::<PyBool>() {
+            visitor.visit_bool(b.is_true())
+        } else if obj.is_instance_of::<PyLong>() {
+            self.visit_int(visitor)
+        } else if obj.is_instance_of::<PyFloat>() {
+            visitor.visit_f64(obj.extract()?)
+        } else if let Ok(s) = obj.downcast::<PyString>() {
+            visitor.visit_string(s.to_cow()?.into_owned())
+        } else if let Ok(b) = obj.downcast::<PyBytes>() {
+            visitor.visit_byte_buf(b.as_bytes().to_vec())
+        } else if let Ok(b) = obj.downcast::<PyByteArray>() {
+            visitor.visit_byte_buf(b.to_vec())
+        } else if let Ok(dict) = obj.downcast::<PyDict>() {
+            visitor.visit_map(MapAccess::new(dict.iter().collect()))
+        } else if obj.is_instance_of::<PyList>()
+            || obj.is_instance_of::<PyTuple>()
+            || obj.is_instance_of::<PySet>()
+            || obj.is_instance_of::<PyFrozenSet>()
+        {
+            visitor.visit_seq(SeqAccess::new(items(obj)?))
+        } else if let Ok(mapping) = obj.downcast::<PyMapping>() {
+            let items = items(mapping.items()?.as_any())?
+                .iter()
+                .map(|item| item.extract())
+                .collect::<PyResult<_>>()?;
+            visitor.visit_map(MapAccess::new(items))
+        } else if obj.downcast::<PySequence>().is_ok() {
+            visitor.visit_seq(SeqAccess::new(items(obj)?))
+        } else {
+            Err(self.unsupported("a builtin scalar, sequence or mapping"))
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.obj.is_none() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if let Ok(s) = self.obj.downcast::<PyString>() {
+            return visitor.visit_enum(s.to_cow()?.into_owned().into_deserializer());
+        }
+        if let Ok(dict) = self.obj.downcast::<PyDict>() {
+            if dict.len() == 1 {
+                let (variant, value) = dict.iter().next().unwrap();
+                return visitor.visit_enum(EnumAccess { variant, value });
+            }
+        }
+        Err(self.unsupported("a variant name or a dict with a single item"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+struct SeqAccess<'py> {
+    items: std::vec::IntoIter<Bound<'py, PyAny>>,
+}
+
+impl<'py> SeqAccess<'py> {
+    fn new(items: Vec<Bound<'py, PyAny>>) -> Self {
+        SeqAccess {
+            items: items.into_iter(),
+        }
+    }
+}
+
+impl<'de, 'py> de::SeqAccess<'de> for SeqAccess<'py> {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.items
+            .next()
+            .map(|item| seed.deserialize(Depythonizer::new(item)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+struct MapAccess<'py> {
+    items: std::vec::IntoIter<(Bound<'py, PyAny>, Bound<'py, PyAny>)>,
+    value: Option<Bound<'py, PyAny>>,
+}
+
+impl<'py> MapAccess<'py> {
+    fn new(items: Vec<(Bound<'py, PyAny>, Bound<'py, PyAny>)>) -> Self {
+        MapAccess {
+            items: items.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de, 'py> de::MapAccess<'de> for MapAccess<'py> {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.items.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(Depythonizer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed should be called after next_key_seed");
+        seed.deserialize(Depythonizer::new(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// Access to an enum variant in the single item dict representation `{variant: value}`.
+struct EnumAccess<'py> {
+    variant: Bound<'py, PyAny>,
+    value: Bound<'py, PyAny>,
+}
+
+impl<'de, 'py> de::EnumAccess<'de> for EnumAccess<'py> {
+    type Error = Error;
+    type Variant = VariantAccess<'py>;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Error> {
+        let variant = seed.deserialize(Depythonizer::new(self.variant))?;
+        Ok((variant, VariantAccess { value: self.value }))
+    }
+}
+
+struct VariantAccess<'py> {
+    value: Bound<'py, PyAny>,
+}
+
+impl<'de, 'py> de::VariantAccess<'de> for VariantAccess<'py> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        if self.value.is_none() {
+            Ok(())
+        } else {
+            Err(Error(PyValueError::new_err(
+                "expected None as the value of a unit variant",
+            )))
+        }
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(Depythonizer::new(self.value))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(Depythonizer::new(self.value), visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(Depythonizer::new(self.value), visitor)
+    }
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "serde")]
+
+//! Enables (de)serialization of [`Py`]`<T>` objects via [serde](https://docs.rs/serde), and
+//! conversions between Python objects and any type implementing [`Serialize`] or [`Deserialize`].
+//!
+//! # Setup
+//!
+//! To use this feature, add this to your **`Cargo.toml`**:
+//!
+//! ```toml
+//! [dependencies]
+#![doc = concat!("pyo3 = { version = \"", env!("CARGO_PKG_VERSION"),  "\", features = [\"serde\"] }")]
+//! serde = "1.0"
+//! ```
+//!
+//! # Converting to and from Python objects
+//!
+//! [`pythonize`] converts a value into builtin Python objects, and [`depythonize`] converts
+//! builtin Python objects back into a value, without going through an intermediate format such
+//! as a JSON string. The serde data model is mapped as follows:
+//!
+//! | serde | Python |
+//! | ----- | ------ |
+//! | `bool`, integers, floats | `bool`, `int`, `float` |
+//! | `char`, string | `str` |
+//! | bytes | `bytes` (`bytearray` is also accepted) |
+//! | `None`, unit, unit struct | `None` |
+//! | sequence | `list` (`tuple`, `set` and other sequences are also accepted) |
+//! | tuple, tuple struct | `tuple` (any sequence is accepted) |
+//! | map, struct | `dict` (other mappings are also accepted) |
+//! | enum variant | `"Variant"` for unit variants, `{"Variant": value}` otherwise |
+//!
+//! ```rust
+//! use pyo3::prelude::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//!     label: Option<String>,
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let point = Point { x: 1, y: 2, label: None };
+//!     let obj = pyo3::serde::pythonize(py, &point).unwrap();
+//!     assert_eq!(obj.to_string(), "{'x': 1, 'y': 2, 'label': None}");
+//!
+//!     let obj = py.eval_bound("{'x': 3, 'y': 4, 'label': 'origin'}", None, None).unwrap();
+//!     let point: Point = pyo3::serde::depythonize(&obj).unwrap();
+//!     assert_eq!(point, Point { x: 3, y: 4, label: Some("origin".to_owned()) });
+//! });
+//! ```
+
+use crate::exceptions::PyValueError;
+use crate::{Bound, Py, PyAny, PyClass, PyErr, PyResult, Python};
+use serde::de::DeserializeOwned;
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+mod depythonizer;
+mod pythonizer;
+
+impl<T> Serialize for Py<T>
+where
+    T: Serialize + PyClass,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
+    where
+        S: Serializer,
+    {
+        Python::with_gil(|py| {
+            self.try_borrow(py)
+                .map_err(|e| ser::Error::custom(e.to_string()))?
+                .serialize(serializer)
+        })
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Py<T>
+where
+    T: PyClass<BaseType = PyAny> + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Py<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let deserialized = T::deserialize(deserializer)?;
+
+        Python::with_gil(|py| {
+            Py::new(py, deserialized).map_err(|e| de::Error::custom(e.to_string()))
+        })
+    }
+}
+
+/// Converts `value` into builtin Python objects.
+///
+/// See the [module-level documentation](self) for how values are represented in Python. Fails if
+/// the [`Serialize`] implementation of `value` fails, which is raised as a `ValueError` unless it
+/// originates from Python.
+pub fn pythonize<'py, T>(py: Python<'py>, value: &T) -> PyResult<Bound<'py, PyAny>>
+where
+    T: ?Sized + Serialize,
+{
+    value
+        .serialize(pythonizer::Pythonizer::new(py))
+        .map_err(|Error(err)| err)
+}
+
+/// Converts the builtin Python objects in `obj` into a value of type `T`.
+///
+/// See the [module-level documentation](self) for which Python objects are accepted. Fails with
+/// `TypeError` for objects which have no serde representation, and with `ValueError` when `obj`
+/// does not match what `T` expects.
+pub fn depythonize<T>(obj: &Bound<'_, PyAny>) -> PyResult<T>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(depythonizer::Depythonizer::new(obj.clone())).map_err(|Error(err)| err)
+}
+
+/// Error type of the serializer and the deserializer, wrapping the `PyErr` they fail with.
+#[derive(Debug)]
+struct Error(PyErr);
+
+impl From<PyErr> for Error {
+    fn from(err: PyErr) -> Self {
+        Error(err)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error(PyValueError::new_err(msg.to_string()))
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error(PyValueError::new_err(msg.to_string()))
+    }
+}
@@ -0,0 +1,379 @@
+use super::Error;
+use crate::types::{PyBytes, PyDict, PyDictMethods, PyList, PyString, PyTuple};
+use crate::{Bound, IntoPy, PyAny, Python};
+use serde::{ser, Serialize};
+
+/// A serde `Serializer` producing Python objects.
+pub(super) struct Pythonizer<'py> {
+    py: Python<'py>,
+}
+
+impl<'py> Pythonizer<'py> {
+    pub(super) fn new(py: Python<'py>) -> Self {
+        Pythonizer { py }
+    }
+
+    fn object<T: IntoPy<crate::PyObject>>(&self, value: T) -> Bound<'py, PyAny> {
+        value.into_py(self.py).into_bound(self.py)
+    }
+
+    /// Wraps `value` into a single item dict keyed by the variant name, the externally tagged
+    /// representation of enums in serde.
+    fn variant(
+        &self,
+        variant: &'static str,
+        value: Bound<'py, PyAny>,
+    ) -> Result<Bound<'py, PyAny>, Error> {
+        let dict = PyDict::new_bound(self.py);
+        dict.set_item(variant, value)?;
+        Ok(dict.into_any())
+    }
+}
+
+/// Serializer of sequences, tuples and tuple variants.
+pub(super) struct SeqSerializer<'py> {
+    py: Python<'py>,
+    items: Vec<Bound<'py, PyAny>>,
+    tuple: bool,
+    variant: Option<&'static str>,
+}
+
+impl<'py> SeqSerializer<'py> {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(value.serialize(Pythonizer::new(self.py))?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Bound<'py, PyAny>, Error> {
+        let pythonizer = Pythonizer::new(self.py);
+        let seq = if self.tuple {
+            PyTuple::new_bound(self.py, self.items).into_any()
+        } else {
+            PyList::new_bound(self.py, self.items).into_any()
+        };
+        match self.variant {
+            Some(variant) => pythonizer.variant(variant, seq),
+            None => Ok(seq),
+        }
+    }
+}
+
+/// Serializer of maps, structs and struct variants.
+pub(super) struct DictSerializer<'py> {
+    dict: Bound<'py, PyDict>,
+    key: Option<Bound<'py, PyAny>>,
+    variant: Option<&'static str>,
+}
+
+impl<'py> DictSerializer<'py> {
+    fn new(py: Python<'py>, variant: Option<&'static str>) -> Self {
+        DictSerializer {
+            dict: PyDict::new_bound(py),
+            key: None,
+            variant,
+        }
+    }
+
+    fn finish(self) -> Result<Bound<'py, PyAny>, Error> {
+        let pythonizer = Pythonizer::new(self.dict.py());
+        match self.variant {
+            Some(variant) => pythonizer.variant(variant, self.dict.into_any()),
+            None => Ok(self.dict.into_any()),
+        }
+    }
+}
+
+impl<'py> ser::Serializer for Pythonizer<'py> {
+    type Ok = Bound<'py, PyAny>;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer<'py>;
+    type SerializeTuple = SeqSerializer<'py>;
+    type SerializeTupleStruct = SeqSerializer<'py>;
+    type SerializeTupleVariant = SeqSerializer<'py>;
+    type SerializeMap = DictSerializer<'py>;
+    type SerializeStruct = DictSerializer<'py>;
+    type SerializeStructVariant = DictSerializer<'py>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Error> {
+        Ok(self.object(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Error> {
+        Ok(PyString::new_bound(self.py, v).into_any())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Error> {
+        Ok(PyBytes::new_bound(self.py, v).into_any())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Error> {
+        Ok(self.py.None().into_bound(self.py))
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Error> {
+        self.serialize_none()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Error> {
+        let value = value.serialize(Pythonizer::new(self.py))?;
+        self.variant(variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(SeqSerializer {
+            py: self.py,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+            tuple: false,
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        Ok(SeqSerializer {
+            py: self.py,
+            items: Vec::with_capacity(len),
+            tuple: true,
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Ok(SeqSerializer {
+            py: self.py,
+            items: Vec::with_capacity(len),
+            tuple: true,
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(DictSerializer::new(self.py, None))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Ok(DictSerializer::new(self.py, None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Ok(DictSerializer::new(self.py, Some(variant)))
+    }
+}
+
+impl<'py> ser::SerializeSeq for SeqSerializer<'py> {
+    type Ok = Bound<'py, PyAny>;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'py> ser::SerializeTuple for SeqSerializer<'py> {
+    type Ok = Bound<'py, PyAny>;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'py> ser::SerializeTupleStruct for SeqSerializer<'py> {
+    type Ok = Bound<'py, PyAny>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'py> ser::SerializeTupleVariant for SeqSerializer<'py> {
+    type Ok = Bound<'py, PyAny>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'py> ser::SerializeMap for DictSerializer<'py> {
+    type Ok = Bound<'py, PyAny>;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(Pythonizer::new(self.dict.py()))?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value should be called after serialize_key");
+        let value = value.serialize(Pythonizer::new(self.dict.py()))?;
+        self.dict.set_item(key, value)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'py> ser::SerializeStruct for DictSerializer<'py> {
+    type Ok = Bound<'py, PyAny>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let value = value.serialize(Pythonizer::new(self.dict.py()))?;
+        self.dict.set_item(key, value)?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
+
+impl<'py> ser::SerializeStructVariant for DictSerializer<'py> {
+    type Ok = Bound<'py, PyAny>;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Error> {
+        self.finish()
+    }
+}
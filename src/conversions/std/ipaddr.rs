@@ -28,6 +28,24 @@ impl FromPyObject<'_> for IpAddr {
     }
 }
 
+impl FromPyObject<'_> for Ipv4Addr {
+    fn extract_bound(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        match obj.extract()? {
+            IpAddr::V4(ip) => Ok(ip),
+            IpAddr::V6(_) => Err(PyValueError::new_err("expected an IPv4 address")),
+        }
+    }
+}
+
+impl FromPyObject<'_> for Ipv6Addr {
+    fn extract_bound(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        match obj.extract()? {
+            IpAddr::V4(_) => Err(PyValueError::new_err("expected an IPv6 address")),
+            IpAddr::V6(ip) => Ok(ip),
+        }
+    }
+}
+
 impl ToPyObject for Ipv4Addr {
     fn to_object(&self, py: Python<'_>) -> PyObject {
         static IPV4_ADDRESS: GILOnceCell<Py<PyType>> = GILOnceCell::new();
@@ -52,6 +70,18 @@ impl ToPyObject for Ipv6Addr {
     }
 }
 
+impl IntoPy<PyObject> for Ipv4Addr {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl IntoPy<PyObject> for Ipv6Addr {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
 impl ToPyObject for IpAddr {
     fn to_object(&self, py: Python<'_>) -> PyObject {
         match self {
@@ -100,6 +130,21 @@ mod test_ipaddr {
         });
     }
 
+    #[test]
+    fn test_specific_versions() {
+        Python::with_gil(|py| {
+            let v4 = Ipv4Addr::new(192, 168, 0, 1);
+            let py_v4 = v4.into_py(py);
+            assert_eq!(py_v4.extract::<Ipv4Addr>(py).unwrap(), v4);
+            assert!(py_v4.extract::<Ipv6Addr>(py).is_err());
+
+            let v6 = Ipv6Addr::LOCALHOST;
+            let py_v6 = v6.into_py(py);
+            assert_eq!(py_v6.extract::<Ipv6Addr>(py).unwrap(), v6);
+            assert!(py_v6.extract::<Ipv4Addr>(py).is_err());
+        });
+    }
+
     #[test]
     fn test_from_pystring() {
         Python::with_gil(|py| {
@@ -8,6 +8,7 @@ mod osstr;
 mod path;
 mod set;
 mod slice;
+mod socketaddr;
 mod string;
 mod time;
 mod vec;
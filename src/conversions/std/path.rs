@@ -1,6 +1,8 @@
 use crate::ffi_ptr_ext::FfiPtrExt;
 use crate::instance::Bound;
 use crate::types::any::PyAnyMethods;
+use crate::types::bytes::PyBytesMethods;
+use crate::types::PyBytes;
 use crate::{ffi, FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
 use std::borrow::Cow;
 use std::ffi::OsString;
@@ -18,6 +20,18 @@ impl FromPyObject<'_> for PathBuf {
     fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
         // We use os.fspath to get the underlying path as bytes or str
         let path = unsafe { ffi::PyOS_FSPath(ob.as_ptr()).assume_owned_or_err(ob.py())? };
+        if let Ok(bytes) = path.downcast::<PyBytes>() {
+            // Decode like os.fsdecode, which is lossless on Unix thanks to surrogateescape
+            let bytes = bytes.as_bytes();
+            let decoded = unsafe {
+                ffi::PyUnicode_DecodeFSDefaultAndSize(
+                    bytes.as_ptr().cast(),
+                    bytes.len() as ffi::Py_ssize_t,
+                )
+                .assume_owned_or_err(ob.py())?
+            };
+            return Ok(decoded.extract::<OsString>()?.into());
+        }
         Ok(path.extract::<OsString>()?.into())
     }
 }
@@ -64,7 +78,7 @@ impl<'a> IntoPy<PyObject> for &'a PathBuf {
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{PyAnyMethods, PyStringMethods};
+    use crate::types::{PyAnyMethods, PyBytes, PyStringMethods};
     use crate::{types::PyString, IntoPy, PyObject, Python, ToPyObject};
     use std::borrow::Cow;
     use std::fmt::Debug;
@@ -91,6 +105,41 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_extract_fspath() {
+        Python::with_gil(|py| {
+            let pathlib_path = py
+                .import_bound("pathlib")
+                .unwrap()
+                .call_method1("PurePath", ("some/dir",))
+                .unwrap();
+            let path: PathBuf = pathlib_path.extract().unwrap();
+            assert_eq!(path, Path::new("some").join("dir"));
+
+            let bytes_path = PyBytes::new_bound(py, b"some/dir");
+            let path: PathBuf = bytes_path.extract().unwrap();
+            assert_eq!(path, Path::new("some/dir"));
+
+            assert!(py.None().extract::<PathBuf>(py).is_err());
+        });
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_extract_non_utf8_bytes() {
+        Python::with_gil(|py| {
+            use std::ffi::OsStr;
+            #[cfg(not(target_os = "wasi"))]
+            use std::os::unix::ffi::OsStrExt;
+            #[cfg(target_os = "wasi")]
+            use std::os::wasi::ffi::OsStrExt;
+
+            let payload = &[250, 251, 252, 253, 254, 255, 0, 255];
+            let path: PathBuf = PyBytes::new_bound(py, payload).extract().unwrap();
+            assert_eq!(path, Path::new(OsStr::from_bytes(payload)));
+        });
+    }
+
     #[test]
     fn test_topyobject_roundtrip() {
         Python::with_gil(|py| {
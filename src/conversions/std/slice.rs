@@ -113,12 +113,14 @@ mod tests {
             let bytes = py.eval_bound(r#"b"foobar""#, None, None).unwrap();
             let cow = bytes.extract::<Cow<'_, [u8]>>().unwrap();
             assert_eq!(cow, Cow::<[u8]>::Borrowed(b"foobar"));
+            assert!(matches!(cow, Cow::Borrowed(_)));
 
             let byte_array = py
                 .eval_bound(r#"bytearray(b"foobar")"#, None, None)
                 .unwrap();
             let cow = byte_array.extract::<Cow<'_, [u8]>>().unwrap();
             assert_eq!(cow, Cow::<[u8]>::Owned(b"foobar".to_vec()));
+            assert!(matches!(cow, Cow::Owned(_)));
 
             let something_else_entirely = py.eval_bound("42", None, None).unwrap();
             something_else_entirely
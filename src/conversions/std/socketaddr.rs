@@ -0,0 +1,144 @@
+//! Socket addresses are converted to the tuples used by Python's `socket` module: `(host, port)`
+//! for IPv4 and `(host, port, flowinfo, scope_id)` for IPv6, with the host as a string.
+use std::net::{IpAddr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::exceptions::PyValueError;
+use crate::instance::Bound;
+use crate::types::any::PyAnyMethods;
+use crate::types::tuple::PyTupleMethods;
+use crate::types::PyTuple;
+use crate::{FromPyObject, IntoPy, PyAny, PyObject, PyResult, Python, ToPyObject};
+
+impl FromPyObject<'_> for SocketAddr {
+    fn extract_bound(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let tuple = obj.downcast::<PyTuple>()?;
+        match tuple.len() {
+            2 => {
+                let (ip, port): (IpAddr, u16) = tuple.extract()?;
+                Ok(SocketAddr::new(ip, port))
+            }
+            4 => tuple.extract().map(SocketAddr::V6),
+            len => Err(PyValueError::new_err(format!(
+                "expected a socket address tuple of length 2 or 4, got length {}",
+                len
+            ))),
+        }
+    }
+}
+
+impl FromPyObject<'_> for SocketAddrV4 {
+    fn extract_bound(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let (ip, port) = obj.extract()?;
+        Ok(SocketAddrV4::new(ip, port))
+    }
+}
+
+impl FromPyObject<'_> for SocketAddrV6 {
+    fn extract_bound(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let tuple = obj.downcast::<PyTuple>()?;
+        let (ip, port, flowinfo, scope_id) = if tuple.len() == 2 {
+            let (ip, port) = tuple.extract()?;
+            (ip, port, 0, 0)
+        } else {
+            tuple.extract()?
+        };
+        Ok(SocketAddrV6::new(ip, port, flowinfo, scope_id))
+    }
+}
+
+impl ToPyObject for SocketAddrV4 {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        (self.ip().to_string(), self.port()).to_object(py)
+    }
+}
+
+impl ToPyObject for SocketAddrV6 {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        (
+            self.ip().to_string(),
+            self.port(),
+            self.flowinfo(),
+            self.scope_id(),
+        )
+            .to_object(py)
+    }
+}
+
+impl ToPyObject for SocketAddr {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        match self {
+            SocketAddr::V4(addr) => addr.to_object(py),
+            SocketAddr::V6(addr) => addr.to_object(py),
+        }
+    }
+}
+
+impl IntoPy<PyObject> for SocketAddrV4 {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl IntoPy<PyObject> for SocketAddrV6 {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl IntoPy<PyObject> for SocketAddr {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PyDict;
+    use crate::types::PyDictMethods;
+
+    #[test]
+    fn test_roundtrip() {
+        Python::with_gil(|py| {
+            for addr in ["127.0.0.1:8080", "[::1]:443", "[fe80::1%3]:22"] {
+                let addr: SocketAddr = addr.parse().unwrap();
+                let py_addr = addr.into_py(py);
+                assert_eq!(py_addr.extract::<SocketAddr>(py).unwrap(), addr);
+            }
+        });
+    }
+
+    #[test]
+    fn test_python_tuples() {
+        Python::with_gil(|py| {
+            let locals = PyDict::new_bound(py);
+            locals
+                .set_item("v4", "10.0.0.1:80".parse::<SocketAddr>().unwrap())
+                .unwrap();
+            locals
+                .set_item("v6", "[::1]:80".parse::<SocketAddrV6>().unwrap())
+                .unwrap();
+            py.run_bound(
+                "assert v4 == ('10.0.0.1', 80)\nassert v6 == ('::1', 80, 0, 0)",
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+
+            let import = |code| py.eval_bound(code, None, None).unwrap();
+            assert_eq!(
+                import("(__import__('ipaddress').ip_address('10.0.0.1'), 80)")
+                    .extract::<SocketAddrV4>()
+                    .unwrap(),
+                "10.0.0.1:80".parse().unwrap()
+            );
+            assert_eq!(
+                import("('::1', 80)").extract::<SocketAddrV6>().unwrap(),
+                "[::1]:80".parse().unwrap()
+            );
+            assert!(import("('::1', 80)").extract::<SocketAddrV4>().is_err());
+            assert!(import("('localhost', 80)").extract::<SocketAddr>().is_err());
+            assert!(import("('::1', 80, 0)").extract::<SocketAddr>().is_err());
+        });
+    }
+}
@@ -138,10 +138,14 @@ impl<'a> crate::conversion::FromPyObjectBound<'a, '_> for &'a str {
     }
 }
 
+/// Allows extracting strings from Python objects without copying.
+///
+/// The `Cow` borrows the UTF-8 representation cached by the `str` object when it is available
+/// to the Python API in use, and is only owned if the string has to be converted.
 #[cfg(feature = "gil-refs")]
 impl<'py> FromPyObject<'py> for Cow<'py, str> {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
-        ob.extract().map(Cow::Owned)
+        ob.clone().into_gil_ref().downcast::<PyString>()?.to_cow()
     }
 
     #[cfg(feature = "experimental-inspect")]
@@ -150,6 +154,10 @@ impl<'py> FromPyObject<'py> for Cow<'py, str> {
     }
 }
 
+/// Allows extracting strings from Python objects without copying.
+///
+/// The `Cow` borrows the UTF-8 representation cached by the `str` object when it is available
+/// to the Python API in use, and is only owned if the string has to be converted.
 #[cfg(not(feature = "gil-refs"))]
 impl<'a> crate::conversion::FromPyObjectBound<'a, '_> for Cow<'a, str> {
     fn from_py_object_bound(ob: crate::Borrowed<'a, '_, PyAny>) -> PyResult<Self> {
@@ -197,6 +205,7 @@ impl FromPyObject<'_> for char {
 #[cfg(test)]
 mod tests {
     use crate::types::any::PyAnyMethods;
+    use crate::types::PyString;
     use crate::Python;
     use crate::{IntoPy, PyObject, ToPyObject};
     use std::borrow::Cow;
@@ -243,6 +252,20 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_extract_cow_str() {
+        Python::with_gil(|py| {
+            for s in ["Hello Python", "Grüß Gott", "☃"] {
+                let py_string = PyString::new_bound(py, s);
+                let cow: Cow<'_, str> = py_string.extract().unwrap();
+                assert_eq!(cow, s);
+                // the UTF-8 representation of the object is borrowed when available
+                #[cfg(any(Py_3_10, not(Py_LIMITED_API)))]
+                assert!(matches!(cow, Cow::Borrowed(_)));
+            }
+        })
+    }
+
     #[test]
     fn test_extract_char() {
         Python::with_gil(|py| {
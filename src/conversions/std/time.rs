@@ -1,4 +1,4 @@
-use crate::exceptions::{PyOverflowError, PyValueError};
+use crate::exceptions::{PyOverflowError, PyTypeError, PyValueError};
 use crate::sync::GILOnceCell;
 use crate::types::any::PyAnyMethods;
 #[cfg(Py_LIMITED_API)]
@@ -97,6 +97,12 @@ impl IntoPy<PyObject> for Duration {
 
 impl FromPyObject<'_> for SystemTime {
     fn extract_bound(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        // A naive datetime has no defined point in time, so don't guess a timezone for it.
+        if obj.getattr(intern!(obj.py(), "tzinfo"))?.is_none() {
+            return Err(PyTypeError::new_err(
+                "expected a timezone aware datetime to convert to SystemTime",
+            ));
+        }
         let duration_since_unix_epoch: Duration = obj
             .call_method1(intern!(obj.py(), "__sub__"), (unix_epoch_py(obj.py()),))?
             .extract()?;
@@ -297,6 +303,17 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_time_frompyobject_naive() {
+        Python::with_gil(|py| {
+            let naive = datetime_class(py).call1((2020, 2, 3)).unwrap();
+            assert_eq!(
+                naive.extract::<SystemTime>().unwrap_err().to_string(),
+                "TypeError: expected a timezone aware datetime to convert to SystemTime"
+            );
+        })
+    }
+
     #[test]
     fn test_time_topyobject() {
         Python::with_gil(|py| {
@@ -0,0 +1,150 @@
+#![cfg(feature = "uuid")]
+//! Conversions to and from [uuid](https://docs.rs/uuid)'s [`Uuid`] type.
+//!
+//! This is useful for converting Python's uuid.UUID into and from a native Rust type.
+//!
+//! # Setup
+//!
+//! To use this feature, add to your **`Cargo.toml`**:
+//!
+//! ```toml
+//! [dependencies]
+#![doc = concat!("pyo3 = { version = \"", env!("CARGO_PKG_VERSION"),  "\", features = [\"uuid\"] }")]
+//! uuid = "1.0"
+//! ```
+//!
+//! Note that you must use a compatible version of uuid and PyO3.
+//! The required uuid version may vary based on the version of PyO3.
+//!
+//! # Example
+//!
+//! Rust code to create a function that returns the version of a UUID
+//!
+//! ```rust
+//! use pyo3::prelude::*;
+//! use uuid::Uuid;
+//!
+//! #[pyfunction]
+//! fn uuid_version(uuid: Uuid) -> usize {
+//!     uuid.get_version_num()
+//! }
+//!
+//! #[pymodule]
+//! fn my_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     m.add_function(wrap_pyfunction!(uuid_version, m)?)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Python code that validates the functionality
+//!
+//! ```python
+//! from my_module import uuid_version
+//! import uuid
+//!
+//! assert uuid_version(uuid.uuid4()) == 4
+//! ```
+//!
+//! The conversion goes through the 16 bytes of the UUID in big-endian order, so it is lossless in
+//! both directions.
+
+use crate::exceptions::PyTypeError;
+use crate::sync::GILOnceCell;
+use crate::types::any::PyAnyMethods;
+use crate::types::dict::PyDictMethods;
+use crate::types::typeobject::PyTypeMethods;
+use crate::types::{PyBytes, PyDict, PyType};
+use crate::{
+    intern, Bound, FromPyObject, IntoPy, Py, PyAny, PyObject, PyResult, Python, ToPyObject,
+};
+use uuid::Uuid;
+
+static UUID_CLS: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+
+fn get_uuid_cls(py: Python<'_>) -> PyResult<&Bound<'_, PyType>> {
+    UUID_CLS.get_or_try_init_type_ref(py, "uuid", "UUID")
+}
+
+impl FromPyObject<'_> for Uuid {
+    fn extract_bound(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let py = obj.py();
+        if !obj.is_instance(get_uuid_cls(py)?)? {
+            return Err(PyTypeError::new_err(format!(
+                "expected uuid.UUID, got '{}'",
+                obj.get_type().qualname()?
+            )));
+        }
+        let bytes: [u8; 16] = obj.getattr(intern!(py, "bytes"))?.extract()?;
+        Ok(Uuid::from_bytes(bytes))
+    }
+}
+
+impl ToPyObject for Uuid {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        // TODO: handle error gracefully when ToPyObject can error
+        let kwargs = PyDict::new_bound(py);
+        kwargs
+            .set_item(
+                intern!(py, "bytes"),
+                PyBytes::new_bound(py, self.as_bytes()),
+            )
+            .expect("failed to set uuid.UUID argument");
+        get_uuid_cls(py)
+            .expect("failed to load uuid.UUID")
+            .call((), Some(&kwargs))
+            .expect("failed to call uuid.UUID(bytes=value)")
+            .unbind()
+    }
+}
+
+impl IntoPy<PyObject> for Uuid {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::string::PyStringMethods;
+    use crate::types::PyString;
+
+    #[test]
+    fn test_roundtrip() {
+        Python::with_gil(|py| {
+            let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+            let py_uuid = uuid.into_py(py);
+            let py_uuid = py_uuid.bind(py);
+            assert_eq!(
+                py_uuid.str().unwrap().to_cow().unwrap(),
+                "67e55044-10b1-426f-9247-bb680e5fe0c8"
+            );
+            assert_eq!(py_uuid.extract::<Uuid>().unwrap(), uuid);
+        });
+    }
+
+    #[test]
+    fn test_from_python() {
+        Python::with_gil(|py| {
+            let py_uuid = py
+                .eval_bound(
+                    "__import__('uuid').uuid5(__import__('uuid').NAMESPACE_DNS, 'python.org')",
+                    None,
+                    None,
+                )
+                .unwrap();
+            let uuid: Uuid = py_uuid.extract().unwrap();
+            assert_eq!(uuid.get_version_num(), 5);
+            assert_eq!(uuid.to_string(), "886313e1-3b8a-5372-9b90-0c9aee199e5d");
+        });
+    }
+
+    #[test]
+    fn test_not_a_uuid() {
+        Python::with_gil(|py| {
+            let s = PyString::new_bound(py, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+            let err = s.extract::<Uuid>().unwrap_err();
+            assert_eq!(err.to_string(), "TypeError: expected uuid.UUID, got 'str'");
+        });
+    }
+}
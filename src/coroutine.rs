@@ -13,14 +13,16 @@ use pyo3_macros::{pyclass, pymethods};
 use crate::{
     coroutine::{cancel::ThrowCallback, waker::AsyncioWaker},
     exceptions::{PyAttributeError, PyRuntimeError, PyStopIteration},
-    panic::PanicException,
+    panic::panic_to_pyerr,
     types::{string::PyStringMethods, PyIterator, PyString},
     Bound, IntoPy, Py, PyAny, PyErr, PyObject, PyResult, Python,
 };
 
+mod allow_threads;
 pub(crate) mod cancel;
 mod waker;
 
+pub use allow_threads::{allow_threads, AllowThreads};
 pub use cancel::CancelHandle;
 
 const COROUTINE_REUSED_ERROR: &str = "cannot reuse already awaited coroutine";
@@ -99,7 +101,7 @@ impl Coroutine {
             }
             Err(err) => {
                 self.close();
-                return Err(PanicException::from_panic_payload(err));
+                return Err(panic_to_pyerr(err));
             }
             _ => {}
         }
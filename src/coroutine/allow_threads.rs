@@ -0,0 +1,52 @@
+use crate::{gil::SuspendGIL, marker::Ungil, Python};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Wrap a future so that the GIL is released while it is polled.
+///
+/// This is the `async` counterpart of [`Python::allow_threads`]: the wrapped future runs
+/// without the GIL, so other Python threads can make progress while it does CPU-bound or
+/// blocking work between `.await` points. As with `allow_threads`, the future must not hold
+/// Python references.
+///
+/// ```rust
+/// # #![allow(dead_code)]
+/// use pyo3::coroutine::allow_threads;
+/// use pyo3::prelude::*;
+///
+/// #[pyfunction]
+/// async fn sum_of_squares(n: u64) -> u64 {
+///     allow_threads(async move { (0..n).map(|i| i * i).sum() }).await
+/// }
+/// ```
+pub fn allow_threads<F>(future: F) -> AllowThreads<F>
+where
+    F: Future + Ungil,
+    F::Output: Ungil,
+{
+    AllowThreads(future)
+}
+
+/// Future returned by [`allow_threads`].
+#[derive(Debug)]
+pub struct AllowThreads<F>(F);
+
+impl<F> Future for AllowThreads<F>
+where
+    F: Future + Ungil,
+    F::Output: Ungil,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: the wrapped future is structurally pinned, it is never moved out of `self`
+        let future = unsafe { self.map_unchecked_mut(|this| &mut this.0) };
+        Python::with_gil(|_| {
+            // Same as `Python::allow_threads`, whose `Ungil` bounds are required on `F` and
+            // its output instead of on a closure.
+            let _guard = unsafe { SuspendGIL::new() };
+            future.poll(cx)
+        })
+    }
+}
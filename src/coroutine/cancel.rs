@@ -1,4 +1,4 @@
-use crate::{Py, PyAny, PyObject};
+use crate::{Py, PyAny, PyErr, PyObject, Python};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
@@ -47,6 +47,15 @@ impl CancelHandle {
         Cancelled(self).await
     }
 
+    /// Retrieve the exception thrown in the associated coroutine as a [`PyErr`].
+    ///
+    /// Returning it from the `async fn`, after running any cleanup, propagates the cancellation
+    /// to Python, whereas returning normally suppresses it.
+    pub async fn cancelled_error(&mut self) -> PyErr {
+        let exc = self.cancelled().await;
+        Python::with_gil(|py| PyErr::from_value_bound(exc.into_bound(py)))
+    }
+
     #[doc(hidden)]
     pub fn throw_callback(&self) -> ThrowCallback {
         ThrowCallback(self.0.clone())
@@ -0,0 +1,197 @@
+//! Helpers for detecting reference leaks in tests.
+//!
+//! A missing decrement of a reference count does not crash anything: the object is simply never
+//! freed, and the leak only shows up as memory growth in long-running processes. The helpers in
+//! this module make such leaks visible in tests instead:
+//!
+//! - [`assert_no_leaks`] runs a closure repeatedly and panics if objects accumulate.
+//! - [`assert_refcount_unchanged`] checks the reference count of a single object across a scope.
+//! - [`PoolStats`] exposes the underlying measurements for custom checks.
+//!
+//! These helpers are intended for tests and are only available with the `pool-diagnostics`
+//! feature, usually enabled for dev-dependencies only.
+//!
+//! # Example
+//!
+//! ```rust
+//! use pyo3::diagnostics::assert_no_leaks;
+//! use pyo3::prelude::*;
+//! use pyo3::types::PyList;
+//!
+//! Python::with_gil(|py| {
+//!     assert_no_leaks(py, |py| {
+//!         let list = PyList::new_bound(py, [1, 2, 3]);
+//!         list.append(PyList::empty_bound(py)).unwrap();
+//!     });
+//! });
+//! ```
+//!
+//! The measurements cover the whole interpreter, so objects created by other threads while the
+//! checked code runs are counted as well. Run leak checks in a test binary of their own if other
+//! tests use Python concurrently.
+use crate::types::any::PyAnyMethods;
+use crate::{gil, intern, Bound, PyAny, PyResult, Python};
+use std::fmt;
+
+/// The number of times [`assert_no_leaks`] runs the checked closure after warming it up.
+const LEAK_CHECK_ITERATIONS: usize = 10;
+
+/// A snapshot of the objects alive in the interpreter, see [`PoolStats::current`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PoolStats {
+    /// The number of objects owned by the `GILPool`s of the current thread.
+    ///
+    /// Always zero without the `gil-refs` feature.
+    pub owned_objects: usize,
+    /// The number of objects tracked by Python's garbage collector, as `len(gc.get_objects())`.
+    ///
+    /// Only objects which can contain references to other objects, such as lists and instances
+    /// of most classes, are tracked.
+    pub tracked_objects: usize,
+    /// The total of the reference counts of all objects, as `sys.gettotalrefcount()`.
+    ///
+    /// Only available on debug builds of Python.
+    pub total_refcount: Option<isize>,
+}
+
+impl PoolStats {
+    /// Measures the objects currently alive.
+    ///
+    /// To make the measurement deterministic, this applies the reference count changes which
+    /// PyO3 deferred while the GIL was not held and runs Python's garbage collector first.
+    pub fn current(py: Python<'_>) -> PyResult<Self> {
+        gil::update_deferred_reference_counts(py);
+
+        let gc = py.import_bound(intern!(py, "gc"))?;
+        gc.call_method0(intern!(py, "collect"))?;
+        let tracked_objects = gc.call_method0(intern!(py, "get_objects"))?.len()?;
+
+        let sys = py.import_bound(intern!(py, "sys"))?;
+        let total_refcount = match sys.getattr(intern!(py, "gettotalrefcount")) {
+            Ok(gettotalrefcount) => Some(gettotalrefcount.call0()?.extract()?),
+            Err(_) => None,
+        };
+
+        Ok(PoolStats {
+            #[cfg(feature = "gil-refs")]
+            owned_objects: gil::owned_object_count(),
+            #[cfg(not(feature = "gil-refs"))]
+            owned_objects: 0,
+            tracked_objects,
+            total_refcount,
+        })
+    }
+
+    /// Describes how the objects grew between the `earlier` snapshot and this one, provided the
+    /// growth suggests a leak in code which ran `iterations` times in between.
+    ///
+    /// Objects owned by a `GILPool` are never expected to accumulate, whereas fewer than one
+    /// tracked object or reference per iteration is attributed to caches warming up.
+    fn leaks_since(&self, earlier: &PoolStats, iterations: usize) -> Option<LeakReport> {
+        let owned_objects = self.owned_objects.saturating_sub(earlier.owned_objects);
+        let tracked_objects = self.tracked_objects.saturating_sub(earlier.tracked_objects);
+        let total_refcount = match (earlier.total_refcount, self.total_refcount) {
+            (Some(earlier), Some(current)) => current - earlier,
+            _ => 0,
+        };
+        let iterations = iterations.max(1);
+        if owned_objects > 0
+            || tracked_objects >= iterations
+            || total_refcount >= iterations as isize
+        {
+            Some(LeakReport {
+                iterations,
+                owned_objects,
+                tracked_objects,
+                total_refcount,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+struct LeakReport {
+    iterations: usize,
+    owned_objects: usize,
+    tracked_objects: usize,
+    total_refcount: isize,
+}
+
+impl fmt::Display for LeakReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "reference leak detected over {} iterations: ",
+            self.iterations
+        )?;
+        write!(
+            f,
+            "{} objects accumulated in the GIL pool, {} more gc-tracked objects",
+            self.owned_objects, self.tracked_objects
+        )?;
+        if self.total_refcount != 0 {
+            write!(f, ", total reference count grew by {}", self.total_refcount)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs `f` repeatedly and panics if objects accumulate across the runs.
+///
+/// `f` is run once first, so that caches filled on first use, such as interned strings, lazily
+/// created type objects or imported modules, are not reported. It is then run ten more times,
+/// and [`PoolStats`] snapshots taken before and after these runs are compared. A leak is
+/// reported if
+///
+/// - objects remain owned by the `GILPool` (with the `gil-refs` feature), or
+/// - at least one gc-tracked object per run is still alive, or
+/// - on debug builds of Python, the total reference count grew by at least one per run.
+///
+/// Objects which are not tracked by the garbage collector, such as strings and integers, are
+/// only covered on debug builds of Python; use [`assert_refcount_unchanged`] to check them
+/// individually.
+///
+/// # Panics
+///
+/// Panics if a leak is detected, or if `f` panics.
+#[track_caller]
+pub fn assert_no_leaks<F>(py: Python<'_>, mut f: F)
+where
+    F: FnMut(Python<'_>),
+{
+    f(py);
+    let before = PoolStats::current(py).expect("failed to measure objects");
+    for _ in 0..LEAK_CHECK_ITERATIONS {
+        f(py);
+    }
+    let after = PoolStats::current(py).expect("failed to measure objects");
+    if let Some(report) = after.leaks_since(&before, LEAK_CHECK_ITERATIONS) {
+        panic!("{}", report);
+    }
+}
+
+/// Runs `f` and panics if the reference count of `obj` differs afterwards.
+///
+/// Reference count changes which PyO3 deferred while the GIL was not held are applied before
+/// both measurements.
+///
+/// # Panics
+///
+/// Panics if the reference count changed, or if `f` panics.
+#[track_caller]
+pub fn assert_refcount_unchanged<R>(obj: &Bound<'_, PyAny>, f: impl FnOnce() -> R) -> R {
+    let py = obj.py();
+    gil::update_deferred_reference_counts(py);
+    let before = obj.get_refcnt();
+    let result = f();
+    gil::update_deferred_reference_counts(py);
+    let after = obj.get_refcnt();
+    assert_eq!(
+        before, after,
+        "reference count of the object changed from {} to {}",
+        before, after
+    );
+    result
+}
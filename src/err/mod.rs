@@ -525,7 +525,7 @@ impl PyErr {
 
     /// Creates a new exception type with the given name and docstring.
     ///
-    /// - `base` can be an existing exception type to subclass, or a tuple of classes.
+    /// - `base` can be an existing exception type to subclass.
     /// - `dict` specifies an optional dictionary of class variables and methods.
     /// - `doc` will be the docstring seen by python users.
     ///
@@ -543,6 +543,17 @@ impl PyErr {
         doc: Option<&str>,
         base: Option<&Bound<'py, PyType>>,
         dict: Option<PyObject>,
+    ) -> PyResult<Py<PyType>> {
+        Self::new_type_with_bases(py, name, doc, base.map(Bound::as_any), dict)
+    }
+
+    /// Implementation of [`PyErr::new_type_bound`], where `base` may also be a tuple of classes.
+    pub(crate) fn new_type_with_bases<'py>(
+        py: Python<'py>,
+        name: &str,
+        doc: Option<&str>,
+        base: Option<&Bound<'py, PyAny>>,
+        dict: Option<PyObject>,
     ) -> PyResult<Py<PyType>> {
         let base: *mut ffi::PyObject = match base {
             None => std::ptr::null_mut(),
@@ -577,7 +588,9 @@ impl PyErr {
         unsafe { Py::from_owned_ptr_or_err(py, ptr) }
     }
 
-    /// Prints a standard traceback to `sys.stderr`.
+    /// Prints a standard traceback to `sys.stderr`, including any chained causes and contexts.
+    ///
+    /// Use [`PyErr::format`] to get the same output as a string.
     pub fn display(&self, py: Python<'_>) {
         #[cfg(Py_3_12)]
         unsafe {
@@ -687,7 +700,8 @@ impl PyErr {
     ///
     /// Calling this method has the benefit that the error goes back into a standardized callback
     /// in Python which for instance allows unittests to ensure that no unraisable error
-    /// actually happend by hooking `sys.unraisablehook`.
+    /// actually happend by hooking `sys.unraisablehook`. Rust code can handle these errors by
+    /// installing a hook with [`set_unraisablehook`](crate::exceptions::set_unraisablehook).
     ///
     /// Example:
     /// ```rust
@@ -727,7 +741,11 @@ impl PyErr {
     ///
     /// The `category` should be one of the `Warning` classes available in
     /// [`pyo3::exceptions`](crate::exceptions), or a subclass.  The Python
-    /// object can be retrieved using [`Python::get_type_bound()`].
+    /// object can be retrieved using [`Python::get_type_bound()`]. Custom warning
+    /// classes can be defined with [`create_exception!`](crate::create_exception).
+    ///
+    /// Whether the warning is shown, ignored or raised as an error is decided by the
+    /// filters of the `warnings` module, exactly as for warnings issued from Python code.
     ///
     /// Example:
     /// ```rust
@@ -873,6 +891,91 @@ impl PyErr {
         }
     }
 
+    /// Returns this error with its cause set to `cause`.
+    ///
+    /// This is equivalent to the Python statement `raise self from cause`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pyo3::exceptions::{PyKeyError, PyValueError};
+    /// use pyo3::prelude::*;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let err = PyValueError::new_err("invalid configuration")
+    ///         .with_cause(py, PyKeyError::new_err("name"));
+    ///     assert_eq!(err.cause(py).unwrap().to_string(), "KeyError: 'name'");
+    /// });
+    /// ```
+    pub fn with_cause(self, py: Python<'_>, cause: PyErr) -> PyErr {
+        self.set_cause(py, Some(cause));
+        self
+    }
+
+    /// Return the context (the exception which was being handled when this one was raised)
+    /// associated with the exception, as accessible from Python through `__context__`.
+    pub fn context(&self, py: Python<'_>) -> Option<PyErr> {
+        use crate::ffi_ptr_ext::FfiPtrExt;
+        let obj = unsafe {
+            ffi::PyException_GetContext(self.value_bound(py).as_ptr()).assume_owned_or_opt(py)
+        };
+        #[cfg(GraalPy)]
+        if let Some(context) = &obj {
+            if context.is_none() {
+                return None;
+            }
+        }
+        obj.map(Self::from_value_bound)
+    }
+
+    /// Set the context associated with the exception, pass `None` to clear it.
+    pub fn set_context(&self, py: Python<'_>, context: Option<Self>) {
+        let value = self.value_bound(py);
+        let context = context.map(|err| err.into_value(py));
+        unsafe {
+            // PyException_SetContext _steals_ a reference to context, so must use .into_ptr()
+            ffi::PyException_SetContext(
+                value.as_ptr(),
+                context.map_or(std::ptr::null_mut(), Py::into_ptr),
+            );
+        }
+    }
+
+    /// Formats the exception the way Python prints an uncaught exception.
+    ///
+    /// The result contains the traceback, any chained causes and contexts, and the exception
+    /// type and message. This is the same output as [`PyErr::display`], returned as a string
+    /// instead of printed to `sys.stderr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let err = py
+    ///         .run_bound("raise ValueError('banana') from KeyError('apple')", None, None)
+    ///         .unwrap_err();
+    ///     let formatted = err.format(py).unwrap();
+    ///     assert!(formatted.starts_with("KeyError: 'apple'"));
+    ///     assert!(formatted.contains("The above exception was the direct cause"));
+    ///     assert!(formatted.ends_with("ValueError: banana\n"));
+    /// });
+    /// ```
+    pub fn format(&self, py: Python<'_>) -> PyResult<String> {
+        let format_exception = py
+            .import_bound(crate::intern!(py, "traceback"))?
+            .getattr(crate::intern!(py, "format_exception"))?;
+        let lines = format_exception.call1((
+            self.get_type_bound(py),
+            self.value_bound(py),
+            self.traceback_bound(py),
+        ))?;
+        crate::intern!(py, "")
+            .call_method1(crate::intern!(py, "join"), (lines,))?
+            .extract()
+    }
+
     #[inline]
     fn from_state(state: PyErrState) -> PyErr {
         PyErr {
@@ -1088,6 +1191,28 @@ pub fn panic_after_error(_py: Python<'_>) -> ! {
 
 /// Returns Ok if the error code is not -1.
 #[inline]
+/// Converts an error chain, outermost error first, into a `PyErr` whose `__cause__` chain
+/// mirrors it. Errors which are not already a `PyErr` become a `RuntimeError` with their message.
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+pub(crate) fn error_chain_to_pyerr<'a>(
+    py: Python<'_>,
+    chain: impl DoubleEndedIterator<Item = &'a (dyn std::error::Error + 'static)>,
+) -> PyErr {
+    chain
+        .rev()
+        .fold(None, |cause: Option<PyErr>, error| {
+            let err = match error.downcast_ref::<PyErr>() {
+                Some(err) => err.clone_ref(py),
+                None => exceptions::PyRuntimeError::new_err(error.to_string()),
+            };
+            if cause.is_some() {
+                err.set_cause(py, cause);
+            }
+            Some(err)
+        })
+        .expect("an error chain contains at least one error")
+}
+
 pub(crate) fn error_on_minusone<T: SignedInteger>(py: Python<'_>, result: T) -> PyResult<()> {
     if result != T::MINUS_ONE {
         Ok(())
@@ -1308,6 +1433,55 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_pyerr_context() {
+        Python::with_gil(|py| {
+            let err = py
+                .run_bound(
+                    "try:\n    raise KeyError('apple')\nexcept KeyError:\n    raise ValueError('banana')",
+                    None,
+                    None,
+                )
+                .expect_err("raising should have given us an error");
+            let context = err
+                .context(py)
+                .expect("raising while handling should have given us a context");
+            assert_eq!(context.to_string(), "KeyError: 'apple'");
+            assert!(err.cause(py).is_none());
+
+            err.set_context(py, None);
+            assert!(err.context(py).is_none());
+
+            err.set_context(py, Some(exceptions::PyTypeError::new_err("orange")));
+            let context = err
+                .context(py)
+                .expect("set_context should have given us a context");
+            assert_eq!(context.to_string(), "TypeError: orange");
+        });
+    }
+
+    #[test]
+    fn test_pyerr_with_cause_and_format() {
+        Python::with_gil(|py| {
+            let err = exceptions::PyValueError::new_err("banana")
+                .with_cause(py, exceptions::PyKeyError::new_err("apple"));
+            assert_eq!(err.cause(py).unwrap().to_string(), "KeyError: 'apple'");
+            assert_eq!(
+                err.format(py).unwrap(),
+                "KeyError: 'apple'\n\n\
+                 The above exception was the direct cause of the following exception:\n\n\
+                 ValueError: banana\n"
+            );
+
+            let err = py
+                .run_bound("raise Exception('banana')", None, None)
+                .expect_err("raising should have given us an error");
+            let formatted = err.format(py).unwrap();
+            assert!(formatted.starts_with("Traceback (most recent call last):\n"));
+            assert!(formatted.ends_with("Exception: banana\n"));
+        });
+    }
+
     #[test]
     fn warnings() {
         use crate::types::any::PyAnyMethods;
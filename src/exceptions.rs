@@ -8,8 +8,12 @@
 //! [`import_exception`](crate::import_exception) macro, which you can use
 //! yourself to import Python classes that are ultimately derived from
 //! `BaseException`.
+//!
+//! Exceptions which cannot be raised to a caller are reported to the hooks installed with
+//! [`set_excepthook`] and [`set_unraisablehook`].
 
-use crate::{ffi, Bound, PyResult, Python};
+use crate::types::{any::PyAnyMethods, PyCFunction, PyString};
+use crate::{ffi, intern, Bound, PyAny, PyErr, PyResult, Python};
 use std::ffi::CStr;
 use std::ops;
 
@@ -178,7 +182,10 @@ macro_rules! import_exception_bound {
 ///
 /// * `module` is the name of the containing module.
 /// * `name` is the name of the new exception type.
-/// * `base` is the base class of `MyError`, usually [`PyException`].
+/// * `base` is the base class of `MyError`, usually [`PyException`]. Use one of the `Warning`
+///   classes such as [`PyDeprecationWarning`] to define a warning category which can be issued
+///   with [`PyErr::warn_bound`](crate::PyErr::warn_bound). A parenthesized list of classes,
+///   e.g. `(PyKeyError, PyIndexError)`, defines an exception with multiple bases.
 /// * `doc` (optional) is the docstring visible to users (with `.__doc__` and `help()`) and
 ///
 /// accompanies your error type in your crate's documentation.
@@ -226,6 +233,29 @@ macro_rules! import_exception_bound {
 /// # }
 /// ```
 ///
+/// An exception can derive from several exception classes, as long as Python accepts them as
+/// bases of the same class:
+///
+/// ```
+/// use pyo3::prelude::*;
+/// use pyo3::create_exception;
+/// use pyo3::exceptions::{PyIndexError, PyKeyError, PyLookupError};
+///
+/// create_exception!(
+///     my_module,
+///     MissingEntry,
+///     (PyKeyError, PyIndexError),
+///     "Raised when an entry is missing."
+/// );
+///
+/// Python::with_gil(|py| {
+///     let err = MissingEntry::new_err(("entry", 3));
+///     assert!(err.is_instance_of::<PyKeyError>(py));
+///     assert!(err.is_instance_of::<PyIndexError>(py));
+///     assert!(err.is_instance_of::<PyLookupError>(py));
+/// });
+/// ```
+///
 /// Python code can handle this exception like any other exception:
 ///
 /// ```python
@@ -240,6 +270,30 @@ macro_rules! import_exception_bound {
 ///
 #[macro_export]
 macro_rules! create_exception {
+    ($module: expr, $name: ident, ($($base: ty),+ $(,)?)) => {
+        #[repr(transparent)]
+        #[allow(non_camel_case_types)] // E.g. `socket.herror`
+        pub struct $name($crate::PyAny);
+
+        $crate::impl_exception_boilerplate!($name);
+
+        $crate::create_exception_type_object!($module, $name, ($($base),+), ::std::option::Option::None);
+    };
+    ($module: expr, $name: ident, ($($base: ty),+ $(,)?), $doc: expr) => {
+        #[repr(transparent)]
+        #[allow(non_camel_case_types)] // E.g. `socket.herror`
+        #[doc = $doc]
+        pub struct $name($crate::PyAny);
+
+        $crate::impl_exception_boilerplate!($name);
+
+        $crate::create_exception_type_object!(
+            $module,
+            $name,
+            ($($base),+),
+            ::std::option::Option::Some($doc)
+        );
+    };
     ($module: expr, $name: ident, $base: ty) => {
         #[repr(transparent)]
         #[allow(non_camel_case_types)] // E.g. `socket.herror`
@@ -271,6 +325,31 @@ macro_rules! create_exception {
 #[doc(hidden)]
 #[macro_export]
 macro_rules! create_exception_type_object {
+    ($module: expr, $name: ident, ($($base: ty),+), $doc: expr) => {
+        $crate::pyobject_native_type_core!(
+            $name,
+            $name::type_object_raw,
+            #module=::std::option::Option::Some(stringify!($module))
+        );
+
+        impl $name {
+            fn type_object_raw(py: $crate::Python<'_>) -> *mut $crate::ffi::PyTypeObject {
+                use $crate::sync::GILOnceCell;
+                static TYPE_OBJECT: GILOnceCell<$crate::Py<$crate::types::PyType>> =
+                    GILOnceCell::new();
+
+                TYPE_OBJECT
+                    .get_or_init(py, ||
+                        $crate::impl_::exceptions::new_exception_type(
+                            py,
+                            concat!(stringify!($module), ".", stringify!($name)),
+                            $doc,
+                            [$(py.get_type_bound::<$base>()),+],
+                        )
+                ).as_ptr() as *mut $crate::ffi::PyTypeObject
+            }
+        }
+    };
     ($module: expr, $name: ident, $base: ty, $doc: expr) => {
         $crate::pyobject_native_type_core!(
             $name,
@@ -879,11 +958,148 @@ pub mod socket {
     }
 }
 
+/// Installs `hook` as `sys.excepthook`, which reports exceptions that reach the top level.
+///
+/// Python calls the hook for uncaught exceptions in the main program, and the default
+/// `threading.excepthook` calls it for uncaught exceptions in threads. An error returned by the
+/// hook is reported by Python along with the original exception.
+///
+/// Returns the previous hook, which can be reinstalled by setting `sys.excepthook` to it.
+///
+/// # Examples
+///
+/// ```rust
+/// use pyo3::exceptions::set_excepthook;
+/// use pyo3::prelude::*;
+///
+/// # fn main() -> PyResult<()> {
+/// Python::with_gil(|py| {
+///     set_excepthook(py, |py, err| {
+///         eprintln!("uncaught Python exception:\n{}", err.format(py)?);
+///         Ok(())
+///     })?;
+///     Ok(())
+/// })
+/// # }
+/// ```
+pub fn set_excepthook<F>(py: Python<'_>, hook: F) -> PyResult<Bound<'_, PyAny>>
+where
+    F: for<'py> Fn(Python<'py>, PyErr) -> PyResult<()> + Send + 'static,
+{
+    let hook = PyCFunction::new_closure_bound(
+        py,
+        Some("excepthook"),
+        None,
+        move |args, _kwargs| -> PyResult<()> {
+            let (_type, value, _traceback): (Bound<'_, PyAny>, Bound<'_, PyAny>, Bound<'_, PyAny>) =
+                args.extract()?;
+            hook(args.py(), PyErr::from_value_bound(value))
+        },
+    )?;
+    replace_sys_hook(py, intern!(py, "excepthook"), hook.into_any())
+}
+
+/// An exception reported to `sys.unraisablehook`.
+///
+/// See [`set_unraisablehook`].
+#[cfg(Py_3_8)]
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct UnraisableError<'py> {
+    /// The exception which could not be raised.
+    pub error: PyErr,
+    /// The message describing the error, if any.
+    ///
+    /// If it is `None`, Python's default hook prints "Exception ignored in:" followed by
+    /// [`object`](Self::object).
+    pub message: Option<String>,
+    /// The object related to the error, such as the object whose destructor failed.
+    pub object: Option<Bound<'py, PyAny>>,
+}
+
+/// Installs `hook` as `sys.unraisablehook`, which reports exceptions which cannot be raised.
+///
+/// Python calls the hook for exceptions raised in destructors, in garbage collection callbacks,
+/// or passed to [`PyErr::write_unraisable_bound`](PyErr::write_unraisable_bound). An
+/// error returned by the hook is written to `sys.stderr`.
+///
+/// Returns the previous hook, which can be reinstalled by setting `sys.unraisablehook` to it.
+///
+/// `sys.unraisablehook` is available from Python 3.8.
+///
+/// # Examples
+///
+/// ```rust
+/// use pyo3::exceptions::{set_unraisablehook, PyRuntimeError};
+/// use pyo3::prelude::*;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static UNRAISABLE: AtomicUsize = AtomicUsize::new(0);
+///
+/// # fn main() -> PyResult<()> {
+/// Python::with_gil(|py| {
+///     let previous = set_unraisablehook(py, |_py, unraisable| {
+///         UNRAISABLE.fetch_add(1, Ordering::Relaxed);
+///         eprintln!("ignored: {}", unraisable.error);
+///         Ok(())
+///     })?;
+///
+///     PyRuntimeError::new_err("cannot report this").write_unraisable_bound(py, None);
+///     assert_eq!(UNRAISABLE.load(Ordering::Relaxed), 1);
+///
+///     py.import_bound("sys")?.setattr("unraisablehook", previous)?;
+///     Ok(())
+/// })
+/// # }
+/// ```
+#[cfg(Py_3_8)]
+pub fn set_unraisablehook<F>(py: Python<'_>, hook: F) -> PyResult<Bound<'_, PyAny>>
+where
+    F: for<'py> Fn(Python<'py>, UnraisableError<'py>) -> PyResult<()> + Send + 'static,
+{
+    let hook = PyCFunction::new_closure_bound(
+        py,
+        Some("unraisablehook"),
+        None,
+        move |args, _kwargs| -> PyResult<()> {
+            let py = args.py();
+            let (unraisable,): (Bound<'_, PyAny>,) = args.extract()?;
+            let value = unraisable.getattr(intern!(py, "exc_value"))?;
+            let error = if value.is_none() {
+                PyErr::from_value_bound(unraisable.getattr(intern!(py, "exc_type"))?)
+            } else {
+                PyErr::from_value_bound(value)
+            };
+            let object = unraisable.getattr(intern!(py, "object"))?;
+            hook(
+                py,
+                UnraisableError {
+                    error,
+                    message: unraisable.getattr(intern!(py, "err_msg"))?.extract()?,
+                    object: if object.is_none() { None } else { Some(object) },
+                },
+            )
+        },
+    )?;
+    replace_sys_hook(py, intern!(py, "unraisablehook"), hook.into_any())
+}
+
+fn replace_sys_hook<'py>(
+    py: Python<'py>,
+    name: &Bound<'py, PyString>,
+    hook: Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let sys = py.import_bound(intern!(py, "sys"))?;
+    let previous = sys.getattr(name)?;
+    sys.setattr(name, hook)?;
+    Ok(previous)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::any::PyAnyMethods;
-    use crate::types::{IntoPyDict, PyDict};
+    use crate::types::{IntoPyDict, PyDict, PyTypeMethods};
     use crate::PyErr;
     #[cfg(feature = "gil-refs")]
     use crate::PyNativeType;
@@ -1045,6 +1261,66 @@ mod tests {
         });
     }
 
+    #[test]
+    fn custom_exception_multiple_bases() {
+        create_exception!(
+            mymodule,
+            CustomError,
+            (PyValueError, PyTypeError),
+            "Some docs"
+        );
+
+        Python::with_gil(|py| {
+            let error_type = py.get_type_bound::<CustomError>();
+            let ctx = [("CustomError", error_type)].into_py_dict_bound(py);
+            py.run_bound(
+                "assert CustomError.__bases__ == (ValueError, TypeError)\n\
+                 assert CustomError.__doc__ == 'Some docs'\n\
+                 assert CustomError.__module__ == 'mymodule'\n\
+                 assert CustomError('oops', 1).args == ('oops', 1)",
+                None,
+                Some(&ctx),
+            )
+            .unwrap();
+
+            let err = CustomError::new_err(("oops", 1));
+            assert!(err.is_instance_of::<CustomError>(py));
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(err.is_instance_of::<PyTypeError>(py));
+        });
+    }
+
+    #[test]
+    fn custom_warning() {
+        create_exception!(mymodule, CustomWarning, PyDeprecationWarning);
+
+        Python::with_gil(|py| {
+            let cls = py.get_type_bound::<CustomWarning>();
+            assert!(cls.is_subclass_of::<PyDeprecationWarning>().unwrap());
+
+            let warnings = py.import_bound("warnings").unwrap();
+            warnings.call_method0("resetwarnings").unwrap();
+
+            assert_warnings!(
+                py,
+                { PyErr::warn_bound(py, &cls, "old api", 0).unwrap() },
+                [(CustomWarning, "old api")]
+            );
+
+            // the warning filters for the base class also apply to the custom class
+            warnings
+                .call_method1(
+                    "simplefilter",
+                    ("error", py.get_type_bound::<PyDeprecationWarning>()),
+                )
+                .unwrap();
+            let err = PyErr::warn_bound(py, &cls, "old api", 0).unwrap_err();
+            assert!(err.is_instance_of::<CustomWarning>(py));
+
+            warnings.call_method0("resetwarnings").unwrap();
+        });
+    }
+
     #[test]
     fn native_exception_debug() {
         Python::with_gil(|py| {
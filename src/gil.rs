@@ -12,7 +12,7 @@ use std::cell::Cell;
 use std::cell::RefCell;
 #[cfg(all(feature = "gil-refs", not(debug_assertions)))]
 use std::cell::UnsafeCell;
-use std::{mem, ptr::NonNull, sync};
+use std::{marker::PhantomData, mem, ptr::NonNull, sync};
 
 static START: sync::Once = sync::Once::new();
 
@@ -44,7 +44,7 @@ const GIL_LOCKED_DURING_TRAVERSE: isize = -1;
 ///  2) PyGILState_Check always returns 1 if the sub-interpreter APIs have ever been called,
 ///     which could lead to incorrect conclusions that the GIL is held.
 #[inline(always)]
-fn gil_is_acquired() -> bool {
+pub(crate) fn gil_is_acquired() -> bool {
     GIL_COUNT.try_with(|c| c.get() > 0).unwrap_or(false)
 }
 
@@ -147,6 +147,195 @@ where
     result
 }
 
+/// Configuration of the Python interpreter embedded in a Rust program.
+///
+/// [`prepare_freethreaded_python`] initializes the interpreter with the default configuration.
+/// Programs which need control over the interpreter can instead build an `InitConfig` and call
+/// [`InitConfig::initialize`] before using any other Python API.
+///
+/// As with [`prepare_freethreaded_python`], signal handling is disabled and the GIL is released
+/// once the interpreter is initialized.
+///
+/// This API is only available on CPython 3.8 and up, and not when building for the limited API.
+///
+/// # Examples
+/// ```rust
+/// use pyo3::prelude::*;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// pyo3::InitConfig::new()
+///     .isolated(true)
+///     .program_name("my-app")
+///     .sys_path("scripts")
+///     .initialize()?;
+///
+/// Python::with_gil(|py| py.run_bound("import sys; print(sys.path)", None, None))?;
+/// # Python::with_gil(|py| py.run_bound("assert sys.path[0] == 'scripts'; assert sys.flags.isolated", None, None))?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(all(Py_3_8, not(any(PyPy, GraalPy, Py_LIMITED_API))))]
+#[derive(Clone, Debug, Default)]
+pub struct InitConfig {
+    isolated: bool,
+    program_name: Option<String>,
+    sys_path: Vec<std::path::PathBuf>,
+}
+
+#[cfg(all(Py_3_8, not(any(PyPy, GraalPy, Py_LIMITED_API))))]
+impl InitConfig {
+    /// Creates the default configuration, matching the one of [`prepare_freethreaded_python`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs Python in isolated mode.
+    ///
+    /// An isolated interpreter ignores environment variables such as `PYTHONPATH` and
+    /// `PYTHONHOME`, and excludes the user site-packages directory from `sys.path`, so that the
+    /// program behaves the same regardless of the Python setup of the machine it runs on.
+    pub fn isolated(mut self, isolated: bool) -> Self {
+        self.isolated = isolated;
+        self
+    }
+
+    /// Sets the program name, which Python uses to locate its standard library and which is
+    /// available as `sys.executable` when it cannot be resolved otherwise.
+    pub fn program_name(mut self, name: impl Into<String>) -> Self {
+        self.program_name = Some(name.into());
+        self
+    }
+
+    /// Adds `path` in front of the default module search paths in `sys.path`.
+    ///
+    /// Paths are searched in the order they were added.
+    pub fn sys_path(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.sys_path.push(path.into());
+        self
+    }
+
+    /// Initializes the Python interpreter with this configuration.
+    ///
+    /// Fails if the interpreter is already initialized, for instance by a previous call to
+    /// [`prepare_freethreaded_python`] or by the `auto-initialize` feature, or if Python reports
+    /// an error during initialization.
+    pub fn initialize(self) -> Result<(), InitError> {
+        let mut result = Err(InitError::new(
+            "the Python interpreter is already initialized",
+        ));
+        START.call_once_force(|_| unsafe {
+            if ffi::Py_IsInitialized() == 0 {
+                result = self.initialize_interpreter();
+                // Release the GIL.
+                if ffi::Py_IsInitialized() != 0 {
+                    ffi::PyEval_SaveThread();
+                }
+            }
+        });
+        result
+    }
+
+    unsafe fn initialize_interpreter(&self) -> Result<(), InitError> {
+        let program_name = self
+            .program_name
+            .as_deref()
+            .map(std::ffi::CString::new)
+            .transpose()
+            .map_err(|_| InitError::new("the program name contains a nul byte"))?;
+
+        let mut config = mem::MaybeUninit::<ffi::PyConfig>::uninit();
+        let config = config.as_mut_ptr();
+        if self.isolated {
+            ffi::PyConfig_InitIsolatedConfig(config);
+        } else {
+            ffi::PyConfig_InitPythonConfig(config);
+        }
+        (*config).install_signal_handlers = 0;
+
+        let status = match &program_name {
+            Some(name) => ffi::PyConfig_SetBytesString(
+                config,
+                std::ptr::addr_of_mut!((*config).program_name),
+                name.as_ptr(),
+            ),
+            None => ffi::PyStatus_Ok(),
+        };
+        let status = if ffi::PyStatus_Exception(status) == 0 {
+            ffi::Py_InitializeFromConfig(config)
+        } else {
+            status
+        };
+        ffi::PyConfig_Clear(config);
+        InitError::check(status)?;
+
+        let guard = GILGuard::assume();
+        let py = guard.python();
+        let result = self.insert_sys_path(py);
+        drop(guard);
+        result.map_err(|err| InitError::new(format!("failed to update `sys.path`: {}", err)))
+    }
+
+    fn insert_sys_path(&self, py: Python<'_>) -> crate::PyResult<()> {
+        use crate::types::any::PyAnyMethods;
+        if self.sys_path.is_empty() {
+            return Ok(());
+        }
+        let path = py.import_bound("sys")?.getattr("path")?;
+        for (index, entry) in self.sys_path.iter().enumerate() {
+            path.call_method1("insert", (index, entry))?;
+        }
+        Ok(())
+    }
+}
+
+/// The error returned by [`InitConfig::initialize`].
+#[cfg(all(Py_3_8, not(any(PyPy, GraalPy, Py_LIMITED_API))))]
+#[derive(Clone, Debug)]
+pub struct InitError {
+    message: String,
+}
+
+#[cfg(all(Py_3_8, not(any(PyPy, GraalPy, Py_LIMITED_API))))]
+impl InitError {
+    fn new(message: impl Into<String>) -> Self {
+        InitError {
+            message: message.into(),
+        }
+    }
+
+    unsafe fn check(status: ffi::PyStatus) -> Result<(), InitError> {
+        if ffi::PyStatus_IsExit(status) != 0 {
+            return Err(InitError::new(format!(
+                "Python exited during initialization with exit code {}",
+                status.exitcode
+            )));
+        }
+        if ffi::PyStatus_Exception(status) == 0 {
+            return Ok(());
+        }
+        let mut message = String::from("failed to initialize Python");
+        if !status.func.is_null() {
+            message.push_str(" in ");
+            message.push_str(&std::ffi::CStr::from_ptr(status.func).to_string_lossy());
+        }
+        if !status.err_msg.is_null() {
+            message.push_str(": ");
+            message.push_str(&std::ffi::CStr::from_ptr(status.err_msg).to_string_lossy());
+        }
+        Err(InitError::new(message))
+    }
+}
+
+#[cfg(all(Py_3_8, not(any(PyPy, GraalPy, Py_LIMITED_API))))]
+impl std::fmt::Display for InitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(all(Py_3_8, not(any(PyPy, GraalPy, Py_LIMITED_API))))]
+impl std::error::Error for InitError {}
+
 /// RAII type that represents the Global Interpreter Lock acquisition.
 pub(crate) enum GILGuard {
     /// Indicates the GIL was already held with this GILGuard was acquired.
@@ -352,6 +541,52 @@ impl Drop for SuspendGIL {
     }
 }
 
+/// A guard which temporarily restores the thread state saved by a [`SuspendGIL`], releasing the
+/// GIL again on `Drop`.
+pub(crate) struct RestoreGIL<'a> {
+    _suspended: PhantomData<&'a SuspendGIL>,
+    #[cfg(feature = "gil-refs")]
+    pool: mem::ManuallyDrop<GILPool>,
+}
+
+impl<'a> RestoreGIL<'a> {
+    /// # Safety
+    ///
+    /// Must be called on the thread which created `suspended`, while that thread does not hold
+    /// the GIL.
+    pub(crate) unsafe fn new(suspended: &'a SuspendGIL) -> Self {
+        ffi::PyEval_RestoreThread(suspended.tstate);
+        increment_gil_count();
+
+        #[cfg(feature = "gil-refs")]
+        #[allow(deprecated)]
+        let pool = mem::ManuallyDrop::new(GILPool::new());
+
+        #[cfg(not(pyo3_disable_reference_pool))]
+        if let Some(pool) = Lazy::get(&POOL) {
+            pool.update_counts(Python::assume_gil_acquired());
+        }
+
+        Self {
+            _suspended: PhantomData,
+            #[cfg(feature = "gil-refs")]
+            pool,
+        }
+    }
+}
+
+impl Drop for RestoreGIL<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            // Drop the objects in the pool before releasing the thread state again
+            #[cfg(feature = "gil-refs")]
+            mem::ManuallyDrop::drop(&mut self.pool);
+            decrement_gil_count();
+            ffi::PyEval_SaveThread();
+        }
+    }
+}
+
 /// Used to lock safe access to the GIL
 pub(crate) struct LockGIL {
     count: isize,
@@ -472,6 +707,27 @@ impl Drop for GILPool {
     }
 }
 
+/// Returns the number of objects owned by the `GILPool`s of the current thread.
+#[cfg(all(feature = "gil-refs", any(test, feature = "pool-diagnostics")))]
+pub(crate) fn owned_object_count() -> usize {
+    #[cfg(debug_assertions)]
+    let len = OWNED_OBJECTS.with(|owned_objects| owned_objects.borrow().len());
+    #[cfg(not(debug_assertions))]
+    let len = OWNED_OBJECTS.with(|owned_objects| unsafe { (*owned_objects.get()).len() });
+    len
+}
+
+/// Applies the reference count changes deferred while the GIL was not held.
+#[cfg(feature = "pool-diagnostics")]
+pub(crate) fn update_deferred_reference_counts(py: Python<'_>) {
+    #[cfg(not(pyo3_disable_reference_pool))]
+    if let Some(pool) = Lazy::get(&POOL) {
+        pool.update_counts(py);
+    }
+    #[cfg(pyo3_disable_reference_pool)]
+    let _ = py;
+}
+
 /// Increments the reference count of a Python object if the GIL is held. If
 /// the GIL is not held, this function will panic.
 ///
@@ -561,10 +817,9 @@ fn decrement_gil_count() {
 
 #[cfg(test)]
 mod tests {
-    use super::GIL_COUNT;
     #[cfg(feature = "gil-refs")]
-    #[allow(deprecated)]
-    use super::OWNED_OBJECTS;
+    use super::owned_object_count;
+    use super::GIL_COUNT;
     #[cfg(not(pyo3_disable_reference_pool))]
     use super::{gil_is_acquired, POOL};
     #[cfg(feature = "gil-refs")]
@@ -577,15 +832,6 @@ mod tests {
         py.eval_bound("object()", None, None).unwrap().unbind()
     }
 
-    #[cfg(feature = "gil-refs")]
-    fn owned_object_count() -> usize {
-        #[cfg(debug_assertions)]
-        let len = OWNED_OBJECTS.with(|owned_objects| owned_objects.borrow().len());
-        #[cfg(not(debug_assertions))]
-        let len = OWNED_OBJECTS.with(|owned_objects| unsafe { (*owned_objects.get()).len() });
-        len
-    }
-
     #[cfg(not(pyo3_disable_reference_pool))]
     fn pool_dec_refs_does_not_contain(obj: &PyObject) -> bool {
         !POOL
@@ -840,6 +1086,21 @@ mod tests {
         })
     }
 
+    #[test]
+    #[cfg(all(Py_3_8, not(any(PyPy, GraalPy, Py_LIMITED_API))))]
+    fn test_init_config_already_initialized() {
+        Python::with_gil(|_| {});
+        let err = crate::InitConfig::new()
+            .isolated(true)
+            .sys_path("unused")
+            .initialize()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the Python interpreter is already initialized"
+        );
+    }
+
     #[test]
     #[cfg(not(pyo3_disable_reference_pool))]
     fn test_gil_guard_update_counts() {
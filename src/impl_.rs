@@ -13,6 +13,7 @@ pub mod exceptions;
 pub mod extract_argument;
 pub mod freelist;
 pub mod frompyobject;
+pub mod intopyobject;
 pub(crate) mod not_send;
 pub mod panic;
 pub mod pycell;
@@ -20,6 +21,8 @@ pub mod pyclass;
 pub mod pyfunction;
 pub mod pymethods;
 pub mod pymodule;
+#[cfg(feature = "macros")]
+pub mod pytest;
 #[doc(hidden)]
 pub mod trampoline;
 pub mod wrap;
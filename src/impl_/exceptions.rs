@@ -1,4 +1,8 @@
-use crate::{sync::GILOnceCell, types::PyType, Bound, Py, Python};
+use crate::{
+    sync::GILOnceCell,
+    types::{PyTuple, PyType},
+    Bound, Py, PyErr, Python,
+};
 
 pub struct ImportedExceptionTypeObject {
     imported_value: GILOnceCell<Py<PyType>>,
@@ -26,3 +30,15 @@ impl ImportedExceptionTypeObject {
             })
     }
 }
+
+/// Creates the type object of an exception declared by `create_exception!` with several bases.
+pub fn new_exception_type<const N: usize>(
+    py: Python<'_>,
+    name: &str,
+    doc: Option<&str>,
+    bases: [Bound<'_, PyType>; N],
+) -> Py<PyType> {
+    let bases = PyTuple::new_bound(py, bases);
+    PyErr::new_type_with_bases(py, name, doc, Some(bases.as_any()), None)
+        .expect("Failed to initialize new exception type.")
+}
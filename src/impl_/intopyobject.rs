@@ -0,0 +1,25 @@
+use crate::sync::GILOnceCell;
+use crate::types::{PyAnyMethods, PyTuple, PyType};
+use crate::{Bound, Py, PyObject, PyResult, Python};
+
+/// Converts `values` into an instance of the `collections.namedtuple` type called `name`, which
+/// is created on first use and cached in `cell`.
+pub fn namedtuple<const N: usize>(
+    py: Python<'_>,
+    cell: &'static GILOnceCell<Py<PyType>>,
+    name: &str,
+    fields: [&str; N],
+    values: [PyObject; N],
+) -> PyObject {
+    let ty = cell
+        .get_or_try_init(py, || -> PyResult<_> {
+            let namedtuple = py.import_bound("collections")?.getattr("namedtuple")?;
+            let ty = namedtuple.call1((name, fields.to_vec()))?;
+            Ok(ty.downcast_into::<PyType>()?.unbind())
+        })
+        .expect("failed to create namedtuple type");
+    ty.bind(py)
+        .call1(PyTuple::new_bound(py, values))
+        .map(Bound::unbind)
+        .expect("failed to construct namedtuple")
+}
@@ -1,22 +1,30 @@
 #[cfg(feature = "gil-refs")]
 use crate::PyNativeType;
 use crate::{
+    err::error_on_minusone,
     exceptions::{PyAttributeError, PyNotImplementedError, PyRuntimeError, PyValueError},
     ffi,
+    ffi_ptr_ext::FfiPtrExt,
     impl_::freelist::FreeList,
     impl_::pycell::{GetBorrowChecker, PyClassMutability, PyClassObjectLayout},
+    impl_::pymethods::tp_new_impl,
+    intern,
     internal_tricks::extract_c_string,
-    pyclass_init::PyObjectInit,
+    pyclass_init::{PyClassInitializer, PyNativeTypeInitializer, PyObjectInit},
+    sync::GILOnceCell,
+    type_object::{get_tp_clear, get_tp_traverse},
     types::any::PyAnyMethods,
-    types::PyBool,
-    Borrowed, Py, PyAny, PyClass, PyErr, PyMethodDefType, PyResult, PyTypeInfo, Python,
+    types::typeobject::PyTypeMethods,
+    types::{dict::PyDictMethods, PyBool, PyDict, PyTuple, PyType},
+    Borrowed, Bound, Py, PyAny, PyClass, PyErr, PyMethodDefType, PyObject, PyResult, PyTypeInfo,
+    Python,
 };
 use std::{
     borrow::Cow,
     ffi::{CStr, CString},
     marker::PhantomData,
     os::raw::{c_int, c_void},
-    ptr::NonNull,
+    ptr::{self, NonNull},
     thread,
 };
 
@@ -39,9 +47,17 @@ pub fn weaklist_offset<T: PyClass>() -> ffi::Py_ssize_t {
 pub trait PyClassDict {
     /// Initial form of a [PyObject](crate::ffi::PyObject) `__dict__` reference.
     const INIT: Self;
-    /// Empties the dictionary of its key-value pairs.
+    /// Releases the reference to the dictionary, if any.
     #[inline]
     fn clear_dict(&mut self, _py: Python<'_>) {}
+    /// Visits the dictionary, if any, for the garbage collector.
+    ///
+    /// # Safety
+    /// - `_visit` and `_arg` must be the arguments passed to `tp_traverse`.
+    #[inline]
+    unsafe fn traverse_dict(&self, _visit: ffi::visitproc, _arg: *mut c_void) -> c_int {
+        0
+    }
     private_decl! {}
 }
 
@@ -84,8 +100,19 @@ impl PyClassDict for PyClassDictSlot {
     const INIT: Self = Self(std::ptr::null_mut());
     #[inline]
     fn clear_dict(&mut self, _py: Python<'_>) {
-        if !self.0.is_null() {
-            unsafe { ffi::PyDict_Clear(self.0) }
+        // Reset the slot before releasing the dict, as dropping its contents may run arbitrary
+        // code which could access `__dict__` again.
+        let dict = std::mem::replace(&mut self.0, std::ptr::null_mut());
+        if !dict.is_null() {
+            unsafe { ffi::Py_DECREF(dict) }
+        }
+    }
+    #[inline]
+    unsafe fn traverse_dict(&self, visit: ffi::visitproc, arg: *mut c_void) -> c_int {
+        if self.0.is_null() {
+            0
+        } else {
+            visit(self.0, arg)
         }
     }
 }
@@ -205,6 +232,12 @@ pub trait PyClassImpl: Sized + 'static {
         None
     }
 
+    /// #[pyclass(metaclass=...)], or `None` to use the metaclass of the base type
+    #[inline]
+    fn metaclass(_py: Python<'_>) -> PyResult<Option<Bound<'_, PyType>>> {
+        Ok(None)
+    }
+
     fn lazy_type_object() -> &'static LazyTypeObject<Self>;
 }
 
@@ -231,6 +264,77 @@ pub fn build_pyclass_doc(
     }
 }
 
+/// Runtime helper for `#[pyclass(metaclass = "module.Name")]`, importing the metaclass on first use.
+pub fn import_metaclass<'py>(
+    py: Python<'py>,
+    cell: &'static GILOnceCell<Py<PyType>>,
+    module_name: &str,
+    attr_name: &str,
+) -> PyResult<Option<Bound<'py, PyType>>> {
+    cell.get_or_try_init_type_ref(py, module_name, attr_name)
+        .map(|ty| Some(ty.clone()))
+}
+
+/// Runtime helper to build the `__members__` class attribute of a simple `#[pyclass]` enum.
+///
+/// Like `enum.Enum.__members__`, this is a read-only mapping from variant names to values, in
+/// declaration order.
+pub fn simple_enum_members(
+    py: Python<'_>,
+    members: &[(&'static str, PyObject)],
+) -> PyResult<PyObject> {
+    let dict = PyDict::new_bound(py);
+    for (name, member) in members {
+        dict.set_item(name, member)?;
+    }
+    unsafe { ffi::PyDictProxy_New(dict.as_ptr()).assume_owned_or_err(py) }.map(Bound::unbind)
+}
+
+/// Runtime helper implementing the `__class_getitem__` classmethod for `#[pyclass(generic)]`,
+/// which returns a `types.GenericAlias` such as `cls[int]` for use in type annotations.
+pub fn class_getitem<'py>(
+    cls: &Bound<'py, PyType>,
+    key: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    static GENERIC_ALIAS: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+    GENERIC_ALIAS
+        .get_or_try_init_type_ref(cls.py(), "types", "GenericAlias")?
+        .call1((cls, key))
+}
+
+/// Runtime helper implementing `__reduce__` for `#[pyclass(pickle)]`.
+///
+/// The object is reconstructed by the `__pyo3_unpickle__` classmethod, after which the unpickler
+/// passes the result of `__getstate__` to `__setstate__`.
+pub fn pickle_reduce<'py>(slf: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyTuple>> {
+    let py = slf.py();
+    let unpickle = slf.get_type().getattr(intern!(py, "__pyo3_unpickle__"))?;
+    let state = slf.call_method0(intern!(py, "__getstate__"))?;
+    Ok(PyTuple::new_bound(
+        py,
+        [unpickle, PyTuple::empty_bound(py).into_any(), state],
+    ))
+}
+
+/// Runtime helper implementing the `__pyo3_unpickle__` classmethod for `#[pyclass(pickle)]`,
+/// which creates an instance of `cls` holding `T::default()`.
+pub fn pickle_default<T>(cls: &Bound<'_, PyType>) -> PyResult<PyObject>
+where
+    T: PyClass + Default + Into<PyClassInitializer<T>>,
+{
+    let py = cls.py();
+    unsafe {
+        tp_new_impl(
+            py,
+            T::default().into(),
+            cls.as_type_ptr(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+        .map(|obj| obj.assume_owned(py).unbind())
+    }
+}
+
 /// Iterator used to process all class items during type instantiation.
 pub struct PyClassItemsIter {
     /// Iteration state
@@ -389,8 +493,8 @@ macro_rules! define_pyclass_setattr_slot {
         $del_trait:ident,
         $set:ident,
         $del:ident,
-        $set_error:expr,
-        $del_error:expr,
+        |$py:ident, $slf:ident, $attr:ident, $value:ident| $set_default:expr,
+        |$del_py:ident, $del_slf:ident, $del_attr:ident| $del_default:expr,
         $generate_macro:ident,
         $slot:ident,
         $func_ty:ident,
@@ -402,12 +506,12 @@ macro_rules! define_pyclass_setattr_slot {
             #[inline]
             unsafe fn $set(
                 self,
-                _py: Python<'_>,
-                _slf: *mut ffi::PyObject,
-                _attr: *mut ffi::PyObject,
-                _value: NonNull<ffi::PyObject>,
+                $py: Python<'_>,
+                $slf: *mut ffi::PyObject,
+                $attr: *mut ffi::PyObject,
+                $value: NonNull<ffi::PyObject>,
             ) -> PyResult<()> {
-                $set_error
+                $set_default
             }
         }
 
@@ -418,11 +522,11 @@ macro_rules! define_pyclass_setattr_slot {
             #[inline]
             unsafe fn $del(
                 self,
-                _py: Python<'_>,
-                _slf: *mut ffi::PyObject,
-                _attr: *mut ffi::PyObject,
+                $del_py: Python<'_>,
+                $del_slf: *mut ffi::PyObject,
+                $del_attr: *mut ffi::PyObject,
             ) -> PyResult<()> {
-                $del_error
+                $del_default
             }
         }
 
@@ -467,8 +571,12 @@ define_pyclass_setattr_slot! {
     PyClass__delattr__SlotFragment,
     __setattr__,
     __delattr__,
-    Err(PyAttributeError::new_err("can't set attribute")),
-    Err(PyAttributeError::new_err("can't delete attribute")),
+    // Like `object.__setattr__` and `object.__delattr__`, so that defining only one of the two
+    // keeps normal behaviour (e.g. `#[pyo3(set)]` fields and `__dict__`) for the other.
+    |py, slf, attr, value| {
+        error_on_minusone(py, ffi::PyObject_GenericSetAttr(slf, attr, value.as_ptr()))
+    },
+    |py, slf, attr| error_on_minusone(py, ffi::PyObject_GenericSetAttr(slf, attr, ptr::null_mut())),
     generate_pyclass_setattr_slot,
     Py_tp_setattro,
     setattrofunc,
@@ -479,8 +587,9 @@ define_pyclass_setattr_slot! {
     PyClass__delete__SlotFragment,
     __set__,
     __delete__,
-    Err(PyNotImplementedError::new_err("can't set descriptor")),
-    Err(PyNotImplementedError::new_err("can't delete descriptor")),
+    // Matches Python classes, where a missing `__set__` or `__delete__` raises AttributeError.
+    |_py, _slf, _attr, _value| Err(PyAttributeError::new_err("__set__")),
+    |_py, _slf, _attr| Err(PyAttributeError::new_err("__delete__")),
     generate_pyclass_setdescr_slot,
     Py_tp_descr_set,
     descrsetfunc,
@@ -491,8 +600,8 @@ define_pyclass_setattr_slot! {
     PyClass__delitem__SlotFragment,
     __setitem__,
     __delitem__,
-    Err(PyNotImplementedError::new_err("can't set item")),
-    Err(PyNotImplementedError::new_err("can't delete item")),
+    |_py, _slf, _attr, _value| Err(PyNotImplementedError::new_err("can't set item")),
+    |_py, _slf, _attr| Err(PyNotImplementedError::new_err("can't delete item")),
     generate_pyclass_setitem_slot,
     Py_mp_ass_subscript,
     objobjargproc,
@@ -1111,6 +1220,14 @@ impl<T> PyClassThreadChecker<T> for ThreadCheckerImpl {
         note = "with the `abi3` feature enabled, PyO3 does not support subclassing native types"
     )
 )]
+#[cfg_attr(
+    all(diagnostic_namespace, not(feature = "abi3")),
+    diagnostic::on_unimplemented(
+        message = "`{Self}` cannot be used as a base class",
+        label = "`{Self}` is not a `#[pyclass]` or a native type with a known layout",
+        note = "native types such as `PyDict`, `PyList`, `PySet` and exceptions can be subclassed"
+    )
+)]
 pub trait PyClassBaseType: Sized {
     type LayoutAsBase: PyClassObjectLayout<Self>;
     type BaseNativeType;
@@ -1118,6 +1235,21 @@ pub trait PyClassBaseType: Sized {
     type PyClassMutability: PyClassMutability;
 }
 
+/// Marker for base types which are initialized by Python rather than by a `#[new]` method,
+/// i.e. native types.
+#[cfg_attr(
+    diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`#[new]` must also initialize the base class `{Self}`",
+        label = "`{Self}` is a `#[pyclass]`, so its value has to be provided",
+        note = "return `(Self, {Self})`, or a `PyClassInitializer` built with `PyClassInitializer::from(base).add_subclass(..)`"
+    )
+)]
+pub trait PyClassNativeBase:
+    PyTypeInfo + PyClassBaseType<Initializer = PyNativeTypeInitializer<Self>>
+{
+}
+
 /// All mutable PyClasses can be used as a base type.
 ///
 /// In the future this will be extended to immutable PyClasses too.
@@ -1142,6 +1274,60 @@ pub(crate) unsafe extern "C" fn tp_dealloc_with_gc<T: PyClass>(obj: *mut ffi::Py
     crate::impl_::trampoline::dealloc(obj, PyClassObject::<T>::tp_dealloc)
 }
 
+/// Implementation of tp_traverse for pyclasses with a `__dict__`.
+///
+/// Visits the instance dict, then calls the `__traverse__` implementation of the class or,
+/// if it has none, the traversal of the base type.
+pub(crate) unsafe extern "C" fn tp_traverse_with_dict<T: PyClass>(
+    slf: *mut ffi::PyObject,
+    visit: ffi::visitproc,
+    arg: *mut c_void,
+) -> c_int {
+    let class_object = &*slf.cast::<PyClassObject<T>>();
+    let retval = class_object.dict().traverse_dict(visit, arg);
+    if retval != 0 {
+        return retval;
+    }
+    if let Some(traverse) = find_slot::<T>(ffi::Py_tp_traverse) {
+        let traverse: ffi::traverseproc = std::mem::transmute(traverse);
+        return traverse(slf, visit, arg);
+    }
+    let base = T::BaseType::type_object_raw(Python::assume_gil_acquired());
+    match get_tp_traverse(base) {
+        Some(traverse) => traverse(slf, visit, arg),
+        None => 0,
+    }
+}
+
+/// Implementation of tp_clear for pyclasses with a `__dict__`.
+///
+/// Calls the `__clear__` implementation of the class or, if it has none, the clear of the base
+/// type, then releases the instance dict.
+pub(crate) unsafe extern "C" fn tp_clear_with_dict<T: PyClass>(slf: *mut ffi::PyObject) -> c_int {
+    let py = Python::assume_gil_acquired();
+    let clear = match find_slot::<T>(ffi::Py_tp_clear) {
+        Some(clear) => Some(std::mem::transmute::<*mut c_void, ffi::inquiry>(clear)),
+        None => get_tp_clear(T::BaseType::type_object_raw(py)),
+    };
+    if let Some(clear) = clear {
+        let retval = clear(slf);
+        if retval != 0 {
+            return retval;
+        }
+    }
+    let class_object = &mut *slf.cast::<PyClassObject<T>>();
+    class_object.dict_mut().clear_dict(py);
+    0
+}
+
+/// Finds a slot defined by the `#[pyclass]` or `#[pymethods]` implementations of `T`.
+fn find_slot<T: PyClass>(slot: c_int) -> Option<*mut c_void> {
+    T::items_iter()
+        .flat_map(|items| items.slots)
+        .find(|def| def.slot == slot)
+        .map(|def| def.pfunc)
+}
+
 pub(crate) unsafe extern "C" fn get_sequence_item_from_mapping(
     obj: *mut ffi::PyObject,
     index: ffi::Py_ssize_t,
@@ -2,6 +2,7 @@ use crate::callback::IntoPyCallbackOutput;
 use crate::exceptions::PyStopAsyncIteration;
 use crate::gil::LockGIL;
 use crate::impl_::panic::PanicTrap;
+use crate::impl_::pyclass::PyClassNativeBase;
 use crate::internal_tricks::extract_c_string;
 use crate::pycell::{PyBorrowError, PyBorrowMutError};
 use crate::pyclass::boolean_struct::False;
@@ -73,7 +74,7 @@ pub enum PyMethodDefType {
 pub enum PyMethodType {
     PyCFunction(ffi::PyCFunction),
     PyCFunctionWithKeywords(ffi::PyCFunctionWithKeywords),
-    #[cfg(not(Py_LIMITED_API))]
+    #[cfg(any(Py_3_10, not(Py_LIMITED_API)))]
     PyCFunctionFastWithKeywords(ffi::_PyCFunctionFastWithKeywords),
 }
 
@@ -152,7 +153,7 @@ impl PyMethodDef {
     }
 
     /// Define a function that can take `*args` and `**kwargs`.
-    #[cfg(not(Py_LIMITED_API))]
+    #[cfg(any(Py_3_10, not(Py_LIMITED_API)))]
     pub const fn fastcall_cfunction_with_keywords(
         name: &'static str,
         cfunction: ffi::_PyCFunctionFastWithKeywords,
@@ -178,7 +179,7 @@ impl PyMethodDef {
             PyMethodType::PyCFunctionWithKeywords(meth) => ffi::PyMethodDefPointer {
                 PyCFunctionWithKeywords: meth,
             },
-            #[cfg(not(Py_LIMITED_API))]
+            #[cfg(any(Py_3_10, not(Py_LIMITED_API)))]
             PyMethodType::PyCFunctionFastWithKeywords(meth) => ffi::PyMethodDefPointer {
                 _PyCFunctionFastWithKeywords: meth,
             },
@@ -568,8 +569,82 @@ pub unsafe fn tp_new_impl<T: PyClass>(
     py: Python<'_>,
     initializer: PyClassInitializer<T>,
     target_type: *mut ffi::PyTypeObject,
+    args: *mut ffi::PyObject,
+    kwargs: *mut ffi::PyObject,
 ) -> PyResult<*mut ffi::PyObject> {
     initializer
-        .create_class_object_of_type(py, target_type)
+        .create_class_object_of_type(py, target_type, args, kwargs)
         .map(Bound::into_ptr)
 }
+
+/// Conversion of the value returned by a `#[new]` method into the initializer of `T`.
+///
+/// This mirrors the `From` implementations of [`PyClassInitializer`], so that an invalid return
+/// type is reported with a message about `#[new]` instead of a missing `From` implementation.
+#[cfg_attr(
+    diagnostic_namespace,
+    diagnostic::on_unimplemented(
+        message = "`{Self}` cannot be returned from a `#[new]` method of `{T}`",
+        label = "invalid return type for `#[new]`",
+        note = "`#[new]` can return `{T}`, `({T}, BaseClass)` for `#[pyclass(extends = BaseClass)]`, `PyClassInitializer<{T}>` or `Py<{T}>`, optionally wrapped in `PyResult`"
+    )
+)]
+pub trait PyClassNewOutput<T: PyClass> {
+    fn into_initializer(self) -> PyResult<PyClassInitializer<T>>;
+}
+
+impl<T> PyClassNewOutput<T> for T
+where
+    T: PyClass,
+    T::BaseType: PyClassNativeBase,
+{
+    #[inline]
+    fn into_initializer(self) -> PyResult<PyClassInitializer<T>> {
+        Ok(self.into())
+    }
+}
+
+impl<S, B> PyClassNewOutput<S> for (S, B)
+where
+    S: PyClass<BaseType = B>,
+    B: PyClass,
+    B::BaseType: PyClassNativeBase,
+{
+    #[inline]
+    fn into_initializer(self) -> PyResult<PyClassInitializer<S>> {
+        Ok(self.into())
+    }
+}
+
+impl<T: PyClass> PyClassNewOutput<T> for PyClassInitializer<T> {
+    #[inline]
+    fn into_initializer(self) -> PyResult<PyClassInitializer<T>> {
+        Ok(self)
+    }
+}
+
+impl<T: PyClass> PyClassNewOutput<T> for Py<T> {
+    #[inline]
+    fn into_initializer(self) -> PyResult<PyClassInitializer<T>> {
+        Ok(self.into())
+    }
+}
+
+impl<T: PyClass> PyClassNewOutput<T> for Bound<'_, T> {
+    #[inline]
+    fn into_initializer(self) -> PyResult<PyClassInitializer<T>> {
+        Ok(self.into())
+    }
+}
+
+impl<T, U, E> PyClassNewOutput<T> for Result<U, E>
+where
+    T: PyClass,
+    U: PyClassNewOutput<T>,
+    E: Into<PyErr>,
+{
+    #[inline]
+    fn into_initializer(self) -> PyResult<PyClassInitializer<T>> {
+        self.map_err(Into::into)?.into_initializer()
+    }
+}
@@ -1,6 +1,16 @@
 //! Implementation details of `#[pymodule]` which need to be accessible from proc-macro generated code.
 
-use std::{cell::UnsafeCell, marker::PhantomData};
+#[cfg(all(
+    not(any(PyPy, GraalPy)),
+    not(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10)))))
+))]
+use std::sync::atomic::AtomicBool;
+use std::{
+    cell::UnsafeCell,
+    ffi::CStr,
+    marker::PhantomData,
+    os::raw::{c_int, c_void},
+};
 
 #[cfg(all(
     not(any(PyPy, GraalPy)),
@@ -21,15 +31,29 @@ use std::sync::atomic::{AtomicI64, Ordering};
 use crate::exceptions::PyImportError;
 use crate::{
     ffi,
+    ffi_ptr_ext::FfiPtrExt,
+    intern,
     sync::GILOnceCell,
-    types::{PyCFunction, PyModule, PyModuleMethods},
+    types::{IntoPyDict, PyAnyMethods, PyCFunction, PyModule, PyModuleMethods},
     Bound, Py, PyClass, PyMethodDef, PyResult, PyTypeInfo, Python,
 };
 
+/// Number of slots in the module definition, including the terminating null slot.
+const SLOTS_LEN: usize = if cfg!(Py_3_12) { 3 } else { 2 };
+
 /// `Sync` wrapper of `ffi::PyModuleDef`.
+///
+/// Modules are created using multi-phase initialization ([PEP 489]): the module object is created
+/// by Python from this definition, and the initializer runs from its `Py_mod_exec` slot.
+///
+/// [PEP 489]: https://peps.python.org/pep-0489/
+// `repr(C)` with `ffi_def` as the first field, so that the `ModuleDef` can be recovered from the
+// `ffi::PyModuleDef` pointer returned by `PyModule_GetDef`.
+#[repr(C)]
 pub struct ModuleDef {
     // wrapped in UnsafeCell so that Rust compiler treats this as interior mutability
     ffi_def: UnsafeCell<ffi::PyModuleDef>,
+    slots: UnsafeCell<[ffi::PyModuleDef_Slot; SLOTS_LEN]>,
     initializer: ModuleInitializer,
     /// Interpreter ID where module was initialized (not applicable on PyPy).
     #[cfg(all(
@@ -38,7 +62,13 @@ pub struct ModuleDef {
         not(all(windows, Py_LIMITED_API, not(Py_3_10)))
     ))]
     interpreter: AtomicI64,
-    /// Initialized module object, cached to avoid reinitialization.
+    /// Whether the module was initialized, where the interpreter ID is not available.
+    #[cfg(all(
+        not(any(PyPy, GraalPy)),
+        not(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10)))))
+    ))]
+    initialized: AtomicBool,
+    /// Module object created by `make_module`, cached to avoid reinitialization.
     module: GILOnceCell<Py<PyModule>>,
 }
 
@@ -68,6 +98,10 @@ impl ModuleDef {
             m_clear: None,
             m_free: None,
         };
+        const NULL_SLOT: ffi::PyModuleDef_Slot = ffi::PyModuleDef_Slot {
+            slot: 0,
+            value: std::ptr::null_mut(),
+        };
 
         let ffi_def = UnsafeCell::new(ffi::PyModuleDef {
             m_name: name.as_ptr().cast(),
@@ -77,6 +111,7 @@ impl ModuleDef {
 
         ModuleDef {
             ffi_def,
+            slots: UnsafeCell::new([NULL_SLOT; SLOTS_LEN]),
             initializer,
             // -1 is never expected to be a valid interpreter ID
             #[cfg(all(
@@ -85,14 +120,85 @@ impl ModuleDef {
                 not(all(windows, Py_LIMITED_API, not(Py_3_10)))
             ))]
             interpreter: AtomicI64::new(-1),
+            #[cfg(all(
+                not(any(PyPy, GraalPy)),
+                not(all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10)))))
+            ))]
+            initialized: AtomicBool::new(false),
             module: GILOnceCell::new(),
         }
     }
-    /// Builds a module using user given initializer. Used for [`#[pymodule]`][crate::pymodule].
+
+    /// Returns the module definition, with its slots filled in.
+    fn ffi_def(&'static self) -> *mut ffi::PyModuleDef {
+        let ffi_def = self.ffi_def.get();
+        // SAFETY: the definition is only accessed with the GIL held, and the slots are only
+        // written before the first time the definition is handed to Python.
+        unsafe {
+            if (*ffi_def).m_slots.is_null() {
+                let slots = &mut *self.slots.get();
+                slots[0] = ffi::PyModuleDef_Slot {
+                    slot: ffi::Py_mod_exec,
+                    value: module_exec as *mut c_void,
+                };
+                // PyO3 keeps per-module state in Rust statics, which can't be shared between
+                // interpreters, so the module can't be imported in a subinterpreter.
+                #[cfg(Py_3_12)]
+                {
+                    slots[1] = ffi::PyModuleDef_Slot {
+                        slot: ffi::Py_mod_multiple_interpreters,
+                        value: ffi::Py_MOD_MULTIPLE_INTERPRETERS_NOT_SUPPORTED,
+                    };
+                }
+                (*ffi_def).m_slots = slots.as_mut_ptr();
+            }
+        }
+        ffi_def
+    }
+
+    /// Returns the module definition to Python, from the module's `PyInit_` function.
+    ///
+    /// Python then creates the module object and runs [`ModuleDef::exec`] on it.
+    pub fn init_multi_phase(&'static self) -> *mut ffi::PyObject {
+        unsafe { ffi::PyModuleDef_Init(self.ffi_def()) }
+    }
+
+    /// Builds a module using user given initializer, outside of Python's import system. Used for
+    /// [`wrap_pymodule!`][crate::wrap_pymodule] and submodules.
     pub fn make_module(&'static self, py: Python<'_>) -> PyResult<Py<PyModule>> {
+        self.module
+            .get_or_try_init(py, || {
+                // The spec only needs a name for modules without a `Py_mod_create` slot.
+                let name = unsafe { CStr::from_ptr((*self.ffi_def.get()).m_name) };
+                let kwargs = [(
+                    "name",
+                    name.to_str().expect("module name should be valid UTF-8"),
+                )]
+                .into_py_dict_bound(py);
+                let spec = py
+                    .import_bound(intern!(py, "types"))?
+                    .getattr(intern!(py, "SimpleNamespace"))?
+                    .call((), Some(&kwargs))?;
+                let ffi_def = self.ffi_def();
+                let module = unsafe {
+                    Py::<PyModule>::from_owned_ptr_or_err(
+                        py,
+                        ffi::PyModule_FromDefAndSpec(ffi_def, spec.as_ptr()),
+                    )?
+                };
+                crate::err::error_on_minusone(py, unsafe {
+                    ffi::PyModule_ExecDef(module.as_ptr(), ffi_def)
+                })?;
+                Ok(module)
+            })
+            .map(|py_module| py_module.clone_ref(py))
+    }
+
+    /// Runs the user given initializer on a module created from this definition.
+    fn exec(&self, module: &Bound<'_, PyModule>) -> PyResult<()> {
         #[cfg(all(PyPy, not(Py_3_8)))]
         {
-            use crate::types::any::PyAnyMethods;
+            let py = module.py();
             const PYPY_GOOD_VERSION: [u8; 3] = [7, 3, 8];
             let version = py
                 .import_bound("sys")?
@@ -118,7 +224,7 @@ impl ModuleDef {
             {
                 let current_interpreter =
                     unsafe { ffi::PyInterpreterState_GetID(ffi::PyInterpreterState_Get()) };
-                crate::err::error_on_minusone(py, current_interpreter)?;
+                crate::err::error_on_minusone(module.py(), current_interpreter)?;
                 if let Err(initialized_interpreter) = self.interpreter.compare_exchange(
                     -1,
                     current_interpreter,
@@ -136,26 +242,51 @@ impl ModuleDef {
             {
                 // CPython before 3.9 does not have APIs to check the interpreter ID, so best that can be
                 // done to guard against subinterpreters is fail if the module is initialized twice
-                if self.module.get(py).is_some() {
+                if self
+                    .initialized
+                    .swap(true, std::sync::atomic::Ordering::SeqCst)
+                {
                     return Err(PyImportError::new_err(
                         "PyO3 modules compiled for CPython 3.8 or older may only be initialized once per interpreter process"
                     ));
                 }
             }
         }
-        self.module
-            .get_or_try_init(py, || {
-                let module = unsafe {
-                    Py::<PyModule>::from_owned_ptr_or_err(
-                        py,
-                        ffi::PyModule_Create(self.ffi_def.get()),
-                    )?
-                };
-                self.initializer.0(module.bind(py))?;
-                Ok(module)
-            })
-            .map(|py_module| py_module.clone_ref(py))
+        #[cfg(feature = "build-info")]
+        module.setattr(
+            intern!(module.py(), "__pyo3_build_info__"),
+            build_info(module.py())?,
+        )?;
+        self.initializer.0(module)
+    }
+}
+
+/// The `key=value` lines written by PyO3's build script for the `build-info` feature.
+#[cfg(feature = "build-info")]
+const BUILD_INFO: &str = include_str!(concat!(env!("OUT_DIR"), "/pyo3-build-info.txt"));
+
+/// Builds the `__pyo3_build_info__` dict describing the Python this extension was built for.
+#[cfg(feature = "build-info")]
+fn build_info(py: Python<'_>) -> PyResult<Bound<'_, crate::types::PyDict>> {
+    use crate::types::PyDictMethods;
+    let info = crate::types::PyDict::new_bound(py);
+    for (key, value) in BUILD_INFO.lines().filter_map(|line| line.split_once('=')) {
+        info.set_item(key, value)?;
+    }
+    Ok(info)
+}
+
+/// `Py_mod_exec` slot of every module definition.
+unsafe extern "C" fn module_exec(module: *mut ffi::PyObject) -> c_int {
+    unsafe fn exec(py: Python<'_>, module: *mut ffi::PyObject) -> PyResult<()> {
+        let module = module.assume_borrowed(py);
+        let module = module.downcast_unchecked::<PyModule>();
+        // SAFETY: modules with this slot are only created from a `ModuleDef`, which starts with
+        // its `ffi::PyModuleDef`
+        let def = &*ffi::PyModule_GetDef(module.as_ptr()).cast::<ModuleDef>();
+        def.exec(&module)
     }
+    crate::impl_::trampoline::module_exec(module, exec)
 }
 
 /// Trait to add an element (class, function...) to a module.
@@ -207,10 +338,26 @@ impl PyAddToModule for PyMethodDef {
 /// For adding a module to a module.
 impl PyAddToModule for ModuleDef {
     fn add_to_module(&'static self, module: &Bound<'_, PyModule>) -> PyResult<()> {
-        module.add_submodule(self.make_module(module.py())?.bind(module.py()))
+        // The submodule's `__name__` may have been qualified with its parent's name by
+        // `set_qualified_name`, so add it under the name it was defined with instead.
+        let name = unsafe { CStr::from_ptr((*self.ffi_def.get()).m_name) };
+        module.add(
+            name.to_str().expect("module name should be valid UTF-8"),
+            self.make_module(module.py())?,
+        )
     }
 }
 
+/// Gives a submodule declared by a `#[pymodule]` its fully qualified `__name__`, and registers it
+/// in `sys.modules` so that it can be imported with `from parent.child import item`.
+pub fn set_qualified_name(module: &Bound<'_, PyModule>, qualified_name: &str) -> PyResult<()> {
+    let py = module.py();
+    module.setattr(intern!(py, "__name__"), qualified_name)?;
+    py.import_bound(intern!(py, "sys"))?
+        .getattr(intern!(py, "modules"))?
+        .set_item(qualified_name, module)
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -219,7 +366,12 @@ mod tests {
     };
 
     use crate::{
-        types::{any::PyAnyMethods, module::PyModuleMethods, PyModule},
+        ffi,
+        ffi_ptr_ext::FfiPtrExt,
+        types::{
+            any::PyAnyMethods, module::PyModuleMethods, IntoPyDict, PyModule, PyStringMethods,
+            PyTypeMethods,
+        },
         Bound, PyResult, Python,
     };
 
@@ -266,6 +418,123 @@ mod tests {
         })
     }
 
+    #[test]
+    // CPython before 3.9 cannot check the interpreter ID, so it refuses to execute a module twice,
+    // see `module_multi_phase_init_once` below
+    #[cfg(any(
+        PyPy,
+        GraalPy,
+        all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10))))
+    ))]
+    fn module_multi_phase_init() {
+        static MODULE_DEF: ModuleDef = unsafe {
+            ModuleDef::new(
+                "test_multi_phase_module\0",
+                "some doc\0",
+                ModuleInitializer(|m| m.add("SOME_CONSTANT", 42)),
+            )
+        };
+        Python::with_gil(|py| {
+            // What the import system does with the result of `PyInit_test_multi_phase_module`
+            let def = unsafe { MODULE_DEF.init_multi_phase().assume_borrowed(py) };
+            assert_eq!(def.get_type().qualname().unwrap(), "moduledef");
+            let spec = py
+                .import_bound("types")
+                .unwrap()
+                .getattr("SimpleNamespace")
+                .unwrap()
+                .call(
+                    (),
+                    Some(&[("name", "test_multi_phase_module")].into_py_dict_bound(py)),
+                )
+                .unwrap();
+
+            // Importing the module again creates and initializes a new module object
+            for _ in 0..2 {
+                let module = unsafe {
+                    let ffi_def = def.as_ptr().cast::<ffi::PyModuleDef>();
+                    let module = ffi::PyModule_FromDefAndSpec(ffi_def, spec.as_ptr())
+                        .assume_owned(py)
+                        .downcast_into::<PyModule>()
+                        .unwrap();
+                    assert_eq!(ffi::PyModule_ExecDef(module.as_ptr(), ffi_def), 0);
+                    module
+                };
+                assert_eq!(
+                    module.name().unwrap().to_cow().unwrap(),
+                    "test_multi_phase_module"
+                );
+                assert_eq!(
+                    module
+                        .getattr("SOME_CONSTANT")
+                        .unwrap()
+                        .extract::<u8>()
+                        .unwrap(),
+                    42,
+                );
+            }
+        })
+    }
+
+    #[test]
+    #[cfg(not(any(
+        PyPy,
+        GraalPy,
+        all(Py_3_9, not(all(windows, Py_LIMITED_API, not(Py_3_10))))
+    )))]
+    fn module_multi_phase_init_once() {
+        static MODULE_DEF: ModuleDef = unsafe {
+            ModuleDef::new(
+                "test_multi_phase_once_module\0",
+                "some doc\0",
+                ModuleInitializer(|_| Ok(())),
+            )
+        };
+        Python::with_gil(|py| {
+            let def = unsafe { MODULE_DEF.init_multi_phase().assume_borrowed(py) };
+            let spec = py
+                .import_bound("types")
+                .unwrap()
+                .getattr("SimpleNamespace")
+                .unwrap()
+                .call(
+                    (),
+                    Some(&[("name", "test_multi_phase_once_module")].into_py_dict_bound(py)),
+                )
+                .unwrap();
+            let exec = || unsafe {
+                let ffi_def = def.as_ptr().cast::<ffi::PyModuleDef>();
+                let module = ffi::PyModule_FromDefAndSpec(ffi_def, spec.as_ptr())
+                    .assume_owned(py)
+                    .downcast_into::<PyModule>()
+                    .unwrap();
+                ffi::PyModule_ExecDef(module.as_ptr(), ffi_def)
+            };
+
+            assert_eq!(exec(), 0);
+            assert_eq!(exec(), -1);
+            let err = crate::PyErr::fetch(py);
+            assert!(err.is_instance_of::<crate::exceptions::PyImportError>(py));
+        })
+    }
+
+    #[test]
+    #[cfg(Py_3_12)]
+    fn module_multiple_interpreters_slot() {
+        static MODULE_DEF: ModuleDef =
+            unsafe { ModuleDef::new("module\0", "\0", ModuleInitializer(|_| Ok(()))) };
+
+        let slots = unsafe { (*MODULE_DEF.ffi_def()).m_slots };
+        let slots = unsafe { std::slice::from_raw_parts(slots, super::SLOTS_LEN) };
+        assert_eq!(slots[0].slot, ffi::Py_mod_exec);
+        assert_eq!(slots[1].slot, ffi::Py_mod_multiple_interpreters);
+        assert_eq!(
+            slots[1].value,
+            ffi::Py_MOD_MULTIPLE_INTERPRETERS_NOT_SUPPORTED
+        );
+        assert_eq!(slots[2].slot, 0);
+    }
+
     #[test]
     fn module_def_new() {
         // To get coverage for ModuleDef::new() need to create a non-static ModuleDef, however init
@@ -0,0 +1,24 @@
+//! Runtime support for `#[pyo3::testing::test]`.
+
+use crate::types::{PyAnyMethods, PyModule, PyModuleMethods};
+use crate::{Py, Python};
+
+/// Initializes the interpreter if needed, then runs the test body with the GIL held.
+pub fn run<F, R>(body: F) -> R
+where
+    F: for<'py> FnOnce(Python<'py>) -> R,
+{
+    #[cfg(not(any(PyPy, GraalPy)))]
+    crate::prepare_freethreaded_python();
+    Python::with_gil(body)
+}
+
+/// Makes `module` importable by adding it to `sys.modules` under its name.
+pub fn add_module(py: Python<'_>, module: Py<PyModule>) {
+    let module = module.bind(py);
+    let name = module.name().expect("failed to get module name");
+    py.import_bound("sys")
+        .and_then(|sys| sys.getattr("modules"))
+        .and_then(|modules| modules.set_item(name, module))
+        .expect("failed to add module to sys.modules");
+}
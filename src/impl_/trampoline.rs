@@ -11,15 +11,23 @@ use std::{
 
 use crate::gil::GILGuard;
 use crate::{
-    callback::PyCallbackOutput, ffi, ffi_ptr_ext::FfiPtrExt, impl_::panic::PanicTrap,
-    methods::IPowModulo, panic::PanicException, types::PyModule, Py, PyResult, Python,
+    callback::PyCallbackOutput,
+    exceptions::PyTypeError,
+    ffi,
+    ffi_ptr_ext::FfiPtrExt,
+    impl_::panic::PanicTrap,
+    methods::IPowModulo,
+    panic::panic_to_pyerr,
+    types::{any::PyAnyMethods, typeobject::PyTypeMethods},
+    PyResult, Python,
 };
 
 #[inline]
-pub unsafe fn module_init(
-    f: for<'py> unsafe fn(Python<'py>) -> PyResult<Py<PyModule>>,
-) -> *mut ffi::PyObject {
-    trampoline(|py| f(py).map(|module| module.into_ptr()))
+pub unsafe fn module_exec(
+    module: *mut ffi::PyObject,
+    f: for<'py> unsafe fn(Python<'py>, *mut ffi::PyObject) -> PyResult<()>,
+) -> c_int {
+    trampoline(|py| f(py, module).map(|()| 0))
 }
 
 #[inline]
@@ -122,6 +130,33 @@ trampolines!(
     pub fn unaryfunc(slf: *mut ffi::PyObject) -> *mut ffi::PyObject;
 );
 
+/// `__init__` is wrapped like an ordinary method, so the result object it returns is checked
+/// to be `None`, as Python does for `__init__` methods defined in Python.
+#[inline]
+pub unsafe fn initproc(
+    slf: *mut ffi::PyObject,
+    args: *mut ffi::PyObject,
+    kwargs: *mut ffi::PyObject,
+    f: for<'py> unsafe fn(
+        Python<'py>,
+        *mut ffi::PyObject,
+        *mut ffi::PyObject,
+        *mut ffi::PyObject,
+    ) -> PyResult<*mut ffi::PyObject>,
+) -> c_int {
+    trampoline(|py| {
+        let result = f(py, slf, args, kwargs)?.assume_owned(py);
+        if result.is_none() {
+            Ok(0)
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "__init__() should return None, not '{}'",
+                result.get_type().qualname()?
+            )))
+        }
+    })
+}
+
 #[cfg(any(not(Py_LIMITED_API), Py_3_11))]
 trampoline! {
     pub fn getbufferproc(slf: *mut ffi::PyObject, buf: *mut ffi::Py_buffer, flags: c_int) -> c_int;
@@ -204,7 +239,7 @@ where
     let py_err = match panic_result {
         Ok(Ok(value)) => return value,
         Ok(Err(py_err)) => py_err,
-        Err(payload) => PanicException::from_panic_payload(payload),
+        Err(payload) => panic_to_pyerr(payload),
     };
     py_err.restore(py);
     R::ERR_VALUE
@@ -232,8 +267,8 @@ where
     let guard = GILGuard::assume();
     let py = guard.python();
 
-    if let Err(py_err) = panic::catch_unwind(move || body(py))
-        .unwrap_or_else(|payload| Err(PanicException::from_panic_payload(payload)))
+    if let Err(py_err) =
+        panic::catch_unwind(move || body(py)).unwrap_or_else(|payload| Err(panic_to_pyerr(payload)))
     {
         py_err.write_unraisable_bound(py, ctx.assume_borrowed_or_opt(py).as_deref());
     }
@@ -1,6 +1,6 @@
 use std::convert::Infallible;
 
-use crate::{ffi, IntoPy, PyObject, PyResult, Python};
+use crate::{ffi, IntoPy, PyErr, PyObject, PyResult, Python};
 
 /// Used to wrap values in `Option<T>` for default arguments.
 pub trait SomeWrap<T> {
@@ -49,6 +49,43 @@ where
     }
 }
 
+/// Used to pass the return value of `#[pyfunction]` and `#[pymethods]` to their
+/// `into_py_with` function, which takes the value without the `Result`.
+///
+/// Unlike `OkWrap`, this does not require `T: IntoPy<PyObject>`; instead `T` is
+/// known from the argument type of the `into_py_with` function.
+pub trait IntoPyWithArg<T> {
+    fn into_arg(self) -> PyResult<T>;
+}
+
+impl<T> IntoPyWithArg<T> for T {
+    #[inline]
+    fn into_arg(self) -> PyResult<T> {
+        Ok(self)
+    }
+}
+
+impl<T, E> IntoPyWithArg<T> for Result<T, E>
+where
+    E: Into<PyErr>,
+{
+    #[inline]
+    fn into_arg(self) -> PyResult<T> {
+        self.map_err(Into::into)
+    }
+}
+
+/// Used instead of `OkWrap::wrap` to convert the return value of `#[pyfunction]`
+/// and `#[pymethods]` with their `#[pyo3(into_py_with = "...")]` function.
+#[inline]
+pub fn convert_with<'py, T, R>(
+    py: Python<'py>,
+    result: impl IntoPyWithArg<T>,
+    into_py_with: impl FnOnce(T, Python<'py>) -> PyResult<R>,
+) -> PyResult<R> {
+    into_py_with(result.into_arg()?, py)
+}
+
 /// This is a follow-up function to `OkWrap::wrap` that converts the result into
 /// a `*mut ffi::PyObject` pointer.
 pub fn map_result_into_ptr<T: IntoPy<PyObject>>(
@@ -0,0 +1,148 @@
+//! Helpers for configuring Python's import system from Rust.
+//!
+//! Applications embedding Python often load plugins or scripts from their own directories rather
+//! than from an installed package. This module wraps the parts of `sys` and `importlib` needed
+//! for that:
+//!
+//! - [`sys_path`], [`prepend_sys_path`] and [`append_sys_path`] edit the module search path.
+//! - [`PyModule::import_from_path_bound`] imports a single file as a module.
+//! - [`add_import_hook`] lets Rust code provide modules which do not exist on the file system.
+//!
+//! # Example
+//!
+//! ```rust
+//! use pyo3::prelude::*;
+//! use pyo3::import;
+//!
+//! # fn main() -> PyResult<()> {
+//! Python::with_gil(|py| {
+//!     import::prepend_sys_path(py, "plugins")?;
+//!
+//!     import::add_import_hook(py, |py, name| {
+//!         if name != "host_app" {
+//!             return Ok(None);
+//!         }
+//!         let module = PyModule::new_bound(py, name)?;
+//!         module.add("VERSION", "1.0")?;
+//!         Ok(Some(module))
+//!     })?;
+//!
+//!     let version: String = py.import_bound("host_app")?.getattr("VERSION")?.extract()?;
+//!     assert_eq!(version, "1.0");
+//!     Ok(())
+//! })
+//! # }
+//! ```
+use crate::types::any::PyAnyMethods;
+use crate::types::dict::PyDictMethods;
+use crate::types::list::PyListMethods;
+use crate::types::string::PyStringMethods;
+use crate::types::tuple::PyTupleMethods;
+use crate::types::{PyCFunction, PyDict, PyList, PyModule, PyString};
+use crate::{intern, Bound, PyObject, PyResult, Python};
+use std::path::Path;
+
+/// Returns `sys.path`, the list of locations searched for modules.
+pub fn sys_path(py: Python<'_>) -> PyResult<Bound<'_, PyList>> {
+    py.import_bound(intern!(py, "sys"))?
+        .getattr(intern!(py, "path"))?
+        .downcast_into()
+        .map_err(Into::into)
+}
+
+/// Inserts `path` at the front of `sys.path`, so that it is searched before all other locations.
+///
+/// Does nothing if `path` is already the first entry.
+pub fn prepend_sys_path(py: Python<'_>, path: impl AsRef<Path>) -> PyResult<()> {
+    let sys_path = sys_path(py)?;
+    let path = path.as_ref();
+    if let Ok(first) = sys_path.get_item(0) {
+        if first.eq(path)? {
+            return Ok(());
+        }
+    }
+    sys_path.insert(0, path)
+}
+
+/// Adds `path` at the end of `sys.path`, unless it is already present.
+pub fn append_sys_path(py: Python<'_>, path: impl AsRef<Path>) -> PyResult<()> {
+    let sys_path = sys_path(py)?;
+    let path = path.as_ref();
+    if sys_path.contains(path)? {
+        return Ok(());
+    }
+    sys_path.append(path)
+}
+
+/// Installs a Rust function providing modules to the `import` statement.
+///
+/// `hook` is called with the full name of every module which the standard import machinery
+/// could not find, for instance `"package.submodule"`. It returns `Ok(None)` for names it does
+/// not provide, so that the import fails with `ModuleNotFoundError` as usual, or the module to be
+/// imported under this name. Errors returned by the hook are raised by the `import` statement.
+///
+/// Modules returned by the hook are stored in `sys.modules`, so the hook is only called once per
+/// module name.
+///
+/// Install hooks before the modules they provide are first imported; they stay installed until
+/// they are removed from `sys.meta_path`.
+pub fn add_import_hook<F>(py: Python<'_>, hook: F) -> PyResult<()>
+where
+    F: for<'py> Fn(Python<'py>, &str) -> PyResult<Option<Bound<'py, PyModule>>> + Send + 'static,
+{
+    let find_spec = PyCFunction::new_closure_bound(
+        py,
+        Some("find_spec"),
+        None,
+        move |args, _kwargs| -> PyResult<PyObject> {
+            let py = args.py();
+            let name = args.get_item(0)?;
+            let name = name.downcast::<PyString>()?.to_cow()?;
+            match hook(py, &name)? {
+                Some(module) => module_spec(py, &name, module),
+                None => Ok(py.None()),
+            }
+        },
+    )?;
+    let finder = namespace(py, &[("find_spec", find_spec)])?;
+    py.import_bound(intern!(py, "sys"))?
+        .getattr(intern!(py, "meta_path"))?
+        .call_method1(intern!(py, "append"), (finder,))?;
+    Ok(())
+}
+
+/// Creates a `ModuleSpec` whose loader returns the given module.
+fn module_spec(py: Python<'_>, name: &str, module: Bound<'_, PyModule>) -> PyResult<PyObject> {
+    let module = module.unbind();
+    let create_module =
+        PyCFunction::new_closure_bound(py, Some("create_module"), None, move |args, _kwargs| {
+            module.clone_ref(args.py())
+        })?;
+    let exec_module =
+        PyCFunction::new_closure_bound(py, Some("exec_module"), None, |_args, _kwargs| ())?;
+    let loader = namespace(
+        py,
+        &[
+            ("create_module", create_module),
+            ("exec_module", exec_module),
+        ],
+    )?;
+    Ok(py
+        .import_bound(intern!(py, "importlib.machinery"))?
+        .getattr(intern!(py, "ModuleSpec"))?
+        .call1((name, loader))?
+        .unbind())
+}
+
+fn namespace<'py>(
+    py: Python<'py>,
+    attributes: &[(&str, Bound<'py, PyCFunction>)],
+) -> PyResult<Bound<'py, crate::PyAny>> {
+    let kwargs = PyDict::new_bound(py);
+    for (name, value) in attributes {
+        kwargs.set_item(name, value)?;
+    }
+    py.import_bound(intern!(py, "types"))?
+        .getattr(intern!(py, "SimpleNamespace"))?
+        .call((), Some(&kwargs))
+}
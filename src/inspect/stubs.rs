@@ -0,0 +1,456 @@
+//! Generation of type stub (`.pyi`) files for modules created with PyO3.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::exceptions::{PyTypeError, PyValueError};
+use crate::inspect::types::{ModuleName, TypeInfo};
+use crate::types::{
+    PyAnyMethods, PyDictMethods, PyListMethods, PyModule, PyModuleMethods, PyNone, PyString,
+    PyStringMethods, PyTuple, PyType, PyTypeMethods,
+};
+use crate::{Bound, PyAny, PyResult, Python};
+
+/// Generates the contents of a type stub (`.pyi`) file for `module`.
+///
+/// The stub is built by inspecting the module at runtime, so it describes exactly what the
+/// module exposes to Python (its `__all__`, or else all of its public attributes):
+///
+/// - functions, with the signature and docstring generated by `#[pyfunction]`,
+/// - classes, with their bases, constructor, methods, properties and class attributes,
+/// - any other attribute, annotated with the type of its value.
+///
+/// Parameter and return types are not known yet, so they are left unannotated and type checkers
+/// treat them as `Any`. Properties are declared as plain attributes because it is not known
+/// whether they have a setter. Submodules are skipped: generate a separate stub for each of them.
+///
+/// ```rust
+/// use pyo3::inspect::stubs::module_stub;
+/// use pyo3::prelude::*;
+/// use pyo3::wrap_pymodule;
+///
+/// /// Doubles the given value.
+/// #[pyfunction]
+/// fn double(x: i64) -> i64 {
+///     x * 2
+/// }
+///
+/// #[pymodule]
+/// fn my_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+///     m.add_function(wrap_pyfunction!(double, m)?)
+/// }
+///
+/// # fn main() -> PyResult<()> {
+/// Python::with_gil(|py| {
+///     let module = wrap_pymodule!(my_module)(py);
+///     let stub = module_stub(module.bind(py))?;
+///     assert!(stub.contains("def double(x):\n    \"\"\"Doubles the given value.\"\"\"\n"));
+///     // std::fs::write("my_module.pyi", stub)?;
+///     Ok(())
+/// })
+/// # }
+/// ```
+pub fn module_stub(module: &Bound<'_, PyModule>) -> PyResult<String> {
+    let mut generator = StubGenerator::new(module)?;
+    let dict = module.dict();
+    for name in public_names(module)? {
+        if let Some(value) = dict.get_item(&name)? {
+            generator.write_item(&name, &value)?;
+        }
+    }
+    Ok(generator.finish())
+}
+
+/// The names listed in `__all__`, or else all attributes not starting with an underscore.
+fn public_names(module: &Bound<'_, PyModule>) -> PyResult<Vec<String>> {
+    let dict = module.dict();
+    if let Some(all) = dict.get_item(intern!(module.py(), "__all__"))? {
+        return all.iter()?.map(|name| name?.extract()).collect();
+    }
+    let names = dict
+        .keys()
+        .iter()
+        .map(|name| name.extract())
+        .collect::<PyResult<Vec<String>>>()?;
+    Ok(names
+        .into_iter()
+        .filter(|name| !name.starts_with('_'))
+        .collect())
+}
+
+/// How an attribute found in a class' `__dict__` is declared in the stub.
+enum ClassMember<'py> {
+    Attribute,
+    Method,
+    ClassMethod,
+    StaticMethod(Bound<'py, PyAny>),
+    ClassAttribute,
+}
+
+struct StubGenerator<'py> {
+    module: Bound<'py, PyModule>,
+    module_name: String,
+    inspect: Bound<'py, PyModule>,
+    builtins: Bound<'py, PyModule>,
+    getset_descriptor: Bound<'py, PyAny>,
+    member_descriptor: Bound<'py, PyAny>,
+    classmethod_descriptor: Bound<'py, PyAny>,
+    /// Names to import, by module.
+    imports: BTreeMap<String, BTreeSet<String>>,
+    /// The top-level statements of the stub, separated by blank lines.
+    items: Vec<String>,
+}
+
+impl<'py> StubGenerator<'py> {
+    fn new(module: &Bound<'py, PyModule>) -> PyResult<Self> {
+        let py = module.py();
+        let types = py.import_bound("types")?;
+        Ok(StubGenerator {
+            module: module.clone(),
+            module_name: module.name()?.to_string(),
+            inspect: py.import_bound("inspect")?,
+            builtins: py.import_bound("builtins")?,
+            getset_descriptor: types.getattr("GetSetDescriptorType")?,
+            member_descriptor: types.getattr("MemberDescriptorType")?,
+            classmethod_descriptor: types.getattr("ClassMethodDescriptorType")?,
+            imports: BTreeMap::new(),
+            items: Vec::new(),
+        })
+    }
+
+    fn py(&self) -> Python<'py> {
+        self.module.py()
+    }
+
+    fn write_item(&mut self, name: &str, value: &Bound<'py, PyAny>) -> PyResult<()> {
+        let mut out = String::new();
+        if let Ok(ty) = value.downcast::<PyType>() {
+            self.write_class(&mut out, name, ty)?;
+        } else if value.is_instance_of::<PyModule>() {
+            return Ok(());
+        } else if value.is_callable() {
+            self.write_function(&mut out, "", name, value, "(*args, **kwargs)")?;
+        } else {
+            let type_info = self.type_info(&value.get_type())?;
+            out.push_str(&format!("{}: {}\n", name, self.annotation(&type_info)));
+        }
+        self.items.push(out);
+        Ok(())
+    }
+
+    fn write_class(
+        &mut self,
+        out: &mut String,
+        name: &str,
+        ty: &Bound<'py, PyType>,
+    ) -> PyResult<()> {
+        let py = self.py();
+        let object = py.get_type_bound::<PyAny>();
+        let mut bases = Vec::new();
+        for base in ty
+            .getattr(intern!(py, "__bases__"))?
+            .downcast_into::<PyTuple>()?
+        {
+            let base = base.downcast_into::<PyType>()?;
+            if !base.is(&object) {
+                let type_info = self.type_info(&base)?;
+                bases.push(self.annotation(&type_info));
+            }
+        }
+        out.push_str("class ");
+        out.push_str(name);
+        if !bases.is_empty() {
+            out.push_str(&format!("({})", bases.join(", ")));
+        }
+        out.push_str(":\n");
+
+        let mut body = String::new();
+        if let Some(doc) = docstring(ty.as_any())? {
+            write_docstring(&mut body, "    ", &doc);
+        }
+        if ty
+            .getattr(intern!(py, "__text_signature__"))?
+            .is_instance_of::<PyString>()
+        {
+            if let Some(signature) = self.signature(ty.as_any())? {
+                let parameters = &signature[1..signature.len() - 1];
+                let separator = if parameters.is_empty() { "" } else { ", " };
+                body.push_str(&format!(
+                    "    def __new__(cls{}{}): ...\n",
+                    separator, parameters
+                ));
+            }
+        }
+        // Sorted because the order properties are added to the class in is not deterministic
+        let mut members = ty
+            .getattr(intern!(py, "__dict__"))?
+            .call_method0(intern!(py, "items"))?
+            .iter()?
+            .map(|item| item?.extract())
+            .collect::<PyResult<Vec<(String, Bound<'py, PyAny>)>>>()?;
+        members.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (member_name, value) in members {
+            if is_hidden_class_member(&member_name) {
+                continue;
+            }
+            match self.class_member(&value)? {
+                ClassMember::Attribute => {
+                    let any = self.annotation(&TypeInfo::Any);
+                    body.push_str(&format!("    {}: {}\n", member_name, any));
+                    if let Some(doc) = docstring(&value)? {
+                        write_docstring(&mut body, "    ", &doc);
+                    }
+                }
+                ClassMember::Method => self.write_function(
+                    &mut body,
+                    "    ",
+                    &member_name,
+                    &value,
+                    "(self, *args, **kwargs)",
+                )?,
+                ClassMember::ClassMethod => {
+                    body.push_str("    @classmethod\n");
+                    self.write_function(
+                        &mut body,
+                        "    ",
+                        &member_name,
+                        &value,
+                        "(cls, *args, **kwargs)",
+                    )?
+                }
+                ClassMember::StaticMethod(function) => {
+                    body.push_str("    @staticmethod\n");
+                    self.write_function(
+                        &mut body,
+                        "    ",
+                        &member_name,
+                        &function,
+                        "(*args, **kwargs)",
+                    )?
+                }
+                ClassMember::ClassAttribute => {
+                    let class_var = TypeInfo::Class {
+                        module: ModuleName::Module(Cow::from("typing")),
+                        name: Cow::from("ClassVar"),
+                        type_vars: vec![self.type_info(&value.get_type())?],
+                    };
+                    let annotation = self.annotation(&class_var);
+                    body.push_str(&format!("    {}: {}\n", member_name, annotation));
+                }
+            }
+        }
+
+        if body.is_empty() {
+            // `class Name:` becomes `class Name: ...`
+            out.pop();
+            out.push_str(" ...\n");
+        } else {
+            out.push_str(&body);
+        }
+        Ok(())
+    }
+
+    fn class_member(&self, value: &Bound<'py, PyAny>) -> PyResult<ClassMember<'py>> {
+        let py = self.py();
+        Ok(
+            if value.is_instance(&self.getset_descriptor)?
+                || value.is_instance(&self.member_descriptor)?
+            {
+                ClassMember::Attribute
+            } else if value.is_instance(&self.builtins.getattr(intern!(py, "staticmethod"))?)? {
+                ClassMember::StaticMethod(value.getattr(intern!(py, "__func__"))?)
+            } else if value.is_instance(&self.classmethod_descriptor)?
+                || value.is_instance(&self.builtins.getattr(intern!(py, "classmethod"))?)?
+            {
+                ClassMember::ClassMethod
+            } else if value.is_callable() {
+                ClassMember::Method
+            } else {
+                ClassMember::ClassAttribute
+            },
+        )
+    }
+
+    fn write_function(
+        &mut self,
+        out: &mut String,
+        indent: &str,
+        name: &str,
+        function: &Bound<'py, PyAny>,
+        fallback_signature: &str,
+    ) -> PyResult<()> {
+        let signature = self.signature(function)?;
+        let signature = signature.as_deref().unwrap_or(fallback_signature);
+        match docstring(function)? {
+            Some(doc) => {
+                out.push_str(&format!("{}def {}{}:\n", indent, name, signature));
+                write_docstring(out, &format!("{}    ", indent), &doc);
+            }
+            None => out.push_str(&format!("{}def {}{}: ...\n", indent, name, signature)),
+        }
+        Ok(())
+    }
+
+    /// The signature of `function` as given by `inspect.signature`, or `None` if it is unknown.
+    fn signature(&self, function: &Bound<'py, PyAny>) -> PyResult<Option<String>> {
+        let py = self.py();
+        match self
+            .inspect
+            .call_method1(intern!(py, "signature"), (function,))
+        {
+            Ok(signature) => Ok(Some(signature.str()?.to_cow()?.into_owned())),
+            Err(err)
+                if err.is_instance_of::<PyValueError>(py)
+                    || err.is_instance_of::<PyTypeError>(py) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Describes `ty` as it is referred to from this module.
+    fn type_info(&self, ty: &Bound<'py, PyType>) -> PyResult<TypeInfo> {
+        let py = self.py();
+        if ty.is(&py.get_type_bound::<PyNone>()) {
+            return Ok(TypeInfo::None);
+        }
+        let module: String = ty.getattr(intern!(py, "__module__"))?.extract()?;
+        let name = ty.qualname()?;
+        let module = if module == self.module_name {
+            ModuleName::CurrentModule
+        } else if module == "builtins" {
+            // `#[pyclass]`es without a `module` option claim to be builtins
+            if self.is_attribute(&self.builtins, &name, ty)? {
+                ModuleName::Builtin
+            } else if self.is_attribute(&self.module, &name, ty)? {
+                ModuleName::CurrentModule
+            } else {
+                return Ok(TypeInfo::Any);
+            }
+        } else {
+            ModuleName::Module(Cow::from(module))
+        };
+        Ok(TypeInfo::Class {
+            module,
+            name: Cow::from(name),
+            type_vars: vec![],
+        })
+    }
+
+    fn is_attribute(
+        &self,
+        module: &Bound<'py, PyModule>,
+        name: &str,
+        value: &Bound<'py, PyType>,
+    ) -> PyResult<bool> {
+        Ok(module
+            .dict()
+            .get_item(name)?
+            .map_or(false, |attribute| attribute.is(value)))
+    }
+
+    /// Formats `type_info` as an annotation, recording the imports it needs.
+    fn annotation(&mut self, type_info: &TypeInfo) -> String {
+        self.add_imports(type_info);
+        type_info.to_string()
+    }
+
+    fn add_imports(&mut self, type_info: &TypeInfo) {
+        match type_info {
+            TypeInfo::None => return,
+            TypeInfo::Any | TypeInfo::NoReturn => {}
+            TypeInfo::Callable(arguments, output) => {
+                for argument in arguments.iter().flatten() {
+                    self.add_imports(argument);
+                }
+                self.add_imports(output);
+            }
+            TypeInfo::Tuple(types) => {
+                for t in types.iter().flatten() {
+                    self.add_imports(t);
+                }
+            }
+            TypeInfo::UnsizedTypedTuple(t) => self.add_imports(t),
+            TypeInfo::Class { type_vars, .. } => {
+                for t in type_vars {
+                    self.add_imports(t);
+                }
+            }
+        }
+        match type_info.module_name() {
+            None | Some("builtins") => {}
+            Some(module) => {
+                let name = type_info.name();
+                // Nested classes are reached through their outermost class
+                let name = name.split('.').next().unwrap_or(&name).to_owned();
+                self.imports
+                    .entry(module.to_owned())
+                    .or_default()
+                    .insert(name);
+            }
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::new();
+        if let Ok(Some(doc)) = docstring(self.module.as_any()) {
+            write_docstring(&mut out, "", &doc);
+            out.push('\n');
+        }
+        if !self.imports.is_empty() {
+            for (module, names) in &self.imports {
+                let names: Vec<&str> = names.iter().map(String::as_str).collect();
+                out.push_str(&format!("from {} import {}\n", module, names.join(", ")));
+            }
+            out.push('\n');
+        }
+        out.push_str(&self.items.join("\n"));
+        out
+    }
+}
+
+/// Attributes of every class which are not part of its interface.
+fn is_hidden_class_member(name: &str) -> bool {
+    matches!(
+        name,
+        "__dict__" | "__doc__" | "__module__" | "__new__" | "__weakref__"
+    ) || name.starts_with("__pyo3")
+}
+
+/// The `__doc__` of `value`, if it is a non-empty string.
+fn docstring(value: &Bound<'_, PyAny>) -> PyResult<Option<String>> {
+    let doc = value.getattr(intern!(value.py(), "__doc__"))?;
+    if let Ok(doc) = doc.downcast::<PyString>() {
+        let doc = doc.to_cow()?;
+        if !doc.is_empty() {
+            return Ok(Some(doc.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+fn write_docstring(out: &mut String, indent: &str, doc: &str) {
+    let mut doc = doc.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    if doc.ends_with('"') {
+        doc.insert(doc.len() - 1, '\\');
+    }
+    out.push_str(indent);
+    out.push_str("\"\"\"");
+    let mut lines = doc.lines();
+    out.push_str(lines.next().unwrap_or_default());
+    let mut multiline = false;
+    for line in lines {
+        multiline = true;
+        out.push('\n');
+        if !line.is_empty() {
+            out.push_str(indent);
+            out.push_str(line);
+        }
+    }
+    if multiline {
+        out.push('\n');
+        out.push_str(indent);
+    }
+    out.push_str("\"\"\"\n");
+}
@@ -1,7 +1,7 @@
 use crate::err::{self, PyErr, PyResult};
 use crate::impl_::pycell::PyClassObject;
 use crate::internal_tricks::ptr_from_ref;
-use crate::pycell::{PyBorrowError, PyBorrowMutError};
+use crate::pycell::{PyBorrowError, PyBorrowMutError, PyRefThreadsafe};
 use crate::pyclass::boolean_struct::{False, True};
 #[cfg(feature = "gil-refs")]
 use crate::type_object::HasPyGilRef;
@@ -1261,6 +1261,40 @@ where
         unsafe { &*self.get_class_object().get_ptr() }
     }
 
+    /// Attempts to immutably borrow the value `T` without acquiring the GIL, returning an error
+    /// if the value is currently mutably borrowed.
+    ///
+    /// This lets Rust threads read the state of a non-[`frozen`][macro@crate::pyclass] class
+    /// without re-acquiring the GIL. The borrow flag is atomic, so the borrow is tracked like one
+    /// made with the GIL held: while the returned [`PyRefThreadsafe`] exists, attempts to mutably
+    /// borrow the value fail. This method never blocks; it fails immediately instead if Python is
+    /// mutating the value. For frozen classes, [`get`][Self::get] needs no borrow at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pyo3::prelude::*;
+    ///
+    /// #[pyclass]
+    /// struct Config {
+    ///     name: String,
+    /// }
+    ///
+    /// let config = Python::with_gil(|py| Py::new(py, Config { name: "demo".into() })).unwrap();
+    ///
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| assert_eq!(config.try_borrow_threadsafe().unwrap().name, "demo"));
+    /// });
+    /// # Python::with_gil(move |_py| drop(config));
+    /// ```
+    #[inline]
+    pub fn try_borrow_threadsafe(&self) -> Result<PyRefThreadsafe<'_, T>, PyBorrowError>
+    where
+        T: Sync,
+    {
+        PyRefThreadsafe::try_borrow(self)
+    }
+
     /// Get a view on the underlying `PyClass` contents.
     #[inline]
     pub(crate) fn get_class_object(&self) -> &PyClassObject<T> {
@@ -2360,6 +2394,32 @@ a = A()
             })
         }
 
+        #[test]
+        fn py_try_borrow_threadsafe() {
+            let instance = Python::with_gil(|py| Py::new(py, SomeClass(7)).unwrap());
+
+            // Borrows from a thread without the GIL are tracked like those made with the GIL held
+            let borrow = std::thread::scope(|s| {
+                s.spawn(|| instance.try_borrow_threadsafe().unwrap().0)
+                    .join()
+                    .unwrap()
+            });
+            assert_eq!(borrow, 7);
+
+            Python::with_gil(|py| {
+                let shared = instance.try_borrow_threadsafe().unwrap();
+                assert!(instance.try_borrow_mut(py).is_err());
+                assert_eq!(instance.borrow(py).0, 7);
+                drop(shared);
+
+                let mutable = instance.borrow_mut(py);
+                assert!(instance.try_borrow_threadsafe().is_err());
+                drop(mutable);
+                assert!(instance.try_borrow_threadsafe().is_ok());
+            });
+            Python::with_gil(move |_py| drop(instance));
+        }
+
         #[crate::pyclass(frozen, crate = "crate")]
         struct FrozenClass(i32);
 
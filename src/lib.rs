@@ -88,12 +88,14 @@
 //! [PEP 384] to be forward-compatible with future Python versions.
 //! - `auto-initialize`: Changes [`Python::with_gil`] to automatically initialize the Python
 //! interpreter if needed.
+//! - `build-info`: Adds a `__pyo3_build_info__` dict describing the Python configuration the
+//! extension was compiled for to every [`#[pymodule]`](macro@crate::pymodule).
 //! - `extension-module`: This will tell the linker to keep the Python symbols unresolved, so that
 //! your module can also be used with statically linked Python interpreters. Use this feature when
 //! building an extension module.
 //! - `multiple-pymethods`: Enables the use of multiple [`#[pymethods]`](macro@crate::pymethods)
 //! blocks per [`#[pyclass]`](macro@crate::pyclass). This adds a dependency on the [inventory]
-//! crate, which is not supported on all platforms.
+//! crate.
 //!
 //! The following features enable interactions with other crates in the Rust ecosystem:
 //! - [`anyhow`]: Enables a conversion from [anyhow]’s [`Error`][anyhow_error] type to [`PyErr`].
@@ -104,6 +106,8 @@
 //! - [`hashbrown`]: Enables conversions between Python objects and [hashbrown]'s [`HashMap`] and
 //! [`HashSet`] types.
 //! - [`indexmap`][indexmap_feature]: Enables conversions between Python dictionary and [indexmap]'s [`IndexMap`].
+//! - [`log`][log_feature]: Enables [`logging::Logger`], which forwards [log]'s records to Python's
+//! `logging` module.
 //! - [`num-bigint`]: Enables conversions between Python objects and [num-bigint]'s [`BigInt`] and
 //! [`BigUint`] types.
 //! - [`num-complex`]: Enables conversions between Python objects and [num-complex]'s [`Complex`]
@@ -112,8 +116,10 @@
 //! - [`rust_decimal`]: Enables conversions between Python's decimal.Decimal and [rust_decimal]'s
 //! [`Decimal`] type.
 //! - [`serde`]: Allows implementing [serde]'s [`Serialize`] and [`Deserialize`] traits for
-//! [`Py`]`<T>` for all `T` that implement [`Serialize`] and [`Deserialize`].
+//! [`Py`]`<T>` for all `T` that implement [`Serialize`] and [`Deserialize`], and converts such
+//! types to and from builtin Python objects.
 //! - [`smallvec`][smallvec]: Enables conversions between Python list and [smallvec]'s [`SmallVec`].
+//! - [`uuid`][uuid_feature]: Enables conversions between Python's uuid.UUID and [uuid]'s [`Uuid`].
 //!
 //! ## Unstable features
 //!
@@ -285,6 +291,8 @@
 //! [`Report`]: https://docs.rs/eyre/latest/eyre/struct.Report.html
 //! [`eyre`]: ./eyre/index.html "Documentation about the `eyre` feature."
 //! [`hashbrown`]: ./hashbrown/index.html "Documentation about the `hashbrown` feature."
+//! [log]: https://docs.rs/log/ "A lightweight logging facade for Rust."
+//! [log_feature]: ./logging/index.html "Documentation about the `log` feature."
 //! [indexmap_feature]: ./indexmap/index.html "Documentation about the `indexmap` feature."
 //! [`maturin`]: https://github.com/PyO3/maturin "Build and publish crates with pyo3, rust-cpython and cffi bindings as well as rust binaries as python packages"
 //! [`num-bigint`]: ./num_bigint/index.html "Documentation about the `num-bigint` feature."
@@ -301,6 +309,9 @@
 //! [global interpreter lock]: https://docs.python.org/3/glossary.html#term-global-interpreter-lock
 //! [hashbrown]: https://docs.rs/hashbrown
 //! [smallvec]: https://docs.rs/smallvec
+//! [uuid]: https://docs.rs/uuid
+//! [uuid_feature]: ./uuid/index.html "Documentation about the `uuid` feature."
+//! [`Uuid`]: https://docs.rs/uuid/latest/uuid/struct.Uuid.html
 //! [indexmap]: https://docs.rs/indexmap
 //! [manual_builds]: https://pyo3.rs/latest/building-and-distribution.html#manual-builds "Manual builds - Building and Distribution - PyO3 user guide"
 //! [num-bigint]: https://docs.rs/num-bigint
@@ -328,6 +339,8 @@ pub use crate::err::{DowncastError, DowncastIntoError, PyErr, PyErrArguments, Py
 pub use crate::gil::GILPool;
 #[cfg(not(any(PyPy, GraalPy)))]
 pub use crate::gil::{prepare_freethreaded_python, with_embedded_python_interpreter};
+#[cfg(all(Py_3_8, not(any(PyPy, GraalPy, Py_LIMITED_API))))]
+pub use crate::gil::{InitConfig, InitError};
 #[cfg(feature = "gil-refs")]
 pub use crate::instance::PyNativeType;
 pub use crate::instance::{Borrowed, Bound, Py, PyObject};
@@ -438,13 +451,21 @@ mod tests;
 #[macro_use]
 mod internal_tricks;
 
+pub mod argument;
+
+#[cfg(feature = "experimental-async")]
+pub mod asyncio;
 pub mod buffer;
 #[doc(hidden)]
 pub mod callback;
+pub mod context_manager;
 pub mod conversion;
 mod conversions;
 #[cfg(feature = "experimental-async")]
 pub mod coroutine;
+#[cfg(feature = "pool-diagnostics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pool-diagnostics")))]
+pub mod diagnostics;
 #[macro_use]
 #[doc(hidden)]
 #[cfg(feature = "gil-refs")]
@@ -455,27 +476,35 @@ pub mod ffi;
 mod gil;
 #[doc(hidden)]
 pub mod impl_;
+pub mod import;
 mod instance;
+#[cfg(feature = "log")]
+#[cfg_attr(docsrs, doc(cfg(feature = "log")))]
+pub mod logging;
 pub mod marker;
 pub mod marshal;
 #[macro_use]
 pub mod sync;
 pub mod panic;
 pub mod prelude;
+#[cfg(all(not(Py_LIMITED_API), not(PyPy), not(GraalPy)))]
+pub mod profile;
 pub mod pybacked;
 pub mod pycell;
 pub mod pyclass;
 pub mod pyclass_init;
+pub mod testing;
 
 pub mod type_object;
 pub mod types;
+pub mod union;
 mod version;
 
 #[allow(unused_imports)] // with no features enabled this module has no public exports
 pub use crate::conversions::*;
 
 #[cfg(feature = "macros")]
-pub use pyo3_macros::{pyfunction, pymethods, pymodule, FromPyObject};
+pub use pyo3_macros::{pyfunction, pymethods, pymodule, FromPyObject, IntoPyObject};
 
 /// A proc macro used to expose Rust structs and fieldless enums as Python objects.
 ///
@@ -552,6 +581,7 @@ pub mod doc_test {
         "guide/src/python-from-rust/function-calls.md" => guide_pfr_function_calls_md,
         "guide/src/python-typing-hints.md" => guide_python_typing_hints_md,
         "guide/src/rust-from-python.md" => guide_rust_from_python_md,
+        "guide/src/testing.md" => guide_testing_md,
         "guide/src/trait-bounds.md" => guide_trait_bounds_md,
         "guide/src/types.md" => guide_types_md,
     }
@@ -0,0 +1,190 @@
+//! Forwarding of [log](https://docs.rs/log) records to Python's `logging` module.
+//!
+//! Extension modules often log through the `log` crate. Once [`Logger`] is installed as the
+//! global logger, these records are passed to the Python logger named after their target, with
+//! `::` replaced by `.`, so that `my_crate::parser` logs to `logging.getLogger("my_crate.parser")`.
+//! They are then filtered, formatted and emitted according to the logging configuration of the
+//! Python application, like the records of any Python library.
+//!
+//! Levels are mapped to the standard Python levels; `Trace` has no Python equivalent and is
+//! mapped to level 5, below `logging.DEBUG`.
+//!
+//! # Setup
+//!
+//! To use this feature, add this to your **`Cargo.toml`**:
+//!
+//! ```toml
+//! [dependencies]
+//! log = "0.4"
+#![doc = concat!("pyo3 = { version = \"", env!("CARGO_PKG_VERSION"),  "\", features = [\"log\"] }")]
+//! ```
+//!
+//! # Example
+//!
+//! ```rust
+//! use pyo3::prelude::*;
+//!
+//! #[pyfunction]
+//! fn parse(input: &str) -> usize {
+//!     log::info!("parsing {} bytes", input.len());
+//!     input.len()
+//! }
+//!
+//! #[pymodule]
+//! fn my_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     // fails if another logger was installed already, which is fine to ignore
+//!     let _ = pyo3::logging::init();
+//!     m.add_function(wrap_pyfunction!(parse, m)?)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Records are only forwarded while the Python interpreter is initialized; logging from Rust
+//! threads acquires the GIL for every record that passes the filter, so fine-grained logging in
+//! hot loops should be limited with [`Logger::filter`].
+use crate::sync::GILOnceCell;
+use crate::types::any::PyAnyMethods;
+use crate::{intern, Bound, Py, PyAny, PyResult, Python};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+/// Python logging level used for `log::Level::Trace`, which has no standard equivalent.
+const TRACE: u8 = 5;
+
+/// A [`log::Log`] implementation forwarding records to Python's `logging` module.
+///
+/// See the [module-level documentation](self) for how records are translated.
+#[derive(Debug)]
+pub struct Logger {
+    filter: LevelFilter,
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Logger {
+    /// Creates a logger which forwards records of all levels, leaving the filtering to Python.
+    pub fn new() -> Self {
+        Logger {
+            filter: LevelFilter::Trace,
+        }
+    }
+
+    /// Only forwards records up to `filter`, discarding more detailed records already in Rust.
+    pub fn filter(mut self, filter: LevelFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Installs this logger as the global logger of the `log` crate.
+    ///
+    /// Fails if a global logger was already installed.
+    pub fn install(self) -> Result<(), SetLoggerError> {
+        let filter = self.filter;
+        log::set_logger(Box::leak(Box::new(self)))?;
+        log::set_max_level(filter);
+        Ok(())
+    }
+
+    fn forward(&self, py: Python<'_>, record: &Record<'_>) -> PyResult<()> {
+        let name = record.target().replace("::", ".");
+        let level = python_level(record.level());
+        let logger = get_logger(py)?.call1((name.as_str(),))?;
+        if !logger
+            .call_method1(intern!(py, "isEnabledFor"), (level,))?
+            .extract::<bool>()?
+        {
+            return Ok(());
+        }
+        let py_record = logger.call_method1(
+            intern!(py, "makeRecord"),
+            (
+                name,
+                level,
+                record.file().unwrap_or("<unknown>"),
+                record.line().unwrap_or(0),
+                record.args().to_string(),
+                (),
+                py.None(),
+            ),
+        )?;
+        logger.call_method1(intern!(py, "handle"), (py_record,))?;
+        Ok(())
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.filter
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) || !is_python_initialized() {
+            return;
+        }
+        Python::with_gil(|py| {
+            if let Err(err) = self.forward(py, record) {
+                err.write_unraisable_bound(py, None);
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs a [`Logger`] forwarding records of all levels as the global logger.
+///
+/// Fails if a global logger was already installed.
+pub fn init() -> Result<(), SetLoggerError> {
+    Logger::new().install()
+}
+
+fn python_level(level: Level) -> u8 {
+    match level {
+        Level::Error => 40,
+        Level::Warn => 30,
+        Level::Info => 20,
+        Level::Debug => 10,
+        Level::Trace => TRACE,
+    }
+}
+
+fn get_logger(py: Python<'_>) -> PyResult<&Bound<'_, PyAny>> {
+    static GET_LOGGER: GILOnceCell<Py<PyAny>> = GILOnceCell::new();
+    GET_LOGGER
+        .get_or_try_init(py, || -> PyResult<_> {
+            Ok(py
+                .import_bound(intern!(py, "logging"))?
+                .getattr(intern!(py, "getLogger"))?
+                .unbind())
+        })
+        .map(|get_logger| get_logger.bind(py))
+}
+
+fn is_python_initialized() -> bool {
+    unsafe { crate::ffi::Py_IsInitialized() != 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_level() {
+        Python::with_gil(|py| {
+            let logging = py.import_bound("logging").unwrap();
+            for (level, name) in [
+                (Level::Error, "ERROR"),
+                (Level::Warn, "WARNING"),
+                (Level::Info, "INFO"),
+                (Level::Debug, "DEBUG"),
+            ] {
+                let expected: u8 = logging.getattr(name).unwrap().extract().unwrap();
+                assert_eq!(python_level(level), expected);
+            }
+            assert!(python_level(Level::Trace) < python_level(Level::Debug));
+        });
+    }
+}
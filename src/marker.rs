@@ -118,7 +118,7 @@
 //! [`Py`]: crate::Py
 use crate::err::{self, PyErr, PyResult};
 use crate::ffi_ptr_ext::FfiPtrExt;
-use crate::gil::{GILGuard, SuspendGIL};
+use crate::gil::{gil_is_acquired, GILGuard, RestoreGIL, SuspendGIL};
 use crate::impl_::not_send::NotSend;
 use crate::py_result_ext::PyResultExt;
 use crate::types::any::PyAnyMethods;
@@ -133,6 +133,10 @@ use crate::{gil::GILPool, FromPyPointer, PyNativeType};
 use std::ffi::{CStr, CString};
 use std::marker::PhantomData;
 use std::os::raw::c_int;
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
 
 /// Types that are safe to access while the GIL is not held.
 ///
@@ -449,6 +453,82 @@ impl Python<'_> {
     }
 }
 
+/// Proof that the current thread has released the GIL inside [`Python::allow_threads_scoped`].
+///
+/// The token allows the GIL to be reacquired temporarily with [`GILReleased::with_gil`]. It is
+/// neither [`Send`] nor [`Sync`], because the saved thread state belongs to the thread which
+/// released the GIL.
+pub struct GILReleased<'a> {
+    suspended: &'a SuspendGIL,
+    last_signal_check: Cell<Option<Instant>>,
+}
+
+/// Minimum time between two checks for signals by [`GILReleased::check_signals`].
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+impl GILReleased<'_> {
+    /// Temporarily reacquires the GIL for the duration of `f`.
+    ///
+    /// If the GIL is already held by the current thread (for example because of a nested
+    /// [`Python::with_gil`]), `f` is run directly with the existing lock.
+    pub fn with_gil<F, R>(&self, f: F) -> R
+    where
+        F: for<'py> FnOnce(Python<'py>) -> R,
+    {
+        if gil_is_acquired() {
+            return Python::with_gil(f);
+        }
+
+        // SAFETY: the token is tied to the thread which saved its thread state, and that thread
+        // does not hold the GIL.
+        let _guard = unsafe { RestoreGIL::new(self.suspended) };
+        f(unsafe { Python::assume_gil_acquired() })
+    }
+
+    /// Checks for pending signals like [`Python::check_signals`], so that long-running Rust code
+    /// with the GIL released can be interrupted with Ctrl-C.
+    ///
+    /// To keep this cheap enough to be called in every iteration of a loop, the GIL is only
+    /// reacquired to check for signals if at least 10 milliseconds have passed since the previous
+    /// check; otherwise this returns `Ok(())` immediately.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    ///
+    /// #[pyfunction]
+    /// fn count_primes(py: Python<'_>, limit: u64) -> PyResult<u64> {
+    ///     py.allow_threads_scoped(|released| {
+    ///         let mut count = 0;
+    ///         for n in 2..limit {
+    ///             // stops with `KeyboardInterrupt` when the user presses Ctrl-C
+    ///             released.check_signals()?;
+    ///             if (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0) {
+    ///                 count += 1;
+    ///             }
+    ///         }
+    ///         Ok(count)
+    ///     })
+    /// }
+    /// #
+    /// # Python::with_gil(|py| {
+    /// #     let count_primes = wrap_pyfunction_bound!(count_primes, py).unwrap();
+    /// #     assert_eq!(count_primes.call1((20,)).unwrap().extract::<u64>().unwrap(), 8);
+    /// # });
+    /// ```
+    pub fn check_signals(&self) -> PyResult<()> {
+        let now = Instant::now();
+        if let Some(last) = self.last_signal_check.get() {
+            if now.duration_since(last) < SIGNAL_CHECK_INTERVAL {
+                return Ok(());
+            }
+        }
+        self.last_signal_check.set(Some(now));
+        self.with_gil(|py| py.check_signals())
+    }
+}
+
 impl<'py> Python<'py> {
     /// Temporarily releases the GIL, thus allowing other Python threads to run. The GIL will be
     /// reacquired when `F`'s scope ends.
@@ -524,6 +604,69 @@ impl<'py> Python<'py> {
         f()
     }
 
+    /// Like [`Python::allow_threads`], but additionally passes a [`GILReleased`] token to the
+    /// closure which can be used to temporarily reacquire the GIL.
+    ///
+    /// Reacquiring the GIL through the token restores the thread state which was saved when the
+    /// GIL was released, which is cheaper than a nested [`Python::with_gil`]. The token cannot
+    /// leave the closure and cannot be sent to other threads.
+    ///
+    /// # Example: Reporting progress to Python while running a computation in Rust-only code
+    ///
+    /// ```
+    /// use pyo3::prelude::*;
+    /// use pyo3::types::PyList;
+    ///
+    /// #[pyfunction]
+    /// fn sum_chunks(py: Python<'_>, numbers: Vec<u64>, progress: Py<PyList>) -> PyResult<u64> {
+    ///     py.allow_threads_scoped(move |released| {
+    ///         let mut total = 0;
+    ///         for chunk in numbers.chunks(2) {
+    ///             total += chunk.iter().sum::<u64>();
+    ///             released.with_gil(|py| progress.bind(py).append(total))?;
+    ///         }
+    ///         Ok(total)
+    ///     })
+    /// }
+    /// #
+    /// # fn main() -> PyResult<()> {
+    /// #     Python::with_gil(|py| -> PyResult<()> {
+    /// #         let fun = pyo3::wrap_pyfunction_bound!(sum_chunks, py)?;
+    /// #         let progress = PyList::empty_bound(py);
+    /// #         let res = fun.call1((vec![1_u64, 2, 3], &progress))?;
+    /// #         assert_eq!(res.extract::<u64>()?, 6);
+    /// #         assert_eq!(progress.extract::<Vec<u64>>()?, vec![3, 6]);
+    /// #         Ok(())
+    /// #     })
+    /// # }
+    /// ```
+    ///
+    /// # Example: The token cannot be sent to another thread
+    ///
+    /// ```compile_fail
+    /// use pyo3::prelude::*;
+    ///
+    /// Python::with_gil(|py| {
+    ///     py.allow_threads_scoped(|released| {
+    ///         std::thread::scope(|s| {
+    ///             s.spawn(|| released.with_gil(|_py| ())); // This causes a compile error.
+    ///         });
+    ///     });
+    /// });
+    /// ```
+    pub fn allow_threads_scoped<T, F>(self, f: F) -> T
+    where
+        F: Ungil + for<'a> FnOnce(GILReleased<'a>) -> T,
+        T: Ungil,
+    {
+        // As in `allow_threads`, the guard reacquires the GIL even if `f` panics.
+        let guard = unsafe { SuspendGIL::new() };
+        f(GILReleased {
+            suspended: &guard,
+            last_signal_check: Cell::new(None),
+        })
+    }
+
     /// Deprecated version of [`Python::eval_bound`]
     #[cfg(feature = "gil-refs")]
     #[deprecated(
@@ -652,48 +795,62 @@ impl<'py> Python<'py> {
     ) -> PyResult<Bound<'py, PyAny>> {
         let code = CString::new(code)?;
         unsafe {
-            let mptr = ffi::PyImport_AddModule("__main__\0".as_ptr().cast());
-            if mptr.is_null() {
-                return Err(PyErr::fetch(self));
-            }
+            let code_obj = ffi::Py_CompileString(code.as_ptr(), "<string>\0".as_ptr() as _, start)
+                .assume_owned_or_err(self)?;
+            self.run_code_object(&code_obj, globals, locals)
+        }
+    }
 
-            let globals = globals
-                .map(|dict| dict.as_ptr())
-                .unwrap_or_else(|| ffi::PyModule_GetDict(mptr));
-            let locals = locals.map(|dict| dict.as_ptr()).unwrap_or(globals);
-
-            // If `globals` don't provide `__builtins__`, most of the code will fail if Python
-            // version is <3.10. That's probably not what user intended, so insert `__builtins__`
-            // for them.
-            //
-            // See also:
-            // - https://github.com/python/cpython/pull/24564 (the same fix in CPython 3.10)
-            // - https://github.com/PyO3/pyo3/issues/3370
-            let builtins_s = crate::intern!(self, "__builtins__").as_ptr();
-            let has_builtins = ffi::PyDict_Contains(globals, builtins_s);
-            if has_builtins == -1 {
-                return Err(PyErr::fetch(self));
-            }
-            if has_builtins == 0 {
-                // Inherit current builtins.
-                let builtins = ffi::PyEval_GetBuiltins();
-
-                // `PyDict_SetItem` doesn't take ownership of `builtins`, but `PyEval_GetBuiltins`
-                // seems to return a borrowed reference, so no leak here.
-                if ffi::PyDict_SetItem(globals, builtins_s, builtins) == -1 {
-                    return Err(PyErr::fetch(self));
-                }
-            }
+    /// Evaluates a compiled code object in the given context.
+    ///
+    /// If `globals` is `None`, it defaults to Python module `__main__`.
+    /// If `locals` is `None`, it defaults to the value of `globals`.
+    ///
+    /// # Safety
+    ///
+    /// `code_obj` must be a Python code object.
+    pub(crate) unsafe fn run_code_object(
+        self,
+        code_obj: &Bound<'py, PyAny>,
+        globals: Option<&Bound<'py, PyDict>>,
+        locals: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mptr = ffi::PyImport_AddModule("__main__\0".as_ptr().cast());
+        if mptr.is_null() {
+            return Err(PyErr::fetch(self));
+        }
 
-            let code_obj = ffi::Py_CompileString(code.as_ptr(), "<string>\0".as_ptr() as _, start);
-            if code_obj.is_null() {
+        let globals = globals
+            .map(|dict| dict.as_ptr())
+            .unwrap_or_else(|| ffi::PyModule_GetDict(mptr));
+        let locals = locals.map(|dict| dict.as_ptr()).unwrap_or(globals);
+
+        // If `globals` don't provide `__builtins__`, most of the code will fail if Python
+        // version is <3.10. That's probably not what user intended, so insert `__builtins__`
+        // for them.
+        //
+        // See also:
+        // - https://github.com/python/cpython/pull/24564 (the same fix in CPython 3.10)
+        // - https://github.com/PyO3/pyo3/issues/3370
+        let builtins_s = crate::intern!(self, "__builtins__").as_ptr();
+        let has_builtins = ffi::PyDict_Contains(globals, builtins_s);
+        if has_builtins == -1 {
+            return Err(PyErr::fetch(self));
+        }
+        if has_builtins == 0 {
+            // Inherit current builtins.
+            let builtins = ffi::PyEval_GetBuiltins();
+
+            // `PyDict_SetItem` doesn't take ownership of `builtins`, but `PyEval_GetBuiltins`
+            // seems to return a borrowed reference, so no leak here.
+            if ffi::PyDict_SetItem(globals, builtins_s, builtins) == -1 {
                 return Err(PyErr::fetch(self));
             }
-            let res_ptr = ffi::PyEval_EvalCode(code_obj, globals, locals);
-            ffi::Py_DECREF(code_obj);
-
-            res_ptr.assume_owned_or_err(self).downcast_into_unchecked()
         }
+
+        ffi::PyEval_EvalCode(code_obj.as_ptr(), globals, locals)
+            .assume_owned_or_err(self)
+            .downcast_into_unchecked()
     }
 
     /// Gets the Python type object for type `T`.
@@ -968,6 +1125,9 @@ impl<'py> Python<'py> {
     /// `KeyboardInterrupt`. For this reason it is good practice to call this function regularly
     /// as part of long-running Rust functions so that users can cancel it.
     ///
+    /// Code running with the GIL released inside [`Python::allow_threads_scoped`] can use
+    /// [`GILReleased::check_signals`] instead.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -1260,6 +1420,68 @@ mod tests {
         });
     }
 
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))] // We are building wasm Python with pthreads disabled
+    fn test_allow_threads_scoped_reacquires_gil() {
+        use crate::types::PyListMethods;
+
+        Python::with_gil(|py| {
+            let list = PyList::empty_bound(py).unbind();
+            let b = std::sync::Arc::new(std::sync::Barrier::new(2));
+
+            let b2 = b.clone();
+            let handle = std::thread::spawn(move || Python::with_gil(|_| b2.wait()));
+
+            py.allow_threads_scoped(|released| {
+                // If allow_threads_scoped does not release the GIL, this will deadlock.
+                b.wait();
+                released.with_gil(|py| {
+                    list.bind(py).append(1).unwrap();
+                    // Nested reacquisition reuses the existing lock.
+                    released.with_gil(|py| list.bind(py).append(2).unwrap());
+                    Python::with_gil(|py| list.bind(py).append(3).unwrap());
+                });
+                assert!(!gil_is_acquired());
+            });
+            handle.join().unwrap();
+
+            assert_eq!(list.bind(py).extract::<Vec<i32>>().unwrap(), vec![1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn test_allow_threads_scoped_check_signals() {
+        Python::with_gil(|py| {
+            py.allow_threads_scoped(|released| {
+                for _ in 0..1000 {
+                    released.check_signals().unwrap();
+                }
+                std::thread::sleep(SIGNAL_CHECK_INTERVAL);
+                released.check_signals().unwrap();
+                assert!(!gil_is_acquired());
+            });
+        });
+    }
+
+    #[test]
+    fn test_allow_threads_scoped_panics_safely() {
+        Python::with_gil(|py| {
+            let result = std::panic::catch_unwind(|| unsafe {
+                let py = Python::assume_gil_acquired();
+                py.allow_threads_scoped(|released| {
+                    released.with_gil(|_| panic!("There was a panic!"));
+                });
+            });
+
+            // Check panic was caught
+            assert!(result.is_err());
+
+            // Both guards were dropped during unwinding, so this thread still owns the GIL here.
+            let list = PyList::new_bound(py, [1, 2, 3, 4]);
+            assert_eq!(list.extract::<Vec<i32>>().unwrap(), vec![1, 2, 3, 4]);
+        });
+    }
+
     #[cfg(not(pyo3_disable_reference_pool))]
     #[test]
     fn test_allow_threads_pass_stuff_in() {
@@ -1,7 +1,13 @@
 //! Helper to convert Rust panics to Python exceptions.
+//!
+//! When Rust code called from Python panics, PyO3 catches the panic before it reaches the FFI
+//! boundary and raises a [`PanicException`] instead. [`set_panic_handler`] replaces this
+//! conversion, for example to raise a different exception type or to report the panic, and
+//! [`install_backtrace_hook`] records the backtraces of panics for it.
 use crate::exceptions::PyBaseException;
 use crate::PyErr;
 use std::any::Any;
+use std::sync::{PoisonError, RwLock};
 
 pyo3_exception!(
     "
@@ -19,14 +25,161 @@ impl PanicException {
     /// Creates a new PanicException from a panic payload.
     ///
     /// Attempts to format the error in the same way panic does.
+    ///
+    /// This is the conversion used when no [panic handler](set_panic_handler) is installed.
     #[cold]
-    pub(crate) fn from_panic_payload(payload: Box<dyn Any + Send + 'static>) -> PyErr {
-        if let Some(string) = payload.downcast_ref::<String>() {
-            Self::new_err((string.clone(),))
-        } else if let Some(s) = payload.downcast_ref::<&str>() {
-            Self::new_err((s.to_string(),))
-        } else {
-            Self::new_err(("panic from Rust code",))
-        }
+    pub fn from_panic_payload(payload: Box<dyn Any + Send + 'static>) -> PyErr {
+        Self::new_err((panic_message(&*payload)
+            .unwrap_or("panic from Rust code")
+            .to_owned(),))
+    }
+}
+
+/// Converts a panic payload into the `PyErr` which is raised in its place.
+///
+/// The payload is the value passed to `std::panic::resume_unwind`, or returned by
+/// `std::panic::catch_unwind`; the message of a panic can be read with [`panic_message`]. The
+/// second argument is the backtrace of the panic, if it was recorded by the hook installed with
+/// [`install_backtrace_hook`].
+pub type PanicHandler = fn(Box<dyn Any + Send + 'static>, Option<String>) -> PyErr;
+
+static PANIC_HANDLER: RwLock<Option<PanicHandler>> = RwLock::new(None);
+
+/// Installs a handler which converts panics caught by PyO3 into Python exceptions, returning
+/// the previously installed handler.
+///
+/// The handler is called for every panic which would otherwise cross from Rust code into
+/// Python, in place of [`PanicException::from_panic_payload`]. Pass `None` to restore the
+/// default behaviour of raising a `PanicException`.
+///
+/// The handler runs once the panic has been caught, when the stack has already been unwound, so
+/// it only receives a backtrace if [`install_backtrace_hook`] was called. The handler may be
+/// called without the GIL held, and must not panic itself.
+///
+/// # Examples
+///
+/// ```rust
+/// use pyo3::exceptions::PyRuntimeError;
+/// use pyo3::panic::{panic_message, set_panic_handler};
+/// use pyo3::prelude::*;
+///
+/// #[pyfunction]
+/// fn fail() {
+///     panic!("something went wrong");
+/// }
+///
+/// set_panic_handler(Some(|payload, _backtrace| {
+///     let message = panic_message(&*payload).unwrap_or("unknown panic");
+///     PyRuntimeError::new_err(format!("Rust panic: {}", message))
+/// }));
+///
+/// Python::with_gil(|py| {
+///     let fail = wrap_pyfunction_bound!(fail, py).unwrap();
+///     let err = fail.call0().unwrap_err();
+///     assert!(err.is_instance_of::<PyRuntimeError>(py));
+///     assert_eq!(err.to_string(), "RuntimeError: Rust panic: something went wrong");
+/// });
+/// # set_panic_handler(None);
+/// ```
+pub fn set_panic_handler(handler: Option<PanicHandler>) -> Option<PanicHandler> {
+    let mut current = PANIC_HANDLER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+    std::mem::replace(&mut *current, handler)
+}
+
+#[cfg(std_backtrace)]
+thread_local! {
+    /// The backtrace of the last panic on this thread, recorded by the backtrace hook.
+    static PANIC_BACKTRACE: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Installs a panic hook which records a backtrace for every panic, so that it can be passed to
+/// the [panic handler](set_panic_handler).
+///
+/// The backtrace has to be captured while the panicking code is still on the stack, which is
+/// only possible from a [`std::panic::set_hook`] hook. The hook installed here records the
+/// backtrace and then calls the hook which was installed before, so the panic is still printed
+/// as usual. Capturing a backtrace is slow, so this is opt-in; calling it more than once has no
+/// further effect.
+///
+/// Requires Rust 1.65 or newer.
+///
+/// # Examples
+///
+/// ```rust
+/// use pyo3::exceptions::PyRuntimeError;
+/// use pyo3::panic::{install_backtrace_hook, panic_message, set_panic_handler};
+/// use pyo3::prelude::*;
+///
+/// #[pyfunction]
+/// fn fail() {
+///     panic!("something went wrong");
+/// }
+///
+/// install_backtrace_hook();
+/// set_panic_handler(Some(|payload, backtrace| {
+///     let message = panic_message(&*payload).unwrap_or("unknown panic");
+///     let backtrace = backtrace.unwrap_or_default();
+///     PyRuntimeError::new_err(format!("Rust panic: {}\n{}", message, backtrace))
+/// }));
+///
+/// Python::with_gil(|py| {
+///     let fail = wrap_pyfunction_bound!(fail, py).unwrap();
+///     let err = fail.call0().unwrap_err();
+///     assert!(err.to_string().starts_with("RuntimeError: Rust panic: something went wrong\n"));
+/// });
+/// # set_panic_handler(None);
+/// ```
+#[cfg(std_backtrace)]
+#[allow(clippy::incompatible_msrv)] // `std_backtrace` is only set on Rust 1.65 and later
+pub fn install_backtrace_hook() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+            // `try_with` because panics can happen while thread locals are being destroyed.
+            let _ = PANIC_BACKTRACE.try_with(|cell| *cell.borrow_mut() = Some(backtrace));
+            previous(info);
+        }));
+    });
+}
+
+/// Takes the backtrace recorded for the last panic on this thread, if any.
+fn take_panic_backtrace() -> Option<String> {
+    #[cfg(std_backtrace)]
+    {
+        PANIC_BACKTRACE
+            .try_with(|cell| cell.borrow_mut().take())
+            .ok()
+            .flatten()
+    }
+    #[cfg(not(std_backtrace))]
+    {
+        None
+    }
+}
+
+/// Returns the message of a panic, if its payload is a string.
+///
+/// This is the case for panics raised by the `panic!` macro and its relatives.
+pub fn panic_message(payload: &(dyn Any + Send)) -> Option<&str> {
+    if let Some(string) = payload.downcast_ref::<String>() {
+        Some(string)
+    } else {
+        payload.downcast_ref::<&str>().copied()
+    }
+}
+
+/// Converts a caught panic into a `PyErr`, using the installed panic handler if any.
+#[cold]
+pub(crate) fn panic_to_pyerr(payload: Box<dyn Any + Send + 'static>) -> PyErr {
+    // Taken even without a handler, so that it can't be mistaken for that of a later panic.
+    let backtrace = take_panic_backtrace();
+    let handler = *PANIC_HANDLER.read().unwrap_or_else(PoisonError::into_inner);
+    match handler {
+        Some(handler) => handler(payload, backtrace),
+        None => PanicException::from_panic_payload(payload),
     }
 }
@@ -25,7 +25,7 @@ pub use crate::types::{PyAny, PyModule};
 pub use crate::PyNativeType;
 
 #[cfg(feature = "macros")]
-pub use pyo3_macros::{pyclass, pyfunction, pymethods, pymodule, FromPyObject};
+pub use pyo3_macros::{pyclass, pyfunction, pymethods, pymodule, FromPyObject, IntoPyObject};
 
 #[cfg(feature = "macros")]
 pub use crate::{wrap_pyfunction, wrap_pyfunction_bound};
@@ -35,6 +35,8 @@ pub use crate::types::boolobject::PyBoolMethods;
 pub use crate::types::bytearray::PyByteArrayMethods;
 pub use crate::types::bytes::PyBytesMethods;
 pub use crate::types::capsule::PyCapsuleMethods;
+#[cfg(all(not(Py_LIMITED_API), not(PyPy), not(GraalPy)))]
+pub use crate::types::code::PyCodeMethods;
 pub use crate::types::complex::PyComplexMethods;
 pub use crate::types::dict::PyDictMethods;
 pub use crate::types::float::PyFloatMethods;
@@ -42,6 +44,7 @@ pub use crate::types::frozenset::PyFrozenSetMethods;
 pub use crate::types::list::PyListMethods;
 pub use crate::types::mapping::PyMappingMethods;
 pub use crate::types::module::PyModuleMethods;
+pub use crate::types::range::PyRangeMethods;
 pub use crate::types::sequence::PySequenceMethods;
 pub use crate::types::set::PySetMethods;
 pub use crate::types::slice::PySliceMethods;
@@ -0,0 +1,169 @@
+//! Rust callbacks as Python profile and trace functions.
+//!
+//! Profilers, coverage tools and debuggers observe Python execution through the hooks set with
+//! [`sys.setprofile`] and [`sys.settrace`]. Implementing these hooks in Rust through
+//! [`set_profile`] and [`set_trace`] avoids the overhead of calling back into a Python function
+//! for every event, as the interpreter calls the Rust callback directly.
+//!
+//! Like their Python counterparts, the hooks only apply to the current thread.
+//!
+//! # Example
+//!
+//! ```rust
+//! use pyo3::prelude::*;
+//! use pyo3::profile::{self, TraceEvent};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! static CALLS: AtomicUsize = AtomicUsize::new(0);
+//!
+//! # fn main() -> PyResult<()> {
+//! Python::with_gil(|py| {
+//!     profile::set_profile(py, |_frame, event| {
+//!         if let TraceEvent::Call = event {
+//!             CALLS.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!         Ok(())
+//!     })?;
+//!     py.run_bound("def f(): pass\nf()\nf()", None, None)?;
+//!     profile::clear_profile(py);
+//!
+//!     // the code object of the snippet and the two calls to `f`
+//!     assert_eq!(CALLS.load(Ordering::Relaxed), 3);
+//!     Ok(())
+//! })
+//! # }
+//! ```
+//!
+//! [`sys.setprofile`]: https://docs.python.org/3/library/sys.html#sys.setprofile
+//! [`sys.settrace`]: https://docs.python.org/3/library/sys.html#sys.settrace
+use crate::ffi_ptr_ext::FfiPtrExt;
+use crate::impl_::trampoline::trampoline;
+use crate::types::any::PyAnyMethods;
+use crate::types::{PyCapsule, PyFrame, PyTuple};
+use crate::{ffi, Bound, PyAny, PyResult, Python};
+use std::os::raw::c_int;
+
+/// An event reported to a profile or trace function.
+///
+/// See the documentation of [`sys.settrace`] for when Python reports each event.
+///
+/// [`sys.settrace`]: https://docs.python.org/3/library/sys.html#sys.settrace
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum TraceEvent<'a, 'py> {
+    /// A function is called, or a code block is entered.
+    Call,
+    /// An exception was raised, with the `(type, value, traceback)` tuple.
+    ///
+    /// Only reported to trace functions.
+    Exception(&'a Bound<'py, PyTuple>),
+    /// A new line of code is about to be executed.
+    ///
+    /// Only reported to trace functions.
+    Line,
+    /// A function or code block is about to return, with the returned value.
+    ///
+    /// If the function is left because of an exception, the value is `None` or, depending on the
+    /// Python version, Python's `None`.
+    Return(Option<&'a Bound<'py, PyAny>>),
+    /// A function implemented in C is about to be called.
+    ///
+    /// Only reported to profile functions.
+    CCall(&'a Bound<'py, PyAny>),
+    /// A function implemented in C raised an exception.
+    ///
+    /// Only reported to profile functions.
+    CException(&'a Bound<'py, PyAny>),
+    /// A function implemented in C returned.
+    ///
+    /// Only reported to profile functions.
+    CReturn(&'a Bound<'py, PyAny>),
+    /// A new opcode is about to be executed.
+    ///
+    /// Only reported to trace functions, when `frame.f_trace_opcodes` is set.
+    Opcode,
+}
+
+/// Installs `callback` as the profile function of the current thread, like `sys.setprofile`.
+///
+/// `callback` is called with the frame being executed for every [`TraceEvent`] reported to
+/// profile functions. If it returns an error, the error is raised in the Python code being
+/// executed. Depending on the Python version, the profile function is then removed.
+///
+/// Replaces any previously installed profile function.
+pub fn set_profile<F>(py: Python<'_>, callback: F) -> PyResult<()>
+where
+    F: for<'py> Fn(&Bound<'py, PyFrame>, TraceEvent<'_, 'py>) -> PyResult<()> + Send + 'static,
+{
+    let capsule = PyCapsule::new_bound(py, Box::new(callback), None)?;
+    unsafe { ffi::PyEval_SetProfile(Some(trace_func::<F>), capsule.as_ptr()) };
+    Ok(())
+}
+
+/// Removes the profile function of the current thread, like `sys.setprofile(None)`.
+pub fn clear_profile(_py: Python<'_>) {
+    unsafe { ffi::PyEval_SetProfile(None, std::ptr::null_mut()) };
+}
+
+/// Installs `callback` as the trace function of the current thread, like `sys.settrace`.
+///
+/// `callback` is called with the frame being executed for every [`TraceEvent`] reported to
+/// trace functions. If it returns an error, the error is raised in the Python code being
+/// executed. Depending on the Python version, the trace function is then removed.
+///
+/// Unlike a trace function set with `sys.settrace`, `callback` receives the events of every
+/// frame, without installing a local trace function through `frame.f_trace`.
+///
+/// Replaces any previously installed trace function.
+pub fn set_trace<F>(py: Python<'_>, callback: F) -> PyResult<()>
+where
+    F: for<'py> Fn(&Bound<'py, PyFrame>, TraceEvent<'_, 'py>) -> PyResult<()> + Send + 'static,
+{
+    let capsule = PyCapsule::new_bound(py, Box::new(callback), None)?;
+    unsafe { ffi::PyEval_SetTrace(Some(trace_func::<F>), capsule.as_ptr()) };
+    Ok(())
+}
+
+/// Removes the trace function of the current thread, like `sys.settrace(None)`.
+pub fn clear_trace(_py: Python<'_>) {
+    unsafe { ffi::PyEval_SetTrace(None, std::ptr::null_mut()) };
+}
+
+unsafe extern "C" fn trace_func<F>(
+    obj: *mut ffi::PyObject,
+    frame: *mut ffi::PyFrameObject,
+    what: c_int,
+    arg: *mut ffi::PyObject,
+) -> c_int
+where
+    F: for<'py> Fn(&Bound<'py, PyFrame>, TraceEvent<'_, 'py>) -> PyResult<()> + Send + 'static,
+{
+    trampoline(|py| {
+        // The callback may replace or remove the hook, which releases the interpreter's reference
+        // to the capsule, so keep the capsule alive until the callback has returned.
+        let capsule = obj.assume_borrowed(py).to_owned();
+        // SAFETY: `obj` is the capsule created by `set_profile` or `set_trace`, which holds the
+        // boxed callback.
+        let callback =
+            &*(ffi::PyCapsule_GetPointer(capsule.as_ptr(), std::ptr::null()) as *const Box<F>);
+        let frame = frame.cast::<ffi::PyObject>();
+        let frame = Bound::ref_from_ptr(py, &frame).downcast_unchecked::<PyFrame>();
+        let arg = arg.assume_borrowed_or_opt(py);
+        let event = match (what, arg.as_deref()) {
+            (ffi::PyTrace_CALL, _) => TraceEvent::Call,
+            (ffi::PyTrace_EXCEPTION, Some(arg)) => {
+                TraceEvent::Exception(arg.downcast_unchecked::<PyTuple>())
+            }
+            (ffi::PyTrace_LINE, _) => TraceEvent::Line,
+            (ffi::PyTrace_RETURN, arg) => TraceEvent::Return(arg),
+            (ffi::PyTrace_C_CALL, Some(arg)) => TraceEvent::CCall(arg),
+            (ffi::PyTrace_C_EXCEPTION, Some(arg)) => TraceEvent::CException(arg),
+            (ffi::PyTrace_C_RETURN, Some(arg)) => TraceEvent::CReturn(arg),
+            (ffi::PyTrace_OPCODE, _) => TraceEvent::Opcode,
+            // unknown events, which future Python versions might add
+            _ => return Ok(0),
+        };
+        callback(frame, event)?;
+        Ok(0)
+    })
+}
@@ -209,7 +209,7 @@ use crate::{
     types::PyAny,
     PyNativeType, PyResult, PyTypeCheck,
 };
-use crate::{ffi, Bound, IntoPy, PyErr, PyObject, Python};
+use crate::{ffi, Bound, IntoPy, Py, PyErr, PyObject, Python};
 use std::fmt;
 use std::mem::ManuallyDrop;
 use std::ops::{Deref, DerefMut};
@@ -849,6 +849,50 @@ impl<T: PyClass + fmt::Debug> fmt::Debug for PyRef<'_, T> {
     }
 }
 
+/// A shared borrow of a pyclass value which does not require the GIL, created by
+/// [`Py::try_borrow_threadsafe`].
+///
+/// While it exists, the value cannot be mutably borrowed, from Python or from Rust. Unlike
+/// [`PyRef`], it gives no access to the Python object or its base classes.
+pub struct PyRefThreadsafe<'a, T: PyClass + Sync> {
+    inner: &'a Py<T>,
+}
+
+impl<'a, T: PyClass + Sync> PyRefThreadsafe<'a, T> {
+    pub(crate) fn try_borrow(obj: &'a Py<T>) -> Result<Self, PyBorrowError> {
+        let cell = obj.get_class_object();
+        cell.check_threadsafe()?;
+        cell.borrow_checker()
+            .try_borrow()
+            .map(|_| Self { inner: obj })
+    }
+}
+
+impl<T: PyClass + Sync> Deref for PyRefThreadsafe<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.inner.get_class_object().get_ptr() }
+    }
+}
+
+impl<T: PyClass + Sync> Drop for PyRefThreadsafe<'_, T> {
+    fn drop(&mut self) {
+        // The borrow flag is atomic, so this is fine without the GIL.
+        self.inner
+            .get_class_object()
+            .borrow_checker()
+            .release_borrow()
+    }
+}
+
+impl<T: PyClass + Sync + fmt::Debug> fmt::Debug for PyRefThreadsafe<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
 /// A wrapper type for a mutably borrowed value from a [`Bound<'py, T>`].
 ///
 /// See the [module-level documentation](self) for more information.
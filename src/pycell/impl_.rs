@@ -1,9 +1,10 @@
 #![allow(missing_docs)]
 //! Crate-private implementation of PyClassObject
 
-use std::cell::{Cell, UnsafeCell};
+use std::cell::UnsafeCell;
 use std::marker::PhantomData;
 use std::mem::ManuallyDrop;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::impl_::pyclass::{
     PyClassBaseType, PyClassDict, PyClassImpl, PyClassThreadChecker, PyClassWeakRef,
@@ -50,22 +51,17 @@ impl<M: PyClassMutability> PyClassMutability for ExtendsMutableAncestor<M> {
     type MutableChild = ExtendsMutableAncestor<MutableClass>;
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-struct BorrowFlag(usize);
+// Atomic so that borrows made without the GIL, by `Py::try_borrow_threadsafe`, are tracked
+// correctly alongside those made with it.
+struct BorrowFlag;
 
 impl BorrowFlag {
-    pub(crate) const UNUSED: BorrowFlag = BorrowFlag(0);
-    const HAS_MUTABLE_BORROW: BorrowFlag = BorrowFlag(usize::MAX);
-    const fn increment(self) -> Self {
-        Self(self.0 + 1)
-    }
-    const fn decrement(self) -> Self {
-        Self(self.0 - 1)
-    }
+    const UNUSED: usize = 0;
+    const HAS_MUTABLE_BORROW: usize = usize::MAX;
 }
 
 pub struct EmptySlot(());
-pub struct BorrowChecker(Cell<BorrowFlag>);
+pub struct BorrowChecker(AtomicUsize);
 
 pub trait PyClassBorrowChecker {
     /// Initial value for self
@@ -119,22 +115,28 @@ impl PyClassBorrowChecker for EmptySlot {
 impl PyClassBorrowChecker for BorrowChecker {
     #[inline]
     fn new() -> Self {
-        Self(Cell::new(BorrowFlag::UNUSED))
+        Self(AtomicUsize::new(BorrowFlag::UNUSED))
     }
 
     fn try_borrow(&self) -> Result<(), PyBorrowError> {
-        let flag = self.0.get();
-        if flag != BorrowFlag::HAS_MUTABLE_BORROW {
-            self.0.set(flag.increment());
-            Ok(())
-        } else {
-            Err(PyBorrowError { _private: () })
+        let mut flag = self.0.load(Ordering::Relaxed);
+        loop {
+            if flag == BorrowFlag::HAS_MUTABLE_BORROW {
+                return Err(PyBorrowError { _private: () });
+            }
+            match self
+                .0
+                .compare_exchange_weak(flag, flag + 1, Ordering::Acquire, Ordering::Relaxed)
+            {
+                Ok(_) => return Ok(()),
+                Err(current) => flag = current,
+            }
         }
     }
 
     #[cfg(feature = "gil-refs")]
     fn try_borrow_unguarded(&self) -> Result<(), PyBorrowError> {
-        let flag = self.0.get();
+        let flag = self.0.load(Ordering::Acquire);
         if flag != BorrowFlag::HAS_MUTABLE_BORROW {
             Ok(())
         } else {
@@ -143,22 +145,23 @@ impl PyClassBorrowChecker for BorrowChecker {
     }
 
     fn release_borrow(&self) {
-        let flag = self.0.get();
-        self.0.set(flag.decrement())
+        self.0.fetch_sub(1, Ordering::Release);
     }
 
     fn try_borrow_mut(&self) -> Result<(), PyBorrowMutError> {
-        let flag = self.0.get();
-        if flag == BorrowFlag::UNUSED {
-            self.0.set(BorrowFlag::HAS_MUTABLE_BORROW);
-            Ok(())
-        } else {
-            Err(PyBorrowMutError { _private: () })
+        match self.0.compare_exchange(
+            BorrowFlag::UNUSED,
+            BorrowFlag::HAS_MUTABLE_BORROW,
+            Ordering::Acquire,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(PyBorrowMutError { _private: () }),
         }
     }
 
     fn release_borrow_mut(&self) {
-        self.0.set(BorrowFlag::UNUSED)
+        self.0.store(BorrowFlag::UNUSED, Ordering::Release)
     }
 }
 
@@ -270,6 +273,14 @@ impl<T: PyClassImpl> PyClassObject<T> {
         self.contents.value.get()
     }
 
+    pub(crate) fn dict(&self) -> &T::Dict {
+        &self.contents.dict
+    }
+
+    pub(crate) fn dict_mut(&mut self) -> &mut T::Dict {
+        &mut self.contents.dict
+    }
+
     /// Gets the offset of the dictionary from the start of the struct in bytes.
     pub(crate) fn dict_offset() -> ffi::Py_ssize_t {
         use memoffset::offset_of;
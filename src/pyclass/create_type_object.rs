@@ -4,15 +4,15 @@ use crate::{
     impl_::{
         pycell::PyClassObject,
         pyclass::{
-            assign_sequence_item_from_mapping, get_sequence_item_from_mapping, tp_dealloc,
-            tp_dealloc_with_gc, PyClassItemsIter,
+            assign_sequence_item_from_mapping, get_sequence_item_from_mapping, tp_clear_with_dict,
+            tp_dealloc, tp_dealloc_with_gc, tp_traverse_with_dict, PyClassItemsIter,
         },
         pymethods::{get_doc, get_name, Getter, Setter},
         trampoline::trampoline,
     },
     internal_tricks::ptr_from_ref,
     types::{typeobject::PyTypeMethods, PyType},
-    Py, PyClass, PyGetterDef, PyMethodDefType, PyResult, PySetterDef, PyTypeInfo, Python,
+    Bound, Py, PyClass, PyGetterDef, PyMethodDefType, PyResult, PySetterDef, PyTypeInfo, Python,
 };
 use std::{
     borrow::Cow,
@@ -39,6 +39,8 @@ where
         base: *mut ffi::PyTypeObject,
         dealloc: unsafe extern "C" fn(*mut ffi::PyObject),
         dealloc_with_gc: unsafe extern "C" fn(*mut ffi::PyObject),
+        traverse_with_dict: ffi::traverseproc,
+        clear_with_dict: ffi::inquiry,
         is_mapping: bool,
         is_sequence: bool,
         doc: &'static CStr,
@@ -48,6 +50,7 @@ where
         items_iter: PyClassItemsIter,
         name: &'static str,
         module: Option<&'static str>,
+        metaclass: Option<Bound<'_, PyType>>,
         size_of: usize,
     ) -> PyResult<PyClassTypeObject> {
         PyTypeBuilder {
@@ -58,6 +61,8 @@ where
             tp_base: base,
             tp_dealloc: dealloc,
             tp_dealloc_with_gc: dealloc_with_gc,
+            tp_traverse_with_dict: traverse_with_dict,
+            tp_clear_with_dict: clear_with_dict,
             is_mapping,
             is_sequence,
             has_new: false,
@@ -75,7 +80,7 @@ where
         .offsets(dict_offset, weaklist_offset)
         .set_is_basetype(is_basetype)
         .class_items(items_iter)
-        .build(py, name, module, size_of)
+        .build(py, name, module, metaclass, size_of)
     }
 
     unsafe {
@@ -84,6 +89,8 @@ where
             T::BaseType::type_object_raw(py),
             tp_dealloc::<T>,
             tp_dealloc_with_gc::<T>,
+            tp_traverse_with_dict::<T>,
+            tp_clear_with_dict::<T>,
             T::IS_MAPPING,
             T::IS_SEQUENCE,
             T::doc(py)?,
@@ -93,6 +100,7 @@ where
             T::items_iter(),
             T::NAME,
             T::MODULE,
+            T::metaclass(py)?,
             std::mem::size_of::<PyClassObject<T>>(),
         )
     }
@@ -111,6 +119,8 @@ struct PyTypeBuilder {
     tp_base: *mut ffi::PyTypeObject,
     tp_dealloc: ffi::destructor,
     tp_dealloc_with_gc: ffi::destructor,
+    tp_traverse_with_dict: ffi::traverseproc,
+    tp_clear_with_dict: ffi::inquiry,
     is_mapping: bool,
     is_sequence: bool,
     has_new: bool,
@@ -191,7 +201,17 @@ impl PyTypeBuilder {
                 let (def, destructor) = def.as_method_def().unwrap();
                 // FIXME: stop leaking destructor
                 std::mem::forget(destructor);
-                self.method_defs.push(def);
+                // Like slots, a method from `#[pymethods]` replaces a default one of the same
+                // name generated by `#[pyclass]`.
+                let name = unsafe { CStr::from_ptr(def.ml_name) };
+                match self
+                    .method_defs
+                    .iter_mut()
+                    .find(|existing| unsafe { CStr::from_ptr(existing.ml_name) } == name)
+                {
+                    Some(existing) => *existing = def,
+                    None => self.method_defs.push(def),
+                }
             }
             // These class attributes are added after the type gets created by LazyStaticType
             PyMethodDefType::ClassAttribute(_) => {}
@@ -411,6 +431,7 @@ impl PyTypeBuilder {
         py: Python<'_>,
         name: &'static str,
         module_name: Option<&'static str>,
+        metaclass: Option<Bound<'_, PyType>>,
         basicsize: usize,
     ) -> PyResult<PyClassTypeObject> {
         // `c_ulong` and `c_uint` have the same size
@@ -426,13 +447,6 @@ impl PyTypeBuilder {
             unsafe { self.push_slot(ffi::Py_tp_new, no_constructor_defined as *mut c_void) }
         }
 
-        let tp_dealloc = if self.has_traverse || unsafe { ffi::PyType_IS_GC(self.tp_base) == 1 } {
-            self.tp_dealloc_with_gc
-        } else {
-            self.tp_dealloc
-        };
-        unsafe { self.push_slot(ffi::Py_tp_dealloc, tp_dealloc as *mut c_void) }
-
         if self.has_clear && !self.has_traverse {
             return Err(PyTypeError::new_err(format!(
                 "`#[pyclass]` {} implements __clear__ without __traverse__",
@@ -440,6 +454,28 @@ impl PyTypeBuilder {
             )));
         }
 
+        // The instance dict can be part of reference cycles, so it must be visible to the GC.
+        // These wrappers call into any `__traverse__` and `__clear__` of the class themselves.
+        if self.dict_offset.is_some() {
+            self.slots
+                .retain(|slot| slot.slot != ffi::Py_tp_traverse && slot.slot != ffi::Py_tp_clear);
+            // Safety: these are the correct slot types for Py_tp_traverse and Py_tp_clear
+            unsafe {
+                self.push_slot(
+                    ffi::Py_tp_traverse,
+                    self.tp_traverse_with_dict as *mut c_void,
+                );
+                self.push_slot(ffi::Py_tp_clear, self.tp_clear_with_dict as *mut c_void);
+            }
+        }
+
+        let tp_dealloc = if self.has_traverse || unsafe { ffi::PyType_IS_GC(self.tp_base) == 1 } {
+            self.tp_dealloc_with_gc
+        } else {
+            self.tp_dealloc
+        };
+        unsafe { self.push_slot(ffi::Py_tp_dealloc, tp_dealloc as *mut c_void) }
+
         // For sequences, implement sq_length instead of mp_length
         if self.is_sequence {
             for slot in &mut self.slots {
@@ -465,9 +501,27 @@ impl PyTypeBuilder {
             slots: self.slots.as_mut_ptr(),
         };
 
-        // Safety: We've correctly setup the PyType_Spec at this point
-        let type_object: Py<PyType> =
-            unsafe { Py::from_owned_ptr_or_err(py, ffi::PyType_FromSpec(&mut spec))? };
+        let type_object = match metaclass {
+            // Safety: We've correctly setup the PyType_Spec at this point
+            None => unsafe { ffi::PyType_FromSpec(&mut spec) },
+            #[cfg(Py_3_12)]
+            Some(metaclass) => unsafe {
+                ffi::PyType_FromMetaclass(
+                    metaclass.as_type_ptr(),
+                    ptr::null_mut(),
+                    &mut spec,
+                    ptr::null_mut(),
+                )
+            },
+            // Rejected by the `#[pyclass]` macro
+            #[cfg(not(Py_3_12))]
+            Some(_) => {
+                return Err(PyTypeError::new_err(
+                    "`#[pyclass(metaclass = ...)]` requires Python 3.12 or newer",
+                ))
+            }
+        };
+        let type_object: Py<PyType> = unsafe { Py::from_owned_ptr_or_err(py, type_object)? };
 
         #[cfg(not(Py_3_11))]
         bpo_45315_workaround(py, class_name);
@@ -531,36 +585,41 @@ unsafe extern "C" fn no_constructor_defined(
 
 #[derive(Default)]
 struct GetSetDefBuilder {
-    doc: Option<&'static str>,
+    getter_doc: Option<&'static str>,
+    setter_doc: Option<&'static str>,
     getter: Option<Getter>,
     setter: Option<Setter>,
 }
 
 impl GetSetDefBuilder {
     fn add_getter(&mut self, getter: &PyGetterDef) {
-        // TODO: be smarter about merging getter and setter docs
-        if self.doc.is_none() {
-            self.doc = Some(getter.doc);
-        }
+        self.getter_doc = Some(getter.doc);
         // TODO: return an error if getter already defined?
         self.getter = Some(getter.meth)
     }
 
     fn add_setter(&mut self, setter: &PySetterDef) {
-        // TODO: be smarter about merging getter and setter docs
-        if self.doc.is_none() {
-            self.doc = Some(setter.doc);
-        }
+        self.setter_doc = Some(setter.doc);
         // TODO: return an error if setter already defined?
         self.setter = Some(setter.meth)
     }
 
+    /// The getter's doc is used if it has one, as for Python's `property`; otherwise the
+    /// setter's doc is used so that documenting only the setter isn't lost.
+    fn doc(&self) -> Option<&'static str> {
+        let is_empty = |doc: &&str| doc.trim_end_matches('\0').is_empty();
+        match (self.getter_doc, self.setter_doc) {
+            (Some(getter_doc), Some(setter_doc)) if is_empty(&getter_doc) => Some(setter_doc),
+            (getter_doc, setter_doc) => getter_doc.or(setter_doc),
+        }
+    }
+
     fn as_get_set_def(
         &self,
         name: &'static str,
     ) -> PyResult<(ffi::PyGetSetDef, GetSetDefDestructor)> {
         let name = get_name(name)?;
-        let doc = self.doc.map(get_doc).transpose()?;
+        let doc = self.doc().map(get_doc).transpose()?;
 
         let getset_type = match (self.getter, self.setter) {
             (Some(getter), None) => GetSetDefType::Getter(getter),
@@ -27,6 +27,22 @@ pub trait PyObjectInit<T>: Sized {
         py: Python<'_>,
         subtype: *mut PyTypeObject,
     ) -> PyResult<*mut ffi::PyObject>;
+
+    /// Like [`into_new_object`](Self::into_new_object), but passes the arguments of the Python
+    /// constructor call on to `__new__` of the native base type.
+    ///
+    /// # Safety
+    /// - `subtype` must be a valid pointer to a type object of T or a subclass.
+    /// - `args` must be null or a pointer to a tuple, `kwargs` null or a pointer to a dict.
+    #[doc(hidden)]
+    unsafe fn into_new_object_with_args(
+        self,
+        py: Python<'_>,
+        subtype: *mut PyTypeObject,
+        args: *mut ffi::PyObject,
+        kwargs: *mut ffi::PyObject,
+    ) -> PyResult<*mut ffi::PyObject>;
+
     private_decl! {}
 }
 
@@ -38,11 +54,23 @@ impl<T: PyTypeInfo> PyObjectInit<T> for PyNativeTypeInitializer<T> {
         self,
         py: Python<'_>,
         subtype: *mut PyTypeObject,
+    ) -> PyResult<*mut ffi::PyObject> {
+        self.into_new_object_with_args(py, subtype, std::ptr::null_mut(), std::ptr::null_mut())
+    }
+
+    unsafe fn into_new_object_with_args(
+        self,
+        py: Python<'_>,
+        subtype: *mut PyTypeObject,
+        args: *mut ffi::PyObject,
+        kwargs: *mut ffi::PyObject,
     ) -> PyResult<*mut ffi::PyObject> {
         unsafe fn inner(
             py: Python<'_>,
             type_object: *mut PyTypeObject,
             subtype: *mut PyTypeObject,
+            args: *mut ffi::PyObject,
+            kwargs: *mut ffi::PyObject,
         ) -> PyResult<*mut ffi::PyObject> {
             // HACK (due to FIXME below): PyBaseObject_Type's tp_new isn't happy with NULL arguments
             let is_base_object = type_object == std::ptr::addr_of_mut!(ffi::PyBaseObject_Type);
@@ -57,14 +85,25 @@ impl<T: PyTypeInfo> PyObjectInit<T> for PyNativeTypeInitializer<T> {
             }
 
             #[cfg(Py_LIMITED_API)]
-            unreachable!("subclassing native types is not possible with the `abi3` feature");
+            {
+                let _ = (args, kwargs);
+                unreachable!("subclassing native types is not possible with the `abi3` feature");
+            }
 
             #[cfg(not(Py_LIMITED_API))]
             {
                 match (*type_object).tp_new {
-                    // FIXME: Call __new__ with actual arguments
                     Some(newfunc) => {
-                        let obj = newfunc(subtype, std::ptr::null_mut(), std::ptr::null_mut());
+                        // Objects created from Rust have no constructor arguments, but native
+                        // `__new__` implementations expect at least an empty tuple.
+                        let empty;
+                        let args = if args.is_null() {
+                            empty = crate::types::PyTuple::empty_bound(py);
+                            empty.as_ptr()
+                        } else {
+                            args
+                        };
+                        let obj = newfunc(subtype, args, kwargs);
                         if obj.is_null() {
                             Err(PyErr::fetch(py))
                         } else {
@@ -78,7 +117,7 @@ impl<T: PyTypeInfo> PyObjectInit<T> for PyNativeTypeInitializer<T> {
             }
         }
         let type_object = T::type_object_raw(py);
-        inner(py, type_object, subtype)
+        inner(py, type_object, subtype, args, kwargs)
     }
 
     private_impl! {}
@@ -210,17 +249,30 @@ impl<T: PyClass> PyClassInitializer<T> {
     where
         T: PyClass,
     {
-        unsafe { self.create_class_object_of_type(py, T::type_object_raw(py)) }
+        unsafe {
+            self.create_class_object_of_type(
+                py,
+                T::type_object_raw(py),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        }
     }
 
     /// Creates a new class object and initializes it given a typeobject `subtype`.
     ///
+    /// `args` and `kwargs` are the arguments of the Python constructor call, if any, and are
+    /// passed on to `__new__` of the native base type.
+    ///
     /// # Safety
-    /// `subtype` must be a valid pointer to the type object of T or a subclass.
+    /// - `subtype` must be a valid pointer to the type object of T or a subclass.
+    /// - `args` must be null or a pointer to a tuple, `kwargs` null or a pointer to a dict.
     pub(crate) unsafe fn create_class_object_of_type(
         self,
         py: Python<'_>,
         target_type: *mut crate::ffi::PyTypeObject,
+        args: *mut ffi::PyObject,
+        kwargs: *mut ffi::PyObject,
     ) -> PyResult<Bound<'_, T>>
     where
         T: PyClass,
@@ -238,7 +290,7 @@ impl<T: PyClass> PyClassInitializer<T> {
             PyClassInitializerImpl::New { init, super_init } => (init, super_init),
         };
 
-        let obj = super_init.into_new_object(py, target_type)?;
+        let obj = super_init.into_new_object_with_args(py, target_type, args, kwargs)?;
 
         let part_init: *mut PartiallyInitializedClassObject<T> = obj.cast();
         std::ptr::write(
@@ -264,7 +316,17 @@ impl<T: PyClass> PyObjectInit<T> for PyClassInitializer<T> {
         py: Python<'_>,
         subtype: *mut PyTypeObject,
     ) -> PyResult<*mut ffi::PyObject> {
-        self.create_class_object_of_type(py, subtype)
+        self.into_new_object_with_args(py, subtype, std::ptr::null_mut(), std::ptr::null_mut())
+    }
+
+    unsafe fn into_new_object_with_args(
+        self,
+        py: Python<'_>,
+        subtype: *mut PyTypeObject,
+        args: *mut ffi::PyObject,
+        kwargs: *mut ffi::PyObject,
+    ) -> PyResult<*mut ffi::PyObject> {
+        self.create_class_object_of_type(py, subtype, args, kwargs)
             .map(Bound::into_ptr)
     }
 
@@ -1,6 +1,9 @@
+#[cfg(all(not(Py_LIMITED_API), not(PyPy), not(GraalPy)))]
+use crate::types::PyCode;
 use crate::types::{
     PyBool, PyByteArray, PyBytes, PyCapsule, PyComplex, PyDict, PyFloat, PyFrozenSet, PyList,
-    PyMapping, PyModule, PySequence, PySet, PySlice, PyString, PyTraceback, PyTuple, PyType,
+    PyMapping, PyModule, PyRange, PySequence, PySet, PySlice, PyString, PyTraceback, PyTuple,
+    PyType,
 };
 use crate::{ffi, Bound, PyAny, PyResult};
 
@@ -18,6 +21,8 @@ impl Sealed for Bound<'_, PyBool> {}
 impl Sealed for Bound<'_, PyByteArray> {}
 impl Sealed for Bound<'_, PyBytes> {}
 impl Sealed for Bound<'_, PyCapsule> {}
+#[cfg(all(not(Py_LIMITED_API), not(PyPy), not(GraalPy)))]
+impl Sealed for Bound<'_, PyCode> {}
 impl Sealed for Bound<'_, PyComplex> {}
 impl Sealed for Bound<'_, PyDict> {}
 impl Sealed for Bound<'_, PyFloat> {}
@@ -25,6 +30,7 @@ impl Sealed for Bound<'_, PyFrozenSet> {}
 impl Sealed for Bound<'_, PyList> {}
 impl Sealed for Bound<'_, PyMapping> {}
 impl Sealed for Bound<'_, PyModule> {}
+impl Sealed for Bound<'_, PyRange> {}
 impl Sealed for Bound<'_, PySequence> {}
 impl Sealed for Bound<'_, PySet> {}
 impl Sealed for Bound<'_, PySlice> {}
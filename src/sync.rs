@@ -66,7 +66,7 @@ unsafe impl<T> Sync for GILProtected<T> where T: Send {}
 ///     from reentrant initialization.
 ///  2) If the initialization function `f` provided to `get_or_init` (or `get_or_try_init`)
 ///     temporarily releases the GIL (e.g. by calling `Python::import`) then it is possible
-///     for a second thread to also begin initializing the `GITOnceCell`. Even when this
+///     for a second thread to also begin initializing the `GILOnceCell`. Even when this
 ///     happens `GILOnceCell` guarantees that only **one** write to the cell ever occurs -
 ///     this is treated as a race, other threads will discard the value they compute and
 ///     return the result of the first complete computation.
@@ -327,4 +327,50 @@ mod tests {
             assert_eq!(cell.into_inner(), None)
         })
     }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))] // We are building wasm Python with pthreads disabled
+    fn test_once_cell_concurrent_init() {
+        static CELL: GILOnceCell<i32> = GILOnceCell::new();
+
+        Python::with_gil(|py| {
+            let value = CELL.get_or_init(py, || {
+                // Release the GIL during initialization so that another thread can win the race.
+                py.allow_threads(|| {
+                    std::thread::spawn(|| {
+                        Python::with_gil(|py| assert_eq!(*CELL.get_or_init(py, || 1), 1));
+                    })
+                    .join()
+                    .unwrap();
+                });
+                2
+            });
+            // The value from the first completed initialization is kept.
+            assert_eq!(*value, 1);
+        });
+    }
+
+    #[test]
+    fn test_gil_protected() {
+        use std::cell::RefCell;
+
+        static NUMBERS: GILProtected<RefCell<Vec<i32>>> =
+            GILProtected::new(RefCell::new(Vec::new()));
+
+        Python::with_gil(|py| {
+            NUMBERS.get(py).borrow_mut().push(1);
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(|| Python::with_gil(|py| NUMBERS.get(py).borrow_mut().push(2)))
+            .join()
+            .unwrap();
+
+        Python::with_gil(|py| {
+            let numbers = NUMBERS.get(py).borrow();
+            assert_eq!(numbers[0], 1);
+            #[cfg(not(target_arch = "wasm32"))]
+            assert_eq!(numbers[1], 2);
+        });
+    }
 }
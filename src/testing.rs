@@ -0,0 +1,6 @@
+//! Utilities for testing code which uses PyO3.
+//!
+//! See the [testing section of the guide](https://pyo3.rs/latest/testing.html) for examples.
+
+#[cfg(feature = "macros")]
+pub use pyo3_macros::test;
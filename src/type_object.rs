@@ -255,3 +255,43 @@ pub(crate) unsafe fn get_tp_free(tp: *mut ffi::PyTypeObject) -> ffi::freefunc {
         std::mem::transmute(ptr)
     }
 }
+
+/// Gets the `tp_traverse` slot of `tp`, if it supports garbage collection.
+#[inline]
+pub(crate) unsafe fn get_tp_traverse(tp: *mut ffi::PyTypeObject) -> Option<ffi::traverseproc> {
+    if ffi::PyType_IS_GC(tp) == 0 {
+        return None;
+    }
+
+    #[cfg(not(Py_LIMITED_API))]
+    {
+        (*tp).tp_traverse
+    }
+
+    // Only heap types can be subclassed with the limited API, for which `PyType_GetSlot`
+    // works on all versions.
+    #[cfg(Py_LIMITED_API)]
+    {
+        let ptr = ffi::PyType_GetSlot(tp, ffi::Py_tp_traverse);
+        std::mem::transmute(ptr)
+    }
+}
+
+/// Gets the `tp_clear` slot of `tp`, if it supports garbage collection.
+#[inline]
+pub(crate) unsafe fn get_tp_clear(tp: *mut ffi::PyTypeObject) -> Option<ffi::inquiry> {
+    if ffi::PyType_IS_GC(tp) == 0 {
+        return None;
+    }
+
+    #[cfg(not(Py_LIMITED_API))]
+    {
+        (*tp).tp_clear
+    }
+
+    #[cfg(Py_LIMITED_API)]
+    {
+        let ptr = ffi::PyType_GetSlot(tp, ffi::Py_tp_clear);
+        std::mem::transmute(ptr)
+    }
+}
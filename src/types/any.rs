@@ -9,7 +9,7 @@ use crate::py_result_ext::PyResultExt;
 use crate::type_object::{PyTypeCheck, PyTypeInfo};
 #[cfg(not(any(PyPy, GraalPy)))]
 use crate::types::PySuper;
-use crate::types::{PyDict, PyIterator, PyList, PyString, PyTuple, PyType};
+use crate::types::{PyDict, PyIterator, PyList, PyMapping, PySequence, PyString, PyTuple, PyType};
 use crate::{err, ffi, Py, Python};
 #[cfg(feature = "gil-refs")]
 use crate::{err::PyDowncastError, type_object::HasPyGilRef, PyNativeType};
@@ -370,6 +370,20 @@ impl PyAny {
         self.as_borrowed().is_callable()
     }
 
+    /// Returns whether this object is an instance of `collections.abc.Sequence`.
+    ///
+    /// This is equivalent to the Python expression `isinstance(self, collections.abc.Sequence)`.
+    pub fn is_sequence(&self) -> bool {
+        self.as_borrowed().is_sequence()
+    }
+
+    /// Returns whether this object is an instance of `collections.abc.Mapping`.
+    ///
+    /// This is equivalent to the Python expression `isinstance(self, collections.abc.Mapping)`.
+    pub fn is_mapping(&self) -> bool {
+        self.as_borrowed().is_mapping()
+    }
+
     /// Calls the object.
     ///
     /// This is equivalent to the Python expression `self(*args, **kwargs)`.
@@ -1266,6 +1280,42 @@ pub trait PyAnyMethods<'py>: crate::sealed::Sealed {
     /// [1]: https://docs.python.org/3/library/functions.html#callable
     fn is_callable(&self) -> bool;
 
+    /// Returns whether this object is an instance of `collections.abc.Sequence`.
+    ///
+    /// This is equivalent to the Python expression `isinstance(self, collections.abc.Sequence)`,
+    /// and to a successful `downcast::<PySequence>()`. Errors raised by the check are swallowed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    /// use pyo3::types::{PyDict, PyList};
+    ///
+    /// Python::with_gil(|py| {
+    ///     assert!(PyList::empty_bound(py).is_sequence());
+    ///     assert!(!PyDict::new_bound(py).is_sequence());
+    /// });
+    /// ```
+    fn is_sequence(&self) -> bool;
+
+    /// Returns whether this object is an instance of `collections.abc.Mapping`.
+    ///
+    /// This is equivalent to the Python expression `isinstance(self, collections.abc.Mapping)`,
+    /// and to a successful `downcast::<PyMapping>()`. Errors raised by the check are swallowed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    /// use pyo3::types::{PyDict, PyList};
+    ///
+    /// Python::with_gil(|py| {
+    ///     assert!(PyDict::new_bound(py).is_mapping());
+    ///     assert!(!PyList::empty_bound(py).is_mapping());
+    /// });
+    /// ```
+    fn is_mapping(&self) -> bool;
+
     /// Calls the object.
     ///
     /// This is equivalent to the Python expression `self(*args, **kwargs)`.
@@ -1986,6 +2036,14 @@ impl<'py> PyAnyMethods<'py> for Bound<'py, PyAny> {
         unsafe { ffi::PyCallable_Check(self.as_ptr()) != 0 }
     }
 
+    fn is_sequence(&self) -> bool {
+        self.downcast::<PySequence>().is_ok()
+    }
+
+    fn is_mapping(&self) -> bool {
+        self.downcast::<PyMapping>().is_ok()
+    }
+
     fn call(
         &self,
         args: impl IntoPy<Py<PyTuple>>,
@@ -2366,7 +2424,10 @@ impl<'py> Bound<'py, PyAny> {
 mod tests {
     use crate::{
         basic::CompareOp,
-        types::{IntoPyDict, PyAny, PyAnyMethods, PyBool, PyList, PyLong, PyModule, PyTypeMethods},
+        types::{
+            IntoPyDict, PyAny, PyAnyMethods, PyBool, PyDict, PyList, PyLong, PyModule,
+            PyTypeMethods,
+        },
         Bound, PyTypeInfo, Python, ToPyObject,
     };
 
@@ -2768,6 +2829,23 @@ class SimpleClass:
         });
     }
 
+    #[test]
+    fn test_is_sequence_and_mapping() {
+        Python::with_gil(|py| {
+            let list = PyList::empty_bound(py).into_any();
+            assert!(list.is_sequence());
+            assert!(!list.is_mapping());
+
+            let dict = PyDict::new_bound(py).into_any();
+            assert!(dict.is_mapping());
+            assert!(!dict.is_sequence());
+
+            let not_container = 5.to_object(py).into_bound(py);
+            assert!(!not_container.is_sequence());
+            assert!(!not_container.is_mapping());
+        });
+    }
+
     #[test]
     fn test_is_empty() {
         Python::with_gil(|py| {
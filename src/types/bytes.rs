@@ -11,6 +11,12 @@ use std::str;
 /// Represents a Python `bytes` object.
 ///
 /// This type is immutable.
+///
+/// A `bytes` object stores its contents inline, so creating one always copies the data; use
+/// [`PyBytes::new_bound_with`] to write the contents in place. For this reason PyO3 has no
+/// zero-copy `PyBytes` constructor from a `Vec<u8>` or `bytes::Bytes`. To hand large buffers
+/// owned by Rust to Python without copying, use `PyMemoryView::from_owned` (which requires the
+/// `macros` feature) instead. It returns a read-only `memoryview` rather than a `bytes` object.
 #[repr(transparent)]
 pub struct PyBytes(PyAny);
 
@@ -7,6 +7,7 @@ use crate::{Bound, Python};
 use crate::{PyErr, PyResult};
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_int, c_void};
+use std::ptr::NonNull;
 /// Represents a Python Capsule
 /// as described in [Capsules](https://docs.python.org/3/c-api/capsule.html#capsules):
 /// > This subtype of PyObject represents an opaque value, useful for C extension
@@ -240,6 +241,13 @@ impl PyCapsule {
         self.as_borrowed().pointer()
     }
 
+    /// Gets the pointer to the value in this capsule, checking that the capsule is named `name`.
+    ///
+    /// Returns an error if the name does not match or this capsule is not valid.
+    pub fn pointer_checked<T>(&self, name: Option<&CStr>) -> PyResult<NonNull<T>> {
+        self.as_borrowed().pointer_checked(name)
+    }
+
     /// Checks if this is a valid capsule.
     ///
     /// Returns true if the stored `pointer()` is non-null.
@@ -319,6 +327,35 @@ pub trait PyCapsuleMethods<'py>: crate::sealed::Sealed {
     /// Returns null if this capsule is not valid.
     fn pointer(&self) -> *mut c_void;
 
+    /// Gets the pointer to the value in this capsule, checking that the capsule is named `name`.
+    ///
+    /// Unlike [`pointer`](PyCapsuleMethods::pointer), which accepts whatever name the capsule
+    /// has, this guards against being handed a capsule exported by a different API, so that the
+    /// pointer can be cast to the type that API is known to store.
+    ///
+    /// Returns an error if the name does not match or this capsule is not valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pyo3::{prelude::*, types::PyCapsule};
+    /// use std::ffi::CString;
+    ///
+    /// Python::with_gil(|py| -> PyResult<()> {
+    ///     let name = CString::new("example.api").unwrap();
+    ///     let capsule = PyCapsule::new_bound(py, 42_u32, Some(name.clone()))?;
+    ///
+    ///     let value = capsule.pointer_checked::<u32>(Some(&name))?;
+    ///     assert_eq!(unsafe { *value.as_ref() }, 42);
+    ///
+    ///     let other = CString::new("other.api").unwrap();
+    ///     assert!(capsule.pointer_checked::<u32>(Some(&other)).is_err());
+    ///     Ok(())
+    /// })
+    /// # .unwrap();
+    /// ```
+    fn pointer_checked<T>(&self, name: Option<&CStr>) -> PyResult<NonNull<T>>;
+
     /// Checks if this is a valid capsule.
     ///
     /// Returns true if the stored `pointer()` is non-null.
@@ -363,6 +400,12 @@ impl<'py> PyCapsuleMethods<'py> for Bound<'py, PyCapsule> {
         }
     }
 
+    fn pointer_checked<T>(&self, name: Option<&CStr>) -> PyResult<NonNull<T>> {
+        let name_ptr = name.map_or(std::ptr::null(), CStr::as_ptr);
+        let ptr = unsafe { ffi::PyCapsule_GetPointer(self.as_ptr(), name_ptr) };
+        NonNull::new(ptr.cast()).ok_or_else(|| PyErr::fetch(self.py()))
+    }
+
     fn is_valid(&self) -> bool {
         // As well as if the stored pointer is null, PyCapsule_IsValid also returns false if
         // self.as_ptr() is null or not a ptr to a PyCapsule object. Both of these are guaranteed
@@ -442,6 +485,7 @@ fn name_ptr_ignore_error(slf: &Bound<'_, PyCapsule>) -> *const c_char {
 mod tests {
     use libc::c_void;
 
+    use crate::exceptions::PyValueError;
     use crate::prelude::PyModule;
     use crate::types::capsule::PyCapsuleMethods;
     use crate::types::module::PyModuleMethods;
@@ -609,4 +653,25 @@ mod tests {
             assert_eq!(cap.context().unwrap(), std::ptr::null_mut());
         });
     }
+
+    #[test]
+    fn test_pycapsule_pointer_checked() {
+        Python::with_gil(|py| {
+            let name = CString::new("foo.api").unwrap();
+            let cap = PyCapsule::new_bound(py, 7u64, Some(name.clone())).unwrap();
+
+            let ptr = cap.pointer_checked::<u64>(Some(&name)).unwrap();
+            assert_eq!(unsafe { *ptr.as_ref() }, 7);
+            assert_eq!(ptr.as_ptr().cast(), cap.pointer());
+
+            let other = CString::new("bar.api").unwrap();
+            let err = cap.pointer_checked::<u64>(Some(&other)).unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(cap.pointer_checked::<u64>(None).is_err());
+
+            let unnamed = PyCapsule::new_bound(py, 7u64, None).unwrap();
+            assert!(unnamed.pointer_checked::<u64>(None).is_ok());
+            assert!(unnamed.pointer_checked::<u64>(Some(&name)).is_err());
+        });
+    }
 }
@@ -1,7 +1,16 @@
-use crate::ffi;
-use crate::PyAny;
+use crate::err::PyResult;
+use crate::ffi_ptr_ext::FfiPtrExt;
+use crate::py_result_ext::PyResultExt;
+use crate::types::PyDict;
+use crate::{ffi, Bound, PyAny, Python};
+use std::ffi::CString;
 
 /// Represents a Python code object.
+///
+/// Code objects are the compiled form of Python source code. Embedders evaluating the same
+/// snippet many times can compile it once with [`PyCode::compile_bound`], keep the result (for
+/// instance as a `Py<PyCode>` in a [`GILOnceCell`](crate::sync::GILOnceCell)), and run it with
+/// [`PyCodeMethods::run`] without parsing the source again.
 #[repr(transparent)]
 pub struct PyCode(PyAny);
 
@@ -11,11 +20,103 @@ pyobject_native_type_core!(
     #checkfunction=ffi::PyCode_Check
 );
 
+/// The kind of source code compiled by [`PyCode::compile_bound`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PyCodeInput {
+    /// A single expression, as accepted by [`Python::eval_bound`].
+    Eval,
+    /// A sequence of statements, as accepted by [`Python::run_bound`].
+    File,
+    /// A single interactive statement, which prints the value of expression statements.
+    Single,
+}
+
+impl PyCode {
+    /// Compiles `source` into a code object.
+    ///
+    /// `file_name` is used for the code object's `co_filename`, and therefore appears in
+    /// tracebacks and syntax errors raised by the code, together with the line numbers
+    /// within `source`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use pyo3::prelude::*;
+    /// use pyo3::types::{PyCode, PyCodeInput, PyCodeMethods, PyDict};
+    ///
+    /// # fn main() -> PyResult<()> {
+    /// Python::with_gil(|py| {
+    ///     let code = PyCode::compile_bound(py, "x * 2", "double.py", PyCodeInput::Eval)?;
+    ///     let globals = PyDict::new_bound(py);
+    ///     for x in 0..3 {
+    ///         globals.set_item("x", x)?;
+    ///         let result: i32 = code.run(Some(&globals), None)?.extract()?;
+    ///         assert_eq!(result, x * 2);
+    ///     }
+    ///     Ok(())
+    /// })
+    /// # }
+    /// ```
+    pub fn compile_bound<'py>(
+        py: Python<'py>,
+        source: &str,
+        file_name: &str,
+        input: PyCodeInput,
+    ) -> PyResult<Bound<'py, PyCode>> {
+        let source = CString::new(source)?;
+        let file_name = CString::new(file_name)?;
+        let start = match input {
+            PyCodeInput::Eval => ffi::Py_eval_input,
+            PyCodeInput::File => ffi::Py_file_input,
+            PyCodeInput::Single => ffi::Py_single_input,
+        };
+        unsafe {
+            ffi::Py_CompileString(source.as_ptr(), file_name.as_ptr(), start)
+                .assume_owned_or_err(py)
+                .downcast_into_unchecked()
+        }
+    }
+}
+
+/// Implementation of functionality for [`PyCode`].
+///
+/// These methods are defined for the `Bound<'py, PyCode>` smart pointer, so to use method call
+/// syntax these methods are separated into a trait, because stable Rust does not yet support
+/// `arbitrary_self_types`.
+#[doc(alias = "PyCode")]
+pub trait PyCodeMethods<'py>: crate::sealed::Sealed {
+    /// Evaluates the code object in the given context and returns the result.
+    ///
+    /// If `globals` is `None`, it defaults to Python module `__main__`.
+    /// If `locals` is `None`, it defaults to the value of `globals`.
+    ///
+    /// If `globals` doesn't contain `__builtins__`, default `__builtins__`
+    /// will be added automatically.
+    ///
+    /// Code compiled with [`PyCodeInput::File`] or [`PyCodeInput::Single`] returns `None`.
+    fn run(
+        &self,
+        globals: Option<&Bound<'py, PyDict>>,
+        locals: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>>;
+}
+
+impl<'py> PyCodeMethods<'py> for Bound<'py, PyCode> {
+    fn run(
+        &self,
+        globals: Option<&Bound<'py, PyDict>>,
+        locals: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        // SAFETY: `self` is a code object
+        unsafe { self.py().run_code_object(self.as_any(), globals, locals) }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::PyTypeMethods;
-    use crate::{PyTypeInfo, Python};
+    use crate::types::{PyAnyMethods, PyDictMethods, PyTracebackMethods, PyTypeMethods};
+    use crate::PyTypeInfo;
 
     #[test]
     fn test_type_object() {
@@ -23,4 +124,56 @@ mod tests {
             assert_eq!(PyCode::type_object_bound(py).name().unwrap(), "code");
         })
     }
+
+    #[test]
+    fn test_compile_and_run() {
+        Python::with_gil(|py| {
+            let code =
+                PyCode::compile_bound(py, "y = x + 1", "snippet.py", PyCodeInput::File).unwrap();
+            assert_eq!(
+                code.getattr("co_filename")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "snippet.py"
+            );
+
+            let globals = PyDict::new_bound(py);
+            globals.set_item("x", 1).unwrap();
+            assert!(code.run(Some(&globals), None).unwrap().is_none());
+            let y: i32 = globals.get_item("y").unwrap().unwrap().extract().unwrap();
+            assert_eq!(y, 2);
+        })
+    }
+
+    #[test]
+    fn test_error_location() {
+        Python::with_gil(|py| {
+            let err = PyCode::compile_bound(py, "x = 1\nx +", "broken.py", PyCodeInput::File)
+                .unwrap_err();
+            let value = err.value_bound(py);
+            assert_eq!(
+                value
+                    .getattr("filename")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "broken.py"
+            );
+            assert_eq!(
+                value.getattr("lineno").unwrap().extract::<i32>().unwrap(),
+                2
+            );
+
+            let code =
+                PyCode::compile_bound(py, "\n1 / 0", "divide.py", PyCodeInput::File).unwrap();
+            let err = code.run(None, None).unwrap_err();
+            let traceback = err.traceback_bound(py).unwrap().format().unwrap();
+            assert!(
+                traceback.contains("File \"divide.py\", line 2"),
+                "{}",
+                traceback
+            );
+        })
+    }
 }
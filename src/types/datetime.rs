@@ -2,36 +2,54 @@
 //!
 //! For more details about these types, see the [Python
 //! documentation](https://docs.python.org/3/library/datetime.html)
+//!
+//! The `datetime` C API is not part of the limited API, so with the `abi3` feature these types are
+//! implemented by calling the `datetime` module through the Python object protocol instead.
 
 use crate::err::PyResult;
+#[cfg(not(Py_LIMITED_API))]
 use crate::ffi::{
     self, PyDateTime_CAPI, PyDateTime_FromTimestamp, PyDateTime_IMPORT, PyDate_FromTimestamp,
 };
+#[cfg(not(Py_LIMITED_API))]
 use crate::ffi::{
     PyDateTime_DATE_GET_FOLD, PyDateTime_DATE_GET_HOUR, PyDateTime_DATE_GET_MICROSECOND,
     PyDateTime_DATE_GET_MINUTE, PyDateTime_DATE_GET_SECOND,
 };
 #[cfg(GraalPy)]
 use crate::ffi::{PyDateTime_DATE_GET_TZINFO, PyDateTime_TIME_GET_TZINFO, Py_IsNone};
+#[cfg(not(Py_LIMITED_API))]
 use crate::ffi::{
     PyDateTime_DELTA_GET_DAYS, PyDateTime_DELTA_GET_MICROSECONDS, PyDateTime_DELTA_GET_SECONDS,
 };
+#[cfg(not(Py_LIMITED_API))]
 use crate::ffi::{PyDateTime_GET_DAY, PyDateTime_GET_MONTH, PyDateTime_GET_YEAR};
+#[cfg(not(Py_LIMITED_API))]
 use crate::ffi::{
     PyDateTime_TIME_GET_FOLD, PyDateTime_TIME_GET_HOUR, PyDateTime_TIME_GET_MICROSECOND,
     PyDateTime_TIME_GET_MINUTE, PyDateTime_TIME_GET_SECOND,
 };
+#[cfg(not(Py_LIMITED_API))]
 use crate::ffi_ptr_ext::FfiPtrExt;
 #[cfg(feature = "gil-refs")]
 use crate::instance::PyNativeType;
+#[cfg(not(Py_LIMITED_API))]
 use crate::py_result_ext::PyResultExt;
+#[cfg(Py_LIMITED_API)]
+use crate::sync::GILOnceCell;
 use crate::types::any::PyAnyMethods;
 use crate::types::PyTuple;
+#[cfg(Py_LIMITED_API)]
+use crate::types::{IntoPyDict, PyString, PyType, PyTypeMethods};
+#[cfg(Py_LIMITED_API)]
+use crate::FromPyObject;
 use crate::{Bound, IntoPy, Py, PyAny, PyErr, Python};
+#[cfg(not(Py_LIMITED_API))]
 use std::os::raw::c_int;
-#[cfg(feature = "chrono")]
+#[cfg(all(feature = "chrono", not(Py_LIMITED_API)))]
 use std::ptr;
 
+#[cfg(not(Py_LIMITED_API))]
 fn ensure_datetime_api(py: Python<'_>) -> PyResult<&'static PyDateTime_CAPI> {
     if let Some(api) = unsafe { pyo3_ffi::PyDateTimeAPI().as_ref() } {
         Ok(api)
@@ -44,6 +62,7 @@ fn ensure_datetime_api(py: Python<'_>) -> PyResult<&'static PyDateTime_CAPI> {
     }
 }
 
+#[cfg(not(Py_LIMITED_API))]
 fn expect_datetime_api(py: Python<'_>) -> &'static PyDateTime_CAPI {
     ensure_datetime_api(py).expect("failed to import `datetime` C API")
 }
@@ -60,6 +79,7 @@ fn expect_datetime_api(py: Python<'_>) -> &'static PyDateTime_CAPI {
 //
 // These functions must only be called when the GIL is held!
 
+#[cfg(not(Py_LIMITED_API))]
 macro_rules! ffi_fun_with_autoinit {
     ($(#[$outer:meta] unsafe fn $name: ident($arg: ident: *mut PyObject) -> $ret: ty;)*) => {
         $(
@@ -79,6 +99,7 @@ macro_rules! ffi_fun_with_autoinit {
     };
 }
 
+#[cfg(not(Py_LIMITED_API))]
 ffi_fun_with_autoinit! {
     /// Check if `op` is a `PyDateTimeAPI.DateType` or subtype.
     unsafe fn PyDate_Check(op: *mut PyObject) -> c_int;
@@ -96,6 +117,54 @@ ffi_fun_with_autoinit! {
     unsafe fn PyTZInfo_Check(op: *mut PyObject) -> c_int;
 }
 
+/// The types of the `datetime` module, used in place of the C API which is not part of the
+/// limited API.
+#[cfg(Py_LIMITED_API)]
+struct DatetimeTypes {
+    date: Py<PyType>,
+    datetime: Py<PyType>,
+    time: Py<PyType>,
+    timedelta: Py<PyType>,
+    timezone_utc: Py<PyAny>,
+    tzinfo: Py<PyType>,
+}
+
+#[cfg(Py_LIMITED_API)]
+impl DatetimeTypes {
+    fn get(py: Python<'_>) -> &Self {
+        Self::try_get(py).expect("failed to load datetime module")
+    }
+
+    fn try_get(py: Python<'_>) -> PyResult<&Self> {
+        static TYPES: GILOnceCell<DatetimeTypes> = GILOnceCell::new();
+        TYPES.get_or_try_init(py, || {
+            let datetime = py.import_bound("datetime")?;
+            let get_type = |name: &str| -> PyResult<Py<PyType>> {
+                Ok(datetime.getattr(name)?.downcast_into::<PyType>()?.unbind())
+            };
+            Ok::<_, PyErr>(Self {
+                date: get_type("date")?,
+                datetime: get_type("datetime")?,
+                time: get_type("time")?,
+                timedelta: get_type("timedelta")?,
+                timezone_utc: datetime.getattr("timezone")?.getattr("utc")?.unbind(),
+                tzinfo: get_type("tzinfo")?,
+            })
+        })
+    }
+}
+
+/// Reads a component of a `datetime` object through its Python attribute.
+#[cfg(Py_LIMITED_API)]
+fn get_component<'py, T: FromPyObject<'py>>(
+    obj: &Bound<'py, PyAny>,
+    name: &Bound<'py, PyString>,
+) -> T {
+    obj.getattr(name)
+        .and_then(|value| value.extract())
+        .expect("failed to read `datetime` component")
+}
+
 // Access traits
 
 /// Trait for accessing the date components of a struct containing a date.
@@ -194,6 +263,7 @@ pub trait PyTzInfoAccess<'py> {
 /// Bindings around `datetime.date`
 #[repr(transparent)]
 pub struct PyDate(PyAny);
+#[cfg(not(Py_LIMITED_API))]
 pyobject_native_type!(
     PyDate,
     crate::ffi::PyDateTime_Date,
@@ -201,6 +271,12 @@ pyobject_native_type!(
     #module=Some("datetime"),
     #checkfunction=PyDate_Check
 );
+#[cfg(Py_LIMITED_API)]
+pyobject_native_type_core!(
+    PyDate,
+    |py| DatetimeTypes::get(py).date.bind(py).as_type_ptr(),
+    #module=Some("datetime")
+);
 
 impl PyDate {
     /// Deprecated form of [`PyDate::new_bound`].
@@ -215,11 +291,22 @@ impl PyDate {
 
     /// Creates a new `datetime.date`.
     pub fn new_bound(py: Python<'_>, year: i32, month: u8, day: u8) -> PyResult<Bound<'_, PyDate>> {
-        let api = ensure_datetime_api(py)?;
-        unsafe {
-            (api.Date_FromDate)(year, c_int::from(month), c_int::from(day), api.DateType)
-                .assume_owned_or_err(py)
-                .downcast_into_unchecked()
+        #[cfg(not(Py_LIMITED_API))]
+        {
+            let api = ensure_datetime_api(py)?;
+            unsafe {
+                (api.Date_FromDate)(year, c_int::from(month), c_int::from(day), api.DateType)
+                    .assume_owned_or_err(py)
+                    .downcast_into_unchecked()
+            }
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            let date = DatetimeTypes::try_get(py)?
+                .date
+                .bind(py)
+                .call1((year, month, day))?;
+            Ok(unsafe { date.downcast_into_unchecked() })
         }
     }
 
@@ -239,13 +326,24 @@ impl PyDate {
     pub fn from_timestamp_bound(py: Python<'_>, timestamp: i64) -> PyResult<Bound<'_, PyDate>> {
         let time_tuple = PyTuple::new_bound(py, [timestamp]);
 
-        // safety ensure that the API is loaded
-        let _api = ensure_datetime_api(py)?;
+        #[cfg(not(Py_LIMITED_API))]
+        {
+            // safety ensure that the API is loaded
+            let _api = ensure_datetime_api(py)?;
 
-        unsafe {
-            PyDate_FromTimestamp(time_tuple.as_ptr())
-                .assume_owned_or_err(py)
-                .downcast_into_unchecked()
+            unsafe {
+                PyDate_FromTimestamp(time_tuple.as_ptr())
+                    .assume_owned_or_err(py)
+                    .downcast_into_unchecked()
+            }
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            let date = DatetimeTypes::try_get(py)?
+                .date
+                .bind(py)
+                .call_method1("fromtimestamp", time_tuple)?;
+            Ok(unsafe { date.downcast_into_unchecked() })
         }
     }
 }
@@ -267,21 +365,43 @@ impl PyDateAccess for PyDate {
 
 impl PyDateAccess for Bound<'_, PyDate> {
     fn get_year(&self) -> i32 {
-        unsafe { PyDateTime_GET_YEAR(self.as_ptr()) }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_GET_YEAR(self.as_ptr())
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "year"))
+        }
     }
 
     fn get_month(&self) -> u8 {
-        unsafe { PyDateTime_GET_MONTH(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_GET_MONTH(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "month"))
+        }
     }
 
     fn get_day(&self) -> u8 {
-        unsafe { PyDateTime_GET_DAY(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_GET_DAY(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "day"))
+        }
     }
 }
 
 /// Bindings for `datetime.datetime`
 #[repr(transparent)]
 pub struct PyDateTime(PyAny);
+#[cfg(not(Py_LIMITED_API))]
 pyobject_native_type!(
     PyDateTime,
     crate::ffi::PyDateTime_DateTime,
@@ -289,6 +409,12 @@ pyobject_native_type!(
     #module=Some("datetime"),
     #checkfunction=PyDateTime_Check
 );
+#[cfg(Py_LIMITED_API)]
+pyobject_native_type_core!(
+    PyDateTime,
+    |py| DatetimeTypes::get(py).datetime.bind(py).as_type_ptr(),
+    #module=Some("datetime")
+);
 
 impl PyDateTime {
     /// Deprecated form of [`PyDateTime::new_bound`].
@@ -336,21 +462,39 @@ impl PyDateTime {
         microsecond: u32,
         tzinfo: Option<&Bound<'py, PyTzInfo>>,
     ) -> PyResult<Bound<'py, PyDateTime>> {
-        let api = ensure_datetime_api(py)?;
-        unsafe {
-            (api.DateTime_FromDateAndTime)(
+        #[cfg(not(Py_LIMITED_API))]
+        {
+            let api = ensure_datetime_api(py)?;
+            unsafe {
+                (api.DateTime_FromDateAndTime)(
+                    year,
+                    c_int::from(month),
+                    c_int::from(day),
+                    c_int::from(hour),
+                    c_int::from(minute),
+                    c_int::from(second),
+                    microsecond as c_int,
+                    opt_to_pyobj(tzinfo),
+                    api.DateTimeType,
+                )
+                .assume_owned_or_err(py)
+                .downcast_into_unchecked()
+            }
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            Self::new_bound_with_fold(
+                py,
                 year,
-                c_int::from(month),
-                c_int::from(day),
-                c_int::from(hour),
-                c_int::from(minute),
-                c_int::from(second),
-                microsecond as c_int,
-                opt_to_pyobj(tzinfo),
-                api.DateTimeType,
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                microsecond,
+                tzinfo,
+                false,
             )
-            .assume_owned_or_err(py)
-            .downcast_into_unchecked()
         }
     }
 
@@ -408,22 +552,34 @@ impl PyDateTime {
         tzinfo: Option<&Bound<'py, PyTzInfo>>,
         fold: bool,
     ) -> PyResult<Bound<'py, PyDateTime>> {
-        let api = ensure_datetime_api(py)?;
-        unsafe {
-            (api.DateTime_FromDateAndTimeAndFold)(
-                year,
-                c_int::from(month),
-                c_int::from(day),
-                c_int::from(hour),
-                c_int::from(minute),
-                c_int::from(second),
-                microsecond as c_int,
-                opt_to_pyobj(tzinfo),
-                c_int::from(fold),
-                api.DateTimeType,
-            )
-            .assume_owned_or_err(py)
-            .downcast_into_unchecked()
+        #[cfg(not(Py_LIMITED_API))]
+        {
+            let api = ensure_datetime_api(py)?;
+            unsafe {
+                (api.DateTime_FromDateAndTimeAndFold)(
+                    year,
+                    c_int::from(month),
+                    c_int::from(day),
+                    c_int::from(hour),
+                    c_int::from(minute),
+                    c_int::from(second),
+                    microsecond as c_int,
+                    opt_to_pyobj(tzinfo),
+                    c_int::from(fold),
+                    api.DateTimeType,
+                )
+                .assume_owned_or_err(py)
+                .downcast_into_unchecked()
+            }
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            let kwargs = [("fold", u8::from(fold))].into_py_dict_bound(py);
+            let datetime = DatetimeTypes::try_get(py)?.datetime.bind(py).call(
+                (year, month, day, hour, minute, second, microsecond, tzinfo),
+                Some(&kwargs),
+            )?;
+            Ok(unsafe { datetime.downcast_into_unchecked() })
         }
     }
 
@@ -452,13 +608,24 @@ impl PyDateTime {
     ) -> PyResult<Bound<'py, PyDateTime>> {
         let args = IntoPy::<Py<PyTuple>>::into_py((timestamp, tzinfo), py).into_bound(py);
 
-        // safety ensure API is loaded
-        let _api = ensure_datetime_api(py)?;
+        #[cfg(not(Py_LIMITED_API))]
+        {
+            // safety ensure API is loaded
+            let _api = ensure_datetime_api(py)?;
 
-        unsafe {
-            PyDateTime_FromTimestamp(args.as_ptr())
-                .assume_owned_or_err(py)
-                .downcast_into_unchecked()
+            unsafe {
+                PyDateTime_FromTimestamp(args.as_ptr())
+                    .assume_owned_or_err(py)
+                    .downcast_into_unchecked()
+            }
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            let datetime = DatetimeTypes::try_get(py)?
+                .datetime
+                .bind(py)
+                .call_method1("fromtimestamp", args)?;
+            Ok(unsafe { datetime.downcast_into_unchecked() })
         }
     }
 }
@@ -480,15 +647,36 @@ impl PyDateAccess for PyDateTime {
 
 impl PyDateAccess for Bound<'_, PyDateTime> {
     fn get_year(&self) -> i32 {
-        unsafe { PyDateTime_GET_YEAR(self.as_ptr()) }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_GET_YEAR(self.as_ptr())
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "year"))
+        }
     }
 
     fn get_month(&self) -> u8 {
-        unsafe { PyDateTime_GET_MONTH(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_GET_MONTH(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "month"))
+        }
     }
 
     fn get_day(&self) -> u8 {
-        unsafe { PyDateTime_GET_DAY(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_GET_DAY(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "day"))
+        }
     }
 }
 
@@ -517,23 +705,58 @@ impl PyTimeAccess for PyDateTime {
 
 impl PyTimeAccess for Bound<'_, PyDateTime> {
     fn get_hour(&self) -> u8 {
-        unsafe { PyDateTime_DATE_GET_HOUR(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_DATE_GET_HOUR(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "hour"))
+        }
     }
 
     fn get_minute(&self) -> u8 {
-        unsafe { PyDateTime_DATE_GET_MINUTE(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_DATE_GET_MINUTE(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "minute"))
+        }
     }
 
     fn get_second(&self) -> u8 {
-        unsafe { PyDateTime_DATE_GET_SECOND(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_DATE_GET_SECOND(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "second"))
+        }
     }
 
     fn get_microsecond(&self) -> u32 {
-        unsafe { PyDateTime_DATE_GET_MICROSECOND(self.as_ptr()) as u32 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_DATE_GET_MICROSECOND(self.as_ptr()) as u32
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "microsecond"))
+        }
     }
 
     fn get_fold(&self) -> bool {
-        unsafe { PyDateTime_DATE_GET_FOLD(self.as_ptr()) > 0 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_DATE_GET_FOLD(self.as_ptr()) > 0
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component::<u8>(self.as_any(), intern!(self.py(), "fold")) != 0
+        }
     }
 }
 
@@ -546,8 +769,9 @@ impl<'py> PyTzInfoAccess<'py> for &'py PyDateTime {
 
 impl<'py> PyTzInfoAccess<'py> for Bound<'py, PyDateTime> {
     fn get_tzinfo_bound(&self) -> Option<Bound<'py, PyTzInfo>> {
+        #[cfg(not(Py_LIMITED_API))]
         let ptr = self.as_ptr() as *mut ffi::PyDateTime_DateTime;
-        #[cfg(not(GraalPy))]
+        #[cfg(all(not(Py_LIMITED_API), not(GraalPy)))]
         unsafe {
             if (*ptr).hastzinfo != 0 {
                 Some(
@@ -575,12 +799,24 @@ impl<'py> PyTzInfoAccess<'py> for Bound<'py, PyDateTime> {
                 )
             }
         }
+
+        #[cfg(Py_LIMITED_API)]
+        {
+            let tzinfo: Bound<'py, PyAny> =
+                get_component(self.as_any(), intern!(self.py(), "tzinfo"));
+            if tzinfo.is_none() {
+                None
+            } else {
+                Some(unsafe { tzinfo.downcast_into_unchecked() })
+            }
+        }
     }
 }
 
 /// Bindings for `datetime.time`
 #[repr(transparent)]
 pub struct PyTime(PyAny);
+#[cfg(not(Py_LIMITED_API))]
 pyobject_native_type!(
     PyTime,
     crate::ffi::PyDateTime_Time,
@@ -588,6 +824,12 @@ pyobject_native_type!(
     #module=Some("datetime"),
     #checkfunction=PyTime_Check
 );
+#[cfg(Py_LIMITED_API)]
+pyobject_native_type_core!(
+    PyTime,
+    |py| DatetimeTypes::get(py).time.bind(py).as_type_ptr(),
+    #module=Some("datetime")
+);
 
 impl PyTime {
     /// Deprecated form of [`PyTime::new_bound`].
@@ -624,18 +866,25 @@ impl PyTime {
         microsecond: u32,
         tzinfo: Option<&Bound<'py, PyTzInfo>>,
     ) -> PyResult<Bound<'py, PyTime>> {
-        let api = ensure_datetime_api(py)?;
-        unsafe {
-            (api.Time_FromTime)(
-                c_int::from(hour),
-                c_int::from(minute),
-                c_int::from(second),
-                microsecond as c_int,
-                opt_to_pyobj(tzinfo),
-                api.TimeType,
-            )
-            .assume_owned_or_err(py)
-            .downcast_into_unchecked()
+        #[cfg(not(Py_LIMITED_API))]
+        {
+            let api = ensure_datetime_api(py)?;
+            unsafe {
+                (api.Time_FromTime)(
+                    c_int::from(hour),
+                    c_int::from(minute),
+                    c_int::from(second),
+                    microsecond as c_int,
+                    opt_to_pyobj(tzinfo),
+                    api.TimeType,
+                )
+                .assume_owned_or_err(py)
+                .downcast_into_unchecked()
+            }
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            Self::new_bound_with_fold(py, hour, minute, second, microsecond, tzinfo, false)
         }
     }
 
@@ -676,19 +925,31 @@ impl PyTime {
         tzinfo: Option<&Bound<'py, PyTzInfo>>,
         fold: bool,
     ) -> PyResult<Bound<'py, PyTime>> {
-        let api = ensure_datetime_api(py)?;
-        unsafe {
-            (api.Time_FromTimeAndFold)(
-                c_int::from(hour),
-                c_int::from(minute),
-                c_int::from(second),
-                microsecond as c_int,
-                opt_to_pyobj(tzinfo),
-                fold as c_int,
-                api.TimeType,
-            )
-            .assume_owned_or_err(py)
-            .downcast_into_unchecked()
+        #[cfg(not(Py_LIMITED_API))]
+        {
+            let api = ensure_datetime_api(py)?;
+            unsafe {
+                (api.Time_FromTimeAndFold)(
+                    c_int::from(hour),
+                    c_int::from(minute),
+                    c_int::from(second),
+                    microsecond as c_int,
+                    opt_to_pyobj(tzinfo),
+                    fold as c_int,
+                    api.TimeType,
+                )
+                .assume_owned_or_err(py)
+                .downcast_into_unchecked()
+            }
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            let kwargs = [("fold", u8::from(fold))].into_py_dict_bound(py);
+            let time = DatetimeTypes::try_get(py)?
+                .time
+                .bind(py)
+                .call((hour, minute, second, microsecond, tzinfo), Some(&kwargs))?;
+            Ok(unsafe { time.downcast_into_unchecked() })
         }
     }
 }
@@ -718,23 +979,58 @@ impl PyTimeAccess for PyTime {
 
 impl PyTimeAccess for Bound<'_, PyTime> {
     fn get_hour(&self) -> u8 {
-        unsafe { PyDateTime_TIME_GET_HOUR(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_TIME_GET_HOUR(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "hour"))
+        }
     }
 
     fn get_minute(&self) -> u8 {
-        unsafe { PyDateTime_TIME_GET_MINUTE(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_TIME_GET_MINUTE(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "minute"))
+        }
     }
 
     fn get_second(&self) -> u8 {
-        unsafe { PyDateTime_TIME_GET_SECOND(self.as_ptr()) as u8 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_TIME_GET_SECOND(self.as_ptr()) as u8
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "second"))
+        }
     }
 
     fn get_microsecond(&self) -> u32 {
-        unsafe { PyDateTime_TIME_GET_MICROSECOND(self.as_ptr()) as u32 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_TIME_GET_MICROSECOND(self.as_ptr()) as u32
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "microsecond"))
+        }
     }
 
     fn get_fold(&self) -> bool {
-        unsafe { PyDateTime_TIME_GET_FOLD(self.as_ptr()) != 0 }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_TIME_GET_FOLD(self.as_ptr()) != 0
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component::<u8>(self.as_any(), intern!(self.py(), "fold")) != 0
+        }
     }
 }
 
@@ -747,8 +1043,9 @@ impl<'py> PyTzInfoAccess<'py> for &'py PyTime {
 
 impl<'py> PyTzInfoAccess<'py> for Bound<'py, PyTime> {
     fn get_tzinfo_bound(&self) -> Option<Bound<'py, PyTzInfo>> {
+        #[cfg(not(Py_LIMITED_API))]
         let ptr = self.as_ptr() as *mut ffi::PyDateTime_Time;
-        #[cfg(not(GraalPy))]
+        #[cfg(all(not(Py_LIMITED_API), not(GraalPy)))]
         unsafe {
             if (*ptr).hastzinfo != 0 {
                 Some(
@@ -776,6 +1073,17 @@ impl<'py> PyTzInfoAccess<'py> for Bound<'py, PyTime> {
                 )
             }
         }
+
+        #[cfg(Py_LIMITED_API)]
+        {
+            let tzinfo: Bound<'py, PyAny> =
+                get_component(self.as_any(), intern!(self.py(), "tzinfo"));
+            if tzinfo.is_none() {
+                None
+            } else {
+                Some(unsafe { tzinfo.downcast_into_unchecked() })
+            }
+        }
     }
 }
 
@@ -786,6 +1094,7 @@ impl<'py> PyTzInfoAccess<'py> for Bound<'py, PyTime> {
 /// the [`zoneinfo` module](https://docs.python.org/3/library/zoneinfo.html).
 #[repr(transparent)]
 pub struct PyTzInfo(PyAny);
+#[cfg(not(Py_LIMITED_API))]
 pyobject_native_type!(
     PyTzInfo,
     crate::ffi::PyObject,
@@ -793,6 +1102,12 @@ pyobject_native_type!(
     #module=Some("datetime"),
     #checkfunction=PyTZInfo_Check
 );
+#[cfg(Py_LIMITED_API)]
+pyobject_native_type_core!(
+    PyTzInfo,
+    |py| DatetimeTypes::get(py).tzinfo.bind(py).as_type_ptr(),
+    #module=Some("datetime")
+);
 
 /// Deprecated form of [`timezone_utc_bound`].
 #[cfg(feature = "gil-refs")]
@@ -809,6 +1124,7 @@ pub fn timezone_utc_bound(py: Python<'_>) -> Bound<'_, PyTzInfo> {
     // TODO: this _could_ have a borrowed form `timezone_utc_borrowed`, but that seems
     // like an edge case optimization and we'd prefer in PyO3 0.21 to use `Bound` as
     // much as possible
+    #[cfg(not(Py_LIMITED_API))]
     unsafe {
         expect_datetime_api(py)
             .TimeZone_UTC
@@ -816,12 +1132,20 @@ pub fn timezone_utc_bound(py: Python<'_>) -> Bound<'_, PyTzInfo> {
             .to_owned()
             .downcast_into_unchecked()
     }
+    #[cfg(Py_LIMITED_API)]
+    unsafe {
+        DatetimeTypes::get(py)
+            .timezone_utc
+            .bind(py)
+            .clone()
+            .downcast_into_unchecked()
+    }
 }
 
 /// Equivalent to `datetime.timezone` constructor
 ///
 /// Only used internally
-#[cfg(feature = "chrono")]
+#[cfg(all(feature = "chrono", not(Py_LIMITED_API)))]
 pub(crate) fn timezone_from_offset<'py>(
     offset: &Bound<'py, PyDelta>,
 ) -> PyResult<Bound<'py, PyTzInfo>> {
@@ -837,6 +1161,7 @@ pub(crate) fn timezone_from_offset<'py>(
 /// Bindings for `datetime.timedelta`
 #[repr(transparent)]
 pub struct PyDelta(PyAny);
+#[cfg(not(Py_LIMITED_API))]
 pyobject_native_type!(
     PyDelta,
     crate::ffi::PyDateTime_Delta,
@@ -844,6 +1169,12 @@ pyobject_native_type!(
     #module=Some("datetime"),
     #checkfunction=PyDelta_Check
 );
+#[cfg(Py_LIMITED_API)]
+pyobject_native_type_core!(
+    PyDelta,
+    |py| DatetimeTypes::get(py).timedelta.bind(py).as_type_ptr(),
+    #module=Some("datetime")
+);
 
 impl PyDelta {
     /// Deprecated form of [`PyDelta::new_bound`].
@@ -870,17 +1201,31 @@ impl PyDelta {
         microseconds: i32,
         normalize: bool,
     ) -> PyResult<Bound<'_, PyDelta>> {
-        let api = ensure_datetime_api(py)?;
-        unsafe {
-            (api.Delta_FromDelta)(
-                days as c_int,
-                seconds as c_int,
-                microseconds as c_int,
-                normalize as c_int,
-                api.DeltaType,
-            )
-            .assume_owned_or_err(py)
-            .downcast_into_unchecked()
+        #[cfg(not(Py_LIMITED_API))]
+        {
+            let api = ensure_datetime_api(py)?;
+            unsafe {
+                (api.Delta_FromDelta)(
+                    days as c_int,
+                    seconds as c_int,
+                    microseconds as c_int,
+                    normalize as c_int,
+                    api.DeltaType,
+                )
+                .assume_owned_or_err(py)
+                .downcast_into_unchecked()
+            }
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            // the `timedelta` constructor always normalizes its arguments
+            let _ = normalize;
+            let delta = DatetimeTypes::try_get(py)?.timedelta.bind(py).call1((
+                days,
+                seconds,
+                microseconds,
+            ))?;
+            Ok(unsafe { delta.downcast_into_unchecked() })
         }
     }
 }
@@ -902,20 +1247,42 @@ impl PyDeltaAccess for PyDelta {
 
 impl PyDeltaAccess for Bound<'_, PyDelta> {
     fn get_days(&self) -> i32 {
-        unsafe { PyDateTime_DELTA_GET_DAYS(self.as_ptr()) }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_DELTA_GET_DAYS(self.as_ptr())
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "days"))
+        }
     }
 
     fn get_seconds(&self) -> i32 {
-        unsafe { PyDateTime_DELTA_GET_SECONDS(self.as_ptr()) }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_DELTA_GET_SECONDS(self.as_ptr())
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "seconds"))
+        }
     }
 
     fn get_microseconds(&self) -> i32 {
-        unsafe { PyDateTime_DELTA_GET_MICROSECONDS(self.as_ptr()) }
+        #[cfg(not(Py_LIMITED_API))]
+        unsafe {
+            PyDateTime_DELTA_GET_MICROSECONDS(self.as_ptr())
+        }
+        #[cfg(Py_LIMITED_API)]
+        {
+            get_component(self.as_any(), intern!(self.py(), "microseconds"))
+        }
     }
 }
 
 // Utility function which returns a borrowed reference to either
 // the underlying tzinfo or None.
+#[cfg(not(Py_LIMITED_API))]
 fn opt_to_pyobj(opt: Option<&Bound<'_, PyTzInfo>>) -> *mut ffi::PyObject {
     match opt {
         Some(tzi) => tzi.as_ptr(),
@@ -979,6 +1346,61 @@ mod tests {
         });
     }
 
+    #[test]
+    #[cfg_attr(target_arch = "wasm32", ignore)] // DateTime import fails on wasm for mysterious reasons
+    fn test_components() {
+        Python::with_gil(|py| {
+            let date = PyDate::new_bound(py, 2021, 1, 23).unwrap();
+            assert_eq!(
+                (date.get_year(), date.get_month(), date.get_day()),
+                (2021, 1, 23)
+            );
+
+            let dt =
+                PyDateTime::new_bound_with_fold(py, 2021, 1, 23, 20, 32, 40, 341516, None, true)
+                    .unwrap();
+            assert!(dt.is_instance_of::<PyDate>());
+            assert_eq!((dt.get_year(), dt.get_month(), dt.get_day()), (2021, 1, 23));
+            assert_eq!(
+                (
+                    dt.get_hour(),
+                    dt.get_minute(),
+                    dt.get_second(),
+                    dt.get_microsecond()
+                ),
+                (20, 32, 40, 341516)
+            );
+            assert!(dt.get_fold());
+
+            let time = PyTime::new_bound(py, 20, 32, 40, 341516, None).unwrap();
+            assert!(!time.is_instance_of::<PyDateTime>());
+            assert_eq!(
+                (
+                    time.get_hour(),
+                    time.get_minute(),
+                    time.get_second(),
+                    time.get_microsecond()
+                ),
+                (20, 32, 40, 341516)
+            );
+            assert!(!time.get_fold());
+
+            let delta = PyDelta::new_bound(py, 1, 86_401, -1, true).unwrap();
+            assert_eq!(
+                (
+                    delta.get_days(),
+                    delta.get_seconds(),
+                    delta.get_microseconds()
+                ),
+                (2, 0, 999_999)
+            );
+
+            let utc = timezone_utc_bound(py);
+            assert!(utc.is_instance_of::<PyTzInfo>());
+            assert!(!date.is_instance_of::<PyTzInfo>());
+        });
+    }
+
     #[test]
     #[cfg_attr(target_arch = "wasm32", ignore)] // DateTime import fails on wasm for mysterious reasons
     fn test_get_tzinfo() {
@@ -1004,7 +1426,7 @@ mod tests {
     }
 
     #[test]
-    #[cfg(all(feature = "macros", feature = "chrono"))]
+    #[cfg(all(feature = "macros", feature = "chrono", not(Py_LIMITED_API)))]
     #[cfg_attr(target_arch = "wasm32", ignore)] // DateTime import fails on wasm for mysterious reasons
     fn test_timezone_from_offset() {
         Python::with_gil(|py| {
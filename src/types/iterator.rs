@@ -2,6 +2,8 @@ use crate::ffi_ptr_ext::FfiPtrExt;
 use crate::instance::Borrowed;
 use crate::py_result_ext::PyResultExt;
 use crate::{ffi, Bound, PyAny, PyErr, PyResult, PyTypeCheck};
+#[cfg(feature = "macros")]
+use crate::{types::PyAnyMethods, IntoPy, PyObject, PyRefMut, Python};
 #[cfg(feature = "gil-refs")]
 use crate::{AsPyPointer, PyDowncastError, PyNativeType};
 
@@ -52,6 +54,58 @@ impl PyIterator {
                 .downcast_into_unchecked()
         }
     }
+
+    /// Creates a Python iterator yielding the items of a Rust iterator.
+    ///
+    /// The items are converted to Python objects lazily, as the Python iterator is advanced.
+    /// Functions in `#[pymethods]` and `#[pyfunction]`s declared to return `impl Iterator<..>`
+    /// use this function to convert their return value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    /// use pyo3::types::PyIterator;
+    ///
+    /// # fn main() -> PyResult<()> {
+    /// Python::with_gil(|py| -> PyResult<()> {
+    ///     let squares = PyIterator::from_rust_iter(py, (1..4).map(|i| i * i))?;
+    ///     pyo3::py_run!(py, squares, "assert list(squares) == [1, 4, 9]");
+    ///     Ok(())
+    /// })
+    /// # }
+    /// ```
+    #[cfg(feature = "macros")]
+    pub fn from_rust_iter<I>(py: Python<'_>, iter: I) -> PyResult<Bound<'_, PyIterator>>
+    where
+        I: IntoIterator,
+        I::IntoIter: Send + 'static,
+        I::Item: IntoPy<PyObject>,
+    {
+        let mut iter = iter.into_iter();
+        let next = Box::new(move |py: Python<'_>| iter.next().map(|item| item.into_py(py)));
+        let iter = Bound::new(py, RustIterator { next })?;
+        Ok(unsafe { iter.into_any().downcast_into_unchecked() })
+    }
+}
+
+/// Python iterator created by [`PyIterator::from_rust_iter`].
+#[cfg(feature = "macros")]
+#[crate::pyclass(crate = "crate")]
+struct RustIterator {
+    next: Box<dyn FnMut(Python<'_>) -> Option<PyObject> + Send>,
+}
+
+#[cfg(feature = "macros")]
+#[crate::pymethods(crate = "crate")]
+impl RustIterator {
+    fn __iter__(slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> Option<PyObject> {
+        (self.next)(py)
+    }
 }
 
 #[cfg(feature = "gil-refs")]
@@ -389,4 +443,49 @@ def fibonacci(target):
             assert_eq!(hint, (3, None));
         });
     }
+
+    #[test]
+    #[cfg(feature = "macros")]
+    fn from_rust_iter() {
+        Python::with_gil(|py| {
+            let it = PyIterator::from_rust_iter(py, vec!["a", "b"]).unwrap();
+            crate::py_run!(
+                py,
+                it,
+                r#"
+                    assert iter(it) is it
+                    assert next(it) == "a"
+                    assert list(it) == ["b"]
+                    assert next(it, None) is None
+                "#
+            );
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "macros")]
+    fn from_rust_iter_is_lazy() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        Python::with_gil(|py| {
+            let pulled = Arc::new(AtomicUsize::new(0));
+            let counter = pulled.clone();
+            let iter = PyIterator::from_rust_iter(
+                py,
+                (0..).inspect(move |_: &u64| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }),
+            )
+            .unwrap();
+            assert_eq!(pulled.load(Ordering::SeqCst), 0);
+
+            let first: Vec<u64> = iter
+                .take(3)
+                .map(|item| item.unwrap().extract().unwrap())
+                .collect();
+            assert_eq!(first, vec![0, 1, 2]);
+            assert_eq!(pulled.load(Ordering::SeqCst), 3);
+        });
+    }
 }
@@ -17,6 +17,15 @@ use crate::types::sequence::PySequenceMethods;
 #[repr(transparent)]
 pub struct PyList(PyAny);
 
+#[cfg(not(any(PyPy, GraalPy)))]
+pyobject_native_type!(
+    PyList,
+    ffi::PyListObject,
+    pyobject_native_static_type_object!(ffi::PyList_Type),
+    #checkfunction=ffi::PyList_Check
+);
+
+#[cfg(any(PyPy, GraalPy))]
 pyobject_native_type_core!(PyList, pyobject_native_static_type_object!(ffi::PyList_Type), #checkfunction=ffi::PyList_Check);
 
 #[inline]
@@ -1,6 +1,8 @@
 use crate::err::PyResult;
 use crate::ffi_ptr_ext::FfiPtrExt;
 use crate::py_result_ext::PyResultExt;
+#[cfg(all(feature = "macros", any(not(Py_LIMITED_API), Py_3_11)))]
+use crate::Python;
 use crate::{ffi, Bound, PyAny};
 #[cfg(feature = "gil-refs")]
 use crate::{AsPyPointer, PyNativeType};
@@ -31,6 +33,82 @@ impl PyMemoryView {
                 .downcast_into_unchecked()
         }
     }
+
+    /// Creates a read-only `memoryview` over bytes owned by Rust, without copying them.
+    ///
+    /// Python `bytes` objects always store their contents inline, so [`PyBytes`] constructors
+    /// have to copy, and a `PyBytes` cannot be created over existing Rust data. This is the
+    /// zero-copy alternative: it moves `data` (for example a `Vec<u8>`, a `Box<[u8]>` or a
+    /// `bytes::Bytes`) into a Python object exporting it through the buffer protocol, and
+    /// returns a view of it; `data` is dropped once the view and everything derived from it
+    /// are gone. Python code can read the view, or slice it, without copying, and calling
+    /// `bytes()` on it makes a copy.
+    ///
+    /// [`PyBytes`]: crate::types::PyBytes
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pyo3::prelude::*;
+    /// use pyo3::types::PyMemoryView;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let payload = vec![0u8; 1 << 20];
+    ///     let view = PyMemoryView::from_owned(py, payload).unwrap();
+    ///     pyo3::py_run!(py, view, "assert len(view) == 1 << 20 and view.readonly");
+    /// });
+    /// ```
+    #[cfg(all(feature = "macros", any(not(Py_LIMITED_API), Py_3_11)))]
+    pub fn from_owned<T>(py: Python<'_>, data: T) -> PyResult<Bound<'_, Self>>
+    where
+        T: AsRef<[u8]> + Send + 'static,
+    {
+        let owner = Bound::new(
+            py,
+            OwnedBuffer {
+                data: Box::new(data),
+            },
+        )?;
+        PyMemoryView::from_bound(owner.as_any())
+    }
+}
+
+/// Exports bytes owned by Rust through the buffer protocol, for [`PyMemoryView::from_owned`].
+#[cfg(all(feature = "macros", any(not(Py_LIMITED_API), Py_3_11)))]
+#[crate::pyclass(crate = "crate")]
+struct OwnedBuffer {
+    data: Box<dyn AsRef<[u8]> + Send>,
+}
+
+#[cfg(all(feature = "macros", any(not(Py_LIMITED_API), Py_3_11)))]
+#[crate::pymethods(crate = "crate")]
+impl OwnedBuffer {
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: std::os::raw::c_int,
+    ) -> PyResult<()> {
+        // The data is never mutated or moved while `slf` is alive, and the view keeps `slf`
+        // alive until it is released.
+        let (ptr, len) = {
+            let owner = slf.borrow();
+            let data = (*owner.data).as_ref();
+            (data.as_ptr(), data.len())
+        };
+        let result = ffi::PyBuffer_FillInfo(
+            view,
+            slf.as_ptr(),
+            ptr as *mut std::os::raw::c_void,
+            len as ffi::Py_ssize_t,
+            1,
+            flags,
+        );
+        if result == -1 {
+            Err(crate::PyErr::fetch(slf.py()))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 #[cfg(feature = "gil-refs")]
@@ -53,3 +131,128 @@ impl<'py> TryFrom<&Bound<'py, PyAny>> for Bound<'py, PyMemoryView> {
         PyMemoryView::from_bound(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::exceptions::PyTypeError;
+    use crate::prelude::*;
+    use crate::types::{PyBytes, PyMemoryView};
+
+    #[test]
+    fn test_memoryview_from_bytes() {
+        Python::with_gil(|py| {
+            let bytes = PyBytes::new_bound(py, b"abc");
+            let view = PyMemoryView::from_bound(&bytes).unwrap();
+            assert!(view.getattr("readonly").unwrap().extract::<bool>().unwrap());
+            assert_eq!(view.len().unwrap(), 3);
+            assert_eq!(
+                view.call_method0("tobytes")
+                    .unwrap()
+                    .extract::<&[u8]>()
+                    .unwrap(),
+                b"abc"
+            );
+        });
+    }
+
+    #[test]
+    fn test_memoryview_from_non_buffer() {
+        Python::with_gil(|py| {
+            let err = PyMemoryView::from_bound(&py.None().into_bound(py)).unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+
+            let err = Bound::<PyMemoryView>::try_from(&42.to_object(py).into_bound(py));
+            assert!(err.unwrap_err().is_instance_of::<PyTypeError>(py));
+        });
+    }
+
+    #[cfg(any(not(Py_LIMITED_API), Py_3_11))]
+    #[test]
+    fn test_memoryview_shares_buffer() {
+        use crate::buffer::PyBuffer;
+        use crate::types::PyByteArray;
+
+        Python::with_gil(|py| {
+            let bytearray = PyByteArray::new_bound(py, b"abc");
+            let view = PyMemoryView::from_bound(&bytearray).unwrap();
+
+            let buffer = PyBuffer::<u8>::get_bound(&view).unwrap();
+            assert!(!buffer.readonly());
+            assert_eq!(buffer.shape(), [3]);
+            let slice = buffer.as_mut_slice(py).unwrap();
+            slice[0].set(b'x');
+            buffer.release(py);
+
+            assert_eq!(unsafe { bytearray.as_bytes() }, b"xbc");
+        });
+    }
+
+    #[cfg(all(feature = "macros", any(not(Py_LIMITED_API), Py_3_11)))]
+    #[test]
+    fn test_memoryview_from_owned() {
+        use crate::buffer::PyBuffer;
+
+        Python::with_gil(|py| {
+            let data = vec![1u8, 2, 3];
+            let data_ptr = data.as_ptr();
+            let view = PyMemoryView::from_owned(py, data).unwrap();
+
+            let buffer = PyBuffer::<u8>::get_bound(&view).unwrap();
+            assert_eq!(buffer.buf_ptr() as *const u8, data_ptr);
+            assert!(buffer.readonly());
+            buffer.release(py);
+
+            crate::py_run!(
+                py,
+                view,
+                r#"
+                assert view.tobytes() == b"\x01\x02\x03"
+                assert bytes(view[1:]) == b"\x02\x03"
+                try:
+                    view[0] = 0
+                except TypeError:
+                    pass
+                else:
+                    assert False
+                "#
+            );
+
+            let empty = PyMemoryView::from_owned(py, Box::<[u8]>::default()).unwrap();
+            assert_eq!(empty.len().unwrap(), 0);
+        });
+    }
+
+    #[cfg(all(feature = "macros", any(not(Py_LIMITED_API), Py_3_11)))]
+    #[test]
+    fn test_memoryview_from_owned_drops_data() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        struct Payload(Vec<u8>, Arc<AtomicBool>);
+
+        impl AsRef<[u8]> for Payload {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl Drop for Payload {
+            fn drop(&mut self) {
+                self.1.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        Python::with_gil(|py| {
+            let view = PyMemoryView::from_owned(py, Payload(vec![0; 8], dropped.clone())).unwrap();
+            let sliced = view
+                .get_item(crate::types::PySlice::new_bound(py, 2, 4, 1))
+                .unwrap();
+            drop(view);
+            assert!(!dropped.load(Ordering::SeqCst));
+            assert_eq!(sliced.len().unwrap(), 2);
+            drop(sliced);
+            assert!(dropped.load(Ordering::SeqCst));
+        });
+    }
+}
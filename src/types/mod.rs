@@ -6,12 +6,11 @@ pub use self::bytearray::{PyByteArray, PyByteArrayMethods};
 pub use self::bytes::{PyBytes, PyBytesMethods};
 pub use self::capsule::{PyCapsule, PyCapsuleMethods};
 #[cfg(all(not(Py_LIMITED_API), not(PyPy), not(GraalPy)))]
-pub use self::code::PyCode;
+pub use self::code::{PyCode, PyCodeInput, PyCodeMethods};
 pub use self::complex::{PyComplex, PyComplexMethods};
 #[allow(deprecated)]
-#[cfg(all(not(Py_LIMITED_API), feature = "gil-refs"))]
+#[cfg(feature = "gil-refs")]
 pub use self::datetime::timezone_utc;
-#[cfg(not(Py_LIMITED_API))]
 pub use self::datetime::{
     timezone_utc_bound, PyDate, PyDateAccess, PyDateTime, PyDelta, PyDeltaAccess, PyTime,
     PyTimeAccess, PyTzInfo, PyTzInfoAccess,
@@ -38,9 +37,10 @@ pub use self::num::PyLong;
 pub use self::num::PyLong as PyInt;
 #[cfg(not(any(PyPy, GraalPy)))]
 pub use self::pysuper::PySuper;
+pub use self::range::{PyRange, PyRangeMethods};
 pub use self::sequence::{PySequence, PySequenceMethods};
 pub use self::set::{PySet, PySetMethods};
-pub use self::slice::{PySlice, PySliceIndices, PySliceMethods};
+pub use self::slice::{PySlice, PySliceIndices, PySliceMethods, SliceOrIndex};
 #[cfg(not(Py_LIMITED_API))]
 pub use self::string::PyStringData;
 pub use self::string::{PyString, PyString as PyUnicode, PyStringMethods};
@@ -316,6 +316,7 @@ macro_rules! pyobject_native_type_sized {
             type Initializer = $crate::pyclass_init::PyNativeTypeInitializer<Self>;
             type PyClassMutability = $crate::pycell::impl_::ImmutableClass;
         }
+        impl<$($generics,)*> $crate::impl_::pyclass::PyClassNativeBase for $name {}
     }
 }
 
@@ -338,9 +339,8 @@ pub(crate) mod bytearray;
 pub(crate) mod bytes;
 pub(crate) mod capsule;
 #[cfg(all(not(Py_LIMITED_API), not(PyPy), not(GraalPy)))]
-mod code;
+pub(crate) mod code;
 pub(crate) mod complex;
-#[cfg(not(Py_LIMITED_API))]
 pub(crate) mod datetime;
 pub(crate) mod dict;
 mod ellipsis;
@@ -359,6 +359,7 @@ mod notimplemented;
 mod num;
 #[cfg(not(any(PyPy, GraalPy)))]
 mod pysuper;
+pub(crate) mod range;
 pub(crate) mod sequence;
 pub(crate) mod set;
 pub(crate) mod slice;
@@ -4,14 +4,15 @@ use crate::ffi_ptr_ext::FfiPtrExt;
 use crate::py_result_ext::PyResultExt;
 use crate::pyclass::PyClass;
 use crate::types::{
-    any::PyAnyMethods, list::PyListMethods, PyAny, PyCFunction, PyDict, PyList, PyString,
+    any::PyAnyMethods, dict::PyDictMethods, list::PyListMethods, tuple::PyTupleMethods, PyAny,
+    PyCFunction, PyDict, PyList, PyString, PyStringMethods, PyWeakrefMethods, PyWeakrefReference,
 };
 use crate::{exceptions, ffi, Bound, IntoPy, Py, PyObject, Python};
 use std::ffi::CString;
 use std::str;
 
 #[cfg(feature = "gil-refs")]
-use {super::PyStringMethods, crate::PyNativeType};
+use crate::PyNativeType;
 
 /// Represents a Python [`module`][1] object.
 ///
@@ -155,6 +156,77 @@ impl PyModule {
                 .downcast_into()
         }
     }
+
+    /// Imports the Python source file at `path` as a module named `module_name`.
+    ///
+    /// This is the equivalent of the following Python code, and is useful to load scripts which
+    /// are not located in a directory of `sys.path`:
+    ///
+    /// ```python
+    /// import importlib.util, sys
+    ///
+    /// spec = importlib.util.spec_from_file_location(module_name, path)
+    /// module = importlib.util.module_from_spec(spec)
+    /// sys.modules[module_name] = module
+    /// spec.loader.exec_module(module)
+    /// ```
+    ///
+    /// Unlike [`PyModule::from_code_bound`], the module gets a proper `__spec__`, so relative
+    /// imports and code inspecting the module's file work as for regularly imported modules.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PyErr` if:
+    /// - `path` does not refer to a file Python can import, such as a `.py` file.
+    /// - Any Python exceptions are raised while executing the module. The module is then removed
+    ///   from `sys.modules` again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    ///
+    /// # fn main() -> PyResult<()> {
+    /// Python::with_gil(|py| -> PyResult<()> {
+    ///     let script = PyModule::import_from_path_bound(py, "script", "assets/script.py")?;
+    ///     assert!(script.getattr("__file__")?.extract::<String>()?.ends_with("script.py"));
+    ///     Ok(())
+    /// })
+    /// # }
+    /// ```
+    pub fn import_from_path_bound<'py>(
+        py: Python<'py>,
+        module_name: &str,
+        path: impl AsRef<std::path::Path>,
+    ) -> PyResult<Bound<'py, PyModule>> {
+        let path = path.as_ref();
+        let util = py.import_bound(intern!(py, "importlib.util"))?;
+        let spec =
+            util.call_method1(intern!(py, "spec_from_file_location"), (module_name, path))?;
+        if spec.is_none() {
+            return Err(exceptions::PyImportError::new_err(format!(
+                "cannot import module '{}' from {}",
+                module_name,
+                path.display()
+            )));
+        }
+        let module = util
+            .call_method1(intern!(py, "module_from_spec"), (&spec,))?
+            .downcast_into::<PyModule>()?;
+
+        let modules = py
+            .import_bound(intern!(py, "sys"))?
+            .getattr(intern!(py, "modules"))?;
+        modules.set_item(module_name, &module)?;
+        let result = spec
+            .getattr(intern!(py, "loader"))?
+            .call_method1(intern!(py, "exec_module"), (&module,));
+        if let Err(err) = result {
+            modules.del_item(module_name)?;
+            return Err(err);
+        }
+        Ok(module)
+    }
 }
 
 #[cfg(feature = "gil-refs")]
@@ -291,14 +363,18 @@ impl PyModule {
     ///
     /// This will result in the following output:
     /// ```text
-    /// Foo is <class 'builtins.Foo'>
+    /// Foo is <class 'my_module.Foo'>
     /// ```
     ///
+    /// Unless the class sets [`#[pyclass(module = "...")]`][2], its `__module__` is the name of
+    /// the first module it is added to.
+    ///
     /// Note that as we haven't defined a [constructor][1], Python code can't actually
     /// make an *instance* of `Foo` (or *get* one for that matter, as we haven't exported
     /// anything that can return instances of `Foo`).
     ///
     /// [1]: https://pyo3.rs/latest/class.html#constructor
+    /// [2]: https://pyo3.rs/latest/class.html#customizing-the-class
     pub fn add_class<T>(&self) -> PyResult<()>
     where
         T: PyClass,
@@ -496,14 +572,18 @@ pub trait PyModuleMethods<'py>: crate::sealed::Sealed {
     ///
     /// This will result in the following output:
     /// ```text
-    /// Foo is <class 'builtins.Foo'>
+    /// Foo is <class 'my_module.Foo'>
     /// ```
     ///
+    /// Unless the class sets [`#[pyclass(module = "...")]`][2], its `__module__` is the name of
+    /// the first module it is added to.
+    ///
     /// Note that as we haven't defined a [constructor][1], Python code can't actually
     /// make an *instance* of `Foo` (or *get* one for that matter, as we haven't exported
     /// anything that can return instances of `Foo`).
     ///
     /// [1]: https://pyo3.rs/latest/class.html#constructor
+    /// [2]: https://pyo3.rs/latest/class.html#customizing-the-class
     fn add_class<T>(&self) -> PyResult<()>
     where
         T: PyClass;
@@ -558,6 +638,50 @@ pub trait PyModuleMethods<'py>: crate::sealed::Sealed {
     /// [2]: https://github.com/PyO3/pyo3/issues/1517#issuecomment-808664021
     fn add_submodule(&self, module: &Bound<'_, PyModule>) -> PyResult<()>;
 
+    /// Adds a submodule which is only created when it is first accessed.
+    ///
+    /// The submodule named `name` is registered through a module-level `__getattr__`
+    /// ([PEP 562]), so `initializer` does not run when the parent module is imported. On first
+    /// access, e.g. `parent.name` or `from parent import name`, a module with the qualified
+    /// name `parent.name` is created, passed to `initializer`, stored as an attribute of the
+    /// parent and inserted into `sys.modules`. From then on `import parent.name` also finds it.
+    ///
+    /// If the module already has a `__getattr__`, for example a `#[pyfunction]` of that name,
+    /// it is called for all other attribute names, so it must be added before any lazy
+    /// submodules.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use pyo3::prelude::*;
+    ///
+    /// fn heavy(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    ///     module.add("answer", 42)
+    /// }
+    ///
+    /// #[pymodule]
+    /// fn my_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    ///     module.add_lazy_submodule("heavy", heavy)
+    /// }
+    /// #
+    /// # Python::with_gil(|py| {
+    /// #     let module = pyo3::wrap_pymodule!(my_module)(py);
+    /// #     pyo3::py_run!(py, module, r#"
+    /// #         import sys
+    /// #         assert "my_module.heavy" not in sys.modules
+    /// #         assert module.heavy.answer == 42
+    /// #         assert sys.modules["my_module.heavy"] is module.heavy
+    /// #     "#);
+    /// # });
+    /// ```
+    ///
+    /// [PEP 562]: https://peps.python.org/pep-0562/
+    fn add_lazy_submodule(
+        &self,
+        name: &str,
+        initializer: fn(&Bound<'_, PyModule>) -> PyResult<()>,
+    ) -> PyResult<()>;
+
     /// Add a function to a module.
     ///
     /// Note that this also requires the [`wrap_pyfunction!`][2] macro
@@ -635,7 +759,7 @@ impl<'py> PyModuleMethods<'py> for Bound<'py, PyModule> {
         #[cfg(PyPy)]
         {
             self.dict()
-                .get_item("__name__")
+                .get_item(__name__(self.py()))
                 .map_err(|_| exceptions::PyAttributeError::new_err("__name__"))?
                 .downcast_into()
                 .map_err(PyErr::from)
@@ -653,7 +777,7 @@ impl<'py> PyModuleMethods<'py> for Bound<'py, PyModule> {
         #[cfg(PyPy)]
         {
             self.dict()
-                .get_item("__file__")
+                .get_item(intern!(self.py(), "__file__"))
                 .map_err(|_| exceptions::PyAttributeError::new_err("__file__"))?
                 .downcast_into()
                 .map_err(PyErr::from)
@@ -670,10 +794,13 @@ impl<'py> PyModuleMethods<'py> for Bound<'py, PyModule> {
             name: Bound<'_, PyString>,
             value: Bound<'_, PyAny>,
         ) -> PyResult<()> {
-            module
-                .index()?
-                .append(&name)
-                .expect("could not append __name__ to __all__");
+            // PEP 562 hooks are looked up by the interpreter and are not public names.
+            if name != "__getattr__" && name != "__dir__" {
+                module
+                    .index()?
+                    .append(&name)
+                    .expect("could not append __name__ to __all__");
+            }
             module.setattr(name, value.into_py(module.py()))
         }
 
@@ -690,7 +817,14 @@ impl<'py> PyModuleMethods<'py> for Bound<'py, PyModule> {
         T: PyClass,
     {
         let py = self.py();
-        self.add(T::NAME, T::lazy_type_object().get_or_try_init(py)?)
+        let ty = T::lazy_type_object().get_or_try_init(py)?;
+        // Without `#[pyclass(module = "...")]`, the class would claim to live in `builtins`, which
+        // breaks pickling and makes for confusing reprs. Attribute it to the first module it is
+        // added to instead.
+        if T::MODULE.is_none() && ty.getattr(__module__(py))?.eq(intern!(py, "builtins"))? {
+            ty.setattr(__module__(py), self.name()?)?;
+        }
+        self.add(T::NAME, ty)
     }
 
     fn add_wrapped<T>(&self, wrapper: &impl Fn(Python<'py>) -> T) -> PyResult<()>
@@ -711,6 +845,54 @@ impl<'py> PyModuleMethods<'py> for Bound<'py, PyModule> {
         self.add(name, module)
     }
 
+    fn add_lazy_submodule(
+        &self,
+        name: &str,
+        initializer: fn(&Bound<'_, PyModule>) -> PyResult<()>,
+    ) -> PyResult<()> {
+        let py = self.py();
+        let name = name.to_owned();
+        let qualified_name = format!("{}.{}", self.name()?.to_cow()?, name);
+        // A weak reference avoids a cycle through the module's own `__getattr__`.
+        let parent = PyWeakrefReference::new_bound(self.as_any())?.unbind();
+        let previous = self.dict().get_item(__getattr__(py))?.map(Bound::unbind);
+
+        self.index()?.append(&name)?;
+        let getattr = PyCFunction::new_closure_bound(
+            py,
+            Some("__getattr__"),
+            None,
+            move |args, _kwargs| -> PyResult<PyObject> {
+                let py = args.py();
+                let attr = args.get_item(0)?;
+                if attr.eq(&name)? {
+                    let submodule = PyModule::new_bound(py, &qualified_name)?;
+                    initializer(&submodule)?;
+                    if let Some(parent) = parent.bind(py).upgrade() {
+                        parent.setattr(name.as_str(), &submodule)?;
+                    }
+                    py.import_bound(intern!(py, "sys"))?
+                        .getattr(intern!(py, "modules"))?
+                        .set_item(&qualified_name, &submodule)?;
+                    return Ok(submodule.into_any().unbind());
+                }
+                match &previous {
+                    Some(previous) => previous.call1(py, (attr,)),
+                    None => {
+                        let parent_name = qualified_name
+                            .rsplit_once('.')
+                            .map_or(qualified_name.as_str(), |(parent, _)| parent);
+                        Err(exceptions::PyAttributeError::new_err(format!(
+                            "module '{}' has no attribute '{}'",
+                            parent_name, attr
+                        )))
+                    }
+                }
+            },
+        )?;
+        self.setattr(__getattr__(py), getattr)
+    }
+
     fn add_function(&self, fun: Bound<'_, PyCFunction>) -> PyResult<()> {
         let name = fun.getattr(__name__(self.py()))?;
         self.add(name.downcast_into::<PyString>()?, fun)
@@ -725,6 +907,14 @@ fn __name__(py: Python<'_>) -> &Bound<'_, PyString> {
     intern!(py, "__name__")
 }
 
+fn __getattr__(py: Python<'_>) -> &Bound<'_, PyString> {
+    intern!(py, "__getattr__")
+}
+
+fn __module__(py: Python<'_>) -> &Bound<'_, PyString> {
+    intern!(py, "__module__")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -0,0 +1,118 @@
+use crate::err::PyResult;
+use crate::ffi;
+use crate::types::any::PyAnyMethods;
+use crate::{Bound, PyAny, PyTypeInfo, Python};
+
+/// Represents a Python `range`.
+///
+/// Only `isize` bounds are supported by the accessors of the `PyRange` object.
+#[repr(transparent)]
+pub struct PyRange(PyAny);
+
+pyobject_native_type_core!(
+    PyRange,
+    pyobject_native_static_type_object!(ffi::PyRange_Type),
+    #checkfunction=ffi::PyRange_Check
+);
+
+impl PyRange {
+    /// Constructs a new range equivalent to the Python expression `range(start, stop, step)`.
+    ///
+    /// Fails with `ValueError` if `step` is zero.
+    pub fn new_bound(
+        py: Python<'_>,
+        start: isize,
+        stop: isize,
+        step: isize,
+    ) -> PyResult<Bound<'_, PyRange>> {
+        let range = PyRange::type_object_bound(py).call1((start, stop, step))?;
+        // SAFETY: calling the `range` type always returns an instance of `range`
+        Ok(unsafe { range.downcast_into_unchecked() })
+    }
+}
+
+/// Implementation of functionality for [`PyRange`].
+///
+/// These methods are defined for the `Bound<'py, PyRange>` smart pointer, so to use method call
+/// syntax these methods are separated into a trait, because stable Rust does not yet support
+/// `arbitrary_self_types`.
+#[doc(alias = "PyRange")]
+pub trait PyRangeMethods<'py>: crate::sealed::Sealed {
+    /// Returns the value of the `start` attribute of the range.
+    fn start(&self) -> PyResult<isize>;
+
+    /// Returns the value of the `stop` attribute of the range.
+    fn stop(&self) -> PyResult<isize>;
+
+    /// Returns the value of the `step` attribute of the range.
+    fn step(&self) -> PyResult<isize>;
+}
+
+impl<'py> PyRangeMethods<'py> for Bound<'py, PyRange> {
+    fn start(&self) -> PyResult<isize> {
+        self.getattr(intern!(self.py(), "start"))?.extract()
+    }
+
+    fn stop(&self) -> PyResult<isize> {
+        self.getattr(intern!(self.py(), "stop"))?.extract()
+    }
+
+    fn step(&self) -> PyResult<isize> {
+        self.getattr(intern!(self.py(), "step"))?.extract()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exceptions::PyValueError;
+    use crate::types::PySequence;
+
+    #[test]
+    fn test_range_new() {
+        Python::with_gil(|py| {
+            let range = PyRange::new_bound(py, 1, 10, 3).unwrap();
+            assert_eq!(range.start().unwrap(), 1);
+            assert_eq!(range.stop().unwrap(), 10);
+            assert_eq!(range.step().unwrap(), 3);
+            assert_eq!(range.len().unwrap(), 3);
+            assert!(range.contains(7).unwrap());
+            assert!(!range.contains(8).unwrap());
+            let items: Vec<isize> = range.extract().unwrap();
+            assert_eq!(items, vec![1, 4, 7]);
+        });
+    }
+
+    #[test]
+    fn test_range_zero_step() {
+        Python::with_gil(|py| {
+            let err = PyRange::new_bound(py, 0, 10, 0).unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+        });
+    }
+
+    #[test]
+    fn test_range_downcast() {
+        Python::with_gil(|py| {
+            let range = py.eval_bound("range(-5, 5)", None, None).unwrap();
+            let range = range.downcast::<PyRange>().unwrap();
+            assert_eq!(range.start().unwrap(), -5);
+            assert_eq!(range.step().unwrap(), 1);
+            assert!(range.is_sequence());
+
+            let list = py.eval_bound("[1, 2, 3]", None, None).unwrap();
+            assert!(list.downcast::<PyRange>().is_err());
+            assert!(list.downcast::<PySequence>().is_ok());
+        });
+    }
+
+    #[test]
+    fn test_range_large_bounds() {
+        Python::with_gil(|py| {
+            let range = py.eval_bound("range(2 ** 100)", None, None).unwrap();
+            let range = range.downcast::<PyRange>().unwrap();
+            assert_eq!(range.start().unwrap(), 0);
+            assert!(range.stop().is_err());
+        });
+    }
+}
@@ -8,7 +8,10 @@ use crate::internal_tricks::get_ssize_index;
 use crate::py_result_ext::PyResultExt;
 use crate::sync::GILOnceCell;
 use crate::type_object::PyTypeInfo;
-use crate::types::{any::PyAnyMethods, PyAny, PyList, PyString, PyTuple, PyType};
+use crate::types::{
+    any::PyAnyMethods, list::PyListMethods, tuple::PyTupleMethods, PyAny, PyList, PyString,
+    PyTuple, PyType,
+};
 #[cfg(feature = "gil-refs")]
 use crate::{err::PyDowncastError, PyNativeType};
 use crate::{ffi, FromPyObject, Py, PyTypeCheck, Python, ToPyObject};
@@ -506,6 +509,25 @@ fn extract_sequence<'py, T>(obj: &Bound<'py, PyAny>) -> PyResult<Vec<T>>
 where
     T: FromPyObject<'py>,
 {
+    // Like `PySequence_Fast`, read the items of exact lists and tuples directly rather than through
+    // the iterator protocol. Subclasses may override `__iter__`, so they take the generic path.
+    if let Ok(list) = obj.downcast_exact::<PyList>() {
+        let mut v = Vec::with_capacity(list.len());
+        // The list iterator checks the length on every step, so this is still correct if
+        // extracting an item runs Python code which modifies the list.
+        for item in list {
+            v.push(item.extract::<T>()?);
+        }
+        return Ok(v);
+    }
+    if let Ok(tuple) = obj.downcast_exact::<PyTuple>() {
+        let mut v = Vec::with_capacity(tuple.len());
+        for item in tuple.iter_borrowed() {
+            v.push(item.extract::<T>()?);
+        }
+        return Ok(v);
+    }
+
     // Types that pass `PySequence_Check` usually implement enough of the sequence protocol
     // to support this function and if not, we will only fail extraction safely.
     let seq = unsafe {
@@ -1078,6 +1100,32 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_extract_list_subclass_to_vec() {
+        Python::with_gil(|py| {
+            // Subclasses don't take the fast path, so an overridden `__iter__` is respected
+            let v: Vec<i32> = py
+                .eval_bound(
+                    "type('L', (list,), {'__iter__': lambda self: iter([3, 4])})([1, 2])",
+                    None,
+                    None,
+                )
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(v == [3, 4]);
+        });
+    }
+
+    #[test]
+    fn test_extract_list_to_vec_error() {
+        Python::with_gil(|py| {
+            let list = py.eval_bound("[1, 2, 'three']", None, None).unwrap();
+            let err = list.extract::<Vec<i32>>().unwrap_err();
+            assert!(err.is_instance_of::<crate::exceptions::PyTypeError>(py));
+        });
+    }
+
     #[test]
     fn test_extract_bytearray_to_vec() {
         Python::with_gil(|py| {
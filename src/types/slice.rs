@@ -1,10 +1,12 @@
 use crate::err::{PyErr, PyResult};
+use crate::exceptions::PyTypeError;
 use crate::ffi;
 use crate::ffi_ptr_ext::FfiPtrExt;
 use crate::types::any::PyAnyMethods;
+use crate::types::PyTypeMethods;
 #[cfg(feature = "gil-refs")]
 use crate::PyNativeType;
-use crate::{Bound, PyAny, PyObject, Python, ToPyObject};
+use crate::{Bound, FromPyObject, PyAny, PyObject, Python, ToPyObject};
 
 /// Represents a Python `slice`.
 ///
@@ -150,9 +152,110 @@ impl ToPyObject for PySliceIndices {
     }
 }
 
+/// An argument to `__getitem__`, `__setitem__` or `__delitem__` which is either an integer index
+/// or a [`PySlice`].
+///
+/// This allows sequence-like `#[pyclass]` types to support both `obj[i]` and `obj[start:stop]`
+/// without inspecting the argument manually. Objects which are neither integers (or implement
+/// `__index__`) nor slices are rejected with a `TypeError`, as for the builtin sequences.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "macros")] {
+/// use pyo3::prelude::*;
+/// use pyo3::types::SliceOrIndex;
+///
+/// #[pyclass]
+/// struct Numbers {
+///     values: Vec<i64>,
+/// }
+///
+/// #[pymethods]
+/// impl Numbers {
+///     fn __getitem__(&self, key: SliceOrIndex<'_>, py: Python<'_>) -> PyResult<PyObject> {
+///         match key {
+///             SliceOrIndex::Index(index) => {
+///                 let len = self.values.len() as isize;
+///                 let index = if index < 0 { index + len } else { index };
+///                 if index < 0 || index >= len {
+///                     return Err(pyo3::exceptions::PyIndexError::new_err("index out of range"));
+///                 }
+///                 Ok(self.values[index as usize].into_py(py))
+///             }
+///             SliceOrIndex::Slice(slice) => {
+///                 let indices = slice.indices(self.values.len() as isize)?;
+///                 let values: Vec<i64> = (0..indices.slicelength)
+///                     .map(|i| self.values[(indices.start + i as isize * indices.step) as usize])
+///                     .collect();
+///                 Ok(values.into_py(py))
+///             }
+///         }
+///     }
+/// }
+/// # Python::with_gil(|py| {
+/// #     let numbers = Py::new(py, Numbers { values: vec![1, 2, 3, 4] }).unwrap();
+/// #     pyo3::py_run!(py, numbers, "assert numbers[-1] == 4");
+/// #     pyo3::py_run!(py, numbers, "assert numbers[::-2] == [4, 2]");
+/// # });
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum SliceOrIndex<'py> {
+    /// A slice, such as `1:10:2`.
+    Slice(Bound<'py, PySlice>),
+    /// An integer index, which may be negative.
+    Index(isize),
+}
+
+impl<'py> FromPyObject<'py> for SliceOrIndex<'py> {
+    fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(slice) = obj.downcast::<PySlice>() {
+            return Ok(SliceOrIndex::Slice(slice.clone()));
+        }
+        match obj.extract() {
+            Ok(index) => Ok(SliceOrIndex::Index(index)),
+            Err(err) if err.is_instance_of::<PyTypeError>(obj.py()) => {
+                Err(PyTypeError::new_err(format!(
+                    "indices must be integers or slices, not {}",
+                    obj.get_type().qualname()?
+                )))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::PyString;
+
+    #[test]
+    fn test_slice_or_index_extract() {
+        Python::with_gil(|py| {
+            let slice = PySlice::new_bound(py, 1, 10, 2);
+            match slice.extract::<SliceOrIndex<'_>>().unwrap() {
+                SliceOrIndex::Slice(s) => assert!(s.is(&slice)),
+                SliceOrIndex::Index(_) => panic!("expected a slice"),
+            }
+
+            let index = (-3).to_object(py).into_bound(py);
+            assert!(matches!(
+                index.extract::<SliceOrIndex<'_>>().unwrap(),
+                SliceOrIndex::Index(-3)
+            ));
+
+            let err = PyString::new_bound(py, "foo")
+                .extract::<SliceOrIndex<'_>>()
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+            assert_eq!(
+                err.value_bound(py).to_string(),
+                "indices must be integers or slices, not str"
+            );
+        });
+    }
 
     #[test]
     fn test_py_slice_new() {
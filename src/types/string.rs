@@ -320,6 +320,14 @@ pub trait PyStringMethods<'py>: crate::sealed::Sealed {
     /// Encodes this string as a Python `bytes` object, using UTF-8 encoding.
     fn encode_utf8(&self) -> PyResult<Bound<'py, PyBytes>>;
 
+    /// Gets the UTF-8 data of the string, if the string is already stored as UTF-8.
+    ///
+    /// This is the case for ASCII strings, and for other strings once their UTF-8 representation
+    /// has been cached (e.g. by [`to_str`](PyStringMethods::to_str)). Returns `None` otherwise;
+    /// this function never encodes the string or allocates.
+    #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
+    fn as_bytes(&self) -> Option<&[u8]>;
+
     /// Obtains the raw data backing the Python string.
     ///
     /// If the Python string object was created through legacy APIs, its internal storage format
@@ -360,6 +368,11 @@ impl<'py> PyStringMethods<'py> for Bound<'py, PyString> {
         }
     }
 
+    #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
+    fn as_bytes(&self) -> Option<&[u8]> {
+        self.as_borrowed().as_bytes()
+    }
+
     #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
     unsafe fn data(&self) -> PyResult<PyStringData<'_>> {
         self.as_borrowed().data()
@@ -370,6 +383,16 @@ impl<'a> Borrowed<'a, '_, PyString> {
     #[cfg(any(Py_3_10, not(Py_LIMITED_API)))]
     #[allow(clippy::wrong_self_convention)]
     pub(crate) fn to_str(self) -> PyResult<&'a str> {
+        // Fast path for strings already stored as UTF-8, which can be borrowed directly.
+        // (Keyword argument extraction relies on this raising `TypeError` for non-`str` objects,
+        // so they are left to `PyUnicode_AsUTF8AndSize`.)
+        #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
+        if unsafe { ffi::PyUnicode_Check(self.as_ptr()) } != 0 {
+            if let Some(bytes) = self.as_bytes() {
+                return Ok(unsafe { str::from_utf8_unchecked(bytes) });
+            }
+        }
+
         // PyUnicode_AsUTF8AndSize only available on limited API starting with 3.10.
         let mut size: ffi::Py_ssize_t = 0;
         let data: *const u8 =
@@ -423,6 +446,38 @@ impl<'a> Borrowed<'a, '_, PyString> {
         Cow::Owned(String::from_utf8_lossy(bytes.as_bytes()).into_owned())
     }
 
+    #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
+    pub(crate) fn as_bytes(self) -> Option<&'a [u8]> {
+        let ptr = self.as_ptr();
+        unsafe {
+            #[cfg(not(Py_3_12))]
+            if ffi::PyUnicode_IS_READY(ptr) == 0 {
+                return None;
+            }
+
+            if ffi::PyUnicode_IS_COMPACT_ASCII(ptr) != 0 {
+                // ASCII data is valid UTF-8.
+                let length = ffi::PyUnicode_GET_LENGTH(ptr) as usize;
+                Some(std::slice::from_raw_parts(
+                    ffi::PyUnicode_DATA(ptr) as *const u8,
+                    length,
+                ))
+            } else {
+                // All other strings have a (possibly null) cached UTF-8 representation.
+                let compact = ptr as *mut ffi::PyCompactUnicodeObject;
+                let utf8 = (*compact).utf8;
+                if utf8.is_null() {
+                    None
+                } else {
+                    Some(std::slice::from_raw_parts(
+                        utf8 as *const u8,
+                        (*compact).utf8_length as usize,
+                    ))
+                }
+            }
+        }
+    }
+
     #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
     unsafe fn data(self) -> PyResult<PyStringData<'a>> {
         let ptr = self.as_ptr();
@@ -873,6 +928,24 @@ mod tests {
         })
     }
 
+    #[test]
+    #[cfg(not(any(Py_LIMITED_API, GraalPy, PyPy)))]
+    fn test_as_bytes() {
+        Python::with_gil(|py| {
+            let ascii = PyString::new_bound(py, "ascii");
+            assert_eq!(ascii.as_bytes(), Some(&b"ascii"[..]));
+
+            let s = PyString::new_bound(py, "ascii 🐈");
+            // The UTF-8 representation of a non-ASCII string is only cached once requested.
+            let utf8 = s.to_str().unwrap();
+            assert_eq!(s.as_bytes(), Some(utf8.as_bytes()));
+
+            let not_cached = PyString::new_bound(py, "ascii 🐈").repr().unwrap();
+            assert_eq!(not_cached.as_bytes(), None);
+            assert_eq!(not_cached.to_str().unwrap(), "'ascii 🐈'");
+        })
+    }
+
     #[test]
     fn test_py_to_str_surrogate() {
         Python::with_gil(|py| {
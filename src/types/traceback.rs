@@ -19,7 +19,8 @@ impl PyTraceback {
     /// Formats the traceback as a string.
     ///
     /// This does not include the exception type and value. The exception type and value can be
-    /// formatted using the `Display` implementation for `PyErr`.
+    /// formatted using the `Display` implementation for `PyErr`, or together with the traceback
+    /// and any chained exceptions using [`PyErr::format`](crate::PyErr::format).
     ///
     /// # Example
     ///
@@ -62,7 +63,8 @@ pub trait PyTracebackMethods<'py>: crate::sealed::Sealed {
     /// Formats the traceback as a string.
     ///
     /// This does not include the exception type and value. The exception type and value can be
-    /// formatted using the `Display` implementation for `PyErr`.
+    /// formatted using the `Display` implementation for `PyErr`, or together with the traceback
+    /// and any chained exceptions using [`PyErr::format`](crate::PyErr::format).
     ///
     /// # Example
     ///
@@ -1,7 +1,8 @@
 use crate::err::{self, PyResult};
 use crate::instance::Borrowed;
 use crate::types::any::PyAnyMethods;
-use crate::types::PyTuple;
+use crate::types::string::PyStringMethods;
+use crate::types::{PyString, PyTuple};
 #[cfg(feature = "gil-refs")]
 use crate::PyNativeType;
 use crate::{ffi, Bound, PyAny, PyTypeInfo, Python};
@@ -116,6 +117,18 @@ pub trait PyTypeMethods<'py>: crate::sealed::Sealed {
     /// Gets the [qualified name](https://docs.python.org/3/glossary.html#term-qualified-name) of the `PyType`.
     fn qualname(&self) -> PyResult<String>;
 
+    /// Gets the name of the module defining the `PyType`.
+    ///
+    /// Equivalent to the Python expression `self.__module__`.
+    fn module(&self) -> PyResult<Bound<'py, PyString>>;
+
+    /// Gets the qualified name of the `PyType` prefixed with its module, e.g. `collections.OrderedDict`.
+    ///
+    /// As in the `repr` of a type, the module is left out for types defined in `builtins` or
+    /// `__main__`. Unlike [`name`](PyTypeMethods::name), the result is the same with and without
+    /// the `abi3` feature.
+    fn fully_qualified_name(&self) -> PyResult<String>;
+
     /// Checks whether `self` is a subclass of `other`.
     ///
     /// Equivalent to the Python expression `issubclass(self, other)`.
@@ -169,6 +182,23 @@ impl<'py> PyTypeMethods<'py> for Bound<'py, PyType> {
         name
     }
 
+    fn module(&self) -> PyResult<Bound<'py, PyString>> {
+        self.getattr(intern!(self.py(), "__module__"))?
+            .downcast_into()
+            .map_err(Into::into)
+    }
+
+    fn fully_qualified_name(&self) -> PyResult<String> {
+        let module = self.module()?;
+        let qualname = self.qualname()?;
+        let module = module.to_cow()?;
+        if module == "builtins" || module == "__main__" {
+            Ok(qualname)
+        } else {
+            Ok(format!("{}.{}", module, qualname))
+        }
+    }
+
     /// Checks whether `self` is a subclass of `other`.
     ///
     /// Equivalent to the Python expression `issubclass(self, other)`.
@@ -268,7 +298,7 @@ impl<'a> Borrowed<'a, '_, PyType> {
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{PyAnyMethods, PyBool, PyInt, PyLong, PyTuple, PyTypeMethods};
+    use crate::types::{PyAnyMethods, PyBool, PyInt, PyLong, PyTuple, PyType, PyTypeMethods};
     use crate::PyAny;
     use crate::Python;
 
@@ -291,6 +321,36 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_type_names() {
+        Python::with_gil(|py| {
+            let bool_type = py.get_type_bound::<PyBool>();
+            assert_eq!(bool_type.qualname().unwrap(), "bool");
+            assert_eq!(bool_type.module().unwrap(), "builtins");
+            assert_eq!(bool_type.fully_qualified_name().unwrap(), "bool");
+
+            let nested = py
+                .run_bound("class Outer:\n    class Inner: pass", None, None)
+                .and_then(|()| py.eval_bound("Outer.Inner", None, None))
+                .unwrap();
+            let nested = nested.downcast::<PyType>().unwrap();
+            assert_eq!(nested.qualname().unwrap(), "Outer.Inner");
+            assert_eq!(nested.module().unwrap(), "__main__");
+            assert_eq!(nested.fully_qualified_name().unwrap(), "Outer.Inner");
+
+            let ordered_dict = py
+                .import_bound("collections")
+                .unwrap()
+                .getattr("OrderedDict")
+                .unwrap();
+            let ordered_dict = ordered_dict.downcast::<PyType>().unwrap();
+            assert_eq!(
+                ordered_dict.fully_qualified_name().unwrap(),
+                "collections.OrderedDict"
+            );
+        });
+    }
+
     #[test]
     fn test_mro() {
         Python::with_gil(|py| {
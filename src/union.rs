@@ -0,0 +1,176 @@
+//! Extraction of arguments which accept several unrelated types.
+//!
+//! Python functions often accept "a `str` or an `int`" without the two types sharing a common
+//! base class. [`Union2`] and [`Union3`] extract such arguments without declaring a dedicated
+//! enum deriving [`FromPyObject`] for every combination of types.
+//!
+//! The types are tried in order, so the first one that matches is used. Put the most specific
+//! types first: for example `Union2<i64, f64>` extracts `1` as an `i64`, whereas
+//! `Union2<f64, i64>` would extract the same object as an `f64`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use pyo3::prelude::*;
+//! use pyo3::union::Union2;
+//!
+//! /// Accepts either an index or a name.
+//! #[pyfunction]
+//! fn lookup(key: Union2<usize, String>) -> String {
+//!     match key {
+//!         Union2::First(index) => format!("item #{}", index),
+//!         Union2::Second(name) => format!("item {:?}", name),
+//!     }
+//! }
+//! #
+//! # Python::with_gil(|py| {
+//! #     let lookup = wrap_pyfunction_bound!(lookup, py).unwrap();
+//! #     assert_eq!(lookup.call1((3,)).unwrap().extract::<String>().unwrap(), "item #3");
+//! #     assert_eq!(lookup.call1(("a",)).unwrap().extract::<String>().unwrap(), "item \"a\"");
+//! #     assert!(lookup.call1((1.5,)).is_err());
+//! # });
+//! ```
+//!
+//! With the `either` feature, [`Either`](https://docs.rs/either/latest/either/enum.Either.html)
+//! can be extracted in the same way as `Union2`.
+
+#[cfg(feature = "experimental-inspect")]
+use crate::inspect::types::TypeInfo;
+use crate::{
+    exceptions::PyTypeError, types::any::PyAnyMethods, Bound, FromPyObject, IntoPy, PyAny,
+    PyObject, PyResult, Python, ToPyObject,
+};
+
+macro_rules! union_type {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident($ty:ident)),+ }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name<$($ty),+> {
+            $(
+                #[allow(missing_docs)]
+                $variant($ty),
+            )+
+        }
+
+        impl<$($ty),+> IntoPy<PyObject> for $name<$($ty),+>
+        where
+            $($ty: IntoPy<PyObject>),+
+        {
+            #[inline]
+            fn into_py(self, py: Python<'_>) -> PyObject {
+                match self {
+                    $($name::$variant(value) => value.into_py(py)),+
+                }
+            }
+        }
+
+        impl<$($ty),+> ToPyObject for $name<$($ty),+>
+        where
+            $($ty: ToPyObject),+
+        {
+            #[inline]
+            fn to_object(&self, py: Python<'_>) -> PyObject {
+                match self {
+                    $($name::$variant(value) => value.to_object(py)),+
+                }
+            }
+        }
+
+        impl<'py, $($ty),+> FromPyObject<'py> for $name<$($ty),+>
+        where
+            $($ty: FromPyObject<'py>),+
+        {
+            fn extract_bound(obj: &Bound<'py, PyAny>) -> PyResult<Self> {
+                $(
+                    if let Ok(value) = obj.extract::<$ty>() {
+                        return Ok($name::$variant(value));
+                    }
+                )+
+                let type_names: &[&str] = &[$(std::any::type_name::<$ty>()),+];
+                Err(PyTypeError::new_err(format!(
+                    "failed to convert the value to 'Union[{}]'",
+                    type_names.join(", ")
+                )))
+            }
+
+            #[cfg(feature = "experimental-inspect")]
+            fn type_input() -> TypeInfo {
+                TypeInfo::union_of(&[$($ty::type_input()),+])
+            }
+        }
+    };
+}
+
+union_type!(
+    /// An argument which is one of two types, see the [module-level documentation](self).
+    Union2 { First(A), Second(B) }
+);
+
+union_type!(
+    /// An argument which is one of three types, see the [module-level documentation](self).
+    Union3 { First(A), Second(B), Third(C) }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union2_extract() {
+        Python::with_gil(|py| {
+            let int = 42.to_object(py).into_bound(py);
+            let string = "foo".to_object(py).into_bound(py);
+            let float = 1.5.to_object(py).into_bound(py);
+
+            type U = Union2<i32, String>;
+            assert_eq!(int.extract::<U>().unwrap(), Union2::First(42));
+            assert_eq!(
+                string.extract::<U>().unwrap(),
+                Union2::Second("foo".to_owned())
+            );
+            let err = float.extract::<U>().unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+            assert_eq!(
+                err.to_string(),
+                "TypeError: failed to convert the value to 'Union[i32, alloc::string::String]'"
+            );
+
+            // the first matching type wins
+            assert_eq!(
+                int.extract::<Union2<f64, i32>>().unwrap(),
+                Union2::First(42.0)
+            );
+        });
+    }
+
+    #[test]
+    fn test_union3_extract() {
+        Python::with_gil(|py| {
+            type U = Union3<bool, i64, String>;
+            let value = py.eval_bound("True", None, None).unwrap();
+            assert_eq!(value.extract::<U>().unwrap(), Union3::First(true));
+            let value = py.eval_bound("-7", None, None).unwrap();
+            assert_eq!(value.extract::<U>().unwrap(), Union3::Second(-7));
+            let value = py.eval_bound("'bar'", None, None).unwrap();
+            assert_eq!(
+                value.extract::<U>().unwrap(),
+                Union3::Third("bar".to_owned())
+            );
+            assert!(py
+                .eval_bound("None", None, None)
+                .unwrap()
+                .extract::<U>()
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_union_to_object() {
+        Python::with_gil(|py| {
+            let value: Union3<i32, &str, f64> = Union3::Second("foo");
+            assert_eq!(value.to_object(py).extract::<String>(py).unwrap(), "foo");
+            let value: Union2<i32, &str> = Union2::First(3);
+            assert_eq!(value.into_py(py).extract::<i32>(py).unwrap(), 3);
+        });
+    }
+}
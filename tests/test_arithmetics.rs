@@ -1,8 +1,10 @@
 #![cfg(feature = "macros")]
 
 use pyo3::class::basic::CompareOp;
+use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
 use pyo3::py_run;
+use pyo3::types::IntoPyDict;
 
 #[path = "../src/tests/common.rs"]
 mod common;
@@ -453,6 +455,81 @@ fn lhs_fellback_to_rhs() {
     });
 }
 
+/// Mixes in-place, binary and reflected operators taking different operand types.
+#[pyclass]
+#[derive(Clone)]
+struct Money {
+    cents: i64,
+}
+
+#[pymethods]
+impl Money {
+    #[new]
+    fn new(cents: i64) -> Self {
+        Money { cents }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Money({})", self.cents)
+    }
+
+    fn __add__(&self, other: i64) -> Money {
+        Money::new(self.cents + other)
+    }
+
+    fn __radd__(&self, other: i64) -> Money {
+        Money::new(other + self.cents)
+    }
+
+    fn __iadd__(&mut self, other: PyRef<'_, Money>) {
+        self.cents += other.cents;
+    }
+
+    fn __mul__(&self, other: i64) -> Money {
+        Money::new(self.cents * other)
+    }
+
+    fn __rmul__(&self, other: i64) -> Money {
+        Money::new(other * self.cents)
+    }
+}
+
+#[test]
+fn mixed_type_operators() {
+    Python::with_gil(|py| {
+        let m = Py::new(py, Money::new(100)).unwrap();
+        let money = py.get_type_bound::<Money>();
+        let env = [("m", m.bind(py).as_any()), ("Money", money.as_any())].into_py_dict_bound(py);
+        let run = |code: &str| {
+            py.run_bound(code, None, Some(&env))
+                .map_err(|e| e.display(py))
+                .unwrap()
+        };
+
+        run("assert repr(m + 1) == 'Money(101)'");
+        run("assert repr(1 + m) == 'Money(101)'");
+        run("assert repr(3 * m) == 'Money(300)'");
+
+        // In-place operators which can't extract the operand fall back to the binary operator,
+        // which creates a new object instead of modifying the original.
+        run("n = m; n += Money(5); assert n is m and repr(m) == 'Money(105)'");
+        run("n = m; n += 1; assert n is not m and repr(n) == 'Money(106)'");
+        run("n = m; n *= 2; assert n is not m and repr(n) == 'Money(210)'");
+
+        // Operands neither side accepts raise TypeError, as for Python classes.
+        for code in ["m + 1.5", "1.5 + m", "m + m", "'a' * m", "n = m; n += 'a'"] {
+            let err = py.run_bound(code, None, Some(&env)).unwrap_err();
+            assert!(err.is_instance_of::<PyTypeError>(py), "{}: {}", code, err);
+        }
+        run("
+try:
+    m + 1.5
+except TypeError as e:
+    assert str(e) == \"unsupported operand type(s) for +: 'builtins.Money' and 'float'\", str(e)
+");
+    });
+}
+
 #[pyclass]
 struct RichComparisons {}
 
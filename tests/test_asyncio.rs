@@ -0,0 +1,235 @@
+#![cfg(feature = "experimental-async")]
+#![cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyCFunction, PyDict},
+};
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+fn handle_windows(test: &str) -> String {
+    let set_event_loop_policy = r#"
+    import asyncio, sys
+    if sys.platform == "win32":
+        asyncio.set_event_loop_policy(asyncio.WindowsSelectorEventLoopPolicy())
+    "#;
+    pyo3::unindent::unindent(set_event_loop_policy) + &pyo3::unindent::unindent(test)
+}
+
+/// Runs `test` with `globals`, so that the functions are visible from within coroutines.
+fn run(py: Python<'_>, globals: &Bound<'_, PyDict>, test: &str) {
+    if let Err(err) = py.run_bound(&handle_windows(test), Some(globals), None) {
+        err.print(py);
+        panic!("{}", test);
+    }
+}
+
+fn globals<'py>(py: Python<'py>, functions: &[Bound<'py, PyCFunction>]) -> Bound<'py, PyDict> {
+    let globals = PyDict::new_bound(py);
+    for function in functions {
+        globals
+            .set_item(function.getattr("__name__").unwrap(), function)
+            .unwrap();
+    }
+    globals
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_future_into_py() {
+    #[pyfunction]
+    fn sleep(py: Python<'_>, value: u32) -> PyResult<Bound<'_, PyAny>> {
+        pyo3::asyncio::tokio::future_into_py(py, async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(value)
+        })
+    }
+
+    Python::with_gil(|py| {
+        let sleep = wrap_pyfunction_bound!(sleep, py).unwrap();
+        let test = r#"
+        import asyncio
+
+        async def main():
+            results = await asyncio.gather(sleep(1), sleep(2), sleep(3))
+            assert results == [1, 2, 3]
+
+        asyncio.run(main())
+        "#;
+        run(py, &globals(py, &[sleep]), test);
+    })
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_future_into_py_error() {
+    #[pyfunction]
+    fn fail(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        pyo3::asyncio::tokio::future_into_py(py, async {
+            Err::<(), _>(PyValueError::new_err("failed in Rust"))
+        })
+    }
+
+    #[pyfunction]
+    fn panic(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        fn panicking() -> PyResult<()> {
+            panic!("panicked in Rust")
+        }
+
+        pyo3::asyncio::tokio::future_into_py(py, async { panicking() })
+    }
+
+    Python::with_gil(|py| {
+        let fail = wrap_pyfunction_bound!(fail, py).unwrap();
+        let panic = wrap_pyfunction_bound!(panic, py).unwrap();
+        let test = r#"
+        import asyncio
+
+        async def main():
+            try:
+                await fail()
+            except ValueError as e:
+                assert str(e) == "failed in Rust"
+            else:
+                assert False
+
+            try:
+                await panic()
+            except BaseException as e:
+                assert type(e).__name__ == "PanicException"
+                assert str(e) == "panicked in Rust"
+            else:
+                assert False
+
+        asyncio.run(main())
+        "#;
+        run(py, &globals(py, &[fail, panic]), test);
+    })
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_future_into_py_cancelled() {
+    #[pyfunction]
+    fn sleep(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        pyo3::asyncio::tokio::future_into_py(py, async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Ok(())
+        })
+    }
+
+    Python::with_gil(|py| {
+        let sleep = wrap_pyfunction_bound!(sleep, py).unwrap();
+        let test = r#"
+        import asyncio
+
+        async def main():
+            future = sleep()
+            future.cancel()
+            # let the Rust future complete after the cancellation
+            await asyncio.sleep(0.05)
+            assert future.cancelled()
+
+        asyncio.run(main())
+        "#;
+        run(py, &globals(py, &[sleep]), test);
+    })
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_into_future() {
+    #[pyfunction]
+    fn add_one<'py>(py: Python<'py>, awaitable: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let future = pyo3::asyncio::into_future(awaitable)?;
+        pyo3::asyncio::tokio::future_into_py(py, async move {
+            let value = future.await?;
+            Python::with_gil(|py| Ok(value.extract::<u32>(py)? + 1))
+        })
+    }
+
+    Python::with_gil(|py| {
+        let add_one = wrap_pyfunction_bound!(add_one, py).unwrap();
+        let test = r#"
+        import asyncio
+
+        async def value():
+            await asyncio.sleep(0.01)
+            return 41
+
+        async def fail():
+            raise ValueError("failed in Python")
+
+        async def main():
+            assert await add_one(value()) == 42
+            # any awaitable works, not only coroutines
+            assert await add_one(asyncio.ensure_future(value())) == 42
+
+            try:
+                await add_one(fail())
+            except ValueError as e:
+                assert str(e) == "failed in Python"
+            else:
+                assert False
+
+        asyncio.run(main())
+        "#;
+        run(py, &globals(py, &[add_one]), test);
+    })
+}
+
+#[cfg(feature = "async-std")]
+#[test]
+fn async_std_future_into_py() {
+    #[pyfunction]
+    fn sleep(py: Python<'_>, value: u32) -> PyResult<Bound<'_, PyAny>> {
+        pyo3::asyncio::async_std::future_into_py(py, async move {
+            async_std::task::sleep(Duration::from_millis(10)).await;
+            Ok(value)
+        })
+    }
+
+    Python::with_gil(|py| {
+        let sleep = wrap_pyfunction_bound!(sleep, py).unwrap();
+        let test = r#"
+        import asyncio
+
+        async def main():
+            assert await sleep(42) == 42
+
+        asyncio.run(main())
+        "#;
+        run(py, &globals(py, &[sleep]), test);
+    })
+}
+
+#[test]
+fn future_into_py_without_running_loop() {
+    struct Never;
+
+    impl pyo3::asyncio::Runtime for Never {
+        fn spawn<F>(_fut: F)
+        where
+            F: std::future::Future<Output = ()> + Send + 'static,
+        {
+            unreachable!("nothing should be spawned without a running loop")
+        }
+    }
+
+    Python::with_gil(|py| {
+        let err = pyo3::asyncio::future_into_py::<Never, _, ()>(py, async { Ok(()) }).unwrap_err();
+        assert!(err.is_instance_of::<pyo3::exceptions::PyRuntimeError>(py));
+
+        let globals = PyDict::new_bound(py);
+        py.run_bound("async def f(): pass", Some(&globals), None)
+            .unwrap();
+        let coroutine = py.eval_bound("f()", Some(&globals), None).unwrap();
+        assert!(pyo3::asyncio::into_future(&coroutine).is_err());
+        // avoid a "coroutine was never awaited" warning
+        coroutine.call_method0("close").unwrap();
+    })
+}
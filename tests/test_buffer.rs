@@ -1,7 +1,7 @@
 #![cfg(feature = "macros")]
 #![cfg(any(not(Py_LIMITED_API), Py_3_11))]
 
-use pyo3::{buffer::PyBuffer, exceptions::PyBufferError, ffi, prelude::*};
+use pyo3::{buffer::PyBuffer, exceptions::PyBufferError, ffi, prelude::*, py_run};
 use std::{
     ffi::CStr,
     os::raw::{c_int, c_void},
@@ -139,3 +139,49 @@ fn test_get_buffer_errors() {
         );
     });
 }
+
+#[pyfunction]
+fn sum_f64(values: &[f64]) -> f64 {
+    values.iter().sum()
+}
+
+#[test]
+fn test_slice_arguments() {
+    Python::with_gil(|py| {
+        let sum_f64 = wrap_pyfunction_bound!(sum_f64, py).unwrap();
+        py_run!(
+            py,
+            sum_f64,
+            r#"
+            import array
+
+            values = array.array("d", [1.0, 2.5, 3.0])
+            assert sum_f64(values) == 6.5
+            assert sum_f64(memoryview(values).toreadonly()) == 6.5
+            assert sum_f64(memoryview(values.tobytes()).cast("d")) == 6.5
+            "#
+        );
+        py_expect_exception!(py, sum_f64, "sum_f64([1.0])", PyTypeError);
+        py_expect_exception!(
+            py,
+            sum_f64,
+            "import array; sum_f64(array.array('i', [1]))",
+            PyBufferError,
+            "buffer contents are not compatible with f64"
+        );
+        py_expect_exception!(
+            py,
+            sum_f64,
+            "sum_f64(memoryview(bytes(16)).cast('d', (1, 2)))",
+            PyBufferError,
+            "expected a one-dimensional buffer, got 2 dimensions"
+        );
+        py_expect_exception!(
+            py,
+            sum_f64,
+            "sum_f64(memoryview(bytes(32)).cast('d')[::2])",
+            PyBufferError,
+            "buffer is not contiguous"
+        );
+    });
+}
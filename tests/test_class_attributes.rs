@@ -1,6 +1,7 @@
 #![cfg(feature = "macros")]
 
 use pyo3::prelude::*;
+use pyo3::py_run;
 
 #[path = "../src/tests/common.rs"]
 mod common;
@@ -96,6 +97,39 @@ fn recursive_class_attributes() {
     });
 }
 
+#[test]
+fn class_attributes_are_initialized_once() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static INIT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    #[pyclass]
+    struct LazyInit {}
+
+    #[pymethods]
+    impl LazyInit {
+        #[new]
+        fn new() -> Self {
+            LazyInit {}
+        }
+
+        #[classattr]
+        fn expensive(py: Python<'_>) -> PyObject {
+            INIT_COUNT.fetch_add(1, Ordering::Relaxed);
+            vec![1, 2, 3].into_py(py)
+        }
+    }
+
+    Python::with_gil(|py| {
+        assert_eq!(INIT_COUNT.load(Ordering::Relaxed), 0);
+        let cls = py.get_type_bound::<LazyInit>();
+        assert_eq!(INIT_COUNT.load(Ordering::Relaxed), 1);
+        py_assert!(py, cls, "cls.expensive is cls.expensive");
+        py_assert!(py, cls, "cls().expensive is cls.expensive == [1, 2, 3]");
+        assert_eq!(INIT_COUNT.load(Ordering::Relaxed), 1);
+    });
+}
+
 #[test]
 fn test_fallible_class_attribute() {
     use pyo3::{exceptions::PyValueError, types::PyString};
@@ -201,6 +235,44 @@ fn test_renaming_all_struct_fields() {
     });
 }
 
+#[pyclass(rename_all = "SCREAMING_SNAKE_CASE")]
+struct StructWithRenamedIndividualFields {
+    #[pyo3(get)]
+    read_only: i32,
+    #[pyo3(set)]
+    write_only: i32,
+    #[pyo3(get, set, name = "renamedField")]
+    read_write: i32,
+    _hidden_field: i32,
+}
+
+#[test]
+fn test_renaming_individual_struct_fields() {
+    Python::with_gil(|py| {
+        let struct_obj = Py::new(
+            py,
+            StructWithRenamedIndividualFields {
+                read_only: 1,
+                write_only: 2,
+                read_write: 3,
+                _hidden_field: 4,
+            },
+        )
+        .unwrap();
+        py_assert!(py, struct_obj, "struct_obj.READ_ONLY == 1");
+        py_run!(py, struct_obj, "struct_obj.WRITE_ONLY = 5");
+        assert_eq!(struct_obj.borrow(py).write_only, 5);
+        py_run!(
+            py,
+            struct_obj,
+            "struct_obj.renamedField = 6; assert struct_obj.renamedField == 6"
+        );
+        py_assert!(py, struct_obj, "not hasattr(struct_obj, 'READ_WRITE')");
+        py_assert!(py, struct_obj, "not hasattr(struct_obj, '_HIDDEN_FIELD')");
+        py_expect_exception!(py, struct_obj, "struct_obj.READ_ONLY = 0", PyAttributeError);
+    });
+}
+
 macro_rules! test_case {
     ($struct_name: ident, $rule: literal, $field_name: ident, $renamed_field_name: literal, $test_name: ident) => {
         #[pyclass(get_all, set_all, rename_all = $rule)]
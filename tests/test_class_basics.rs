@@ -202,6 +202,35 @@ fn empty_class_in_module() {
     });
 }
 
+#[pyclass(subclass, module = "reflection")]
+struct ReflectedBase {}
+
+#[pyclass(extends = ReflectedBase, module = "reflection")]
+struct ReflectedChild {}
+
+#[test]
+fn pyclass_type_introspection() {
+    Python::with_gil(|py| {
+        let base = PyType::new_bound::<ReflectedBase>(py);
+        let child = PyType::new_bound::<ReflectedChild>(py);
+
+        assert_eq!(child.qualname().unwrap(), "ReflectedChild");
+        assert_eq!(child.module().unwrap(), "reflection");
+        assert_eq!(
+            child.fully_qualified_name().unwrap(),
+            "reflection.ReflectedChild"
+        );
+
+        assert!(child.is_subclass_of::<ReflectedBase>().unwrap());
+        assert!(!base.is_subclass_of::<ReflectedChild>().unwrap());
+        assert!(child.bases().eq((&base,)).unwrap());
+        assert!(child
+            .mro()
+            .eq((&child, &base, py.get_type_bound::<PyAny>()))
+            .unwrap());
+    });
+}
+
 #[cfg(feature = "py-clone")]
 #[pyclass]
 struct ClassWithObjectField {
@@ -258,6 +287,126 @@ fn class_with_hash() {
     });
 }
 
+#[pyclass(str)]
+struct ClassWithStr {
+    value: usize,
+}
+
+impl std::fmt::Display for ClassWithStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "value is {}", self.value)
+    }
+}
+
+#[test]
+fn class_with_str() {
+    Python::with_gil(|py| {
+        let obj = Py::new(py, ClassWithStr { value: 42 }).unwrap();
+        py_assert!(py, obj, "str(obj) == 'value is 42'");
+        py_assert!(py, obj, "f'{obj}' == 'value is 42'");
+        py_assert!(
+            py,
+            obj,
+            "repr(obj).startswith('<builtins.ClassWithStr object at')"
+        );
+    });
+}
+
+#[pyclass(pickle, subclass, module = "pickle_test_module")]
+#[derive(Default)]
+struct Picklable {
+    #[pyo3(get)]
+    value: usize,
+    #[pyo3(get)]
+    name: String,
+}
+
+#[pymethods]
+impl Picklable {
+    #[new]
+    fn new(value: usize, name: String) -> Self {
+        Picklable { value, name }
+    }
+
+    fn __getstate__(&self) -> (usize, String) {
+        (self.value, self.name.clone())
+    }
+
+    fn __setstate__(&mut self, state: (usize, String)) {
+        (self.value, self.name) = state;
+    }
+}
+
+#[test]
+fn class_with_pickle() {
+    Python::with_gil(|py| {
+        // pickle looks up classes by module and name
+        let module = PyModule::new_bound(py, "pickle_test_module").unwrap();
+        module.add_class::<Picklable>().unwrap();
+        py.import_bound("sys")
+            .unwrap()
+            .getattr("modules")
+            .unwrap()
+            .set_item("pickle_test_module", &module)
+            .unwrap();
+
+        let obj = Py::new(py, Picklable::new(42, "hello".to_owned())).unwrap();
+        py_run!(
+            py,
+            obj,
+            r#"
+            import copy, pickle
+            for protocol in range(pickle.HIGHEST_PROTOCOL + 1):
+                copied = pickle.loads(pickle.dumps(obj, protocol=protocol))
+                assert type(copied) is type(obj)
+                assert (copied.value, copied.name) == (42, "hello")
+            copied = copy.deepcopy(obj)
+            assert copied is not obj
+            assert (copied.value, copied.name) == (42, "hello")
+
+            class Sub(type(obj)):
+                pass
+
+            copied = copy.copy(Sub(1, "sub"))
+            assert type(copied) is Sub
+            assert (copied.value, copied.name) == (1, "sub")
+            "#
+        );
+    });
+}
+
+#[cfg(Py_3_9)]
+#[pyclass(generic, subclass)]
+struct GenericContainer {}
+
+#[cfg(Py_3_9)]
+#[test]
+fn class_with_generic() {
+    Python::with_gil(|py| {
+        let cls = py.get_type_bound::<GenericContainer>();
+        py_run!(
+            py,
+            cls,
+            r#"
+            import typing
+            alias = cls[int, str]
+            assert typing.get_origin(alias) is cls
+            assert typing.get_args(alias) == (int, str)
+            assert repr(alias) == "GenericContainer[int, str]"
+
+            class Sub(cls[int]):
+                pass
+            assert Sub.__orig_bases__ == (cls[int],)
+            assert issubclass(Sub, cls)
+
+            def annotated(value: cls[int]) -> cls[str]:
+                pass
+            assert typing.get_type_hints(annotated) == {"value": cls[int], "return": cls[str]}
+            "#
+        );
+    });
+}
+
 #[pyclass(unsendable, subclass)]
 struct UnsendableBase {
     value: std::rc::Rc<usize>,
@@ -402,6 +551,76 @@ fn test_pymethods_from_py_with() {
     })
 }
 
+/// A type which doesn't implement `IntoPy`.
+struct Length(usize);
+
+fn length_to_py(value: Length, py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    py.import_bound("builtins")?
+        .getattr("range")?
+        .call1((value.0,))
+}
+
+#[pyclass]
+struct ClassWithIntoPyWithMethods {}
+
+#[pymethods]
+impl ClassWithIntoPyWithMethods {
+    #[pyo3(into_py_with = "length_to_py")]
+    fn instance_method(&self) -> Length {
+        Length(1)
+    }
+
+    #[classmethod]
+    #[pyo3(into_py_with = "length_to_py")]
+    #[allow(clippy::unnecessary_wraps)]
+    fn classmethod(_cls: &Bound<'_, PyType>) -> PyResult<Length> {
+        Ok(Length(2))
+    }
+
+    #[staticmethod]
+    #[pyo3(into_py_with = "length_to_py")]
+    fn staticmethod() -> Length {
+        Length(3)
+    }
+
+    #[getter]
+    #[pyo3(into_py_with = "length_to_py")]
+    fn getter(&self) -> Length {
+        Length(4)
+    }
+
+    #[classattr]
+    #[pyo3(into_py_with = "length_to_py")]
+    fn class_attribute() -> Length {
+        Length(5)
+    }
+
+    #[pyo3(into_py_with = "length_to_py")]
+    fn __call__(&self) -> Length {
+        Length(6)
+    }
+}
+
+#[test]
+fn test_pymethods_into_py_with() {
+    Python::with_gil(|py| {
+        let instance = Py::new(py, ClassWithIntoPyWithMethods {}).unwrap();
+
+        py_run!(
+            py,
+            instance,
+            r#"
+        assert len(instance.instance_method()) == 1
+        assert len(instance.classmethod()) == 2
+        assert len(instance.staticmethod()) == 3
+        assert len(instance.getter) == 4
+        assert len(instance.class_attribute) == 5
+        assert len(instance()) == 6
+        "#
+        );
+    })
+}
+
 #[pyclass]
 struct TupleClass(#[pyo3(get, set, name = "value")] i32);
 
@@ -591,6 +810,40 @@ fn inherited_weakref() {
     });
 }
 
+#[test]
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+fn weakref_cleared_on_drop() {
+    Python::with_gil(|py| {
+        let inst = Bound::new(
+            py,
+            WeakRefSupport {
+                _pad: *b"DEADBEEFDEADBEEFDEADBEEFDEADBEEF",
+            },
+        )
+        .unwrap();
+        let weakref = py
+            .import_bound("weakref")
+            .unwrap()
+            .call_method1("ref", (&inst,))
+            .unwrap();
+        assert!(weakref.call0().unwrap().is(&inst));
+
+        drop(inst);
+        assert!(weakref.call0().unwrap().is_none());
+    });
+}
+
+#[test]
+fn weakref_unsupported() {
+    #[pyclass]
+    struct NoWeakRef {}
+
+    Python::with_gil(|py| {
+        let inst = Py::new(py, NoWeakRef {}).unwrap();
+        py_expect_exception!(py, inst, "import weakref; weakref.ref(inst)", PyTypeError);
+    });
+}
+
 #[test]
 fn access_frozen_class_without_gil() {
     use std::sync::atomic::{AtomicUsize, Ordering};
@@ -617,6 +870,41 @@ fn access_frozen_class_without_gil() {
     Python::with_gil(move |_py| drop(py_counter));
 }
 
+#[test]
+#[cfg_attr(target_arch = "wasm32", ignore)]
+fn share_frozen_class_across_threads() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[pyclass(frozen)]
+    struct FrozenCounter {
+        value: AtomicUsize,
+    }
+
+    let py_counter: Py<FrozenCounter> = Python::with_gil(|py| {
+        Py::new(
+            py,
+            FrozenCounter {
+                value: AtomicUsize::new(0),
+            },
+        )
+        .unwrap()
+    });
+
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..100 {
+                    py_counter.get().value.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+
+    assert_eq!(py_counter.get().value.load(Ordering::Relaxed), 400);
+
+    Python::with_gil(move |_py| drop(py_counter));
+}
+
 #[test]
 #[cfg(Py_3_8)] // sys.unraisablehook not available until Python 3.8
 #[cfg_attr(target_arch = "wasm32", ignore)]
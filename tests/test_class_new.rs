@@ -2,9 +2,13 @@
 
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::py_run;
 use pyo3::sync::GILOnceCell;
 use pyo3::types::IntoPyDict;
 
+#[path = "../src/tests/common.rs"]
+mod common;
+
 #[pyclass]
 struct EmptyClassWithNew {}
 
@@ -298,3 +302,131 @@ fn test_new_returns_bound() {
         assert!(obj.is_exact_instance_of::<NewReturnsBound>());
     })
 }
+
+#[pyclass]
+struct NewWithDefaults {
+    #[pyo3(get)]
+    a: i32,
+    #[pyo3(get)]
+    b: Option<String>,
+}
+
+#[pymethods]
+impl NewWithDefaults {
+    #[new]
+    #[pyo3(signature = (a = 1, *, b = None))]
+    fn new(a: i32, b: Option<String>) -> Self {
+        Self { a, b }
+    }
+}
+
+#[test]
+fn test_new_with_defaults() {
+    Python::with_gil(|py| {
+        let cls = py.get_type_bound::<NewWithDefaults>();
+        py_assert!(py, cls, "cls().a == 1 and cls().b is None");
+        py_assert!(py, cls, "cls(5, b='x').a == 5 and cls(5, b='x').b == 'x'");
+        py_assert!(py, cls, "cls.__text_signature__ == '(a=1, *, b=None)'");
+    });
+}
+
+#[pyclass(subclass)]
+struct Initialized {
+    #[pyo3(get)]
+    value: i32,
+    #[pyo3(get)]
+    init_calls: usize,
+}
+
+#[pymethods]
+impl Initialized {
+    #[new]
+    #[pyo3(signature = (*_args, **_kwargs))]
+    fn new(_args: &Bound<'_, PyAny>, _kwargs: Option<&Bound<'_, PyAny>>) -> Self {
+        Self {
+            value: 0,
+            init_calls: 0,
+        }
+    }
+
+    #[pyo3(signature = (value = 10))]
+    fn __init__(&mut self, value: i32) -> PyResult<()> {
+        if value < 0 {
+            return Err(PyValueError::new_err("value must not be negative"));
+        }
+        self.value = value;
+        self.init_calls += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_init() {
+    Python::with_gil(|py| {
+        let cls = py.get_type_bound::<Initialized>();
+        py_assert!(py, cls, "cls().value == 10");
+        py_assert!(py, cls, "cls(3).value == 3");
+        py_assert!(py, cls, "cls(value=4).init_calls == 1");
+        py_expect_exception!(py, cls, "cls(-1)", PyValueError);
+
+        py_run!(
+            py,
+            cls,
+            r#"
+            obj = cls(1)
+            obj.__init__(2)
+            assert obj.value == 2
+            assert obj.init_calls == 2
+            "#
+        );
+    });
+}
+
+#[test]
+fn test_init_from_python_subclass() {
+    Python::with_gil(|py| {
+        let cls = py.get_type_bound::<Initialized>();
+        py_run!(
+            py,
+            cls,
+            r#"
+            class Sub(cls):
+                def __init__(self, a, b):
+                    super().__init__(a + b)
+                    self.extra = a * b
+
+            obj = Sub(2, 3)
+            assert obj.value == 5
+            assert obj.extra == 6
+            assert obj.init_calls == 1
+            "#
+        );
+    });
+}
+
+#[pyclass]
+struct InitReturnsValue {}
+
+#[pymethods]
+impl InitReturnsValue {
+    #[new]
+    fn new() -> Self {
+        Self {}
+    }
+
+    fn __init__(&self) -> i32 {
+        1
+    }
+}
+
+#[test]
+fn test_init_must_return_none() {
+    Python::with_gil(|py| {
+        let cls = py.get_type_bound::<InitReturnsValue>();
+        let err = cls.call0().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: __init__() should return None, not 'int'"
+        );
+    });
+}
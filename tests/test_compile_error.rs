@@ -26,6 +26,7 @@ fn test_compile_errors() {
     t.compile_fail("tests/ui/pyclass_send.rs");
     t.compile_fail("tests/ui/invalid_argument_attributes.rs");
     t.compile_fail("tests/ui/invalid_frompy_derive.rs");
+    t.compile_fail("tests/ui/invalid_intopy_derive.rs");
     t.compile_fail("tests/ui/static_ref.rs");
     #[cfg(not(feature = "gil-refs"))]
     t.compile_fail("tests/ui/wrong_aspyref_lifetimes.rs");
@@ -34,10 +35,14 @@ fn test_compile_errors() {
     // output changes with async feature
     #[cfg(all(Py_LIMITED_API, feature = "experimental-async"))]
     t.compile_fail("tests/ui/abi3_nativetype_inheritance.rs");
+    // output changes with async feature
+    #[cfg(all(not(Py_LIMITED_API), feature = "experimental-async"))]
+    t.compile_fail("tests/ui/invalid_base_class.rs");
     #[cfg(not(feature = "gil-refs"))]
     t.compile_fail("tests/ui/invalid_intern_arg.rs");
     t.compile_fail("tests/ui/invalid_frozen_pyclass_borrow.rs");
     t.compile_fail("tests/ui/invalid_pymethod_receiver.rs");
+    t.compile_fail("tests/ui/invalid_constructors.rs");
     t.compile_fail("tests/ui/missing_intopy.rs");
     // adding extra error conversion impls changes the output
     #[cfg(not(any(
@@ -67,4 +72,6 @@ fn test_compile_errors() {
     t.compile_fail("tests/ui/abi3_weakref.rs");
     #[cfg(all(Py_LIMITED_API, not(Py_3_9)))]
     t.compile_fail("tests/ui/abi3_dict.rs");
+    #[cfg(all(Py_LIMITED_API, not(Py_3_11)))]
+    t.compile_fail("tests/ui/abi3_buffer.rs");
 }
@@ -124,6 +124,24 @@ fn sleep_coroutine() {
     })
 }
 
+#[test]
+#[cfg(not(Py_LIMITED_API))] // PyGILState_Check is not in the limited API
+fn allow_threads_coroutine() {
+    #[pyfunction]
+    async fn gil_released() -> bool {
+        pyo3::coroutine::allow_threads(async {
+            // SAFETY: only checks whether the current thread holds the GIL
+            unsafe { pyo3::ffi::PyGILState_Check() == 0 }
+        })
+        .await
+    }
+    Python::with_gil(|gil| {
+        let gil_released = wrap_pyfunction_bound!(gil_released, gil).unwrap();
+        let test = "import asyncio; assert asyncio.run(gil_released())";
+        py_run!(gil, gil_released, &handle_windows(test));
+    })
+}
+
 #[test]
 fn cancelled_coroutine() {
     Python::with_gil(|gil| {
@@ -189,6 +207,50 @@ fn coroutine_cancel_handle() {
     })
 }
 
+#[test]
+fn coroutine_cancel_handle_propagate() {
+    static CLEANED_UP: AtomicBool = AtomicBool::new(false);
+    #[pyfunction]
+    async fn cancellable_sleep(
+        seconds: f64,
+        #[pyo3(cancel_handle)] mut cancel: CancelHandle,
+    ) -> PyResult<usize> {
+        futures::select! {
+            _ = sleep(seconds).fuse() => Ok(42),
+            err = cancel.cancelled_error().fuse() => {
+                CLEANED_UP.store(true, Ordering::SeqCst);
+                Err(err)
+            }
+        }
+    }
+    Python::with_gil(|gil| {
+        let cancellable_sleep = wrap_pyfunction_bound!(cancellable_sleep, gil).unwrap();
+        let test = r#"
+        import asyncio;
+        async def main():
+            task = asyncio.create_task(cancellable_sleep(999))
+            await asyncio.sleep(0)
+            task.cancel()
+            try:
+                await task
+            except asyncio.CancelledError:
+                return "cancelled"
+        assert asyncio.run(main()) == "cancelled"
+        "#;
+        let globals = gil.import_bound("__main__").unwrap().dict();
+        globals
+            .set_item("cancellable_sleep", cancellable_sleep)
+            .unwrap();
+        gil.run_bound(
+            &pyo3::unindent::unindent(&handle_windows(test)),
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+        assert!(CLEANED_UP.load(Ordering::SeqCst));
+    })
+}
+
 #[test]
 fn coroutine_is_cancelled() {
     #[pyfunction]
@@ -3,6 +3,7 @@
 use pyo3::create_exception;
 use pyo3::exceptions::PyException;
 use pyo3::prelude::*;
+use pyo3::py_run;
 use pyo3::sync::GILOnceCell;
 #[cfg(not(Py_LIMITED_API))]
 use pyo3::types::PyBool;
@@ -239,3 +240,83 @@ fn test_module_names() {
         );
     })
 }
+
+#[test]
+fn test_submodule_qualified_names() {
+    Python::with_gil(|py| {
+        let m = declarative_module(py);
+        py_assert!(py, m, "m.__name__ == 'declarative_module'");
+        py_assert!(py, m, "m.inner.__name__ == 'declarative_module.inner'");
+        py_assert!(
+            py,
+            m,
+            "m.inner.triple.__module__ == 'declarative_module.inner'"
+        );
+        py_assert!(
+            py,
+            m,
+            "m.inner_custom_root.__name__ == 'custom_root.inner_custom_root'"
+        );
+        // Modules exported with `use` are not nested, so they keep their own name
+        py_assert!(
+            py,
+            m,
+            "m.declarative_submodule.__name__ == 'declarative_submodule'"
+        );
+    })
+}
+
+#[test]
+fn test_import_from_submodule() {
+    Python::with_gil(|py| {
+        let m = declarative_module(py);
+        py_run!(
+            py,
+            m,
+            r#"
+import sys
+assert sys.modules['declarative_module.inner'] is m.inner
+
+from declarative_module.inner import triple
+assert triple(3) == 9
+"#
+        );
+    })
+}
+
+#[pymodule]
+mod declarative_module_with_getattr {
+    use super::*;
+
+    #[pyfunction]
+    fn __getattr__(name: &str) -> PyResult<usize> {
+        name.strip_prefix("value_")
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| pyo3::exceptions::PyAttributeError::new_err(name.to_string()))
+    }
+
+    fn lazy(m: &Bound<'_, PyModule>) -> PyResult<()> {
+        m.add_function(wrap_pyfunction!(double, m)?)
+    }
+
+    #[pymodule_init]
+    fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+        m.add_lazy_submodule("lazy", lazy)
+    }
+}
+
+#[test]
+fn test_declarative_module_getattr() {
+    Python::with_gil(|py| {
+        let m = pyo3::wrap_pymodule!(declarative_module_with_getattr)(py);
+        py_assert!(py, m, "m.value_3 == 3");
+        py_assert!(py, m, "not hasattr(m, 'missing')");
+        py_assert!(py, m, "m.lazy.double(2) == 4");
+        py_assert!(
+            py,
+            m,
+            "m.lazy.__name__ == 'declarative_module_with_getattr.lazy'"
+        );
+        py_assert!(py, m, "m.__all__ == ['lazy']");
+    })
+}
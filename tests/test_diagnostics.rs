@@ -0,0 +1,80 @@
+#![cfg(feature = "pool-diagnostics")]
+
+use pyo3::diagnostics::{assert_no_leaks, assert_refcount_unchanged, PoolStats};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+fn panic_message(result: std::thread::Result<()>) -> String {
+    let payload = result.unwrap_err();
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .unwrap_or_else(|| payload.downcast_ref::<&str>().unwrap().to_string())
+}
+
+// The measurements cover the whole interpreter, so all checks share a single test.
+#[test]
+fn test_leak_detection() {
+    Python::with_gil(|py| {
+        let stats = PoolStats::current(py).unwrap();
+        assert!(stats.tracked_objects > 0);
+        assert_eq!(stats.owned_objects, 0);
+
+        assert_no_leaks(py, |py| {
+            let list = PyList::new_bound(py, [1, 2, 3]);
+            list.append(PyDict::new_bound(py)).unwrap();
+        });
+
+        // one-off allocations on first use are not reported
+        let cache = PyList::empty_bound(py);
+        assert_no_leaks(py, |py| {
+            if cache.is_empty() {
+                cache.append(PyDict::new_bound(py)).unwrap();
+            }
+        });
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            assert_no_leaks(py, |py| std::mem::forget(PyList::empty_bound(py)));
+        }));
+        let message = panic_message(result);
+        assert!(
+            message.starts_with("reference leak detected over 10 iterations"),
+            "{}",
+            message
+        );
+
+        #[cfg(feature = "gil-refs")]
+        {
+            #[allow(deprecated)]
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                assert_no_leaks(py, |py| {
+                    py.eval("object()", None, None).unwrap();
+                });
+            }));
+            let message = panic_message(result);
+            assert!(
+                message.contains("10 objects accumulated in the GIL pool"),
+                "{}",
+                message
+            );
+        }
+
+        let obj = py.eval_bound("object()", None, None).unwrap();
+        let len = assert_refcount_unchanged(&obj, || {
+            let list = PyList::new_bound(py, [&obj, &obj]);
+            list.len()
+        });
+        assert_eq!(len, 2);
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            assert_refcount_unchanged(&obj, || std::mem::forget(obj.clone()));
+        }));
+        let message = panic_message(result);
+        assert!(
+            message.contains("reference count of the object changed"),
+            "{}",
+            message
+        );
+    });
+}
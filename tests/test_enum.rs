@@ -101,6 +101,96 @@ fn test_enum_compare_int() {
     })
 }
 
+#[test]
+fn test_enum_index() {
+    Python::with_gil(|py| {
+        let two = Py::new(py, CustomDiscriminant::Two).unwrap();
+        py_run!(
+            py,
+            two,
+            r#"
+            import operator
+            assert operator.index(two) == 2
+            assert ["a", "b", "c"][two] == "c"
+            assert hex(two) == "0x2"
+        "#
+        )
+    })
+}
+
+#[test]
+fn test_enum_from_int() {
+    Python::with_gil(|py| {
+        #[allow(non_snake_case)]
+        let CustomDiscriminant = py.get_type_bound::<CustomDiscriminant>();
+        py_run!(
+            py,
+            CustomDiscriminant,
+            r#"
+            assert CustomDiscriminant.from_int(1) == CustomDiscriminant.One
+            assert CustomDiscriminant.from_int(2) == CustomDiscriminant.Two
+            try:
+                CustomDiscriminant.from_int(3)
+            except ValueError as e:
+                assert str(e) == "3 is not a valid CustomDiscriminant"
+            else:
+                assert False
+        "#
+        );
+        let one: CustomDiscriminant = CustomDiscriminant
+            .call_method1("from_int", (1,))
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(one, CustomDiscriminant::One);
+    })
+}
+
+#[test]
+fn test_enum_from_int_overridden() {
+    #[pyclass(eq, eq_int)]
+    #[derive(Debug, PartialEq, Eq, Clone)]
+    enum MyEnumFromStr {
+        Variant,
+    }
+
+    #[pymethods]
+    impl MyEnumFromStr {
+        #[staticmethod]
+        fn from_int(value: &str) -> String {
+            format!("custom {}", value)
+        }
+    }
+
+    Python::with_gil(|py| {
+        let cls = py.get_type_bound::<MyEnumFromStr>();
+        py_assert!(py, cls, "cls.from_int('0') == 'custom 0'");
+    })
+}
+
+#[test]
+fn test_enum_members() {
+    Python::with_gil(|py| {
+        #[allow(non_snake_case)]
+        let CustomDiscriminant = py.get_type_bound::<CustomDiscriminant>();
+        py_run!(
+            py,
+            CustomDiscriminant,
+            r#"
+            members = CustomDiscriminant.__members__
+            assert list(members) == ["One", "Two"]
+            assert list(members.values()) == [CustomDiscriminant.One, CustomDiscriminant.Two]
+            try:
+                members["Three"] = 3
+            except TypeError:
+                pass
+            else:
+                assert False, "__members__ should be read-only"
+        "#
+        );
+    })
+}
+
 #[pyclass(eq, eq_int)]
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[repr(u8)]
@@ -296,6 +386,63 @@ fn test_complex_enum_with_hash() {
     });
 }
 
+#[pyclass(eq, eq_int, str)]
+#[derive(PartialEq)]
+enum SimpleEnumWithStr {
+    A,
+    B,
+}
+
+impl std::fmt::Display for SimpleEnumWithStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimpleEnumWithStr::A => write!(f, "variant a"),
+            SimpleEnumWithStr::B => write!(f, "variant b"),
+        }
+    }
+}
+
+#[test]
+fn test_simple_enum_with_str() {
+    Python::with_gil(|py| {
+        let cls = py.get_type_bound::<SimpleEnumWithStr>();
+        py_assert!(py, cls, "str(cls.A) == 'variant a'");
+        py_assert!(py, cls, "str(cls.B) == 'variant b'");
+        py_assert!(py, cls, "repr(cls.A) == 'SimpleEnumWithStr.A'");
+    });
+}
+
+#[pyclass(str)]
+enum ComplexEnumWithStr {
+    A(u32),
+    B { msg: String },
+}
+
+impl std::fmt::Display for ComplexEnumWithStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComplexEnumWithStr::A(value) => write!(f, "a: {}", value),
+            ComplexEnumWithStr::B { msg } => write!(f, "b: {}", msg),
+        }
+    }
+}
+
+#[test]
+fn test_complex_enum_with_str() {
+    Python::with_gil(|py| {
+        let a = Py::new(py, ComplexEnumWithStr::A(1)).unwrap();
+        let b = Py::new(
+            py,
+            ComplexEnumWithStr::B {
+                msg: String::from("Hello"),
+            },
+        )
+        .unwrap();
+        py_assert!(py, a, "str(a) == 'a: 1'");
+        py_assert!(py, b, "str(b) == 'b: Hello'");
+    });
+}
+
 #[allow(deprecated)]
 mod deprecated {
     use crate::py_assert;
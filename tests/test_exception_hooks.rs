@@ -0,0 +1,79 @@
+use std::sync::{Arc, Mutex};
+
+use pyo3::exceptions::set_excepthook;
+use pyo3::prelude::*;
+use pyo3::py_run;
+
+#[test]
+fn test_excepthook() {
+    Python::with_gil(|py| {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let previous = set_excepthook(py, move |py, err| {
+            recorded.lock().unwrap().push(err.to_string());
+            assert!(err.traceback_bound(py).is_some());
+            Ok(())
+        })
+        .unwrap();
+
+        py_run!(
+            py,
+            *py.import_bound("sys").unwrap().dict(),
+            r#"
+try:
+    raise ValueError("uncaught")
+except ValueError:
+    excepthook(*exc_info())
+"#
+        );
+        py.import_bound("sys")
+            .unwrap()
+            .setattr("excepthook", previous)
+            .unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), ["ValueError: uncaught"]);
+    });
+}
+
+#[test]
+#[cfg(Py_3_8)]
+fn test_unraisablehook() {
+    use pyo3::exceptions::{set_unraisablehook, PyRuntimeError, PyValueError};
+
+    Python::with_gil(|py| {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let previous = set_unraisablehook(py, move |_py, unraisable| {
+            let object = unraisable
+                .object
+                .map(|object| object.repr().unwrap().to_string());
+            recorded.lock().unwrap().push((
+                unraisable.error.to_string(),
+                unraisable.message,
+                object,
+            ));
+            Ok(())
+        })
+        .unwrap();
+
+        PyRuntimeError::new_err("first").write_unraisable_bound(py, None);
+        let object = "context".to_object(py);
+        PyValueError::new_err("second").write_unraisable_bound(py, Some(object.bind(py)));
+        py.import_bound("sys")
+            .unwrap()
+            .setattr("unraisablehook", previous)
+            .unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                ("RuntimeError: first".to_owned(), None, None),
+                (
+                    "ValueError: second".to_owned(),
+                    None,
+                    Some("'context'".to_owned())
+                ),
+            ]
+        );
+    });
+}
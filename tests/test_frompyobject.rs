@@ -162,6 +162,31 @@ fn test_named_field_with_ext_fn() {
     });
 }
 
+#[derive(Debug, FromPyObject)]
+pub struct IntegerKeys {
+    #[pyo3(item(0))]
+    first: String,
+    #[pyo3(item(1))]
+    second: usize,
+}
+
+#[test]
+fn test_named_fields_with_integer_item_keys() {
+    Python::with_gil(|py| {
+        let list = PyList::new_bound(py, &["first".into_py(py), 2.into_py(py)]);
+        let keys = list
+            .extract::<IntegerKeys>()
+            .expect("Failed to extract IntegerKeys from list");
+        assert_eq!(keys.first, "first");
+        assert_eq!(keys.second, 2);
+
+        let dict = PyDict::new_bound(py);
+        dict.set_item(0, "zero").expect("Failed to set item");
+        let err = dict.extract::<IntegerKeys>().unwrap_err();
+        assert_eq!(extract_traceback(py, err), "KeyError: 1");
+    });
+}
+
 #[derive(Debug, FromPyObject)]
 pub struct Tuple(String, usize);
 
@@ -122,6 +122,175 @@ fn gc_integration() {
     });
 }
 
+#[allow(dead_code)]
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+#[pyclass(dict)]
+struct GcDictIntegration {
+    dropped: TestDropCall,
+}
+
+#[test]
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+fn gc_dict_integration() {
+    let drop_called = Arc::new(AtomicBool::new(false));
+
+    Python::with_gil(|py| {
+        let inst = Py::new(
+            py,
+            GcDictIntegration {
+                dropped: TestDropCall {
+                    drop_called: Arc::clone(&drop_called),
+                },
+            },
+        )
+        .unwrap();
+
+        // a reference cycle only through the instance dict
+        py_run!(
+            py,
+            inst,
+            "import gc; inst.self_ref = inst; assert inst in gc.get_objects()"
+        );
+    });
+
+    Python::with_gil(|py| {
+        py.run_bound("import gc; gc.collect()", None, None).unwrap();
+        assert!(drop_called.load(Ordering::Relaxed));
+    });
+}
+
+#[allow(dead_code)]
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+#[pyclass(dict)]
+struct GcDictWithTraverse {
+    cycle: Option<PyObject>,
+    dropped: TestDropCall,
+}
+
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+#[pymethods]
+impl GcDictWithTraverse {
+    fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+        visit.call(&self.cycle)
+    }
+
+    fn __clear__(&mut self) {
+        self.cycle = None;
+    }
+}
+
+#[test]
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+fn gc_dict_with_traverse() {
+    let drop_called1 = Arc::new(AtomicBool::new(false));
+    let drop_called2 = Arc::new(AtomicBool::new(false));
+
+    Python::with_gil(|py| {
+        // one cycle through the field, visited by `__traverse__`
+        let inst = Bound::new(
+            py,
+            GcDictWithTraverse {
+                cycle: None,
+                dropped: TestDropCall {
+                    drop_called: Arc::clone(&drop_called1),
+                },
+            },
+        )
+        .unwrap();
+        inst.borrow_mut().cycle = Some(inst.to_object(py));
+
+        // and one through the instance dict
+        let inst = Py::new(
+            py,
+            GcDictWithTraverse {
+                cycle: None,
+                dropped: TestDropCall {
+                    drop_called: Arc::clone(&drop_called2),
+                },
+            },
+        )
+        .unwrap();
+        py_run!(py, inst, "inst.self_ref = inst");
+    });
+
+    Python::with_gil(|py| {
+        py.run_bound("import gc; gc.collect()", None, None).unwrap();
+        assert!(drop_called1.load(Ordering::Relaxed));
+        assert!(drop_called2.load(Ordering::Relaxed));
+    });
+}
+
+#[pyclass(subclass)]
+struct GcBase {
+    cycle: Option<PyObject>,
+}
+
+#[pymethods]
+impl GcBase {
+    fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+        visit.call(&self.cycle)
+    }
+
+    fn __clear__(&mut self) {
+        self.cycle = None;
+    }
+}
+
+#[allow(dead_code)]
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+#[pyclass(extends = GcBase, dict)]
+struct GcDictChild {
+    dropped: TestDropCall,
+}
+
+#[test]
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+fn gc_dict_child_traverses_base() {
+    let drop_called = Arc::new(AtomicBool::new(false));
+
+    Python::with_gil(|py| {
+        let inst = Bound::new(
+            py,
+            (
+                GcDictChild {
+                    dropped: TestDropCall {
+                        drop_called: Arc::clone(&drop_called),
+                    },
+                },
+                GcBase { cycle: None },
+            ),
+        )
+        .unwrap();
+        let obj = inst.to_object(py);
+        inst.borrow_mut().into_super().cycle = Some(obj);
+    });
+
+    Python::with_gil(|py| {
+        py.run_bound("import gc; gc.collect()", None, None).unwrap();
+        assert!(drop_called.load(Ordering::Relaxed));
+    });
+}
+
+#[test]
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+fn dict_released_on_drop() {
+    Python::with_gil(|py| {
+        let inst = Py::new(
+            py,
+            GcDictIntegration {
+                dropped: TestDropCall {
+                    drop_called: Arc::new(AtomicBool::new(false)),
+                },
+            },
+        )
+        .unwrap();
+        let dict = inst.bind(py).getattr("__dict__").unwrap();
+        let refcnt = dict.get_refcnt();
+        drop(inst);
+        assert_eq!(dict.get_refcnt(), refcnt - 1);
+    });
+}
+
 #[pyclass]
 struct GcNullTraversal {
     cycle: Option<Py<Self>>,
@@ -565,6 +734,45 @@ fn unsendable_are_not_traversed_on_foreign_thread() {
     });
 }
 
+#[pyclass]
+struct ClearWithoutTraverse {}
+
+#[pymethods]
+impl ClearWithoutTraverse {
+    fn __clear__(&mut self) {}
+}
+
+#[test]
+#[should_panic(expected = "failed to create type object for ClearWithoutTraverse")]
+fn clear_without_traverse() {
+    Python::with_gil(|py| {
+        py.get_type_bound::<ClearWithoutTraverse>();
+    });
+}
+
+#[pyclass]
+struct TraverseOnly {
+    member: Option<PyObject>,
+}
+
+#[pymethods]
+impl TraverseOnly {
+    fn __traverse__(&self, visit: PyVisit<'_>) -> Result<(), PyTraverseError> {
+        visit.call(&self.member)
+    }
+}
+
+#[test]
+fn traverse_enables_gc() {
+    Python::with_gil(|py| {
+        let ty = py.get_type_bound::<TraverseOnly>();
+        assert_eq!(unsafe { pyo3::ffi::PyType_IS_GC(ty.as_type_ptr()) }, 1);
+
+        let ty = py.get_type_bound::<ClassWithFreelist>();
+        assert_eq!(unsafe { pyo3::ffi::PyType_IS_GC(ty.as_type_ptr()) }, 0);
+    });
+}
+
 // Manual traversal utilities
 
 unsafe fn get_type_traverse(tp: *mut pyo3::ffi::PyTypeObject) -> Option<pyo3::ffi::traverseproc> {
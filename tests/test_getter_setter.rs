@@ -90,6 +90,54 @@ fn class_with_properties() {
     });
 }
 
+#[pyclass]
+struct PropertyDocs {
+    num: i32,
+}
+
+#[pymethods]
+impl PropertyDocs {
+    #[getter]
+    fn get_setter_documented(&self) -> i32 {
+        self.num
+    }
+
+    /// documented on the setter
+    #[setter]
+    fn set_setter_documented(&mut self, value: i32) {
+        self.num = value;
+    }
+
+    /// documented on the getter
+    #[getter]
+    fn get_both_documented(&self) -> i32 {
+        self.num
+    }
+
+    /// also documented on the setter
+    #[setter]
+    fn set_both_documented(&mut self, value: i32) {
+        self.num = value;
+    }
+}
+
+#[test]
+fn property_docs() {
+    Python::with_gil(|py| {
+        let d = [("C", py.get_type_bound::<PropertyDocs>())].into_py_dict_bound(py);
+        py_assert!(
+            py,
+            *d,
+            "C.setter_documented.__doc__ == 'documented on the setter'"
+        );
+        py_assert!(
+            py,
+            *d,
+            "C.both_documented.__doc__ == 'documented on the getter'"
+        );
+    });
+}
+
 #[pyclass]
 struct GetterSetter {
     #[pyo3(get, set)]
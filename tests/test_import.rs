@@ -0,0 +1,109 @@
+use pyo3::exceptions::{PyImportError, PyValueError};
+use pyo3::import;
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::PyList;
+
+#[test]
+fn test_import_from_path() {
+    let dir = std::env::temp_dir().join(format!("pyo3_test_import_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let good = dir.join("good_plugin.py");
+    std::fs::write(&good, "def answer():\n    return 42\n").unwrap();
+    let bad = dir.join("bad_plugin.py");
+    std::fs::write(&bad, "raise ValueError('broken plugin')\n").unwrap();
+
+    Python::with_gil(|py| {
+        let module = PyModule::import_from_path_bound(py, "good_plugin", &good).unwrap();
+        let answer: i32 = module
+            .getattr("answer")
+            .unwrap()
+            .call0()
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert_eq!(answer, 42);
+        // the module is registered like a regularly imported one
+        assert!(py.import_bound("good_plugin").unwrap().is(&module));
+
+        let err = PyModule::import_from_path_bound(py, "bad_plugin", &bad).unwrap_err();
+        assert!(err.is_instance_of::<PyValueError>(py));
+        py_run!(
+            py,
+            *py.import_bound("sys").unwrap().dict(),
+            "assert 'bad_plugin' not in modules"
+        );
+
+        let err =
+            PyModule::import_from_path_bound(py, "not_python", dir.join("data.txt")).unwrap_err();
+        assert!(err.is_instance_of::<PyImportError>(py));
+    });
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_sys_path() {
+    Python::with_gil(|py| {
+        let sys_path = import::sys_path(py).unwrap();
+        let len = sys_path.len();
+
+        import::prepend_sys_path(py, "pyo3_test_first").unwrap();
+        import::prepend_sys_path(py, "pyo3_test_first").unwrap();
+        import::append_sys_path(py, "pyo3_test_last").unwrap();
+        import::append_sys_path(py, "pyo3_test_last").unwrap();
+
+        let sys_path: Bound<'_, PyList> = import::sys_path(py).unwrap();
+        assert_eq!(sys_path.len(), len + 2);
+        let first: String = sys_path.get_item(0).unwrap().extract().unwrap();
+        assert_eq!(first, "pyo3_test_first");
+        let last: String = sys_path.get_item(len + 1).unwrap().extract().unwrap();
+        assert_eq!(last, "pyo3_test_last");
+
+        py_run!(
+            py,
+            sys_path,
+            "sys_path.remove('pyo3_test_first'); sys_path.remove('pyo3_test_last')"
+        );
+    });
+}
+
+#[test]
+fn test_import_hook() {
+    Python::with_gil(|py| {
+        import::add_import_hook(py, |py, name| match name {
+            "pyo3_test_hooked" => {
+                let module = PyModule::new_bound(py, name)?;
+                module.add("VALUE", 7)?;
+                Ok(Some(module))
+            }
+            "pyo3_test_failing" => Err(PyValueError::new_err("cannot provide module")),
+            _ => Ok(None),
+        })
+        .unwrap();
+
+        py_run!(
+            py,
+            *py.import_bound("builtins").unwrap().dict(),
+            r#"
+import pyo3_test_hooked
+assert pyo3_test_hooked.VALUE == 7
+assert pyo3_test_hooked.__spec__.name == "pyo3_test_hooked"
+
+try:
+    import pyo3_test_failing
+except ValueError as e:
+    assert str(e) == "cannot provide module"
+else:
+    assert False
+
+try:
+    import pyo3_test_missing
+except ModuleNotFoundError:
+    pass
+else:
+    assert False
+"#
+        );
+    });
+}
@@ -32,6 +32,41 @@ fn subclass() {
     });
 }
 
+#[pyclass(subclass)]
+struct SubclassHook {}
+
+#[pymethods]
+impl SubclassHook {
+    #[classmethod]
+    #[pyo3(signature = (**kwargs))]
+    fn __init_subclass__(
+        cls: &Bound<'_, pyo3::types::PyType>,
+        kwargs: Option<&Bound<'_, pyo3::types::PyDict>>,
+    ) -> PyResult<()> {
+        cls.setattr("options", kwargs)
+    }
+}
+
+#[test]
+fn init_subclass() {
+    Python::with_gil(|py| {
+        let d = [("SubclassHook", py.get_type_bound::<SubclassHook>())].into_py_dict_bound(py);
+
+        py.run_bound(
+            r#"
+class A(SubclassHook, flag=True): pass
+class B(A): pass
+assert A.options == {"flag": True}
+assert B.options is None
+"#,
+            None,
+            Some(&d),
+        )
+        .map_err(|e| e.display(py))
+        .unwrap();
+    });
+}
+
 #[pymethods]
 impl BaseClass {
     #[new]
@@ -177,7 +212,7 @@ except Exception as e:
 mod inheriting_native_type {
     use super::*;
     use pyo3::exceptions::PyException;
-    use pyo3::types::PyDict;
+    use pyo3::types::{PyDict, PyFloat};
 
     #[cfg(not(PyPy))]
     #[test]
@@ -255,6 +290,88 @@ mod inheriting_native_type {
         })
     }
 
+    #[cfg(not(any(PyPy, GraalPy)))]
+    #[test]
+    fn inherit_list() {
+        use pyo3::types::PyList;
+
+        #[pyclass(extends=PyList)]
+        struct ListWithName {
+            #[pyo3(get)]
+            name: &'static str,
+        }
+
+        #[pymethods]
+        impl ListWithName {
+            #[new]
+            #[pyo3(signature = (*_args))]
+            fn new(_args: &Bound<'_, PyAny>) -> Self {
+                ListWithName { name: "Hello :)" }
+            }
+
+            fn total(slf: &Bound<'_, Self>) -> PyResult<i64> {
+                slf.downcast::<PyList>()?
+                    .iter()
+                    .map(|item| item.extract::<i64>())
+                    .sum()
+            }
+        }
+
+        Python::with_gil(|py| {
+            let cls = py.get_type_bound::<ListWithName>();
+            py_run!(
+                py,
+                cls,
+                r#"
+                list_sub = cls([1, 2])
+                assert isinstance(list_sub, list)
+                list_sub.append(3)
+                assert list_sub == [1, 2, 3]
+                assert len(list_sub) == 3
+                assert list_sub.total() == 6
+                assert list_sub.name == "Hello :)"
+                "#
+            );
+        });
+    }
+
+    #[pyclass(extends=PyFloat)]
+    struct Meters {}
+
+    #[pymethods]
+    impl Meters {
+        #[new]
+        #[pyo3(signature = (*_args))]
+        fn new(_args: &Bound<'_, PyAny>) -> Self {
+            Meters {}
+        }
+
+        fn __repr__(slf: &Bound<'_, Self>) -> PyResult<String> {
+            Ok(format!("{}m", slf.downcast::<PyFloat>()?.value()))
+        }
+    }
+
+    #[test]
+    fn inherit_float_forwards_args() {
+        Python::with_gil(|py| {
+            let cls = py.get_type_bound::<Meters>();
+            py_run!(
+                py,
+                cls,
+                r#"
+                assert cls() == 0.0
+                m = cls("1.5")
+                assert isinstance(m, float)
+                assert m == 1.5
+                assert repr(m) == "1.5m"
+                "#
+            );
+
+            let m = Py::new(py, Meters {}).unwrap();
+            py_run!(py, m, "assert m == 0.0");
+        });
+    }
+
     #[pyclass(extends=PyException)]
     struct CustomException {
         #[pyo3(get)]
@@ -0,0 +1,224 @@
+#![cfg(feature = "macros")]
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+
+#[macro_use]
+#[path = "../src/tests/common.rs"]
+mod common;
+
+#[derive(Debug, IntoPyObject)]
+pub struct Point {
+    x: i32,
+    #[pyo3(item("y-coordinate"))]
+    y: i32,
+    #[pyo3(skip)]
+    #[allow(dead_code)]
+    cache: Vec<u8>,
+}
+
+#[test]
+fn test_named_fields_struct() {
+    Python::with_gil(|py| {
+        let point = Point {
+            x: 1,
+            y: 2,
+            cache: vec![0; 16],
+        }
+        .into_py(py);
+        let point = point.bind(py).downcast::<PyDict>().unwrap();
+        py_assert!(py, point, "point == {'x': 1, 'y-coordinate': 2}");
+    });
+}
+
+#[derive(IntoPyObject)]
+#[pyo3(transparent)]
+pub struct Transparent {
+    inner: String,
+}
+
+#[derive(IntoPyObject)]
+pub struct Newtype(Vec<u32>);
+
+#[test]
+fn test_transparent_structs() {
+    Python::with_gil(|py| {
+        let transparent = Transparent {
+            inner: "test".into(),
+        }
+        .into_py(py);
+        py_assert!(py, transparent, "transparent == 'test'");
+
+        let newtype = Newtype(vec![1, 2, 3]).into_py(py);
+        py_assert!(py, newtype, "newtype == [1, 2, 3]");
+    });
+}
+
+#[derive(IntoPyObject)]
+pub struct Tuple(String, usize, Option<f64>);
+
+#[test]
+fn test_tuple_struct() {
+    Python::with_gil(|py| {
+        let tuple = Tuple("test".into(), 1, None).into_py(py);
+        assert!(tuple.bind(py).is_instance_of::<PyTuple>());
+        py_assert!(py, tuple, "tuple == ('test', 1, None)");
+    });
+}
+
+#[derive(IntoPyObject)]
+pub struct Generic<T> {
+    value: T,
+    #[pyo3(attribute("label"))]
+    name: &'static str,
+}
+
+#[test]
+fn test_generic_struct() {
+    Python::with_gil(|py| {
+        let generic = Generic {
+            value: (1, 2),
+            name: "pair",
+        }
+        .into_py(py);
+        py_assert!(py, generic, "generic == {'value': (1, 2), 'label': 'pair'}");
+    });
+}
+
+#[derive(IntoPyObject)]
+pub enum Shape {
+    Circle {
+        radius: f64,
+    },
+    Rectangle(f64, f64),
+    #[pyo3(transparent)]
+    Named {
+        name: String,
+    },
+    Points(Vec<(i32, i32)>),
+}
+
+#[test]
+fn test_enum() {
+    Python::with_gil(|py| {
+        let circle = Shape::Circle { radius: 1.5 }.into_py(py);
+        py_assert!(py, circle, "circle == {'radius': 1.5}");
+
+        let rectangle = Shape::Rectangle(1.0, 2.0).into_py(py);
+        py_assert!(py, rectangle, "rectangle == (1.0, 2.0)");
+
+        let named = Shape::Named {
+            name: "square".into(),
+        }
+        .into_py(py);
+        py_assert!(py, named, "named == 'square'");
+
+        let points = Shape::Points(vec![(0, 0), (1, 1)]).into_py(py);
+        py_assert!(py, points, "points == [(0, 0), (1, 1)]");
+    });
+}
+
+#[derive(Debug, PartialEq, FromPyObject, IntoPyObject)]
+#[pyo3(from_item_all)]
+pub struct Config {
+    name: String,
+    #[pyo3(item("max-size"))]
+    max_size: usize,
+    #[pyo3(skip)]
+    loaded: bool,
+}
+
+#[test]
+fn test_round_trip() {
+    Python::with_gil(|py| {
+        let config = Config {
+            name: "test".into(),
+            max_size: 10,
+            loaded: true,
+        };
+        let obj = config.into_py(py);
+        py_assert!(py, obj, "obj == {'name': 'test', 'max-size': 10}");
+
+        let config: Config = obj.extract(py).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                name: "test".into(),
+                max_size: 10,
+                loaded: false,
+            }
+        );
+    });
+}
+
+#[derive(Debug, PartialEq, FromPyObject, IntoPyObject)]
+#[pyo3(namedtuple)]
+pub struct Measurement {
+    value: f64,
+    #[pyo3(attribute("units"))]
+    unit: String,
+    #[pyo3(skip)]
+    cached: bool,
+}
+
+#[test]
+fn test_namedtuple() {
+    Python::with_gil(|py| {
+        let measurement = Measurement {
+            value: 1.5,
+            unit: "m".into(),
+            cached: true,
+        };
+        let obj = measurement.into_py(py);
+        assert!(obj.bind(py).is_instance_of::<PyTuple>());
+        py_assert!(py, obj, "obj == (1.5, 'm')");
+        py_assert!(py, obj, "obj.value == 1.5 and obj.units == 'm'");
+        py_assert!(py, obj, "obj._fields == ('value', 'units')");
+        py_assert!(
+            py,
+            obj,
+            "repr(obj) == \"Measurement(value=1.5, units='m')\""
+        );
+
+        // the namedtuple type is only created once
+        let other = Measurement {
+            value: 2.0,
+            unit: "s".into(),
+            cached: false,
+        }
+        .into_py(py);
+        assert!(obj.bind(py).get_type().is(&other.bind(py).get_type()));
+
+        // `FromPyObject` reads the fields back as attributes
+        let measurement: Measurement = obj.extract(py).unwrap();
+        assert_eq!(
+            measurement,
+            Measurement {
+                value: 1.5,
+                unit: "m".into(),
+                cached: false,
+            }
+        );
+    });
+}
+
+#[derive(IntoPyObject)]
+pub enum Event {
+    #[pyo3(namedtuple)]
+    Click {
+        x: i32,
+        y: i32,
+    },
+    Key(String),
+}
+
+#[test]
+fn test_namedtuple_variant() {
+    Python::with_gil(|py| {
+        let click = Event::Click { x: 1, y: 2 }.into_py(py);
+        py_assert!(py, click, "repr(click) == 'Click(x=1, y=2)'");
+
+        let key = Event::Key("a".into()).into_py(py);
+        py_assert!(py, key, "key == 'a'");
+    });
+}
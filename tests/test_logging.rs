@@ -0,0 +1,61 @@
+#![cfg(feature = "log")]
+
+use log::LevelFilter;
+use pyo3::logging::Logger;
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::PyDict;
+
+// The logger is global to the process, so all checks share a single test.
+#[test]
+fn test_logger() {
+    Logger::new().filter(LevelFilter::Debug).install().unwrap();
+    assert!(Logger::new().install().is_err());
+
+    Python::with_gil(|py| {
+        let globals = PyDict::new_bound(py);
+        py.run_bound(
+            r#"
+import logging
+
+records = []
+
+class ListHandler(logging.Handler):
+    def emit(self, record):
+        records.append((record.name, record.levelno, record.getMessage()))
+
+logger = logging.getLogger("test_logging")
+logger.addHandler(ListHandler())
+logger.setLevel(logging.DEBUG)
+logger.propagate = False
+"#,
+            Some(&globals),
+            None,
+        )
+        .unwrap();
+
+        log::info!(target: "test_logging", "hello {}", 1);
+        log::warn!(target: "test_logging::submodule", "100% done");
+        // discarded by the Rust filter
+        log::trace!(target: "test_logging", "trace");
+
+        py.run_bound("logger.setLevel(logging.ERROR)", Some(&globals), None)
+            .unwrap();
+        // discarded by the Python logger level
+        log::warn!(target: "test_logging", "warning");
+        log::error!(target: "test_logging", "error");
+
+        let records = globals.get_item("records").unwrap().unwrap();
+        py_run!(
+            py,
+            records,
+            r#"
+assert records == [
+    ("test_logging", 20, "hello 1"),
+    ("test_logging.submodule", 30, "100% done"),
+    ("test_logging", 40, "error"),
+], records
+"#
+        );
+    });
+}
@@ -0,0 +1,92 @@
+#![cfg(all(feature = "macros", Py_3_12))]
+
+use pyo3::prelude::*;
+use pyo3::py_run;
+use pyo3::types::PyType;
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+const META: &str = r#"
+class Registry(type):
+    instances = 0
+
+    def __call__(cls, *args, **kwargs):
+        Registry.instances += 1
+        return super().__call__(*args, **kwargs)
+
+    def __getitem__(cls, key):
+        return f"{cls.__name__}[{key}]"
+
+    def __repr__(cls):
+        return f"<registered {cls.__name__}>"
+"#;
+
+fn register_metaclass(py: Python<'_>) {
+    let module =
+        PyModule::from_code_bound(py, META, "metaclass_test.py", "metaclass_test").unwrap();
+    py.import_bound("sys")
+        .unwrap()
+        .getattr("modules")
+        .unwrap()
+        .set_item("metaclass_test", module)
+        .unwrap();
+}
+
+#[pyclass(subclass, metaclass = "metaclass_test.Registry")]
+struct Registered {
+    #[pyo3(get)]
+    value: i32,
+}
+
+#[pymethods]
+impl Registered {
+    #[new]
+    fn new(value: i32) -> Self {
+        Registered { value }
+    }
+}
+
+#[test]
+fn python_metaclass() {
+    Python::with_gil(|py| {
+        register_metaclass(py);
+        let cls = py.get_type_bound::<Registered>();
+        let meta = py
+            .import_bound("metaclass_test")
+            .unwrap()
+            .getattr("Registry")
+            .unwrap();
+        py_run!(
+            py,
+            cls meta,
+            r#"
+            assert type(cls) is meta
+            assert repr(cls) == "<registered Registered>"
+            assert cls["int"] == "Registered[int]"
+
+            before = meta.instances
+            assert cls(3).value == 3
+            assert meta.instances == before + 1
+
+            class Sub(cls):
+                pass
+
+            assert type(Sub) is meta
+            assert Sub(4).value == 4
+            assert meta.instances == before + 2
+            "#
+        );
+    });
+}
+
+#[pyclass(metaclass = PyType)]
+struct PlainMetaclass;
+
+#[test]
+fn rust_metaclass() {
+    Python::with_gil(|py| {
+        let cls = py.get_type_bound::<PlainMetaclass>();
+        assert!(cls.get_type().is(&py.get_type_bound::<PyType>()));
+    });
+}
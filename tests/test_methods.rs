@@ -1165,3 +1165,49 @@ fn test_issue_2988() {
     ) {
     }
 }
+
+#[test]
+fn test_method_returning_iterator() {
+    #[pyclass]
+    struct Inventory {
+        items: Vec<String>,
+    }
+
+    #[pymethods]
+    impl Inventory {
+        fn items(&self) -> impl Iterator<Item = String> {
+            self.items.clone().into_iter()
+        }
+
+        fn enumerated(&self) -> impl Iterator<Item = (usize, String)> + Send {
+            self.items.clone().into_iter().enumerate()
+        }
+
+        #[staticmethod]
+        fn countdown(start: u32) -> impl Iterator<Item = u32> {
+            (0..=start).rev()
+        }
+    }
+
+    Python::with_gil(|py| {
+        let inventory = Py::new(
+            py,
+            Inventory {
+                items: vec!["apple".into(), "pear".into()],
+            },
+        )
+        .unwrap();
+        py_run!(
+            py,
+            inventory,
+            r#"
+            items = inventory.items()
+            assert iter(items) is items
+            assert list(items) == ["apple", "pear"]
+            assert list(items) == []
+            assert dict(inventory.enumerated()) == {0: "apple", 1: "pear"}
+            assert list(inventory.countdown(3)) == [3, 2, 1, 0]
+            "#
+        );
+    });
+}
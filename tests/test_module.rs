@@ -91,6 +91,17 @@ fn test_module_with_functions() {
             *d,
             "module_with_functions.LocatedClass.__module__ == 'module'"
         );
+        // Classes without an explicit module are attributed to the module they were added to
+        py_assert!(
+            py,
+            *d,
+            "module_with_functions.ValueClass.__module__ == 'module_with_functions'"
+        );
+        py_assert!(
+            py,
+            *d,
+            "repr(module_with_functions.ValueClass(1)).startswith('<module_with_functions.ValueClass object')"
+        );
         py_assert!(py, *d, "module_with_functions.double(3) == 6");
         py_assert!(
             py,
@@ -156,6 +167,31 @@ fn test_module_renaming() {
     });
 }
 
+#[test]
+#[cfg(feature = "build-info")]
+fn test_module_build_info() {
+    use pyo3::wrap_pymodule;
+
+    Python::with_gil(|py| {
+        let m = wrap_pymodule!(some_name)(py);
+        py_run!(
+            py,
+            m,
+            r#"
+            import sys
+
+            info = m.__pyo3_build_info__
+            assert info["implementation"] in ("CPython", "PyPy", "GraalVM")
+            assert info["abi3"] in ("true", "false")
+            if info["abi3"] == "false":
+                assert info["version"] == "{}.{}".format(*sys.version_info[:2])
+            assert "pyo3_version" in info and "target" in info
+            assert "__pyo3_build_info__" not in getattr(m, "__all__", [])
+            "#
+        );
+    });
+}
+
 #[test]
 fn test_module_from_code_bound() {
     Python::with_gil(|py| {
@@ -314,6 +350,38 @@ fn test_module_nesting() {
     });
 }
 
+#[pyfunction]
+fn requires_json() -> &'static str {
+    "json"
+}
+
+#[pyfunction]
+fn requires_missing_module() -> &'static str {
+    "missing"
+}
+
+#[pymodule]
+fn conditional_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    if m.py().import_bound("json").is_ok() {
+        m.add_function(wrap_pyfunction!(requires_json, m)?)?;
+    }
+    if m.py().import_bound("pyo3_missing_module").is_ok() {
+        m.add_function(wrap_pyfunction!(requires_missing_module, m)?)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_module_conditional_registration() {
+    Python::with_gil(|py| {
+        let m = pyo3::wrap_pymodule!(conditional_module)(py);
+
+        py_assert!(py, m, "m.requires_json() == 'json'");
+        py_assert!(py, m, "not hasattr(m, 'requires_missing_module')");
+        py_assert!(py, m, "m.__all__ == ['requires_json']");
+    });
+}
+
 // Test that argument parsing specification works for pyfunctions
 
 #[pyfunction(signature = (a=5, *args))]
@@ -479,3 +547,66 @@ fn test_module_doc_hidden() {
         py_assert!(py, m, "m.__doc__ == ''");
     })
 }
+
+#[pyfunction]
+fn __getattr__(name: &str) -> PyResult<String> {
+    if name == "dynamic" {
+        Ok("dynamic value".to_string())
+    } else {
+        Err(pyo3::exceptions::PyAttributeError::new_err(
+            name.to_string(),
+        ))
+    }
+}
+
+fn lazy_submodule(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(double, m)?)
+}
+
+#[pymodule]
+fn module_with_getattr(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(__getattr__, m)?)?;
+    m.add_lazy_submodule("lazy", lazy_submodule)?;
+    m.add("constant", 1)
+}
+
+#[test]
+fn test_module_getattr() {
+    Python::with_gil(|py| {
+        let m = pyo3::wrap_pymodule!(module_with_getattr)(py);
+
+        py_assert!(py, m, "m.dynamic == 'dynamic value'");
+        py_assert!(py, m, "m.constant == 1");
+        py_assert!(py, m, "not hasattr(m, 'missing')");
+        py_assert!(py, m, "m.__all__ == ['lazy', 'constant']");
+    });
+}
+
+#[test]
+fn test_lazy_submodule() {
+    Python::with_gil(|py| {
+        let m = pyo3::wrap_pymodule!(module_with_getattr)(py);
+
+        py_run!(
+            py,
+            m,
+            r#"
+            import sys
+            assert "lazy" not in m.__dict__
+            assert "module_with_getattr.lazy" not in sys.modules
+
+            lazy = m.lazy
+            assert lazy.__name__ == "module_with_getattr.lazy"
+            assert lazy.double(2) == 4
+            assert m.__dict__["lazy"] is lazy
+            assert sys.modules["module_with_getattr.lazy"] is lazy
+
+            sys.modules["module_with_getattr"] = m
+            import module_with_getattr.lazy
+            from module_with_getattr.lazy import double
+            assert double(3) == 6
+            del sys.modules["module_with_getattr"]
+            "#
+        );
+    });
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "macros")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::panic::{panic_message, set_panic_handler, PanicException};
+use pyo3::prelude::*;
+use pyo3::py_run;
+
+#[pyfunction]
+fn fail() {
+    panic!("failed in Rust");
+}
+
+#[pyfunction]
+fn fail_with_payload() {
+    std::panic::panic_any(42_u32);
+}
+
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+static WITH_BACKTRACE: AtomicUsize = AtomicUsize::new(0);
+
+fn handler(payload: Box<dyn std::any::Any + Send + 'static>, backtrace: Option<String>) -> PyErr {
+    HANDLED.fetch_add(1, Ordering::SeqCst);
+    if matches!(backtrace, Some(backtrace) if !backtrace.is_empty()) {
+        WITH_BACKTRACE.fetch_add(1, Ordering::SeqCst);
+    }
+    match panic_message(&*payload) {
+        Some(message) => PyRuntimeError::new_err(format!("panic: {}", message)),
+        None => PanicException::from_panic_payload(payload),
+    }
+}
+
+#[test]
+fn test_panic_handler() {
+    Python::with_gil(|py| {
+        let fail = wrap_pyfunction_bound!(fail, py).unwrap();
+        let fail_with_payload = wrap_pyfunction_bound!(fail_with_payload, py).unwrap();
+
+        // fetching a `PanicException` in Rust resumes the panic, so check it from Python
+        let panic_exception = py.get_type_bound::<PanicException>();
+        py_run!(
+            py,
+            fail panic_exception,
+            r#"
+            try:
+                fail()
+            except panic_exception as e:
+                assert str(e) == "failed in Rust"
+            else:
+                assert False
+            "#
+        );
+
+        assert!(set_panic_handler(Some(handler)).is_none());
+
+        let err = fail.call0().unwrap_err();
+        assert!(err.is_instance_of::<PyRuntimeError>(py));
+        assert_eq!(err.to_string(), "RuntimeError: panic: failed in Rust");
+
+        py_run!(
+            py,
+            fail_with_payload panic_exception,
+            r#"
+            try:
+                fail_with_payload()
+            except panic_exception as e:
+                assert str(e) == "panic from Rust code"
+            else:
+                assert False
+            "#
+        );
+        assert_eq!(HANDLED.load(Ordering::SeqCst), 2);
+        assert_eq!(WITH_BACKTRACE.load(Ordering::SeqCst), 0);
+
+        #[cfg(std_backtrace)]
+        {
+            pyo3::panic::install_backtrace_hook();
+            assert!(fail.call0().is_err());
+            assert_eq!(HANDLED.load(Ordering::SeqCst), 3);
+            assert_eq!(WITH_BACKTRACE.load(Ordering::SeqCst), 1);
+        }
+        let handled = HANDLED.load(Ordering::SeqCst);
+
+        assert!(set_panic_handler(None).is_some());
+
+        py_run!(
+            py,
+            fail panic_exception,
+            r#"
+            try:
+                fail()
+            except panic_exception:
+                pass
+            else:
+                assert False
+            "#
+        );
+        assert_eq!(HANDLED.load(Ordering::SeqCst), handled);
+    });
+}
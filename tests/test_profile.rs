@@ -0,0 +1,167 @@
+#![cfg(not(any(Py_LIMITED_API, PyPy, GraalPy)))]
+
+use std::sync::{Arc, Mutex};
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::profile::{self, TraceEvent};
+use pyo3::types::PyFrame;
+
+fn code_name(frame: &Bound<'_, PyFrame>) -> PyResult<String> {
+    frame.getattr("f_code")?.getattr("co_name")?.extract()
+}
+
+const CODE: &str = r#"
+def double(x):
+    return x * 2
+
+def fail():
+    raise ValueError("fail")
+
+double(len("abc"))
+try:
+    fail()
+except ValueError:
+    pass
+"#;
+
+#[test]
+fn test_profile() {
+    Python::with_gil(|py| {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        profile::set_profile(py, move |frame, event| {
+            let event = match event {
+                TraceEvent::Call => "call".to_owned(),
+                TraceEvent::Return(Some(value)) => format!("return {}", value),
+                TraceEvent::Return(None) => "return None".to_owned(),
+                TraceEvent::CCall(function) => format!("c_call {}", function.getattr("__name__")?),
+                TraceEvent::CReturn(_) => "c_return".to_owned(),
+                _ => return Ok(()),
+            };
+            recorded
+                .lock()
+                .unwrap()
+                .push(format!("{}: {}", code_name(frame)?, event));
+            Ok(())
+        })
+        .unwrap();
+        py.run_bound(CODE, None, None).unwrap();
+        profile::clear_profile(py);
+
+        let events = events.lock().unwrap();
+        let expected = [
+            "double: call",
+            "double: return 6",
+            "fail: call",
+            "fail: return None",
+        ];
+        let relevant: Vec<_> = events
+            .iter()
+            .filter(|event| event.starts_with("double") || event.starts_with("fail"))
+            .collect();
+        assert_eq!(relevant, expected);
+        assert!(events.contains(&"<module>: c_call len".to_owned()));
+        assert!(events.contains(&"<module>: c_return".to_owned()));
+    });
+}
+
+#[test]
+fn test_trace() {
+    Python::with_gil(|py| {
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let recorded = lines.clone();
+        profile::set_trace(py, move |frame, event| {
+            match event {
+                TraceEvent::Line if code_name(frame)? == "double" => {
+                    recorded
+                        .lock()
+                        .unwrap()
+                        .push(frame.getattr("f_lineno")?.extract::<u32>()?);
+                }
+                TraceEvent::Exception(exc_info) => {
+                    assert_eq!(exc_info.len(), 3);
+                    recorded.lock().unwrap().push(0);
+                }
+                _ => {}
+            }
+            Ok(())
+        })
+        .unwrap();
+        py.run_bound(CODE, None, None).unwrap();
+        profile::clear_trace(py);
+
+        // line 3 of `double`, then the exception raised by `fail`, which is seen again in
+        // `<module>` when it propagates there
+        assert_eq!(*lines.lock().unwrap(), [3, 0, 0]);
+    });
+}
+
+#[test]
+fn test_profile_error() {
+    Python::with_gil(|py| {
+        profile::set_profile(py, |frame, event| match event {
+            TraceEvent::Call if code_name(frame)? == "double" => {
+                Err(PyRuntimeError::new_err("stop"))
+            }
+            _ => Ok(()),
+        })
+        .unwrap();
+        let err = py.run_bound(CODE, None, None).unwrap_err();
+        assert!(err.is_instance_of::<PyRuntimeError>(py));
+        assert_eq!(err.to_string(), "RuntimeError: stop");
+        profile::clear_profile(py);
+
+        let sys = py.import_bound("sys").unwrap();
+        assert!(sys.call_method0("getprofile").unwrap().is_none());
+    });
+}
+
+#[test]
+fn test_clear_profile_in_callback() {
+    Python::with_gil(|py| {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        profile::set_profile(py, move |frame, event| {
+            if let TraceEvent::Call = event {
+                // removing the hook drops the interpreter's reference to this closure
+                profile::clear_profile(frame.py());
+                recorded.lock().unwrap().push(code_name(frame)?);
+            }
+            Ok(())
+        })
+        .unwrap();
+        py.run_bound(CODE, None, None).unwrap();
+
+        assert_eq!(calls.lock().unwrap().len(), 1);
+        let sys = py.import_bound("sys").unwrap();
+        assert!(sys.call_method0("getprofile").unwrap().is_none());
+    });
+}
+
+#[test]
+fn test_replace_trace_in_callback() {
+    Python::with_gil(|py| {
+        let events = Arc::new(Mutex::new(0));
+        let recorded = events.clone();
+        profile::set_trace(py, move |frame, _event| {
+            let recorded = recorded.clone();
+            // replacing the hook drops the closure currently running
+            profile::set_trace(frame.py(), move |_frame, _event| {
+                *recorded.lock().unwrap() += 1;
+                Ok(())
+            })?;
+            // runs Python code which removes the hook again
+            frame
+                .py()
+                .import_bound("sys")?
+                .call_method1("settrace", (frame.py().None(),))?;
+            Ok(())
+        })
+        .unwrap();
+        py.run_bound(CODE, None, None).unwrap();
+        profile::clear_trace(py);
+
+        assert_eq!(*events.lock().unwrap(), 0);
+    });
+}
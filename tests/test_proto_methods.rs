@@ -1,7 +1,7 @@
 #![cfg(feature = "macros")]
 
-use pyo3::exceptions::{PyAttributeError, PyIndexError, PyValueError};
-use pyo3::types::{PyDict, PyList, PyMapping, PySequence, PySlice, PyType};
+use pyo3::exceptions::{PyAttributeError, PyBaseException, PyIndexError, PyValueError};
+use pyo3::types::{PyDict, PyList, PyMapping, PySequence, PySlice, PyString, PyTraceback, PyType};
 use pyo3::{prelude::*, py_run};
 use std::iter;
 
@@ -629,6 +629,122 @@ fn getattr_and_getattribute() {
     });
 }
 
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+#[pyclass(dict)]
+struct ClassWithSetAttr {
+    #[pyo3(get, set)]
+    data: u32,
+}
+
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+#[pymethods]
+impl ClassWithSetAttr {
+    fn __setattr__(&mut self, name: &str, value: u32) -> PyResult<()> {
+        if name == "doubled" {
+            self.data = value * 2;
+            Ok(())
+        } else {
+            Err(PyAttributeError::new_err(format!("can't set {}", name)))
+        }
+    }
+}
+
+#[test]
+#[cfg(any(Py_3_9, not(Py_LIMITED_API)))]
+fn setattr_without_delattr() {
+    Python::with_gil(|py| {
+        let inst = Py::new(py, ClassWithSetAttr { data: 4 }).unwrap();
+        py_run!(py, inst, "inst.doubled = 3; assert inst.data == 6");
+        py_expect_exception!(py, inst, "inst.other = 1", PyAttributeError);
+        // deletion falls back to normal attribute lookup
+        py_run!(py, inst, "inst.__dict__['other'] = 1; del inst.other");
+        py_assert!(py, inst, "not hasattr(inst, 'other')");
+        py_expect_exception!(py, inst, "del inst.missing", PyAttributeError);
+    });
+}
+
+#[pyclass]
+struct ClassWithDelAttr {
+    #[pyo3(get, set)]
+    data: u32,
+}
+
+#[pymethods]
+impl ClassWithDelAttr {
+    fn __delattr__(&mut self, name: &str) -> PyResult<()> {
+        if name == "data" {
+            self.data = 0;
+            Ok(())
+        } else {
+            Err(PyAttributeError::new_err(format!("can't delete {}", name)))
+        }
+    }
+}
+
+#[test]
+fn delattr_without_setattr() {
+    Python::with_gil(|py| {
+        let inst = Py::new(py, ClassWithDelAttr { data: 4 }).unwrap();
+        // setting falls back to normal attribute lookup, so the setter is still used
+        py_run!(py, inst, "inst.data = 7; assert inst.data == 7");
+        py_run!(py, inst, "del inst.data; assert inst.data == 0");
+        py_expect_exception!(py, inst, "inst.other = 1", PyAttributeError);
+        py_expect_exception!(py, inst, "del inst.other", PyAttributeError);
+    });
+}
+
+/// Forwards all attribute access to a wrapped Python object.
+#[pyclass]
+struct Proxy {
+    target: PyObject,
+}
+
+#[pymethods]
+impl Proxy {
+    fn __getattr__(&self, py: Python<'_>, name: &Bound<'_, PyString>) -> PyResult<PyObject> {
+        self.target.getattr(py, name)
+    }
+
+    fn __setattr__(
+        &self,
+        py: Python<'_>,
+        name: &Bound<'_, PyString>,
+        value: PyObject,
+    ) -> PyResult<()> {
+        self.target.setattr(py, name, value)
+    }
+
+    fn __delattr__(&self, py: Python<'_>, name: &Bound<'_, PyString>) -> PyResult<()> {
+        self.target.bind(py).delattr(name)
+    }
+}
+
+#[test]
+fn proxy_attribute_access() {
+    Python::with_gil(|py| {
+        let target = py
+            .eval_bound("__import__('types').SimpleNamespace(x=1)", None, None)
+            .unwrap();
+        let proxy = Py::new(
+            py,
+            Proxy {
+                target: target.clone().unbind(),
+            },
+        )
+        .unwrap();
+        py_run!(py, proxy target, r#"
+proxy.y = 2
+assert target.y == 2
+assert proxy.x == 1 and proxy.y == 2
+del proxy.x
+assert not hasattr(target, "x")
+assert proxy.__class__.__name__ == "Proxy"
+"#);
+        py_expect_exception!(py, proxy, "proxy.missing", PyAttributeError);
+        py_expect_exception!(py, proxy, "del proxy.missing", PyAttributeError);
+    });
+}
+
 /// Wraps a Python future and yield it once.
 #[pyclass]
 #[derive(Debug)]
@@ -748,6 +864,83 @@ asyncio.run(main())
     });
 }
 
+/// Records the exceptions passed to `__exit__`, suppressing `ValueError`s.
+#[pyclass]
+#[derive(Default)]
+struct ExitRecorder {
+    entered: bool,
+    exception: Option<String>,
+    had_traceback: bool,
+}
+
+#[pymethods]
+impl ExitRecorder {
+    fn __enter__(mut slf: PyRefMut<'_, Self>) -> PyRefMut<'_, Self> {
+        slf.entered = true;
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        exc_type: Option<&Bound<'_, PyType>>,
+        exc_value: Option<&Bound<'_, PyBaseException>>,
+        traceback: Option<&Bound<'_, PyTraceback>>,
+    ) -> PyResult<bool> {
+        self.exception = exc_value.map(|value| value.to_string());
+        self.had_traceback = traceback.is_some();
+        match exc_type {
+            Some(ty) => ty.is_subclass_of::<PyValueError>(),
+            None => Ok(false),
+        }
+    }
+}
+
+#[test]
+fn test_context_manager() {
+    Python::with_gil(|py| {
+        let recorder = Py::new(py, ExitRecorder::default()).unwrap();
+        py_run!(
+            py,
+            recorder,
+            r#"
+with recorder as r:
+    assert r is recorder
+"#
+        );
+        {
+            let recorder = recorder.borrow(py);
+            assert!(recorder.entered);
+            assert_eq!(recorder.exception, None);
+            assert!(!recorder.had_traceback);
+        }
+
+        py_run!(
+            py,
+            recorder,
+            r#"
+with recorder:
+    raise ValueError("suppressed")
+"#
+        );
+        {
+            let recorder = recorder.borrow(py);
+            assert_eq!(recorder.exception.as_deref(), Some("suppressed"));
+            assert!(recorder.had_traceback);
+        }
+
+        py_expect_exception!(
+            py,
+            recorder,
+            r#"
+with recorder:
+    raise IndexError("propagated")
+"#,
+            PyIndexError
+        );
+        assert_eq!(recorder.borrow(py).exception.as_deref(), Some("propagated"));
+    });
+}
+
 /// Increment the count when `__get__` is called.
 #[pyclass]
 struct DescrCounter {
@@ -817,6 +1010,173 @@ assert c.counter.count == 1
     });
 }
 
+/// Records the attribute name it was assigned to in a class body.
+#[pyclass]
+struct DescrName {
+    #[pyo3(get)]
+    name: Option<String>,
+}
+
+#[pymethods]
+impl DescrName {
+    #[new]
+    fn new() -> Self {
+        DescrName { name: None }
+    }
+
+    fn __set_name__(&mut self, _owner: &Bound<'_, PyType>, name: String) {
+        self.name = Some(name);
+    }
+}
+
+#[test]
+fn descr_set_name() {
+    Python::with_gil(|py| {
+        let descr = py.get_type_bound::<DescrName>();
+        let source = pyo3::indoc::indoc!(
+            r#"
+class Class:
+    first = Descr()
+    second = Descr()
+
+assert Class.__dict__["first"].name == "first"
+assert Class.__dict__["second"].name == "second"
+assert Descr().name is None
+"#
+        );
+        let globals = PyModule::import_bound(py, "__main__").unwrap().dict();
+        globals.set_item("Descr", descr).unwrap();
+        py.run_bound(source, Some(&globals), None)
+            .map_err(|e| e.display(py))
+            .unwrap();
+    });
+}
+
+/// A validating field which stores its value in the instance `__dict__`.
+#[pyclass]
+struct PositiveField {
+    name: Option<String>,
+}
+
+#[pymethods]
+impl PositiveField {
+    #[new]
+    fn new() -> Self {
+        PositiveField { name: None }
+    }
+
+    fn __set_name__(&mut self, _owner: &Bound<'_, PyType>, name: String) {
+        self.name = Some(name);
+    }
+
+    fn __get__<'py>(
+        slf: &Bound<'py, Self>,
+        instance: &Bound<'py, PyAny>,
+        _owner: Option<&Bound<'py, PyType>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if instance.is_none() {
+            return Ok(slf.clone().into_any());
+        }
+        let field = slf.borrow();
+        let name = field.name.as_deref().unwrap_or_default();
+        instance
+            .getattr("__dict__")?
+            .get_item(name)
+            .map_err(|_| PyAttributeError::new_err(name.to_owned()))
+    }
+
+    fn __set__(&self, instance: &Bound<'_, PyAny>, value: i64) -> PyResult<()> {
+        if value <= 0 {
+            return Err(PyValueError::new_err("value must be positive"));
+        }
+        let name = self.name.as_deref().unwrap_or_default();
+        instance.getattr("__dict__")?.set_item(name, value)
+    }
+
+    fn __delete__(&self, instance: &Bound<'_, PyAny>) -> PyResult<()> {
+        let name = self.name.as_deref().unwrap_or_default();
+        instance.getattr("__dict__")?.del_item(name)
+    }
+}
+
+#[test]
+fn descr_validating_field() {
+    Python::with_gil(|py| {
+        let field = py.get_type_bound::<PositiveField>();
+        let source = pyo3::indoc::indoc!(
+            r#"
+class Model:
+    size = Field()
+
+# class access returns the descriptor itself
+assert isinstance(Model.size, Field)
+
+m = Model()
+try:
+    m.size
+except AttributeError:
+    pass
+else:
+    assert False
+
+m.size = 3
+assert m.size == 3
+assert m.__dict__["size"] == 3
+
+# data descriptors take precedence over the instance dict
+m.__dict__["size"] = 5
+assert m.size == 5
+
+try:
+    m.size = -1
+except ValueError as e:
+    assert str(e) == "value must be positive"
+else:
+    assert False
+assert m.size == 5
+
+try:
+    m.size = "big"
+except TypeError:
+    pass
+else:
+    assert False
+
+del m.size
+assert "size" not in m.__dict__
+"#
+        );
+        let globals = PyModule::import_bound(py, "__main__").unwrap().dict();
+        globals.set_item("Field", field).unwrap();
+        py.run_bound(source, Some(&globals), None)
+            .map_err(|e| e.display(py))
+            .unwrap();
+    });
+}
+
+/// A data descriptor which can be assigned but not deleted.
+#[pyclass]
+struct SetOnlyDescr;
+
+#[pymethods]
+impl SetOnlyDescr {
+    fn __set__(&self, _instance: &Bound<'_, PyAny>, _value: &Bound<'_, PyAny>) {}
+}
+
+#[test]
+fn descr_missing_delete() {
+    Python::with_gil(|py| {
+        let descr = Py::new(py, SetOnlyDescr).unwrap();
+        let class = py.eval_bound("type('Class', (), {})", None, None).unwrap();
+        class.setattr("attr", descr).unwrap();
+        let instance = class.call0().unwrap();
+        instance.setattr("attr", 1).unwrap();
+        let err = instance.delattr("attr").unwrap_err();
+        assert!(err.is_instance_of::<PyAttributeError>(py));
+        assert_eq!(err.to_string(), "AttributeError: __delete__");
+    });
+}
+
 #[pyclass]
 struct NotHashable;
 
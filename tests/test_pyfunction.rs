@@ -553,6 +553,96 @@ fn test_some_wrap_arguments() {
     })
 }
 
+/// A type which doesn't implement `IntoPy`, e.g. because it comes from another crate.
+struct Celsius(f64);
+
+fn celsius_to_py(value: Celsius, py: Python<'_>) -> PyResult<PyObject> {
+    if !value.0.is_finite() {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "temperature must be finite",
+        ));
+    }
+    Ok(format!("{}°C", value.0).into_py(py))
+}
+
+#[pyfunction]
+#[pyo3(into_py_with = "celsius_to_py")]
+fn freezing_point() -> Celsius {
+    Celsius(0.0)
+}
+
+#[pyfunction(into_py_with = "celsius_to_py")]
+fn checked_temperature(value: f64) -> PyResult<Celsius> {
+    if value < -273.15 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "below absolute zero",
+        ));
+    }
+    Ok(Celsius(value))
+}
+
+#[test]
+fn test_function_with_into_py_with() {
+    Python::with_gil(|py| {
+        let freezing_point = wrap_pyfunction_bound!(freezing_point)(py).unwrap();
+        let checked_temperature = wrap_pyfunction_bound!(checked_temperature)(py).unwrap();
+
+        py_assert!(py, freezing_point, "freezing_point() == '0°C'");
+        py_assert!(
+            py,
+            checked_temperature,
+            "checked_temperature(21.5) == '21.5°C'"
+        );
+        py_expect_exception!(
+            py,
+            checked_temperature,
+            "checked_temperature(float('inf'))",
+            PyValueError,
+            "temperature must be finite"
+        );
+        py_expect_exception!(
+            py,
+            checked_temperature,
+            "checked_temperature(-300)",
+            PyValueError,
+            "below absolute zero"
+        );
+    });
+}
+
+#[test]
+fn test_signature_with_all_argument_kinds() {
+    #[pyfunction(signature = (a, b = 1, /, *, key = None, **kwargs))]
+    fn all_argument_kinds(
+        a: i32,
+        b: i32,
+        key: Option<&str>,
+        kwargs: Option<&Bound<'_, types::PyDict>>,
+    ) -> (i32, i32, Option<String>, usize) {
+        (
+            a,
+            b,
+            key.map(str::to_owned),
+            kwargs.map_or(0, |kwargs| kwargs.len()),
+        )
+    }
+
+    Python::with_gil(|py| {
+        let f = wrap_pyfunction_bound!(all_argument_kinds, py).unwrap();
+        py_assert!(py, f, "f(1) == (1, 1, None, 0)");
+        py_assert!(py, f, "f(1, 2, key='k', x=3, y=4) == (1, 2, 'k', 2)");
+        // `a` and `b` are positional-only, so they end up in `**kwargs` when passed by name
+        py_assert!(py, f, "f(1, a=2, b=3) == (1, 1, None, 2)");
+        py_expect_exception!(py, f, "f(1, 2, 'k')", PyTypeError);
+        py_expect_exception!(py, f, "f()", PyTypeError);
+        py_assert!(
+            py,
+            f,
+            "f.__text_signature__ == '(a, b=1, /, *, key=None, **kwargs)'"
+        );
+    })
+}
+
 #[test]
 fn test_reference_to_bound_arguments() {
     #[pyfunction]
@@ -597,3 +687,42 @@ fn test_pyfunction_raw_ident() {
         py_assert!(py, m, "m.enum()");
     })
 }
+
+#[test]
+fn test_pyfunction_returning_iterator() {
+    #[pyfunction]
+    fn squares(n: u64) -> impl Iterator<Item = u64> {
+        (1..=n).map(|i| i * i)
+    }
+
+    Python::with_gil(|py| {
+        let squares = wrap_pyfunction_bound!(squares, py).unwrap();
+        py_assert!(py, squares, "list(squares(4)) == [1, 4, 9, 16]");
+        py_assert!(py, squares, "sum(squares(100)) == 338350");
+    });
+}
+
+#[test]
+fn test_maybe_missing_argument() {
+    use pyo3::argument::MaybeMissing;
+
+    #[pyfunction]
+    #[pyo3(signature = (value = MaybeMissing::Missing, *, key = MaybeMissing::Missing))]
+    fn describe(value: MaybeMissing<Option<i32>>, key: MaybeMissing<Bound<'_, PyAny>>) -> String {
+        let key = match key {
+            MaybeMissing::Missing => "missing".to_owned(),
+            MaybeMissing::Present(key) => key.repr().unwrap().to_string(),
+        };
+        format!("{:?} {}", value, key)
+    }
+
+    Python::with_gil(|py| {
+        let f = wrap_pyfunction_bound!(describe, py).unwrap();
+        py_assert!(py, f, "f() == 'Missing missing'");
+        py_assert!(py, f, "f(None) == 'Present(None) missing'");
+        py_assert!(py, f, "f(3, key=None) == 'Present(Some(3)) None'");
+        py_assert!(py, f, "f(key='k') == \"Missing 'k'\"");
+        py_assert!(py, f, "f.__text_signature__ == '(value=..., *, key=...)'");
+        py_expect_exception!(py, f, "f('a')", PyTypeError);
+    });
+}
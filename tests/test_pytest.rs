@@ -0,0 +1,91 @@
+#![cfg(feature = "macros")]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::py_run;
+
+#[pyfunction]
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+#[pymodule]
+fn test_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(double, m)?)
+}
+
+mod nested {
+    use pyo3::prelude::*;
+
+    #[pymodule]
+    pub fn nested_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+        m.add("ANSWER", 42)
+    }
+}
+
+#[pyo3::testing::test]
+fn test_python_token(py: Python<'_>) {
+    assert!(py.version_info() >= (3, 7));
+}
+
+#[pyo3::testing::test]
+fn test_without_arguments() {
+    // the GIL is already held, nested acquisition reuses it
+    Python::with_gil(|py| {
+        assert_eq!(
+            py.eval_bound("1 + 1", None, None)
+                .unwrap()
+                .extract::<i32>()
+                .unwrap(),
+            2
+        )
+    });
+}
+
+#[pyo3::testing::test]
+fn test_result(py: Python<'_>) -> PyResult<()> {
+    let value: i32 = py.eval_bound("6 * 7", None, None)?.extract()?;
+    assert_eq!(value, 42);
+    Ok(())
+}
+
+#[pyo3::testing::test(module = test_module, module = nested::nested_module)]
+fn test_modules(py: Python<'_>) {
+    py_run!(
+        py,
+        *py.import_bound("builtins").unwrap().dict(),
+        r#"
+import test_module
+import nested_module
+assert test_module.double(21) == nested_module.ANSWER
+"#
+    );
+}
+
+/// Other attributes are kept on the test.
+#[pyo3::testing::test]
+#[should_panic(expected = "invalid literal")]
+fn test_attributes(py: Python<'_>) {
+    let err = py.eval_bound("int('x')", None, None).unwrap_err();
+    assert!(err.is_instance_of::<PyValueError>(py));
+    panic!("{}", err);
+}
+
+/// Importing PyO3's `test` attribute, or everything from `pyo3`, must not break `#[test]`.
+mod imports {
+    #[allow(unused_imports)]
+    use pyo3::*;
+
+    #[test]
+    fn test_plain_with_crate_glob() {}
+
+    mod testing_import {
+        use pyo3::testing::test;
+
+        // this `#[test]` is PyO3's, which must expand to the builtin attribute, not to itself
+        #[test]
+        fn test_imported_attribute(py: pyo3::Python<'_>) {
+            assert!(py.version_info() >= (3, 7));
+        }
+    }
+}
@@ -1,7 +1,7 @@
 #![cfg(feature = "macros")]
 
 use pyo3::exceptions::{PyIndexError, PyValueError};
-use pyo3::types::{IntoPyDict, PyList, PyMapping, PySequence};
+use pyo3::types::{IntoPyDict, PyList, PyMapping, PySequence, SliceOrIndex};
 use pyo3::{ffi, prelude::*};
 
 use pyo3::py_run;
@@ -360,3 +360,56 @@ fn sequence_length() {
         unsafe { ffi::PyErr_Clear() };
     })
 }
+
+#[pyclass]
+struct SliceableSequence {
+    items: Vec<i32>,
+}
+
+#[pymethods]
+impl SliceableSequence {
+    fn __len__(&self) -> usize {
+        self.items.len()
+    }
+
+    fn __getitem__(&self, key: SliceOrIndex<'_>, py: Python<'_>) -> PyResult<PyObject> {
+        match key {
+            SliceOrIndex::Index(idx) => {
+                let len = self.items.len() as isize;
+                let idx = if idx < 0 { idx + len } else { idx };
+                if idx < 0 || idx >= len {
+                    return Err(PyIndexError::new_err("Index out of bounds"));
+                }
+                Ok(self.items[idx as usize].into_py(py))
+            }
+            SliceOrIndex::Slice(slice) => {
+                let indices = slice.indices(self.items.len() as isize)?;
+                let items: Vec<i32> = (0..indices.slicelength as isize)
+                    .map(|i| self.items[(indices.start + i * indices.step) as usize])
+                    .collect();
+                Ok(items.into_py(py))
+            }
+        }
+    }
+}
+
+#[test]
+fn sequence_slicing() {
+    Python::with_gil(|py| {
+        let seq = Py::new(
+            py,
+            SliceableSequence {
+                items: vec![1, 2, 3, 4, 5],
+            },
+        )
+        .unwrap();
+
+        py_assert!(py, seq, "seq[-1] == 5");
+        py_assert!(py, seq, "seq[1:3] == [2, 3]");
+        py_assert!(py, seq, "seq[::-2] == [5, 3, 1]");
+        py_assert!(py, seq, "seq[10:] == []");
+        py_expect_exception!(py, seq, "seq[5]", PyIndexError);
+        py_expect_exception!(py, seq, "seq[-6]", PyIndexError);
+        py_expect_exception!(py, seq, "seq['a']", PyTypeError);
+    });
+}
@@ -77,4 +77,101 @@ mod test_serde {
             )
         });
     }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rectangle(u32, u32),
+        Polygon { points: Vec<(i64, i64)> },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Drawing {
+        name: String,
+        shapes: Vec<Shape>,
+        tags: std::collections::BTreeMap<String, bool>,
+        parent: Option<Box<Drawing>>,
+        id: u128,
+    }
+
+    fn drawing() -> Drawing {
+        Drawing {
+            name: "sketch".into(),
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(1.5),
+                Shape::Rectangle(2, 3),
+                Shape::Polygon {
+                    points: vec![(0, 0), (-1, 2)],
+                },
+            ],
+            tags: [("draft".to_owned(), true)].into_iter().collect(),
+            parent: None,
+            id: u128::MAX,
+        }
+    }
+
+    #[test]
+    fn test_pythonize() {
+        Python::with_gil(|py| {
+            let obj = pyo3::serde::pythonize(py, &drawing()).unwrap();
+            let expected = py
+                .eval_bound(
+                    "{'name': 'sketch', 'shapes': ['Empty', {'Circle': 1.5}, \
+                     {'Rectangle': (2, 3)}, {'Polygon': {'points': [(0, 0), (-1, 2)]}}], \
+                     'tags': {'draft': True}, 'parent': None, 'id': 2 ** 128 - 1}",
+                    None,
+                    None,
+                )
+                .unwrap();
+            assert!(obj.eq(expected).unwrap(), "{}", obj);
+        });
+    }
+
+    #[test]
+    fn test_depythonize() {
+        Python::with_gil(|py| {
+            let obj = pyo3::serde::pythonize(py, &drawing()).unwrap();
+            let roundtrip: Drawing = pyo3::serde::depythonize(&obj).unwrap();
+            assert_eq!(roundtrip, drawing());
+
+            // other sequence types and mappings are accepted too
+            let obj = py
+                .eval_bound(
+                    "{'name': 'copy', 'shapes': ({'Polygon': {'points': [[1, 2]]}},), \
+                     'tags': {}, 'parent': {'name': 'sketch', 'shapes': [], 'tags': {}, \
+                     'parent': None, 'id': 0}, 'id': 1}",
+                    None,
+                    None,
+                )
+                .unwrap();
+            let drawing: Drawing = pyo3::serde::depythonize(&obj).unwrap();
+            assert_eq!(
+                drawing.shapes,
+                vec![Shape::Polygon {
+                    points: vec![(1, 2)]
+                }]
+            );
+            assert_eq!(drawing.parent.unwrap().name, "sketch");
+        });
+    }
+
+    #[test]
+    fn test_depythonize_errors() {
+        Python::with_gil(|py| {
+            let obj = py.eval_bound("{'name': 'sketch'}", None, None).unwrap();
+            let err = pyo3::serde::depythonize::<Drawing>(&obj).unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyValueError>(py));
+            assert_eq!(err.to_string(), "ValueError: missing field `shapes`");
+
+            let obj = py.eval_bound("object()", None, None).unwrap();
+            let err = pyo3::serde::depythonize::<Vec<u8>>(&obj).unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+
+            let obj = py.eval_bound("-1", None, None).unwrap();
+            assert!(pyo3::serde::depythonize::<u32>(&obj).is_err());
+            assert_eq!(pyo3::serde::depythonize::<i8>(&obj).unwrap(), -1);
+        });
+    }
 }
@@ -0,0 +1,138 @@
+#![cfg(all(feature = "macros", feature = "experimental-inspect"))]
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::inspect::stubs::module_stub;
+use pyo3::prelude::*;
+use pyo3::wrap_pymodule;
+
+#[path = "../src/tests/common.rs"]
+mod common;
+
+/// A point.
+#[pyclass(module = "stubbed")]
+struct Point {
+    /// The x coordinate.
+    #[pyo3(get, set)]
+    x: f64,
+    #[pyo3(get)]
+    y: f64,
+}
+
+#[pymethods]
+impl Point {
+    #[new]
+    #[pyo3(signature = (x, y = 0.0))]
+    fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+
+    /// Scales the point.
+    ///
+    /// Both coordinates are multiplied by `factor`.
+    fn scale(&mut self, factor: f64) {
+        self.x *= factor;
+        self.y *= factor;
+    }
+
+    #[staticmethod]
+    fn origin() -> Self {
+        Point { x: 0.0, y: 0.0 }
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (*, x))]
+    fn on_x_axis(_cls: &Bound<'_, pyo3::types::PyType>, x: f64) -> Self {
+        Point { x, y: 0.0 }
+    }
+
+    #[classattr]
+    const DIMENSIONS: usize = 2;
+
+    fn __len__(&self) -> usize {
+        2
+    }
+}
+
+#[pyclass(module = "stubbed")]
+struct Empty {}
+
+create_exception!(stubbed, StubbedError, PyException, "Raised on purpose.");
+
+/// Adds two numbers.
+#[pyfunction]
+#[pyo3(signature = (a, b = 1, /, *, c = None))]
+fn add(a: i32, b: i32, c: Option<i32>) -> i32 {
+    a + b + c.unwrap_or(0)
+}
+
+#[pyfunction]
+fn undocumented() {}
+
+#[pymodule]
+fn nested(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("NESTED", true)
+}
+
+/// A module to generate stubs for.
+#[pymodule]
+fn stubbed(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Point>()?;
+    m.add_class::<Empty>()?;
+    m.add("StubbedError", m.py().get_type_bound::<StubbedError>())?;
+    m.add_function(wrap_pyfunction!(add, m)?)?;
+    m.add_function(wrap_pyfunction!(undocumented, m)?)?;
+    m.add_wrapped(wrap_pymodule!(nested))?;
+    m.add("VERSION", "1.0")?;
+    m.add("ORIGIN", Point::origin())?;
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(all(Py_LIMITED_API, not(Py_3_10)), ignore)]
+fn test_module_stub() {
+    Python::with_gil(|py| {
+        let module = wrap_pymodule!(stubbed)(py);
+        let stub = module_stub(module.bind(py)).unwrap();
+        assert_eq!(
+            stub,
+            r#""""A module to generate stubs for."""
+
+from typing import Any, ClassVar
+
+class Point:
+    """A point."""
+    def __new__(cls, x, y=0.0): ...
+    DIMENSIONS: ClassVar[int]
+    def __len__(self, /):
+        """Return len(self)."""
+    @classmethod
+    def on_x_axis(cls, /, *, x): ...
+    @staticmethod
+    def origin(): ...
+    def scale(self, /, factor):
+        """Scales the point.
+
+        Both coordinates are multiplied by `factor`.
+        """
+    x: Any
+    """The x coordinate."""
+    y: Any
+
+class Empty: ...
+
+class StubbedError(Exception):
+    """Raised on purpose."""
+
+def add(a, b=1, /, *, c=None):
+    """Adds two numbers."""
+
+def undocumented(): ...
+
+VERSION: str
+
+ORIGIN: Point
+"#
+        );
+    });
+}
@@ -0,0 +1,18 @@
+//! With abi3, the buffer protocol is not supported until python 3.11 or greater
+use pyo3::prelude::*;
+
+#[pyclass]
+struct TestClass {}
+
+#[pymethods]
+impl TestClass {
+    unsafe fn __getbuffer__(
+        _slf: PyRefMut<'_, Self>,
+        _view: *mut std::os::raw::c_void,
+        _flags: std::os::raw::c_int,
+    ) -> PyResult<()> {
+        Ok(())
+    }
+}
+
+fn main() {}
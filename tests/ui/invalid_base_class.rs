@@ -0,0 +1,7 @@
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+
+#[pyclass(extends=PyTuple)]
+struct ExtendsTuple {}
+
+fn main() {}
@@ -0,0 +1,48 @@
+use pyo3::prelude::*;
+
+#[pyclass(subclass)]
+struct Base {}
+
+#[pyclass(extends = Base)]
+struct Sub {}
+
+#[pymethods]
+impl Sub {
+    #[new]
+    fn new() -> (Base, Sub) {
+        (Base {}, Sub {})
+    }
+}
+
+#[pyclass(extends = Base)]
+struct SubWithoutBase {}
+
+#[pymethods]
+impl SubWithoutBase {
+    #[new]
+    fn new() -> Self {
+        SubWithoutBase {}
+    }
+}
+
+#[pyclass]
+struct Plain {}
+
+#[pymethods]
+impl Plain {
+    #[new]
+    fn new() -> PyResult<i32> {
+        Ok(1)
+    }
+}
+
+#[pyclass]
+struct StaticInit {}
+
+#[pymethods]
+impl StaticInit {
+    #[staticmethod]
+    fn __init__() {}
+}
+
+fn main() {}
@@ -0,0 +1,54 @@
+use pyo3::IntoPyObject;
+
+#[derive(IntoPyObject)]
+struct Foo();
+
+#[derive(IntoPyObject)]
+struct Foo2 {}
+
+#[derive(IntoPyObject)]
+enum EmptyEnum {}
+
+#[derive(IntoPyObject)]
+enum EnumWithEmptyTupleVar {
+    EmptyTuple(),
+    Valid(String),
+}
+
+#[derive(IntoPyObject)]
+struct SkipTupleElement(#[pyo3(skip)] String, String);
+
+#[derive(IntoPyObject)]
+struct SkipAllFields {
+    #[pyo3(skip)]
+    a: String,
+}
+
+#[derive(IntoPyObject)]
+#[pyo3(transparent)]
+struct SkipTransparent {
+    #[pyo3(skip)]
+    a: String,
+}
+
+#[derive(IntoPyObject)]
+#[pyo3(transparent)]
+struct TransparentTuple(String, String);
+
+#[derive(IntoPyObject)]
+#[pyo3(namedtuple)]
+struct NamedTupleTuple(String, String);
+
+#[derive(IntoPyObject)]
+#[pyo3(namedtuple)]
+struct NamedTupleItemKey {
+    #[pyo3(item("key"))]
+    a: String,
+}
+
+#[derive(IntoPyObject)]
+union Union {
+    a: usize,
+}
+
+fn main() {}
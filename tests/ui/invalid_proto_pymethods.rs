@@ -49,6 +49,14 @@ impl MyClass {
     }
 }
 
+#[pymethods]
+impl MyClass {
+    #[pyo3(into_py_with = "std::convert::identity")]
+    fn __repr__(&self) -> String {
+        String::new()
+    }
+}
+
 #[pyclass]
 struct EqAndRichcmp;
 
@@ -63,4 +71,12 @@ impl EqAndRichcmp {
     }
 }
 
+#[pyclass]
+struct ExitMissingArguments;
+
+#[pymethods]
+impl ExitMissingArguments {
+    fn __exit__(&self, _exc_type: &Bound<'_, PyAny>) {}
+}
+
 fn main() {}
@@ -76,4 +76,7 @@ struct InvalidOrderedStruct {
     inner: i32
 }
 
+#[pyclass(metaclass = "NoModule")]
+struct InvalidMetaclassPath;
+
 fn main() {}
@@ -82,15 +82,25 @@ enum ComplexHashOptRequiresEq {
 
 #[pyclass(ord)]
 enum InvalidOrderedComplexEnum {
-    VariantA (i32),
-    VariantB { msg: String }
+    VariantA(i32),
+    VariantB { msg: String },
 }
 
-#[pyclass(eq,ord)]
+#[pyclass(eq, ord)]
 #[derive(PartialEq)]
 enum InvalidOrderedComplexEnum2 {
-    VariantA (i32),
-    VariantB { msg: String }
+    VariantA(i32),
+    VariantB { msg: String },
+}
+
+#[pyclass(pickle)]
+enum PickleEnum {
+    A,
+}
+
+#[pyclass(generic)]
+enum GenericEnum {
+    A,
 }
 
 fn main() {}
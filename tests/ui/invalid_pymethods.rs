@@ -121,6 +121,22 @@ impl MyClass {
     fn signature_on_classattr() {}
 }
 
+#[pymethods]
+impl MyClass {
+    #[setter(x)]
+    #[pyo3(into_py_with = "std::convert::identity")]
+    fn into_py_with_on_setter(&self) {}
+}
+
+#[pymethods]
+impl MyClass {
+    #[new]
+    #[pyo3(into_py_with = "std::convert::identity")]
+    fn into_py_with_on_new() -> Self {
+        MyClass {}
+    }
+}
+
 #[pymethods]
 impl MyClass {
     #[new]